@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
-use light_river::anomaly::half_space_tree::HalfSpaceTree;
+use light_river::anomaly::half_space_trees::{HalfSpaceTrees, HalfSpaceTreesOptions};
+use std::collections::HashMap;
 
 fn creation(c: &mut Criterion) {
     let mut group = c.benchmark_group("creation");
@@ -47,7 +48,19 @@ fn creation(c: &mut Criterion) {
                 format!("height={}-n_trees={}", height, n_trees),
                 &input,
                 |b, &input| {
-                    b.iter(|| HalfSpaceTree::new(0, input.1, input.0, Some(features.clone())));
+                    b.iter(|| {
+                        // The trees are built upfront when the limits of the features are known
+                        let options = HalfSpaceTreesOptions {
+                            n_trees: input.1 as usize,
+                            height: input.0,
+                            limits: features
+                                .iter()
+                                .map(|name| (name.clone(), (0.0, 1.0)))
+                                .collect::<HashMap<_, _>>(),
+                            ..Default::default()
+                        };
+                        HalfSpaceTrees::<f32>::new(options, Some(42))
+                    });
                 },
             );
         }
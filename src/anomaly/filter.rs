@@ -0,0 +1,326 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::AnomalyDetector;
+use crate::sketch::p2::P2Quantile;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+// Quantile of the scores, either over the whole stream with the P² algorithm, or exactly over a
+// sliding window.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum ScoreQuantile<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    Stream(P2Quantile<F>),
+    Window {
+        q: F,
+        size: usize,
+        scores: VecDeque<F>,
+        sorted: Vec<F>,
+    },
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ScoreQuantile<F> {
+    fn update(&mut self, score: F) {
+        match self {
+            ScoreQuantile::Stream(p2) => p2.update(score),
+            ScoreQuantile::Window {
+                size,
+                scores,
+                sorted,
+                ..
+            } => {
+                if scores.len() == *size {
+                    let oldest = scores.pop_front().unwrap();
+                    let i = sorted.partition_point(|s| *s < oldest);
+                    sorted.remove(i);
+                }
+                scores.push_back(score);
+                let i = sorted.partition_point(|s| *s < score);
+                sorted.insert(i, score);
+            }
+        }
+    }
+    fn get(&self) -> F {
+        match self {
+            ScoreQuantile::Stream(p2) => p2.get(),
+            ScoreQuantile::Window { q, sorted, .. } => {
+                // Linear interpolation between the closest ranks
+                let rank = *q * F::from_usize(sorted.len() - 1).unwrap();
+                let (lower, upper) = (rank.floor(), rank.ceil());
+                let (i, j) = (lower.to_usize().unwrap(), upper.to_usize().unwrap());
+                sorted[i] + (rank - lower) * (sorted[j] - sorted[i])
+            }
+        }
+    }
+}
+
+/// Turns the scores of an anomaly detector into alerts, by flagging the scores above a quantile
+/// of the past scores.
+///
+/// The quantile is either computed over the whole stream, approximately with the P² algorithm, or
+/// exactly over the last `window_size` scores, which adapts to changes of the scale of the scores.
+/// The rate of alerts is thus about `1 - q`, whatever the scale of the scores of the detector.
+///
+/// # Parameters
+///
+/// - `detector`: The anomaly detector whose scores are filtered.
+/// - `q`: The quantile above which the scores are anomalous, between 0 and 1.
+/// - `window_size`: The number of past scores the quantile is computed over. All the past scores
+///   are used if `None`.
+/// - `protect_detector`: Whether the detector is kept from learning from the samples which are
+///   flagged as anomalous, so that it doesn't get used to the anomalies.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::filter::QuantileFilter;
+/// use light_river::anomaly::half_space_trees::{HalfSpaceTrees, HalfSpaceTreesOptions};
+/// use light_river::common::Observation;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = HalfSpaceTreesOptions {
+///     window_size: 50,
+///     ..Default::default()
+/// };
+/// let detector: HalfSpaceTrees<f64> = HalfSpaceTrees::new(options, Some(42));
+/// let mut filter = QuantileFilter::new(detector, 0.95, None, true);
+/// let mut n_alerts = 0;
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = Observation::from([("x".to_string(), rng.gen::<f64>())]);
+///     if filter.predict_one(&x) {
+///         n_alerts += 1;
+///     }
+///     filter.learn_one(&x);
+/// }
+/// assert!(n_alerts < 100);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantileFilter<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    D,
+> {
+    detector: D,
+    protect_detector: bool,
+    quantile: ScoreQuantile<F>,
+    n_scores: usize,
+}
+
+impl<F, D> QuantileFilter<F, D>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    D: AnomalyDetector<F>,
+{
+    pub fn new(detector: D, q: F, window_size: Option<usize>, protect_detector: bool) -> Self {
+        assert!(q >= F::zero() && q <= F::one(), "q must lie in [0, 1]");
+        let quantile = match window_size {
+            None => ScoreQuantile::Stream(P2Quantile::new(q)),
+            Some(size) => {
+                assert!(size > 0, "window_size must be strictly positive");
+                ScoreQuantile::Window {
+                    q,
+                    size,
+                    scores: VecDeque::with_capacity(size),
+                    sorted: Vec::with_capacity(size),
+                }
+            }
+        };
+        Self {
+            detector,
+            protect_detector,
+            quantile,
+            n_scores: 0,
+        }
+    }
+    pub fn detector(&self) -> &D {
+        &self.detector
+    }
+    /// The current threshold, i.e. the quantile of the past scores, or `None` if no score has
+    /// been seen yet.
+    pub fn threshold(&self) -> Option<F> {
+        (self.n_scores > 0).then(|| self.quantile.get())
+    }
+    /// Whether a score is anomalous, i.e. strictly above the threshold. No score is anomalous
+    /// until a score has been seen.
+    pub fn classify(&self, score: F) -> bool {
+        self.threshold().is_some_and(|threshold| score > threshold)
+    }
+    /// Whether an observation is anomalous.
+    pub fn predict_one(&self, x: &Observation<F>) -> bool {
+        self.classify(self.detector.score_one(x))
+    }
+    /// Update the quantile with the score of an observation, and the detector with the
+    /// observation unless it is protected and the observation is anomalous.
+    pub fn learn_one(&mut self, x: &Observation<F>) {
+        let score = self.detector.score_one(x);
+        if !(self.protect_detector && self.classify(score)) {
+            self.detector.learn_one(x);
+        }
+        self.quantile.update(score);
+        self.n_scores += 1;
+    }
+}
+
+/// Turns the scores of an anomaly detector into alerts, by flagging the scores above a constant
+/// threshold.
+///
+/// # Parameters
+///
+/// - `detector`: The anomaly detector whose scores are filtered.
+/// - `threshold`: The score above which an observation is anomalous.
+/// - `protect_detector`: Whether the detector is kept from learning from the samples which are
+///   flagged as anomalous, so that it doesn't get used to the anomalies.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::filter::ConstantThresholder;
+/// use light_river::anomaly::one_class_svm::OneClassSVM;
+/// use light_river::common::Observation;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// // The scores of a one-class SVM are positive beyond the decision boundary
+/// let detector = OneClassSVM::new(SGD::new(0.01), None, None);
+/// let mut thresholder = ConstantThresholder::new(detector, 0.0, false);
+/// let obs = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..5000 {
+///     let (a, b) = (rng.gen::<f64>(), rng.gen::<f64>());
+///     thresholder.learn_one(&obs(2.0 + a, 2.0 + b));
+/// }
+/// assert!(thresholder.predict_one(&obs(0.5, 0.5)));
+/// assert!(!thresholder.predict_one(&obs(2.5, 2.5)));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstantThresholder<F, D> {
+    detector: D,
+    threshold: F,
+    protect_detector: bool,
+    _float: PhantomData<F>,
+}
+
+impl<F, D> ConstantThresholder<F, D>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    D: AnomalyDetector<F>,
+{
+    pub fn new(detector: D, threshold: F, protect_detector: bool) -> Self {
+        Self {
+            detector,
+            threshold,
+            protect_detector,
+            _float: PhantomData,
+        }
+    }
+    pub fn detector(&self) -> &D {
+        &self.detector
+    }
+    pub fn threshold(&self) -> F {
+        self.threshold
+    }
+    /// Whether a score is anomalous, i.e. strictly above the threshold.
+    pub fn classify(&self, score: F) -> bool {
+        score > self.threshold
+    }
+    /// Whether an observation is anomalous.
+    pub fn predict_one(&self, x: &Observation<F>) -> bool {
+        self.classify(self.detector.score_one(x))
+    }
+    /// Update the detector with an observation, unless it is protected and the observation is
+    /// anomalous.
+    pub fn learn_one(&mut self, x: &Observation<F>) {
+        if !(self.protect_detector && self.predict_one(x)) {
+            self.detector.learn_one(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    // A detector whose score is the "score" feature, and which counts the samples it learns from
+    #[derive(Clone, Debug, Default)]
+    struct Identity {
+        n_learned: usize,
+    }
+
+    impl AnomalyDetector<f64> for Identity {
+        fn learn_one(&mut self, _x: &Observation<f64>) {
+            self.n_learned += 1;
+        }
+        fn score_one(&self, x: &Observation<f64>) -> f64 {
+            x.get_numeric("score").unwrap()
+        }
+    }
+
+    fn obs(score: f64) -> Observation<f64> {
+        Observation::from([("score".to_string(), score)])
+    }
+
+    // A deterministic permutation of 0, 1, ..., 999, scaled by `scale`
+    fn score(i: usize, scale: f64) -> f64 {
+        scrambled(i, 1000) as f64 * scale
+    }
+
+    #[test]
+    fn test_alert_rate() {
+        for window_size in [None, Some(200)] {
+            let mut filter = QuantileFilter::new(Identity::default(), 0.9, window_size, false);
+            assert_eq!(filter.threshold(), None);
+            assert!(!filter.predict_one(&obs(1e10)));
+            let mut n_alerts = 0;
+            for i in 0..5000 {
+                let x = obs(score(i, 1.0));
+                if i >= 1000 && filter.predict_one(&x) {
+                    n_alerts += 1;
+                }
+                filter.learn_one(&x);
+            }
+            let rate = n_alerts as f64 / 4000.0;
+            assert!((rate - 0.1).abs() < 0.02, "{:?}: {}", window_size, rate);
+            assert_eq!(filter.detector().n_learned, 5000);
+        }
+    }
+
+    #[test]
+    fn test_window_adapts() {
+        let mut filter = QuantileFilter::new(Identity::default(), 0.5, Some(100), false);
+        for i in 0..1000 {
+            filter.learn_one(&obs(score(i, 1.0)));
+        }
+        assert!((filter.threshold().unwrap() - 500.0).abs() < 100.0);
+        // The scale of the scores changes
+        for i in 0..100 {
+            filter.learn_one(&obs(score(i, 0.01)));
+        }
+        assert!((filter.threshold().unwrap() - 5.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_protection() {
+        let mut protected = ConstantThresholder::new(Identity::default(), 0.5, true);
+        let mut unprotected = ConstantThresholder::new(Identity::default(), 0.5, false);
+        for score in [0.1, 0.9, 0.5, 0.7] {
+            protected.learn_one(&obs(score));
+            unprotected.learn_one(&obs(score));
+        }
+        assert_eq!(protected.detector().n_learned, 2);
+        assert_eq!(unprotected.detector().n_learned, 4);
+        assert!(protected.classify(0.51) && !protected.classify(0.5));
+
+        let mut filter = QuantileFilter::new(Identity::default(), 0.5, None, true);
+        for score in [1.0, 2.0, 3.0, 0.0] {
+            filter.learn_one(&obs(score));
+        }
+        // The first score is learned, as well as those below the median of the previous ones
+        assert_eq!(filter.detector().n_learned, 2);
+    }
+}
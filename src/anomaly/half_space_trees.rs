@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::AnomalyDetector;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+// Fraction of the range of a feature which is left on each side when drawing a threshold, so
+// that the splits don't isolate the edges of the space.
+const PADDING: f64 = 0.15;
+
+/// Options of [`HalfSpaceTrees`].
+///
+/// - `n_trees`: The number of trees, 10 by default.
+/// - `height`: The number of levels of each tree, 8 by default.
+/// - `window_size`: The number of observations in each window, 250 by default.
+/// - `limits`: The range of values of each feature. The features which aren't listed range in
+///   [0, 1]. If empty, which is the default, the features are those of the first observation.
+/// - `size_limit`: The fraction of `window_size` below which the mass of a node is deemed too small
+///   to go deeper when scoring, 0.1 by default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HalfSpaceTreesOptions<F> {
+    pub n_trees: usize,
+    pub height: u32,
+    pub window_size: usize,
+    pub limits: HashMap<String, (F, F)>,
+    pub size_limit: F,
+}
+
+impl<F: Float + FromPrimitive> Default for HalfSpaceTreesOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_trees: 10,
+            height: 8,
+            window_size: 250,
+            limits: HashMap::new(),
+            size_limit: F::from_f64(0.1).unwrap(),
+        }
+    }
+}
+
+// A node of a tree. The children of node `i` are `2i + 1` and `2i + 2`, and the leaves have no
+// split.
+#[derive(Clone, Debug)]
+struct Node<F> {
+    split: Option<(usize, F)>,
+    // Mass in the reference window, i.e. the last complete one
+    r_mass: F,
+    // Mass in the latest window, which is being filled
+    l_mass: F,
+}
+
+/// Half-space trees, a streaming variant of isolation forests.
+///
+/// Each tree is a complete binary tree built before any observation is seen: the nodes split the
+/// range of a random feature at a random threshold, so that the leaves are boxes of the feature
+/// space. The stream is cut in windows of `window_size` observations. Each node counts how many
+/// observations of the latest window fall into its box, and keeps the count of the previous
+/// window, i.e. its mass in the reference window. The anomaly score of an observation is high when
+/// it falls into boxes whose reference masses are small, the deepest boxes weighing the most.
+/// Scoring and learning both take O(`n_trees` × `height`) time.
+///
+/// The thresholds are drawn within the ranges of `options.limits`, which should therefore be set
+/// when the features don't range in [0, 1], e.g. by scaling them beforehand. Half-space trees work
+/// well when anomalies are spread out, but not when they come in bursts which fill the reference
+/// window.
+///
+/// # Parameters
+///
+/// - `options`: See [`HalfSpaceTreesOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::half_space_trees::{HalfSpaceTrees, HalfSpaceTreesOptions};
+/// use light_river::common::Observation;
+/// use light_river::learner::AnomalyDetector;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+/// use std::collections::HashMap;
+///
+/// let options = HalfSpaceTreesOptions {
+///     window_size: 100,
+///     limits: HashMap::from([("x".to_string(), (0.0, 100.0))]),
+///     ..Default::default()
+/// };
+/// let mut model: HalfSpaceTrees<f64> = HalfSpaceTrees::new(options, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen_range(40.0..60.0);
+///     model.learn_one(&Observation::from([("x".to_string(), x)]));
+/// }
+///
+/// let normal = model.score_one(&Observation::from([("x".to_string(), 50.0)]));
+/// let anomaly = model.score_one(&Observation::from([("x".to_string(), 95.0)]));
+/// assert!(anomaly > normal);
+/// ```
+///
+/// # References
+///
+/// [^1]: S. C. Tan, K. M. Ting and T. F. Liu (2011). "Fast anomaly detection for streaming data".
+/// Proceedings of the twenty-second international joint conference on artificial intelligence,
+/// 1511-1516.
+#[derive(Clone, Debug)]
+pub struct HalfSpaceTrees<F> {
+    options: HalfSpaceTreesOptions<F>,
+    features: Vec<String>,
+    // The nodes of all the trees, one tree after the other
+    nodes: Vec<Node<F>>,
+    counter: usize,
+    first_window: bool,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> HalfSpaceTrees<F> {
+    pub fn new(options: HalfSpaceTreesOptions<F>, seed: Option<u64>) -> Self {
+        assert!(options.n_trees > 0, "n_trees must be strictly positive");
+        assert!(options.height > 0, "height must be strictly positive");
+        assert!(
+            options.window_size > 0,
+            "window_size must be strictly positive"
+        );
+        assert!(
+            options.limits.values().all(|(lo, hi)| lo < hi),
+            "limits must be non-empty ranges"
+        );
+        let mut model = Self {
+            options,
+            features: Vec::new(),
+            nodes: Vec::new(),
+            counter: 0,
+            first_window: true,
+            rng: rng(seed),
+        };
+        if !model.options.limits.is_empty() {
+            let mut features: Vec<String> = model.options.limits.keys().cloned().collect();
+            features.sort();
+            model.build(features);
+        }
+        model
+    }
+    pub fn options(&self) -> &HalfSpaceTreesOptions<F> {
+        &self.options
+    }
+    /// Features the trees split on, which are empty until the trees have been built.
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+    fn n_nodes(&self) -> usize {
+        (1 << self.options.height) - 1
+    }
+    fn build(&mut self, features: Vec<String>) {
+        let (zero, one) = (F::zero(), F::one());
+        let limits: Vec<(F, F)> = features
+            .iter()
+            .map(|name| {
+                self.options
+                    .limits
+                    .get(name)
+                    .copied()
+                    .unwrap_or((zero, one))
+            })
+            .collect();
+        self.features = features;
+        let n_nodes = self.n_nodes();
+        let n_branches = n_nodes / 2;
+        let padding = F::from_f64(PADDING).unwrap();
+        for _ in 0..self.options.n_trees {
+            // The ranges of the features in the box of each node of the tree
+            let mut boxes = vec![limits.clone()];
+            for i in 0..n_nodes {
+                if i >= n_branches || self.features.is_empty() {
+                    self.nodes.push(Node {
+                        split: None,
+                        r_mass: zero,
+                        l_mass: zero,
+                    });
+                    continue;
+                }
+                let feature = self.rng.gen_range(0..self.features.len());
+                let (lo, hi) = boxes[i][feature];
+                let u = F::from_f64(self.rng.gen()).unwrap();
+                let threshold = lo + (padding + u * (one - padding - padding)) * (hi - lo);
+                let (mut left, mut right) = (boxes[i].clone(), boxes[i].clone());
+                left[feature].1 = threshold;
+                right[feature].0 = threshold;
+                boxes.push(left);
+                boxes.push(right);
+                self.nodes.push(Node {
+                    split: Some((feature, threshold)),
+                    r_mass: zero,
+                    l_mass: zero,
+                });
+            }
+        }
+    }
+    // Index of the child of a node of a tree in which the observation falls. Observations which
+    // lack the feature follow the heaviest child of the reference window.
+    fn child(&self, offset: usize, i: usize, x: &Observation<F>) -> Option<usize> {
+        let (feature, threshold) = self.nodes[offset + i].split?;
+        let (left, right) = (2 * i + 1, 2 * i + 2);
+        match x.get_numeric(&self.features[feature]) {
+            Some(value) if value < threshold => Some(left),
+            Some(_) => Some(right),
+            None if self.nodes[offset + left].r_mass >= self.nodes[offset + right].r_mass => {
+                Some(left)
+            }
+            None => Some(right),
+        }
+    }
+    fn max_score(&self) -> F {
+        F::from_usize(self.options.n_trees * self.options.window_size * self.n_nodes()).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyDetector<F>
+    for HalfSpaceTrees<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if self.nodes.is_empty() {
+            let features = x.numeric().map(|(name, _)| name.clone()).collect();
+            self.build(features);
+        }
+        let n_nodes = self.n_nodes();
+        for tree in 0..self.options.n_trees {
+            let offset = tree * n_nodes;
+            let mut i = 0;
+            loop {
+                self.nodes[offset + i].l_mass += F::one();
+                match self.child(offset, i, x) {
+                    Some(child) => i = child,
+                    None => break,
+                }
+            }
+        }
+        self.counter += 1;
+        if self.counter == self.options.window_size {
+            for node in self.nodes.iter_mut() {
+                node.r_mass = node.l_mass;
+                node.l_mass = F::zero();
+            }
+            self.counter = 0;
+            self.first_window = false;
+        }
+    }
+    /// The anomaly score, between 0 and 1. It is 0 until the first window is complete.
+    fn score_one(&self, x: &Observation<F>) -> F {
+        if self.first_window {
+            return F::zero();
+        }
+        let size_limit = self.options.size_limit * F::from_usize(self.options.window_size).unwrap();
+        let n_nodes = self.n_nodes();
+        let mut score = F::zero();
+        for tree in 0..self.options.n_trees {
+            let offset = tree * n_nodes;
+            let (mut i, mut depth_weight) = (0, F::one());
+            loop {
+                let mass = self.nodes[offset + i].r_mass;
+                score += mass * depth_weight;
+                if mass < size_limit {
+                    break;
+                }
+                match self.child(offset, i, x) {
+                    Some(child) => i = child,
+                    None => break,
+                }
+                depth_weight += depth_weight;
+            }
+        }
+        F::one() - score / self.max_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::credit_card::CreditCard;
+    use crate::stream::iter_csv::IterCsv;
+    use crate::testing::{scrambled, scrambled_other};
+    use std::fs::File;
+
+    fn normal(i: usize) -> Observation<f64> {
+        Observation::from([
+            ("x".to_string(), 0.4 + scrambled(i, 100) as f64 / 500.0),
+            (
+                "y".to_string(),
+                0.4 + scrambled_other(i, 100) as f64 / 500.0,
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_outliers() {
+        let options = HalfSpaceTreesOptions {
+            window_size: 100,
+            ..Default::default()
+        };
+        let mut model: HalfSpaceTrees<f64> = HalfSpaceTrees::new(options, Some(42));
+        for i in 0..99 {
+            model.learn_one(&normal(i));
+        }
+        assert_eq!(model.score_one(&normal(0)), 0.0);
+        for i in 99..500 {
+            model.learn_one(&normal(i));
+        }
+        assert_eq!(model.features(), ["x", "y"]);
+        let outliers = [(0.95, 0.05), (0.05, 0.95), (0.9, 0.9), (0.1, 0.1)];
+        for (x, y) in outliers {
+            let outlier = Observation::from([("x".to_string(), x), ("y".to_string(), y)]);
+            let score = model.score_one(&outlier);
+            assert!(score > 0.9, "{}", score);
+            for i in 0..20 {
+                assert!(model.score_one(&normal(i)) < score);
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        // The same data, on a scale which only works with the right limits
+        let scaled = |i: usize| {
+            let x = normal(i);
+            Observation::from([
+                ("x".to_string(), x.get_numeric("x").unwrap() * 1000.0),
+                ("y".to_string(), x.get_numeric("y").unwrap() * 1000.0),
+            ])
+        };
+        let options = HalfSpaceTreesOptions {
+            window_size: 100,
+            limits: HashMap::from([
+                ("x".to_string(), (0.0, 1000.0)),
+                ("y".to_string(), (0.0, 1000.0)),
+            ]),
+            ..Default::default()
+        };
+        let mut model: HalfSpaceTrees<f64> = HalfSpaceTrees::new(options, Some(42));
+        for i in 0..500 {
+            model.learn_one(&scaled(i));
+        }
+        let outlier = Observation::from([("x".to_string(), 950.0), ("y".to_string(), 50.0)]);
+        assert!(model.score_one(&outlier) > model.score_one(&scaled(3)));
+    }
+
+    #[test]
+    fn test_hst() {
+        let options = HalfSpaceTreesOptions {
+            window_size: 1000,
+            n_trees: 50,
+            height: 6,
+            ..Default::default()
+        };
+        let mut model: HalfSpaceTrees<f32> = HalfSpaceTrees::new(options, Some(42));
+        let transactions: IterCsv<f32, File> = CreditCard::load_credit_card_transactions().unwrap();
+        for transaction in transactions {
+            let observation = transaction.unwrap().get_observation();
+            let score = model.score_one(&observation);
+            assert!((0.0..=1.0).contains(&score), "{}", score);
+            model.learn_one(&observation);
+        }
+    }
+
+    #[test]
+    fn test_window_pivot() {
+        let options = HalfSpaceTreesOptions {
+            n_trees: 1,
+            height: 3,
+            window_size: 10,
+            ..Default::default()
+        };
+        let mut model: HalfSpaceTrees<f64> = HalfSpaceTrees::new(options, Some(42));
+        for i in 0..25 {
+            model.learn_one(&normal(i));
+        }
+        // The reference window holds samples 10 to 19, the latest one samples 20 to 24
+        assert_eq!(model.nodes[0].r_mass, 10.0);
+        assert_eq!(model.nodes[0].l_mass, 5.0);
+    }
+}
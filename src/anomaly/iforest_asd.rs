@@ -0,0 +1,366 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::AnomalyDetector;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of [`IForestASD`].
+///
+/// - `n_trees`: The number of isolation trees, 50 by default.
+/// - `window_size`: The number of observations in each window, on which the trees are built, 256
+///   by default.
+/// - `max_height`: The height beyond which the nodes aren't split anymore. If `None`, which is the
+///   default, it is the ceiling of log2(`window_size`), the average height of a random tree.
+/// - `anomaly_threshold`: The score above which an observation counts as an anomaly when checking
+///   for drift, 0.6 by default. Scores of normal observations are around 0.5.
+/// - `drift_threshold`: The rate of anomalies in a window above which the trees are rebuilt, 0.5
+///   by default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IForestASDOptions<F> {
+    pub n_trees: usize,
+    pub window_size: usize,
+    pub max_height: Option<usize>,
+    pub anomaly_threshold: F,
+    pub drift_threshold: F,
+}
+
+impl<F: Float + FromPrimitive> Default for IForestASDOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_trees: 50,
+            window_size: 256,
+            max_height: None,
+            anomaly_threshold: F::from_f64(0.6).unwrap(),
+            drift_threshold: F::from_f64(0.5).unwrap(),
+        }
+    }
+}
+
+// Average path length of an unsuccessful search in a binary search tree of n elements, which
+// normalizes the path lengths of the isolation trees.
+fn average_path_length<F: Float + FromPrimitive>(n: usize) -> F {
+    match n {
+        0 | 1 => F::zero(),
+        2 => F::one(),
+        _ => {
+            let n = F::from_usize(n).unwrap();
+            let harmonic = (n - F::one()).ln() + F::from_f64(0.5772156649).unwrap();
+            (harmonic + harmonic) - (n + n - F::one() - F::one()) / n
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node<F> {
+    Leaf {
+        size: usize,
+    },
+    Split {
+        feature: String,
+        threshold: F,
+        left: usize,
+        right: usize,
+        size: usize,
+    },
+}
+
+impl<F> Node<F> {
+    fn size(&self) -> usize {
+        match self {
+            Node::Leaf { size } | Node::Split { size, .. } => *size,
+        }
+    }
+}
+
+// Isolation tree, whose nodes split the range of a random feature of their samples at a random
+// threshold, until the samples are isolated or the maximum height is reached.
+#[derive(Clone, Debug)]
+struct IsolationTree<F> {
+    nodes: Vec<Node<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign> IsolationTree<F> {
+    fn new(samples: &[Observation<F>], max_height: usize, rng: &mut StdRng) -> Self {
+        let mut tree = Self { nodes: Vec::new() };
+        tree.build((0..samples.len()).collect(), samples, 0, max_height, rng);
+        tree
+    }
+    // Build the subtree of the given samples, and return the index of its root.
+    fn build(
+        &mut self,
+        indices: Vec<usize>,
+        samples: &[Observation<F>],
+        height: usize,
+        max_height: usize,
+        rng: &mut StdRng,
+    ) -> usize {
+        let index = self.nodes.len();
+        let size = indices.len();
+        self.nodes.push(Node::Leaf { size });
+        if size <= 1 || height >= max_height {
+            return index;
+        }
+        // The features which take at least two values among the samples
+        let mut ranges: Vec<(&String, F, F)> = Vec::new();
+        for i in indices.iter() {
+            for (name, value) in samples[*i].numeric() {
+                match ranges.iter_mut().find(|(feature, _, _)| *feature == name) {
+                    Some((_, min, max)) => {
+                        *min = min.min(value);
+                        *max = max.max(value);
+                    }
+                    None => ranges.push((name, value, value)),
+                }
+            }
+        }
+        ranges.retain(|(_, min, max)| min < max);
+        let Some((feature, min, max)) = ranges.choose(rng).cloned() else {
+            return index;
+        };
+        let threshold = min + F::from_f64(rng.gen()).unwrap() * (max - min);
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        let mut missing = Vec::new();
+        for i in indices {
+            match samples[i].get_numeric(feature) {
+                Some(value) if value < threshold => left.push(i),
+                Some(_) => right.push(i),
+                None => missing.push(i),
+            }
+        }
+        // The samples which lack the feature go with the majority
+        if left.len() >= right.len() {
+            left.extend(missing);
+        } else {
+            right.extend(missing);
+        }
+        let feature = feature.clone();
+        let left = self.build(left, samples, height + 1, max_height, rng);
+        let right = self.build(right, samples, height + 1, max_height, rng);
+        self.nodes[index] = Node::Split {
+            feature,
+            threshold,
+            left,
+            right,
+            size,
+        };
+        index
+    }
+    // Depth of the leaf the observation falls into, plus the expected depth at which the samples
+    // of the leaf would have been isolated.
+    fn path_length(&self, x: &Observation<F>) -> F {
+        let (mut i, mut depth) = (0, F::zero());
+        while let Node::Split {
+            feature,
+            threshold,
+            left,
+            right,
+            ..
+        } = &self.nodes[i]
+        {
+            i = match x.get_numeric(feature) {
+                Some(value) if value < *threshold => *left,
+                Some(_) => *right,
+                None if self.nodes[*left].size() >= self.nodes[*right].size() => *left,
+                None => *right,
+            };
+            depth += F::one();
+        }
+        depth + average_path_length(self.nodes[i].size())
+    }
+}
+
+/// Isolation forest for anomaly detection in streams, also known as iForestASD.
+///
+/// An isolation forest is a batch model: each tree splits the samples it is built on at random
+/// until they are isolated, and anomalies, which are few and different, get isolated closer to the
+/// root than the normal samples. The score of an observation is `2^(-E[h] / c)`, where `E[h]` is
+/// its average path length in the trees and `c` the average path length of a random tree of the
+/// same size. The scores therefore lie in [0, 1], as expected by the
+/// [anomaly metrics](crate::metrics::anomaly), and are close to 1 for anomalies.
+///
+/// To handle streams, the observations are gathered in windows of `window_size` samples. The trees
+/// are built on the first window, and the rate of anomalies is monitored over each of the
+/// following windows. If it exceeds `drift_threshold`, the distribution of the samples is deemed
+/// to have changed, and the trees are rebuilt on the last window. Observations are scored 0 until
+/// the first window is complete.
+///
+/// # Parameters
+///
+/// - `options`: See [`IForestASDOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::iforest_asd::{IForestASD, IForestASDOptions};
+/// use light_river::common::Observation;
+/// use light_river::learner::AnomalyDetector;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = IForestASDOptions {
+///     window_size: 100,
+///     ..Default::default()
+/// };
+/// let mut model: IForestASD<f64> = IForestASD::new(options, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..300 {
+///     let x = rng.gen::<f64>();
+///     model.learn_one(&Observation::from([("x".to_string(), x)]));
+/// }
+///
+/// let normal = model.score_one(&Observation::from([("x".to_string(), 0.5)]));
+/// let anomaly = model.score_one(&Observation::from([("x".to_string(), 5.0)]));
+/// assert!(anomaly > 0.6 && normal < 0.5);
+/// ```
+///
+/// # References
+///
+/// [^1]: Z. Ding and M. Fei (2013). "An anomaly detection approach based on isolation forest
+/// algorithm for streaming data using sliding window". IFAC Proceedings Volumes 46(20):12-17.
+///
+/// [^2]: F. T. Liu, K. M. Ting and Z. H. Zhou (2008). "Isolation forest". Proceedings of the eighth
+/// IEEE international conference on data mining, 413-422.
+#[derive(Clone, Debug)]
+pub struct IForestASD<F> {
+    options: IForestASDOptions<F>,
+    trees: Vec<IsolationTree<F>>,
+    // Size of the window the trees were built on
+    n_samples: usize,
+    window: Vec<Observation<F>>,
+    n_anomalies: usize,
+    n_builds: usize,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> IForestASD<F> {
+    pub fn new(options: IForestASDOptions<F>, seed: Option<u64>) -> Self {
+        assert!(options.n_trees > 0, "n_trees must be strictly positive");
+        assert!(
+            options.window_size > 1,
+            "window_size must be greater than 1"
+        );
+        Self {
+            options,
+            trees: Vec::new(),
+            n_samples: 0,
+            window: Vec::with_capacity(options.window_size),
+            n_anomalies: 0,
+            n_builds: 0,
+            rng: rng(seed),
+        }
+    }
+    pub fn options(&self) -> &IForestASDOptions<F> {
+        &self.options
+    }
+    /// Number of times the trees have been built, including the first one. Every build after the
+    /// first one follows a drift.
+    pub fn n_builds(&self) -> usize {
+        self.n_builds
+    }
+    fn build(&mut self) {
+        let max_height = self
+            .options
+            .max_height
+            .unwrap_or_else(|| self.window.len().next_power_of_two().trailing_zeros() as usize);
+        self.trees = (0..self.options.n_trees)
+            .map(|_| IsolationTree::new(&self.window, max_height, &mut self.rng))
+            .collect();
+        self.n_samples = self.window.len();
+        self.n_builds += 1;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyDetector<F>
+    for IForestASD<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if !self.trees.is_empty() && self.score_one(x) > self.options.anomaly_threshold {
+            self.n_anomalies += 1;
+        }
+        self.window.push(x.clone());
+        if self.window.len() < self.options.window_size {
+            return;
+        }
+        let rate = F::from_usize(self.n_anomalies).unwrap()
+            / F::from_usize(self.options.window_size).unwrap();
+        if self.trees.is_empty() || rate > self.options.drift_threshold {
+            self.build();
+        }
+        self.window.clear();
+        self.n_anomalies = 0;
+    }
+    fn score_one(&self, x: &Observation<F>) -> F {
+        if self.trees.is_empty() {
+            return F::zero();
+        }
+        let total = self
+            .trees
+            .iter()
+            .fold(F::zero(), |acc, tree| acc + tree.path_length(x));
+        let mean = total / F::from_usize(self.trees.len()).unwrap();
+        let two = F::one() + F::one();
+        two.powf(-mean / average_path_length(self.n_samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{scrambled, scrambled_other};
+
+    fn sample(i: usize, offset: f64) -> Observation<f64> {
+        Observation::from([
+            ("x".to_string(), offset + scrambled(i, 100) as f64 / 100.0),
+            (
+                "y".to_string(),
+                offset + scrambled_other(i, 97) as f64 / 97.0,
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_average_path_length() {
+        assert_eq!(average_path_length::<f64>(1), 0.0);
+        assert_eq!(average_path_length::<f64>(2), 1.0);
+        // 2 * (ln(255) + 0.5772156649) - 2 * 255 / 256
+        assert!((average_path_length::<f64>(256) - 10.2448).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_outliers() {
+        let mut model: IForestASD<f64> = IForestASD::new(Default::default(), Some(42));
+        for i in 0..255 {
+            model.learn_one(&sample(i, 0.0));
+        }
+        assert_eq!(model.score_one(&sample(0, 0.0)), 0.0);
+        model.learn_one(&sample(255, 0.0));
+        let outlier = Observation::from([("x".to_string(), 3.0), ("y".to_string(), -2.0)]);
+        let score = model.score_one(&outlier);
+        assert!(score > 0.6, "{}", score);
+        for i in 0..50 {
+            assert!(model.score_one(&sample(i, 0.0)) < score);
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        let options = IForestASDOptions {
+            window_size: 100,
+            ..Default::default()
+        };
+        let mut model: IForestASD<f64> = IForestASD::new(options, Some(42));
+        for i in 0..500 {
+            model.learn_one(&sample(i, 0.0));
+        }
+        assert_eq!(model.n_builds(), 1);
+        // The whole stream moves away, and the trees are rebuilt after the first shifted window
+        for i in 0..200 {
+            model.learn_one(&sample(i, 10.0));
+        }
+        assert_eq!(model.n_builds(), 2);
+        assert!(model.score_one(&sample(3, 10.0)) < 0.5);
+        assert!(model.score_one(&sample(3, 0.0)) > 0.6);
+    }
+}
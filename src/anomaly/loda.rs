@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::AnomalyDetector;
+use crate::sketch::histogram::Histogram;
+use crate::utils::{rng, standard_normal};
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of [`LODA`].
+///
+/// - `n_projections`: The number of random projections, 100 by default.
+/// - `n_bins`: The maximum number of bins of the streaming histogram of each projection, at least
+///   2, 10 by default.
+/// - `warm_up`: The number of observations during which the scores are 0, 256 by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LODAOptions {
+    pub n_projections: usize,
+    pub n_bins: usize,
+    pub warm_up: usize,
+}
+
+impl Default for LODAOptions {
+    fn default() -> Self {
+        Self {
+            n_projections: 100,
+            n_bins: 10,
+            warm_up: 256,
+        }
+    }
+}
+
+// Negative log-density of a projected value, with a Laplace correction of one value spread over
+// the average width of the bins, so that the density isn't null beyond the values seen.
+fn nll<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    histogram: &Histogram<F>,
+    z: F,
+) -> F {
+    let weight = histogram.weight();
+    let width = histogram.range().map_or(F::zero(), |(min, max)| {
+        (max - min) / F::from_usize(histogram.bins().len()).unwrap()
+    });
+    let width = if width > F::zero() { width } else { F::one() };
+    -((weight * histogram.pdf(z) + F::one() / width) / (weight + F::one())).ln()
+}
+
+/// Lightweight on-line detector of anomalies (LODA).
+///
+/// The observations are projected on `n_projections` random directions. Each direction is sparse:
+/// it involves the square root of the number of features, with weights drawn from a standard
+/// normal distribution. The density of each projection is estimated with a streaming
+/// [`Histogram`], and the anomaly score of an observation is its average negative log-density over the projections. Each
+/// histogram only takes the observations in one dimension, which makes LODA cheap and well-suited
+/// to high-dimensional streams.
+///
+/// The features are those of the first observation, and missing ones count as zeros. Each
+/// observation updates the histograms, and the scores are 0 until `warm_up` observations have been
+/// seen.
+///
+/// The sparsity of the projections makes it possible to tell which features make an observation
+/// anomalous, with [`attribution`](Self::attribution).
+///
+/// # Parameters
+///
+/// - `options`: See [`LODAOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::loda::{LODA, LODAOptions};
+/// use light_river::common::Observation;
+/// use light_river::learner::AnomalyDetector;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = LODAOptions {
+///     warm_up: 100,
+///     ..Default::default()
+/// };
+/// let mut model = LODA::new(options, Some(42));
+/// let obs = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..500 {
+///     let (a, b) = (rng.gen::<f64>(), rng.gen::<f64>());
+///     model.learn_one(&obs(a, b));
+/// }
+///
+/// assert!(model.score_one(&obs(0.5, 5.0)) > model.score_one(&obs(0.5, 0.5)));
+/// let attribution = model.attribution(&obs(0.5, 5.0));
+/// assert!(attribution["b"] > attribution["a"]);
+/// ```
+///
+/// # References
+///
+/// [^1]: T. Pevný (2016). "Loda: Lightweight on-line detector of anomalies". Machine Learning
+/// 102(2):275-304.
+#[derive(Clone, Debug)]
+pub struct LODA<F> {
+    options: LODAOptions,
+    features: Vec<String>,
+    // Sparse projections, as pairs of feature indices and weights
+    projections: Vec<Vec<(usize, F)>>,
+    histograms: Vec<Histogram<F>>,
+    n: usize,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> LODA<F> {
+    pub fn new(options: LODAOptions, seed: Option<u64>) -> Self {
+        assert!(
+            options.n_projections > 1,
+            "n_projections must be greater than 1"
+        );
+        assert!(options.n_bins >= 2, "n_bins must be at least 2");
+        assert!(options.warm_up > 0, "warm_up must be strictly positive");
+        Self {
+            options,
+            features: Vec::new(),
+            projections: Vec::new(),
+            histograms: Vec::new(),
+            n: 0,
+            rng: rng(seed),
+        }
+    }
+    pub fn options(&self) -> &LODAOptions {
+        &self.options
+    }
+    /// Features the observations are projected from, which are empty until the first observation
+    /// has been seen.
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+    fn init_projections(&mut self, x: &Observation<F>) {
+        self.features = x.numeric().map(|(name, _)| name.clone()).collect();
+        let d = self.features.len();
+        let n_nonzero = ((d as f64).sqrt().round() as usize).max(1).min(d);
+        self.projections = (0..self.options.n_projections)
+            .map(|_| {
+                rand::seq::index::sample(&mut self.rng, d, n_nonzero)
+                    .into_iter()
+                    .map(|j| {
+                        let z = standard_normal(&mut self.rng);
+                        (j, F::from_f64(z).unwrap())
+                    })
+                    .collect()
+            })
+            .collect();
+        self.histograms = vec![Histogram::new(self.options.n_bins); self.projections.len()];
+    }
+    fn project(&self, x: &Observation<F>) -> Vec<F> {
+        self.projections
+            .iter()
+            .map(|projection| {
+                projection.iter().fold(F::zero(), |acc, (j, w)| {
+                    acc + *w * x.get_numeric(&self.features[*j]).unwrap_or(F::zero())
+                })
+            })
+            .collect()
+    }
+    // Negative log-densities of an observation along each projection, or `None` during the
+    // warm-up.
+    fn nlls(&self, x: &Observation<F>) -> Option<Vec<F>> {
+        if self.n < self.options.warm_up {
+            return None;
+        }
+        let nlls = self
+            .project(x)
+            .into_iter()
+            .zip(self.histograms.iter())
+            .map(|(z, histogram)| nll(histogram, z))
+            .collect();
+        Some(nlls)
+    }
+    /// Contribution of each feature to the anomaly score of an observation. For each feature, the
+    /// negative log-densities of the projections which involve it are compared with those of the
+    /// other projections with Welch's t-statistic. The larger the statistic, the more anomalous
+    /// the observation is along the feature. All the statistics are 0 during the warm-up, as well
+    /// as those of the features which are involved in fewer than two projections or in all but one.
+    pub fn attribution(&self, x: &Observation<F>) -> HashMap<String, F> {
+        let mut attribution: HashMap<String, F> = self
+            .features
+            .iter()
+            .map(|name| (name.clone(), F::zero()))
+            .collect();
+        let Some(nlls) = self.nlls(x) else {
+            return attribution;
+        };
+        // Mean and variance of a set of values
+        let moments = |values: &[F]| {
+            let n = F::from_usize(values.len()).unwrap();
+            let mean = values.iter().fold(F::zero(), |acc, v| acc + *v) / n;
+            let ss = values
+                .iter()
+                .fold(F::zero(), |acc, v| acc + (*v - mean) * (*v - mean));
+            (n, mean, ss / (n - F::one()))
+        };
+        for (j, name) in self.features.iter().enumerate() {
+            let (mut with, mut without) = (Vec::new(), Vec::new());
+            for (projection, nll) in self.projections.iter().zip(nlls.iter()) {
+                if projection.iter().any(|(k, _)| *k == j) {
+                    with.push(*nll);
+                } else {
+                    without.push(*nll);
+                }
+            }
+            if with.len() < 2 || without.len() < 2 {
+                continue;
+            }
+            let (n1, mean1, var1) = moments(&with);
+            let (n2, mean2, var2) = moments(&without);
+            let se = (var1 / n1 + var2 / n2).sqrt();
+            if se > F::zero() {
+                attribution.insert(name.clone(), (mean1 - mean2) / se);
+            }
+        }
+        attribution
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyDetector<F>
+    for LODA<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if self.projections.is_empty() {
+            self.init_projections(x);
+        }
+        let values = self.project(x);
+        for (histogram, z) in self.histograms.iter_mut().zip(values) {
+            histogram.update(z);
+        }
+        self.n += 1;
+    }
+    fn score_one(&self, x: &Observation<F>) -> F {
+        match self.nlls(x) {
+            Some(nlls) => {
+                nlls.iter().fold(F::zero(), |acc, v| acc + *v) / F::from_usize(nlls.len()).unwrap()
+            }
+            None => F::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use crate::testing::{scrambled, scrambled_other};
+
+    // Ten features, uniformly distributed in [0, 1]
+    fn sample(i: usize) -> Observation<f64> {
+        (0..10)
+            .map(|j| {
+                let value =
+                    ((scrambled(i, 1009) + scrambled_other(j, 1009)) % 1009) as f64 / 1009.0;
+                (format!("x{}", j), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nll() {
+        let mut histogram = Histogram::new(5);
+        for z in [0.0, 1.0, 2.0, 3.0, 4.0, 10.0] {
+            histogram.update(z);
+        }
+        // Sparse regions have a lower density than dense ones, and the density is finite beyond
+        // the range of the values
+        assert!(nll(&histogram, 7.0) > nll(&histogram, 2.0));
+        assert!(nll(&histogram, -5.0) > nll(&histogram, 2.0));
+        assert!(nll(&histogram, -5.0).is_finite());
+        assert!(nll(&Histogram::new(5), 1.0).is_finite());
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let options = LODAOptions {
+            warm_up: 10,
+            ..Default::default()
+        };
+        let mut model: LODA<f64> = LODA::new(options, Some(42));
+        for i in 0..9 {
+            model.learn_one(&sample(i));
+        }
+        assert_eq!(model.score_one(&sample(0)), 0.0);
+        assert!(model.attribution(&sample(0)).values().all(|t| *t == 0.0));
+        model.learn_one(&sample(9));
+        assert!(model.score_one(&sample(0)) != 0.0);
+        assert_eq!(model.features().len(), 10);
+    }
+
+    #[test]
+    fn test_outliers_and_attribution() {
+        let mut model: LODA<f64> = LODA::new(Default::default(), Some(42));
+        for i in 0..2000 {
+            model.learn_one(&sample(i));
+        }
+        let mut outlier = sample(2000);
+        outlier.insert("x3", FeatureValue::Numeric(4.0));
+        let score = model.score_one(&outlier);
+        for i in 0..50 {
+            assert!(model.score_one(&sample(i)) < score);
+        }
+        let attribution = model.attribution(&outlier);
+        let top = attribution
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .unwrap();
+        assert_eq!(top.0, "x3");
+    }
+}
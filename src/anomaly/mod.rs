@@ -1 +1,6 @@
-pub mod half_space_tree;
+pub mod filter;
+pub mod half_space_trees;
+pub mod iforest_asd;
+pub mod loda;
+pub mod one_class_svm;
+pub mod rrcf;
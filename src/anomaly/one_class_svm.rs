@@ -0,0 +1,178 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureKey, Features, Observation};
+use crate::learner::AnomalyDetector;
+use crate::linear_model::glm::{features, observation_features, GLMOptions, Glm};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// One-class support vector machine, trained online with stochastic gradient descent.
+///
+/// The model learns weights `w` and an offset `rho` such that `w·x >= rho` for most of the
+/// samples, while pushing the hyperplane as far as possible from the origin. The objective is the
+/// one of Schölkopf et al., scaled by `nu`:
+///
+/// `nu / 2 * ||w||² - nu * rho + max(0, rho - w·x)`
+///
+/// whose minimizer leaves a fraction `nu` of the samples on the wrong side of the hyperplane. The
+/// weights are updated by the optimizer, and the offset with plain gradient descent. The anomaly
+/// score is the signed distance `rho - w·x`, which is positive beyond the decision boundary, i.e.
+/// for anomalies, and negative for normal samples.
+///
+/// The decision boundary is linear, so that the model only detects samples which lie too close to
+/// the origin in the direction of the normal samples. Nonlinear boundaries can be learned by
+/// mapping the features beforehand, e.g. with random Fourier features.
+///
+/// # Parameters
+///
+/// - `optimizer`: The optimizer of the weights, e.g. [`SGD`](crate::optim::optimizers::SGD).
+/// - `nu`: An upper bound on the fraction of training samples which are deemed anomalous, and a
+///   lower bound on the fraction of support vectors, 0.1 by default.
+/// - `intercept_lr`: The learning rate of the offset, 0.01 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::one_class_svm::OneClassSVM;
+/// use light_river::common::Observation;
+/// use light_river::learner::AnomalyDetector;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model = OneClassSVM::new(SGD::new(0.01), None, None);
+/// let obs = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..5000 {
+///     let (a, b) = (rng.gen::<f64>(), rng.gen::<f64>());
+///     model.learn_one(&obs(2.0 + a, 2.0 + b));
+/// }
+///
+/// assert!(model.score_one(&obs(2.5, 2.5)) < 0.0);
+/// assert!(model.score_one(&obs(0.5, 0.5)) > 0.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: B. Schölkopf, J. C. Platt, J. Shawe-Taylor, A. J. Smola and R. C. Williamson (2001).
+/// "Estimating the support of a high-dimensional distribution". Neural computation
+/// 13(7):1443-1471.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneClassSVM<F, O> {
+    // The intercept of the linear model is -rho
+    glm: Glm<F, O>,
+    nu: F,
+    intercept_lr: F,
+}
+
+impl<F, O> OneClassSVM<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+{
+    pub fn new(optimizer: O, nu: Option<F>, intercept_lr: Option<F>) -> Self {
+        let nu = nu.unwrap_or(F::from_f64(0.1).unwrap());
+        assert!(nu > F::zero() && nu <= F::one(), "nu must lie in (0, 1]");
+        let intercept_lr = intercept_lr.unwrap_or(F::from_f64(0.01).unwrap());
+        let options = GLMOptions {
+            l1: F::zero(),
+            l2: nu,
+            intercept_lr,
+        };
+        Self {
+            glm: Glm::new(optimizer, options),
+            nu,
+            intercept_lr,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.glm.weights
+    }
+    /// The offset of the decision boundary.
+    pub fn rho(&self) -> F {
+        -self.glm.intercept
+    }
+    /// Learn from a sample whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>) {
+        self.learn(&features(x));
+    }
+    /// Anomaly score of a sample whose features are either dense or sparse.
+    pub fn score_features(&self, x: &Features<F>) -> F {
+        -self.glm.predict(&features(x))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)]) {
+        // Derivative of the hinge loss with respect to w·x - rho
+        let loss_gradient = if self.glm.predict(x) < F::zero() {
+            -F::one()
+        } else {
+            F::zero()
+        };
+        self.glm.learn(x, loss_gradient, F::one());
+        // Derivative of -nu * rho with respect to the intercept
+        self.glm.intercept -= self.intercept_lr * self.nu;
+    }
+}
+
+impl<F, O> AnomalyDetector<F> for OneClassSVM<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.learn(&observation_features(x));
+    }
+    fn score_one(&self, x: &Observation<F>) -> F {
+        -self.glm.predict(&observation_features(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::optimizers::SGD;
+    use crate::testing::{scrambled, scrambled_other};
+
+    fn sample(i: usize) -> Observation<f64> {
+        Observation::from([
+            ("a".to_string(), 2.0 + scrambled(i, 100) as f64 / 100.0),
+            ("b".to_string(), 2.0 + scrambled_other(i, 97) as f64 / 97.0),
+        ])
+    }
+
+    #[test]
+    fn test_nu() {
+        for nu in [0.05, 0.2, 0.5] {
+            let mut model = OneClassSVM::new(SGD::new(0.01), Some(nu), None);
+            for i in 0..20000 {
+                model.learn_one(&sample(i));
+            }
+            // The fraction of samples beyond the boundary is close to nu
+            let n_outliers = (0..1000)
+                .filter(|i| model.score_one(&sample(*i)) > 0.0)
+                .count();
+            let rate = n_outliers as f64 / 1000.0;
+            assert!((rate - nu).abs() < 0.1, "nu = {}: {}", nu, rate);
+            assert!(model.rho() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut model = OneClassSVM::new(SGD::new(0.01), None, None);
+        let sparse = |x: &Observation<f64>| -> Features<f64> {
+            SparseVector::from([
+                (0, x.get_numeric("a").unwrap()),
+                (1, x.get_numeric("b").unwrap()),
+            ])
+            .into()
+        };
+        for i in 0..5000 {
+            model.learn_features(&sparse(&sample(i)));
+        }
+        let outlier: Features<f64> = SparseVector::from([(0, 0.2), (1, 0.1)]).into();
+        assert!(model.score_features(&outlier) > 0.0);
+        assert!(model.score_features(&sparse(&sample(3))) < model.score_features(&outlier));
+        assert_eq!(model.weights().len(), 2);
+    }
+}
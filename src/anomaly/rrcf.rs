@@ -0,0 +1,521 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::AnomalyDetector;
+use crate::sketch::hash;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of a [`RobustRandomCutForest`].
+///
+/// - `n_trees`: The number of trees, 50 by default.
+/// - `tree_size`: The number of points in the sample of each tree, 256 by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RobustRandomCutForestOptions {
+    pub n_trees: usize,
+    pub tree_size: usize,
+}
+
+impl Default for RobustRandomCutForestOptions {
+    fn default() -> Self {
+        Self {
+            n_trees: 50,
+            tree_size: 256,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Kind<F> {
+    // The box of a leaf is its point
+    Leaf,
+    // The points whose coordinate `dim` is at most `value` are on the left
+    Branch {
+        dim: usize,
+        value: F,
+        left: usize,
+        right: usize,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Node<F> {
+    parent: Option<usize>,
+    // Number of points in the subtree, duplicates included
+    size: usize,
+    bbox: Vec<(F, F)>,
+    kind: Kind<F>,
+}
+
+// Smallest box containing a box and a point.
+fn extend<F: Float>(bbox: &[(F, F)], point: &[F]) -> Vec<(F, F)> {
+    bbox.iter()
+        .zip(point)
+        .map(|((lo, hi), x)| (lo.min(*x), hi.max(*x)))
+        .collect()
+}
+
+// Random cut of a box, whose dimension is drawn proportionally to the spans of the box, or `None`
+// if the box is a single point.
+fn random_cut<F: Float + FromPrimitive>(bbox: &[(F, F)], rng: &mut StdRng) -> Option<(usize, F)> {
+    let total = bbox
+        .iter()
+        .fold(F::zero(), |acc, (lo, hi)| acc + (*hi - *lo));
+    if total == F::zero() {
+        return None;
+    }
+    let mut r = F::from_f64(rng.gen()).unwrap() * total;
+    for (dim, (lo, hi)) in bbox.iter().enumerate() {
+        let span = *hi - *lo;
+        if r < span {
+            return Some((dim, *lo + r));
+        }
+        r = r - span;
+    }
+    // Rounding errors, the cut goes at the end of the last dimension which has a span
+    bbox.iter()
+        .enumerate()
+        .rev()
+        .find(|(_, (lo, hi))| hi > lo)
+        .map(|(dim, (lo, hi))| (dim, *lo + (*hi - *lo) * F::from_f64(0.5).unwrap()))
+}
+
+// Dimension and value of a cut, and whether the point it isolates is on its right.
+type Cut<F> = (usize, F, bool);
+
+// Robust random cut tree, which supports inserting and deleting points.
+#[derive(Clone, Debug)]
+struct RandomCutTree<F> {
+    nodes: Vec<Node<F>>,
+    // Indices of the nodes which have been deleted and can be reused
+    free: Vec<usize>,
+    root: Option<usize>,
+    // Leaves of the points of the reservoir
+    sample: Vec<usize>,
+}
+
+impl<F: Float + FromPrimitive> RandomCutTree<F> {
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            sample: Vec::new(),
+        }
+    }
+    fn alloc(&mut self, node: Node<F>) -> usize {
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+    fn children(&self, i: usize) -> Option<(usize, usize)> {
+        match self.nodes[i].kind {
+            Kind::Branch { left, right, .. } => Some((left, right)),
+            Kind::Leaf => None,
+        }
+    }
+    fn sibling(&self, i: usize) -> Option<usize> {
+        let parent = self.nodes[i].parent?;
+        let (left, right) = self.children(parent).unwrap();
+        Some(if left == i { right } else { left })
+    }
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        self.nodes[new].parent = parent;
+        match parent {
+            None => self.root = Some(new),
+            Some(p) => {
+                if let Kind::Branch { left, right, .. } = &mut self.nodes[p].kind {
+                    if *left == old {
+                        *left = new;
+                    } else {
+                        *right = new;
+                    }
+                }
+            }
+        }
+    }
+    // Walk down the tree as the insertion of the point would. Return the node above which the
+    // point is cut off, with the cut and whether the point goes to its right, or the leaf of the
+    // same point.
+    fn descend(&self, point: &[F], rng: &mut StdRng) -> Option<(usize, Option<Cut<F>>)> {
+        let mut i = self.root?;
+        loop {
+            let node = &self.nodes[i];
+            let bbox = extend(&node.bbox, point);
+            let Some((dim, value)) = random_cut(&bbox, rng) else {
+                // The point is a duplicate of a leaf
+                return Some((i, None));
+            };
+            let (lo, hi) = node.bbox[dim];
+            if value < lo {
+                return Some((i, Some((dim, value, false))));
+            }
+            if value >= hi {
+                return Some((i, Some((dim, value, true))));
+            }
+            match node.kind {
+                Kind::Branch {
+                    dim,
+                    value,
+                    left,
+                    right,
+                    ..
+                } => i = if point[dim] <= value { left } else { right },
+                Kind::Leaf => return Some((i, None)),
+            }
+        }
+    }
+    // Insert a point, and return its leaf.
+    fn insert(&mut self, point: Vec<F>, rng: &mut StdRng) -> usize {
+        let bbox: Vec<(F, F)> = point.iter().map(|x| (*x, *x)).collect();
+        let leaf = Node {
+            parent: None,
+            size: 1,
+            bbox,
+            kind: Kind::Leaf,
+        };
+        let Some((i, cut)) = self.descend(&point, rng) else {
+            let leaf = self.alloc(leaf);
+            self.root = Some(leaf);
+            return leaf;
+        };
+        let (leaf, start) = match cut {
+            None => (i, i),
+            Some((dim, value, is_right)) => {
+                let parent = self.nodes[i].parent;
+                let leaf = self.alloc(leaf);
+                let (left, right) = if is_right { (i, leaf) } else { (leaf, i) };
+                let branch = self.alloc(Node {
+                    parent,
+                    size: self.nodes[i].size,
+                    bbox: self.nodes[i].bbox.clone(),
+                    kind: Kind::Branch {
+                        dim,
+                        value,
+                        left,
+                        right,
+                    },
+                });
+                self.replace_child(parent, i, branch);
+                self.nodes[i].parent = Some(branch);
+                self.nodes[leaf].parent = Some(branch);
+                (leaf, branch)
+            }
+        };
+        // The sizes and boxes of the ancestors include the point
+        let mut current = Some(start);
+        while let Some(j) = current {
+            self.nodes[j].size += 1;
+            self.nodes[j].bbox = extend(&self.nodes[j].bbox, &point);
+            current = self.nodes[j].parent;
+        }
+        leaf
+    }
+    // Remove one copy of the point of a leaf.
+    fn delete(&mut self, leaf: usize) {
+        let mut current = if self.nodes[leaf].size > 1 {
+            Some(leaf)
+        } else {
+            let parent = self.nodes[leaf].parent;
+            self.free.push(leaf);
+            let Some(parent) = parent else {
+                self.root = None;
+                return;
+            };
+            let sibling = self.sibling(leaf).unwrap();
+            let grandparent = self.nodes[parent].parent;
+            self.replace_child(grandparent, parent, sibling);
+            self.free.push(parent);
+            grandparent
+        };
+        while let Some(j) = current {
+            self.nodes[j].size -= 1;
+            if let Some((left, right)) = self.children(j) {
+                self.nodes[j].bbox = self.nodes[left]
+                    .bbox
+                    .iter()
+                    .zip(self.nodes[right].bbox.iter())
+                    .map(|((l1, h1), (l2, h2))| (l1.min(*l2), h1.max(*h2)))
+                    .collect();
+            }
+            current = self.nodes[j].parent;
+        }
+    }
+    // Collusive displacement of a point, as if it was inserted in the tree: the largest ratio
+    // between the number of points of a sibling of one of its ancestors, which would be displaced
+    // if the point was removed, and the number of points of the ancestor.
+    fn codisp(&self, point: &[F], rng: &mut StdRng) -> F {
+        let Some((i, cut)) = self.descend(point, rng) else {
+            return F::zero();
+        };
+        let (mut ratio, mut size) = match cut {
+            // The point is the sibling of the node
+            Some(_) => (
+                F::from_usize(self.nodes[i].size).unwrap(),
+                self.nodes[i].size + 1,
+            ),
+            None => (F::zero(), self.nodes[i].size + 1),
+        };
+        let mut current = i;
+        while let Some(parent) = self.nodes[current].parent {
+            let sibling = self.sibling(current).unwrap();
+            let displacement =
+                F::from_usize(self.nodes[sibling].size).unwrap() / F::from_usize(size).unwrap();
+            ratio = ratio.max(displacement);
+            size = self.nodes[parent].size + 1;
+            current = parent;
+        }
+        ratio
+    }
+}
+
+/// Robust random cut forest (RRCF).
+///
+/// Each tree is built by cutting the bounding box of its points at random, the dimension of each
+/// cut being drawn proportionally to the span of the box, until each point is isolated. Unlike
+/// isolation forests, the trees are maintained incrementally: inserting or deleting a point gives
+/// a tree which has the same distribution as a tree built from scratch. Each tree keeps a
+/// reservoir sample of `tree_size` points of the stream, so that the points are inserted and
+/// deleted as the reservoir is updated.
+///
+/// The anomaly score of an observation is its collusive displacement (CoDisp), averaged over the
+/// trees. The displacement is the number of points whose depth would change if the observation
+/// was removed from a tree, i.e. the size of the sibling of its leaf. As a group of anomalies can
+/// mask each other, the displacement is computed at each ancestor, relatively to its size, and the
+/// largest one is kept. Scoring doesn't modify the trees: the cuts of the hypothetical insertion are
+/// drawn from a generator seeded with the observation, so that the scores are deterministic.
+///
+/// The features are those of the first observation, and missing ones count as zeros. For time
+/// series, the observations are usually shingles, i.e. the last values of the series.
+///
+/// # Parameters
+///
+/// - `options`: See [`RobustRandomCutForestOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::anomaly::rrcf::{RobustRandomCutForest, RobustRandomCutForestOptions};
+/// use light_river::common::Observation;
+/// use light_river::learner::AnomalyDetector;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = RobustRandomCutForestOptions {
+///     n_trees: 20,
+///     tree_size: 64,
+/// };
+/// let mut model = RobustRandomCutForest::new(options, Some(42));
+/// let obs = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..500 {
+///     let (a, b) = (rng.gen::<f64>(), rng.gen::<f64>());
+///     model.learn_one(&obs(a, b));
+/// }
+///
+/// assert!(model.score_one(&obs(3.0, 0.5)) > 2.0 * model.score_one(&obs(0.5, 0.5)));
+/// ```
+///
+/// # References
+///
+/// [^1]: S. Guha, N. Mishra, G. Roy and O. Schrijvers (2016). "Robust random cut forest based
+/// anomaly detection on streams". Proceedings of the 33rd international conference on machine
+/// learning, 2712-2721.
+#[derive(Clone, Debug)]
+pub struct RobustRandomCutForest<F> {
+    options: RobustRandomCutForestOptions,
+    features: Vec<String>,
+    trees: Vec<RandomCutTree<F>>,
+    n_samples: usize,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RobustRandomCutForest<F>
+{
+    pub fn new(options: RobustRandomCutForestOptions, seed: Option<u64>) -> Self {
+        assert!(options.n_trees > 0, "n_trees must be strictly positive");
+        assert!(options.tree_size > 0, "tree_size must be strictly positive");
+        Self {
+            options,
+            features: Vec::new(),
+            trees: (0..options.n_trees).map(|_| RandomCutTree::new()).collect(),
+            n_samples: 0,
+            rng: rng(seed),
+        }
+    }
+    pub fn options(&self) -> &RobustRandomCutForestOptions {
+        &self.options
+    }
+    /// Number of points in the sample of each tree.
+    pub fn tree_sizes(&self) -> Vec<usize> {
+        self.trees
+            .iter()
+            .map(|tree| tree.root.map_or(0, |root| tree.nodes[root].size))
+            .collect()
+    }
+    fn point(&self, x: &Observation<F>) -> Vec<F> {
+        self.features
+            .iter()
+            .map(|name| x.get_numeric(name).unwrap_or(F::zero()))
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyDetector<F>
+    for RobustRandomCutForest<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if self.features.is_empty() {
+            self.features = x.numeric().map(|(name, _)| name.clone()).collect();
+        }
+        let point = self.point(x);
+        self.n_samples += 1;
+        let tree_size = self.options.tree_size;
+        for tree in self.trees.iter_mut() {
+            // Reservoir sampling: the n-th point is kept with probability tree_size / n
+            if tree.sample.len() < tree_size {
+                let leaf = tree.insert(point.clone(), &mut self.rng);
+                tree.sample.push(leaf);
+            } else if self.rng.gen_range(0..self.n_samples) < tree_size {
+                let slot = self.rng.gen_range(0..tree_size);
+                tree.delete(tree.sample[slot]);
+                tree.sample[slot] = tree.insert(point.clone(), &mut self.rng);
+            }
+        }
+    }
+    fn score_one(&self, x: &Observation<F>) -> F {
+        if self.n_samples == 0 {
+            return F::zero();
+        }
+        let point = self.point(x);
+        let total = self
+            .trees
+            .iter()
+            .enumerate()
+            .fold(F::zero(), |acc, (i, tree)| {
+                // Each tree has its own hash function, seeded by its index
+                let mut rng = StdRng::seed_from_u64(hash(x, i as u64));
+                acc + tree.codisp(&point, &mut rng)
+            });
+        total / F::from_usize(self.trees.len()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{scrambled, scrambled_other};
+
+    // Check the sizes, boxes and parents of all the nodes reachable from the root
+    fn check(tree: &RandomCutTree<f64>) -> usize {
+        let Some(root) = tree.root else {
+            return 0;
+        };
+        assert_eq!(tree.nodes[root].parent, None);
+        let mut stack = vec![root];
+        let mut n_leaves = 0;
+        while let Some(i) = stack.pop() {
+            let node = &tree.nodes[i];
+            match &node.kind {
+                Kind::Leaf => {
+                    n_leaves += 1;
+                    assert!(node.bbox.iter().all(|(lo, hi)| lo == hi));
+                }
+                Kind::Branch {
+                    dim,
+                    value,
+                    left,
+                    right,
+                    ..
+                } => {
+                    let (l, r) = (&tree.nodes[*left], &tree.nodes[*right]);
+                    assert_eq!(l.parent, Some(i));
+                    assert_eq!(r.parent, Some(i));
+                    assert_eq!(node.size, l.size + r.size);
+                    assert!(l.bbox[*dim].1 <= *value && r.bbox[*dim].0 > *value);
+                    for d in 0..node.bbox.len() {
+                        assert_eq!(node.bbox[d].0, l.bbox[d].0.min(r.bbox[d].0));
+                        assert_eq!(node.bbox[d].1, l.bbox[d].1.max(r.bbox[d].1));
+                    }
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+            }
+        }
+        n_leaves
+    }
+
+    #[test]
+    fn test_insert_delete() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tree = RandomCutTree::new();
+        let mut leaves = Vec::new();
+        for i in 0..200 {
+            // Each point appears 4 times
+            let a = scrambled(i, 50);
+            let point = vec![a as f64, (a % 7) as f64];
+            leaves.push(tree.insert(point, &mut rng));
+        }
+        assert_eq!(tree.nodes[tree.root.unwrap()].size, 200);
+        assert_eq!(check(&tree), 50);
+        for leaf in leaves.iter().step_by(2) {
+            tree.delete(*leaf);
+        }
+        check(&tree);
+        assert_eq!(tree.nodes[tree.root.unwrap()].size, 100);
+        for leaf in leaves.iter().skip(1).step_by(2) {
+            tree.delete(*leaf);
+        }
+        assert_eq!(tree.root, None);
+        assert_eq!(tree.free.len(), tree.nodes.len());
+    }
+
+    #[test]
+    fn test_codisp() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tree = RandomCutTree::new();
+        for i in 0..100 {
+            tree.insert(vec![(i % 10) as f64 / 10.0], &mut rng);
+        }
+        // A far away point is cut off above the whole tree
+        assert_eq!(tree.codisp(&[100.0], &mut rng), 100.0);
+        assert!(tree.codisp(&[0.5], &mut rng) < 10.0);
+    }
+
+    #[test]
+    fn test_reservoir_and_scores() {
+        let options = RobustRandomCutForestOptions {
+            n_trees: 10,
+            tree_size: 50,
+        };
+        let mut model = RobustRandomCutForest::new(options, Some(42));
+        let sample = |i: usize| {
+            Observation::from([
+                ("a".to_string(), scrambled(i, 100) as f64 / 100.0),
+                ("b".to_string(), scrambled_other(i, 97) as f64 / 97.0),
+            ])
+        };
+        for i in 0..1000 {
+            model.learn_one(&sample(i));
+        }
+        assert_eq!(model.tree_sizes(), vec![50; 10]);
+        for tree in model.trees.iter() {
+            assert_eq!(check(tree), 50);
+        }
+        let outlier = Observation::from([("a".to_string(), 0.5), ("b".to_string(), 5.0)]);
+        let score = model.score_one(&outlier);
+        assert_eq!(score, model.score_one(&outlier));
+        for i in 0..20 {
+            assert!(model.score_one(&sample(i)) < score);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::bandit::{Arms, Policy};
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Epsilon-greedy policy.
+///
+/// A random arm is played with probability `epsilon`, and the arm with the best mean reward
+/// otherwise. Each arm is played once before that, in order.
+///
+/// # Parameters
+///
+/// - `n_arms`: The number of arms.
+/// - `epsilon`: The probability of exploring, between 0 and 1.
+/// - `seed`: Random seed of the exploration.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::bandit::epsilon_greedy::EpsilonGreedy;
+/// use light_river::bandit::Policy;
+///
+/// let mut policy = EpsilonGreedy::new(3, 0.1, Some(42));
+/// for _ in 0..1000 {
+///     let arm = policy.pull();
+///     // The second arm pays the most
+///     let reward = [0.2, 0.8, 0.5][arm];
+///     policy.update(arm, reward);
+/// }
+/// assert_eq!(policy.best_arm(), 1);
+/// assert!(policy.n_pulls()[1] > 900);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EpsilonGreedy<F> {
+    epsilon: F,
+    arms: Arms<F>,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive> EpsilonGreedy<F> {
+    pub fn new(n_arms: usize, epsilon: F, seed: Option<u64>) -> Self {
+        assert!(
+            epsilon >= F::zero() && epsilon <= F::one(),
+            "epsilon must be between 0 and 1"
+        );
+        Self {
+            epsilon,
+            arms: Arms::new(n_arms),
+            rng: rng(seed),
+        }
+    }
+    /// The arm with the best mean reward.
+    pub fn best_arm(&self) -> usize {
+        self.arms.argmax(|arm| self.arms.means[arm])
+    }
+    /// Number of times each arm has been played.
+    pub fn n_pulls(&self) -> &[usize] {
+        &self.arms.n_pulls
+    }
+    /// Mean reward of each arm, which is zero for the arms which haven't been played.
+    pub fn means(&self) -> &[F] {
+        &self.arms.means
+    }
+}
+
+impl<F: Float + FromPrimitive> Policy<F> for EpsilonGreedy<F> {
+    fn pull(&mut self) -> usize {
+        if let Some(arm) = self.arms.untried() {
+            return arm;
+        }
+        if F::from_f64(self.rng.gen()).unwrap() < self.epsilon {
+            self.rng.gen_range(0..self.arms.len())
+        } else {
+            self.best_arm()
+        }
+    }
+    fn update(&mut self, arm: usize, reward: F) {
+        self.arms.update(arm, reward);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exploration() {
+        let mut policy = EpsilonGreedy::new(4, 0.0, Some(42));
+        for _ in 0..4 {
+            let arm = policy.pull();
+            policy.update(arm, arm as f64);
+        }
+        assert_eq!(policy.n_pulls(), [1, 1, 1, 1]);
+        // Without exploration, only the best arm is played from then on
+        for _ in 0..10 {
+            let arm = policy.pull();
+            policy.update(arm, arm as f64);
+        }
+        assert_eq!(policy.n_pulls(), [1, 1, 1, 11]);
+
+        let mut policy = EpsilonGreedy::new(4, 1.0, Some(42));
+        for _ in 0..4000 {
+            let arm = policy.pull();
+            policy.update(arm, arm as f64);
+        }
+        assert!(
+            policy.n_pulls().iter().all(|n| *n > 900),
+            "{:?}",
+            policy.n_pulls()
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::bandit::ContextualPolicy;
+use crate::common::Observation;
+use num::{Float, FromPrimitive};
+
+// Ridge regression of the rewards of an arm on the context, along with an intercept.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Arm<F> {
+    // Inverse of the regularized Gram matrix of the contexts, row-major
+    inverse: Vec<F>,
+    // Sum of the contexts weighted by their rewards
+    b: Vec<F>,
+    n_pulls: usize,
+}
+
+impl<F: Float + FromPrimitive> Arm<F> {
+    fn new(dim: usize) -> Self {
+        let mut inverse = vec![F::zero(); dim * dim];
+        for i in 0..dim {
+            inverse[i * dim + i] = F::one();
+        }
+        Self {
+            inverse,
+            b: vec![F::zero(); dim],
+            n_pulls: 0,
+        }
+    }
+    // Product of the inverse Gram matrix with a context.
+    fn solve(&self, x: &[F]) -> Vec<F> {
+        self.inverse
+            .chunks(x.len())
+            .map(|row| dot(row, x))
+            .collect()
+    }
+    // Estimated reward and width of its confidence interval.
+    fn estimate(&self, x: &[F]) -> (F, F) {
+        let theta = self.solve(&self.b);
+        (dot(&theta, x), dot(&self.solve(x), x).max(F::zero()).sqrt())
+    }
+    fn update(&mut self, x: &[F], reward: F) {
+        // Sherman-Morrison update of the inverse, which is symmetric
+        let ax = self.solve(x);
+        let denominator = F::one() + dot(&ax, x);
+        let dim = x.len();
+        for i in 0..dim {
+            for j in 0..dim {
+                self.inverse[i * dim + j] = self.inverse[i * dim + j] - ax[i] * ax[j] / denominator;
+            }
+        }
+        for (b, x) in self.b.iter_mut().zip(x.iter()) {
+            *b = *b + reward * *x;
+        }
+        self.n_pulls += 1;
+    }
+}
+
+fn dot<F: Float>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b.iter())
+        .fold(F::zero(), |sum, (a, b)| sum + *a * *b)
+}
+
+/// LinUCB, a contextual bandit policy whose rewards are linear in the features of the context.
+///
+/// The reward of each arm is estimated by a ridge regression on the numeric features of the
+/// context, and the arm with the highest upper confidence bound of its estimated reward is
+/// played, i.e. the estimate plus `alpha` times its standard deviation. Ties are broken in favor
+/// of the first arm.
+///
+/// The features are the numeric features of the first observation, along with an intercept, and
+/// missing features count as zeros. New features are ignored, so all the features should be
+/// present from the start.
+///
+/// # Parameters
+///
+/// - `n_arms`: The number of arms.
+/// - `alpha`: The scale of the confidence bound. The larger, the more exploration. Defaults to 1.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::bandit::lin_ucb::LinUCB;
+/// use light_river::bandit::ContextualPolicy;
+/// use light_river::common::Observation;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut policy = LinUCB::new(2, None);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..500 {
+///     // The first arm pays off for positive values of the feature, the second for negative ones
+///     let a = rng.gen_range(-1.0..1.0);
+///     let x = Observation::from([("a".to_string(), a)]);
+///     let arm = policy.pull(&x);
+///     let reward = if arm == 0 { a } else { -a };
+///     policy.update(arm, &x, reward);
+/// }
+/// assert_eq!(policy.pull(&Observation::from([("a".to_string(), 0.5)])), 0);
+/// assert_eq!(policy.pull(&Observation::from([("a".to_string(), -0.5)])), 1);
+/// ```
+///
+/// # References
+///
+/// [^1]: L. Li, W. Chu, J. Langford and R. E. Schapire (2010). "A contextual-bandit approach to
+/// personalized news article recommendation". Proceedings of the 19th international conference on
+/// World Wide Web.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinUCB<F> {
+    alpha: F,
+    n_arms: usize,
+    // Features of the context, those of the first observation, which are empty until then
+    features: Option<Vec<String>>,
+    arms: Vec<Arm<F>>,
+}
+
+impl<F: Float + FromPrimitive> LinUCB<F> {
+    pub fn new(n_arms: usize, alpha: Option<F>) -> Self {
+        assert!(n_arms > 0, "n_arms must be strictly positive");
+        let alpha = alpha.unwrap_or(F::one());
+        assert!(alpha >= F::zero(), "alpha must be positive");
+        Self {
+            alpha,
+            n_arms,
+            features: None,
+            arms: Vec::new(),
+        }
+    }
+    /// The features of the context, which are known once the first observation has been seen.
+    pub fn features(&self) -> Option<&[String]> {
+        self.features.as_deref()
+    }
+    /// Number of times each arm has been played, which is empty until the first observation has
+    /// been seen.
+    pub fn n_pulls(&self) -> Vec<usize> {
+        self.arms.iter().map(|arm| arm.n_pulls).collect()
+    }
+    /// The estimated reward of each arm in a context, along with the standard deviation of the
+    /// estimate.
+    pub fn estimates(&mut self, x: &Observation<F>) -> Vec<(F, F)> {
+        let context = self.context(x);
+        self.arms.iter().map(|arm| arm.estimate(&context)).collect()
+    }
+    // The intercept followed by the features of an observation, which are set by the first one.
+    fn context(&mut self, x: &Observation<F>) -> Vec<F> {
+        let features = self
+            .features
+            .get_or_insert_with(|| x.numeric().map(|(name, _)| name.clone()).collect());
+        let context: Vec<F> = std::iter::once(F::one())
+            .chain(
+                features
+                    .iter()
+                    .map(|name| x.get_numeric(name).unwrap_or(F::zero())),
+            )
+            .collect();
+        if self.arms.is_empty() {
+            self.arms = (0..self.n_arms).map(|_| Arm::new(context.len())).collect();
+        }
+        context
+    }
+}
+
+impl<F: Float + FromPrimitive> ContextualPolicy<F> for LinUCB<F> {
+    fn pull(&mut self, x: &Observation<F>) -> usize {
+        let bounds: Vec<F> = self
+            .estimates(x)
+            .into_iter()
+            .map(|(estimate, std)| estimate + self.alpha * std)
+            .collect();
+        (0..self.n_arms).fold(0, |best, arm| {
+            if bounds[arm] > bounds[best] {
+                arm
+            } else {
+                best
+            }
+        })
+    }
+    fn update(&mut self, arm: usize, x: &Observation<F>, reward: F) {
+        let context = self.context(x);
+        self.arms[arm].update(&context, reward);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ridge_estimates() {
+        let mut policy = LinUCB::new(2, Some(0.0));
+        let x = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+        // The rewards of the first arm are 1 + 2a - b, without noise
+        for i in 0..200 {
+            let (a, b) = ((i % 7) as f64 - 3.0, (i % 5) as f64 - 2.0);
+            policy.update(0, &x(a, b), 1.0 + 2.0 * a - b);
+        }
+        assert_eq!(policy.features().unwrap(), ["a", "b"]);
+        assert_eq!(policy.n_pulls(), [200, 0]);
+        let estimates = policy.estimates(&x(1.0, 1.0));
+        assert!((estimates[0].0 - 2.0).abs() < 0.05, "{:?}", estimates);
+        assert!(estimates[0].1 < 0.2);
+        // Nothing is known of the second arm, whose estimate is zero with a unit variance
+        assert_eq!(estimates[1].0, 0.0);
+        assert!((estimates[1].1 - 3.0f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,63 @@
+pub mod epsilon_greedy;
+pub mod lin_ucb;
+pub mod thompson;
+pub mod ucb;
+
+use crate::common::Observation;
+use num::Float;
+
+/// Trait for implementing a multi-armed bandit policy, which picks one of several arms to play
+/// and learns from the reward it then obtains, e.g. to pick which model to train or which variant
+/// to show. The arms are numbered from 0, and the larger the reward, the better.
+pub trait Policy<F> {
+    /// The arm to play next.
+    fn pull(&mut self) -> usize;
+    /// Learn the reward obtained by playing an arm.
+    fn update(&mut self, arm: usize, reward: F);
+}
+
+/// Trait for implementing a contextual bandit policy, whose choice of arm depends on the features
+/// of the context in which it is played.
+pub trait ContextualPolicy<F: Float> {
+    /// The arm to play next, given the context.
+    fn pull(&mut self, x: &Observation<F>) -> usize;
+    /// Learn the reward obtained by playing an arm in a context.
+    fn update(&mut self, arm: usize, x: &Observation<F>, reward: F);
+}
+
+// Number of times each arm has been played, and its mean reward.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Arms<F> {
+    pub(crate) n_pulls: Vec<usize>,
+    pub(crate) means: Vec<F>,
+}
+
+impl<F: Float> Arms<F> {
+    pub(crate) fn new(n_arms: usize) -> Self {
+        assert!(n_arms > 0, "n_arms must be strictly positive");
+        Self {
+            n_pulls: vec![0; n_arms],
+            means: vec![F::zero(); n_arms],
+        }
+    }
+    pub(crate) fn len(&self) -> usize {
+        self.n_pulls.len()
+    }
+    pub(crate) fn update(&mut self, arm: usize, reward: F) {
+        self.n_pulls[arm] += 1;
+        let n = F::from(self.n_pulls[arm]).unwrap();
+        self.means[arm] = self.means[arm] + (reward - self.means[arm]) / n;
+    }
+    // The first arm which has never been played.
+    pub(crate) fn untried(&self) -> Option<usize> {
+        self.n_pulls.iter().position(|n| *n == 0)
+    }
+    // The arm with the largest score, the first one among ties.
+    pub(crate) fn argmax(&self, score: impl Fn(usize) -> F) -> usize {
+        (0..self.len()).fold(
+            0,
+            |best, arm| if score(arm) > score(best) { arm } else { best },
+        )
+    }
+}
@@ -0,0 +1,148 @@
+use crate::bandit::Policy;
+use crate::utils::{rng, standard_normal};
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Thompson sampling for rewards between 0 and 1, e.g. clicks.
+///
+/// The mean reward of each arm has a beta posterior distribution, which starts from a uniform
+/// prior, and whose parameters count the successes and the failures of the arm. A reward `r`
+/// counts as `r` success and `1 - r` failure. A mean reward is drawn from the posterior of each
+/// arm, and the arm with the largest draw is played, so that each arm is played with the
+/// probability that it is the best one.
+///
+/// # Parameters
+///
+/// - `n_arms`: The number of arms.
+/// - `seed`: Random seed of the draws.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::bandit::thompson::ThompsonSampling;
+/// use light_river::bandit::Policy;
+///
+/// let mut policy = ThompsonSampling::new(3, Some(42));
+/// for i in 0..2000 {
+///     let arm = policy.pull();
+///     // The arms succeed one time out of 5, 2 and 4
+///     let reward = if i % [5, 2, 4][arm] == 0 { 1.0 } else { 0.0 };
+///     policy.update(arm, reward);
+/// }
+/// assert_eq!(policy.best_arm(), 1);
+/// assert!(policy.n_pulls()[1] > 1500);
+/// ```
+///
+/// # References
+///
+/// [^1]: O. Chapelle and L. Li (2011). "An empirical evaluation of Thompson sampling". Advances
+/// in neural information processing systems 24.
+#[derive(Clone, Debug)]
+pub struct ThompsonSampling<F> {
+    // Parameters of the beta posterior of each arm
+    alphas: Vec<F>,
+    betas: Vec<F>,
+    n_pulls: Vec<usize>,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive> ThompsonSampling<F> {
+    pub fn new(n_arms: usize, seed: Option<u64>) -> Self {
+        assert!(n_arms > 0, "n_arms must be strictly positive");
+        Self {
+            alphas: vec![F::one(); n_arms],
+            betas: vec![F::one(); n_arms],
+            n_pulls: vec![0; n_arms],
+            rng: rng(seed),
+        }
+    }
+    /// The mean of the posterior of the mean reward of an arm.
+    pub fn mean(&self, arm: usize) -> F {
+        self.alphas[arm] / (self.alphas[arm] + self.betas[arm])
+    }
+    /// The arm with the best posterior mean.
+    pub fn best_arm(&self) -> usize {
+        (0..self.n_pulls.len()).fold(0, |best, arm| {
+            if self.mean(arm) > self.mean(best) {
+                arm
+            } else {
+                best
+            }
+        })
+    }
+    /// Number of times each arm has been played.
+    pub fn n_pulls(&self) -> &[usize] {
+        &self.n_pulls
+    }
+    // Draw a gamma variable of unit scale with the method of Marsaglia and Tsang, boosting the
+    // shape above 1 when it is smaller.
+    fn gamma(&mut self, shape: f64) -> f64 {
+        if shape < 1.0 {
+            let u: f64 = self.rng.gen();
+            return self.gamma(shape + 1.0) * u.powf(1.0 / shape);
+        }
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let z = standard_normal(&mut self.rng);
+            let v = (1.0 + c * z).powi(3);
+            if v <= 0.0 {
+                continue;
+            }
+            let u: f64 = 1.0 - self.rng.gen::<f64>();
+            if u.ln() < 0.5 * z * z + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+    // Draw a beta variable as a ratio of gamma variables.
+    fn beta(&mut self, alpha: F, beta: F) -> f64 {
+        let x = self.gamma(alpha.to_f64().unwrap());
+        let y = self.gamma(beta.to_f64().unwrap());
+        x / (x + y)
+    }
+}
+
+impl<F: Float + FromPrimitive> Policy<F> for ThompsonSampling<F> {
+    fn pull(&mut self) -> usize {
+        let mut best = (0, f64::NEG_INFINITY);
+        for arm in 0..self.n_pulls.len() {
+            let draw = self.beta(self.alphas[arm], self.betas[arm]);
+            if draw > best.1 {
+                best = (arm, draw);
+            }
+        }
+        best.0
+    }
+    /// # Panics
+    ///
+    /// If the reward isn't between 0 and 1.
+    fn update(&mut self, arm: usize, reward: F) {
+        assert!(
+            reward >= F::zero() && reward <= F::one(),
+            "The rewards of Thompson sampling must be between 0 and 1"
+        );
+        self.alphas[arm] = self.alphas[arm] + reward;
+        self.betas[arm] = self.betas[arm] + F::one() - reward;
+        self.n_pulls[arm] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beta_draws() {
+        let mut policy: ThompsonSampling<f64> = ThompsonSampling::new(1, Some(42));
+        // Beta(2, 6) has a mean of 1/4 and a variance of 12 / (64 * 9)
+        let draws: Vec<f64> = (0..20000).map(|_| policy.beta(2.0, 6.0)).collect();
+        let mean = draws.iter().sum::<f64>() / 20000.0;
+        let var = draws.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 20000.0;
+        assert!((mean - 0.25).abs() < 0.005, "{}", mean);
+        assert!((var - 12.0 / 576.0).abs() < 0.001, "{}", var);
+        // Shapes below 1 are handled too
+        let mean = (0..20000).map(|_| policy.beta(0.5, 0.5)).sum::<f64>() / 20000.0;
+        assert!((mean - 0.5).abs() < 0.01, "{}", mean);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::bandit::{Arms, Policy};
+use num::{Float, FromPrimitive};
+
+/// Upper confidence bound policy, UCB1.
+///
+/// The arm with the highest upper confidence bound of its mean reward is played, i.e. its mean
+/// reward plus `delta * sqrt(2 ln(n) / n_i)`, where `n_i` is the number of times it has been
+/// played out of `n`. The arms which are rarely played thus get explored, less and less as
+/// evidence accumulates. Each arm is played once before that, in order. The policy is
+/// deterministic.
+///
+/// # Parameters
+///
+/// - `n_arms`: The number of arms.
+/// - `delta`: The scale of the confidence bound, which should be of the order of the spread of
+///   the rewards. The larger, the more exploration. Defaults to 1, which suits rewards between 0
+///   and 1.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::bandit::ucb::UCB1;
+/// use light_river::bandit::Policy;
+///
+/// let mut policy = UCB1::new(3, None);
+/// for i in 0..3000 {
+///     let arm = policy.pull();
+///     // The rewards of the arms alternate around means of 0.3, 0.6 and 0.5
+///     let reward = [0.3, 0.6, 0.5][arm] + if i % 2 == 0 { 0.2 } else { -0.2 };
+///     policy.update(arm, reward);
+/// }
+/// let n_pulls = policy.n_pulls();
+/// assert!(n_pulls[1] > n_pulls[0] + n_pulls[2]);
+/// ```
+///
+/// # References
+///
+/// [^1]: P. Auer, N. Cesa-Bianchi and P. Fischer (2002). "Finite-time analysis of the multiarmed
+/// bandit problem". Machine Learning 47:235-256.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UCB1<F> {
+    delta: F,
+    arms: Arms<F>,
+}
+
+impl<F: Float + FromPrimitive> UCB1<F> {
+    pub fn new(n_arms: usize, delta: Option<F>) -> Self {
+        let delta = delta.unwrap_or(F::one());
+        assert!(delta >= F::zero(), "delta must be positive");
+        Self {
+            delta,
+            arms: Arms::new(n_arms),
+        }
+    }
+    /// The upper confidence bound of the mean reward of an arm, which is infinite for the arms
+    /// which haven't been played.
+    pub fn bound(&self, arm: usize) -> F {
+        let n_i = self.arms.n_pulls[arm];
+        if n_i == 0 {
+            return F::infinity();
+        }
+        let n = F::from_usize(self.arms.n_pulls.iter().sum()).unwrap();
+        let n_i = F::from_usize(n_i).unwrap();
+        let two = F::from_f64(2.0).unwrap();
+        self.arms.means[arm] + self.delta * (two * n.ln() / n_i).sqrt()
+    }
+    /// The arm with the best mean reward.
+    pub fn best_arm(&self) -> usize {
+        self.arms.argmax(|arm| self.arms.means[arm])
+    }
+    /// Number of times each arm has been played.
+    pub fn n_pulls(&self) -> &[usize] {
+        &self.arms.n_pulls
+    }
+    /// Mean reward of each arm, which is zero for the arms which haven't been played.
+    pub fn means(&self) -> &[F] {
+        &self.arms.means
+    }
+}
+
+impl<F: Float + FromPrimitive> Policy<F> for UCB1<F> {
+    fn pull(&mut self) -> usize {
+        if let Some(arm) = self.arms.untried() {
+            return arm;
+        }
+        self.arms.argmax(|arm| self.bound(arm))
+    }
+    fn update(&mut self, arm: usize, reward: F) {
+        self.arms.update(arm, reward);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bound() {
+        let mut policy = UCB1::new(2, Some(0.5));
+        assert_eq!(policy.bound(0), f64::INFINITY);
+        for (arm, reward) in [(0, 1.0), (1, 0.0), (1, 1.0), (0, 0.0)] {
+            policy.update(arm, reward);
+        }
+        let expected = 0.5 + 0.5 * (2.0 * 4.0f64.ln() / 2.0).sqrt();
+        assert!((policy.bound(0) - expected).abs() < 1e-12);
+        // Ties are broken in favor of the first arm
+        assert_eq!(policy.pull(), 0);
+        policy.update(1, 1.0);
+        assert_eq!(policy.pull(), 1);
+    }
+}
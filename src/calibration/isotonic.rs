@@ -0,0 +1,214 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::calibration::positive_proba;
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba};
+use num::{Float, FromPrimitive};
+
+/// Isotonic calibration of the probabilities of a binary classifier.
+///
+/// The probabilities of the positive class given by the classifier are binned into `n_bins`
+/// intervals of equal width, and the frequency of the positive class is counted within each bin.
+/// The calibrated probability of a bin is then its frequency, made non-decreasing with the
+/// probabilities with the pool adjacent violators algorithm, so that the mapping preserves the
+/// ranking of the classifier. Unlike Platt scaling, any monotonic distortion of the probabilities
+/// can be corrected, but more samples are needed.
+///
+/// The probabilities whose bin is empty are left as they are until it isn't. As for
+/// [`PlattCalibrator`](crate::calibration::platt::PlattCalibrator), the mapping is learned from
+/// the predictions that the classifier makes before learning from each sample, and the labels
+/// must be booleans.
+///
+/// # Parameters
+///
+/// - `classifier`: The binary classifier to calibrate.
+/// - `n_bins`: The number of bins of the probabilities, 20 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::calibration::isotonic::IsotonicCalibrator;
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+/// let mut calibrated = IsotonicCalibrator::new(model, Some(10));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     calibrated.learn_one(&observation, ClassifierTarget::from(x > 0.5));
+/// }
+/// let calibration: Vec<f64> = calibrated.calibration().iter().flatten().copied().collect();
+/// assert!(calibration.windows(2).all(|pair| pair[0] <= pair[1]));
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(calibrated.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: B. Zadrozny and C. Elkan (2002). "Transforming classifier scores into accurate multiclass
+/// probability estimates". Proceedings of the eighth ACM SIGKDD international conference on
+/// knowledge discovery and data mining, 694-699.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IsotonicCalibrator<F, M> {
+    classifier: M,
+    // Number of samples and of positive samples in each bin
+    counts: Vec<(F, F)>,
+    // Calibrated probability of each bin, if any
+    calibration: Vec<Option<F>>,
+}
+
+impl<F, M> IsotonicCalibrator<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    pub fn new(classifier: M, n_bins: Option<usize>) -> Self {
+        let n_bins = n_bins.unwrap_or(20);
+        assert!(n_bins > 0, "n_bins must be strictly positive");
+        Self {
+            classifier,
+            counts: vec![(F::zero(), F::zero()); n_bins],
+            calibration: vec![None; n_bins],
+        }
+    }
+    pub fn classifier(&self) -> &M {
+        &self.classifier
+    }
+    /// The calibrated probability of each bin, or `None` while it is empty.
+    pub fn calibration(&self) -> &[Option<F>] {
+        &self.calibration
+    }
+    /// The calibrated probability of the positive class, given the one of the classifier.
+    pub fn calibrate(&self, p: F) -> F {
+        self.calibration[self.bin(p)].unwrap_or(p)
+    }
+    fn bin(&self, p: F) -> usize {
+        let n_bins = self.counts.len();
+        let bin = (p * F::from_usize(n_bins).unwrap()).to_usize().unwrap_or(0);
+        bin.min(n_bins - 1)
+    }
+    // Pool adjacent violators over the bins which aren't empty.
+    fn update_calibration(&mut self) {
+        // Blocks of pooled bins, as their first bin, number of samples and of positive samples
+        let mut blocks: Vec<(usize, F, F)> = Vec::new();
+        for (i, (n, positives)) in self.counts.iter().enumerate() {
+            if *n == F::zero() {
+                continue;
+            }
+            blocks.push((i, *n, *positives));
+            while blocks.len() > 1 {
+                let (_, n_last, pos_last) = blocks[blocks.len() - 1];
+                let (_, n_prev, pos_prev) = blocks[blocks.len() - 2];
+                if pos_prev / n_prev <= pos_last / n_last {
+                    break;
+                }
+                blocks.pop();
+                let previous = blocks.last_mut().unwrap();
+                previous.1 += n_last;
+                previous.2 += pos_last;
+            }
+        }
+        let mut blocks = blocks.into_iter().peekable();
+        let mut current = None;
+        for (i, p) in self.calibration.iter_mut().enumerate() {
+            while let Some(&(start, n, positives)) = blocks.peek() {
+                if start > i {
+                    break;
+                }
+                current = Some(positives / n);
+                blocks.next();
+            }
+            if self.counts[i].0 > F::zero() {
+                *p = current;
+            }
+        }
+    }
+}
+
+impl<F, M> Classifier<F> for IsotonicCalibrator<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let label = binary_label(y.clone(), "IsotonicCalibrator");
+        if let Some(p) = positive_proba(&self.classifier.predict_proba(x)) {
+            let bin = self.bin(p);
+            self.counts[bin].0 += F::one();
+            if label {
+                self.counts[bin].1 += F::one();
+            }
+            self.update_calibration();
+        }
+        self.classifier.learn_one(x, y);
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        match positive_proba(&self.classifier.predict_proba(x)) {
+            Some(p) => binary_proba(self.calibrate(p)),
+            None => ClassifierTargetProbabilities::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    // Gives x³ as the probability of the positive class, whose true probability is x
+    struct Skewed;
+
+    impl Classifier<f64> for Skewed {
+        fn learn_one(&mut self, _x: &Observation<f64>, _y: ClassifierTarget) {}
+        fn predict_proba(&self, x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            binary_proba(x.get_numeric("x").unwrap().powi(3))
+        }
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut calibrated = IsotonicCalibrator::new(Skewed, Some(10));
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20000 {
+            let x: f64 = rng.gen();
+            let y = rng.gen::<f64>() < x;
+            calibrated.learn_one(
+                &Observation::from([("x".to_string(), x)]),
+                ClassifierTarget::Bool(y),
+            );
+        }
+        // The bin [0.1, 0.2) holds the x in [0.46, 0.58)
+        assert!((calibrated.calibrate(0.15) - 0.52).abs() < 0.03);
+        let calibration: Vec<f64> = calibrated.calibration().iter().flatten().copied().collect();
+        assert_eq!(calibration.len(), 10);
+        assert!(calibration.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(calibration[0] < 0.4 && calibration[9] > 0.95);
+    }
+
+    #[test]
+    fn test_pooling() {
+        let mut calibrated = IsotonicCalibrator::new(Skewed, Some(4));
+        // The bins of 0.1 and 0.3 violate the order, and are pooled; the bin of 0.6 stays empty
+        for (x, y) in [(0.1, true), (0.1, true), (0.3, false), (0.9, true)] {
+            let bin = calibrated.bin(x);
+            calibrated.counts[bin].0 += 1.0;
+            if y {
+                calibrated.counts[bin].1 += 1.0;
+            }
+        }
+        calibrated.update_calibration();
+        assert_eq!(
+            calibrated.calibration(),
+            [Some(2.0 / 3.0), Some(2.0 / 3.0), None, Some(1.0)]
+        );
+        assert_eq!(calibrated.calibrate(0.6), 0.6);
+    }
+}
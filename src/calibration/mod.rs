@@ -0,0 +1,20 @@
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+use num::Float;
+
+pub mod isotonic;
+pub mod platt;
+
+// Probability of the positive class given by a binary classifier, if it gave any probability.
+pub(crate) fn positive_proba<F: Float>(
+    probabilities: &ClassifierTargetProbabilities<F>,
+) -> Option<F> {
+    if probabilities.is_empty() {
+        return None;
+    }
+    Some(
+        probabilities
+            .get(&ClassifierTarget::Bool(true))
+            .copied()
+            .unwrap_or(F::zero()),
+    )
+}
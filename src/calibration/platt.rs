@@ -0,0 +1,177 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::calibration::positive_proba;
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Observation};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Platt scaling of the probabilities of a binary classifier.
+///
+/// The probability `p` of the positive class given by the classifier is mapped to
+/// `sigmoid(a * logit(p) + b)`, where `a` and `b` are learned on the stream by an optimizer of the
+/// log loss. The mapping is the identity before anything is learned, and corrects
+/// classifiers which are over or under confident, or biased towards a class.
+///
+/// Each sample is first used to calibrate the prediction that the classifier makes before learning
+/// from it, so that the mapping is learned from predictions on unseen samples. The labels must be
+/// booleans.
+///
+/// # Parameters
+///
+/// - `classifier`: The binary classifier to calibrate.
+/// - `optimizer`: The optimizer of `a` and `b`, see [`crate::optim::optimizers`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::calibration::platt::PlattCalibrator;
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+/// let mut calibrated = PlattCalibrator::new(model, SGD::new(0.01));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     calibrated.learn_one(&observation, ClassifierTarget::from(x > 0.5));
+/// }
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// let probabilities = calibrated.predict_proba(&observation);
+/// assert!(probabilities[&ClassifierTarget::from(true)] > 0.5);
+/// assert_eq!(calibrated.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Platt (1999). "Probabilistic outputs for support vector machines and comparisons to
+/// regularized likelihood methods". Advances in large margin classifiers 10(3):61-74.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlattCalibrator<F, M, O> {
+    classifier: M,
+    optimizer: O,
+    // The slope and the intercept of the mapping
+    weights: Weights<F>,
+}
+
+const SLOPE: FeatureKey<'static> = FeatureKey::Name("a");
+const INTERCEPT: FeatureKey<'static> = FeatureKey::Name("b");
+
+impl<F, M, O> PlattCalibrator<F, M, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+    O: Optimizer<F>,
+{
+    pub fn new(classifier: M, optimizer: O) -> Self {
+        let mut weights = Weights::new();
+        *weights.get_mut(SLOPE) = F::one();
+        Self {
+            classifier,
+            optimizer,
+            weights,
+        }
+    }
+    pub fn classifier(&self) -> &M {
+        &self.classifier
+    }
+    /// The slope and the intercept of the mapping.
+    pub fn parameters(&self) -> (F, F) {
+        (self.weights.get(SLOPE), self.weights.get(INTERCEPT))
+    }
+    /// The calibrated probability of the positive class, given the one of the classifier.
+    pub fn calibrate(&self, p: F) -> F {
+        let (a, b) = self.parameters();
+        sigmoid(a * logit(p) + b)
+    }
+}
+
+// Probabilities are clipped so that their logit is finite.
+fn logit<F: Float + FromPrimitive>(p: F) -> F {
+    let eps = F::from_f64(1e-7).unwrap();
+    let p = p.max(eps).min(F::one() - eps);
+    (p / (F::one() - p)).ln()
+}
+
+fn sigmoid<F: Float>(z: F) -> F {
+    F::one() / (F::one() + (-z).exp())
+}
+
+impl<F, M, O> Classifier<F> for PlattCalibrator<F, M, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+    O: Optimizer<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let label = binary_label(y.clone(), "PlattCalibrator");
+        if let Some(p) = positive_proba(&self.classifier.predict_proba(x)) {
+            let z = logit(p);
+            let target = if label { F::one() } else { F::zero() };
+            // Derivative of the log loss with respect to a * z + b
+            let gradient = self.calibrate(p) - target;
+            self.optimizer.step(
+                &mut self.weights,
+                &[(SLOPE, gradient * z), (INTERCEPT, gradient)],
+            );
+        }
+        self.classifier.learn_one(x, y);
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        match positive_proba(&self.classifier.predict_proba(x)) {
+            Some(p) => binary_proba(self.calibrate(p)),
+            None => ClassifierTargetProbabilities::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::optimizers::SGD;
+    use rand::prelude::*;
+
+    // Gives x³ as the probability of the positive class, whose true probability is x
+    struct Skewed;
+
+    impl Classifier<f64> for Skewed {
+        fn learn_one(&mut self, _x: &Observation<f64>, _y: ClassifierTarget) {}
+        fn predict_proba(&self, x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            binary_proba(x.get_numeric("x").unwrap().powi(3))
+        }
+    }
+
+    #[test]
+    fn test_log_loss_improves() {
+        let mut calibrated = PlattCalibrator::new(Skewed, SGD::new(0.05));
+        let mut rng = StdRng::seed_from_u64(42);
+        let (mut raw_loss, mut calibrated_loss) = (0.0, 0.0);
+        for i in 0..20000 {
+            let x: f64 = rng.gen_range(0.05..0.95);
+            let y = rng.gen::<f64>() < x;
+            let observation = Observation::from([("x".to_string(), x)]);
+            if i >= 10000 {
+                let loss = |p: f64| -(if y { p } else { 1.0 - p }).ln();
+                raw_loss += loss(x.powi(3));
+                calibrated_loss +=
+                    loss(calibrated.predict_proba(&observation)[&ClassifierTarget::Bool(true)]);
+            }
+            calibrated.learn_one(&observation, ClassifierTarget::Bool(y));
+        }
+        assert!(
+            calibrated_loss < 0.9 * raw_loss,
+            "{} {}",
+            calibrated_loss,
+            raw_loss
+        );
+        // The probabilities are pushed up
+        assert!(calibrated.calibrate(0.125) > 0.3);
+    }
+}
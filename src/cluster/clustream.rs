@@ -0,0 +1,512 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Clusterer;
+use num::{Float, FromPrimitive};
+
+// Squared Euclidean distance between two sparse points, missing coordinates being zero.
+fn squared_distance<F: Float>(a: &HashMap<String, F>, b: &HashMap<String, F>) -> F {
+    let mut sum = F::zero();
+    for (name, u) in a.iter() {
+        let v = b.get(name).copied().unwrap_or(F::zero());
+        sum = sum + (*u - v) * (*u - v);
+    }
+    for (name, v) in b.iter() {
+        if !a.contains_key(name) {
+            sum = sum + *v * *v;
+        }
+    }
+    sum
+}
+
+// Cluster feature vector: the number of points, and the sums and squared sums of their
+// coordinates and of their arrival times, which are all additive.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct MicroCluster<F> {
+    // Identifiers of the micro-clusters which were merged into this one
+    ids: Vec<usize>,
+    n: F,
+    linear_sum: HashMap<String, F>,
+    squared_sum: HashMap<String, F>,
+    time_sum: F,
+    time_squared_sum: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MicroCluster<F> {
+    fn new(id: usize, x: &Observation<F>, t: usize) -> Self {
+        let mut cluster = Self {
+            ids: vec![id],
+            n: F::zero(),
+            linear_sum: HashMap::new(),
+            squared_sum: HashMap::new(),
+            time_sum: F::zero(),
+            time_squared_sum: F::zero(),
+        };
+        cluster.insert(x, t);
+        cluster
+    }
+    fn insert(&mut self, x: &Observation<F>, t: usize) {
+        let t = F::from_usize(t).unwrap();
+        self.n += F::one();
+        self.time_sum += t;
+        self.time_squared_sum += t * t;
+        for (name, value) in x.numeric() {
+            *self.linear_sum.entry(name.clone()).or_insert(F::zero()) += value;
+            *self.squared_sum.entry(name.clone()).or_insert(F::zero()) += value * value;
+        }
+    }
+    // Add (sign = 1) or subtract (sign = -1) the statistics of another micro-cluster.
+    fn add(&mut self, other: &MicroCluster<F>, sign: F) {
+        self.n += sign * other.n;
+        self.time_sum += sign * other.time_sum;
+        self.time_squared_sum += sign * other.time_squared_sum;
+        for (name, value) in other.linear_sum.iter() {
+            *self.linear_sum.entry(name.clone()).or_insert(F::zero()) += sign * *value;
+        }
+        for (name, value) in other.squared_sum.iter() {
+            *self.squared_sum.entry(name.clone()).or_insert(F::zero()) += sign * *value;
+        }
+    }
+    fn merge(&mut self, other: MicroCluster<F>) {
+        self.add(&other, F::one());
+        self.ids.extend(other.ids);
+    }
+    fn center(&self) -> HashMap<String, F> {
+        self.linear_sum
+            .iter()
+            .map(|(name, value)| (name.clone(), *value / self.n))
+            .collect()
+    }
+    // Root mean squared deviation of the points from the center.
+    fn radius(&self) -> F {
+        let variance = self
+            .linear_sum
+            .iter()
+            .fold(F::zero(), |sum, (name, value)| {
+                let mean = *value / self.n;
+                sum + self.squared_sum[name] / self.n - mean * mean
+            });
+        variance.max(F::zero()).sqrt()
+    }
+    // Approximate arrival time of the most recent points, assuming that the arrival times are
+    // normally distributed.
+    fn relevance_stamp(&self) -> F {
+        let mean = self.time_sum / self.n;
+        let variance = self.time_squared_sum / self.n - mean * mean;
+        mean + variance.max(F::zero()).sqrt()
+    }
+}
+
+// Micro-clusters at a given time.
+type Snapshot<F> = (usize, Vec<MicroCluster<F>>);
+
+/// Options of [`CluStream`].
+///
+/// - `n_micro_clusters`: The maximal number of micro-clusters, 100 by default.
+/// - `n_macro_clusters`: The number of clusters of the macro-clustering, 5 by default.
+/// - `max_radius_factor`: A point is absorbed by its closest micro-cluster if it lies within
+///   this factor of the radius of the micro-cluster, 2 by default.
+/// - `time_window`: The age beyond which a micro-cluster which has received no point may be
+///   deleted to make room for a new one, 1000 samples by default.
+/// - `time_gap`: The number of samples between two snapshots of the micro-clusters, 100 by
+///   default. The macro-clustering of the whole stream is refreshed at the same time.
+/// - `alpha`: The base of the pyramidal time frame of the snapshots, 2 by default.
+/// - `l`: Each order of the pyramidal time frame holds `alpha ^ l + 1` snapshots, 2 by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CluStreamOptions {
+    pub n_micro_clusters: usize,
+    pub n_macro_clusters: usize,
+    pub max_radius_factor: usize,
+    pub time_window: usize,
+    pub time_gap: usize,
+    pub alpha: usize,
+    pub l: u32,
+}
+
+impl Default for CluStreamOptions {
+    fn default() -> Self {
+        Self {
+            n_micro_clusters: 100,
+            n_macro_clusters: 5,
+            max_radius_factor: 2,
+            time_window: 1000,
+            time_gap: 100,
+            alpha: 2,
+            l: 2,
+        }
+    }
+}
+
+/// CluStream, a two-phase stream clustering algorithm.
+///
+/// The online phase summarizes the stream with micro-clusters, which are additive statistics of
+/// the points they absorb. Each of the first `n_micro_clusters` points starts its own
+/// micro-cluster. Afterwards, a point which is far from every micro-cluster starts a new one, which
+/// takes the place of a micro-cluster that has become irrelevant, or of the two closest
+/// micro-clusters once they are merged. Snapshots of the micro-clusters are stored in a pyramidal
+/// time frame: the older the snapshots, the sparser they are, so that their number grows
+/// logarithmically with the length of the stream.
+///
+/// The offline phase clusters the micro-clusters with a weighted k-means. Since the statistics
+/// are additive, subtracting a snapshot from the current micro-clusters gives the micro-clusters
+/// of the points which arrived since, so the macro-clustering can be computed over any time
+/// horizon with [`macro_clusters`](Self::macro_clusters).
+///
+/// # Parameters
+///
+/// - `options`: See [`CluStreamOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::cluster::clustream::{CluStream, CluStreamOptions};
+/// use light_river::common::Observation;
+/// use light_river::learner::Clusterer;
+///
+/// let options = CluStreamOptions {
+///     n_macro_clusters: 2,
+///     time_gap: 10,
+///     ..Default::default()
+/// };
+/// let mut model: CluStream<f64> = CluStream::new(options);
+/// for i in 0..100 {
+///     let offset = if i % 2 == 0 { 0.0 } else { 10.0 };
+///     let x = offset + (i % 7) as f64 / 7.0;
+///     model.learn_one(&Observation::from([("x".to_string(), x)]));
+/// }
+///
+/// let a = Observation::from([("x".to_string(), 0.5)]);
+/// let b = Observation::from([("x".to_string(), 9.5)]);
+/// assert_ne!(model.predict_one(&a), model.predict_one(&b));
+/// ```
+///
+/// # References
+///
+/// [^1]: C. C. Aggarwal, J. Han, J. Wang and P. S. Yu (2003). "A framework for clustering
+/// evolving data streams". Proceedings of the 29th international conference on very large data
+/// bases, 81-92.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CluStream<F> {
+    options: CluStreamOptions,
+    micro_clusters: Vec<MicroCluster<F>>,
+    // Snapshots indexed by their order in the pyramidal time frame, oldest first, along with the
+    // time at which they were taken
+    snapshots: BTreeMap<u32, VecDeque<Snapshot<F>>>,
+    macro_centers: Vec<HashMap<String, F>>,
+    next_id: usize,
+    t: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CluStream<F> {
+    pub fn new(options: CluStreamOptions) -> Self {
+        assert!(
+            options.n_micro_clusters >= 2,
+            "n_micro_clusters must be at least 2"
+        );
+        assert!(
+            options.n_macro_clusters > 0,
+            "n_macro_clusters must be strictly positive"
+        );
+        assert!(options.time_gap > 0, "time_gap must be strictly positive");
+        assert!(options.alpha >= 2, "alpha must be at least 2");
+        Self {
+            options,
+            micro_clusters: Vec::new(),
+            snapshots: BTreeMap::new(),
+            macro_centers: Vec::new(),
+            next_id: 0,
+            t: 0,
+        }
+    }
+    pub fn options(&self) -> &CluStreamOptions {
+        &self.options
+    }
+    /// Number of samples seen so far.
+    pub fn n_samples(&self) -> usize {
+        self.t
+    }
+    /// Centers and weights of the micro-clusters.
+    pub fn micro_clusters(&self) -> Vec<(HashMap<String, F>, F)> {
+        self.micro_clusters
+            .iter()
+            .map(|cluster| (cluster.center(), cluster.n))
+            .collect()
+    }
+    /// Times at which the stored snapshots were taken, in increasing order.
+    pub fn snapshot_times(&self) -> Vec<usize> {
+        let mut times: Vec<usize> = self
+            .snapshots
+            .values()
+            .flat_map(|snapshots| snapshots.iter().map(|(t, _)| *t))
+            .collect();
+        times.sort_unstable();
+        times
+    }
+    /// Centers of the macro-clustering of the points of the last `horizon` samples, or of the
+    /// whole stream if `None`. The horizon is rounded to the closest snapshot which is at least
+    /// as old, so the points of a slightly longer horizon may be included.
+    pub fn macro_clusters(&self, horizon: Option<usize>) -> Vec<HashMap<String, F>> {
+        let mut micro_clusters = self.micro_clusters.clone();
+        let snapshot = horizon.and_then(|h| {
+            self.snapshots
+                .values()
+                .flat_map(|snapshots| snapshots.iter())
+                .filter(|(t, _)| *t + h <= self.t)
+                .max_by_key(|(t, _)| *t)
+        });
+        if let Some((_, snapshot)) = snapshot {
+            for cluster in micro_clusters.iter_mut() {
+                // A micro-cluster of the snapshot is part of the current one it was merged into
+                let ids = cluster.ids.clone();
+                for old in snapshot.iter().filter(|old| ids.contains(&old.ids[0])) {
+                    cluster.add(old, -F::one());
+                }
+            }
+            let epsilon = F::from_f64(1e-9).unwrap();
+            micro_clusters.retain(|cluster| cluster.n > epsilon);
+        }
+        let points: Vec<(HashMap<String, F>, F)> = micro_clusters
+            .iter()
+            .map(|cluster| (cluster.center(), cluster.n))
+            .collect();
+        weighted_kmeans(&points, self.options.n_macro_clusters)
+    }
+    fn take_snapshot(&mut self) {
+        let clock = self.t / self.options.time_gap;
+        // The order of a snapshot is the largest i such that alpha ^ i divides its clock
+        let mut order = 0;
+        let mut power = self.options.alpha;
+        while clock.is_multiple_of(power) {
+            order += 1;
+            power *= self.options.alpha;
+        }
+        let capacity = self.options.alpha.pow(self.options.l) + 1;
+        let snapshots = self.snapshots.entry(order).or_default();
+        snapshots.push_back((self.t, self.micro_clusters.clone()));
+        if snapshots.len() > capacity {
+            snapshots.pop_front();
+        }
+    }
+    fn absorb(&mut self, x: &Observation<F>) {
+        let point: HashMap<String, F> = x
+            .numeric()
+            .map(|(name, value)| (name.clone(), value))
+            .collect();
+        let distances: Vec<F> = self
+            .micro_clusters
+            .iter()
+            .map(|cluster| squared_distance(&point, &cluster.center()).sqrt())
+            .collect();
+        let closest =
+            (0..distances.len()).min_by(|a, b| distances[*a].partial_cmp(&distances[*b]).unwrap());
+        // Each of the first points starts its own micro-cluster
+        let initialized = self.t > self.options.n_micro_clusters;
+        if let Some(i) = closest.filter(|_| initialized) {
+            let cluster = &self.micro_clusters[i];
+            let boundary = if cluster.n > F::one() {
+                F::from_usize(self.options.max_radius_factor).unwrap() * cluster.radius()
+            } else {
+                // The boundary of a single point is the distance to the closest micro-cluster
+                let center = cluster.center();
+                self.micro_clusters
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, other)| squared_distance(&center, &other.center()).sqrt())
+                    .fold(F::infinity(), F::min)
+            };
+            if distances[i] <= boundary {
+                self.micro_clusters[i].insert(x, self.t);
+                return;
+            }
+        }
+
+        // The point starts a new micro-cluster, after making room for it if needed
+        if self.micro_clusters.len() == self.options.n_micro_clusters {
+            let threshold = F::from_usize(self.t.saturating_sub(self.options.time_window)).unwrap();
+            let oldest = (0..self.micro_clusters.len())
+                .map(|i| (self.micro_clusters[i].relevance_stamp(), i))
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+                .unwrap();
+            if oldest.0 < threshold {
+                self.micro_clusters.swap_remove(oldest.1);
+            } else {
+                self.merge_closest();
+            }
+        }
+        self.micro_clusters
+            .push(MicroCluster::new(self.next_id, x, self.t));
+        self.next_id += 1;
+    }
+    fn merge_closest(&mut self) {
+        let centers: Vec<HashMap<String, F>> = self
+            .micro_clusters
+            .iter()
+            .map(|cluster| cluster.center())
+            .collect();
+        let mut closest = (F::infinity(), 0, 1);
+        for i in 0..centers.len() {
+            for j in i + 1..centers.len() {
+                let distance = squared_distance(&centers[i], &centers[j]);
+                if distance < closest.0 {
+                    closest = (distance, i, j);
+                }
+            }
+        }
+        let removed = self.micro_clusters.swap_remove(closest.2);
+        self.micro_clusters[closest.1].merge(removed);
+    }
+}
+
+// Weighted Lloyd's k-means, initialized with the heaviest points.
+fn weighted_kmeans<F: Float + FromPrimitive + AddAssign + DivAssign>(
+    points: &[(HashMap<String, F>, F)],
+    k: usize,
+) -> Vec<HashMap<String, F>> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|a, b| points[*b].1.partial_cmp(&points[*a].1).unwrap());
+    let mut centers: Vec<HashMap<String, F>> =
+        order.iter().take(k).map(|i| points[*i].0.clone()).collect();
+    for _ in 0..20 {
+        let mut sums: Vec<HashMap<String, F>> = vec![HashMap::new(); centers.len()];
+        let mut weights = vec![F::zero(); centers.len()];
+        for (point, weight) in points {
+            let closest = (0..centers.len())
+                .min_by(|a, b| {
+                    let (da, db) = (
+                        squared_distance(point, &centers[*a]),
+                        squared_distance(point, &centers[*b]),
+                    );
+                    da.partial_cmp(&db).unwrap()
+                })
+                .unwrap();
+            weights[closest] += *weight;
+            for (name, value) in point {
+                *sums[closest].entry(name.clone()).or_insert(F::zero()) += *weight * *value;
+            }
+        }
+        for ((center, mut sum), weight) in centers.iter_mut().zip(sums).zip(weights) {
+            if weight > F::zero() {
+                sum.values_mut().for_each(|value| *value /= weight);
+                *center = sum;
+            }
+        }
+    }
+    centers
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Clusterer<F>
+    for CluStream<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.t += 1;
+        self.absorb(x);
+        if self.t.is_multiple_of(self.options.time_gap) {
+            self.take_snapshot();
+            self.macro_centers = self.macro_clusters(None);
+        }
+    }
+    /// Index of the closest center of the macro-clustering of the whole stream, as of the last
+    /// snapshot, or 0 before the first snapshot.
+    fn predict_one(&self, x: &Observation<F>) -> i32 {
+        let point: HashMap<String, F> = x
+            .numeric()
+            .map(|(name, value)| (name.clone(), value))
+            .collect();
+        self.macro_centers
+            .iter()
+            .map(|center| squared_distance(&point, center))
+            .enumerate()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map_or(0, |(i, _)| i as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn point(a: f64, b: f64) -> Observation<f64> {
+        Observation::from([("a".to_string(), a), ("b".to_string(), b)])
+    }
+
+    #[test]
+    fn test_micro_cluster_statistics() {
+        let mut cluster = MicroCluster::new(0, &point(1.0, 0.0), 1);
+        cluster.insert(&point(3.0, 0.0), 3);
+        assert_eq!(cluster.center()["a"], 2.0);
+        assert_eq!(cluster.radius(), 1.0);
+        assert_eq!(cluster.relevance_stamp(), 3.0);
+        let other = cluster.clone();
+        cluster.add(&other, -1.0);
+        assert_eq!(cluster.n, 0.0);
+    }
+
+    #[test]
+    fn test_pyramidal_time_frame() {
+        let options = CluStreamOptions {
+            n_micro_clusters: 10,
+            time_gap: 1,
+            ..Default::default()
+        };
+        let mut model = CluStream::new(options);
+        for i in 0..1000 {
+            model.learn_one(&point((i % 10) as f64, 0.0));
+            assert!(model.micro_clusters().len() <= 10);
+        }
+        let times = model.snapshot_times();
+        // At most alpha ^ l + 1 = 5 snapshots per order, of which there are 10
+        assert!(times.len() <= 50, "{}", times.len());
+        assert_eq!(*times.last().unwrap(), 1000);
+        // Recent snapshots are dense, old ones sparse
+        assert!(times.contains(&999) && times.contains(&998));
+        assert!(times.contains(&512));
+    }
+
+    #[test]
+    fn test_horizon() {
+        // Two blobs at first, replaced by two others far away after 1000 samples
+        let mut rng = StdRng::seed_from_u64(42);
+        let options = CluStreamOptions {
+            n_micro_clusters: 20,
+            n_macro_clusters: 2,
+            time_gap: 50,
+            ..Default::default()
+        };
+        let mut model = CluStream::new(options);
+        for i in 0..1500 {
+            let (a, b) = match (i % 2, i < 1000) {
+                (0, true) => (0.0, 0.0),
+                (_, true) => (5.0, 0.0),
+                (0, false) => (50.0, 50.0),
+                (_, false) => (50.0, 150.0),
+            };
+            model.learn_one(&point(
+                a + rng.gen_range(-0.5..0.5),
+                b + rng.gen_range(-0.5..0.5),
+            ));
+        }
+        let mut recent: Vec<f64> = model
+            .macro_clusters(Some(400))
+            .iter()
+            .map(|center| center["b"])
+            .collect();
+        recent.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((recent[0] - 50.0).abs() < 1.0, "{:?}", recent);
+        assert!((recent[1] - 150.0).abs() < 1.0, "{:?}", recent);
+        // Over the whole stream, the old blobs, which hold most of the points, pull a center
+        // towards them
+        let mut all: Vec<f64> = model
+            .macro_clusters(None)
+            .iter()
+            .map(|center| center["b"])
+            .collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(all[0] < 25.0, "{:?}", all);
+    }
+}
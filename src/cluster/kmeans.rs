@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Clusterer;
+use crate::utils::{rng, standard_normal};
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// How the centers of a [`KMeans`] are initialized.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Seeding<F> {
+    /// Each coordinate of each center is drawn from a normal distribution, the first time the
+    /// feature is seen.
+    Random { mu: F, sigma: F },
+    /// The first `n_clusters` samples are the initial centers. Until they have all been seen,
+    /// the samples are assigned to the centers which already exist.
+    FirstSamples,
+}
+
+impl<F: Float> Default for Seeding<F> {
+    fn default() -> Self {
+        Seeding::Random {
+            mu: F::zero(),
+            sigma: F::one(),
+        }
+    }
+}
+
+/// Options of [`KMeans`].
+///
+/// - `n_clusters`: The number of clusters, 5 by default.
+/// - `halflife`: How far a center moves towards each sample which is assigned to it, between 0
+///   and 1, so that the oldest samples are forgotten. If `None`, which is the default, each
+///   center is the running mean of its samples, as in MacQueen's sequential k-means.
+/// - `seeding`: How the centers are initialized, see [`Seeding`].
+/// - `p`: The power of the Minkowski distance, e.g. 1 for the Manhattan distance and 2 for the
+///   Euclidean one, which is the default.
+/// - `batch_size`: The number of samples which are assigned before the centers are moved, 1 by
+///   default. Larger values give the mini-batch k-means of Sculley, whose assignments are
+///   independent of the order of the samples within a batch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KMeansOptions<F> {
+    pub n_clusters: usize,
+    pub halflife: Option<F>,
+    pub seeding: Seeding<F>,
+    pub p: F,
+    pub batch_size: usize,
+}
+
+impl<F: Float + FromPrimitive> Default for KMeansOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_clusters: 5,
+            halflife: None,
+            seeding: Seeding::default(),
+            p: F::from_f64(2.0).unwrap(),
+            batch_size: 1,
+        }
+    }
+}
+
+/// Incremental k-means clustering.
+///
+/// Each sample is assigned to its closest center, which is then moved towards it. The numeric
+/// features of the samples don't need to be the same: a center whose coordinate is unknown for a
+/// feature is seeded with it.
+///
+/// # Parameters
+///
+/// - `options`: See [`KMeansOptions`].
+/// - `seed`: Random seed of the initialization of the centers.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::cluster::kmeans::{KMeans, KMeansOptions, Seeding};
+/// use light_river::common::Observation;
+/// use light_river::learner::Clusterer;
+///
+/// let options = KMeansOptions {
+///     n_clusters: 2,
+///     seeding: Seeding::FirstSamples,
+///     ..Default::default()
+/// };
+/// let mut model: KMeans<f64> = KMeans::new(options, Some(42));
+/// let samples = [[1.0, 2.0], [-4.0, 2.0], [1.0, 4.0], [1.0, 0.0], [-4.0, 4.0], [-4.0, 0.0]];
+/// for [a, b] in samples {
+///     model.learn_one(&Observation::from([("a".to_string(), a), ("b".to_string(), b)]));
+/// }
+///
+/// let x = Observation::from([("a".to_string(), 0.0), ("b".to_string(), 0.0)]);
+/// let y = Observation::from([("a".to_string(), -4.0), ("b".to_string(), 4.0)]);
+/// assert_eq!(model.predict_one(&x), 0);
+/// assert_eq!(model.predict_one(&y), 1);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. MacQueen (1967). "Some methods for classification and analysis of multivariate
+/// observations". Proceedings of the fifth Berkeley symposium on mathematical statistics and
+/// probability, 281-297.
+///
+/// [^2]: D. Sculley (2010). "Web-scale k-means clustering". Proceedings of the 19th international
+/// conference on World wide web, 1177-1178.
+#[derive(Clone, Debug)]
+pub struct KMeans<F> {
+    options: KMeansOptions<F>,
+    centers: Vec<HashMap<String, F>>,
+    counts: Vec<usize>,
+    batch: Vec<Observation<F>>,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> KMeans<F> {
+    pub fn new(options: KMeansOptions<F>, seed: Option<u64>) -> Self {
+        assert!(
+            options.n_clusters > 0,
+            "n_clusters must be strictly positive"
+        );
+        assert!(
+            options.batch_size > 0,
+            "batch_size must be strictly positive"
+        );
+        assert!(
+            options
+                .halflife
+                .is_none_or(|h| h > F::zero() && h <= F::one()),
+            "halflife must lie in (0, 1]"
+        );
+        let n_centers = match options.seeding {
+            Seeding::Random { .. } => options.n_clusters,
+            Seeding::FirstSamples => 0,
+        };
+        Self {
+            options,
+            centers: vec![HashMap::new(); n_centers],
+            counts: vec![0; n_centers],
+            batch: Vec::with_capacity(options.batch_size),
+            rng: rng(seed),
+        }
+    }
+    pub fn options(&self) -> &KMeansOptions<F> {
+        &self.options
+    }
+    /// Coordinates of the centers, indexed by the cluster indices.
+    pub fn centers(&self) -> &[HashMap<String, F>] {
+        &self.centers
+    }
+    /// Number of samples assigned to each cluster so far.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+    // Unknown coordinates of a center are the mean of the seeding distribution.
+    fn coordinate(&self, center: &HashMap<String, F>, name: &str) -> F {
+        match (center.get(name), self.options.seeding) {
+            (Some(value), _) => *value,
+            (None, Seeding::Random { mu, .. }) => mu,
+            (None, Seeding::FirstSamples) => F::zero(),
+        }
+    }
+    fn distance(&self, x: &Observation<F>, center: &HashMap<String, F>) -> F {
+        let p = self.options.p;
+        let mut sum = F::zero();
+        for (name, value) in x.numeric() {
+            sum += (value - self.coordinate(center, name)).abs().powf(p);
+        }
+        for (name, value) in center.iter() {
+            if x.get_numeric(name).is_none() {
+                sum += value.abs().powf(p);
+            }
+        }
+        sum.powf(p.recip())
+    }
+    fn closest(&self, x: &Observation<F>) -> Option<usize> {
+        self.centers
+            .iter()
+            .map(|center| self.distance(x, center))
+            .enumerate()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(i, _)| i)
+    }
+    fn update(&mut self) {
+        let batch = std::mem::take(&mut self.batch);
+        // The samples of a batch are all assigned before any center moves
+        let assignments: Vec<Option<usize>> = batch.iter().map(|x| self.closest(x)).collect();
+        for (x, closest) in batch.iter().zip(assignments) {
+            let Some(i) = closest else {
+                continue;
+            };
+            if let Seeding::Random { mu, sigma } = self.options.seeding {
+                for (name, _) in x.numeric() {
+                    if !self.centers[i].contains_key(name) {
+                        let value =
+                            mu + sigma * F::from_f64(standard_normal(&mut self.rng)).unwrap();
+                        self.centers[i].insert(name.clone(), value);
+                    }
+                }
+            }
+            self.counts[i] += 1;
+            let lr = self
+                .options
+                .halflife
+                .unwrap_or(F::from_usize(self.counts[i]).unwrap().recip());
+            for (name, value) in x.numeric() {
+                let coordinate = self.centers[i].entry(name.clone()).or_insert(F::zero());
+                *coordinate += lr * (value - *coordinate);
+            }
+        }
+        self.batch = batch;
+        self.batch.clear();
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Clusterer<F>
+    for KMeans<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if self.centers.len() < self.options.n_clusters {
+            // Seeding with the first samples
+            self.centers.push(
+                x.numeric()
+                    .map(|(name, value)| (name.clone(), value))
+                    .collect(),
+            );
+            self.counts.push(1);
+            return;
+        }
+        self.batch.push(x.clone());
+        if self.batch.len() == self.options.batch_size {
+            self.update();
+        }
+    }
+    /// Index of the closest center, or 0 if no center has been seeded yet.
+    fn predict_one(&self, x: &Observation<F>) -> i32 {
+        self.closest(x).unwrap_or(0) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Samples around (0, 0), (10, 0) and (0, 10), in turn
+    fn blobs(n: usize, rng: &mut StdRng) -> Vec<(Observation<f64>, usize)> {
+        let centers = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)];
+        (0..n)
+            .map(|i| {
+                let (a, b) = centers[i % 3];
+                let x = Observation::from([
+                    ("a".to_string(), a + rng.gen_range(-1.0..1.0)),
+                    ("b".to_string(), b + rng.gen_range(-1.0..1.0)),
+                ]);
+                (x, i % 3)
+            })
+            .collect()
+    }
+
+    // Whether the clustering and the true clusters agree, up to a permutation of the indices
+    fn assert_recovers(model: &KMeans<f64>, samples: &[(Observation<f64>, usize)]) {
+        let mut mapping = HashMap::new();
+        for (x, y) in samples {
+            let cluster = model.predict_one(x);
+            assert_eq!(*mapping.entry(*y).or_insert(cluster), cluster);
+        }
+        let mut clusters: Vec<i32> = mapping.into_values().collect();
+        clusters.sort();
+        clusters.dedup();
+        assert_eq!(clusters.len(), 3);
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let options = KMeansOptions {
+            n_clusters: 3,
+            seeding: Seeding::FirstSamples,
+            ..Default::default()
+        };
+        let mut model = KMeans::new(options, Some(42));
+        let samples = blobs(300, &mut rng);
+        for (x, _) in samples.iter() {
+            model.learn_one(x);
+        }
+        assert_recovers(&model, &samples);
+        assert_eq!(model.counts(), [100, 100, 100]);
+        // Each center is the mean of its samples
+        assert!((model.centers()[1]["a"] - 10.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_random_seeding_and_batches() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let options = KMeansOptions {
+            n_clusters: 3,
+            seeding: Seeding::Random {
+                mu: 5.0,
+                sigma: 3.0,
+            },
+            halflife: Some(0.1),
+            batch_size: 10,
+            ..Default::default()
+        };
+        let mut model = KMeans::new(options, Some(7));
+        let samples = blobs(600, &mut rng);
+        for (x, _) in samples.iter() {
+            model.learn_one(x);
+        }
+        assert_recovers(&model, &samples);
+        assert_eq!(model.counts().iter().sum::<usize>(), 600);
+    }
+
+    #[test]
+    fn test_halflife_forgets() {
+        let options = KMeansOptions {
+            n_clusters: 1,
+            seeding: Seeding::FirstSamples,
+            halflife: Some(0.5),
+            ..Default::default()
+        };
+        let mut model = KMeans::new(options, None);
+        for a in [0.0, 0.0, 0.0, 8.0] {
+            model.learn_one(&Observation::from([("a".to_string(), a)]));
+        }
+        assert_eq!(model.centers()[0]["a"], 4.0);
+    }
+
+    #[test]
+    fn test_manhattan() {
+        let options = KMeansOptions {
+            p: 1.0,
+            ..Default::default()
+        };
+        let model: KMeans<f64> = KMeans::new(options, Some(42));
+        let center = HashMap::from([("a".to_string(), 1.0), ("c".to_string(), -2.0)]);
+        let x = Observation::from([("a".to_string(), 4.0), ("b".to_string(), 1.0)]);
+        // The unknown coordinate b of the center is mu = 0
+        assert_eq!(model.distance(&x, &center), 6.0);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod clustream;
+pub mod kmeans;
@@ -1,25 +1,440 @@
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+    mem,
     ops::{AddAssign, DivAssign, MulAssign, SubAssign},
 };
 
+use crate::learner::Clusterer;
+// The traits used to live here, keep the old paths working
+pub use crate::learner::{AnomalyDetector, Classifier, Regressor};
 use num::{Float, FromPrimitive};
 
-/// Represents an observation, using a HashMap of String keys and Float values.
+/// Value of a single feature of an observation.
 ///
 /// # Example
 ///
 /// ```
+/// use light_river::common::FeatureValue;
+///
+/// let age = FeatureValue::Numeric(42.0f32);
+/// let city = FeatureValue::<f32>::Categorical("Paris".to_string());
+/// assert_eq!(age.as_numeric(), Some(42.0));
+/// assert_eq!(city.as_numeric(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeatureValue<F> {
+    Numeric(F),
+    Categorical(String),
+    Missing,
+}
+
+impl<F: Float> FeatureValue<F> {
+    pub fn as_numeric(&self) -> Option<F> {
+        match self {
+            FeatureValue::Numeric(x) => Some(*x),
+            _ => None,
+        }
+    }
+    pub fn as_categorical(&self) -> Option<&str> {
+        match self {
+            FeatureValue::Categorical(x) => Some(x),
+            _ => None,
+        }
+    }
+    pub fn is_missing(&self) -> bool {
+        matches!(self, FeatureValue::Missing)
+    }
+}
+
+impl<F: Float> Hash for FeatureValue<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            // Equal floats have the same decomposition, except for 0 and -0
+            FeatureValue::Numeric(x) if x.is_zero() => F::zero().integer_decode().hash(state),
+            FeatureValue::Numeric(x) => x.integer_decode().hash(state),
+            FeatureValue::Categorical(x) => x.hash(state),
+            FeatureValue::Missing => {}
+        }
+    }
+}
+
+/// Represents an observation, i.e. the features of a sample, indexed by name.
+///
+/// Features are either numeric or categorical, and may be explicitly missing. They are kept sorted
+/// by name, so that iterating over them is fast and always happens in the same order, whatever the
+/// order in which they were inserted. Looking a feature up is done with a binary search.
+///
+/// Observations can be built from a `HashMap` or an array of numeric features, and from the rows
+/// of a CSV file with `DataStream::get_observation`.
+///
+/// # Example
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
 /// use std::collections::HashMap;
-/// use num::Float;
 ///
-/// type Observation<F: Float> = HashMap<String, F>;
+/// let mut obs: Observation<f32> = Observation::from(HashMap::from([
+///     ("height".to_string(), 1.8),
+///     ("weight".to_string(), 80.0),
+/// ]));
+/// obs.insert("city", FeatureValue::Categorical("Paris".to_string()));
+/// obs.insert("age", FeatureValue::Missing);
+///
+/// assert_eq!(obs.get_numeric("height"), Some(1.8));
+/// assert_eq!(obs.get_numeric("city"), None);
+/// let names: Vec<&String> = obs.keys().collect();
+/// assert_eq!(names, ["age", "city", "height", "weight"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Observation<F> {
+    features: Vec<(String, FeatureValue<F>)>,
+}
+
+impl<F: Float> Observation<F> {
+    pub fn new() -> Self {
+        Self {
+            features: Vec::new(),
+        }
+    }
+    fn position(&self, name: &str) -> Result<usize, usize> {
+        self.features
+            .binary_search_by(|(feature, _)| feature.as_str().cmp(name))
+    }
+    /// Set the value of a feature, returning its previous value if any.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        value: FeatureValue<F>,
+    ) -> Option<FeatureValue<F>> {
+        let name = name.into();
+        match self.position(&name) {
+            Ok(i) => Some(mem::replace(&mut self.features[i].1, value)),
+            Err(i) => {
+                self.features.insert(i, (name, value));
+                None
+            }
+        }
+    }
+    pub fn remove(&mut self, name: &str) -> Option<FeatureValue<F>> {
+        self.position(name).ok().map(|i| self.features.remove(i).1)
+    }
+    pub fn get(&self, name: &str) -> Option<&FeatureValue<F>> {
+        self.position(name).ok().map(|i| &self.features[i].1)
+    }
+    /// Value of a numeric feature. Categorical and missing features are treated as absent.
+    pub fn get_numeric(&self, name: &str) -> Option<F> {
+        self.get(name).and_then(|value| value.as_numeric())
+    }
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.position(name).is_ok()
+    }
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+    /// Iterate over the features, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FeatureValue<F>)> {
+        self.features.iter().map(|(name, value)| (name, value))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.features.iter().map(|(name, _)| name)
+    }
+    /// Iterate over the numeric features, sorted by name.
+    pub fn numeric(&self) -> impl Iterator<Item = (&String, F)> {
+        self.features
+            .iter()
+            .filter_map(|(name, value)| value.as_numeric().map(|x| (name, x)))
+    }
+}
+
+impl<F: Float> Default for Observation<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Hash for Observation<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.features.hash(state);
+    }
+}
+
+impl<F: Float> FromIterator<(String, FeatureValue<F>)> for Observation<F> {
+    fn from_iter<I: IntoIterator<Item = (String, FeatureValue<F>)>>(iter: I) -> Self {
+        let mut features: Vec<(String, FeatureValue<F>)> = iter.into_iter().collect();
+        // Later values override earlier ones, as with repeated calls to `insert`
+        features.reverse();
+        features.sort_by(|a, b| a.0.cmp(&b.0));
+        features.dedup_by(|a, b| a.0 == b.0);
+        Self { features }
+    }
+}
+
+impl<F: Float> FromIterator<(String, F)> for Observation<F> {
+    fn from_iter<I: IntoIterator<Item = (String, F)>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|(name, x)| (name, FeatureValue::Numeric(x)))
+            .collect()
+    }
+}
+
+impl<F: Float> From<HashMap<String, F>> for Observation<F> {
+    fn from(features: HashMap<String, F>) -> Self {
+        features.into_iter().collect()
+    }
+}
+
+impl<F: Float, const N: usize> From<[(String, F); N]> for Observation<F> {
+    fn from(features: [(String, F); N]) -> Self {
+        features.into_iter().collect()
+    }
+}
+
+/// Sparse vector of numeric features, indexed by position, e.g. the output of feature hashing or
+/// of a bag of words.
+///
+/// Only the non-zero entries are stored, sorted by index, so that the products and sums of two
+/// sparse vectors are computed in a single pass over both of them.
+///
+/// # Example
+///
+/// ```
+/// use light_river::common::SparseVector;
+///
+/// let x: SparseVector<f64> = SparseVector::from([(3, 1.0), (10, -2.0)]);
+/// let mut y: SparseVector<f64> = SparseVector::from([(0, 4.0), (10, 1.0)]);
+/// assert_eq!(x.dot(&y), -2.0);
+/// assert_eq!(x.dot_dense(&[0.0, 0.0, 0.0, 5.0]), 5.0);
+///
+/// // y <- y + 2x
+/// y.axpy(2.0, &x);
+/// assert_eq!(y.get(3), 2.0);
+/// assert_eq!(y.get(10), -3.0);
+/// assert_eq!(y.norm_l1(), 9.0);
+/// assert!((y.norm_l2() - 29.0_f64.sqrt()).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SparseVector<F> {
+    entries: Vec<(usize, F)>,
+}
+
+impl<F: Float> SparseVector<F> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+    /// Set the value at `index`. Setting a value to zero removes the entry.
+    pub fn set(&mut self, index: usize, value: F) {
+        match self.entries.binary_search_by_key(&index, |(i, _)| *i) {
+            Ok(i) if value.is_zero() => {
+                self.entries.remove(i);
+            }
+            Ok(i) => self.entries[i].1 = value,
+            Err(_) if value.is_zero() => {}
+            Err(i) => self.entries.insert(i, (index, value)),
+        }
+    }
+    pub fn get(&self, index: usize) -> F {
+        match self.entries.binary_search_by_key(&index, |(i, _)| *i) {
+            Ok(i) => self.entries[i].1,
+            Err(_) => F::zero(),
+        }
+    }
+    /// Number of non-zero entries.
+    pub fn nnz(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Iterate over the non-zero entries, sorted by index.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, F)> + '_ {
+        self.entries.iter().cloned()
+    }
+    pub fn dot(&self, other: &SparseVector<F>) -> F {
+        let (mut i, mut j) = (0, 0);
+        let mut sum = F::zero();
+        while i < self.entries.len() && j < other.entries.len() {
+            let (a, b) = (self.entries[i], other.entries[j]);
+            match a.0.cmp(&b.0) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    sum = sum + a.1 * b.1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+    /// Dot product with a dense vector. Entries beyond the length of `dense` are ignored.
+    pub fn dot_dense(&self, dense: &[F]) -> F {
+        self.entries
+            .iter()
+            .filter(|(i, _)| *i < dense.len())
+            .fold(F::zero(), |sum, (i, x)| sum + *x * dense[*i])
+    }
+    /// Add `alpha * x` to this vector.
+    pub fn axpy(&mut self, alpha: F, x: &SparseVector<F>) {
+        let mut entries = Vec::with_capacity(self.entries.len() + x.entries.len());
+        let (mut i, mut j) = (0, 0);
+        while i < self.entries.len() || j < x.entries.len() {
+            let order = match (self.entries.get(i), x.entries.get(j)) {
+                (Some(a), Some(b)) => a.0.cmp(&b.0),
+                (Some(_), None) => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+            let entry = match order {
+                Ordering::Less => {
+                    i += 1;
+                    self.entries[i - 1]
+                }
+                Ordering::Greater => {
+                    j += 1;
+                    (x.entries[j - 1].0, alpha * x.entries[j - 1].1)
+                }
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                    let (index, value) = self.entries[i - 1];
+                    (index, value + alpha * x.entries[j - 1].1)
+                }
+            };
+            if !entry.1.is_zero() {
+                entries.push(entry);
+            }
+        }
+        self.entries = entries;
+    }
+    /// Add `alpha` times this vector to a dense vector, which is grown as needed.
+    pub fn axpy_dense(&self, alpha: F, dense: &mut Vec<F>) {
+        if let Some((last, _)) = self.entries.last() {
+            if dense.len() <= *last {
+                dense.resize(last + 1, F::zero());
+            }
+        }
+        for (i, x) in self.entries.iter() {
+            dense[*i] = dense[*i] + alpha * *x;
+        }
+    }
+    pub fn norm_l1(&self) -> F {
+        self.entries
+            .iter()
+            .fold(F::zero(), |sum, (_, x)| sum + x.abs())
+    }
+    pub fn norm_l2(&self) -> F {
+        self.entries
+            .iter()
+            .fold(F::zero(), |sum, (_, x)| sum + *x * *x)
+            .sqrt()
+    }
+}
+
+impl<F: Float> Default for SparseVector<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> FromIterator<(usize, F)> for SparseVector<F> {
+    /// Entries with the same index are summed, and zeros are dropped.
+    fn from_iter<I: IntoIterator<Item = (usize, F)>>(iter: I) -> Self {
+        let mut entries: Vec<(usize, F)> = iter.into_iter().collect();
+        entries.sort_by_key(|(i, _)| *i);
+        let mut summed: Vec<(usize, F)> = Vec::with_capacity(entries.len());
+        for (index, value) in entries {
+            match summed.last_mut() {
+                Some(last) if last.0 == index => last.1 = last.1 + value,
+                _ => summed.push((index, value)),
+            }
+        }
+        summed.retain(|(_, x)| !x.is_zero());
+        Self { entries: summed }
+    }
+}
+
+impl<F: Float, const N: usize> From<[(usize, F); N]> for SparseVector<F> {
+    fn from(entries: [(usize, F); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+/// Key of a numeric feature of [`Features`]: a name for dense observations, and an index for
+/// sparse vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureKey<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+/// Input of the models which handle both dense observations and sparse vectors, such as linear
+/// models.
+///
+/// # Example
+///
+/// ```
+/// use light_river::common::{FeatureKey, Features, Observation, SparseVector};
 ///
-/// let mut obs: Observation<f32> = HashMap::new();
-/// obs.insert("feature1".to_string(), 1.0);
-/// obs.insert("featur2".to_string(), 2.0);
+/// let dense: Features<f64> = Observation::from([("x".to_string(), 2.0)]).into();
+/// let sparse: Features<f64> = SparseVector::from([(7, 3.0)]).into();
+/// assert_eq!(dense.numeric().collect::<Vec<_>>(), [(FeatureKey::Name("x"), 2.0)]);
+/// assert_eq!(sparse.numeric().collect::<Vec<_>>(), [(FeatureKey::Index(7), 3.0)]);
 /// ```
-pub type Observation<F> = HashMap<String, F>;
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Features<F> {
+    Dense(Observation<F>),
+    Sparse(SparseVector<F>),
+}
+
+impl<F: Float> Features<F> {
+    /// Iterate over the numeric features. Categorical and missing features of dense observations
+    /// are skipped.
+    pub fn numeric(&self) -> Box<dyn Iterator<Item = (FeatureKey<'_>, F)> + '_> {
+        match self {
+            Features::Dense(x) => Box::new(
+                x.numeric()
+                    .map(|(name, value)| (FeatureKey::Name(name), value)),
+            ),
+            Features::Sparse(x) => Box::new(
+                x.iter()
+                    .map(|(index, value)| (FeatureKey::Index(index), value)),
+            ),
+        }
+    }
+    pub fn norm_l2(&self) -> F {
+        match self {
+            Features::Dense(x) => x
+                .numeric()
+                .fold(F::zero(), |sum, (_, value)| sum + value * value)
+                .sqrt(),
+            Features::Sparse(x) => x.norm_l2(),
+        }
+    }
+}
+
+impl<F> From<Observation<F>> for Features<F> {
+    fn from(x: Observation<F>) -> Self {
+        Features::Dense(x)
+    }
+}
+
+impl<F> From<SparseVector<F>> for Features<F> {
+    fn from(x: SparseVector<F>) -> Self {
+        Features::Sparse(x)
+    }
+}
 
 /// Enum for classification targets, supporting boolean, integer, and string labels.
 ///
@@ -33,20 +448,21 @@ pub type Observation<F> = HashMap<String, F>;
 /// let target_string = ClassifierTarget::String("class".to_string());
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClassifierTarget {
     Bool(bool),
     Int(i32),
     String(String),
 }
-// impl fmt::Display for ClassifierTarget {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         match self {
-//             ClassifierTarget::Bool(b) => write!(f, "{}", b),
-//             ClassifierTarget::Int(i) => write!(f, "{}", i),
-//             ClassifierTarget::String(s) => write!(f, "{}", s),
-//         }
-//     }
-// }
+impl fmt::Display for ClassifierTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClassifierTarget::Bool(b) => write!(f, "{}", b),
+            ClassifierTarget::Int(i) => write!(f, "{}", i),
+            ClassifierTarget::String(s) => write!(f, "{}", s),
+        }
+    }
+}
 impl ClassifierTarget {
     pub fn from<T: Into<ClassifierTarget>>(item: T) -> Self {
         item.into()
@@ -74,6 +490,7 @@ impl ClassifierTarget {
     ///     vec![ClassifierTarget::Bool(true), ClassifierTarget::Bool(false)]
     /// );
     /// ```
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter<I: IntoClassifierTargetIter>(iter: I) -> Box<dyn Iterator<Item = Self>> {
         iter.into_classifier_target_iter()
     }
@@ -184,6 +601,65 @@ where
 /// ```
 pub type ClassifierTargetProbabilities<F> = HashMap<ClassifierTarget, F>;
 
+/// Represents the output of a multi-label classifier, i.e. the set of labels that are predicted
+/// for an instance. The same type is used for the set of true labels.
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, MultiLabelOutput};
+///
+/// let labels: MultiLabelOutput = [ClassifierTarget::from("sports"), ClassifierTarget::from("politics")]
+///     .into_iter()
+///     .collect();
+/// assert!(labels.contains(&ClassifierTarget::from("sports")));
+/// ```
+pub type MultiLabelOutput = HashSet<ClassifierTarget>;
+
+/// Represents the targets of a multi-output classification instance, i.e. a class for each of its
+/// outputs, indexed by the name of the output. The same type is used for the predictions.
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, MultiClassifierTarget};
+///
+/// let y = MultiClassifierTarget::from([
+///     ("genre".to_string(), ClassifierTarget::from("jazz")),
+///     ("explicit".to_string(), ClassifierTarget::Bool(false)),
+/// ]);
+/// assert_eq!(y["explicit"], ClassifierTarget::Bool(false));
+/// ```
+pub type MultiClassifierTarget = HashMap<String, ClassifierTarget>;
+
+/// Represents the class probabilities of each output of a multi-output classifier.
+pub type MultiClassifierTargetProbabilities<F> = HashMap<String, ClassifierTargetProbabilities<F>>;
+
+// (De)serialize a map as a sequence of key-value pairs, because formats such as JSON only allow
+// string keys whereas we use `ClassifierTarget` keys.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
 /// Represents the output of a classifier model, which can be either a prediction or a probability distribution.
 /// The probability distribution is represented by a HashMap of ClassifierTarget and Float values.
 /// The prediction is represented by a ClassifierTarget.
@@ -202,10 +678,21 @@ pub type ClassifierTargetProbabilities<F> = HashMap<ClassifierTarget, F>;
 /// });
 /// let mut prediction = probs.get_predicition();
 /// assert_eq!(prediction, ClassifierTarget::String("Cat".to_string()));
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
 pub enum ClassifierOutput<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
 {
-    Probabilities(ClassifierTargetProbabilities<F>),
+    Probabilities(
+        #[cfg_attr(feature = "serde", serde(with = "serde_pairs"))]
+        ClassifierTargetProbabilities<F>,
+    ),
     Prediction(ClassifierTarget),
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ClassifierOutput<F> {
@@ -215,7 +702,7 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> C
             ClassifierOutput::Probabilities(y) => {
                 // Find the key with the highest probabilities
                 y.iter()
-                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
                     .unwrap()
                     .0
                     .clone()
@@ -246,6 +733,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> C
 /// ```
 pub type RegressionTarget<F> = F;
 
+/// Represents the targets of a multi-target regression instance, indexed by the name of the
+/// target. The same type is used for the predictions.
+///
+/// ```
+/// use light_river::common::MultiRegressionTarget;
+///
+/// let y: MultiRegressionTarget<f32> =
+///     MultiRegressionTarget::from([("price".to_string(), 9.5), ("volume".to_string(), 120.0)]);
+/// assert_eq!(y["price"], 9.5);
+/// ```
+pub type MultiRegressionTarget<F> = HashMap<String, RegressionTarget<F>>;
+
 /// Enum for all possible model targets (classification, regression, clustering, anomaly).
 ///
 /// # Example
@@ -259,6 +758,7 @@ pub type RegressionTarget<F> = F;
 /// let target_anomaly = ModelTarget::Anomaly(0.8f32);
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ModelTarget<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
     Classification(ClassifierTarget),
     Regression(RegressionTarget<F>),
@@ -266,41 +766,6 @@ pub enum ModelTarget<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssig
     Anomaly(F),
 }
 
-/// Trait for implementing a classifier model.
-///
-/// Implement this trait for your classifier to use the `learn_one`, `predict_proba`, and
-/// `predict_one` methods.
-pub trait Classifier<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
-    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget);
-    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F>;
-    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget;
-}
-
-/// Trait for implementing a regression model.
-///
-/// Implement this trait for your regressor to use the `learn_one` and `predict_one` methods.
-pub trait Regressor<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
-    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>);
-    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F>;
-}
-
-/// Trait for implementing an anomaly detector model.
-///
-/// Implement this trait for your anomaly detector to use the `learn_one` and `score_one` methods.
-pub trait AnomalyDetector<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
-{
-    fn learn_one(&mut self, x: &Observation<F>);
-    fn score_one(&self, x: &Observation<F>) -> F;
-}
-
-/// Trait for implementing a clustering model.
-///
-/// Implement this trait for your clustering model to use the `learn_one` and `predict_one` methods.
-pub trait Clusterer<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
-    fn learn_one(&mut self, x: &Observation<F>);
-    fn predict_one(&self, x: &Observation<F>) -> i32;
-}
-
 /// Represents a generic model which can be one of several types (classifier, regressor, anomaly detector, or clusterer).
 pub enum ModelType<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
     Classifier(Box<dyn Classifier<F>>),
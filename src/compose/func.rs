@@ -0,0 +1,79 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// A stateless transformer which applies a function to the observations.
+///
+/// It is the simplest way to engineer features within a pipeline, as the function is called
+/// on each observation to transform, and there is nothing to learn.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::compose::func::FuncTransformer;
+/// use light_river::learner::Transformer;
+///
+/// let bmi = FuncTransformer::new(|x: &Observation<f64>| {
+///     let mut x = x.clone();
+///     if let (Some(weight), Some(height)) = (x.get_numeric("weight"), x.get_numeric("height")) {
+///         x.insert("bmi", FeatureValue::Numeric(weight / (height * height)));
+///     }
+///     x
+/// });
+/// let x = Observation::from([("weight".to_string(), 81.0), ("height".to_string(), 1.8)]);
+/// assert!((bmi.transform_one(&x).get_numeric("bmi").unwrap() - 25.0).abs() < 1e-9);
+/// ```
+#[derive(Clone)]
+pub struct FuncTransformer<G> {
+    func: G,
+}
+
+impl<G> FuncTransformer<G> {
+    pub fn new(func: G) -> Self {
+        Self { func }
+    }
+}
+
+impl<F, G> Transformer<F> for FuncTransformer<G>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    G: Fn(&Observation<F>) -> Observation<F>,
+{
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        (self.func)(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use crate::compose::pipeline::Chain;
+    use crate::preprocessing::one_hot::OneHotEncoder;
+
+    #[test]
+    fn test_in_chain() {
+        // The encoder learns the categories output by the function
+        let bucket = |x: &Observation<f64>| {
+            x.numeric()
+                .map(|(name, value)| {
+                    let size = if value < 10.0 { "small" } else { "large" };
+                    (name.clone(), FeatureValue::Categorical(size.to_string()))
+                })
+                .collect()
+        };
+        let mut chain = Chain::new(
+            FuncTransformer::new(bucket),
+            OneHotEncoder::new(Default::default()),
+        );
+        for amount in [3.0, 30.0] {
+            chain.learn_one(&Observation::from([("amount".to_string(), amount)]));
+        }
+        let z = chain.transform_one(&Observation::from([("amount".to_string(), 50.0)]));
+        assert_eq!(z.get_numeric("amount_large"), Some(1.0));
+        assert_eq!(z.get_numeric("amount_small"), Some(0.0));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod func;
+pub mod pipeline;
+pub mod select;
+pub mod union;
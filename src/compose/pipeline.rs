@@ -0,0 +1,268 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, Observation, RegressionTarget,
+};
+use crate::learner::{AnomalyDetector, Classifier, Clusterer, Regressor, Transformer};
+use num::{Float, FromPrimitive};
+
+/// Two transformers applied one after the other.
+///
+/// Each transformer learns from the observations as they are output by the previous one. Longer
+/// chains are built with [`Chain::then`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::compose::pipeline::Chain;
+/// use light_river::learner::Transformer;
+/// use light_river::preprocessing::imputer::{Imputation, StatImputer};
+/// use light_river::preprocessing::one_hot::OneHotEncoder;
+///
+/// let fill = Imputation::Constant(FeatureValue::Categorical("unknown".to_string()));
+/// let mut chain = Chain::new(
+///     StatImputer::new(vec![], Some(fill)),
+///     OneHotEncoder::new(Default::default()),
+/// );
+/// let mut x: Observation<f64> = Observation::new();
+/// x.insert("city", FeatureValue::Missing);
+/// chain.learn_one(&x);
+/// assert_eq!(chain.transform_one(&x).get_numeric("city_unknown"), Some(1.0));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+    /// Append a transformer to the chain.
+    pub fn then<C>(self, next: C) -> Chain<Chain<A, B>, C> {
+        Chain::new(self, next)
+    }
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+}
+
+impl<F, A, B> Transformer<F> for Chain<A, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    A: Transformer<F>,
+    B: Transformer<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.first.learn_one(x);
+        self.second.learn_one(&self.first.transform_one(x));
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        self.second.transform_one(&self.first.transform_one(x))
+    }
+}
+
+/// A transformer followed by a model, which behaves as the model.
+///
+/// The observations are transformed before they are passed to the model, when learning as well
+/// as when predicting. When learning, the transformer learns from an observation before it
+/// transforms it, so that e.g. a new category is already encoded. Several transformers are
+/// combined into one with [`Chain`].
+///
+/// The pipeline is a [`Classifier`], a [`Regressor`], an [`AnomalyDetector`] or a [`Clusterer`]
+/// when the model is one.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, FeatureValue, Observation};
+/// use light_river::compose::pipeline::Pipeline;
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use light_river::preprocessing::one_hot::OneHotEncoder;
+///
+/// // Linear models ignore the categorical features, which are thus one-hot encoded
+/// let mut model = Pipeline::new(
+///     OneHotEncoder::new(Default::default()),
+///     LogisticRegression::new(SGD::new(0.1), Log, Default::default()),
+/// );
+/// let fruit = |name: &str| {
+///     let mut x: Observation<f64> = Observation::new();
+///     x.insert("fruit", FeatureValue::Categorical(name.to_string()));
+///     x
+/// };
+/// for i in 0..300 {
+///     let (name, sweet) = [("apple", true), ("lemon", false), ("grape", true)][i % 3];
+///     model.learn_one(&fruit(name), ClassifierTarget::from(sweet));
+/// }
+/// assert_eq!(model.predict_one(&fruit("lemon")), ClassifierTarget::from(false));
+/// assert_eq!(model.predict_one(&fruit("grape")), ClassifierTarget::from(true));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pipeline<T, M> {
+    transformer: T,
+    model: M,
+}
+
+impl<T, M> Pipeline<T, M> {
+    pub fn new(transformer: T, model: M) -> Self {
+        Self { transformer, model }
+    }
+    pub fn transformer(&self) -> &T {
+        &self.transformer
+    }
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<F, T, M> Classifier<F> for Pipeline<T, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    T: Transformer<F>,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.transformer.learn_one(x);
+        let x = self.transformer.transform_one(x);
+        self.model.learn_one(&x, y);
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.model.predict_proba(&self.transformer.transform_one(x))
+    }
+    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget {
+        self.model.predict_one(&self.transformer.transform_one(x))
+    }
+}
+
+impl<F, T, M> Regressor<F> for Pipeline<T, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    T: Transformer<F>,
+    M: Regressor<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.transformer.learn_one(x);
+        let x = self.transformer.transform_one(x);
+        self.model.learn_one(&x, y);
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        self.model.predict_one(&self.transformer.transform_one(x))
+    }
+}
+
+impl<F, T, M> AnomalyDetector<F> for Pipeline<T, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    T: Transformer<F>,
+    M: AnomalyDetector<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.transformer.learn_one(x);
+        let x = self.transformer.transform_one(x);
+        self.model.learn_one(&x);
+    }
+    fn score_one(&self, x: &Observation<F>) -> F {
+        self.model.score_one(&self.transformer.transform_one(x))
+    }
+}
+
+impl<F, T, M> Clusterer<F> for Pipeline<T, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    T: Transformer<F>,
+    M: Clusterer<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.transformer.learn_one(x);
+        let x = self.transformer.transform_one(x);
+        self.model.learn_one(&x);
+    }
+    fn predict_one(&self, x: &Observation<F>) -> i32 {
+        self.model.predict_one(&self.transformer.transform_one(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use crate::linear_model::linear_regression::LinearRegression;
+    use crate::optim::losses::Squared;
+    use crate::optim::optimizers::SGD;
+    use crate::preprocessing::imputer::{Imputation, StatImputer};
+    use crate::preprocessing::one_hot::OneHotEncoder;
+
+    // Counts the observations it learns from, and outputs them with an extra feature
+    #[derive(Clone, Debug, Default)]
+    struct Tag {
+        name: &'static str,
+        n: usize,
+    }
+
+    impl Transformer<f64> for Tag {
+        fn learn_one(&mut self, _x: &Observation<f64>) {
+            self.n += 1;
+        }
+        fn transform_one(&self, x: &Observation<f64>) -> Observation<f64> {
+            let mut x = x.clone();
+            x.insert(self.name, FeatureValue::Numeric(x.len() as f64));
+            x
+        }
+    }
+
+    #[test]
+    fn test_chain_order() {
+        let mut chain = Chain::new(Tag { name: "a", n: 0 }, Tag { name: "b", n: 0 })
+            .then(Tag { name: "c", n: 0 });
+        chain.learn_one(&Observation::new());
+        assert_eq!(chain.first().first().n, 1);
+        assert_eq!(chain.second().n, 1);
+        let x = chain.transform_one(&Observation::new());
+        // Each transformer sees the features added by the previous ones
+        assert_eq!(
+            (x.get_numeric("a"), x.get_numeric("b"), x.get_numeric("c")),
+            (Some(0.0), Some(1.0), Some(2.0))
+        );
+    }
+
+    #[test]
+    fn test_regression_pipeline() {
+        // The target only depends on a categorical feature, which is sometimes missing
+        let mut model = Pipeline::new(
+            Chain::new(
+                StatImputer::new(vec![], Some(Imputation::Mode)),
+                OneHotEncoder::new(Default::default()),
+            ),
+            LinearRegression::new(SGD::new(0.05), Squared, Default::default()),
+        );
+        let observation = |size: Option<&str>| {
+            let mut x: Observation<f64> = Observation::new();
+            let value = size.map_or(FeatureValue::Missing, |s| {
+                FeatureValue::Categorical(s.to_string())
+            });
+            x.insert("size", value);
+            x
+        };
+        for i in 0..3000 {
+            let (size, y) = [
+                (Some("small"), 1.0),
+                (Some("large"), 5.0),
+                (Some("large"), 5.0),
+            ][i % 3];
+            model.learn_one(&observation(size), y);
+        }
+        assert!((model.predict_one(&observation(Some("small"))) - 1.0).abs() < 0.1);
+        // Missing sizes are imputed with the most frequent one
+        assert!((model.predict_one(&observation(None)) - 5.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// Keeps the given features of the observations, and drops the others.
+///
+/// Chained before a transformer, it restricts the transformer to a subset of the features, e.g.
+/// within a [`TransformerUnion`](crate::compose::union::TransformerUnion).
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::compose::select::Select;
+/// use light_river::learner::Transformer;
+///
+/// let select = Select::new(["a", "c"]);
+/// let x: Observation<f64> = Observation::from([
+///     ("a".to_string(), 1.0),
+///     ("b".to_string(), 2.0),
+///     ("c".to_string(), 3.0),
+/// ]);
+/// let y = select.transform_one(&x);
+/// let names: Vec<&String> = y.keys().collect();
+/// assert_eq!(names, ["a", "c"]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Select {
+    features: HashSet<String>,
+}
+
+impl Select {
+    pub fn new<S: Into<String>>(features: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            features: features.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for Select
+{
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .filter(|(name, _)| self.features.contains(*name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+/// Drops the given features of the observations, and keeps the others.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::compose::select::Discard;
+/// use light_river::learner::Transformer;
+///
+/// let discard = Discard::new(["id"]);
+/// let x: Observation<f64> = Observation::from([("id".to_string(), 7.0), ("a".to_string(), 1.0)]);
+/// let y = discard.transform_one(&x);
+/// let names: Vec<&String> = y.keys().collect();
+/// assert_eq!(names, ["a"]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Discard {
+    features: HashSet<String>,
+}
+
+impl Discard {
+    pub fn new<S: Into<String>>(features: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            features: features.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for Discard
+{
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .filter(|(name, _)| !self.features.contains(*name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+/// Renames features of the observations, from their old name to their new one.
+///
+/// The features which aren't renamed are left as they are. A renamed feature replaces any feature
+/// which already has its new name.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::compose::select::Renamer;
+/// use light_river::learner::Transformer;
+///
+/// let renamer = Renamer::new([("temp", "temperature")]);
+/// let x: Observation<f64> = Observation::from([("temp".to_string(), 21.5)]);
+/// assert_eq!(renamer.transform_one(&x).get_numeric("temperature"), Some(21.5));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Renamer {
+    mapping: HashMap<String, String>,
+}
+
+impl Renamer {
+    pub fn new<S: Into<String>>(mapping: impl IntoIterator<Item = (S, S)>) -> Self {
+        Self {
+            mapping: mapping
+                .into_iter()
+                .map(|(old, new)| (old.into(), new.into()))
+                .collect(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for Renamer
+{
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let (renamed, kept): (Vec<_>, Vec<_>) = x
+            .iter()
+            .partition(|(name, _)| self.mapping.contains_key(*name));
+        // Later features override earlier ones
+        kept.into_iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .chain(
+                renamed
+                    .into_iter()
+                    .map(|(name, value)| (self.mapping[name].clone(), value.clone())),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+
+    #[test]
+    fn test_renamer() {
+        // Features can be swapped, and a renamed feature overrides an existing one
+        let renamer = Renamer::new([("a", "b"), ("b", "a"), ("c", "d")]);
+        let x: Observation<f64> = Observation::from([
+            ("a".to_string(), 1.0),
+            ("b".to_string(), 2.0),
+            ("c".to_string(), 3.0),
+            ("d".to_string(), 4.0),
+        ]);
+        let y = renamer.transform_one(&x);
+        assert_eq!(
+            y,
+            Observation::from([
+                ("a".to_string(), 2.0),
+                ("b".to_string(), 1.0),
+                ("d".to_string(), 3.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_select_and_discard_complement() {
+        let mut x: Observation<f64> = Observation::new();
+        x.insert("a", FeatureValue::Numeric(1.0));
+        x.insert("b", FeatureValue::Categorical("u".to_string()));
+        x.insert("c", FeatureValue::Missing);
+        let selected = Select::new(["b", "z"]).transform_one(&x);
+        let discarded = Discard::new(["b", "z"]).transform_one(&x);
+        assert_eq!(selected.len() + discarded.len(), x.len());
+        assert!(selected.contains_key("b") && !discarded.contains_key("b"));
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// Two transformers applied side by side, whose outputs are merged.
+///
+/// Both transformers learn from, and transform, the same observations. Together with
+/// [`Select`](crate::compose::select::Select), it applies different transformers to different
+/// subsets of the features. When both output a feature with the same name, the second one wins.
+/// More transformers are added with [`TransformerUnion::with`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::compose::pipeline::Chain;
+/// use light_river::compose::select::Select;
+/// use light_river::compose::union::TransformerUnion;
+/// use light_river::learner::Transformer;
+/// use light_river::preprocessing::one_hot::OneHotEncoder;
+///
+/// // The city is one-hot encoded, the age is kept, and the other features are dropped
+/// let mut union = TransformerUnion::new(
+///     Chain::new(Select::new(["city"]), OneHotEncoder::new(Default::default())),
+///     Select::new(["age"]),
+/// );
+/// let mut x: Observation<f64> = Observation::new();
+/// x.insert("city", FeatureValue::Categorical("Oslo".to_string()));
+/// x.insert("age", FeatureValue::Numeric(30.0));
+/// x.insert("id", FeatureValue::Numeric(12345.0));
+/// union.learn_one(&x);
+///
+/// let y = union.transform_one(&x);
+/// let names: Vec<&String> = y.keys().collect();
+/// assert_eq!(names, ["age", "city_Oslo"]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformerUnion<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TransformerUnion<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+    /// Add a transformer to the union.
+    pub fn with<C>(self, other: C) -> TransformerUnion<TransformerUnion<A, B>, C> {
+        TransformerUnion::new(self, other)
+    }
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+}
+
+impl<F, A, B> Transformer<F> for TransformerUnion<A, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    A: Transformer<F>,
+    B: Transformer<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        self.first.learn_one(x);
+        self.second.learn_one(x);
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let mut merged = self.first.transform_one(x);
+        for (name, value) in self.second.transform_one(x).iter() {
+            merged.insert(name.clone(), value.clone());
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use crate::compose::pipeline::Chain;
+    use crate::compose::select::{Discard, Renamer, Select};
+    use crate::preprocessing::imputer::{Imputation, StatImputer};
+
+    #[test]
+    fn test_union_of_subsets() {
+        let mut union = TransformerUnion::new(
+            Chain::new(
+                Select::new(["a"]),
+                StatImputer::new(vec![], Some(Imputation::Mean)),
+            ),
+            Discard::new(["a"]),
+        )
+        .with(Chain::new(
+            Select::new(["b"]),
+            Renamer::new([("b", "b_raw")]),
+        ));
+        for a in [1.0, 3.0] {
+            union.learn_one(&Observation::from([
+                ("a".to_string(), a),
+                ("b".to_string(), 5.0),
+            ]));
+        }
+        let mut x: Observation<f64> = Observation::from([("b".to_string(), 4.0)]);
+        x.insert("a", FeatureValue::Missing);
+        assert_eq!(
+            union.transform_one(&x),
+            Observation::from([
+                ("a".to_string(), 2.0),
+                ("b".to_string(), 4.0),
+                ("b_raw".to_string(), 4.0),
+            ])
+        );
+    }
+}
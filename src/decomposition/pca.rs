@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Incremental principal component analysis, with the candid covariance-free algorithm (CCIPCA).
+///
+/// The principal components are estimated without storing the covariance matrix: each
+/// observation, once centered with the running mean, pulls the estimate of the first component
+/// towards itself in proportion to their alignment, is deflated by the component, and the
+/// remainder updates the next component, and so on. The norm of each estimate converges to the
+/// variance along its component, i.e. the eigenvalue.
+///
+/// The numeric features are indexed by name as they are discovered, and are zero in the
+/// observations in which they are absent. An observation is projected onto the current
+/// components, and the projections are output as features named `pc_0`, `pc_1`, and so on. The
+/// signs of the components are arbitrary.
+///
+/// # Parameters
+///
+/// - `n_components`: The number of principal components.
+/// - `amnesia`: How much more the recent observations weigh, 0 by default, for a stationary
+///   stream. Typical values are between 2 and 4.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::decomposition::pca::IncrementalPCA;
+/// use light_river::learner::Transformer;
+///
+/// let mut pca = IncrementalPCA::new(1, None);
+/// // The points lie on the line a = b
+/// for i in 0..1050 {
+///     let t = (i % 21) as f64 - 10.0;
+///     pca.learn_one(&Observation::from([("a".to_string(), t), ("b".to_string(), t)]));
+/// }
+/// assert!(pca.explained_variance_ratio()[0] > 0.99);
+/// let z = pca.transform_one(&Observation::from([("a".to_string(), 2.0), ("b".to_string(), 2.0)]));
+/// assert!((z.get_numeric("pc_0").unwrap().abs() - 8.0_f64.sqrt()).abs() < 1e-6);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Weng, Y. Zhang and W.-S. Hwang (2003). "Candid covariance-free incremental principal
+/// component analysis". IEEE Transactions on Pattern Analysis and Machine Intelligence 25(8):
+/// 1034-1040.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncrementalPCA<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    n_components: usize,
+    amnesia: F,
+    n: usize,
+    index: HashMap<String, usize>,
+    features: Vec<String>,
+    // Mean and population variance of each feature
+    vars: Vec<Var<F>>,
+    // Unnormalized estimates of the components, whose norms are the eigenvalues
+    vectors: Vec<Vec<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> IncrementalPCA<F> {
+    pub fn new(n_components: usize, amnesia: Option<F>) -> Self {
+        assert!(n_components > 0, "n_components must be strictly positive");
+        let amnesia = amnesia.unwrap_or(F::zero());
+        assert!(amnesia >= F::zero(), "amnesia must be positive");
+        Self {
+            n_components,
+            amnesia,
+            n: 0,
+            index: HashMap::new(),
+            features: Vec::new(),
+            vars: Vec::new(),
+            vectors: Vec::new(),
+        }
+    }
+    /// The variance along each component estimated so far, in decreasing order of importance.
+    pub fn explained_variance(&self) -> Vec<F> {
+        self.vectors.iter().map(|v| norm(v)).collect()
+    }
+    /// The share of the total variance of the features along each component.
+    pub fn explained_variance_ratio(&self) -> Vec<F> {
+        if self.n == 0 {
+            return Vec::new();
+        }
+        let total = self.vars.iter().fold(F::zero(), |sum, var| sum + var.get());
+        self.explained_variance()
+            .into_iter()
+            .map(|variance| {
+                if total > F::zero() {
+                    variance / total
+                } else {
+                    F::zero()
+                }
+            })
+            .collect()
+    }
+    /// The unit vector of a component, as the weight of each feature.
+    pub fn component(&self, i: usize) -> Observation<F> {
+        let v = &self.vectors[i];
+        let norm = norm(v);
+        self.features
+            .iter()
+            .zip(v.iter())
+            .map(|(name, w)| {
+                let w = if norm > F::zero() {
+                    *w / norm
+                } else {
+                    F::zero()
+                };
+                (name.clone(), FeatureValue::Numeric(w))
+            })
+            .collect()
+    }
+    /// Number of observations learned.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+    // The observation as a dense vector, centered with the running mean.
+    fn centered(&self, x: &Observation<F>) -> Vec<F> {
+        let mut u: Vec<F> = self.vars.iter().map(|var| -var.mean()).collect();
+        for (name, value) in x.numeric() {
+            if let Some(&j) = self.index.get(name) {
+                u[j] += value;
+            }
+        }
+        u
+    }
+}
+
+fn dot<F: Float>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b.iter())
+        .fold(F::zero(), |sum, (a, b)| sum + *a * *b)
+}
+
+fn norm<F: Float>(v: &[F]) -> F {
+    dot(v, v).sqrt()
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for IncrementalPCA<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        for (name, _) in x.numeric() {
+            if !self.index.contains_key(name) {
+                self.index.insert(name.clone(), self.features.len());
+                self.features.push(name.clone());
+                // The feature was zero in the observations learned so far
+                let mut var = Var::new(Some(0));
+                if self.n > 0 {
+                    var.update_weighted(F::zero(), F::from_usize(self.n).unwrap());
+                }
+                self.vars.push(var);
+                for v in self.vectors.iter_mut() {
+                    v.push(F::zero());
+                }
+            }
+        }
+        self.n += 1;
+        let n = F::from_usize(self.n).unwrap();
+        let mut dense = vec![F::zero(); self.features.len()];
+        for (name, value) in x.numeric() {
+            dense[self.index[name]] = value;
+        }
+        for (var, value) in self.vars.iter_mut().zip(dense) {
+            var.update(value);
+        }
+
+        let mut u = self.centered(x);
+        let (keep, learn) = (
+            (n - F::one() - self.amnesia) / n,
+            (F::one() + self.amnesia) / n,
+        );
+        for i in 0..self.n_components {
+            if i == self.vectors.len() {
+                self.vectors.push(vec![F::zero(); u.len()]);
+            }
+            let v = &mut self.vectors[i];
+            let v_norm = norm(v);
+            if v_norm > F::zero() {
+                let alignment = dot(&u, v) / v_norm;
+                for (w, u) in v.iter_mut().zip(u.iter()) {
+                    *w = keep * *w + learn * alignment * *u;
+                }
+            } else {
+                // The estimate starts from the first residual which isn't null
+                v.clone_from(&u);
+            }
+            // Remove the component from the observation before updating the next one
+            let v = &self.vectors[i];
+            let v_norm = norm(v);
+            if v_norm > F::zero() {
+                let projection = dot(&u, v) / v_norm;
+                for (u, w) in u.iter_mut().zip(v.iter()) {
+                    *u -= projection * *w / v_norm;
+                }
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let u = self.centered(x);
+        (0..self.n_components)
+            .map(|i| {
+                let projection = match self.vectors.get(i) {
+                    Some(v) if norm(v) > F::zero() => dot(&u, v) / norm(v),
+                    _ => F::zero(),
+                };
+                (format!("pc_{}", i), FeatureValue::Numeric(projection))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_components() {
+        // The variance is 9 along (1, 1, 0), 1 along (1, -1, 0), and 0.01 along (0, 0, 1)
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut pca = IncrementalPCA::new(2, None);
+        let (s, r) = (3.0_f64.sqrt(), 0.5_f64.sqrt());
+        for _ in 0..20000 {
+            let t: f64 = 3.0 * rng.gen_range(-s..s);
+            let o: f64 = rng.gen_range(-s..s);
+            let e: f64 = 0.1 * rng.gen_range(-s..s);
+            pca.learn_one(&Observation::from([
+                ("a".to_string(), 5.0 + r * (t + o)),
+                ("b".to_string(), -2.0 + r * (t - o)),
+                ("c".to_string(), e),
+            ]));
+        }
+        let variances = pca.explained_variance();
+        assert!((variances[0] - 9.0).abs() < 0.5, "{:?}", variances);
+        assert!((variances[1] - 1.0).abs() < 0.1, "{:?}", variances);
+        let ratios = pca.explained_variance_ratio();
+        assert!((ratios[0] + ratios[1] - 10.0 / 10.01).abs() < 0.01);
+
+        let first = pca.component(0);
+        let (a, b) = (
+            first.get_numeric("a").unwrap(),
+            first.get_numeric("b").unwrap(),
+        );
+        assert!((a.abs() - r).abs() < 0.02 && (a - b).abs() < 0.05);
+        assert!(first.get_numeric("c").unwrap().abs() < 0.05);
+
+        // The mean projects onto the origin
+        let z = pca.transform_one(&Observation::from([
+            ("a".to_string(), 5.0),
+            ("b".to_string(), -2.0),
+            ("c".to_string(), 0.0),
+        ]));
+        assert_eq!(z.len(), 2);
+        assert!(z.numeric().all(|(_, p)| p.abs() < 0.1));
+    }
+}
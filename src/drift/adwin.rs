@@ -0,0 +1,255 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{DriftDetector, DriftStatus};
+use num::{Float, FromPrimitive};
+
+// Summary of a bucket of 2^i values: their sum, and the sum of their squared deviations from
+// their mean.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Bucket<F> {
+    total: F,
+    variance: F,
+}
+
+/// ADaptive WINdowing (ADWIN) change detector.
+///
+/// ADWIN keeps a window of the most recent values, whose size adapts to the stream: whenever two
+/// large enough sub-windows have means which differ by more than a bound derived from the
+/// Hoeffding inequality, the older values are dropped, and a change is signaled. The window is
+/// compressed with an exponential histogram, i.e. buckets of 1, 2, 4, ... values of which at most
+/// `max_buckets` of each size are kept, so that it only takes logarithmic memory and time.
+///
+/// # Parameters
+///
+/// - `delta`: The confidence of the bound, 0.002 by default. The lower, the fewer false alarms,
+///   but the slower the detection.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::adwin::ADWIN;
+/// use light_river::drift::DriftStatus;
+///
+/// let mut adwin: ADWIN<f64> = ADWIN::new(None);
+/// let mut detections = Vec::new();
+/// for i in 0..2000 {
+///     let value = if i < 1000 { 0.2 } else { 0.8 };
+///     if adwin.update(value) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// assert!(!detections.is_empty());
+/// assert!(detections[0] >= 1000 && detections[0] < 1100);
+/// // Only the values after the change are left
+/// assert!(adwin.width() < 1000);
+/// assert!((adwin.mean() - 0.8).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: A. Bifet and R. Gavaldà (2007). "Learning from time-changing data with adaptive
+/// windowing". Proceedings of the 2007 SIAM international conference on data mining, 443-448.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ADWIN<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    delta: F,
+    clock: usize,
+    max_buckets: usize,
+    min_window_length: usize,
+    grace_period: usize,
+    // Row i holds the buckets of 2^i values, from the oldest to the newest
+    rows: Vec<VecDeque<Bucket<F>>>,
+    width: usize,
+    total: F,
+    variance: F,
+    tick: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ADWIN<F> {
+    pub fn new(delta: Option<F>) -> Self {
+        let delta = delta.unwrap_or(F::from_f64(0.002).unwrap());
+        assert!(
+            delta > F::zero() && delta < F::one(),
+            "delta must lie in (0, 1)"
+        );
+        Self {
+            delta,
+            clock: 32,
+            max_buckets: 5,
+            min_window_length: 5,
+            grace_period: 10,
+            rows: vec![VecDeque::new()],
+            width: 0,
+            total: F::zero(),
+            variance: F::zero(),
+            tick: 0,
+        }
+    }
+    /// Add a value to the window, and return whether a change has been detected. ADWIN never
+    /// raises warnings.
+    pub fn update(&mut self, value: F) -> DriftStatus {
+        self.insert(value);
+        self.compress();
+        self.tick += 1;
+        if self.tick.is_multiple_of(self.clock) && self.width > self.grace_period && self.detect() {
+            DriftStatus::Drift
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    /// Number of values in the window.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Mean of the values in the window.
+    pub fn mean(&self) -> F {
+        if self.width == 0 {
+            F::zero()
+        } else {
+            self.total / F::from(self.width).unwrap()
+        }
+    }
+    /// Variance of the values in the window.
+    pub fn variance(&self) -> F {
+        if self.width == 0 {
+            F::zero()
+        } else {
+            self.variance / F::from(self.width).unwrap()
+        }
+    }
+    fn insert(&mut self, value: F) {
+        self.width += 1;
+        if self.width > 1 {
+            let n = F::from(self.width).unwrap();
+            let mean = self.total / (n - F::one());
+            self.variance += (n - F::one()) * (value - mean).powi(2) / n;
+        }
+        self.total += value;
+        self.rows[0].push_back(Bucket {
+            total: value,
+            variance: F::zero(),
+        });
+    }
+    // Merge the two oldest buckets of each row which holds too many of them.
+    fn compress(&mut self) {
+        let mut i = 0;
+        while i < self.rows.len() {
+            if self.rows[i].len() <= self.max_buckets {
+                break;
+            }
+            let b1 = self.rows[i].pop_front().unwrap();
+            let b2 = self.rows[i].pop_front().unwrap();
+            let n = F::from(1usize << i).unwrap();
+            let (u1, u2) = (b1.total / n, b2.total / n);
+            let merged = Bucket {
+                total: b1.total + b2.total,
+                variance: b1.variance + b2.variance + n * n * (u1 - u2).powi(2) / (n + n),
+            };
+            if i + 1 == self.rows.len() {
+                self.rows.push(VecDeque::new());
+            }
+            self.rows[i + 1].push_back(merged);
+            i += 1;
+        }
+    }
+    // Drop the oldest bucket of the window.
+    fn delete_oldest(&mut self) {
+        let i = self.rows.iter().rposition(|row| !row.is_empty()).unwrap();
+        let bucket = self.rows[i].pop_front().unwrap();
+        let n = 1usize << i;
+        self.width -= n;
+        self.total -= bucket.total;
+        let (n, width) = (F::from(n).unwrap(), F::from(self.width).unwrap());
+        let u = bucket.total / n;
+        self.variance -=
+            bucket.variance + n * width * (u - self.total / width).powi(2) / (n + width);
+        if self.rows[i].is_empty() && i > 0 {
+            self.rows.pop();
+        }
+    }
+    // Drop the oldest values as long as the window can be cut into two sub-windows with different
+    // means.
+    fn detect(&mut self) -> bool {
+        let mut detected = false;
+        'scan: loop {
+            let width = F::from(self.width).unwrap();
+            let variance = self.variance / width;
+            let dd = ((F::one() + F::one()) * width.ln() / self.delta).ln();
+            let min_length = F::from(self.min_window_length).unwrap();
+
+            // Move the cut from the oldest to the newest bucket
+            let (mut n0, mut total0) = (0usize, F::zero());
+            for i in (0..self.rows.len()).rev() {
+                for bucket in self.rows[i].iter() {
+                    n0 += 1 << i;
+                    total0 += bucket.total;
+                    let n1 = self.width - n0;
+                    if n0 < self.min_window_length || n1 < self.min_window_length {
+                        continue;
+                    }
+                    let (n0f, n1f) = (F::from(n0).unwrap(), F::from(n1).unwrap());
+                    let diff = total0 / n0f - (self.total - total0) / n1f;
+                    let m = F::one() / (n0f - min_length + F::one())
+                        + F::one() / (n1f - min_length + F::one());
+                    let two = F::one() + F::one();
+                    let epsilon =
+                        (two * m * variance * dd).sqrt() + two / F::from(3).unwrap() * dd * m;
+                    if diff.abs() > epsilon {
+                        detected = true;
+                        self.delete_oldest();
+                        if self.width > self.grace_period {
+                            continue 'scan;
+                        }
+                        break 'scan;
+                    }
+                }
+            }
+            break;
+        }
+        detected
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for ADWIN<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        ADWIN::update(self, value)
+    }
+    fn reset(&mut self) {
+        *self = ADWIN::new(Some(self.delta));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics() {
+        let mut adwin = ADWIN::new(None);
+        let values: Vec<f64> = (0..100).map(|i| (i % 7) as f64).collect();
+        for value in values.iter() {
+            assert_eq!(adwin.update(*value), DriftStatus::Stable);
+        }
+        let mean = values.iter().sum::<f64>() / 100.0;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / 100.0;
+        assert_eq!(adwin.width(), 100);
+        assert!((adwin.mean() - mean).abs() < 1e-10);
+        assert!((adwin.variance() - variance).abs() < 1e-10);
+        // Compressed into buckets of up to 16 values, at most 5 of each size
+        assert!(adwin.rows.len() <= 5);
+        assert!(adwin.rows.iter().all(|row| row.len() <= 5));
+    }
+
+    #[test]
+    fn test_stationary_stream() {
+        let mut adwin = ADWIN::new(None);
+        for i in 0..10000 {
+            assert_eq!(adwin.update((i % 2) as f64), DriftStatus::Stable);
+        }
+        assert_eq!(adwin.width(), 10000);
+    }
+}
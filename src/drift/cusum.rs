@@ -0,0 +1,152 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{ChangeDirection, DriftDetector, DriftStatus};
+use num::{Float, FromPrimitive};
+
+/// Options of a [`CUSUM`] detector.
+///
+/// # Parameters
+///
+/// - `min_instances`: The number of values seen before any change can be detected, 30 by default.
+/// - `delta`: The magnitude of the changes which are tolerated, 0.005 by default.
+/// - `lambda`: The threshold of the cumulative sums above which a change is signaled, 50 by
+///   default. The higher, the fewer false alarms, but the slower the detection.
+/// - `direction`: Which changes of the mean are detected, both increases and decreases by default.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CUSUMOptions<F> {
+    pub min_instances: usize,
+    pub delta: F,
+    pub lambda: F,
+    pub direction: ChangeDirection,
+}
+
+impl<F: Float + FromPrimitive> Default for CUSUMOptions<F> {
+    fn default() -> Self {
+        Self {
+            min_instances: 30,
+            delta: F::from_f64(0.005).unwrap(),
+            lambda: F::from_f64(50.0).unwrap(),
+            direction: ChangeDirection::Both,
+        }
+    }
+}
+
+/// Cumulative sum (CUSUM) change detector.
+///
+/// The two-sided tabular CUSUM accumulates the deviations of the values above their running mean
+/// beyond the tolerated magnitude `delta` in a sum which is clamped at zero, and signals a change
+/// when it exceeds `lambda`. Decreases are accumulated in a second sum. Unlike
+/// [`PageHinkley`](super::page_hinkley::PageHinkley), the sums don't forget, and are reset
+/// whenever the values fall back to the mean. The detector starts afresh after a change.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::cusum::{CUSUM, CUSUMOptions};
+/// use light_river::drift::{DriftDetector, DriftStatus};
+///
+/// let mut detector: CUSUM<f64> = CUSUM::new(CUSUMOptions::default());
+/// let mut detections = Vec::new();
+/// for i in 0..2000 {
+///     let value = if i < 1000 { (i % 5) as f64 } else { 5.0 + (i % 5) as f64 };
+///     if detector.update(value) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// assert_eq!(detections.len(), 1);
+/// assert!(detections[0] >= 1000 && detections[0] < 1020);
+/// ```
+///
+/// # References
+///
+/// [^1]: E. S. Page (1954). "Continuous inspection schemes". Biometrika 41(1/2):100-115.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CUSUM<F> {
+    options: CUSUMOptions<F>,
+    n: usize,
+    mean: F,
+    sum_up: F,
+    sum_down: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CUSUM<F> {
+    pub fn new(options: CUSUMOptions<F>) -> Self {
+        assert!(
+            options.lambda > F::zero(),
+            "lambda must be strictly positive"
+        );
+        Self {
+            options,
+            n: 0,
+            mean: F::zero(),
+            sum_up: F::zero(),
+            sum_down: F::zero(),
+        }
+    }
+    /// The mean of the values since the last change.
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for CUSUM<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        self.n += 1;
+        self.mean += (value - self.mean) / F::from_usize(self.n).unwrap();
+        let deviation = value - self.mean;
+        let options = &self.options;
+
+        self.sum_up = (self.sum_up + deviation - options.delta).max(F::zero());
+        self.sum_down = (self.sum_down - deviation - options.delta).max(F::zero());
+        if self.n < options.min_instances {
+            return DriftStatus::Stable;
+        }
+
+        let up = options.direction.up() && self.sum_up > options.lambda;
+        let down = options.direction.down() && self.sum_down > options.lambda;
+        if up || down {
+            self.reset();
+            DriftStatus::Drift
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    fn reset(&mut self) {
+        *self = CUSUM::new(self.options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(i: usize) -> f64 {
+        if i < 1000 {
+            (i % 5) as f64
+        } else {
+            10.0 + (i % 5) as f64
+        }
+    }
+
+    #[test]
+    fn test_direction() {
+        for (direction, detected) in [
+            (ChangeDirection::Up, true),
+            (ChangeDirection::Down, false),
+            (ChangeDirection::Both, true),
+        ] {
+            let options = CUSUMOptions {
+                direction,
+                ..Default::default()
+            };
+            let mut detector: CUSUM<f64> = CUSUM::new(options);
+            let statuses: Vec<_> = (0..2000).map(|i| detector.update(stream(i))).collect();
+            assert!(statuses[..1000].iter().all(|s| !s.is_drift()));
+            assert_eq!(statuses.iter().any(|s| s.is_drift()), detected);
+        }
+    }
+}
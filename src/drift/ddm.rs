@@ -0,0 +1,153 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{DriftDetector, DriftStatus};
+use num::{Float, FromPrimitive};
+
+/// Options of a [`DDM`] detector.
+///
+/// # Parameters
+///
+/// - `warm_start`: The number of values seen before any change can be detected, 30 by default.
+/// - `warning_threshold`: The number of standard deviations above the minimum error rate which
+///   raises a warning, 2 by default.
+/// - `drift_threshold`: The number of standard deviations above the minimum error rate which
+///   signals a change, 3 by default.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DDMOptions<F> {
+    pub warm_start: usize,
+    pub warning_threshold: F,
+    pub drift_threshold: F,
+}
+
+impl<F: Float + FromPrimitive> Default for DDMOptions<F> {
+    fn default() -> Self {
+        Self {
+            warm_start: 30,
+            warning_threshold: F::from_f64(2.0).unwrap(),
+            drift_threshold: F::from_f64(3.0).unwrap(),
+        }
+    }
+}
+
+/// Drift Detection Method (DDM).
+///
+/// DDM monitors the error rate `p` of a classifier, whose errors are modeled by a binomial
+/// distribution, i.e. the values are 1 for a mistake and 0 otherwise. The standard deviation of
+/// the error rate after `n` values is `s = sqrt(p * (1 - p) / n)`. The minimum of `p + s` is
+/// tracked, and a warning is raised when `p + s` rises `warning_threshold` standard deviations
+/// above it, and a change is signaled at `drift_threshold` standard deviations, after which the
+/// detector starts afresh.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::ddm::{DDM, DDMOptions};
+/// use light_river::drift::{DriftDetector, DriftStatus};
+///
+/// let mut ddm: DDM<f64> = DDM::new(DDMOptions::default());
+/// let mut detections = Vec::new();
+/// for i in 0..2000 {
+///     // The error rate goes from 10% to 50%
+///     let error = if i < 1000 { i % 10 == 0 } else { i % 2 == 0 };
+///     if ddm.update(if error { 1.0 } else { 0.0 }) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// assert!(!detections.is_empty());
+/// assert!(detections[0] >= 1000 && detections[0] < 1100);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Gama, P. Medas, G. Castillo and P. Rodrigues (2004). "Learning with drift detection".
+/// Brazilian symposium on artificial intelligence, 286-295.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DDM<F> {
+    options: DDMOptions<F>,
+    n: usize,
+    error_rate: F,
+    p_min: F,
+    s_min: F,
+    ps_min: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DDM<F> {
+    pub fn new(options: DDMOptions<F>) -> Self {
+        assert!(
+            options.warning_threshold > F::zero(),
+            "warning_threshold must be strictly positive"
+        );
+        assert!(
+            options.drift_threshold >= options.warning_threshold,
+            "drift_threshold must be at least warning_threshold"
+        );
+        Self {
+            options,
+            n: 0,
+            error_rate: F::zero(),
+            p_min: F::infinity(),
+            s_min: F::infinity(),
+            ps_min: F::infinity(),
+        }
+    }
+    /// The error rate since the last change.
+    pub fn error_rate(&self) -> F {
+        self.error_rate
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for DDM<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        self.n += 1;
+        let n = F::from_usize(self.n).unwrap();
+        self.error_rate += (value - self.error_rate) / n;
+        if self.n < self.options.warm_start {
+            return DriftStatus::Stable;
+        }
+
+        let p = self.error_rate;
+        let s = (p * (F::one() - p) / n).sqrt();
+        if p + s <= self.ps_min {
+            self.p_min = p;
+            self.s_min = s;
+            self.ps_min = p + s;
+        }
+        if p + s > self.p_min + self.options.drift_threshold * self.s_min {
+            self.reset();
+            DriftStatus::Drift
+        } else if p + s > self.p_min + self.options.warning_threshold * self.s_min {
+            DriftStatus::Warning
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    fn reset(&mut self) {
+        *self = DDM::new(self.options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_before_drift() {
+        let mut ddm: DDM<f64> = DDM::new(DDMOptions::default());
+        let mut statuses = Vec::new();
+        for i in 0..3000 {
+            let error = if i < 1000 { i % 10 == 0 } else { i % 3 == 0 };
+            statuses.push(ddm.update(if error { 1.0 } else { 0.0 }));
+        }
+        assert!(statuses[..1000].iter().all(|s| *s == DriftStatus::Stable));
+        let warning = statuses.iter().position(|s| s.is_warning()).unwrap();
+        let drift = statuses.iter().position(|s| s.is_drift()).unwrap();
+        assert!(warning >= 1000 && warning < drift);
+        // The detector starts afresh after the change, and the new error rate is stable
+        assert!(statuses[drift + 1..].iter().all(|s| !s.is_drift()));
+        assert!((ddm.error_rate() - 1.0 / 3.0).abs() < 0.01);
+    }
+}
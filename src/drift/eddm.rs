@@ -0,0 +1,151 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{DriftDetector, DriftStatus};
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Options of an [`EDDM`] detector.
+///
+/// # Parameters
+///
+/// - `warm_start`: The number of errors seen before any change can be detected, 30 by default.
+/// - `alpha`: The ratio to the maximum below which a warning is raised, 0.95 by default.
+/// - `beta`: The ratio to the maximum below which a change is signaled, 0.9 by default.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EDDMOptions<F> {
+    pub warm_start: usize,
+    pub alpha: F,
+    pub beta: F,
+}
+
+impl<F: Float + FromPrimitive> Default for EDDMOptions<F> {
+    fn default() -> Self {
+        Self {
+            warm_start: 30,
+            alpha: F::from_f64(0.95).unwrap(),
+            beta: F::from_f64(0.9).unwrap(),
+        }
+    }
+}
+
+/// Early Drift Detection Method (EDDM).
+///
+/// EDDM monitors the distance between consecutive errors of a classifier rather than its error
+/// rate, which makes it better at detecting gradual changes than [`DDM`](super::ddm::DDM). The
+/// values are 1 for a mistake and 0 otherwise. The maximum of `m + 2 * s` is tracked, where `m`
+/// and `s` are the mean and the standard deviation of the distances between errors. A warning is
+/// raised when `(m + 2 * s) / max` falls below `alpha`, and a change is signaled when it falls
+/// below `beta`, after which the detector starts afresh.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::eddm::{EDDM, EDDMOptions};
+/// use light_river::drift::{DriftDetector, DriftStatus};
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let mut eddm: EDDM<f64> = EDDM::new(EDDMOptions::default());
+/// let mut detections = Vec::new();
+/// for i in 0..3000 {
+///     // The errors become more frequent
+///     let error = rng.gen_bool(if i < 2000 { 0.1 } else { 0.5 });
+///     if eddm.update(if error { 1.0 } else { 0.0 }) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// assert!(!detections.is_empty());
+/// assert!(detections[0] >= 2000 && detections[0] < 2100);
+/// ```
+///
+/// # References
+///
+/// [^1]: M. Baena-García, J. del Campo-Ávila, R. Fidalgo, A. Bifet, R. Gavaldà and R. Morales-Bueno
+/// (2006). "Early drift detection method". Fourth international workshop on knowledge discovery
+/// from data streams, 77-86.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EDDM<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    options: EDDMOptions<F>,
+    tick: usize,
+    last_error: usize,
+    // Mean and population variance of the distances between errors
+    distances: Var<F>,
+    max: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> EDDM<F> {
+    pub fn new(options: EDDMOptions<F>) -> Self {
+        assert!(
+            options.beta > F::zero() && options.beta <= options.alpha && options.alpha < F::one(),
+            "0 < beta <= alpha < 1 must hold"
+        );
+        Self {
+            options,
+            tick: 0,
+            last_error: 0,
+            distances: Var::new(Some(0)),
+            max: F::zero(),
+        }
+    }
+    /// The mean distance between errors since the last change.
+    pub fn mean_distance(&self) -> F {
+        self.distances.mean()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for EDDM<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        self.tick += 1;
+        if value <= F::zero() {
+            return DriftStatus::Stable;
+        }
+
+        let distance = F::from_usize(self.tick - self.last_error).unwrap();
+        self.last_error = self.tick;
+        self.distances.update(distance);
+        if self.distances.n() < F::from_usize(self.options.warm_start).unwrap() {
+            return DriftStatus::Stable;
+        }
+
+        let std = self.distances.get().sqrt();
+        let m2s = self.distances.mean() + (F::one() + F::one()) * std;
+        if m2s > self.max {
+            self.max = m2s;
+            return DriftStatus::Stable;
+        }
+        let ratio = m2s / self.max;
+        if ratio < self.options.beta {
+            self.reset();
+            DriftStatus::Drift
+        } else if ratio < self.options.alpha {
+            DriftStatus::Warning
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    fn reset(&mut self) {
+        *self = EDDM::new(self.options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_stationary_stream() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut eddm: EDDM<f64> = EDDM::new(EDDMOptions::default());
+        for _ in 0..20000 {
+            let error = rng.gen_bool(0.1);
+            assert!(!eddm.update(if error { 1.0 } else { 0.0 }).is_drift());
+        }
+        assert!((eddm.mean_distance() - 10.0).abs() < 1.0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::drift::{DriftDetector, DriftStatus};
+use num::{Float, FromPrimitive};
+
+/// Monitors each numeric feature of a stream of observations with its own change detector.
+///
+/// A copy of the given detector is attached to each feature the first time it is seen, and is
+/// updated with the values of the feature. Categorical and missing values are ignored. The state
+/// of the stream is the most severe state of its features, and every change is recorded with the
+/// feature which drifted and the index of the observation, so that the monitor doubles as a data
+/// quality check of a stream.
+///
+/// # Parameters
+///
+/// - `detector`: The change detector which is copied for each feature.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::drift::feature_monitor::FeatureDriftMonitor;
+/// use light_river::drift::page_hinkley::{PageHinkley, PageHinkleyOptions};
+/// use light_river::drift::DriftStatus;
+///
+/// let detector = PageHinkley::new(PageHinkleyOptions::default());
+/// let mut monitor = FeatureDriftMonitor::new(detector);
+/// for i in 0..2000 {
+///     let a = (i % 5) as f64;
+///     // The mean of b increases from the 1000th observation
+///     let b = if i < 1000 { a } else { a + 5.0 };
+///     let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+///     monitor.update(&x);
+/// }
+///
+/// assert_eq!(monitor.drifts().len(), 1);
+/// let (index, feature) = &monitor.drifts()[0];
+/// assert_eq!(feature, "b");
+/// assert!(*index >= 1000 && *index < 1020);
+/// assert_eq!(monitor.statuses()["a"], DriftStatus::Stable);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureDriftMonitor<F, D> {
+    detector: D,
+    detectors: HashMap<String, D>,
+    statuses: HashMap<String, DriftStatus>,
+    drifts: Vec<(usize, String)>,
+    n_samples: usize,
+    _float: PhantomData<F>,
+}
+
+impl<F, D> FeatureDriftMonitor<F, D>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    D: DriftDetector<F> + Clone,
+{
+    pub fn new(detector: D) -> Self {
+        Self {
+            detector,
+            detectors: HashMap::new(),
+            statuses: HashMap::new(),
+            drifts: Vec::new(),
+            n_samples: 0,
+            _float: PhantomData,
+        }
+    }
+    /// Update the detectors with the numeric features of an observation, and return the most
+    /// severe state of the features which it holds.
+    pub fn update(&mut self, x: &Observation<F>) -> DriftStatus {
+        let mut status = DriftStatus::Stable;
+        for (name, value) in x.numeric() {
+            let feature_status = self
+                .detectors
+                .entry(name.clone())
+                .or_insert_with(|| self.detector.clone())
+                .update(value);
+            if feature_status.is_drift() {
+                self.drifts.push((self.n_samples, name.clone()));
+            }
+            status = match (status, feature_status) {
+                (DriftStatus::Drift, _) | (_, DriftStatus::Drift) => DriftStatus::Drift,
+                (DriftStatus::Warning, _) | (_, DriftStatus::Warning) => DriftStatus::Warning,
+                _ => DriftStatus::Stable,
+            };
+            self.statuses.insert(name.clone(), feature_status);
+        }
+        self.n_samples += 1;
+        status
+    }
+    /// The state of each feature after its last update.
+    pub fn statuses(&self) -> &HashMap<String, DriftStatus> {
+        &self.statuses
+    }
+    /// The features whose last update signaled a change.
+    pub fn drifting_features(&self) -> Vec<&String> {
+        let mut features: Vec<_> = self
+            .statuses
+            .iter()
+            .filter(|(_, status)| status.is_drift())
+            .map(|(name, _)| name)
+            .collect();
+        features.sort();
+        features
+    }
+    /// Every change signaled so far, as the index of the observation and the feature which
+    /// drifted, in order.
+    pub fn drifts(&self) -> &[(usize, String)] {
+        &self.drifts
+    }
+    /// The detector of a feature, if it has been seen.
+    pub fn detector(&self, name: &str) -> Option<&D> {
+        self.detectors.get(name)
+    }
+    pub fn n_samples(&self) -> usize {
+        self.n_samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use crate::drift::ddm::{DDMOptions, DDM};
+
+    #[test]
+    fn test_aggregation() {
+        let mut monitor = FeatureDriftMonitor::new(DDM::new(DDMOptions::default()));
+        let mut statuses = Vec::new();
+        for i in 0..3000 {
+            let mut x = Observation::new();
+            // a is an error rate which goes from 10% to 50%, b stays at 10%
+            let a = if i < 1500 { i % 10 == 0 } else { i % 2 == 0 };
+            x.insert(
+                "a".to_string(),
+                FeatureValue::Numeric(if a { 1.0 } else { 0.0 }),
+            );
+            x.insert(
+                "b".to_string(),
+                FeatureValue::Numeric(if i % 10 == 0 { 1.0 } else { 0.0 }),
+            );
+            x.insert("c".to_string(), FeatureValue::Categorical("c".to_string()));
+            let status = monitor.update(&x);
+            if status.is_drift() {
+                assert_eq!(monitor.drifting_features(), vec!["a"]);
+            }
+            statuses.push(status);
+        }
+        assert_eq!(monitor.n_samples(), 3000);
+        assert!(monitor.detector("c").is_none());
+        assert!(monitor
+            .drifts()
+            .iter()
+            .all(|(i, name)| *i >= 1500 && name == "a"));
+
+        let warning = statuses.iter().position(|s| s.is_warning()).unwrap();
+        let drift = statuses.iter().position(|s| s.is_drift()).unwrap();
+        assert!(warning >= 1500 && warning < drift);
+        assert_eq!(monitor.drifts()[0].0, drift);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{DriftDetector, DriftStatus};
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+
+/// Options of a [`KSWIN`] detector.
+///
+/// # Parameters
+///
+/// - `alpha`: The significance level of the Kolmogorov-Smirnov test, 0.005 by default. The lower,
+///   the fewer false alarms, but the slower the detection.
+/// - `window_size`: The number of most recent values which are kept, 100 by default.
+/// - `stat_size`: The number of most recent values which are compared to the rest of the window,
+///   30 by default. It must be at most half of `window_size`.
+#[derive(Clone, Copy, Debug)]
+pub struct KSWINOptions<F> {
+    pub alpha: F,
+    pub window_size: usize,
+    pub stat_size: usize,
+}
+
+impl<F: Float + FromPrimitive> Default for KSWINOptions<F> {
+    fn default() -> Self {
+        Self {
+            alpha: F::from_f64(0.005).unwrap(),
+            window_size: 100,
+            stat_size: 30,
+        }
+    }
+}
+
+/// Kolmogorov-Smirnov WINdowing (KSWIN) change detector.
+///
+/// KSWIN keeps a sliding window of the most recent values. Once it is full, the `stat_size` most
+/// recent values are compared with as many values sampled uniformly from the older part of the
+/// window, with the two-sample Kolmogorov-Smirnov test. A change is signaled when the statistic
+/// `D`, the largest distance between the empirical distribution functions of both samples, exceeds
+/// `sqrt(-ln(alpha) / stat_size)`, after which only the most recent values are kept.
+///
+/// The test compares whole distributions rather than means, so that KSWIN also detects changes of
+/// the spread or of the shape of the values, e.g. of a feature.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::kswin::{KSWIN, KSWINOptions};
+/// use light_river::drift::{DriftDetector, DriftStatus};
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut kswin: KSWIN<f64> = KSWIN::new(KSWINOptions::default(), Some(42));
+/// let mut detections = Vec::new();
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..2000 {
+///     // The mean stays the same, but the spread increases
+///     let value = rng.gen_range(-0.5..0.5);
+///     let value = if i < 1000 { value } else { 5.0 * value };
+///     if kswin.update(value) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// // The change is detected shortly after it happens, along with a few false alarms
+/// assert!(detections.iter().any(|i| (1000..1050).contains(i)));
+/// ```
+///
+/// # References
+///
+/// [^1]: C. Raab, M. Heusinger and F.-M. Schleif (2020). "Reactive soft prototype computing for
+/// concept drift streams". Neurocomputing 416:340-351.
+#[derive(Clone, Debug)]
+pub struct KSWIN<F> {
+    options: KSWINOptions<F>,
+    window: VecDeque<F>,
+    statistic: F,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> KSWIN<F> {
+    pub fn new(options: KSWINOptions<F>, seed: Option<u64>) -> Self {
+        assert!(
+            options.alpha > F::zero() && options.alpha < F::one(),
+            "alpha must lie in (0, 1)"
+        );
+        assert!(options.stat_size > 0, "stat_size must be strictly positive");
+        assert!(
+            2 * options.stat_size <= options.window_size,
+            "stat_size must be at most half of window_size"
+        );
+        let rng = rng(seed);
+        Self {
+            options,
+            window: VecDeque::with_capacity(options.window_size),
+            statistic: F::zero(),
+            rng,
+        }
+    }
+    /// The Kolmogorov-Smirnov statistic of the last test.
+    pub fn statistic(&self) -> F {
+        self.statistic
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for KSWIN<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        let KSWINOptions {
+            alpha,
+            window_size,
+            stat_size,
+        } = self.options;
+        if self.window.len() == window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(value);
+        if self.window.len() < window_size {
+            return DriftStatus::Stable;
+        }
+
+        let older = window_size - stat_size;
+        let reference: Vec<F> = sample(&mut self.rng, older, stat_size)
+            .into_iter()
+            .map(|i| self.window[i])
+            .collect();
+        let recent: Vec<F> = self.window.range(older..).copied().collect();
+        self.statistic = ks_statistic(reference, recent);
+
+        let critical = (-alpha.ln() / F::from_usize(stat_size).unwrap()).sqrt();
+        if self.statistic > critical {
+            self.window.drain(..older);
+            DriftStatus::Drift
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    fn reset(&mut self) {
+        self.window.clear();
+        self.statistic = F::zero();
+    }
+}
+
+// The largest distance between the empirical distribution functions of two samples.
+fn ks_statistic<F: Float + FromPrimitive>(mut a: Vec<F>, mut b: Vec<F>) -> F {
+    a.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    let (na, nb) = (
+        F::from_usize(a.len()).unwrap(),
+        F::from_usize(b.len()).unwrap(),
+    );
+    let (mut i, mut j) = (0, 0);
+    let mut statistic = F::zero();
+    while i < a.len() && j < b.len() {
+        // Step over all the copies of the smallest value in both samples
+        let value = a[i].min(b[j]);
+        while i < a.len() && a[i] == value {
+            i += 1;
+        }
+        while j < b.len() && b[j] == value {
+            j += 1;
+        }
+        let distance = F::from_usize(i).unwrap() / na - F::from_usize(j).unwrap() / nb;
+        statistic = statistic.max(distance.abs());
+    }
+    statistic
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    #[test]
+    fn test_ks_statistic() {
+        assert_eq!(ks_statistic(vec![1.0, 2.0, 3.0], vec![3.0, 2.0, 1.0]), 0.0);
+        assert_eq!(ks_statistic(vec![1.0, 2.0], vec![3.0, 4.0]), 1.0);
+        assert_eq!(
+            ks_statistic(vec![1.0, 2.0, 3.0, 4.0], vec![3.0, 4.0, 5.0, 6.0]),
+            0.5
+        );
+        // Ties are counted in both samples at once
+        assert_eq!(
+            ks_statistic(vec![1.0, 1.0, 2.0], vec![1.0, 2.0, 2.0]),
+            1.0 / 3.0
+        );
+    }
+
+    #[test]
+    fn test_stationary_stream() {
+        let mut kswin: KSWIN<f64> = KSWIN::new(KSWINOptions::default(), Some(42));
+        for i in 0..10000 {
+            let value = scrambled(i, 1000) as f64;
+            assert!(!kswin.update(value).is_drift());
+        }
+        assert!(kswin.statistic() > 0.0);
+    }
+}
@@ -0,0 +1,61 @@
+pub mod adwin;
+pub mod cusum;
+pub mod ddm;
+pub mod eddm;
+pub mod feature_monitor;
+pub mod kswin;
+pub mod page_hinkley;
+pub mod retraining;
+
+/// The state of a stream after an update of a change detector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DriftStatus {
+    /// No change has been detected.
+    #[default]
+    Stable,
+    /// A change may be happening, but there isn't enough evidence yet.
+    Warning,
+    /// A change has been detected.
+    Drift,
+}
+
+impl DriftStatus {
+    pub fn is_drift(&self) -> bool {
+        *self == DriftStatus::Drift
+    }
+    pub fn is_warning(&self) -> bool {
+        *self == DriftStatus::Warning
+    }
+}
+
+/// Which changes of the mean of a stream are detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeDirection {
+    /// Increases of the mean.
+    Up,
+    /// Decreases of the mean.
+    Down,
+    /// Both increases and decreases of the mean.
+    #[default]
+    Both,
+}
+
+impl ChangeDirection {
+    pub(crate) fn up(&self) -> bool {
+        *self != ChangeDirection::Down
+    }
+    pub(crate) fn down(&self) -> bool {
+        *self != ChangeDirection::Up
+    }
+}
+
+/// Trait for implementing a change detector, which monitors a univariate stream of values, e.g.
+/// the errors of a model.
+pub trait DriftDetector<F> {
+    /// Update the detector with a value, and return the state of the stream.
+    fn update(&mut self, value: F) -> DriftStatus;
+    /// Forget everything the detector has seen.
+    fn reset(&mut self);
+}
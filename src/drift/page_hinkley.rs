@@ -0,0 +1,165 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::drift::{ChangeDirection, DriftDetector, DriftStatus};
+use num::{Float, FromPrimitive};
+
+/// Options of a [`PageHinkley`] detector.
+///
+/// # Parameters
+///
+/// - `min_instances`: The number of values seen before any change can be detected, 30 by default.
+/// - `delta`: The magnitude of the changes which are tolerated, 0.005 by default.
+/// - `lambda`: The threshold of the cumulative deviation above which a change is signaled, 50 by
+///   default. The higher, the fewer false alarms, but the slower the detection.
+/// - `alpha`: The forgetting factor of the cumulative deviation, 0.9999 by default.
+/// - `direction`: Which changes of the mean are detected, both increases and decreases by default.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageHinkleyOptions<F> {
+    pub min_instances: usize,
+    pub delta: F,
+    pub lambda: F,
+    pub alpha: F,
+    pub direction: ChangeDirection,
+}
+
+impl<F: Float + FromPrimitive> Default for PageHinkleyOptions<F> {
+    fn default() -> Self {
+        Self {
+            min_instances: 30,
+            delta: F::from_f64(0.005).unwrap(),
+            lambda: F::from_f64(50.0).unwrap(),
+            alpha: F::from_f64(0.9999).unwrap(),
+            direction: ChangeDirection::Both,
+        }
+    }
+}
+
+/// Page-Hinkley change detector.
+///
+/// The Page-Hinkley test accumulates the deviations of the values from their running mean, minus
+/// the tolerated magnitude `delta`, and signals a change when the cumulative sum rises `lambda`
+/// above its minimum. Decreases are detected symmetrically. The cumulative sums are discounted by
+/// `alpha`, so that old deviations are slowly forgotten. The detector starts afresh after a
+/// change.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::drift::page_hinkley::{PageHinkley, PageHinkleyOptions};
+/// use light_river::drift::{DriftDetector, DriftStatus};
+///
+/// let mut detector: PageHinkley<f64> = PageHinkley::new(PageHinkleyOptions::default());
+/// let mut detections = Vec::new();
+/// for i in 0..2000 {
+///     let value = if i < 1000 { (i % 5) as f64 } else { 5.0 + (i % 5) as f64 };
+///     if detector.update(value) == DriftStatus::Drift {
+///         detections.push(i);
+///     }
+/// }
+/// assert_eq!(detections.len(), 1);
+/// assert!(detections[0] >= 1000 && detections[0] < 1020);
+/// ```
+///
+/// # References
+///
+/// [^1]: E. S. Page (1954). "Continuous inspection schemes". Biometrika 41(1/2):100-115.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageHinkley<F> {
+    options: PageHinkleyOptions<F>,
+    n: usize,
+    mean: F,
+    sum_up: F,
+    min_up: F,
+    sum_down: F,
+    max_down: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PageHinkley<F> {
+    pub fn new(options: PageHinkleyOptions<F>) -> Self {
+        assert!(
+            options.lambda > F::zero(),
+            "lambda must be strictly positive"
+        );
+        assert!(
+            options.alpha > F::zero() && options.alpha <= F::one(),
+            "alpha must lie in (0, 1]"
+        );
+        Self {
+            options,
+            n: 0,
+            mean: F::zero(),
+            sum_up: F::zero(),
+            min_up: F::zero(),
+            sum_down: F::zero(),
+            max_down: F::zero(),
+        }
+    }
+    /// The mean of the values since the last change.
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DriftDetector<F>
+    for PageHinkley<F>
+{
+    fn update(&mut self, value: F) -> DriftStatus {
+        self.n += 1;
+        self.mean += (value - self.mean) / F::from_usize(self.n).unwrap();
+        let deviation = value - self.mean;
+        let options = &self.options;
+
+        self.sum_up = options.alpha * self.sum_up + deviation - options.delta;
+        self.min_up = self.min_up.min(self.sum_up);
+        self.sum_down = options.alpha * self.sum_down + deviation + options.delta;
+        self.max_down = self.max_down.max(self.sum_down);
+        if self.n < options.min_instances {
+            return DriftStatus::Stable;
+        }
+
+        let up = options.direction.up() && self.sum_up - self.min_up > options.lambda;
+        let down = options.direction.down() && self.max_down - self.sum_down > options.lambda;
+        if up || down {
+            self.reset();
+            DriftStatus::Drift
+        } else {
+            DriftStatus::Stable
+        }
+    }
+    fn reset(&mut self) {
+        *self = PageHinkley::new(self.options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(i: usize) -> f64 {
+        if i < 1000 {
+            10.0 + (i % 5) as f64
+        } else {
+            (i % 5) as f64
+        }
+    }
+
+    #[test]
+    fn test_direction() {
+        for (direction, detected) in [
+            (ChangeDirection::Up, false),
+            (ChangeDirection::Down, true),
+            (ChangeDirection::Both, true),
+        ] {
+            let options = PageHinkleyOptions {
+                direction,
+                ..Default::default()
+            };
+            let mut detector: PageHinkley<f64> = PageHinkley::new(options);
+            let statuses: Vec<_> = (0..2000).map(|i| detector.update(stream(i))).collect();
+            assert!(statuses[..1000].iter().all(|s| !s.is_drift()));
+            assert_eq!(statuses.iter().any(|s| s.is_drift()), detected);
+        }
+    }
+}
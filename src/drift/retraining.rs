@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::drift::{DriftDetector, DriftStatus};
+use crate::learner::Classifier;
+use num::{Float, FromPrimitive};
+
+/// Retrains a classifier when a change of its error rate is detected.
+///
+/// The errors of the classifier are monitored by a change detector, with values of 1 for a
+/// mistake and 0 otherwise. When the detector raises a warning, a background model, i.e. a fresh
+/// copy of the base classifier, starts learning alongside the current one. It is dropped if the
+/// warning turns out to be a false alarm, and replaces the current model if a change is
+/// confirmed. Detectors which never raise warnings, such as [`ADWIN`](super::adwin::ADWIN), thus
+/// replace the model by a fresh copy of the base classifier.
+///
+/// # Parameters
+///
+/// - `model`: The base classifier, which is cloned whenever a new model is needed.
+/// - `detector`: The change detector of the errors of the model.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::drift::ddm::{DDM, DDMOptions};
+/// use light_river::drift::retraining::DriftRetrainingClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let mut model = DriftRetrainingClassifier::new(tree, DDM::new(DDMOptions::default()));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..6000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     // The concept is reversed halfway through the stream
+///     model.learn_one(&observation, ClassifierTarget::from((x > 0.5) == (i < 3000)));
+/// }
+/// assert_eq!(model.n_drifts(), 1);
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(false));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DriftRetrainingClassifier<F, M, D> {
+    base: M,
+    model: M,
+    background: Option<M>,
+    detector: D,
+    n_drifts: usize,
+    _float: PhantomData<F>,
+}
+
+impl<F, M, D> DriftRetrainingClassifier<F, M, D>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+    D: DriftDetector<F>,
+{
+    pub fn new(model: M, detector: D) -> Self {
+        Self {
+            model: model.clone(),
+            base: model,
+            background: None,
+            detector,
+            n_drifts: 0,
+            _float: PhantomData,
+        }
+    }
+    /// The model which makes the predictions.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+    /// The model which learns since the last warning, if any.
+    pub fn background(&self) -> Option<&M> {
+        self.background.as_ref()
+    }
+    pub fn detector(&self) -> &D {
+        &self.detector
+    }
+    /// Number of times the model has been replaced.
+    pub fn n_drifts(&self) -> usize {
+        self.n_drifts
+    }
+}
+
+impl<F, M, D> Classifier<F> for DriftRetrainingClassifier<F, M, D>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+    D: DriftDetector<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        // Models which can't predict yet don't make mistakes
+        let correct = self.model.predict_proba(x).is_empty() || self.model.predict_one(x) == y;
+        match self
+            .detector
+            .update(if correct { F::zero() } else { F::one() })
+        {
+            DriftStatus::Stable => self.background = None,
+            DriftStatus::Warning => {
+                if self.background.is_none() {
+                    self.background = Some(self.base.clone());
+                }
+            }
+            DriftStatus::Drift => {
+                self.model = self.background.take().unwrap_or_else(|| self.base.clone());
+                self.detector.reset();
+                self.n_drifts += 1;
+            }
+        }
+        self.model.learn_one(x, y.clone());
+        if let Some(background) = self.background.as_mut() {
+            background.learn_one(x, y);
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.model.predict_proba(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::adwin::ADWIN;
+    use crate::drift::ddm::{DDMOptions, DDM};
+    use crate::testing::{accuracy, drifting_stream};
+    use crate::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+
+    fn tree() -> HoeffdingTreeClassifier<f64> {
+        HoeffdingTreeClassifier::new(Default::default())
+    }
+
+    #[test]
+    fn test_stationary_stream() {
+        let stream = drifting_stream(5000, usize::MAX);
+        let mut model = DriftRetrainingClassifier::new(tree(), DDM::new(DDMOptions::default()));
+        assert!(accuracy(&mut model, &stream, 0) > 0.95);
+        assert_eq!(model.n_drifts(), 0);
+    }
+
+    #[test]
+    fn test_drift() {
+        let stream = drifting_stream(10000, 5000);
+        let mut plain = tree();
+        let plain_accuracy = accuracy(&mut plain, &stream, 5000);
+        let mut ddm = DriftRetrainingClassifier::new(tree(), DDM::new(DDMOptions::default()));
+        let mut adwin = DriftRetrainingClassifier::new(tree(), ADWIN::new(None));
+        for retrained_accuracy in [
+            accuracy(&mut ddm, &stream, 5000),
+            accuracy(&mut adwin, &stream, 5000),
+        ] {
+            assert!(retrained_accuracy > 0.9, "{}", retrained_accuracy);
+            assert!(retrained_accuracy > plain_accuracy + 0.1);
+        }
+        assert!(ddm.n_drifts() > 0 && adwin.n_drifts() > 0);
+        assert!(adwin.background().is_none());
+    }
+}
@@ -0,0 +1,284 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::drift::adwin::ADWIN;
+use crate::ensemble::utils::{average_proba, poisson};
+use crate::learner::Classifier;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Online bagging, also known as Oza bagging.
+///
+/// In batch bagging, each model is trained on a bootstrap sample of the dataset, in which each
+/// sample appears a number of times which follows a binomial distribution. As the size of the
+/// dataset grows, this distribution tends to a Poisson distribution of mean 1, which is why each
+/// model learns from each sample `k ~ Poisson(1)` times. The predictions are the average of the
+/// class probabilities of the models.
+///
+/// # Parameters
+///
+/// - `model`: The base classifier, which is cloned `n_models` times.
+/// - `n_models`: The size of the ensemble.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::ensemble::bagging::BaggingClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let mut bagging = BaggingClassifier::new(tree, 5, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     bagging.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(bagging.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: N. C. Oza and S. Russell (2001). "Online bagging and boosting". Proceedings of the eighth
+/// international workshop on artificial intelligence and statistics, 229-236.
+#[derive(Clone, Debug)]
+pub struct BaggingClassifier<F, M> {
+    models: Vec<M>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> BaggingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(model: M, n_models: usize, seed: Option<u64>) -> Self {
+        assert!(n_models > 0, "At least one model is required");
+        Self {
+            models: vec![model; n_models],
+            rng: rng(seed),
+            _float: PhantomData,
+        }
+    }
+    pub fn models(&self) -> &[M] {
+        &self.models
+    }
+}
+
+impl<F, M> Classifier<F> for BaggingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        for model in self.models.iter_mut() {
+            for _ in 0..poisson(1.0, &mut self.rng) {
+                model.learn_one(x, y.clone());
+            }
+        }
+    }
+    /// Average of the class probabilities of the models which make a prediction.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        average_proba(
+            self.models
+                .iter()
+                .map(|model| (model.predict_proba(x), F::one())),
+        )
+    }
+}
+
+/// Leveraging bagging.
+///
+/// A variant of [`BaggingClassifier`] which increases the diversity of the ensemble, and adapts to
+/// concept drift:
+///
+/// - Each model learns from each sample `k ~ Poisson(w)` times, with `w` greater than 1, so that
+///   the models see more samples and differ more from each other.
+/// - The error of each model is monitored by an [`ADWIN`] detector. When a change is detected, the
+///   model with the highest error is replaced by a fresh copy of the base classifier.
+///
+/// # Parameters
+///
+/// - `model`: The base classifier, which is cloned `n_models` times.
+/// - `n_models`: The size of the ensemble.
+/// - `w`: The mean of the Poisson distribution of the sample weights, 6 by default.
+/// - `adwin_delta`: The confidence of the ADWIN detectors, 0.002 by default.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::ensemble::bagging::LeveragingBaggingClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let mut bagging = LeveragingBaggingClassifier::new(tree, 5, None, None, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..6000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     // The concept is reversed halfway through the stream
+///     bagging.learn_one(&observation, ClassifierTarget::from((x > 0.5) == (i < 3000)));
+/// }
+/// assert!(bagging.n_resets() > 0);
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(bagging.predict_one(&observation), ClassifierTarget::from(false));
+/// ```
+///
+/// # References
+///
+/// [^1]: A. Bifet, G. Holmes and B. Pfahringer (2010). "Leveraging bagging for evolving data
+/// streams". Joint European conference on machine learning and knowledge discovery in databases,
+/// 135-150.
+#[derive(Clone, Debug)]
+pub struct LeveragingBaggingClassifier<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M,
+> {
+    model: M,
+    models: Vec<M>,
+    detectors: Vec<ADWIN<F>>,
+    w: F,
+    adwin_delta: F,
+    n_resets: usize,
+    rng: StdRng,
+}
+
+impl<F, M> LeveragingBaggingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(
+        model: M,
+        n_models: usize,
+        w: Option<F>,
+        adwin_delta: Option<F>,
+        seed: Option<u64>,
+    ) -> Self {
+        assert!(n_models > 0, "At least one model is required");
+        let w = w.unwrap_or(F::from_f64(6.0).unwrap());
+        assert!(w > F::zero(), "w must be strictly positive");
+        let adwin_delta = adwin_delta.unwrap_or(F::from_f64(0.002).unwrap());
+        Self {
+            models: vec![model.clone(); n_models],
+            detectors: vec![ADWIN::new(Some(adwin_delta)); n_models],
+            model,
+            w,
+            adwin_delta,
+            n_resets: 0,
+            rng: rng(seed),
+        }
+    }
+    pub fn models(&self) -> &[M] {
+        &self.models
+    }
+    /// Number of models which have been replaced because of a concept drift.
+    pub fn n_resets(&self) -> usize {
+        self.n_resets
+    }
+}
+
+impl<F, M> Classifier<F> for LeveragingBaggingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let w = self.w.to_f64().unwrap();
+        let mut drift = false;
+        for (model, detector) in self.models.iter_mut().zip(self.detectors.iter_mut()) {
+            let proba = model.predict_proba(x);
+            let correct = !proba.is_empty() && model.predict_one(x) == y;
+            drift |= detector
+                .update(if correct { F::zero() } else { F::one() })
+                .is_drift();
+            for _ in 0..poisson(w, &mut self.rng) {
+                model.learn_one(x, y.clone());
+            }
+        }
+        if drift {
+            let worst = (0..self.models.len())
+                .max_by(|a, b| {
+                    let (ea, eb) = (self.detectors[*a].mean(), self.detectors[*b].mean());
+                    ea.partial_cmp(&eb).unwrap()
+                })
+                .unwrap();
+            self.models[worst] = self.model.clone();
+            self.detectors[worst] = ADWIN::new(Some(self.adwin_delta));
+            self.n_resets += 1;
+        }
+    }
+    /// Average of the class probabilities of the models which make a prediction.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        average_proba(
+            self.models
+                .iter()
+                .map(|model| (model.predict_proba(x), F::one())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{accuracy, drifting_stream};
+    use crate::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+
+    fn tree() -> HoeffdingTreeClassifier<f64> {
+        HoeffdingTreeClassifier::new(Default::default())
+    }
+
+    #[test]
+    fn test_bagging() {
+        let stream = drifting_stream(5000, usize::MAX);
+        let mut bagging = BaggingClassifier::new(tree(), 10, Some(42));
+        assert!(bagging.predict_proba(&stream[0].0).is_empty());
+        assert!(accuracy(&mut bagging, &stream, 0) > 0.95);
+        // The models have been trained on different samples
+        let x = &stream[0].0;
+        let probabilities: Vec<_> = bagging
+            .models()
+            .iter()
+            .map(|m| m.predict_proba(x))
+            .collect();
+        assert!(probabilities.iter().any(|p| *p != probabilities[0]));
+    }
+
+    #[test]
+    fn test_seed() {
+        let stream = drifting_stream(1000, usize::MAX);
+        let mut first = BaggingClassifier::new(tree(), 3, Some(7));
+        let mut second = BaggingClassifier::new(tree(), 3, Some(7));
+        accuracy(&mut first, &stream, 0);
+        accuracy(&mut second, &stream, 0);
+        for (x, _) in stream.iter().take(100) {
+            assert_eq!(first.predict_proba(x), second.predict_proba(x));
+        }
+    }
+
+    #[test]
+    fn test_leveraging_bagging_drift() {
+        let stream = drifting_stream(10000, 5000);
+        let mut leveraging = LeveragingBaggingClassifier::new(tree(), 5, None, None, Some(42));
+        let mut bagging = BaggingClassifier::new(tree(), 5, Some(42));
+        let leveraging_accuracy = accuracy(&mut leveraging, &stream, 5000);
+        let bagging_accuracy = accuracy(&mut bagging, &stream, 5000);
+        assert!(leveraging.n_resets() > 0);
+        assert!(leveraging_accuracy > 0.9, "{}", leveraging_accuracy);
+        assert!(leveraging_accuracy > bagging_accuracy + 0.1);
+    }
+}
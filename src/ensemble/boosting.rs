@@ -0,0 +1,206 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::ensemble::utils::{average_proba, poisson};
+use crate::learner::Classifier;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Online boosting, i.e. Oza and Russell's online version of AdaBoost.
+///
+/// The models are trained one after the other on each sample, `k ~ Poisson(λ)` times. `λ` starts
+/// at 1, and is increased before moving to the next model if the current one misclassifies the
+/// sample, and decreased otherwise, so that the next models focus on the samples the previous ones
+/// get wrong. This mimics the reweighting of the samples done by AdaBoost.
+///
+/// The predictions are the class probabilities of the models, weighted by `ln(1 / β)`, where
+/// `β = ε / (1 - ε)` and `ε` is the weighted error rate of the model. Models whose error rate is
+/// zero or above 0.5 are given a weight of 1.
+///
+/// # Parameters
+///
+/// - `model`: The base classifier, which is cloned `n_models` times.
+/// - `n_models`: The size of the ensemble.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::ensemble::boosting::AdaBoostClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::{
+///     HoeffdingTreeClassifier, HoeffdingTreeClassifierOptions,
+/// };
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// // Decision stumps
+/// let stump: HoeffdingTreeClassifier<f64> =
+///     HoeffdingTreeClassifier::new(HoeffdingTreeClassifierOptions {
+///         max_depth: Some(1),
+///         ..Default::default()
+///     });
+/// let mut boosting = AdaBoostClassifier::new(stump, 5, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     boosting.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(boosting.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: N. C. Oza and S. Russell (2001). "Online bagging and boosting". Proceedings of the eighth
+/// international workshop on artificial intelligence and statistics, 229-236.
+#[derive(Clone, Debug)]
+pub struct AdaBoostClassifier<F, M> {
+    models: Vec<M>,
+    // Sum of the λ of the samples each model classified correctly and wrongly
+    correct_weight: Vec<F>,
+    wrong_weight: Vec<F>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> AdaBoostClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(model: M, n_models: usize, seed: Option<u64>) -> Self {
+        assert!(n_models > 0, "At least one model is required");
+        Self {
+            models: vec![model; n_models],
+            correct_weight: vec![F::zero(); n_models],
+            wrong_weight: vec![F::zero(); n_models],
+            rng: rng(seed),
+            _float: PhantomData,
+        }
+    }
+    pub fn models(&self) -> &[M] {
+        &self.models
+    }
+    /// Weighted error rate of each model.
+    pub fn errors(&self) -> Vec<F> {
+        self.correct_weight
+            .iter()
+            .zip(self.wrong_weight.iter())
+            .map(|(c, w)| {
+                if *c + *w > F::zero() {
+                    *w / (*c + *w)
+                } else {
+                    F::zero()
+                }
+            })
+            .collect()
+    }
+}
+
+impl<F, M> Classifier<F> for AdaBoostClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let two = F::one() + F::one();
+        let mut lambda = F::one();
+        for (i, model) in self.models.iter_mut().enumerate() {
+            for _ in 0..poisson(lambda.to_f64().unwrap(), &mut self.rng) {
+                model.learn_one(x, y.clone());
+            }
+            let correct = !model.predict_proba(x).is_empty() && model.predict_one(x) == y;
+            let (c, w) = (&mut self.correct_weight[i], &mut self.wrong_weight[i]);
+            if correct {
+                *c += lambda;
+                lambda *= (*c + *w) / (two * *c);
+            } else {
+                *w += lambda;
+                lambda *= (*c + *w) / (two * *w);
+            }
+        }
+    }
+    /// Average of the class probabilities of the models, weighted by their error rates.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let half = F::from_f64(0.5).unwrap();
+        average_proba(self.models.iter().zip(self.errors()).map(|(model, error)| {
+            let weight = if error == F::zero() || error > half {
+                F::one()
+            } else {
+                ((F::one() - error) / error).ln()
+            };
+            (model.predict_proba(x), weight)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::hoeffding_tree_classifier::{
+        HoeffdingTreeClassifier, HoeffdingTreeClassifierOptions,
+    };
+
+    // Two numeric features, the class being whether they lie in the top-right or the bottom-left
+    // quadrant, which no single stump can capture
+    fn xor_stream(n: usize) -> Vec<(Observation<f64>, ClassifierTarget)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let (a, b): (f64, f64) = (rng.gen(), rng.gen());
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, ClassifierTarget::from((a > 0.5) == (b > 0.5)))
+            })
+            .collect()
+    }
+
+    fn stump() -> HoeffdingTreeClassifier<f64> {
+        HoeffdingTreeClassifier::new(HoeffdingTreeClassifierOptions {
+            max_depth: Some(1),
+            grace_period: 50,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut boosting = AdaBoostClassifier::new(stump(), 3, Some(42));
+        assert!(boosting.predict_proba(&Observation::new()).is_empty());
+        assert_eq!(boosting.errors(), vec![0.0; 3]);
+        let stream = xor_stream(2000);
+        for (x, y) in stream.iter() {
+            boosting.learn_one(x, y.clone());
+        }
+        let errors = boosting.errors();
+        // Every model has seen every sample, and none is perfect
+        assert!(errors.iter().all(|e| *e > 0.0 && *e < 1.0), "{:?}", errors);
+        assert!(boosting.correct_weight[0] + boosting.wrong_weight[0] == 2000.0);
+    }
+
+    #[test]
+    fn test_boosting_beats_single_model() {
+        let stream = xor_stream(5000);
+        let mut boosting = AdaBoostClassifier::new(stump(), 10, Some(42));
+        let mut single = stump();
+        let (mut boosting_correct, mut single_correct) = (0, 0);
+        for (i, (x, y)) in stream.iter().enumerate() {
+            if i >= 4000 {
+                boosting_correct += (boosting.predict_one(x) == *y) as usize;
+                single_correct += (single.predict_one(x) == *y) as usize;
+            }
+            boosting.learn_one(x, y.clone());
+            single.learn_one(x, y.clone());
+        }
+        assert!(
+            boosting_correct > single_correct,
+            "{} <= {}",
+            boosting_correct,
+            single_correct
+        );
+    }
+}
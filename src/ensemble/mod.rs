@@ -0,0 +1,5 @@
+pub mod bagging;
+pub mod boosting;
+pub mod stacking;
+pub mod utils;
+pub mod voting;
@@ -0,0 +1,174 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, FeatureValue, Observation};
+use crate::learner::Classifier;
+use num::{Float, FromPrimitive};
+
+/// Stacked generalization: a meta-classifier learns to combine the predictions of several
+/// classifiers, which may be of different kinds.
+///
+/// For each sample, the class probabilities of the base models are computed before they learn
+/// from it, in the spirit of progressive validation, so that the meta-classifier is trained on
+/// predictions made on unseen samples. The probability given by the `i`-th model to the class `c`
+/// becomes the feature `"{i}_{c}"` of the meta-classifier. The original features can be passed
+/// through as well.
+///
+/// # Parameters
+///
+/// - `models`: The base classifiers.
+/// - `meta_classifier`: The classifier which combines their predictions.
+/// - `passthrough`: Whether the meta-classifier also sees the original features.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::ensemble::stacking::StackingClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let models: Vec<Box<dyn Classifier<f64>>> = vec![Box::new(tree.clone()), Box::new(tree.clone())];
+/// let mut stacking = StackingClassifier::new(models, tree, false);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     stacking.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(stacking.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: D. H. Wolpert (1992). "Stacked generalization". Neural networks 5(2):241-259.
+pub struct StackingClassifier<F, M> {
+    models: Vec<Box<dyn Classifier<F>>>,
+    meta_classifier: M,
+    passthrough: bool,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> StackingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    pub fn new(models: Vec<Box<dyn Classifier<F>>>, meta_classifier: M, passthrough: bool) -> Self {
+        assert!(!models.is_empty(), "At least one model is required");
+        Self {
+            models,
+            meta_classifier,
+            passthrough,
+            _float: PhantomData,
+        }
+    }
+    pub fn models(&self) -> &[Box<dyn Classifier<F>>] {
+        &self.models
+    }
+    pub fn meta_classifier(&self) -> &M {
+        &self.meta_classifier
+    }
+    // Features of the meta-classifier.
+    fn meta_features(&self, x: &Observation<F>) -> Observation<F> {
+        let mut meta_x = if self.passthrough {
+            x.clone()
+        } else {
+            Observation::new()
+        };
+        for (i, model) in self.models.iter().enumerate() {
+            for (y, p) in model.predict_proba(x) {
+                meta_x.insert(format!("{}_{}", i, y), FeatureValue::Numeric(p));
+            }
+        }
+        meta_x
+    }
+}
+
+impl<F, M> Classifier<F> for StackingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let meta_x = self.meta_features(x);
+        self.meta_classifier.learn_one(&meta_x, y.clone());
+        for model in self.models.iter_mut() {
+            model.learn_one(x, y.clone());
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.meta_classifier.predict_proba(&self.meta_features(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+    use rand::prelude::*;
+
+    // Predicts random probabilities
+    struct Noise(std::cell::RefCell<StdRng>);
+
+    impl Classifier<f64> for Noise {
+        fn learn_one(&mut self, _x: &Observation<f64>, _y: ClassifierTarget) {}
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            let p: f64 = self.0.borrow_mut().gen();
+            ClassifierTargetProbabilities::from([
+                (ClassifierTarget::from(true), p),
+                (ClassifierTarget::from(false), 1.0 - p),
+            ])
+        }
+    }
+
+    fn tree() -> HoeffdingTreeClassifier<f64> {
+        HoeffdingTreeClassifier::new(Default::default())
+    }
+
+    fn stream(n: usize) -> Vec<(Observation<f64>, ClassifierTarget)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let (a, b): (f64, f64) = (rng.gen(), rng.gen());
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, ClassifierTarget::from(a > 0.5 && b > 0.5))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_meta_features() {
+        let models: Vec<Box<dyn Classifier<f64>>> = vec![
+            Box::new(tree()),
+            Box::new(Noise(StdRng::seed_from_u64(42).into())),
+        ];
+        let stacking = StackingClassifier::new(models, tree(), true);
+        let x = Observation::from([("a".to_string(), 0.3)]);
+        let meta_x = stacking.meta_features(&x);
+        // The tree predicts nothing yet
+        let keys: Vec<&String> = meta_x.keys().collect();
+        assert_eq!(keys, ["1_false", "1_true", "a"]);
+    }
+
+    #[test]
+    fn test_ignores_noise() {
+        let models: Vec<Box<dyn Classifier<f64>>> = vec![
+            Box::new(Noise(StdRng::seed_from_u64(42).into())),
+            Box::new(tree()),
+        ];
+        let mut stacking = StackingClassifier::new(models, tree(), false);
+        let mut correct = 0;
+        for (i, (x, y)) in stream(5000).into_iter().enumerate() {
+            if i >= 4000 && stacking.predict_one(&x) == y {
+                correct += 1;
+            }
+            stacking.learn_one(&x, y);
+        }
+        assert!(correct > 950, "{}", correct);
+    }
+}
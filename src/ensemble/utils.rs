@@ -0,0 +1,79 @@
+use std::ops::AddAssign;
+
+use crate::common::ClassifierTargetProbabilities;
+use num::Float;
+use rand::prelude::*;
+
+// Sample from a Poisson distribution of mean `lambda`, with Knuth's algorithm.
+pub(crate) fn poisson<R: Rng>(lambda: f64, rng: &mut R) -> usize {
+    let limit = (-lambda).exp();
+    let mut k = 0;
+    let mut p = rng.gen::<f64>();
+    while p > limit {
+        k += 1;
+        p *= rng.gen::<f64>();
+    }
+    k
+}
+
+// Average of class probabilities, weighted by `weights`. Members which predict nothing are left
+// out.
+pub(crate) fn average_proba<F: Float + AddAssign>(
+    probabilities: impl Iterator<Item = (ClassifierTargetProbabilities<F>, F)>,
+) -> ClassifierTargetProbabilities<F> {
+    let mut average = ClassifierTargetProbabilities::new();
+    let mut total = F::zero();
+    for (proba, weight) in probabilities {
+        if proba.is_empty() {
+            continue;
+        }
+        total += weight;
+        for (y, p) in proba {
+            *average.entry(y).or_insert(F::zero()) += weight * p;
+        }
+    }
+    if total > F::zero() {
+        for p in average.values_mut() {
+            *p = *p / total;
+        }
+    }
+    average
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ClassifierTarget;
+
+    #[test]
+    fn test_poisson() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for lambda in [1.0, 6.0] {
+            let samples: Vec<f64> = (0..10000)
+                .map(|_| poisson(lambda, &mut rng) as f64)
+                .collect();
+            let mean = samples.iter().sum::<f64>() / 10000.0;
+            let variance = samples.iter().map(|k| (k - mean).powi(2)).sum::<f64>() / 10000.0;
+            assert!((mean - lambda).abs() < 0.1 * lambda, "{}", mean);
+            assert!((variance - lambda).abs() < 0.1 * lambda, "{}", variance);
+        }
+    }
+
+    #[test]
+    fn test_average_proba() {
+        let (a, b) = (ClassifierTarget::from("a"), ClassifierTarget::from("b"));
+        let average = average_proba(
+            [
+                (ClassifierTargetProbabilities::from([(a.clone(), 1.0)]), 1.0),
+                (ClassifierTargetProbabilities::new(), 5.0),
+                (
+                    ClassifierTargetProbabilities::from([(a.clone(), 0.25), (b.clone(), 0.75)]),
+                    3.0,
+                ),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(average[&a], 0.4375);
+        assert_eq!(average[&b], 0.5625);
+    }
+}
@@ -0,0 +1,143 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::ensemble::utils::average_proba;
+use crate::learner::Classifier;
+use num::{Float, FromPrimitive};
+
+/// How a [`VotingClassifier`] combines the predictions of its models.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Voting {
+    /// Each model votes for its most likely class, and the probabilities are the shares of votes.
+    Hard,
+    /// The class probabilities of the models are averaged.
+    #[default]
+    Soft,
+}
+
+/// Combine the predictions of several classifiers, which may be of different kinds.
+///
+/// Every model learns from every sample. Models which can't make a prediction yet are left out
+/// of the vote.
+///
+/// # Parameters
+///
+/// - `models`: The classifiers to combine.
+/// - `voting`: How their predictions are combined.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::ensemble::bagging::BaggingClassifier;
+/// use light_river::ensemble::voting::{Voting, VotingClassifier};
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let models: Vec<Box<dyn Classifier<f64>>> = vec![
+///     Box::new(tree.clone()),
+///     Box::new(BaggingClassifier::new(tree, 3, Some(42))),
+/// ];
+/// let mut voting = VotingClassifier::new(models, Voting::Soft);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     voting.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(voting.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+pub struct VotingClassifier<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    models: Vec<Box<dyn Classifier<F>>>,
+    voting: Voting,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> VotingClassifier<F> {
+    pub fn new(models: Vec<Box<dyn Classifier<F>>>, voting: Voting) -> Self {
+        assert!(!models.is_empty(), "At least one model is required");
+        Self { models, voting }
+    }
+    pub fn models(&self) -> &[Box<dyn Classifier<F>>] {
+        &self.models
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for VotingClassifier<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        for model in self.models.iter_mut() {
+            model.learn_one(x, y.clone());
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let probabilities = self.models.iter().map(|model| model.predict_proba(x));
+        match self.voting {
+            Voting::Soft => average_proba(probabilities.map(|p| (p, F::one()))),
+            Voting::Hard => average_proba(probabilities.filter(|p| !p.is_empty()).map(|p| {
+                let vote = p
+                    .into_iter()
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .unwrap()
+                    .0;
+                (
+                    ClassifierTargetProbabilities::from([(vote, F::one())]),
+                    F::one(),
+                )
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Always predicts the same probabilities
+    struct Constant(ClassifierTargetProbabilities<f64>);
+
+    impl Classifier<f64> for Constant {
+        fn learn_one(&mut self, _x: &Observation<f64>, _y: ClassifierTarget) {}
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            self.0.clone()
+        }
+    }
+
+    fn models() -> Vec<Box<dyn Classifier<f64>>> {
+        let (a, b) = (ClassifierTarget::from("a"), ClassifierTarget::from("b"));
+        [(0.9, 0.1), (0.4, 0.6), (0.4, 0.6)]
+            .into_iter()
+            .map(|(pa, pb)| {
+                Box::new(Constant(ClassifierTargetProbabilities::from([
+                    (a.clone(), pa),
+                    (b.clone(), pb),
+                ]))) as Box<dyn Classifier<f64>>
+            })
+            .chain([Box::new(Constant(ClassifierTargetProbabilities::new())) as _])
+            .collect()
+    }
+
+    #[test]
+    fn test_hard_voting() {
+        let voting = VotingClassifier::new(models(), Voting::Hard);
+        let x = Observation::new();
+        assert_eq!(voting.predict_one(&x), ClassifierTarget::from("b"));
+        let probabilities = voting.predict_proba(&x);
+        assert!((probabilities[&ClassifierTarget::from("b")] - 2.0 / 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_soft_voting() {
+        let voting = VotingClassifier::new(models(), Voting::Soft);
+        let x = Observation::new();
+        assert_eq!(voting.predict_one(&x), ClassifierTarget::from("a"));
+        let probabilities = voting.predict_proba(&x);
+        assert!((probabilities[&ClassifierTarget::from("a")] - 1.7 / 3.0).abs() < 1e-10);
+    }
+}
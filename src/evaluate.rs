@@ -0,0 +1,639 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+use std::time::{Duration, Instant};
+
+use crate::common::{ClassifierOutput, ModelTarget, ModelType, Observation};
+use crate::learner::Forecaster;
+use crate::metrics::time_series::HorizonMetric;
+use crate::metrics::traits::{Metric, RegressionMetric};
+use num::{Float, FromPrimitive};
+
+/// Options of the evaluation harness.
+///
+/// - `step`: The number of samples between two checkpoints. When `None`, only the final value of
+///   the metric is reported.
+/// - `print`: Whether to print each checkpoint to the standard output.
+#[derive(Clone, Debug, Default)]
+pub struct EvaluateOptions {
+    pub step: Option<usize>,
+    pub print: bool,
+}
+
+/// Value of the metric after a given number of samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint<F> {
+    pub n_samples: usize,
+    pub value: F,
+    /// Time elapsed since the start of the evaluation.
+    pub elapsed: Duration,
+}
+
+/// When the label of a sample becomes available, relative to the moment of the sample.
+#[derive(Clone, Debug)]
+pub enum Delay {
+    /// The labels of all the samples arrive after the same duration.
+    Fixed(Duration),
+    /// The delay of each sample, in seconds, is given by one of its features.
+    Field(String),
+}
+
+// Output of a model for a single observation, as expected by the matching kind of metric.
+pub(crate) enum Prediction<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    Classification(ClassifierOutput<F>),
+    Regression(F),
+    Anomaly(F),
+    Clustering(i32),
+}
+
+pub(crate) fn predict<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    model: &ModelType<F>,
+    x: &Observation<F>,
+) -> Prediction<F> {
+    match model {
+        ModelType::Classifier(classifier) => {
+            Prediction::Classification(ClassifierOutput::Probabilities(classifier.predict_proba(x)))
+        }
+        ModelType::Regressor(regressor) => Prediction::Regression(regressor.predict_one(x)),
+        ModelType::AnomalyDetector(detector) => Prediction::Anomaly(detector.score_one(x)),
+        ModelType::Clusterer(clusterer) => Prediction::Clustering(clusterer.predict_one(x)),
+    }
+}
+
+pub(crate) fn score<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    metric: &mut Metric<F>,
+    y_pred: &Prediction<F>,
+    y_true: &ModelTarget<F>,
+) {
+    match (metric, y_pred, y_true) {
+        // An untrained classifier has no class to predict yet
+        (
+            Metric::Classification(_),
+            Prediction::Classification(ClassifierOutput::Probabilities(y_pred)),
+            ModelTarget::Classification(_),
+        ) if y_pred.is_empty() => {}
+        (
+            Metric::Classification(metric),
+            Prediction::Classification(y_pred),
+            ModelTarget::Classification(y_true),
+        ) => metric.update(y_pred, y_true, None),
+        (
+            Metric::Regression(metric),
+            Prediction::Regression(y_pred),
+            ModelTarget::Regression(y_true),
+        ) => metric.update(*y_true, *y_pred, None),
+        (Metric::Anomaly(metric), Prediction::Anomaly(score), ModelTarget::Anomaly(label)) => {
+            metric.update(*score, *label != F::zero(), None)
+        }
+        (
+            Metric::Clustring(metric),
+            Prediction::Clustering(y_pred),
+            ModelTarget::Clustering(y_true),
+        ) => metric.update(*y_true, *y_pred),
+        _ => panic!("Mismatch between ModelType, Metric and ModelTarget"),
+    }
+}
+
+// Bookkeeping of the samples whose label has been revealed, and of the checkpoints.
+pub(crate) struct Evaluation<F> {
+    options: EvaluateOptions,
+    start: Instant,
+    n_samples: usize,
+    checkpoints: Vec<Checkpoint<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Evaluation<F> {
+    pub(crate) fn new(options: EvaluateOptions) -> Self {
+        Self {
+            options,
+            start: Instant::now(),
+            n_samples: 0,
+            checkpoints: Vec::new(),
+        }
+    }
+    // Update the metric with the prediction made for `x`, and then train the model.
+    pub(crate) fn reveal(
+        &mut self,
+        model: &mut ModelType<F>,
+        metric: &mut Metric<F>,
+        x: &Observation<F>,
+        y: ModelTarget<F>,
+        y_pred: &Prediction<F>,
+    ) {
+        score(metric, y_pred, &y);
+        model.learn_one(x, y);
+        self.n_samples += 1;
+        if self
+            .options
+            .step
+            .is_some_and(|step| self.n_samples.is_multiple_of(step))
+        {
+            self.checkpoint(metric);
+        }
+    }
+    fn checkpoint(&mut self, metric: &Metric<F>) {
+        let checkpoint = Checkpoint {
+            n_samples: self.n_samples,
+            value: metric.get(),
+            elapsed: self.start.elapsed(),
+        };
+        if self.options.print {
+            println!(
+                "[{}] {:.6} ({}ms)",
+                checkpoint.n_samples,
+                checkpoint.value.to_f64().unwrap(),
+                checkpoint.elapsed.as_millis()
+            );
+        }
+        self.checkpoints.push(checkpoint);
+    }
+    pub(crate) fn finish(mut self, metric: &Metric<F>) -> Vec<Checkpoint<F>> {
+        if self.checkpoints.last().map(|c| c.n_samples) != Some(self.n_samples) {
+            self.checkpoint(metric);
+        }
+        self.checkpoints
+    }
+}
+
+/// Evaluate a model on a stream with progressive validation, also known as prequential or
+/// test-then-train evaluation.
+///
+/// Each sample is first used to make a prediction, which updates the metric, and then to train
+/// the model. The model is thus always evaluated on samples it has never seen, and all the samples
+/// are used for training, which is how models are evaluated and used in production.
+///
+/// The metric must match the kind of model: classifiers, whose probabilities are given to the
+/// metric, go with classification metrics, regressors with regression metrics, anomaly detectors
+/// with anomaly metrics and clusterers with clustering metrics. For anomaly detection, a target
+/// different from 0 marks an anomaly.
+///
+/// # Parameters
+///
+/// - `stream`: The samples along with their targets.
+/// - `model`: The model to evaluate, which is trained along the way.
+/// - `metric`: The metric to update.
+/// - `options`: How often to report the metric.
+///
+/// Returns the checkpoints of the metric, the last one being its value on the whole stream.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::learner::Regressor;
+/// use light_river::evaluate::{progressive_val_score, EvaluateOptions};
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+///
+/// // Predicts the mean of the targets seen so far
+/// struct RunningMean {
+///     n: f64,
+///     mean: f64,
+/// }
+///
+/// impl Regressor<f64> for RunningMean {
+///     fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+///         self.n += 1.0;
+///         self.mean += (y - self.mean) / self.n;
+///     }
+///     fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+///         self.mean
+///     }
+/// }
+///
+/// let stream = (0..100).map(|i| (Observation::new(), ModelTarget::Regression((i % 2) as f64)));
+/// let mut model = ModelType::Regressor(Box::new(RunningMean { n: 0.0, mean: 0.0 }));
+/// let mut metric = Metric::Regression(Box::new(MAE::new()));
+/// let options = EvaluateOptions {
+///     step: Some(25),
+///     ..Default::default()
+/// };
+///
+/// let checkpoints = progressive_val_score(stream, &mut model, &mut metric, options);
+/// assert_eq!(checkpoints.len(), 4);
+/// assert_eq!(checkpoints[3].n_samples, 100);
+/// assert_eq!(checkpoints[3].value, metric.get());
+/// ```
+///
+/// # References
+///
+/// [^1]: A. P. Dawid (1984). "Present position and potential developments: some personal views.
+/// Statistical theory: the prequential approach". Journal of the Royal Statistical Society, Series
+/// A 147(2):278-292.
+pub fn progressive_val_score<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    stream: impl IntoIterator<Item = (Observation<F>, ModelTarget<F>)>,
+    model: &mut ModelType<F>,
+    metric: &mut Metric<F>,
+    options: EvaluateOptions,
+) -> Vec<Checkpoint<F>> {
+    let mut evaluation = Evaluation::new(options);
+    for (x, y) in stream {
+        let y_pred = predict(model, &x);
+        evaluation.reveal(model, metric, &x, y, &y_pred);
+    }
+    evaluation.finish(metric)
+}
+
+// A sample whose prediction has been made and whose label hasn't been revealed yet.
+struct Pending<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    reveal_at: F,
+    x: Observation<F>,
+    y: ModelTarget<F>,
+    y_pred: Prediction<F>,
+}
+
+/// Evaluate a model on a stream whose labels arrive with a delay.
+///
+/// In production, the label of a sample is usually known some time after the prediction has been
+/// made, e.g. when a customer churns or a transaction turns out to be fraudulent. Meanwhile, the
+/// model keeps on making predictions without having learned from the previous samples. This
+/// evaluation simulates that feedback loop: the prediction of each sample is made when it arrives
+/// and kept aside, and the metric is only updated and the model only trained once the label has
+/// arrived. Labels which arrive at the same time are revealed in the order of the samples, and
+/// all the labels which haven't arrived by the end of the stream are revealed at the end.
+///
+/// With no delay, this is the same as `progressive_val_score`.
+///
+/// # Parameters
+///
+/// - `stream`: The samples along with their targets, ordered by moment.
+/// - `model`: The model to evaluate, which is trained along the way.
+/// - `metric`: The metric to update.
+/// - `moment`: The feature holding the moment at which each sample arrives, in seconds, e.g. a
+///   Unix timestamp. The feature is left in the observation given to the model.
+/// - `delay`: When the label of each sample arrives.
+/// - `options`: How often to report the metric. Checkpoints are made according to the number of
+///   labels which have arrived.
+///
+/// # Panics
+///
+/// If a sample lacks the `moment` feature or the feature holding its delay, or if they aren't
+/// numeric.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::learner::Regressor;
+/// use light_river::evaluate::{delayed_progressive_val_score, Delay, EvaluateOptions};
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+///
+/// // Predicts the last target seen
+/// struct Last(f64);
+///
+/// impl Regressor<f64> for Last {
+///     fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+///         self.0 = y;
+///     }
+///     fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+///         self.0
+///     }
+/// }
+///
+/// // One sample per second, whose target is its moment
+/// let stream = (0..10).map(|t| {
+///     let x = Observation::from([("time".to_string(), t as f64)]);
+///     (x, ModelTarget::Regression(t as f64))
+/// });
+/// let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+/// let mut metric = Metric::Regression(Box::new(MAE::new()));
+///
+/// let checkpoints = delayed_progressive_val_score(
+///     stream,
+///     &mut model,
+///     &mut metric,
+///     "time",
+///     Delay::Fixed(Duration::from_secs(2)),
+///     EvaluateOptions::default(),
+/// );
+/// // Once the first labels have arrived, each prediction lags two seconds behind
+/// assert_eq!(checkpoints[0].n_samples, 10);
+/// assert!((metric.get() - 1.7).abs() < 1e-10);
+/// ```
+pub fn delayed_progressive_val_score<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    stream: impl IntoIterator<Item = (Observation<F>, ModelTarget<F>)>,
+    model: &mut ModelType<F>,
+    metric: &mut Metric<F>,
+    moment: &str,
+    delay: Delay,
+    options: EvaluateOptions,
+) -> Vec<Checkpoint<F>> {
+    let mut evaluation = Evaluation::new(options);
+    // Sorted by the time at which the labels arrive, and then by arrival of the samples
+    let mut pending: Vec<Pending<F>> = Vec::new();
+    for (x, y) in stream {
+        let now = x
+            .get_numeric(moment)
+            .unwrap_or_else(|| panic!("Missing moment feature '{}'", moment));
+        let n_revealed = pending.partition_point(|p| p.reveal_at <= now);
+        for p in pending.drain(..n_revealed) {
+            evaluation.reveal(model, metric, &p.x, p.y, &p.y_pred);
+        }
+
+        let reveal_at = now
+            + match &delay {
+                Delay::Fixed(duration) => F::from_f64(duration.as_secs_f64()).unwrap(),
+                Delay::Field(name) => x
+                    .get_numeric(name)
+                    .unwrap_or_else(|| panic!("Missing delay feature '{}'", name)),
+            };
+        let y_pred = predict(model, &x);
+        let index = pending.partition_point(|p| p.reveal_at <= reveal_at);
+        pending.insert(
+            index,
+            Pending {
+                reveal_at,
+                x,
+                y,
+                y_pred,
+            },
+        );
+    }
+    for p in pending {
+        evaluation.reveal(model, metric, &p.x, p.y, &p.y_pred);
+    }
+    evaluation.finish(metric)
+}
+
+/// Evaluate a forecaster on a time series, step by step of the horizon.
+///
+/// The forecaster first learns from the first `grace_period` values of the series. Then, before
+/// learning from each value, it forecasts the next `horizon` values, starting with that one, and
+/// the forecasts are compared with the true values by the metric of each step. The evaluation
+/// stops when there aren't enough values left in the series to check a whole forecast.
+///
+/// The exogenous features of each value are given to the forecaster when it learns from it, and
+/// those of the values to forecast are given along with the forecast.
+///
+/// # Parameters
+///
+/// - `series`: The values of the series, along with their exogenous features.
+/// - `model`: The forecaster to evaluate, which is trained along the way.
+/// - `metric`: The metric of each step, whose horizon is that of the forecasts.
+/// - `grace_period`: The number of values learned from before the first forecast. Defaults to the
+///   horizon.
+///
+/// Returns an iterator over the values of the metric for each step, after each forecast.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::evaluate::iter_evaluate_forecasts;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::time_series::HorizonMetric;
+/// use light_river::time_series::holt_winters::{HoltWinters, Seasonality};
+///
+/// let series = (0..100).map(|t| (Observation::new(), 2.0 * t as f64));
+/// let mut model = HoltWinters::new(0.8, Some(0.8), None, 0, Seasonality::Additive);
+/// let mut metric = HorizonMetric::new(MAE::new(), 3);
+///
+/// let steps: Vec<Vec<f64>> = iter_evaluate_forecasts(series, &mut model, &mut metric, None).collect();
+/// // The last value starting a whole forecast is the third to last
+/// assert_eq!(steps.len(), 95);
+/// // The trend is learned, and errors grow with the number of steps ahead
+/// let mae = metric.get();
+/// assert!(mae[0] < mae[1] && mae[1] < mae[2]);
+/// ```
+pub fn iter_evaluate_forecasts<'a, F, M>(
+    series: impl IntoIterator<Item = (Observation<F>, F)> + 'a,
+    model: &'a mut impl Forecaster<F>,
+    metric: &'a mut HorizonMetric<F, M>,
+    grace_period: Option<usize>,
+) -> impl Iterator<Item = Vec<F>> + 'a
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + 'a,
+    M: RegressionMetric<F> + Clone + 'a,
+{
+    let horizon = metric.horizon();
+    let mut grace_period = grace_period.unwrap_or(horizon);
+    let mut series = series.into_iter();
+    // The values to forecast, the first of which is learned from after the forecast
+    let mut window: VecDeque<(Observation<F>, F)> = VecDeque::with_capacity(horizon);
+    std::iter::from_fn(move || {
+        while grace_period > 0 {
+            let (x, y) = series.next()?;
+            model.learn_one(y, Some(&x));
+            grace_period -= 1;
+        }
+        while window.len() < horizon {
+            window.push_back(series.next()?);
+        }
+        let xs: Vec<Observation<F>> = window.iter().map(|(x, _)| x.clone()).collect();
+        let y_true: Vec<F> = window.iter().map(|(_, y)| *y).collect();
+        let y_pred = model.forecast(horizon, Some(&xs));
+        metric.update(&y_true, &y_pred);
+        let (x, y) = window.pop_front()?;
+        model.learn_one(y, Some(&x));
+        Some(metric.get())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+    use crate::learner::{Classifier, Regressor};
+    use crate::metrics::accuracy::Accuracy;
+    use crate::metrics::regression::MAE;
+    use crate::metrics::report::{Average, Precision};
+    use crate::naive_bayes::gaussian::GaussianNB;
+    use std::time::Duration;
+
+    // Predicts the last target seen
+    struct Last(f64);
+
+    impl Regressor<f64> for Last {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+            self.0 = y;
+        }
+        fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+            self.0
+        }
+    }
+
+    // Predicts the class given by the sign of the "x" feature, once it has seen a sample
+    struct Sign(bool);
+
+    impl Classifier<f64> for Sign {
+        fn learn_one(&mut self, _x: &Observation<f64>, _y: ClassifierTarget) {
+            self.0 = true;
+        }
+        fn predict_proba(&self, x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            let positive = self.0 && x.get_numeric("x").unwrap() > 0.0;
+            ClassifierTargetProbabilities::from([(ClassifierTarget::from(positive), 1.0)])
+        }
+    }
+
+    fn observation(x: f64) -> Observation<f64> {
+        Observation::from([("x".to_string(), x)])
+    }
+
+    #[test]
+    fn test_test_then_train() {
+        let stream = [1.0, 3.0, 2.0, 2.0, 6.0]
+            .into_iter()
+            .map(|y| (observation(0.0), ModelTarget::Regression(y)));
+        let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let options = EvaluateOptions {
+            step: Some(2),
+            ..Default::default()
+        };
+        let checkpoints = progressive_val_score(stream, &mut model, &mut metric, options);
+        // Each target is predicted with the previous one, the first one with 0
+        let values: Vec<(usize, f64)> =
+            checkpoints.iter().map(|c| (c.n_samples, c.value)).collect();
+        assert_eq!(values, vec![(2, 1.5), (4, 1.0), (5, 1.6)]);
+    }
+
+    #[test]
+    fn test_classification() {
+        let stream = [1.0, -1.0, 2.0, -3.0].into_iter().map(|x| {
+            (
+                observation(x),
+                ModelTarget::Classification(ClassifierTarget::from(x > 0.0)),
+            )
+        });
+        let mut model = ModelType::Classifier(Box::new(Sign(false)));
+        let mut metric = Metric::Classification(Box::new(Accuracy::new()));
+        let checkpoints =
+            progressive_val_score(stream, &mut model, &mut metric, EvaluateOptions::default());
+        assert_eq!(checkpoints.len(), 1);
+        // The model is only wrong on the first sample, as it hasn't been trained yet
+        assert_eq!(checkpoints[0].value, 0.75);
+    }
+
+    #[test]
+    fn test_untrained_classifier() {
+        let stream = [1.0, -1.0, 2.0, -3.0, 4.0].into_iter().map(|x| {
+            (
+                observation(x),
+                ModelTarget::Classification(ClassifierTarget::from(x > 0.0)),
+            )
+        });
+        let mut model = ModelType::Classifier(Box::new(GaussianNB::new()));
+        let mut metric = Metric::Classification(Box::new(Precision::new(Average::Macro)));
+        let checkpoints =
+            progressive_val_score(stream, &mut model, &mut metric, EvaluateOptions::default());
+        assert_eq!(checkpoints[0].n_samples, 5);
+        // The first sample, predicted before any training, is left out of the metric
+        assert_eq!(checkpoints[0].value, 0.25);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatch")]
+    fn test_mismatch() {
+        let stream = [(observation(0.0), ModelTarget::Regression(1.0))];
+        let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+        let mut metric = Metric::Classification(Box::new(Accuracy::new()));
+        progressive_val_score(stream, &mut model, &mut metric, EvaluateOptions::default());
+    }
+
+    fn timed(t: f64, delay: f64, y: f64) -> (Observation<f64>, ModelTarget<f64>) {
+        let x = Observation::from([("t".to_string(), t), ("delay".to_string(), delay)]);
+        (x, ModelTarget::Regression(y))
+    }
+
+    #[test]
+    fn test_fixed_delay() {
+        let stream = [1.0, 3.0, 2.0, 2.0, 6.0]
+            .into_iter()
+            .enumerate()
+            .map(|(t, y)| timed(t as f64, 0.0, y));
+        let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let checkpoints = delayed_progressive_val_score(
+            stream,
+            &mut model,
+            &mut metric,
+            "t",
+            Delay::Fixed(Duration::from_millis(1500)),
+            EvaluateOptions::default(),
+        );
+        // The predictions are 0, 0, 1, 3 and 2, against 1.6 without delay
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].n_samples, 5);
+        assert_eq!(checkpoints[0].value, 2.0);
+    }
+
+    #[test]
+    fn test_no_delay() {
+        let stream = [1.0, 3.0, 2.0, 2.0, 6.0]
+            .into_iter()
+            .enumerate()
+            .map(|(t, y)| timed(t as f64, 0.0, y));
+        let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let checkpoints = delayed_progressive_val_score(
+            stream,
+            &mut model,
+            &mut metric,
+            "t",
+            Delay::Field("delay".to_string()),
+            EvaluateOptions::default(),
+        );
+        assert_eq!(checkpoints[0].value, 1.6);
+    }
+
+    #[test]
+    fn test_delay_field() {
+        let stream = [
+            timed(0.0, 5.0, 4.0),
+            timed(1.0, 0.0, 1.0),
+            timed(2.0, 0.0, 2.0),
+        ];
+        let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let options = EvaluateOptions {
+            step: Some(1),
+            ..Default::default()
+        };
+        let checkpoints = delayed_progressive_val_score(
+            stream,
+            &mut model,
+            &mut metric,
+            "t",
+            Delay::Field("delay".to_string()),
+            options,
+        );
+        // The label of the first sample is the last one to arrive
+        let values: Vec<f64> = checkpoints.iter().map(|c| c.value).collect();
+        assert_eq!(values, vec![1.0, 1.0, 2.0]);
+    }
+
+    // Forecasts the last value it learned from, at every step
+    struct Naive(f64);
+
+    impl Forecaster<f64> for Naive {
+        fn learn_one(&mut self, y: f64, _x: Option<&Observation<f64>>) {
+            self.0 = y;
+        }
+        fn forecast(&self, horizon: usize, _xs: Option<&[Observation<f64>]>) -> Vec<f64> {
+            vec![self.0; horizon]
+        }
+    }
+
+    #[test]
+    fn test_forecasts() {
+        let series = [1.0, 2.0, 4.0, 8.0, 16.0]
+            .into_iter()
+            .map(|y| (Observation::new(), y));
+        let mut model = Naive(0.0);
+        let mut metric = HorizonMetric::new(MAE::new(), 2);
+        let steps: Vec<Vec<f64>> =
+            iter_evaluate_forecasts(series, &mut model, &mut metric, Some(1)).collect();
+        // 1 is forecast instead of 2 and 4, 2 instead of 4 and 8, and 4 instead of 8 and 16
+        assert_eq!(
+            steps,
+            [vec![1.0, 3.0], vec![1.5, 4.5], vec![7.0 / 3.0, 7.0]]
+        );
+        assert_eq!(model.0, 8.0);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::FeatureKey;
+use crate::linear_model::glm::{GLMOptions, Glm};
+use crate::optim::optimizers::{Optimizer, Weights};
+use crate::sketch::hash;
+use crate::utils::standard_normal;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of the factorization machines.
+///
+/// - `n_factors`: The dimension of the latent vectors, 10 by default.
+/// - `init_std`: The standard deviation of the normal distribution the latent vectors are drawn
+///   from, 0.1 by default.
+/// - `l2_latent`: The amount of L2 regularization of the latent vectors, 0 by default.
+/// - `linear`: The regularization of the weights and the learning rate of the intercept, see
+///   [`GLMOptions`].
+/// - `seed`: Random seed of the latent vectors. A latent vector only depends on the seed and on
+///   its feature, not on the order in which the features are met.
+#[derive(Clone, Debug)]
+pub struct FMOptions<F> {
+    pub n_factors: usize,
+    pub init_std: F,
+    pub l2_latent: F,
+    pub linear: GLMOptions<F>,
+    pub seed: Option<u64>,
+}
+
+impl<F: Float + FromPrimitive> Default for FMOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_factors: 10,
+            init_std: F::from_f64(0.1).unwrap(),
+            l2_latent: F::zero(),
+            linear: GLMOptions::default(),
+            seed: None,
+        }
+    }
+}
+
+// Latent vectors of the features. Each coordinate of the latent vectors is a set of weights,
+// keyed like those of a linear model, which has its own copy of the optimizer.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Latents<F, O> {
+    // The features whose latent vector has been drawn
+    named: HashSet<String>,
+    indexed: HashSet<usize>,
+    weights: Vec<Weights<F>>,
+    optimizers: Vec<O>,
+}
+
+impl<F, O> Latents<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    fn new(optimizer: O, n_factors: usize) -> Self {
+        Self {
+            named: HashSet::new(),
+            indexed: HashSet::new(),
+            weights: vec![Weights::new(); n_factors],
+            optimizers: vec![optimizer; n_factors],
+        }
+    }
+    fn contains(&self, key: FeatureKey) -> bool {
+        match key {
+            FeatureKey::Name(name) => self.named.contains(name),
+            FeatureKey::Index(index) => self.indexed.contains(&index),
+        }
+    }
+    fn insert(&mut self, key: FeatureKey, latent: Vec<F>) {
+        for (weights, v) in self.weights.iter_mut().zip(latent) {
+            *weights.get_mut(key) = v;
+        }
+        match key {
+            FeatureKey::Name(name) => self.named.insert(name.to_string()),
+            FeatureKey::Index(index) => self.indexed.insert(index),
+        };
+    }
+    fn len(&self) -> usize {
+        self.named.len() + self.indexed.len()
+    }
+}
+
+// Linear model along with a latent vector per feature, whose dot products weigh the pairwise
+// interactions of the features. The raw score is
+//
+//   w0 + sum_i w_i x_i + sum_{i < j} <v_i, v_j> x_i x_j
+//
+// where the last sum is computed in linear time as
+// 1/2 sum_f [(sum_i v_if x_i)^2 - sum_i v_if^2 x_i^2].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Fm<F, O> {
+    pub(crate) glm: Glm<F, O>,
+    latents: Latents<F, O>,
+    n_factors: usize,
+    init_std: F,
+    l2_latent: F,
+    seed: u64,
+}
+
+impl<F, O> Fm<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    pub(crate) fn new(optimizer: O, options: FMOptions<F>) -> Self {
+        assert!(options.n_factors > 0, "n_factors must be strictly positive");
+        assert!(options.init_std >= F::zero(), "init_std must be positive");
+        Self {
+            latents: Latents::new(optimizer.clone(), options.n_factors),
+            glm: Glm::new(optimizer, options.linear),
+            n_factors: options.n_factors,
+            init_std: options.init_std,
+            l2_latent: options.l2_latent,
+            seed: options.seed.unwrap_or_else(random),
+        }
+    }
+    pub(crate) fn n_latents(&self) -> usize {
+        self.latents.len()
+    }
+    // Draw the latent vector of a feature, from a generator seeded by the feature.
+    fn draw(&self, key: FeatureKey) -> Vec<F> {
+        let mut rng = StdRng::seed_from_u64(hash(&key, self.seed));
+        (0..self.n_factors)
+            .map(|_| self.init_std * F::from_f64(standard_normal(&mut rng)).unwrap())
+            .collect()
+    }
+    // Sum of the latent vectors weighted by the feature values, along with the raw score.
+    fn forward(&self, x: &[(FeatureKey<'_>, F)]) -> (Vec<F>, F) {
+        let mut sums = vec![F::zero(); self.n_factors];
+        let mut squares = F::zero();
+        // The features which haven't been learned yet have a null latent vector
+        for (sum, weights) in sums.iter_mut().zip(self.latents.weights.iter()) {
+            for (key, value) in x.iter() {
+                let v = weights.get(*key);
+                *sum += v * *value;
+                squares += v * v * *value * *value;
+            }
+        }
+        let interactions = sums
+            .iter()
+            .fold(F::zero(), |total, sum| total + *sum * *sum);
+        let half = F::from_f64(0.5).unwrap();
+        let score = self.glm.predict(x) + half * (interactions - squares);
+        (sums, score)
+    }
+    pub(crate) fn predict(&self, x: &[(FeatureKey<'_>, F)]) -> F {
+        self.forward(x).1
+    }
+    // Take a step given the derivative of the loss with respect to the raw score, which is
+    // computed once the latent vectors of the new features are drawn.
+    pub(crate) fn learn(
+        &mut self,
+        x: &[(FeatureKey<'_>, F)],
+        loss_gradient: impl Fn(F) -> F,
+        w: F,
+    ) {
+        for (key, _) in x.iter() {
+            if !self.latents.contains(*key) {
+                let latent = self.draw(*key);
+                self.latents.insert(*key, latent);
+            }
+        }
+        let (sums, score) = self.forward(x);
+        let gradient = loss_gradient(score);
+        let latents = &mut self.latents;
+        let coordinates = latents
+            .weights
+            .iter_mut()
+            .zip(latents.optimizers.iter_mut());
+        for ((weights, optimizer), sum) in coordinates.zip(sums) {
+            let latent_gradient: Vec<_> = x
+                .iter()
+                .map(|(key, value)| {
+                    let v = weights.get(*key);
+                    let interaction = *value * (sum - v * *value);
+                    (*key, w * (gradient * interaction + self.l2_latent * v))
+                })
+                .collect();
+            optimizer.step(weights, &latent_gradient);
+        }
+        self.glm.learn(x, gradient, w);
+    }
+}
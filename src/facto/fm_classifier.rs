@@ -0,0 +1,155 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::facto::fm::{FMOptions, Fm};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba, features, observation_features};
+use crate::optim::losses::{BinaryLoss, Log};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Factorization machine for binary classification, e.g. click-through rate prediction.
+///
+/// The probability of the positive class is the logistic function of the raw score of a
+/// factorization machine, which weighs each pair of numeric features by the dot product of their
+/// latent vectors on top of a linear model. See [`FMRegressor`](super::fm_regressor::FMRegressor)
+/// for the details. Categorical features are ignored, and should be one-hot encoded beforehand.
+///
+/// The labels are booleans, `true` being the positive class. The model learns from dense
+/// observations through the [`Classifier`] trait, and from sparse vectors too through
+/// [`learn_features`](Self::learn_features).
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights and the latent vectors are updated, see
+///   [`crate::optim::optimizers`]. Each coordinate of the latent vectors has its own copy of it.
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `options`: The latent vectors and the regularization, see [`FMOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::facto::fm::FMOptions;
+/// use light_river::facto::fm_classifier::FMClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = FMOptions {
+///     seed: Some(42),
+///     ..Default::default()
+/// };
+/// let mut model = FMClassifier::new(SGD::new(0.1), Log, options);
+/// // The label is whether both features have the same sign, which isn't linearly separable
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..20000 {
+///     let (a, b) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+///     let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+///     model.learn_one(&x, ClassifierTarget::from(a * b > 0.0));
+/// }
+///
+/// let x = Observation::from([("a".to_string(), -0.7), ("b".to_string(), -0.6)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(true));
+/// let x = Observation::from([("a".to_string(), 0.7), ("b".to_string(), -0.6)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(false));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FMClassifier<F, O, L = Log> {
+    fm: Fm<F, O>,
+    loss: L,
+}
+
+impl<F, O, L> FMClassifier<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+    L: BinaryLoss<F>,
+{
+    pub fn new(optimizer: O, loss: L, options: FMOptions<F>) -> Self {
+        Self {
+            fm: Fm::new(optimizer, options),
+            loss,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.fm.glm.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.fm.glm.intercept
+    }
+    /// Number of features which have a latent vector, i.e. which have been learned.
+    pub fn n_latents(&self) -> usize {
+        self.fm.n_latents()
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        self.loss.mean(self.fm.predict(&features(x)))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool, w: F) {
+        let loss = &self.loss;
+        self.fm.learn(x, |score| loss.gradient(y, score), w);
+    }
+}
+
+impl<F, O, L> Classifier<F> for FMClassifier<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+    L: BinaryLoss<F>,
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(
+            &observation_features(x),
+            binary_label(y, "FMClassifier"),
+            F::one(),
+        );
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let p = self.loss.mean(self.fm.predict(&observation_features(x)));
+        binary_proba(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::optimizers::SGD;
+
+    #[test]
+    fn test_crossed_features() {
+        // Clicks which only happen for some pairs of ad and site, one-hot encoded as sparse
+        // vectors, the ads 0..4 and the sites 4..8
+        let options = FMOptions {
+            n_factors: 4,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let mut model = FMClassifier::new(SGD::new(0.1), Log, options);
+        let click = |ad: usize, site: usize| (ad + site).is_multiple_of(2);
+        for i in 0..20000 {
+            let (ad, site) = (i % 4, 4 + (i / 4 + i / 16) % 4);
+            let x = SparseVector::from([(ad, 1.0), (site, 1.0)]);
+            model.learn_features(&x.into(), click(ad, site), 1.0);
+        }
+        for ad in 0..4 {
+            for site in 4..8 {
+                let x = SparseVector::from([(ad, 1.0), (site, 1.0)]);
+                let p = model.predict_proba_features(&x.into());
+                assert_eq!(p > 0.5, click(ad, site), "{} {} {}", ad, site, p);
+            }
+        }
+    }
+}
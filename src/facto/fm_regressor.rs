@@ -0,0 +1,155 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureKey, Features, Observation, RegressionTarget};
+use crate::facto::fm::{FMOptions, Fm};
+use crate::learner::Regressor;
+use crate::linear_model::glm::{features, observation_features};
+use crate::optim::losses::{RegressionLoss, Squared};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Factorization machine for regression.
+///
+/// A linear model whose raw score also weighs each pair of numeric features by the dot product
+/// of their latent vectors, so that it learns the interactions of the features, including those
+/// of pairs which were rarely or never seen together. This is what linear models lack for
+/// recommendation, where the features are typically a one-hot encoded user and item.
+/// Categorical features are ignored, and should be one-hot encoded beforehand.
+///
+/// The weights and the latent vectors are updated by the optimizer after each sample, the
+/// intercept by plain gradient descent. The latent vector of a feature is drawn when the feature is
+/// first learned, and the features which haven't been learned yet don't interact. The model
+/// learns from dense observations through the [`Regressor`] trait, and from sparse vectors too
+/// through [`learn_features`](Self::learn_features).
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights and the latent vectors are updated, see
+///   [`crate::optim::optimizers`]. Each coordinate of the latent vectors has its own copy of it.
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `options`: The latent vectors and the regularization, see [`FMOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::facto::fm::FMOptions;
+/// use light_river::facto::fm_regressor::FMRegressor;
+/// use light_river::learner::Regressor;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = FMOptions {
+///     n_factors: 4,
+///     seed: Some(42),
+///     ..Default::default()
+/// };
+/// let mut model = FMRegressor::new(SGD::new(0.05), Squared, options);
+/// // The target is the product of the features, which a linear model can't fit
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..20000 {
+///     let (a, b): (f64, f64) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+///     let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+///     model.learn_one(&x, a * b);
+/// }
+///
+/// let x = Observation::from([("a".to_string(), 0.8), ("b".to_string(), -0.5)]);
+/// assert!((model.predict_one(&x) + 0.4).abs() < 0.05);
+/// ```
+///
+/// # References
+///
+/// [^1]: S. Rendle (2010). "Factorization machines". IEEE International Conference on Data
+/// Mining.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FMRegressor<F, O, L = Squared> {
+    fm: Fm<F, O>,
+    loss: L,
+}
+
+impl<F, O, L> FMRegressor<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+    L: RegressionLoss<F>,
+{
+    pub fn new(optimizer: O, loss: L, options: FMOptions<F>) -> Self {
+        Self {
+            fm: Fm::new(optimizer, options),
+            loss,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.fm.glm.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.fm.glm.intercept
+    }
+    /// Number of features which have a latent vector, i.e. which have been learned.
+    pub fn n_latents(&self) -> usize {
+        self.fm.n_latents()
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: RegressionTarget<F>, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Prediction given features which are either dense or sparse.
+    pub fn predict_features(&self, x: &Features<F>) -> RegressionTarget<F> {
+        self.loss.mean(self.fm.predict(&features(x)))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: RegressionTarget<F>, w: F) {
+        let loss = &self.loss;
+        self.fm.learn(x, |score| loss.gradient(y, score), w);
+    }
+}
+
+impl<F, O, L> Regressor<F> for FMRegressor<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+    L: RegressionLoss<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.learn(&observation_features(x), y, F::one());
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        self.loss.mean(self.fm.predict(&observation_features(x)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::optimizers::SGD;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_unseen_pairs() {
+        // Ratings of users and items one-hot encoded as sparse vectors, the users 0..10 and the
+        // items 10..20, with a rating which is the product of a user and an item factor
+        let factor = |i: usize| if i.is_multiple_of(2) { 1.0 } else { -1.0 };
+        let options = FMOptions {
+            n_factors: 2,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let mut model = FMRegressor::new(SGD::new(0.05), Squared, options);
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20000 {
+            let (user, item) = (rng.gen_range(0..10), rng.gen_range(10..20));
+            // The pair of the first user and the first item is never seen
+            if (user, item) == (0, 10) {
+                continue;
+            }
+            let x = SparseVector::from([(user, 1.0), (item, 1.0)]);
+            model.learn_features(&x.into(), factor(user) * factor(item), 1.0);
+        }
+        let x = SparseVector::from([(0, 1.0), (10, 1.0)]);
+        let prediction = model.predict_features(&x.into());
+        assert!((prediction - 1.0).abs() < 0.2, "{}", prediction);
+        assert_eq!(model.n_latents(), 20);
+    }
+}
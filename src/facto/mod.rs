@@ -0,0 +1,3 @@
+pub mod fm;
+pub mod fm_classifier;
+pub mod fm_regressor;
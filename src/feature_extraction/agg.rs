@@ -0,0 +1,257 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation, RegressionTarget};
+use crate::feature_extraction::{group, group_suffix};
+use crate::learner::SupervisedTransformer;
+use num::{Float, FromPrimitive};
+
+/// Aggregate computed by [`TargetAgg`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aggregate {
+    Mean,
+    Sum,
+    Count,
+}
+
+/// Past values which are aggregated by [`TargetAgg`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Window {
+    /// All the past values.
+    All,
+    /// The given number of most recent values.
+    Last(usize),
+    /// The values of the given number of seconds before the moment of the observation, which is
+    /// given in seconds by the feature of the given name, e.g. a Unix timestamp.
+    Seconds { field: String, duration: f64 },
+}
+
+// Past values of a group, along with their moments, the oldest first.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct History<F> {
+    values: VecDeque<(F, F)>,
+    // Sum and count of all the values, for unbounded windows
+    sum: F,
+    count: usize,
+}
+
+/// Rolling aggregate of the target, per group of observations.
+///
+/// The observations are grouped by the values of the features in `by`, and the aggregate of the
+/// past targets of the group of an observation is added to its features, as `y_{how}`, followed
+/// by `_by_{feature}` for each grouping feature joined with `_and_`, e.g. `y_mean_by_shop`. The
+/// aggregate is left out when a grouping feature is missing, and the mean is left out for groups
+/// without any past target in the window. The features of the observation are kept.
+///
+/// As the aggregates are those of past targets, an observation should be transformed before its
+/// target is learned.
+///
+/// # Parameters
+///
+/// - `by`: The features whose values define the groups. All the observations make up a single
+///   group when there are none.
+/// - `how`: The aggregate of the targets, see [`Aggregate`].
+/// - `window`: The past targets which are aggregated, see [`Window`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::feature_extraction::agg::{Aggregate, TargetAgg, Window};
+/// use light_river::learner::SupervisedTransformer;
+///
+/// let mut agg = TargetAgg::new(["shop"], Aggregate::Mean, Window::Last(2));
+/// let sale = |shop: &str| {
+///     let mut x: Observation<f64> = Observation::new();
+///     x.insert("shop", FeatureValue::Categorical(shop.to_string()));
+///     x
+/// };
+/// for (shop, y) in [("a", 10.0), ("b", 3.0), ("a", 20.0), ("a", 40.0)] {
+///     agg.learn_one(&sale(shop), y);
+/// }
+/// let x = agg.transform_one(&sale("a"));
+/// assert_eq!(x.get_numeric("y_mean_by_shop"), Some(30.0));
+/// let x = agg.transform_one(&sale("c"));
+/// assert!(!x.contains_key("y_mean_by_shop"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct TargetAgg<F> {
+    by: Vec<String>,
+    how: Aggregate,
+    window: Window,
+    name: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    groups: HashMap<Vec<String>, History<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> TargetAgg<F> {
+    pub fn new<S: Into<String>>(
+        by: impl IntoIterator<Item = S>,
+        how: Aggregate,
+        window: Window,
+    ) -> Self {
+        match &window {
+            Window::Last(n) => assert!(*n > 0, "the window must be strictly positive"),
+            Window::Seconds { duration, .. } => {
+                assert!(*duration > 0.0, "the window must be strictly positive")
+            }
+            Window::All => {}
+        }
+        let by: Vec<String> = by.into_iter().map(Into::into).collect();
+        let how_name = match how {
+            Aggregate::Mean => "mean",
+            Aggregate::Sum => "sum",
+            Aggregate::Count => "count",
+        };
+        Self {
+            name: format!("y_{}{}", how_name, group_suffix(&by)),
+            by,
+            how,
+            window,
+            groups: HashMap::new(),
+        }
+    }
+    /// The name of the feature holding the aggregate.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    // The moment of an observation, when the window is a duration.
+    fn moment(&self, x: &Observation<F>) -> Option<F> {
+        match &self.window {
+            Window::Seconds { field, .. } => Some(
+                x.get_numeric(field)
+                    .unwrap_or_else(|| panic!("Missing moment feature '{}'", field)),
+            ),
+            _ => None,
+        }
+    }
+    /// The aggregate of the past targets of the group of an observation, if it has one.
+    ///
+    /// # Panics
+    ///
+    /// If the window is a duration and the observation lacks its moment.
+    pub fn aggregate(&self, x: &Observation<F>) -> Option<F> {
+        let key = group(x, &self.by)?;
+        let (sum, count) = match (self.groups.get(&key), &self.window) {
+            (None, _) => (F::zero(), 0),
+            (Some(history), Window::All) => (history.sum, history.count),
+            (Some(history), Window::Last(_)) => (
+                history
+                    .values
+                    .iter()
+                    .fold(F::zero(), |sum, (_, y)| sum + *y),
+                history.values.len(),
+            ),
+            (Some(history), Window::Seconds { duration, .. }) => {
+                let start = self.moment(x).unwrap() - F::from_f64(*duration).unwrap();
+                history
+                    .values
+                    .iter()
+                    .filter(|(t, _)| *t > start)
+                    .fold((F::zero(), 0), |(sum, count), (_, y)| (sum + *y, count + 1))
+            }
+        };
+        match self.how {
+            Aggregate::Mean if count == 0 => None,
+            Aggregate::Mean => Some(sum / F::from_usize(count).unwrap()),
+            Aggregate::Sum => Some(sum),
+            Aggregate::Count => Some(F::from_usize(count).unwrap()),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    SupervisedTransformer<F> for TargetAgg<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        let Some(key) = group(x, &self.by) else {
+            return;
+        };
+        let moment = self.moment(x);
+        let history = self.groups.entry(key).or_insert_with(|| History {
+            values: VecDeque::new(),
+            sum: F::zero(),
+            count: 0,
+        });
+        match &self.window {
+            Window::All => {
+                history.sum += y;
+                history.count += 1;
+            }
+            Window::Last(n) => {
+                history.values.push_back((F::zero(), y));
+                if history.values.len() > *n {
+                    history.values.pop_front();
+                }
+            }
+            Window::Seconds { duration, .. } => {
+                let moment = moment.unwrap();
+                let start = moment - F::from_f64(*duration).unwrap();
+                history.values.push_back((moment, y));
+                while history.values.front().is_some_and(|(t, _)| *t <= start) {
+                    history.values.pop_front();
+                }
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let mut y = x.clone();
+        if let Some(aggregate) = self.aggregate(x) {
+            y.insert(self.name.clone(), FeatureValue::Numeric(aggregate));
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(shop: &str, day: f64, t: f64) -> Observation<f64> {
+        let mut x = Observation::from([("day".to_string(), day), ("t".to_string(), t)]);
+        x.insert("shop", FeatureValue::Categorical(shop.to_string()));
+        x
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut agg = TargetAgg::new(["shop", "day"], Aggregate::Sum, Window::All);
+        assert_eq!(agg.name(), "y_sum_by_shop_and_day");
+        for (shop, day, y) in [("a", 1.0, 1.0), ("a", 2.0, 2.0), ("a", 1.0, 4.0)] {
+            agg.learn_one(&observation(shop, day, 0.0), y);
+        }
+        assert_eq!(agg.aggregate(&observation("a", 1.0, 0.0)), Some(5.0));
+        assert_eq!(agg.aggregate(&observation("b", 1.0, 0.0)), Some(0.0));
+        // A missing grouping feature leaves the aggregate out
+        let x = Observation::from([("day".to_string(), 1.0)]);
+        assert_eq!(agg.transform_one(&x), x);
+    }
+
+    #[test]
+    fn test_time_window() {
+        let window = Window::Seconds {
+            field: "t".to_string(),
+            duration: 10.0,
+        };
+        let mut agg = TargetAgg::new(Vec::<String>::new(), Aggregate::Count, window);
+        assert_eq!(agg.name(), "y_count");
+        for t in [0.0, 5.0, 8.0, 12.0] {
+            agg.learn_one(&observation("a", 1.0, t), 1.0);
+        }
+        // Only the targets of the last 10 seconds count
+        assert_eq!(agg.aggregate(&observation("a", 1.0, 14.0)), Some(3.0));
+        assert_eq!(agg.aggregate(&observation("a", 1.0, 16.0)), Some(2.0));
+        assert_eq!(agg.groups[&vec![]].values.len(), 3);
+    }
+}
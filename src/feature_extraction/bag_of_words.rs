@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation, SparseVector};
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// Default tokenizer, which splits a text into its words of at least two alphanumeric
+/// characters.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| token.chars().count() >= 2)
+        .map(|token| token.to_string())
+        .collect()
+}
+
+// Replace the accented latin letters by their base letter.
+fn strip_accents(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    for c in text.chars() {
+        let base = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+            'æ' => "ae",
+            'Æ' => "AE",
+            'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+            'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+            'ď' | 'đ' => "d",
+            'Ď' | 'Đ' => "D",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+            'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+            'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+            'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+            'ł' | 'ľ' | 'ĺ' | 'ļ' => "l",
+            'Ł' | 'Ľ' | 'Ĺ' | 'Ļ' => "L",
+            'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+            'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "N",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+            'œ' => "oe",
+            'Œ' => "OE",
+            'ŕ' | 'ř' | 'ŗ' => "r",
+            'Ŕ' | 'Ř' | 'Ŗ' => "R",
+            'ś' | 'ŝ' | 'ş' | 'š' => "s",
+            'Ś' | 'Ŝ' | 'Ş' | 'Š' => "S",
+            'ß' => "ss",
+            'ţ' | 'ť' => "t",
+            'Ţ' | 'Ť' => "T",
+            'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+            'ý' | 'ÿ' => "y",
+            'Ý' | 'Ÿ' => "Y",
+            'ź' | 'ż' | 'ž' => "z",
+            'Ź' | 'Ż' | 'Ž' => "Z",
+            _ => {
+                stripped.push(c);
+                continue;
+            }
+        };
+        stripped.push_str(base);
+    }
+    stripped
+}
+
+/// Options of a [`BagOfWords`] or [`crate::feature_extraction::tfidf::TFIDF`] vectorizer.
+///
+/// # Parameters
+///
+/// - `on`: The feature which holds the text, as a categorical value. All the categorical
+///   features are considered as texts by default.
+/// - `lowercase`: Whether the texts are lowercased, `true` by default.
+/// - `strip_accents`: Whether the accents of the latin letters are removed, `true` by default.
+/// - `ngram_range`: The minimum and maximum numbers of consecutive tokens of the terms, `(1, 1)`
+///   by default, i.e. single words. The tokens of a term are joined by a space.
+/// - `tokenizer`: The function which splits a text into tokens, [`tokenize`] by default.
+#[derive(Clone, Debug)]
+pub struct BagOfWordsOptions {
+    pub on: Option<String>,
+    pub lowercase: bool,
+    pub strip_accents: bool,
+    pub ngram_range: (usize, usize),
+    pub tokenizer: fn(&str) -> Vec<String>,
+}
+
+impl Default for BagOfWordsOptions {
+    fn default() -> Self {
+        Self {
+            on: None,
+            lowercase: true,
+            strip_accents: true,
+            ngram_range: (1, 1),
+            tokenizer: tokenize,
+        }
+    }
+}
+
+/// Bag-of-words vectorizer, which counts the occurrences of the terms of a text.
+///
+/// A text is preprocessed, split into tokens, and its terms are the n-grams of the tokens. The
+/// counts of the terms are output either as a [`SparseVector`], e.g. for linear models, whose
+/// indices are those of the terms in a vocabulary which grows with `learn_text`, or as features
+/// named after the terms, e.g. for naive Bayes, as a [`Transformer`]. The latter is stateless,
+/// replaces the text features by the counts of their terms, and keeps the other features.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::feature_extraction::bag_of_words::{BagOfWords, BagOfWordsOptions};
+/// use light_river::learner::Transformer;
+///
+/// let mut bow = BagOfWords::new(BagOfWordsOptions {
+///     ngram_range: (1, 2),
+///     ..Default::default()
+/// });
+/// assert_eq!(bow.terms("Café au lait"), ["cafe", "au", "lait", "cafe au", "au lait"]);
+///
+/// bow.learn_text("the cat sat");
+/// bow.learn_text("the dog");
+/// let x = bow.transform_text::<f64>("The dog, the cat");
+/// assert_eq!(x.get(bow.vocabulary()["the"]), 2.0);
+/// assert_eq!(x.get(bow.vocabulary()["the cat"]), 1.0);
+/// // "dog the" is not in the vocabulary
+/// assert_eq!(x.nnz(), 5);
+///
+/// let mut review: Observation<f64> = Observation::new();
+/// review.insert("text", FeatureValue::Categorical("Great great food".to_string()));
+/// let counts = bow.transform_one(&review);
+/// assert_eq!(counts.get_numeric("great"), Some(2.0));
+/// assert_eq!(counts.get_numeric("great food"), Some(1.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BagOfWords {
+    options: BagOfWordsOptions,
+    vocabulary: HashMap<String, usize>,
+}
+
+impl BagOfWords {
+    pub fn new(options: BagOfWordsOptions) -> Self {
+        let (min_n, max_n) = options.ngram_range;
+        assert!(
+            min_n >= 1 && min_n <= max_n,
+            "ngram_range must satisfy 1 <= min <= max"
+        );
+        Self {
+            options,
+            vocabulary: HashMap::new(),
+        }
+    }
+    /// The terms of a text, in order of size and then of position.
+    pub fn terms(&self, text: &str) -> Vec<String> {
+        let mut text = text.to_string();
+        if self.options.lowercase {
+            text = text.to_lowercase();
+        }
+        if self.options.strip_accents {
+            text = strip_accents(&text);
+        }
+        let tokens = (self.options.tokenizer)(&text);
+        let (min_n, max_n) = self.options.ngram_range;
+        (min_n..=max_n)
+            .flat_map(|n| tokens.windows(n).map(|ngram| ngram.join(" ")))
+            .collect()
+    }
+    /// The number of occurrences of each term of a text.
+    pub fn counts(&self, text: &str) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for term in self.terms(text) {
+            *counts.entry(term).or_insert(0) += 1;
+        }
+        counts
+    }
+    /// Add the new terms of a text to the vocabulary.
+    pub fn learn_text(&mut self, text: &str) {
+        for term in self.terms(text) {
+            let index = self.vocabulary.len();
+            self.vocabulary.entry(term).or_insert(index);
+        }
+    }
+    /// The counts of the terms of a text, indexed by the vocabulary. The terms which aren't in
+    /// the vocabulary are ignored.
+    pub fn transform_text<F: Float>(&self, text: &str) -> SparseVector<F> {
+        self.counts(text)
+            .into_iter()
+            .filter_map(|(term, count)| {
+                let index = self.vocabulary.get(&term)?;
+                Some((*index, F::from(count).unwrap()))
+            })
+            .collect()
+    }
+    /// The index of each term which has been learned.
+    pub fn vocabulary(&self) -> &HashMap<String, usize> {
+        &self.vocabulary
+    }
+    /// Whether a feature holds a text.
+    pub(crate) fn is_text<F>(&self, name: &str, value: &FeatureValue<F>) -> bool {
+        matches!(value, FeatureValue::Categorical(_))
+            && self.options.on.as_ref().is_none_or(|on| on == name)
+    }
+    /// The counts of the terms of all the texts of an observation.
+    pub(crate) fn observation_counts<F: Float>(
+        &self,
+        x: &Observation<F>,
+    ) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for (name, value) in x.iter() {
+            if let (true, FeatureValue::Categorical(text)) = (self.is_text(name, value), value) {
+                for (term, count) in self.counts(text) {
+                    *counts.entry(term).or_insert(0) += count;
+                }
+            }
+        }
+        counts
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for BagOfWords
+{
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let counts = self.observation_counts(x);
+        x.iter()
+            .filter(|(name, value)| !self.is_text(name, value))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .chain(
+                counts
+                    .into_iter()
+                    .map(|(term, count)| (term, FeatureValue::Numeric(F::from(count).unwrap()))),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preprocessing() {
+        let bow = BagOfWords::new(BagOfWordsOptions::default());
+        assert_eq!(
+            bow.terms("Ünïcödé, naïve CAFÉ! a b2"),
+            ["unicode", "naive", "cafe", "b2"]
+        );
+        let raw = BagOfWords::new(BagOfWordsOptions {
+            lowercase: false,
+            strip_accents: false,
+            ngram_range: (2, 3),
+            tokenizer: |text| text.split(' ').map(|t| t.to_string()).collect(),
+            ..Default::default()
+        });
+        assert_eq!(raw.terms("Ça va a"), ["Ça va", "va a", "Ça va a"]);
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let mut bow = BagOfWords::new(BagOfWordsOptions::default());
+        bow.learn_text("spam spam eggs");
+        bow.learn_text("eggs ham");
+        assert_eq!(bow.vocabulary().len(), 3);
+        assert_eq!(bow.vocabulary()["spam"], 0);
+        assert_eq!(bow.vocabulary()["ham"], 2);
+        let x: SparseVector<f64> = bow.transform_text("ham spam spam bacon");
+        assert_eq!(x, SparseVector::from([(0, 2.0), (2, 1.0)]));
+    }
+
+    #[test]
+    fn test_transformer_on_field() {
+        let bow = BagOfWords::new(BagOfWordsOptions {
+            on: Some("title".to_string()),
+            ..Default::default()
+        });
+        let mut x: Observation<f64> = Observation::new();
+        x.insert("title", FeatureValue::Categorical("big news".to_string()));
+        x.insert("section", FeatureValue::Categorical("world".to_string()));
+        x.insert("length", FeatureValue::Numeric(120.0));
+        let counts = bow.transform_one(&x);
+        let names: Vec<&String> = counts.keys().collect();
+        assert_eq!(names, ["big", "length", "news", "section"]);
+    }
+}
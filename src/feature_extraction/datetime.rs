@@ -0,0 +1,181 @@
+use std::f64::consts::PI;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+use time::{OffsetDateTime, UtcOffset};
+
+/// Expands a timestamp into cyclic calendar features and elapsed times.
+///
+/// The timestamp is a numeric feature holding a Unix time, i.e. a number of seconds since
+/// 1970-01-01 00:00 UTC. It is replaced by the following features, prefixed by its name:
+///
+/// - `_hour_sin` and `_hour_cos`: The time of the day, as a point on a circle, so that 23:59 is
+///   close to 00:00.
+/// - `_weekday_sin` and `_weekday_cos`: The day of the week, from Monday, as a point on a circle.
+/// - `_month_sin` and `_month_cos`: The month of the year, as a point on a circle.
+/// - `_delta`: The number of seconds since the last timestamp learned from, if any.
+/// - `_elapsed`: The number of seconds since the first timestamp learned from, if any.
+///
+/// Observations whose timestamp is missing, categorical or out of range are left as they are.
+///
+/// # Parameters
+///
+/// - `field`: The name of the timestamp feature.
+/// - `utc_offset`: The offset from UTC of the local time the calendar features are expressed in,
+///   in seconds. Defaults to 0.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::feature_extraction::datetime::DatetimeFeatures;
+/// use light_river::learner::Transformer;
+///
+/// let mut transformer = DatetimeFeatures::new("time", None);
+/// // Monday 2024-01-01 at 00:00 UTC, and 6 hours later
+/// let x: Observation<f64> = Observation::from([("time".to_string(), 1704067200.0)]);
+/// transformer.learn_one(&x);
+/// let x = Observation::from([("time".to_string(), 1704067200.0 + 6.0 * 3600.0)]);
+/// let y = transformer.transform_one(&x);
+/// assert!(!y.contains_key("time"));
+/// assert!((y.get_numeric("time_hour_sin").unwrap() - 1.0).abs() < 1e-10);
+/// assert!((y.get_numeric("time_weekday_cos").unwrap() - 1.0).abs() < 1e-10);
+/// assert_eq!(y.get_numeric("time_delta"), Some(21600.0));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DatetimeFeatures<F> {
+    field: String,
+    utc_offset: i32,
+    first: Option<F>,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DatetimeFeatures<F> {
+    pub fn new(field: impl Into<String>, utc_offset: Option<i32>) -> Self {
+        let utc_offset = utc_offset.unwrap_or(0);
+        assert!(
+            UtcOffset::from_whole_seconds(utc_offset).is_ok(),
+            "utc_offset must be less than a day"
+        );
+        Self {
+            field: field.into(),
+            utc_offset,
+            first: None,
+            last: None,
+        }
+    }
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+    fn datetime(&self, timestamp: F) -> Option<OffsetDateTime> {
+        let datetime = OffsetDateTime::from_unix_timestamp(timestamp.floor().to_i64()?).ok()?;
+        Some(datetime.to_offset(UtcOffset::from_whole_seconds(self.utc_offset).ok()?))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for DatetimeFeatures<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        if let Some(timestamp) = x.get_numeric(&self.field) {
+            self.first.get_or_insert(timestamp);
+            self.last = Some(timestamp);
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let Some(timestamp) = x.get_numeric(&self.field) else {
+            return x.clone();
+        };
+        let Some(datetime) = self.datetime(timestamp) else {
+            return x.clone();
+        };
+        let mut y = x.clone();
+        y.remove(&self.field);
+        let mut cyclic = |name: &str, position: f64, period: f64| {
+            let angle = 2.0 * PI * position / period;
+            for (suffix, value) in [("sin", angle.sin()), ("cos", angle.cos())] {
+                y.insert(
+                    format!("{}_{}_{}", self.field, name, suffix),
+                    FeatureValue::Numeric(F::from_f64(value).unwrap()),
+                );
+            }
+        };
+        let hour = datetime.hour() as f64
+            + datetime.minute() as f64 / 60.0
+            + datetime.second() as f64 / 3600.0;
+        cyclic("hour", hour, 24.0);
+        let weekday = datetime.weekday().number_days_from_monday();
+        cyclic("weekday", weekday as f64, 7.0);
+        cyclic("month", (datetime.month() as u8 - 1) as f64, 12.0);
+        for (name, reference) in [("delta", self.last), ("elapsed", self.first)] {
+            if let Some(reference) = reference {
+                y.insert(
+                    format!("{}_{}", self.field, name),
+                    FeatureValue::Numeric(timestamp - reference),
+                );
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(y: &Observation<f64>) -> Vec<(String, f64)> {
+        y.numeric()
+            .map(|(name, v)| (name.clone(), (v * 1e6).round() / 1e6))
+            .collect()
+    }
+
+    #[test]
+    fn test_calendar() {
+        let mut transformer = DatetimeFeatures::new("t", Some(3600));
+        // Sunday 2023-07-02 at 22:30 UTC, which is Sunday 23:30 an hour ahead of UTC
+        let x = Observation::from([("t".to_string(), 1688337000.0), ("a".to_string(), 1.0)]);
+        let angle = |position: f64, period: f64| 2.0 * PI * position / period;
+        let round = |v: f64| (v * 1e6).round() / 1e6;
+        let expected = |delta: Option<f64>, elapsed: Option<f64>| {
+            let mut expected = vec![("a".to_string(), 1.0)];
+            for (name, a) in [
+                ("hour", angle(23.5, 24.0)),
+                ("month", angle(6.0, 12.0)),
+                ("weekday", angle(6.0, 7.0)),
+            ] {
+                expected.push((format!("t_{}_cos", name), round(a.cos())));
+                expected.push((format!("t_{}_sin", name), round(a.sin())));
+            }
+            if let Some(delta) = delta {
+                expected.push(("t_delta".to_string(), delta));
+            }
+            if let Some(elapsed) = elapsed {
+                expected.push(("t_elapsed".to_string(), elapsed));
+            }
+            expected.sort_by(|a, b| a.0.cmp(&b.0));
+            expected
+        };
+        assert_eq!(
+            features(&transformer.transform_one(&x)),
+            expected(None, None)
+        );
+        transformer.learn_one(&Observation::from([("t".to_string(), 1688336000.0)]));
+        transformer.learn_one(&Observation::from([("t".to_string(), 1688336900.0)]));
+        assert_eq!(
+            features(&transformer.transform_one(&x)),
+            expected(Some(100.0), Some(1000.0))
+        );
+    }
+
+    #[test]
+    fn test_no_timestamp() {
+        let transformer = DatetimeFeatures::new("t", None);
+        let mut x = Observation::from([("a".to_string(), 1.0)]);
+        assert_eq!(transformer.transform_one(&x), x);
+        x.insert("t", FeatureValue::Categorical("yesterday".to_string()));
+        assert_eq!(transformer.transform_one(&x), x);
+    }
+}
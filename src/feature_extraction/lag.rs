@@ -0,0 +1,147 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::feature_extraction::{group, group_suffix};
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// Lagged values of numeric features, per group of observations.
+///
+/// The observations are grouped by the values of the features in `by`, and the last `n_lags`
+/// values of each of the given features, among the observations of the group learned from, are
+/// added to the features of an observation. The `i`-th last value of a feature is named
+/// `{feature}_lag_{i}`, followed by `_by_{feature}` for each grouping feature joined with
+/// `_and_`, e.g. `price_lag_1_by_product`. The lags which aren't known yet are left out, as are
+/// all of them when a grouping feature is missing. Observations whose feature is missing or
+/// categorical don't count as a value of the feature. The features of the observation are kept.
+///
+/// # Parameters
+///
+/// - `features`: The numeric features to lag.
+/// - `n_lags`: The number of past values of each feature.
+/// - `by`: The features whose values define the groups. All the observations make up a single
+///   group when there are none.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::feature_extraction::lag::Lagger;
+/// use light_river::learner::Transformer;
+///
+/// let mut lagger = Lagger::new(["price"], 2, ["product"]);
+/// let sale = |product: &str, price: f64| {
+///     let mut x = Observation::from([("price".to_string(), price)]);
+///     x.insert("product", FeatureValue::Categorical(product.to_string()));
+///     x
+/// };
+/// for (product, price) in [("a", 1.0), ("b", 5.0), ("a", 2.0), ("a", 3.0)] {
+///     lagger.learn_one(&sale(product, price));
+/// }
+/// let x = lagger.transform_one(&sale("a", 4.0));
+/// assert_eq!(x.get_numeric("price_lag_1_by_product"), Some(3.0));
+/// assert_eq!(x.get_numeric("price_lag_2_by_product"), Some(2.0));
+/// let x = lagger.transform_one(&sale("b", 6.0));
+/// assert!(!x.contains_key("price_lag_2_by_product"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Lagger<F> {
+    features: Vec<String>,
+    n_lags: usize,
+    by: Vec<String>,
+    // Past values of each feature per group, the most recent first
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    lags: HashMap<Vec<String>, HashMap<String, VecDeque<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Lagger<F> {
+    pub fn new<S: Into<String>, T: Into<String>>(
+        features: impl IntoIterator<Item = S>,
+        n_lags: usize,
+        by: impl IntoIterator<Item = T>,
+    ) -> Self {
+        assert!(n_lags > 0, "n_lags must be strictly positive");
+        Self {
+            features: features.into_iter().map(Into::into).collect(),
+            n_lags,
+            by: by.into_iter().map(Into::into).collect(),
+            lags: HashMap::new(),
+        }
+    }
+    /// The past values of a feature in the group of an observation, the most recent first.
+    pub fn lags(&self, x: &Observation<F>, feature: &str) -> Option<&VecDeque<F>> {
+        self.lags.get(&group(x, &self.by)?)?.get(feature)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for Lagger<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        let Some(key) = group(x, &self.by) else {
+            return;
+        };
+        let lags = self.lags.entry(key).or_default();
+        for feature in self.features.iter() {
+            if let Some(value) = x.get_numeric(feature) {
+                let values = lags.entry(feature.clone()).or_default();
+                values.push_front(value);
+                values.truncate(self.n_lags);
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let mut y = x.clone();
+        let suffix = group_suffix(&self.by);
+        for feature in self.features.iter() {
+            let Some(values) = self.lags(x, feature) else {
+                continue;
+            };
+            for (i, value) in values.iter().enumerate() {
+                y.insert(
+                    format!("{}_lag_{}{}", feature, i + 1, suffix),
+                    FeatureValue::Numeric(*value),
+                );
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_group() {
+        let mut lagger = Lagger::new(["a", "b"], 2, Vec::<String>::new());
+        for (a, b) in [(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)] {
+            lagger.learn_one(&Observation::from([
+                ("a".to_string(), a),
+                ("b".to_string(), b),
+            ]));
+        }
+        // Only the values of "a" are learned from
+        lagger.learn_one(&Observation::from([("a".to_string(), 4.0)]));
+        let y = lagger.transform_one(&Observation::new());
+        let features: Vec<(&String, f64)> = y.numeric().collect();
+        assert_eq!(
+            features,
+            [
+                (&"a_lag_1".to_string(), 4.0),
+                (&"a_lag_2".to_string(), 3.0),
+                (&"b_lag_1".to_string(), 30.0),
+                (&"b_lag_2".to_string(), 20.0),
+            ]
+        );
+    }
+}
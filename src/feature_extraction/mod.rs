@@ -0,0 +1,29 @@
+use crate::common::{FeatureValue, Observation};
+use num::Float;
+
+pub mod agg;
+pub mod bag_of_words;
+pub mod datetime;
+pub mod lag;
+pub mod rbf_sampler;
+pub mod tfidf;
+
+// Values of the grouping features of an observation, or None if one of them is missing.
+fn group<F: Float>(x: &Observation<F>, by: &[String]) -> Option<Vec<String>> {
+    by.iter()
+        .map(|name| match x.get(name)? {
+            FeatureValue::Categorical(category) => Some(category.clone()),
+            FeatureValue::Numeric(value) => Some(value.to_f64()?.to_string()),
+            FeatureValue::Missing => None,
+        })
+        .collect()
+}
+
+// Suffix of the names of the features computed per group.
+fn group_suffix(by: &[String]) -> String {
+    if by.is_empty() {
+        String::new()
+    } else {
+        format!("_by_{}", by.join("_and_"))
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use crate::utils::{rng, standard_normal};
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of an [`RBFSampler`].
+///
+/// - `gamma`: The parameter of the RBF kernel `exp(-gamma * ||x - y||^2)`, 1 by default.
+/// - `n_components`: The number of random features, 100 by default. The more, the better the
+///   kernel is approximated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RBFSamplerOptions<F> {
+    pub gamma: F,
+    pub n_components: usize,
+}
+
+impl<F: Float + FromPrimitive> Default for RBFSamplerOptions<F> {
+    fn default() -> Self {
+        Self {
+            gamma: F::one(),
+            n_components: 100,
+        }
+    }
+}
+
+/// Random Fourier features, which approximate the RBF kernel.
+///
+/// The numeric features are projected along random directions `w`, drawn from a normal
+/// distribution of variance `2 * gamma`, and each component is `sqrt(2 / n_components) *
+/// cos(w.x + b)`, with a random offset `b` uniform in `[0, 2 * pi]`. The dot product of the
+/// components of two observations is then an unbiased estimate of their RBF kernel, so that a
+/// linear model learned on the components approximates a kernel machine, at the cost of
+/// `n_components` features.
+///
+/// The directions of a feature are drawn when it is first learned, and the features which
+/// haven't been learned yet are ignored. The output features are named `rbf_0`, `rbf_1`, and so
+/// on, and the categorical features are dropped.
+///
+/// # Parameters
+///
+/// - `options`: See [`RBFSamplerOptions`].
+/// - `seed`: Random seed of the directions and offsets.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::feature_extraction::rbf_sampler::{RBFSampler, RBFSamplerOptions};
+/// use light_river::learner::Transformer;
+///
+/// let options = RBFSamplerOptions { gamma: 0.5, n_components: 5000 };
+/// let mut sampler: RBFSampler<f64> = RBFSampler::new(options, Some(42));
+/// let x = Observation::from([("a".to_string(), 1.0), ("b".to_string(), 0.0)]);
+/// let y = Observation::from([("a".to_string(), 0.0), ("b".to_string(), 1.0)]);
+/// sampler.learn_one(&x);
+///
+/// let (zx, zy) = (sampler.transform_one(&x), sampler.transform_one(&y));
+/// assert_eq!(zx.len(), 5000);
+/// let kernel: f64 = zx.numeric().zip(zy.numeric()).map(|((_, a), (_, b))| a * b).sum();
+/// assert!((kernel - (-0.5_f64 * 2.0).exp()).abs() < 0.05);
+/// ```
+///
+/// # References
+///
+/// [^1]: A. Rahimi and B. Recht (2007). "Random features for large-scale kernel machines".
+/// Advances in neural information processing systems 20.
+#[derive(Clone, Debug)]
+pub struct RBFSampler<F> {
+    options: RBFSamplerOptions<F>,
+    // Component of the random directions along each feature
+    weights: HashMap<String, Vec<F>>,
+    offsets: Vec<F>,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RBFSampler<F> {
+    pub fn new(options: RBFSamplerOptions<F>, seed: Option<u64>) -> Self {
+        assert!(options.gamma > F::zero(), "gamma must be strictly positive");
+        assert!(
+            options.n_components > 0,
+            "n_components must be strictly positive"
+        );
+        let mut rng = rng(seed);
+        let offsets = (0..options.n_components)
+            .map(|_| F::from_f64(2.0 * PI * rng.gen::<f64>()).unwrap())
+            .collect();
+        Self {
+            options,
+            weights: HashMap::new(),
+            offsets,
+            rng,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for RBFSampler<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        let scale = (F::from_f64(2.0).unwrap() * self.options.gamma).sqrt();
+        for (name, _) in x.numeric() {
+            if !self.weights.contains_key(name) {
+                let weights = (0..self.options.n_components)
+                    .map(|_| scale * F::from_f64(standard_normal(&mut self.rng)).unwrap())
+                    .collect();
+                self.weights.insert(name.clone(), weights);
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let mut projections = self.offsets.clone();
+        for (name, value) in x.numeric() {
+            if let Some(weights) = self.weights.get(name) {
+                for (projection, w) in projections.iter_mut().zip(weights.iter()) {
+                    *projection += *w * value;
+                }
+            }
+        }
+        let n_components = F::from_usize(self.options.n_components).unwrap();
+        let scale = (F::from_f64(2.0).unwrap() / n_components).sqrt();
+        projections
+            .into_iter()
+            .enumerate()
+            .map(|(i, projection)| {
+                let component = FeatureValue::Numeric(scale * projection.cos());
+                (format!("rbf_{}", i), component)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ClassifierTarget;
+    use crate::learner::Classifier;
+    use crate::linear_model::logistic_regression::LogisticRegression;
+    use crate::optim::losses::Log;
+    use crate::optim::optimizers::SGD;
+
+    #[test]
+    fn test_unknown_features() {
+        let mut sampler: RBFSampler<f64> = RBFSampler::new(RBFSamplerOptions::default(), Some(1));
+        let x = Observation::from([("a".to_string(), 3.0)]);
+        let empty = sampler.transform_one(&Observation::new());
+        // Features which haven't been learned don't move the projections
+        assert_eq!(sampler.transform_one(&x), empty);
+        sampler.learn_one(&x);
+        assert_ne!(sampler.transform_one(&x), empty);
+    }
+
+    #[test]
+    fn test_nonlinear_boundary() {
+        // The class is whether a point lies within the unit circle, which no linear model of the
+        // raw features can tell
+        let mut sampler = RBFSampler::new(
+            RBFSamplerOptions {
+                gamma: 1.0,
+                n_components: 200,
+            },
+            Some(42),
+        );
+        let mut model = LogisticRegression::new(SGD::new(0.5), Log, Default::default());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut correct = 0;
+        for i in 0..10000 {
+            let (a, b): (f64, f64) = (rng.gen_range(-2.0..2.0), rng.gen_range(-2.0..2.0));
+            let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+            let y = ClassifierTarget::Bool(a * a + b * b < 1.0);
+            sampler.learn_one(&x);
+            let z = sampler.transform_one(&x);
+            if i >= 9000 && model.predict_one(&z) == y {
+                correct += 1;
+            }
+            model.learn_one(&z, y);
+        }
+        assert!(correct as f64 / 1000.0 > 0.9, "{}", correct);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation, SparseVector};
+use crate::feature_extraction::bag_of_words::{BagOfWords, BagOfWordsOptions};
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// TF-IDF vectorizer, which weighs the counts of the terms of a text by their rarity.
+///
+/// The count of a term in a text is multiplied by its inverse document frequency,
+/// `ln((1 + n) / (1 + df)) + 1`, where `n` is the number of texts learned so far, and `df` the
+/// number of them which contain the term. The frequencies are updated with each text, so that the
+/// weights follow the stream. The weights of a text are then normalized to a unit L2 norm,
+/// unless `normalize` is `false`.
+///
+/// As for [`BagOfWords`], whose options are shared, the weights are output either as a
+/// [`SparseVector`] indexed by the vocabulary, or as features named after the terms, as a
+/// [`Transformer`]. The latter learns the texts of each observation as a single document.
+///
+/// # Parameters
+///
+/// - `options`: The options of the underlying [`BagOfWords`].
+/// - `normalize`: Whether the weights are divided by their L2 norm.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::feature_extraction::bag_of_words::BagOfWordsOptions;
+/// use light_river::feature_extraction::tfidf::TFIDF;
+///
+/// let mut tfidf = TFIDF::new(BagOfWordsOptions::default(), true);
+/// for text in ["the cat sat", "the dog barked", "the cat purred"] {
+///     tfidf.learn_text(text);
+/// }
+/// let x = tfidf.transform_text::<f64>("the cat barked");
+/// let weight = |term: &str| x.get(tfidf.vocabulary()[term]);
+/// // The rarer the term, the higher its weight
+/// assert!(weight("barked") > weight("cat") && weight("cat") > weight("the"));
+/// assert!((x.norm_l2() - 1.0).abs() < 1e-12);
+/// ```
+///
+/// # References
+///
+/// [^1]: C. D. Manning, P. Raghavan and H. Schütze (2008). "Introduction to information
+/// retrieval", chapter 6. Cambridge University Press.
+#[derive(Clone, Debug)]
+pub struct TFIDF {
+    bow: BagOfWords,
+    normalize: bool,
+    n_documents: usize,
+    // Number of documents which contain each term
+    document_frequencies: HashMap<String, usize>,
+}
+
+impl TFIDF {
+    pub fn new(options: BagOfWordsOptions, normalize: bool) -> Self {
+        Self {
+            bow: BagOfWords::new(options),
+            normalize,
+            n_documents: 0,
+            document_frequencies: HashMap::new(),
+        }
+    }
+    /// Update the document frequencies with a text, and add its new terms to the vocabulary.
+    pub fn learn_text(&mut self, text: &str) {
+        self.bow.learn_text(text);
+        let counts = self.bow.counts(text);
+        self.learn_counts(counts);
+    }
+    /// The weights of the terms of a text, indexed by the vocabulary. The terms which aren't in
+    /// the vocabulary are ignored.
+    pub fn transform_text<F: Float>(&self, text: &str) -> SparseVector<F> {
+        let vocabulary = self.bow.vocabulary();
+        let counts = self
+            .bow
+            .counts(text)
+            .into_iter()
+            .filter(|(term, _)| vocabulary.contains_key(term))
+            .collect();
+        self.weights(counts)
+            .into_iter()
+            .map(|(term, weight)| (vocabulary[&term], weight))
+            .collect()
+    }
+    /// The inverse document frequency of a term.
+    pub fn idf<F: Float>(&self, term: &str) -> F {
+        let df = self.document_frequencies.get(term).copied().unwrap_or(0);
+        let ratio = F::from(1 + self.n_documents).unwrap() / F::from(1 + df).unwrap();
+        ratio.ln() + F::one()
+    }
+    /// The index of each term which has been learned.
+    pub fn vocabulary(&self) -> &HashMap<String, usize> {
+        self.bow.vocabulary()
+    }
+    /// Number of texts learned.
+    pub fn n_documents(&self) -> usize {
+        self.n_documents
+    }
+    fn learn_counts(&mut self, counts: HashMap<String, usize>) {
+        self.n_documents += 1;
+        for term in counts.into_keys() {
+            *self.document_frequencies.entry(term).or_insert(0) += 1;
+        }
+    }
+    fn weights<F: Float>(&self, counts: HashMap<String, usize>) -> Vec<(String, F)> {
+        let mut weights: Vec<(String, F)> = counts
+            .into_iter()
+            .map(|(term, count)| {
+                let weight = F::from(count).unwrap() * self.idf(&term);
+                (term, weight)
+            })
+            .collect();
+        if self.normalize {
+            let norm = weights
+                .iter()
+                .fold(F::zero(), |sum, (_, w)| sum + *w * *w)
+                .sqrt();
+            if norm > F::zero() {
+                for (_, w) in weights.iter_mut() {
+                    *w = *w / norm;
+                }
+            }
+        }
+        weights
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for TFIDF
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        let counts = self.bow.observation_counts(x);
+        self.learn_counts(counts);
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let weights = self.weights(self.bow.observation_counts(x));
+        x.iter()
+            .filter(|(name, value)| !self.bow.is_text(name, value))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .chain(
+                weights
+                    .into_iter()
+                    .map(|(term, weight)| (term, FeatureValue::Numeric(weight))),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idf() {
+        let mut tfidf = TFIDF::new(BagOfWordsOptions::default(), false);
+        for text in ["aa bb", "aa cc", "aa aa"] {
+            tfidf.learn_text(text);
+        }
+        assert_eq!(tfidf.n_documents(), 3);
+        assert_eq!(tfidf.idf::<f64>("aa"), 1.0);
+        assert!((tfidf.idf::<f64>("bb") - (2.0_f64.ln() + 1.0)).abs() < 1e-12);
+        let x: SparseVector<f64> = tfidf.transform_text("aa aa bb dd");
+        // Terms out of the vocabulary are ignored
+        assert_eq!(x.nnz(), 2);
+        assert_eq!(x.get(tfidf.vocabulary()["aa"]), 2.0);
+    }
+
+    #[test]
+    fn test_transformer() {
+        let mut tfidf = TFIDF::new(BagOfWordsOptions::default(), true);
+        let document = |text: &str| {
+            let mut x: Observation<f64> = Observation::new();
+            x.insert("body", FeatureValue::Categorical(text.to_string()));
+            x.insert("votes", FeatureValue::Numeric(3.0));
+            x
+        };
+        for text in ["rust is fast", "python is slow", "rust is safe"] {
+            tfidf.learn_one(&document(text));
+        }
+        // The vocabulary is only needed for sparse vectors
+        assert!(tfidf.vocabulary().is_empty());
+        let weights = tfidf.transform_one(&document("rust is new"));
+        assert_eq!(weights.get_numeric("votes"), Some(3.0));
+        let (is, rust, new) = (
+            weights.get_numeric("is").unwrap(),
+            weights.get_numeric("rust").unwrap(),
+            weights.get_numeric("new").unwrap(),
+        );
+        assert!(new > rust && rust > is);
+        assert!(((is * is + rust * rust + new * new) - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{Observation, RegressionTarget};
+use crate::learner::SupervisedTransformer;
+use crate::stats::cov::Cov;
+use crate::stats::var::Var;
+use crate::stats::{Bivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+/// How the features are scored by a [`SelectKBest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Score {
+    /// Absolute Pearson correlation between the feature and the target, learned from the
+    /// observations in which the feature is present.
+    Pearson,
+    /// Chi² statistic of the sums of the feature within each class of a binary target, e.g. for
+    /// counts of terms. The features should be positive, and are zero when absent.
+    Chi2,
+}
+
+// Running statistics of a feature, from which it is scored.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Stat<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    Pearson {
+        cov: Cov<F>,
+        var_x: Var<F>,
+        var_y: Var<F>,
+    },
+    // Sum of the feature over the negative and the positive observations
+    Chi2 {
+        sums: [F; 2],
+    },
+}
+
+/// Keeps the `k` numeric features which are the most related to the target, and removes the
+/// other features.
+///
+/// The features are scored with running statistics, and the selection is updated after each
+/// observation is learned. Features with equal scores are ranked by name, so that the selection
+/// only changes when the order of the scores does. Until `k` features have been seen, all the
+/// seen numeric features are kept.
+///
+/// Classification targets are expected as 0 or 1.
+///
+/// # Parameters
+///
+/// - `k`: The number of features to keep.
+/// - `score`: How the features are scored, see [`Score`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::feature_selection::k_best::{Score, SelectKBest};
+/// use light_river::learner::SupervisedTransformer;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut selector = SelectKBest::new(1, Score::Pearson);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..100 {
+///     let signal = i as f64;
+///     let noise = rng.gen_range(0..13) as f64;
+///     let x = Observation::from([("signal".to_string(), signal), ("noise".to_string(), noise)]);
+///     selector.learn_one(&x, 2.0 * signal + 1.0);
+/// }
+/// assert_eq!(selector.selected(), ["signal"]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectKBest<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    k: usize,
+    score: Score,
+    stats: HashMap<String, Stat<F>>,
+    // Number of negative and positive observations, for the chi² statistic
+    class_counts: [F; 2],
+    selected: HashSet<String>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> SelectKBest<F> {
+    pub fn new(k: usize, score: Score) -> Self {
+        assert!(k > 0, "k must be strictly positive");
+        Self {
+            k,
+            score,
+            stats: HashMap::new(),
+            class_counts: [F::zero(); 2],
+            selected: HashSet::new(),
+        }
+    }
+    /// The current score of a feature, if it has been seen.
+    pub fn score(&self, feature: &str) -> Option<F> {
+        let score = match self.stats.get(feature)? {
+            Stat::Pearson { cov, var_x, var_y } => {
+                let scale = (var_x.get() * var_y.get()).sqrt();
+                if scale > F::zero() {
+                    (cov.get() / scale).abs()
+                } else {
+                    F::zero()
+                }
+            }
+            Stat::Chi2 { sums } => {
+                let n = self.class_counts[0] + self.class_counts[1];
+                let total = sums[0] + sums[1];
+                let mut chi2 = F::zero();
+                for (sum, count) in sums.iter().zip(self.class_counts.iter()) {
+                    let expected = total * *count / n;
+                    if expected > F::zero() {
+                        chi2 += (*sum - expected).powi(2) / expected;
+                    }
+                }
+                chi2
+            }
+        };
+        Some(score)
+    }
+    /// The names of the selected features, in alphabetical order.
+    pub fn selected(&self) -> Vec<&String> {
+        let mut selected: Vec<&String> = self.selected.iter().collect();
+        selected.sort();
+        selected
+    }
+    fn update_selection(&mut self) {
+        let mut scores: Vec<(&String, F)> = self
+            .stats
+            .keys()
+            .map(|name| (name, self.score(name).unwrap()))
+            .collect();
+        scores.sort_by(|(a, score_a), (b, score_b)| {
+            score_b
+                .partial_cmp(score_a)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.cmp(b))
+        });
+        self.selected = scores
+            .into_iter()
+            .take(self.k)
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    SupervisedTransformer<F> for SelectKBest<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        let positive = usize::from(y > F::from_f64(0.5).unwrap());
+        if self.score == Score::Chi2 {
+            self.class_counts[positive] += F::one();
+        }
+        for (name, value) in x.numeric() {
+            let stat = self
+                .stats
+                .entry(name.clone())
+                .or_insert_with(|| match self.score {
+                    Score::Pearson => Stat::Pearson {
+                        cov: Cov::new(None),
+                        var_x: Var::new(None),
+                        var_y: Var::new(None),
+                    },
+                    Score::Chi2 => Stat::Chi2 {
+                        sums: [F::zero(); 2],
+                    },
+                });
+            match stat {
+                Stat::Pearson { cov, var_x, var_y } => {
+                    cov.update(value, y);
+                    var_x.update(value);
+                    var_y.update(y);
+                }
+                Stat::Chi2 { sums } => sums[positive] += value,
+            }
+        }
+        self.update_selection();
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .filter(|(name, _)| self.selected.contains(*name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+
+    #[test]
+    fn test_chi2() {
+        // "spam" only occurs in positive documents, "the" in all of them
+        let mut selector = SelectKBest::new(1, Score::Chi2);
+        for i in 0..40 {
+            let positive = i % 2 == 0;
+            let mut x: Observation<f64> = Observation::new();
+            x.insert("the", FeatureValue::Numeric(2.0));
+            if positive {
+                x.insert("spam", FeatureValue::Numeric(1.0));
+            }
+            x.insert("lang", FeatureValue::Categorical("en".to_string()));
+            selector.learn_one(&x, if positive { 1.0 } else { 0.0 });
+        }
+        assert_eq!(selector.score("the"), Some(0.0));
+        // 20 occurrences expected to be split evenly: (20 - 10)² / 10 + (0 - 10)² / 10
+        assert!((selector.score("spam").unwrap() - 20.0).abs() < 1e-9);
+        let mut x: Observation<f64> = Observation::new();
+        x.insert("the", FeatureValue::Numeric(1.0));
+        x.insert("spam", FeatureValue::Numeric(3.0));
+        x.insert("lang", FeatureValue::Categorical("en".to_string()));
+        let y = selector.transform_one(&x);
+        let names: Vec<&String> = y.keys().collect();
+        assert_eq!(names, ["spam"]);
+    }
+
+    #[test]
+    fn test_ties_and_warmup() {
+        let mut selector: SelectKBest<f64> = SelectKBest::new(2, Score::Pearson);
+        // Constant targets make every score zero, which ranks the features by name
+        let x = Observation::from([
+            ("c".to_string(), 1.0),
+            ("a".to_string(), 2.0),
+            ("b".to_string(), 3.0),
+        ]);
+        selector.learn_one(&Observation::from([("c".to_string(), 1.0)]), 1.0);
+        assert_eq!(selector.selected(), ["c"]);
+        selector.learn_one(&x, 1.0);
+        assert_eq!(selector.selected(), ["a", "b"]);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod k_best;
+pub mod variance;
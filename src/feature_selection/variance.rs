@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Removes the numeric features whose running variance is too low, e.g. constant features.
+///
+/// The variance of each numeric feature is learned from the observations in which it is present.
+/// A feature is kept as long as it has been seen less than `min_samples` times, as its variance
+/// is not known yet, and afterwards only while its variance is above the threshold. The other
+/// features are left as they are.
+///
+/// # Parameters
+///
+/// - `threshold`: The variance at or below which a feature is removed, 0 by default.
+/// - `min_samples`: The number of values of a feature needed before it can be removed, 2 by
+///   default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::feature_selection::variance::VarianceThreshold;
+/// use light_river::learner::Transformer;
+///
+/// let mut selector = VarianceThreshold::new(None, None);
+/// for i in 0..10 {
+///     let x = Observation::from([("a".to_string(), i as f64), ("b".to_string(), 1.0)]);
+///     selector.learn_one(&x);
+/// }
+/// let x = Observation::from([("a".to_string(), 3.0), ("b".to_string(), 1.0)]);
+/// let y = selector.transform_one(&x);
+/// let names: Vec<&String> = y.keys().collect();
+/// assert_eq!(names, ["a"]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VarianceThreshold<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    threshold: F,
+    min_samples: usize,
+    variances: HashMap<String, Var<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    VarianceThreshold<F>
+{
+    pub fn new(threshold: Option<F>, min_samples: Option<usize>) -> Self {
+        let threshold = threshold.unwrap_or(F::zero());
+        assert!(threshold >= F::zero(), "threshold must be positive");
+        Self {
+            threshold,
+            min_samples: min_samples.unwrap_or(2),
+            variances: HashMap::new(),
+        }
+    }
+    /// The running variance of a feature, if it has been seen.
+    pub fn variance(&self, feature: &str) -> Option<F> {
+        self.variances.get(feature).map(|var| var.get())
+    }
+    /// Whether a feature is currently kept.
+    pub fn is_selected(&self, feature: &str) -> bool {
+        match self.variances.get(feature) {
+            Some(var) if var.n() >= F::from_usize(self.min_samples).unwrap() => {
+                var.get() > self.threshold
+            }
+            _ => true,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for VarianceThreshold<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        for (name, value) in x.numeric() {
+            self.variances
+                .entry(name.clone())
+                .or_insert_with(|| Var::new(None))
+                .update(value);
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .filter(|(name, value)| {
+                !matches!(value, FeatureValue::Numeric(_)) || self.is_selected(name)
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_and_min_samples() {
+        let mut selector = VarianceThreshold::new(Some(0.5), Some(5));
+        let mut x: Observation<f64> = Observation::new();
+        x.insert("city", FeatureValue::Categorical("Rome".to_string()));
+        for i in 0..4 {
+            x.insert("low", FeatureValue::Numeric((i % 2) as f64 * 0.1));
+            x.insert("high", FeatureValue::Numeric(i as f64));
+            selector.learn_one(&x);
+        }
+        // Too few values to remove any feature yet
+        assert_eq!(selector.transform_one(&x).len(), 3);
+        selector.learn_one(&x);
+        let y = selector.transform_one(&x);
+        let names: Vec<&String> = y.keys().collect();
+        assert_eq!(names, ["city", "high"]);
+        assert!(selector.variance("low").unwrap() < 0.5);
+        // Unknown features are kept
+        assert!(selector.is_selected("other"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod mondrian_forest_classifier;
+pub mod mondrian_forest_regressor;
+pub mod mondrian_tree;
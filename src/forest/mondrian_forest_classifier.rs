@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::forest::mondrian_tree::MondrianTree;
+use crate::learner::Classifier;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of a [`MondrianForestClassifier`].
+///
+/// - `n_estimators`: The number of trees, 10 by default.
+/// - `step`: The learning rate of the exponential weights of the nodes, 1 by default.
+/// - `use_aggregation`: Whether the predictions of a tree aggregate those of all the prunings of
+///   the tree, which is the default, or are those of the leaves.
+/// - `dirichlet`: The parameter of the Dirichlet prior of the class distributions of the nodes,
+///   0.5 by default, which gives the Krichevsky-Trofimov estimator.
+/// - `max_nodes`: The number of nodes beyond which a tree stops growing. Unlimited when `None`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MondrianForestClassifierOptions<F> {
+    pub n_estimators: usize,
+    pub step: F,
+    pub use_aggregation: bool,
+    pub dirichlet: F,
+    pub max_nodes: Option<usize>,
+}
+
+impl<F: Float + FromPrimitive> Default for MondrianForestClassifierOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_estimators: 10,
+            step: F::one(),
+            use_aggregation: true,
+            dirichlet: F::from_f64(0.5).unwrap(),
+            max_nodes: None,
+        }
+    }
+}
+
+// Class counts of a node.
+#[derive(Clone, Debug)]
+struct ClassCounts<F> {
+    counts: HashMap<ClassifierTarget, F>,
+    total: F,
+}
+
+impl<F: Float> Default for ClassCounts<F> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+            total: F::zero(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign> ClassCounts<F> {
+    fn proba(&self, class: &ClassifierTarget, dirichlet: F, n_classes: usize) -> F {
+        let count = self.counts.get(class).copied().unwrap_or(F::zero());
+        (count + dirichlet) / (self.total + dirichlet * F::from_usize(n_classes).unwrap())
+    }
+    fn update(&mut self, class: &ClassifierTarget) {
+        *self.counts.entry(class.clone()).or_insert(F::zero()) += F::one();
+        self.total += F::one();
+    }
+}
+
+/// Aggregated Mondrian forest classifier, also known as AMF.
+///
+/// Each tree is a Mondrian tree whose lifetime is infinite: a leaf is split each time a sample
+/// falls outside the box of the samples it has seen, at a feature and threshold drawn from the
+/// Mondrian process. The nodes keep the class counts of their samples, from which they predict
+/// with a Dirichlet prior. Instead of using the leaves alone, the predictions of a tree are the
+/// average over all its prunings, weighted by their past performance. This average is computed
+/// exactly in time proportional to the depth of the tree, thanks to the exponential weights kept
+/// in the nodes. The predictions of the forest are the average of those of the trees.
+///
+/// The trees don't need any warm-up nor split criterion, but they grow with the number of
+/// distinct samples. Their size can be bounded with `options.max_nodes`, in which case the
+/// leaves keep updating their statistics once the bound is reached. Only the numeric features
+/// are used.
+///
+/// # Parameters
+///
+/// - `options`: See [`MondrianForestClassifierOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::forest::mondrian_forest_classifier::MondrianForestClassifier;
+/// use light_river::learner::Classifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: MondrianForestClassifier<f64> =
+///     MondrianForestClassifier::new(Default::default(), Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..500 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(true));
+/// assert!(model.predict_proba(&observation)[&ClassifierTarget::from(true)] > 0.8);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Mourtada, S. Gaïffas and E. Scornet (2021). "AMF: Aggregated Mondrian forests for
+/// online learning". Journal of the Royal Statistical Society Series B 83(3):505-533.
+///
+/// [^2]: B. Lakshminarayanan, D. M. Roy and Y. W. Teh (2014). "Mondrian forests: Efficient online
+/// random forests". Advances in neural information processing systems 27.
+#[derive(Clone, Debug)]
+pub struct MondrianForestClassifier<F> {
+    options: MondrianForestClassifierOptions<F>,
+    trees: Vec<MondrianTree<F, ClassCounts<F>>>,
+    classes: Vec<ClassifierTarget>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    MondrianForestClassifier<F>
+{
+    pub fn new(options: MondrianForestClassifierOptions<F>, seed: Option<u64>) -> Self {
+        assert!(
+            options.n_estimators > 0,
+            "n_estimators must be strictly positive"
+        );
+        assert!(options.step > F::zero(), "step must be strictly positive");
+        assert!(
+            options.dirichlet > F::zero(),
+            "dirichlet must be strictly positive"
+        );
+        let mut rng = rng(seed);
+        let trees = (0..options.n_estimators)
+            .map(|_| MondrianTree::new(options.step, options.max_nodes, rng.gen()))
+            .collect();
+        Self {
+            options,
+            trees,
+            classes: Vec::new(),
+        }
+    }
+    pub fn options(&self) -> &MondrianForestClassifierOptions<F> {
+        &self.options
+    }
+    /// Classes seen so far, in order of appearance.
+    pub fn classes(&self) -> &[ClassifierTarget] {
+        &self.classes
+    }
+    /// Number of nodes of each tree.
+    pub fn n_nodes(&self) -> Vec<usize> {
+        self.trees.iter().map(|tree| tree.n_nodes()).collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for MondrianForestClassifier<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        if !self.classes.contains(&y) {
+            self.classes.push(y.clone());
+        }
+        let (dirichlet, n_classes) = (self.options.dirichlet, self.classes.len());
+        for tree in self.trees.iter_mut() {
+            tree.learn(
+                x,
+                |counts| -counts.proba(&y, dirichlet, n_classes).ln(),
+                |counts| counts.update(&y),
+            );
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let (dirichlet, n_classes) = (self.options.dirichlet, self.classes.len());
+        let predict = |counts: &ClassCounts<F>| -> Vec<F> {
+            self.classes
+                .iter()
+                .map(|class| counts.proba(class, dirichlet, n_classes))
+                .collect()
+        };
+        let mix = |node: Vec<F>, below: Vec<F>, weight: F| -> Vec<F> {
+            node.iter()
+                .zip(below)
+                .map(|(a, b)| weight * *a + (F::one() - weight) * b)
+                .collect()
+        };
+        let mut proba = vec![F::zero(); n_classes];
+        let mut n_trees = F::zero();
+        for tree in self.trees.iter() {
+            if let Some(tree_proba) = tree.predict(x, self.options.use_aggregation, predict, mix) {
+                for (p, q) in proba.iter_mut().zip(tree_proba) {
+                    *p += q;
+                }
+                n_trees += F::one();
+            }
+        }
+        if n_trees == F::zero() {
+            return ClassifierTargetProbabilities::new();
+        }
+        self.classes
+            .iter()
+            .cloned()
+            .zip(proba.into_iter().map(|p| p / n_trees))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    // Two interleaved half-moons, with a deterministic sequence of angles
+    fn sample(i: usize) -> (Observation<f64>, ClassifierTarget) {
+        let angle = scrambled(i, 1000) as f64 / 1000.0 * std::f64::consts::PI;
+        let (x, y, label) = if i.is_multiple_of(2) {
+            (angle.cos(), angle.sin(), 0)
+        } else {
+            (1.0 - angle.cos(), 0.5 - angle.sin(), 1)
+        };
+        let obs = Observation::from([("x".to_string(), x), ("y".to_string(), y)]);
+        (obs, ClassifierTarget::from(label))
+    }
+
+    fn accuracy(options: MondrianForestClassifierOptions<f64>) -> f64 {
+        let mut model = MondrianForestClassifier::new(options, Some(42));
+        let mut correct = 0;
+        for i in 0..2000 {
+            let (x, y) = sample(i);
+            if i >= 1000 && model.predict_one(&x) == y {
+                correct += 1;
+            }
+            model.learn_one(&x, y);
+        }
+        correct as f64 / 1000.0
+    }
+
+    #[test]
+    fn test_moons() {
+        assert!(accuracy(Default::default()) > 0.95);
+        let options = MondrianForestClassifierOptions {
+            use_aggregation: false,
+            ..Default::default()
+        };
+        assert!(accuracy(options) > 0.9);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let mut model: MondrianForestClassifier<f64> =
+            MondrianForestClassifier::new(Default::default(), Some(42));
+        assert!(model.predict_proba(&sample(0).0).is_empty());
+        for i in 0..200 {
+            let (x, y) = sample(i);
+            model.learn_one(&x, y);
+        }
+        let proba = model.predict_proba(&sample(0).0);
+        assert_eq!(proba.len(), 2);
+        assert!((proba.values().sum::<f64>() - 1.0).abs() < 1e-10);
+        assert_eq!(model.classes().len(), 2);
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let options = MondrianForestClassifierOptions {
+            max_nodes: Some(31),
+            ..Default::default()
+        };
+        let mut model = MondrianForestClassifier::new(options, Some(42));
+        for i in 0..1000 {
+            let (x, y) = sample(i);
+            model.learn_one(&x, y);
+        }
+        assert!(model.n_nodes().iter().all(|n| *n <= 31));
+        assert!(accuracy(options) > 0.8);
+    }
+}
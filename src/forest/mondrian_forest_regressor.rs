@@ -0,0 +1,225 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{Observation, RegressionTarget};
+use crate::forest::mondrian_tree::MondrianTree;
+use crate::learner::Regressor;
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of a [`MondrianForestRegressor`].
+///
+/// - `n_estimators`: The number of trees, 10 by default.
+/// - `step`: The learning rate of the exponential weights of the nodes, 1 by default. As the
+///   weights depend on the squared errors of the nodes, it should be lowered when the targets have
+///   a large scale.
+/// - `use_aggregation`: Whether the predictions of a tree aggregate those of all the prunings of
+///   the tree, which is the default, or are those of the leaves.
+/// - `max_nodes`: The number of nodes beyond which a tree stops growing. Unlimited when `None`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MondrianForestRegressorOptions<F> {
+    pub n_estimators: usize,
+    pub step: F,
+    pub use_aggregation: bool,
+    pub max_nodes: Option<usize>,
+}
+
+impl<F: Float + FromPrimitive> Default for MondrianForestRegressorOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_estimators: 10,
+            step: F::one(),
+            use_aggregation: true,
+            max_nodes: None,
+        }
+    }
+}
+
+// Running mean and population variance of the targets of a node.
+#[derive(Clone, Debug)]
+struct Moments<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(Var<F>);
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for Moments<F>
+{
+    fn default() -> Self {
+        Self(Var::new(Some(0)))
+    }
+}
+
+/// Aggregated Mondrian forest regressor.
+///
+/// The regression counterpart of the
+/// [`MondrianForestClassifier`](crate::forest::mondrian_forest_classifier::MondrianForestClassifier):
+/// the trees grow in the same way, but their nodes keep the mean and the variance of the targets
+/// of their samples, and their weights depend on the squared errors of their means. The
+/// prediction of a tree is the average of the means of the nodes on the path of the sample,
+/// weighted as in the classifier.
+///
+/// Each tree, and the forest as a whole, is a mixture of the distributions of the targets of the
+/// nodes, whose variance is given by [`predict_mean_variance`](Self::predict_mean_variance). It
+/// accounts both for the spread of the targets within the nodes and for the disagreement of the
+/// nodes and the trees.
+///
+/// # Parameters
+///
+/// - `options`: See [`MondrianForestRegressorOptions`].
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::forest::mondrian_forest_regressor::MondrianForestRegressor;
+/// use light_river::learner::Regressor;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: MondrianForestRegressor<f64> =
+///     MondrianForestRegressor::new(Default::default(), Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, if x > 0.5 { 2.0 } else { 0.0 });
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.8)]);
+/// assert!((model.predict_one(&observation) - 2.0).abs() < 0.1);
+/// let (_, variance) = model.predict_mean_variance(&observation);
+/// assert!(variance < 0.1);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Mourtada, S. Gaïffas and E. Scornet (2021). "AMF: Aggregated Mondrian forests for
+/// online learning". Journal of the Royal Statistical Society Series B 83(3):505-533.
+#[derive(Clone, Debug)]
+pub struct MondrianForestRegressor<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    options: MondrianForestRegressorOptions<F>,
+    trees: Vec<MondrianTree<F, Moments<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    MondrianForestRegressor<F>
+{
+    pub fn new(options: MondrianForestRegressorOptions<F>, seed: Option<u64>) -> Self {
+        assert!(
+            options.n_estimators > 0,
+            "n_estimators must be strictly positive"
+        );
+        assert!(options.step > F::zero(), "step must be strictly positive");
+        let mut rng = rng(seed);
+        let trees = (0..options.n_estimators)
+            .map(|_| MondrianTree::new(options.step, options.max_nodes, rng.gen()))
+            .collect();
+        Self { options, trees }
+    }
+    pub fn options(&self) -> &MondrianForestRegressorOptions<F> {
+        &self.options
+    }
+    /// Number of nodes of each tree.
+    pub fn n_nodes(&self) -> Vec<usize> {
+        self.trees.iter().map(|tree| tree.n_nodes()).collect()
+    }
+    /// Mean and variance of the predictive distribution of the forest. Both are zero until a
+    /// sample has been seen.
+    pub fn predict_mean_variance(&self, x: &Observation<F>) -> (F, F) {
+        // The moments of a mixture are the mixtures of the moments
+        let predict = |Moments(var): &Moments<F>| (var.mean(), var.get() + var.mean() * var.mean());
+        let mix = |node: (F, F), below: (F, F), weight: F| {
+            (
+                weight * node.0 + (F::one() - weight) * below.0,
+                weight * node.1 + (F::one() - weight) * below.1,
+            )
+        };
+        let mut n_trees = F::zero();
+        let (mut mean, mut second_moment) = (F::zero(), F::zero());
+        for tree in self.trees.iter() {
+            if let Some((m, s)) = tree.predict(x, self.options.use_aggregation, predict, mix) {
+                mean += m;
+                second_moment += s;
+                n_trees += F::one();
+            }
+        }
+        if n_trees == F::zero() {
+            return (F::zero(), F::zero());
+        }
+        mean /= n_trees;
+        second_moment /= n_trees;
+        (mean, (second_moment - mean * mean).max(F::zero()))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Regressor<F>
+    for MondrianForestRegressor<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        let half = F::from_f64(0.5).unwrap();
+        for tree in self.trees.iter_mut() {
+            tree.learn(
+                x,
+                |Moments(var)| half * (y - var.mean()).powi(2),
+                |Moments(var)| var.update(y),
+            );
+        }
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        self.predict_mean_variance(x).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{scrambled, scrambled_other};
+
+    fn sample(i: usize) -> (Observation<f64>, f64) {
+        let x = scrambled(i, 1000) as f64 / 1000.0;
+        let z = scrambled_other(i, 997) as f64 / 997.0;
+        let obs = Observation::from([("x".to_string(), x), ("z".to_string(), z)]);
+        (obs, (6.0 * x).sin() + x * z)
+    }
+
+    #[test]
+    fn test_smooth_function() {
+        let mut model = MondrianForestRegressor::new(Default::default(), Some(42));
+        let mut error = 0.0;
+        for i in 0..3000 {
+            let (x, y) = sample(i);
+            if i >= 2000 {
+                error += (model.predict_one(&x) - y).abs();
+            }
+            model.learn_one(&x, y);
+        }
+        assert!(error / 1000.0 < 0.1, "{}", error / 1000.0);
+    }
+
+    #[test]
+    fn test_variance() {
+        let mut model: MondrianForestRegressor<f64> =
+            MondrianForestRegressor::new(Default::default(), Some(42));
+        assert_eq!(model.predict_mean_variance(&sample(0).0), (0.0, 0.0));
+        // Noisy targets on the left, constant ones on the right
+        for i in 0..2000 {
+            let x = scrambled(i, 1000) as f64 / 1000.0;
+            let y = if x < 0.5 { (i % 5) as f64 } else { 1.0 };
+            model.learn_one(&Observation::from([("x".to_string(), x)]), y);
+        }
+        let (left_mean, left_var) =
+            model.predict_mean_variance(&Observation::from([("x".to_string(), 0.25)]));
+        let (right_mean, right_var) =
+            model.predict_mean_variance(&Observation::from([("x".to_string(), 0.75)]));
+        assert!((left_mean - 2.0).abs() < 0.3, "{}", left_mean);
+        assert!((right_mean - 1.0).abs() < 0.1, "{}", right_mean);
+        assert!(
+            left_var > 1.0 && right_var < 0.1,
+            "{} {}",
+            left_var,
+            right_var
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+// Split of an internal node: the samples whose feature is at most the threshold go left.
+#[derive(Clone, Debug)]
+struct Split<F> {
+    feature: String,
+    threshold: F,
+    left: usize,
+    right: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node<F, S> {
+    parent: Option<usize>,
+    split: Option<Split<F>>,
+    // Time at which the cell of the node was created, i.e. the split time of its parent
+    time: F,
+    // Smallest box containing the samples which went through the node
+    range: HashMap<String, (F, F)>,
+    n_samples: usize,
+    // Logarithms of the exponential weights of the node and of the subtree rooted at it
+    weight: F,
+    log_weight_tree: F,
+    stats: S,
+}
+
+impl<F: Float, S: Default> Node<F, S> {
+    fn leaf(parent: Option<usize>, time: F) -> Self {
+        Self {
+            parent,
+            split: None,
+            time,
+            range: HashMap::new(),
+            n_samples: 0,
+            weight: F::zero(),
+            log_weight_tree: F::zero(),
+            stats: S::default(),
+        }
+    }
+}
+
+// log((exp(a) + exp(b)) / 2), computed without overflowing.
+fn log_sum_2_exp<F: Float + FromPrimitive>(a: F, b: F) -> F {
+    let (max, min) = if a > b { (a, b) } else { (b, a) };
+    max + ((F::one() + (min - max).exp()) / F::from_f64(2.0).unwrap()).ln()
+}
+
+// Aggregated Mondrian tree, shared by the Mondrian forests. The tree only takes care of its
+// structure and of the exponential weights of its nodes: the statistics `S` of the nodes, their
+// predictions and their losses are given by the forest which owns the tree.
+//
+// The lifetime of the Mondrian process is infinite, so that a leaf is split each time a sample
+// falls outside the box of the samples it has seen, unless `max_nodes` has been reached.
+#[derive(Clone, Debug)]
+pub(crate) struct MondrianTree<F, S> {
+    nodes: Vec<Node<F, S>>,
+    step: F,
+    max_nodes: Option<usize>,
+    rng: StdRng,
+}
+
+impl<
+        F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+        S: Clone + Default,
+    > MondrianTree<F, S>
+{
+    pub(crate) fn new(step: F, max_nodes: Option<usize>, seed: u64) -> Self {
+        Self {
+            nodes: Vec::new(),
+            step,
+            max_nodes,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+    pub(crate) fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    // Extension of the box of a node needed to contain the sample, for each feature.
+    fn extensions(&self, i: usize, x: &Observation<F>) -> Vec<(String, F)> {
+        let range = &self.nodes[i].range;
+        x.numeric()
+            .filter_map(|(name, value)| {
+                let (min, max) = range.get(name)?;
+                let extension = (*min - value).max(F::zero()) + (value - *max).max(F::zero());
+                (extension > F::zero()).then(|| (name.clone(), extension))
+            })
+            .collect()
+    }
+    fn child(&self, i: usize, x: &Observation<F>) -> Option<usize> {
+        let split = self.nodes[i].split.as_ref()?;
+        match x.get_numeric(&split.feature) {
+            Some(value) if value <= split.threshold => Some(split.left),
+            Some(_) => Some(split.right),
+            // Samples which lack the feature follow the majority
+            None => {
+                let (left, right) = (&self.nodes[split.left], &self.nodes[split.right]);
+                if left.n_samples >= right.n_samples {
+                    Some(split.left)
+                } else {
+                    Some(split.right)
+                }
+            }
+        }
+    }
+    fn leaf(&self, x: &Observation<F>) -> Option<usize> {
+        let mut i = if self.nodes.is_empty() { None } else { Some(0) }?;
+        while let Some(child) = self.child(i, x) {
+            i = child;
+        }
+        Some(i)
+    }
+    // Time of a split of the node which would separate the sample from the samples it has seen,
+    // if it happens before the split of the node itself.
+    fn split_time(&mut self, i: usize, extensions: &[(String, F)]) -> Option<F> {
+        let rate = extensions.iter().fold(F::zero(), |acc, (_, e)| acc + *e);
+        if rate == F::zero() {
+            return None;
+        }
+        let u: f64 = self.rng.gen();
+        let time = self.nodes[i].time + F::from_f64(-(1.0 - u).ln()).unwrap() / rate;
+        match &self.nodes[i].split {
+            None => Some(time),
+            Some(split) if time < self.nodes[split.left].time => Some(time),
+            Some(_) => None,
+        }
+    }
+    // Split the node so that the sample falls alone in a new leaf. The node keeps its place in the
+    // tree and its statistics, while a copy of it becomes the sibling of the new leaf.
+    fn split(&mut self, i: usize, time: F, extensions: &[(String, F)], x: &Observation<F>) {
+        let rate = extensions.iter().fold(F::zero(), |acc, (_, e)| acc + *e);
+        let mut target = F::from_f64(self.rng.gen()).unwrap() * rate;
+        let mut feature = &extensions[extensions.len() - 1].0;
+        for (name, extension) in extensions {
+            if target < *extension {
+                feature = name;
+                break;
+            }
+            target -= *extension;
+        }
+        let value = x.get_numeric(feature).unwrap();
+        let (min, max) = self.nodes[i].range[feature];
+        let u = F::from_f64(self.rng.gen()).unwrap();
+        let (threshold, is_right) = if value < min {
+            (value + u * (min - value), false)
+        } else {
+            (max + u * (value - max), true)
+        };
+
+        let mut copy = self.nodes[i].clone();
+        copy.parent = Some(i);
+        copy.time = time;
+        let copy_index = self.nodes.len();
+        if let Some(split) = &copy.split {
+            self.nodes[split.left].parent = Some(copy_index);
+            self.nodes[split.right].parent = Some(copy_index);
+        }
+        self.nodes.push(copy);
+        let leaf_index = self.nodes.len();
+        self.nodes.push(Node::leaf(Some(i), time));
+        let (left, right) = if is_right {
+            (copy_index, leaf_index)
+        } else {
+            (leaf_index, copy_index)
+        };
+        self.nodes[i].split = Some(Split {
+            feature: feature.clone(),
+            threshold,
+            left,
+            right,
+        });
+    }
+    // Update a node with a sample: its weight is multiplied by exp(-step * loss), where the loss
+    // is the one of the prediction the node made before seeing the sample.
+    fn update_node(
+        &mut self,
+        i: usize,
+        x: &Observation<F>,
+        loss: &impl Fn(&S) -> F,
+        update: &impl Fn(&mut S),
+    ) {
+        let step = self.step;
+        let node = &mut self.nodes[i];
+        node.weight -= step * loss(&node.stats);
+        update(&mut node.stats);
+        node.n_samples += 1;
+        for (name, value) in x.numeric() {
+            let range = node.range.entry(name.clone()).or_insert((value, value));
+            range.0 = range.0.min(value);
+            range.1 = range.1.max(value);
+        }
+    }
+    // Update the weights of the subtrees from a leaf up to the root.
+    fn update_upwards(&mut self, leaf: usize) {
+        let mut i = leaf;
+        loop {
+            let node = &self.nodes[i];
+            let log_weight_tree = match &node.split {
+                None => node.weight,
+                Some(split) => log_sum_2_exp(
+                    node.weight,
+                    self.nodes[split.left].log_weight_tree
+                        + self.nodes[split.right].log_weight_tree,
+                ),
+            };
+            self.nodes[i].log_weight_tree = log_weight_tree;
+            match self.nodes[i].parent {
+                Some(parent) => i = parent,
+                None => break,
+            }
+        }
+    }
+    // Learn from a sample. `loss` gives the loss of the prediction of a node on the sample, and
+    // `update` updates the statistics of a node with it.
+    pub(crate) fn learn(
+        &mut self,
+        x: &Observation<F>,
+        loss: impl Fn(&S) -> F,
+        update: impl Fn(&mut S),
+    ) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::leaf(None, F::zero()));
+            self.update_node(0, x, &|_| F::zero(), &update);
+            self.update_upwards(0);
+            return;
+        }
+        let mut i = 0;
+        loop {
+            let can_grow = self.max_nodes.is_none_or(|max| self.nodes.len() + 2 <= max);
+            let extensions = self.extensions(i, x);
+            if can_grow {
+                if let Some(time) = self.split_time(i, &extensions) {
+                    self.split(i, time, &extensions, x);
+                    self.update_node(i, x, &loss, &update);
+                    i = self.child(i, x).unwrap();
+                    self.update_node(i, x, &loss, &update);
+                    break;
+                }
+            }
+            self.update_node(i, x, &loss, &update);
+            match self.child(i, x) {
+                Some(child) => i = child,
+                None => break,
+            }
+        }
+        self.update_upwards(i);
+    }
+    // Prediction of the tree for a sample, or `None` if it hasn't learnt anything. Without
+    // aggregation, this is the prediction of the leaf the sample falls into. Otherwise, the
+    // predictions of the nodes on the path from the leaf to the root are mixed with `mix`, whose
+    // last argument is the weight of the node, i.e. the posterior probability of pruning the tree
+    // at this node.
+    pub(crate) fn predict<P>(
+        &self,
+        x: &Observation<F>,
+        use_aggregation: bool,
+        predict: impl Fn(&S) -> P,
+        mix: impl Fn(P, P, F) -> P,
+    ) -> Option<P> {
+        let mut i = self.leaf(x)?;
+        let mut prediction = predict(&self.nodes[i].stats);
+        if !use_aggregation {
+            return Some(prediction);
+        }
+        let half = F::from_f64(0.5).unwrap();
+        while let Some(parent) = self.nodes[i].parent {
+            i = parent;
+            let node = &self.nodes[i];
+            let weight = half * (node.weight - node.log_weight_tree).exp();
+            prediction = mix(predict(&node.stats), prediction, weight);
+        }
+        Some(prediction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(x: f64) -> Observation<f64> {
+        Observation::from([("x".to_string(), x)])
+    }
+
+    #[test]
+    fn test_growth() {
+        let mut tree: MondrianTree<f64, usize> = MondrianTree::new(1.0, None, 42);
+        tree.learn(&obs(0.0), |_| 0.0, |n| *n += 1);
+        assert_eq!(tree.n_nodes(), 1);
+        // A sample inside the box of the root doesn't split it
+        tree.learn(&obs(0.0), |_| 0.0, |n| *n += 1);
+        assert_eq!(tree.n_nodes(), 1);
+        // Each sample outside the box of its leaf creates a new leaf and a new internal node
+        tree.learn(&obs(1.0), |_| 0.0, |n| *n += 1);
+        tree.learn(&obs(2.0), |_| 0.0, |n| *n += 1);
+        assert_eq!(tree.n_nodes(), 5);
+        let count = |x: f64| tree.predict(&obs(x), false, |n| *n, |a, _, _| a);
+        assert_eq!(count(0.0), Some(2));
+        assert_eq!(count(2.0), Some(1));
+        // The root has seen all the samples
+        assert_eq!(tree.nodes[0].stats, 4);
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let mut tree: MondrianTree<f64, usize> = MondrianTree::new(1.0, Some(5), 42);
+        for i in 0..100 {
+            tree.learn(&obs(i as f64), |_| 0.0, |n| *n += 1);
+        }
+        assert_eq!(tree.n_nodes(), 5);
+        assert_eq!(tree.nodes[0].stats, 100);
+    }
+
+    #[test]
+    fn test_log_sum_2_exp() {
+        let expected = ((1f64.exp() + 3f64.exp()) / 2.0).ln();
+        assert!((log_sum_2_exp(1.0, 3.0) - expected).abs() < 1e-12);
+        assert!((log_sum_2_exp(-1000.0, -1000.0) + 1000.0).abs() < 1e-12);
+    }
+}
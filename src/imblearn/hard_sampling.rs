@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Replays the samples on which the classifier did the worst, so that it focuses on them.
+///
+/// The hardness of a sample is one minus the probability given to its class by the classifier
+/// before learning from it. The `size` hardest samples seen so far are kept in a buffer, and after
+/// learning from each sample, the classifier learns with probability `p` from a sample drawn
+/// uniformly from the buffer as well. With imbalanced classes, the hard samples are mostly those
+/// of the rare classes, which are thus learned more often.
+///
+/// # Parameters
+///
+/// - `classifier`: The classifier which learns from the samples.
+/// - `size`: The number of hard samples which are kept.
+/// - `p`: The probability that a hard sample is replayed after each sample.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::imblearn::hard_sampling::HardSamplingClassifier;
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+///
+/// let mut model = HardSamplingClassifier::new(GaussianNB::new(), 50, 0.5, Some(42));
+/// for i in 0..2000 {
+///     let fraud = i % 20 == 0;
+///     let amount = if fraud { 700.0 + (i % 200) as f64 } else { (i % 100) as f64 };
+///     let x = Observation::from([("amount".to_string(), amount)]);
+///     model.learn_one(&x, ClassifierTarget::from(fraud));
+/// }
+/// let x = Observation::from([("amount".to_string(), 850.0)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(true));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HardSamplingClassifier<F, M> {
+    classifier: M,
+    size: usize,
+    p: f64,
+    // Hardness, observation and class of the hardest samples
+    buffer: Vec<(F, Observation<F>, ClassifierTarget)>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> HardSamplingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    pub fn new(classifier: M, size: usize, p: f64, seed: Option<u64>) -> Self {
+        assert!(size > 0, "size must be strictly positive");
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        Self {
+            classifier,
+            size,
+            p,
+            buffer: Vec::with_capacity(size),
+            rng: rng(seed),
+            _float: PhantomData,
+        }
+    }
+    pub fn classifier(&self) -> &M {
+        &self.classifier
+    }
+    /// The hardness of each sample of the buffer.
+    pub fn hardness(&self) -> impl Iterator<Item = F> + '_ {
+        self.buffer.iter().map(|(hardness, _, _)| *hardness)
+    }
+    fn keep(&mut self, hardness: F, x: &Observation<F>, y: &ClassifierTarget) {
+        if self.buffer.len() < self.size {
+            self.buffer.push((hardness, x.clone(), y.clone()));
+            return;
+        }
+        let (easiest, _) = self
+            .buffer
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.0.partial_cmp(&b.0).unwrap())
+            .unwrap();
+        if hardness > self.buffer[easiest].0 {
+            self.buffer[easiest] = (hardness, x.clone(), y.clone());
+        }
+    }
+}
+
+impl<F, M> Classifier<F> for HardSamplingClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let probabilities = self.classifier.predict_proba(x);
+        let hardness = F::one() - probabilities.get(&y).copied().unwrap_or(F::zero());
+        self.keep(hardness, x, &y);
+        self.classifier.learn_one(x, y);
+        if self.rng.gen::<f64>() < self.p {
+            let (_, x, y) = &self.buffer[self.rng.gen_range(0..self.buffer.len())];
+            self.classifier.learn_one(x, y.clone());
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.classifier.predict_proba(x)
+    }
+    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget {
+        self.classifier.predict_one(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Always predicts "a" with certainty, and remembers the classes it learns from
+    #[derive(Default)]
+    struct Constant(Vec<ClassifierTarget>);
+
+    impl Classifier<f64> for Constant {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+            self.0.push(y);
+        }
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            ClassifierTargetProbabilities::from([(ClassifierTarget::from("a"), 1.0)])
+        }
+    }
+
+    #[test]
+    fn test_replays_hard_samples() {
+        let mut model = HardSamplingClassifier::new(Constant::default(), 10, 1.0, Some(42));
+        for i in 0..1000 {
+            let y = if i % 50 == 0 { "b" } else { "a" };
+            model.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+        // The buffer only holds samples of "b" once ten have been seen
+        assert!(model.hardness().all(|hardness| hardness == 1.0));
+        let learned = &model.classifier().0;
+        assert_eq!(learned.len(), 2000);
+        let b = learned
+            .iter()
+            .filter(|y| **y == ClassifierTarget::from("b"))
+            .count();
+        assert!(b > 500, "{}", b);
+    }
+}
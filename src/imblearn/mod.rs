@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+use num::Float;
+
+pub mod hard_sampling;
+pub mod random;
+
+// Desired and actual distributions of the classes of a stream.
+#[derive(Clone, Debug)]
+struct ClassDistribution<F> {
+    desired: ClassifierTargetProbabilities<F>,
+    counts: HashMap<ClassifierTarget, F>,
+}
+
+impl<F: Float> ClassDistribution<F> {
+    fn new(desired: ClassifierTargetProbabilities<F>) -> Self {
+        assert!(
+            desired.values().all(|p| *p >= F::zero()),
+            "The desired proportions must be positive"
+        );
+        let total = desired.values().fold(F::zero(), |sum, p| sum + *p);
+        assert!(
+            total > F::zero(),
+            "The desired distribution must not be empty"
+        );
+        Self {
+            desired: desired.into_iter().map(|(y, p)| (y, p / total)).collect(),
+            counts: HashMap::new(),
+        }
+    }
+    fn update(&mut self, y: &ClassifierTarget) {
+        let count = self.counts.entry(y.clone()).or_insert(F::zero());
+        *count = *count + F::one();
+    }
+    // Ratio of the desired proportion of a class seen so far to its count.
+    fn ratio(&self, y: &ClassifierTarget) -> F {
+        let desired = self.desired.get(y).copied().unwrap_or(F::zero());
+        desired / self.counts[y]
+    }
+    fn ratios(&self) -> impl Iterator<Item = F> + '_ {
+        self.counts.keys().map(|y| self.ratio(y))
+    }
+}
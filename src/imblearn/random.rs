@@ -0,0 +1,265 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::ensemble::utils::poisson;
+use crate::imblearn::ClassDistribution;
+use crate::learner::Classifier;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Random under-sampling of the classes which are more frequent than desired.
+///
+/// Each sample is learned with a probability which makes the distribution of the learned classes
+/// follow the desired one, given the distribution of the classes seen so far. The class which is
+/// the most under-represented with respect to the desired distribution is always learned, and
+/// the others are discarded as needed. Classes which aren't in the desired distribution are never
+/// learned.
+///
+/// # Parameters
+///
+/// - `classifier`: The classifier which learns from the samples.
+/// - `desired_dist`: The desired proportion of each class, which is normalized.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+/// use light_river::imblearn::random::RandomUnderSampler;
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+///
+/// let desired = ClassifierTargetProbabilities::from([
+///     (ClassifierTarget::from(true), 0.5),
+///     (ClassifierTarget::from(false), 0.5),
+/// ]);
+/// let mut model = RandomUnderSampler::new(GaussianNB::new(), desired, Some(42));
+/// for i in 0..2000 {
+///     // Only one sample in twenty is a fraud
+///     let fraud = i % 20 == 0;
+///     let amount = if fraud { 700.0 + (i % 200) as f64 } else { (i % 100) as f64 };
+///     let x = Observation::from([("amount".to_string(), amount)]);
+///     model.learn_one(&x, ClassifierTarget::from(fraud));
+/// }
+/// let x = Observation::from([("amount".to_string(), 850.0)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(true));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomUnderSampler<F, M> {
+    classifier: M,
+    dist: ClassDistribution<F>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> RandomUnderSampler<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    pub fn new(
+        classifier: M,
+        desired_dist: ClassifierTargetProbabilities<F>,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            classifier,
+            dist: ClassDistribution::new(desired_dist),
+            rng: rng(seed),
+            _float: PhantomData,
+        }
+    }
+    pub fn classifier(&self) -> &M {
+        &self.classifier
+    }
+}
+
+impl<F, M> Classifier<F> for RandomUnderSampler<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.dist.update(&y);
+        let Some(max) = self.dist.ratios().reduce(F::max) else {
+            return;
+        };
+        let probability = self.dist.ratio(&y) / max;
+        if F::from_f64(self.rng.gen::<f64>()).unwrap() < probability {
+            self.classifier.learn_one(x, y);
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.classifier.predict_proba(x)
+    }
+    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget {
+        self.classifier.predict_one(x)
+    }
+}
+
+/// Random over-sampling of the classes which are less frequent than desired.
+///
+/// Each sample is learned `k ~ Poisson(rate)` times, where the rate makes the distribution of
+/// the learned classes follow the desired one, given the distribution of the classes seen so far.
+/// The rate is one for the class which is the most over-represented with respect to
+/// the desired distribution, and higher for the others. Classes which aren't in the desired
+/// distribution are never learned.
+///
+/// # Parameters
+///
+/// - `classifier`: The classifier which learns from the samples.
+/// - `desired_dist`: The desired proportion of each class, which is normalized.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+/// use light_river::imblearn::random::RandomOverSampler;
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+///
+/// let desired = ClassifierTargetProbabilities::from([
+///     (ClassifierTarget::from(true), 0.5),
+///     (ClassifierTarget::from(false), 0.5),
+/// ]);
+/// let mut model = RandomOverSampler::new(GaussianNB::new(), desired, Some(42));
+/// for i in 0..2000 {
+///     let fraud = i % 20 == 0;
+///     let amount = if fraud { 700.0 + (i % 200) as f64 } else { (i % 100) as f64 };
+///     let x = Observation::from([("amount".to_string(), amount)]);
+///     model.learn_one(&x, ClassifierTarget::from(fraud));
+/// }
+/// let x = Observation::from([("amount".to_string(), 850.0)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(true));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomOverSampler<F, M> {
+    classifier: M,
+    dist: ClassDistribution<F>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> RandomOverSampler<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    pub fn new(
+        classifier: M,
+        desired_dist: ClassifierTargetProbabilities<F>,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            classifier,
+            dist: ClassDistribution::new(desired_dist),
+            rng: rng(seed),
+            _float: PhantomData,
+        }
+    }
+    pub fn classifier(&self) -> &M {
+        &self.classifier
+    }
+}
+
+impl<F, M> Classifier<F> for RandomOverSampler<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.dist.update(&y);
+        let Some(min) = self
+            .dist
+            .ratios()
+            .filter(|ratio| *ratio > F::zero())
+            .reduce(F::min)
+        else {
+            return;
+        };
+        let rate = (self.dist.ratio(&y) / min).to_f64().unwrap();
+        for _ in 0..poisson(rate, &mut self.rng) {
+            self.classifier.learn_one(x, y.clone());
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.classifier.predict_proba(x)
+    }
+    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget {
+        self.classifier.predict_one(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // Counts the samples it learns from, by class
+    #[derive(Default)]
+    struct Counter(HashMap<ClassifierTarget, usize>);
+
+    impl Classifier<f64> for Counter {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+            *self.0.entry(y).or_insert(0) += 1;
+        }
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            ClassifierTargetProbabilities::new()
+        }
+    }
+
+    // 80% of "a", 15% of "b", 5% of "c", of which the desired proportions are 1:1:2
+    fn learn_stream<M: Classifier<f64>>(model: &mut M) {
+        for i in 0..20000 {
+            let y = match i % 20 {
+                0..=15 => "a",
+                16..=18 => "b",
+                _ => "c",
+            };
+            model.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+    }
+
+    fn desired() -> ClassifierTargetProbabilities<f64> {
+        ClassifierTargetProbabilities::from([
+            (ClassifierTarget::from("a"), 1.0),
+            (ClassifierTarget::from("b"), 1.0),
+            (ClassifierTarget::from("c"), 2.0),
+        ])
+    }
+
+    #[test]
+    fn test_under_sampling() {
+        let mut model = RandomUnderSampler::new(Counter::default(), desired(), Some(42));
+        learn_stream(&mut model);
+        let counts = &model.classifier().0;
+        // All the samples of the rarest class are learned
+        assert_eq!(counts[&ClassifierTarget::from("c")], 1000);
+        for class in ["a", "b"] {
+            let n = counts[&ClassifierTarget::from(class)] as f64;
+            assert!((n - 500.0).abs() < 50.0, "{} {}", class, n);
+        }
+    }
+
+    #[test]
+    fn test_over_sampling() {
+        let mut model = RandomOverSampler::new(Counter::default(), desired(), Some(42));
+        learn_stream(&mut model);
+        let counts = &model.classifier().0;
+        let a = counts[&ClassifierTarget::from("a")] as f64;
+        assert!((a - 16000.0).abs() < 500.0, "{}", a);
+        let (b, c) = (
+            counts[&ClassifierTarget::from("b")] as f64,
+            counts[&ClassifierTarget::from("c")] as f64,
+        );
+        assert!(
+            (b / a - 1.0).abs() < 0.1 && (c / a - 2.0).abs() < 0.2,
+            "{} {}",
+            b,
+            c
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierOutput, ClassifierTarget, ClassifierTargetProbabilities, MultiClassifierTarget,
+    MultiClassifierTargetProbabilities, MultiRegressionTarget, Observation, RegressionTarget,
+};
+use num::{Float, FromPrimitive};
+
+/// Trait for implementing a classifier model.
+///
+/// Implement this trait for your classifier to use the `learn_one`, `predict_proba`, and
+/// `predict_one` methods. By default, `predict_one` returns the most likely class according to
+/// `predict_proba`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+/// use light_river::learner::Classifier;
+/// use std::collections::HashMap;
+///
+/// // Predicts the class frequencies seen so far
+/// #[derive(Default)]
+/// struct Prior {
+///     counts: HashMap<ClassifierTarget, f64>,
+/// }
+///
+/// impl Classifier<f64> for Prior {
+///     fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+///         *self.counts.entry(y).or_insert(0.0) += 1.0;
+///     }
+///     fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+///         let total: f64 = self.counts.values().sum();
+///         self.counts.iter().map(|(y, n)| (y.clone(), n / total)).collect()
+///     }
+/// }
+///
+/// let mut model = Prior::default();
+/// for y in ["cat", "dog", "cat"] {
+///     model.learn_one(&Observation::new(), ClassifierTarget::from(y));
+/// }
+/// assert_eq!(model.predict_one(&Observation::new()), ClassifierTarget::from("cat"));
+/// ```
+pub trait Classifier<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget);
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F>;
+    /// # Panics
+    ///
+    /// By default, if `predict_proba` returns no probabilities.
+    fn predict_one(&self, x: &Observation<F>) -> ClassifierTarget {
+        ClassifierOutput::Probabilities(self.predict_proba(x)).get_predicition()
+    }
+}
+
+/// Trait for implementing a regression model.
+///
+/// Implement this trait for your regressor to use the `learn_one` and `predict_one` methods.
+pub trait Regressor<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>);
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F>;
+}
+
+/// Trait for implementing a multi-output classifier, which predicts a class for each of several
+/// named outputs at once.
+///
+/// The targets of an instance may miss some of the outputs, which are then not learned from. By
+/// default, `predict_one` returns the most likely class of each output according to
+/// `predict_proba`, leaving out the outputs without probabilities.
+pub trait MultiOutputClassifier<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: &MultiClassifierTarget);
+    fn predict_proba(&self, x: &Observation<F>) -> MultiClassifierTargetProbabilities<F>;
+    fn predict_one(&self, x: &Observation<F>) -> MultiClassifierTarget {
+        self.predict_proba(x)
+            .into_iter()
+            .filter(|(_, probabilities)| !probabilities.is_empty())
+            .map(|(name, probabilities)| {
+                let y = ClassifierOutput::Probabilities(probabilities).get_predicition();
+                (name, y)
+            })
+            .collect()
+    }
+}
+
+/// Trait for implementing a multi-target regressor, which predicts several named targets at once.
+///
+/// The targets of an instance may miss some of them, which are then not learned from.
+pub trait MultiOutputRegressor<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: &MultiRegressionTarget<F>);
+    fn predict_one(&self, x: &Observation<F>) -> MultiRegressionTarget<F>;
+}
+
+/// Trait for implementing an anomaly detector model.
+///
+/// Implement this trait for your anomaly detector to use the `learn_one` and `score_one` methods.
+/// Anomaly detectors are unsupervised, and the score is expected to be higher for anomalies.
+pub trait AnomalyDetector<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    fn learn_one(&mut self, x: &Observation<F>);
+    fn score_one(&self, x: &Observation<F>) -> F;
+}
+
+/// Trait for implementing a clustering model.
+///
+/// Implement this trait for your clustering model to use the `learn_one` and `predict_one` methods.
+pub trait Clusterer<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, x: &Observation<F>);
+    fn predict_one(&self, x: &Observation<F>) -> i32;
+}
+
+/// Trait for implementing a forecaster, which learns from a time series one value at a time and
+/// forecasts its next values.
+///
+/// Exogenous features may be given along with each value, and with each forecast step, in which
+/// case there must be one observation per step. Forecasters which don't use them ignore them.
+pub trait Forecaster<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, y: F, x: Option<&Observation<F>>);
+    fn forecast(&self, horizon: usize, xs: Option<&[Observation<F>]>) -> Vec<F>;
+}
+
+/// Trait for implementing a recommender, which learns from the ratings given by users to items,
+/// one rating at a time, and predicts the rating of any pair of user and item, including the
+/// users and items it has never seen.
+pub trait Recommender<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, user: &str, item: &str, y: RegressionTarget<F>);
+    fn predict_one(&self, user: &str, item: &str) -> RegressionTarget<F>;
+}
+
+/// Trait for implementing a transformer, which maps an observation to new features, e.g. to
+/// encode or scale them.
+///
+/// Transformers are applied with `transform_one`, and learn from the stream with `learn_one`,
+/// which does nothing by default, as for stateless transformers.
+pub trait Transformer<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn learn_one(&mut self, _x: &Observation<F>) {}
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F>;
+}
+
+/// Trait for implementing a supervised transformer, which also learns from the target, e.g. to
+/// encode the features with statistics of the target. Classification targets are expected as 0
+/// or 1.
+///
+/// An observation should be transformed before the transformer learns from it, as its own target
+/// would otherwise leak into its features.
+pub trait SupervisedTransformer<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>);
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F>;
+}
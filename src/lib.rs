@@ -1,8 +1,41 @@
 pub mod anomaly;
+pub mod bandit;
+pub mod calibration;
+pub mod cluster;
 pub mod common;
+pub mod compose;
 pub mod datasets;
+pub mod decomposition;
+pub mod drift;
+pub mod ensemble;
+pub mod evaluate;
+pub mod facto;
+pub mod feature_extraction;
+pub mod feature_selection;
+pub mod forest;
+pub mod imblearn;
+pub mod learner;
+pub mod linear_model;
 pub mod metrics;
+pub mod model_selection;
+pub mod multiclass;
+pub mod multioutput;
+pub mod naive_bayes;
+pub mod neighbors;
+pub mod neural;
+pub mod optim;
+pub mod preprocessing;
+pub mod reco;
+pub mod sampling;
+pub mod sketch;
+pub mod stats;
 pub mod stream;
+pub mod time_series;
+pub mod tree;
+pub(crate) mod utils;
+
+#[cfg(test)]
+pub(crate) mod testing;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,232 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba, features, observation_features};
+use crate::optim::losses::sigmoid;
+use crate::optim::optimizers::Weights;
+use num::{Float, FromPrimitive};
+
+// r-norm of a vector.
+fn norm<F: Float>(values: impl Iterator<Item = F>, r: F) -> F {
+    values
+        .fold(F::zero(), |sum, v| sum + v.abs().powf(r))
+        .powf(r.recip())
+}
+
+// Apply in place the gradient of half the squared r-norm, which maps the weights from one space
+// to its dual. The inverse of the mapping for r is the mapping for the conjugate exponent.
+fn link<F: Float>(weights: &mut Weights<F>, r: F) {
+    let two = F::one() + F::one();
+    let norm = norm(weights.iter().map(|(_, w)| w), r);
+    if norm == F::zero() || r == two {
+        return;
+    }
+    for w in weights.values_mut() {
+        *w = w.signum() * w.abs().powf(r - F::one()) / norm.powf(r - two);
+    }
+}
+
+/// Approximate large margin algorithm, a binary linear classifier.
+///
+/// Like the perceptron, ALMA only updates its weights on samples which aren't classified with a
+/// large enough margin. The margin it targets grows closer to the maximal one, up to a factor of
+/// `1 - alpha`, while the learning rate decreases with the number of updates. The samples are
+/// normalized with the p-norm and the weights are kept in the unit ball of the dual q-norm,
+/// `1/p + 1/q = 1`. Large values of `p` are suited to sparse targets among many features. There
+/// is no intercept, so a constant feature should be added if the classes aren't separated by a
+/// hyperplane through the origin.
+///
+/// The predicted probability is the logistic function of the margin, which is only meant to rank
+/// the samples. The labels are booleans, `true` being the positive class.
+///
+/// # Parameters
+///
+/// - `p`: The order of the norm, at least 2, which is 2 by default.
+/// - `alpha`: How close to the maximal margin the targeted one is, in `(0, 1]`, 0.9 by default.
+///   The margin constants are set to `B = 1 / alpha` and `C = sqrt(2)`, as recommended in the
+///   paper.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::alma::ALMAClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: ALMAClassifier<f64> = ALMAClassifier::new(None, None);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let a = rng.gen_range(-0.5..0.5);
+///     let b = rng.gen_range(-0.5..0.5);
+///     let observation = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+///     model.learn_one(&observation, ClassifierTarget::from(a > b));
+/// }
+///
+/// let observation = Observation::from([("a".to_string(), 0.3), ("b".to_string(), -0.1)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: C. Gentile (2001). "A new approximate maximal margin classification algorithm".
+/// Journal of machine learning research 2:213-242.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ALMAClassifier<F> {
+    p: F,
+    alpha: F,
+    b: F,
+    c: F,
+    weights: Weights<F>,
+    // One plus the number of updates
+    k: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ALMAClassifier<F> {
+    pub fn new(p: Option<F>, alpha: Option<F>) -> Self {
+        let p = p.unwrap_or(F::from_f64(2.0).unwrap());
+        let alpha = alpha.unwrap_or(F::from_f64(0.9).unwrap());
+        assert!(p >= F::from_f64(2.0).unwrap(), "p must be at least 2");
+        assert!(
+            alpha > F::zero() && alpha <= F::one(),
+            "alpha must lie in (0, 1]"
+        );
+        Self {
+            p,
+            alpha,
+            b: alpha.recip(),
+            c: F::from_f64(2.0).unwrap().sqrt(),
+            weights: Weights::new(),
+            k: F::one(),
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.weights
+    }
+    /// Number of samples on which the weights have been updated.
+    pub fn n_updates(&self) -> usize {
+        (self.k - F::one()).to_usize().unwrap()
+    }
+    /// Learn from a sample whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool) {
+        self.learn(&features(x), y);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        sigmoid(self.weights.dot(features(x).iter()))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool) {
+        let x_norm = norm(x.iter().map(|(_, v)| *v), self.p);
+        if x_norm == F::zero() {
+            return;
+        }
+        let x: Vec<(FeatureKey<'_>, F)> = x.iter().map(|(key, v)| (*key, *v / x_norm)).collect();
+        let sign = if y { F::one() } else { -F::one() };
+        let root = (self.p - F::one()).sqrt() / self.k.sqrt();
+        let gamma = self.b * root;
+        if sign * self.weights.dot(x.iter()) > (F::one() - self.alpha) * gamma {
+            return;
+        }
+
+        // The update is made in the dual space, then the weights are projected back onto the
+        // unit ball of the q-norm
+        let q = self.p / (self.p - F::one());
+        let eta = self.c / (self.p - F::one()) * root;
+        link(&mut self.weights, q);
+        for (key, value) in x.iter() {
+            *self.weights.get_mut(*key) += eta * sign * *value;
+        }
+        link(&mut self.weights, self.p);
+        let w_norm = norm(self.weights.iter().map(|(_, w)| w), q);
+        if w_norm > F::one() {
+            for w in self.weights.values_mut() {
+                *w /= w_norm;
+            }
+        }
+        self.k += F::one();
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for ALMAClassifier<F>
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(&observation_features(x), binary_label(y, "ALMAClassifier"));
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        binary_proba(sigmoid(self.weights.dot(observation_features(x).iter())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_link_inverse() {
+        let mut weights = Weights::new();
+        *weights.get_mut(FeatureKey::Name("a")) = 0.5;
+        *weights.get_mut(FeatureKey::Index(1)) = -2.0;
+        let original = weights.clone();
+        link(&mut weights, 4.0);
+        // sign(w) |w|³ / ||w||₄²
+        let expected = 0.125 / 16.0625f64.sqrt();
+        assert!((weights.get(FeatureKey::Name("a")) - expected).abs() < 1e-10);
+        link(&mut weights, 4.0 / 3.0);
+        for (key, w) in original.iter() {
+            assert!((weights.get(key) - w).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_separable_data() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for p in [2.0, 4.0] {
+            let mut model = ALMAClassifier::new(Some(p), None);
+            let mut correct = 0;
+            for i in 0..3000 {
+                // Sparse samples, the target only depending on the first two features
+                let mut x = SparseVector::new();
+                for j in 0..100 {
+                    if j < 2 || rng.gen_bool(0.1) {
+                        x.set(j, rng.gen_range(-1.0..1.0));
+                    }
+                }
+                let score: f64 = x.iter().filter(|(j, _)| *j < 2).map(|(_, v)| v).sum();
+                if score.abs() < 0.1 {
+                    continue;
+                }
+                let x = Features::from(x);
+                if i >= 2000 && (model.predict_proba_features(&x) > 0.5) == (score > 0.0) {
+                    correct += 1;
+                }
+                model.learn_features(&x, score > 0.0);
+            }
+            assert!(correct > 800, "p = {}: {}", p, correct);
+            // The weights stay in the unit ball of the dual norm
+            let q = p / (p - 1.0);
+            assert!(norm(model.weights().iter().map(|(_, w)| w), q) <= 1.0 + 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_passive_on_large_margin() {
+        let mut model = ALMAClassifier::new(None, Some(1.0));
+        let x = Observation::from([("a".to_string(), 3.0)]);
+        model.learn_one(&x, ClassifierTarget::from(true));
+        // eta = sqrt(2) and the weight is projected back onto the unit ball
+        assert_eq!(model.weights().get(FeatureKey::Name("a")), 1.0);
+        // With alpha = 1, any positive margin is large enough
+        model.learn_one(&x, ClassifierTarget::from(true));
+        assert_eq!(model.n_updates(), 1);
+    }
+}
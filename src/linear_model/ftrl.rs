@@ -0,0 +1,215 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba, features, observation_features};
+use crate::optim::losses::sigmoid;
+use crate::optim::optimizers::Weights;
+use num::{Float, FromPrimitive};
+
+/// Follow The Regularized Leader - Proximal, a logistic regression suited to sparse and
+/// high-dimensional streams, such as click-through rate prediction with hashed features.
+///
+/// Each coordinate has its own learning rate, which decreases with the sum of its squared
+/// gradients, as with AdaGrad. The weights aren't stored: they are derived from two
+/// accumulators per coordinate, in closed form, and the L1 regularization sets a weight to
+/// exactly zero as long as the accumulated gradient of its coordinate stays below `l1`. The model
+/// is therefore sparse, which saves memory when most features are rare. The intercept is learned
+/// as the weight of a feature which is always 1, without regularization.
+///
+/// The labels are booleans, `true` being the positive class.
+///
+/// # Parameters
+///
+/// - `alpha`: Scales the per-coordinate learning rates, 0.05 by default.
+/// - `beta`: Smooths the per-coordinate learning rates at the start, 1 by default.
+/// - `l1`: The amount of L1 regularization, 0 by default.
+/// - `l2`: The amount of L2 regularization, 1 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::SparseVector;
+/// use light_river::linear_model::ftrl::FTRLProximal;
+///
+/// let mut model: FTRLProximal<f64> = FTRLProximal::new(Some(0.5), None, Some(1.0), None);
+/// for i in 0..5000 {
+///     // Hashed features, of which only the first one tells whether the ad is clicked
+///     let clicked = i % 3 == 0;
+///     let x = SparseVector::from([(if clicked { 0 } else { 1 }, 1.0), (2 + i % 1000, 1.0)]);
+///     model.learn_features(&x.into(), clicked, 1.0);
+/// }
+///
+/// assert!(model.predict_proba_features(&SparseVector::from([(0, 1.0)]).into()) > 0.9);
+/// // The rare features are too uninformative to be given a weight
+/// assert!(model.weights().len() < 10);
+/// ```
+///
+/// # References
+///
+/// [^1]: H. B. McMahan et al. (2013). "Ad click prediction: a view from the trenches".
+/// Proceedings of the 19th ACM SIGKDD international conference on knowledge discovery and data
+/// mining, 1222-1230.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FTRLProximal<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    alpha: F,
+    beta: F,
+    l1: F,
+    l2: F,
+    // Accumulated gradient, shifted by the weights, and sum of the squared gradients
+    z: Weights<F>,
+    n: Weights<F>,
+    intercept_z: F,
+    intercept_n: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> FTRLProximal<F> {
+    pub fn new(alpha: Option<F>, beta: Option<F>, l1: Option<F>, l2: Option<F>) -> Self {
+        Self {
+            alpha: alpha.unwrap_or(F::from_f64(0.05).unwrap()),
+            beta: beta.unwrap_or(F::one()),
+            l1: l1.unwrap_or(F::zero()),
+            l2: l2.unwrap_or(F::one()),
+            z: Weights::new(),
+            n: Weights::new(),
+            intercept_z: F::zero(),
+            intercept_n: F::zero(),
+        }
+    }
+    // Weight of a coordinate, given its accumulators.
+    fn weight(&self, z: F, n: F, l1: F, l2: F) -> F {
+        if z.abs() <= l1 {
+            F::zero()
+        } else {
+            -(z - z.signum() * l1) / ((self.beta + n.sqrt()) / self.alpha + l2)
+        }
+    }
+    /// The non-zero weights.
+    pub fn weights(&self) -> Weights<F> {
+        let mut weights = Weights::new();
+        for (key, z) in self.z.iter() {
+            let w = self.weight(z, self.n.get(key), self.l1, self.l2);
+            if w != F::zero() {
+                *weights.get_mut(key) = w;
+            }
+        }
+        weights
+    }
+    pub fn intercept(&self) -> F {
+        self.weight(self.intercept_z, self.intercept_n, F::zero(), F::zero())
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        self.predict(&features(x))
+    }
+    fn predict(&self, x: &[(FeatureKey<'_>, F)]) -> F {
+        let dot = x.iter().fold(self.intercept(), |sum, (key, value)| {
+            sum + self.weight(self.z.get(*key), self.n.get(*key), self.l1, self.l2) * *value
+        });
+        sigmoid(dot)
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool, w: F) {
+        let y = if y { F::one() } else { F::zero() };
+        let loss_gradient = w * (self.predict(x) - y);
+        let update = |z: &mut F, n: &mut F, weight: F, g: F, alpha: F| {
+            let sigma = ((*n + g * g).sqrt() - n.sqrt()) / alpha;
+            *z += g - sigma * weight;
+            *n += g * g;
+        };
+        for (key, value) in x {
+            let weight = self.weight(self.z.get(*key), self.n.get(*key), self.l1, self.l2);
+            let mut n = self.n.get(*key);
+            update(
+                self.z.get_mut(*key),
+                &mut n,
+                weight,
+                loss_gradient * *value,
+                self.alpha,
+            );
+            *self.n.get_mut(*key) = n;
+        }
+        let weight = self.intercept();
+        update(
+            &mut self.intercept_z,
+            &mut self.intercept_n,
+            weight,
+            loss_gradient,
+            self.alpha,
+        );
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for FTRLProximal<F>
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(
+            &observation_features(x),
+            binary_label(y, "FTRLProximal"),
+            F::one(),
+        );
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let p = self.predict(&observation_features(x));
+        binary_proba(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use rand::prelude::*;
+
+    // Hashed features: two informative ones and many noisy ones
+    fn stream(n: usize) -> Vec<(SparseVector<f64>, bool)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let y: bool = rng.gen_bool(0.3);
+                let signal = if rng.gen_bool(0.9) == y { 0 } else { 1 };
+                let noise = 2 + rng.gen_range(0..10000);
+                (SparseVector::from([(signal, 1.0), (noise, 1.0)]), y)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_l1_sparsity() {
+        let mut dense = FTRLProximal::new(Some(0.1), None, None, None);
+        let mut sparse = FTRLProximal::new(Some(0.1), None, Some(1.0), None);
+        for (x, y) in stream(20000) {
+            let x: Features<f64> = x.into();
+            dense.learn_features(&x, y, 1.0);
+            sparse.learn_features(&x, y, 1.0);
+        }
+        let (n_dense, n_sparse) = (dense.weights().len(), sparse.weights().len());
+        assert!(n_dense > 5000);
+        assert!(n_sparse * 10 < n_dense, "{} {}", n_sparse, n_dense);
+        // The informative feature is kept
+        assert!(sparse.weights().get(FeatureKey::Index(0)) > 1.0);
+        let p = sparse.predict_proba_features(&SparseVector::from([(0, 1.0)]).into());
+        assert!(p > 0.7, "{}", p);
+    }
+
+    #[test]
+    fn test_intercept() {
+        // Without features, the model learns the base rate through the intercept
+        let mut model = FTRLProximal::new(Some(0.1), None, None, None);
+        for (_, y) in stream(20000) {
+            model.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+        let p = model.predict_proba(&Observation::new())[&ClassifierTarget::from(true)];
+        assert!((p - 0.3).abs() < 0.05, "{}", p);
+    }
+}
@@ -0,0 +1,113 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Options of the generalized linear models.
+///
+/// - `l1`: The amount of L1 regularization, which pushes the weights towards zero.
+/// - `l2`: The amount of L2 regularization, which keeps the weights small.
+/// - `intercept_lr`: The learning rate of the intercept, which is updated with plain gradient
+///   descent. The intercept isn't learned when it is zero.
+///
+/// The regularization is applied lazily: a weight is only regularized when its feature is present
+/// in a sample.
+#[derive(Clone, Debug)]
+pub struct GLMOptions<F> {
+    pub l1: F,
+    pub l2: F,
+    pub intercept_lr: F,
+}
+
+impl<F: Float + FromPrimitive> Default for GLMOptions<F> {
+    fn default() -> Self {
+        Self {
+            l1: F::zero(),
+            l2: F::zero(),
+            intercept_lr: F::from_f64(0.01).unwrap(),
+        }
+    }
+}
+
+// Numeric features of an observation, as the weights of a linear model expect them.
+pub(crate) fn observation_features<F: Float>(x: &Observation<F>) -> Vec<(FeatureKey<'_>, F)> {
+    x.numeric()
+        .map(|(name, value)| (FeatureKey::Name(name), value))
+        .collect()
+}
+
+pub(crate) fn features<F: Float>(x: &Features<F>) -> Vec<(FeatureKey<'_>, F)> {
+    x.numeric().collect()
+}
+
+// Label of a binary classifier, whose labels must be booleans.
+pub(crate) fn binary_label(y: ClassifierTarget, model: &str) -> bool {
+    match y {
+        ClassifierTarget::Bool(y) => y,
+        _ => panic!(
+            "{} is a binary classifier, whose labels must be booleans",
+            model
+        ),
+    }
+}
+
+// Class probabilities of a binary classifier, given the probability of the positive class.
+pub(crate) fn binary_proba<F: Float>(p: F) -> ClassifierTargetProbabilities<F> {
+    ClassifierTargetProbabilities::from([
+        (ClassifierTarget::Bool(true), p),
+        (ClassifierTarget::Bool(false), F::one() - p),
+    ])
+}
+
+// Weights and intercept of a linear model, trained by an optimizer given the derivative of the
+// loss with respect to the linear prediction.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Glm<F, O> {
+    pub(crate) weights: Weights<F>,
+    pub(crate) intercept: F,
+    optimizer: O,
+    l1: F,
+    l2: F,
+    intercept_lr: F,
+}
+
+impl<F, O> Glm<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+{
+    pub(crate) fn new(optimizer: O, options: GLMOptions<F>) -> Self {
+        Self {
+            weights: Weights::new(),
+            intercept: F::zero(),
+            optimizer,
+            l1: options.l1,
+            l2: options.l2,
+            intercept_lr: options.intercept_lr,
+        }
+    }
+    pub(crate) fn predict(&self, x: &[(FeatureKey<'_>, F)]) -> F {
+        self.intercept + self.weights.dot(x.iter())
+    }
+    // Take a step given the derivative of the loss with respect to the prediction.
+    pub(crate) fn learn(&mut self, x: &[(FeatureKey<'_>, F)], loss_gradient: F, w: F) {
+        let gradient: Vec<(FeatureKey, F)> = x
+            .iter()
+            .map(|(key, value)| {
+                let weight = self.weights.get(*key);
+                let regularization = if weight == F::zero() {
+                    self.l2 * weight
+                } else {
+                    self.l2 * weight + self.l1 * weight.signum()
+                };
+                (*key, w * (loss_gradient * *value + regularization))
+            })
+            .collect();
+        self.optimizer.step(&mut self.weights, &gradient);
+        self.intercept -= self.intercept_lr * w * loss_gradient;
+    }
+}
@@ -0,0 +1,215 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureKey, Features, Observation, RegressionTarget};
+use crate::learner::Regressor;
+use crate::linear_model::glm::{features, observation_features, GLMOptions, Glm};
+use crate::optim::losses::{RegressionLoss, Squared};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Linear regression, trained by minimizing the squared loss or any other regression loss.
+///
+/// The prediction is a linear combination of the numeric features, mapped by the mean function of
+/// the loss, e.g. the exponential for the [`Poisson`](crate::optim::losses::Poisson) loss. Categorical and missing
+/// features are ignored. The weights are updated by the optimizer after each sample, and the
+/// intercept by plain gradient descent. The model learns from dense observations through the
+/// [`Regressor`] trait, and from sparse vectors too through
+/// [`learn_features`](Self::learn_features).
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights are updated, see [`crate::optim::optimizers`].
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `options`: The regularization, see [`GLMOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::learner::Regressor;
+/// use light_river::linear_model::glm::GLMOptions;
+/// use light_river::linear_model::linear_regression::LinearRegression;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let options = GLMOptions {
+///     intercept_lr: 0.1,
+///     ..Default::default()
+/// };
+/// let mut model = LinearRegression::new(SGD::new(0.1), Squared, options);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, 3.0 * x + 1.0);
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.5)]);
+/// assert!((model.predict_one(&observation) - 2.5).abs() < 0.01);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearRegression<F, O, L = Squared> {
+    glm: Glm<F, O>,
+    loss: L,
+}
+
+impl<F, O, L> LinearRegression<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: RegressionLoss<F>,
+{
+    pub fn new(optimizer: O, loss: L, options: GLMOptions<F>) -> Self {
+        Self {
+            glm: Glm::new(optimizer, options),
+            loss,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.glm.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.glm.intercept
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: RegressionTarget<F>, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Prediction given features which are either dense or sparse.
+    pub fn predict_features(&self, x: &Features<F>) -> RegressionTarget<F> {
+        self.loss.mean(self.glm.predict(&features(x)))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: RegressionTarget<F>, w: F) {
+        let gradient = self.loss.gradient(y, self.glm.predict(x));
+        self.glm.learn(x, gradient, w);
+    }
+}
+
+impl<F, O, L> Regressor<F> for LinearRegression<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: RegressionLoss<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.learn(&observation_features(x), y, F::one());
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        self.loss.mean(self.glm.predict(&observation_features(x)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::losses::{Huber, Poisson, Quantile};
+    use crate::optim::optimizers::SGD;
+    use rand::prelude::*;
+
+    fn options() -> GLMOptions<f64> {
+        GLMOptions {
+            intercept_lr: 0.05,
+            ..Default::default()
+        }
+    }
+
+    // y = 2a - 3b + 1, with every 20th target replaced by a large outlier
+    fn stream(n: usize) -> Vec<(Observation<f64>, f64)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|i| {
+                let (a, b): (f64, f64) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                let y = if i % 20 == 0 {
+                    100.0
+                } else {
+                    2.0 * a - 3.0 * b + 1.0
+                };
+                (x, y)
+            })
+            .collect()
+    }
+
+    fn fit<L: RegressionLoss<f64>>(loss: L) -> LinearRegression<f64, SGD<f64>, L> {
+        let mut model = LinearRegression::new(SGD::new(0.05), loss, options());
+        for (x, y) in stream(5000) {
+            model.learn_one(&x, y);
+        }
+        model
+    }
+
+    #[test]
+    fn test_huber_is_robust() {
+        let error = |weights: &Weights<f64>, intercept: f64| {
+            (weights.get(FeatureKey::Name("a")) - 2.0).abs()
+                + (weights.get(FeatureKey::Name("b")) + 3.0).abs()
+                + (intercept - 1.0).abs()
+        };
+        let squared = fit(Squared);
+        let huber = fit(Huber::new(Some(1.0)));
+        let squared_error = error(squared.weights(), squared.intercept());
+        let huber_error = error(huber.weights(), huber.intercept());
+        assert!(huber_error < 0.5, "{}", huber_error);
+        assert!(huber_error < squared_error);
+    }
+
+    #[test]
+    fn test_quantile_loss() {
+        // y = a + u, with u uniform in [0, 2], whose median is a + 1 and 0.9 quantile a + 1.8
+        let fit = |alpha| {
+            let mut rng = StdRng::seed_from_u64(42);
+            let mut model =
+                LinearRegression::new(SGD::new(0.01), Quantile::new(Some(alpha)), options());
+            for _ in 0..20000 {
+                let a: f64 = rng.gen_range(-1.0..1.0);
+                let y = a + rng.gen_range(0.0..2.0);
+                model.learn_one(&Observation::from([("a".to_string(), a)]), y);
+            }
+            model.predict_one(&Observation::from([("a".to_string(), 0.5)]))
+        };
+        let (median, upper) = (fit(0.5), fit(0.9));
+        assert!((median - 1.5).abs() < 0.1, "{}", median);
+        assert!((upper - 2.3).abs() < 0.1, "{}", upper);
+    }
+
+    #[test]
+    fn test_poisson_loss() {
+        // Counts whose log-rate is linear in the feature
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut model = LinearRegression::new(SGD::new(0.01), Poisson, options());
+        for _ in 0..20000 {
+            let a: f64 = rng.gen_range(0.0..1.0);
+            let rate = (1.0 + a).exp();
+            // Knuth's sampling of a Poisson variable
+            let (threshold, mut count, mut p) = ((-rate).exp(), 0, rng.gen::<f64>());
+            while p > threshold {
+                count += 1;
+                p *= rng.gen::<f64>();
+            }
+            model.learn_one(&Observation::from([("a".to_string(), a)]), count as f64);
+        }
+        let x = Observation::from([("a".to_string(), 0.5)]);
+        let expected = 1.5f64.exp();
+        assert!(
+            (model.predict_one(&x) - expected).abs() < 0.5,
+            "{}",
+            model.predict_one(&x)
+        );
+    }
+
+    #[test]
+    fn test_sparse_features() {
+        let mut model = LinearRegression::new(SGD::new(0.1), Squared, options());
+        for i in 0..2000 {
+            let (index, value) = (i % 5, (i % 7) as f64 / 7.0);
+            let x = SparseVector::from([(index, value)]);
+            model.learn_features(&x.into(), index as f64 * value, 1.0);
+        }
+        let x = SparseVector::from([(3, 0.5)]);
+        assert!((model.predict_features(&x.into()) - 1.5).abs() < 0.1);
+        assert_eq!(model.weights().len(), 5);
+    }
+}
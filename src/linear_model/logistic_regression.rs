@@ -0,0 +1,237 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{
+    binary_label, binary_proba, features, observation_features, GLMOptions, Glm,
+};
+use crate::optim::losses::{BinaryLoss, Log};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Logistic regression, a binary classifier trained by minimizing the log loss.
+///
+/// The probability of the positive class is the logistic function of a linear combination of the
+/// numeric features. Other binary losses can be minimized instead, e.g. the
+/// [`Hinge`](crate::optim::losses::Hinge) loss turns the model into a linear support vector
+/// machine. Categorical and missing features are ignored. The weights are updated by the
+/// optimizer after each sample, and the intercept by plain gradient descent.
+///
+/// The labels are booleans, `true` being the positive class. The model learns from dense
+/// observations through the [`Classifier`] trait, and from sparse vectors too through
+/// [`learn_features`](Self::learn_features).
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights are updated, see [`crate::optim::optimizers`].
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `options`: The regularization, see [`GLMOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen_range(-0.5..0.5);
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, ClassifierTarget::from(x > 0.1));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.4)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(true));
+/// let observation = Observation::from([("x".to_string(), -0.2)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(false));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogisticRegression<F, O, L = Log> {
+    glm: Glm<F, O>,
+    loss: L,
+}
+
+impl<F, O, L> LogisticRegression<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: BinaryLoss<F>,
+{
+    pub fn new(optimizer: O, loss: L, options: GLMOptions<F>) -> Self {
+        Self {
+            glm: Glm::new(optimizer, options),
+            loss,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.glm.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.glm.intercept
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        self.loss.mean(self.glm.predict(&features(x)))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool, w: F) {
+        let gradient = self.loss.gradient(y, self.glm.predict(x));
+        self.glm.learn(x, gradient, w);
+    }
+}
+
+impl<F, O, L> Classifier<F> for LogisticRegression<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: BinaryLoss<F>,
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(
+            &observation_features(x),
+            binary_label(y, "LogisticRegression"),
+            F::one(),
+        );
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let p = self.loss.mean(self.glm.predict(&observation_features(x)));
+        binary_proba(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::losses::Hinge;
+    use crate::optim::optimizers::{AdaGrad, Adam, RMSProp, SGD};
+    use rand::prelude::*;
+
+    // Three features in [-1, 1], of which only the first two are informative
+    fn stream(n: usize) -> Vec<(Observation<f64>, bool)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let (a, b, c): (f64, f64, f64) = (
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                );
+                let x = Observation::from([
+                    ("a".to_string(), a),
+                    ("b".to_string(), b),
+                    ("c".to_string(), c),
+                ]);
+                (x, 2.0 * a - b > 0.5)
+            })
+            .collect()
+    }
+
+    fn accuracy<O: Optimizer<f64>, L: BinaryLoss<f64>>(
+        model: &mut LogisticRegression<f64, O, L>,
+    ) -> f64 {
+        let stream = stream(5000);
+        let mut correct = 0;
+        for (i, (x, y)) in stream.into_iter().enumerate() {
+            if i >= 4000 && model.predict_one(&x) == ClassifierTarget::from(y) {
+                correct += 1;
+            }
+            model.learn_one(&x, ClassifierTarget::from(y));
+        }
+        correct as f64 / 1000.0
+    }
+
+    #[test]
+    fn test_optimizers() {
+        let options = || GLMOptions {
+            intercept_lr: 0.1,
+            ..Default::default()
+        };
+        let accuracies = [
+            accuracy(&mut LogisticRegression::new(SGD::new(0.5), Log, options())),
+            accuracy(&mut LogisticRegression::new(
+                Adam::new(0.05, None, None, None),
+                Log,
+                options(),
+            )),
+            accuracy(&mut LogisticRegression::new(
+                AdaGrad::new(0.5, None),
+                Log,
+                options(),
+            )),
+            accuracy(&mut LogisticRegression::new(
+                RMSProp::new(0.01, None, None),
+                Log,
+                options(),
+            )),
+        ];
+        for accuracy in accuracies {
+            assert!(accuracy > 0.9, "{:?}", accuracies);
+        }
+    }
+
+    #[test]
+    fn test_hinge_loss() {
+        let mut model =
+            LogisticRegression::new(SGD::new(0.1), Hinge::new(None), Default::default());
+        assert!(accuracy(&mut model) > 0.9);
+    }
+
+    #[test]
+    fn test_l1_sparsity() {
+        let mut plain = LogisticRegression::new(SGD::new(0.5), Log, Default::default());
+        let mut sparse = LogisticRegression::new(
+            SGD::new(0.5),
+            Log,
+            GLMOptions {
+                l1: 0.01,
+                ..Default::default()
+            },
+        );
+        accuracy(&mut plain);
+        accuracy(&mut sparse);
+        let c = FeatureKey::Name("c");
+        assert!(
+            sparse.weights().get(c).abs() < plain.weights().get(c).abs() / 2.0,
+            "{} {}",
+            sparse.weights().get(c),
+            plain.weights().get(c)
+        );
+    }
+
+    #[test]
+    fn test_sparse_features() {
+        let mut model = LogisticRegression::new(SGD::new(0.5), Log, Default::default());
+        for i in 0..1000 {
+            // Feature 3 is a sign of the positive class, feature 5 of the negative one
+            let y = i % 2 == 0;
+            let x = SparseVector::from([(if y { 3 } else { 5 }, 1.0), (i % 7, 0.5)]);
+            model.learn_features(&x.into(), y, 1.0);
+        }
+        let p = model.predict_proba_features(&SparseVector::from([(3, 1.0)]).into());
+        assert!(p > 0.9, "{}", p);
+        let p = model.predict_proba_features(&SparseVector::from([(5, 1.0)]).into());
+        assert!(p < 0.1, "{}", p);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_boolean_labels() {
+        let mut model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+        model.learn_one(&Observation::new(), ClassifierTarget::from("yes"));
+    }
+}
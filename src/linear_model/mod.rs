@@ -0,0 +1,8 @@
+pub mod alma;
+pub mod ftrl;
+pub mod glm;
+pub mod linear_regression;
+pub mod logistic_regression;
+pub mod pa;
+pub mod perceptron;
+pub mod softmax_regression;
@@ -0,0 +1,312 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+    RegressionTarget,
+};
+use crate::learner::{Classifier, Regressor};
+use crate::linear_model::glm::{binary_label, binary_proba, features, observation_features};
+use crate::optim::losses::sigmoid;
+use crate::optim::optimizers::Weights;
+use num::{Float, FromPrimitive};
+
+/// How aggressive the updates of the passive-aggressive models are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PAVariant {
+    /// The smallest update which brings the loss of the sample to zero.
+    PA,
+    /// The update of `PA`, capped by `C`.
+    #[default]
+    PAI,
+    /// The update of `PA`, damped by a term which decreases with `C`.
+    PAII,
+}
+
+// Weights of a passive-aggressive model, the intercept being the weight of a feature which is
+// always 1.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct PA<F> {
+    c: F,
+    variant: PAVariant,
+    weights: Weights<F>,
+    intercept: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PA<F> {
+    fn new(c: F, variant: PAVariant) -> Self {
+        assert!(c > F::zero(), "C must be strictly positive");
+        Self {
+            c,
+            variant,
+            weights: Weights::new(),
+            intercept: F::zero(),
+        }
+    }
+    fn predict(&self, x: &[(FeatureKey<'_>, F)]) -> F {
+        self.intercept + self.weights.dot(x.iter())
+    }
+    // Move the weights by `tau` times the sample in the given direction, `tau` being derived from
+    // the loss of the sample.
+    fn update(&mut self, x: &[(FeatureKey<'_>, F)], loss: F, direction: F) {
+        if loss <= F::zero() {
+            return;
+        }
+        let squared_norm = x
+            .iter()
+            .fold(F::one(), |sum, (_, value)| sum + *value * *value);
+        let tau = match self.variant {
+            PAVariant::PA => loss / squared_norm,
+            PAVariant::PAI => (loss / squared_norm).min(self.c),
+            PAVariant::PAII => {
+                let two = F::one() + F::one();
+                loss / (squared_norm + F::one() / (two * self.c))
+            }
+        };
+        for (key, value) in x {
+            *self.weights.get_mut(*key) += tau * direction * *value;
+        }
+        self.intercept += tau * direction;
+    }
+}
+
+/// Passive-aggressive classifier, a binary linear classifier.
+///
+/// The model stays passive when a sample is classified with a margin of at least 1, i.e. when its
+/// hinge loss is zero. Otherwise, it aggressively updates its weights just enough to classify the
+/// sample correctly with a margin of 1, the `C` parameter bounding how far the weights can move in
+/// the `PAI` and `PAII` variants, which makes them robust to noisy labels. The predicted
+/// probability is the logistic function of the margin.
+///
+/// The labels are booleans, `true` being the positive class.
+///
+/// # Parameters
+///
+/// - `c`: The aggressiveness parameter, 1 by default.
+/// - `variant`: See [`PAVariant`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::pa::PAClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: PAClassifier<f64> = PAClassifier::new(None, Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen_range(-0.5..0.5);
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, ClassifierTarget::from(x > 0.0));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.3)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: K. Crammer, O. Dekel, J. Keshet, S. Shalev-Shwartz and Y. Singer (2006). "Online
+/// passive-aggressive algorithms". Journal of machine learning research 7:551-585.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PAClassifier<F> {
+    pa: PA<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PAClassifier<F> {
+    pub fn new(c: Option<F>, variant: PAVariant) -> Self {
+        Self {
+            pa: PA::new(c.unwrap_or(F::one()), variant),
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.pa.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.pa.intercept
+    }
+    /// Learn from a sample whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool) {
+        self.learn(&features(x), y);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        sigmoid(self.pa.predict(&features(x)))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool) {
+        let sign = if y { F::one() } else { -F::one() };
+        let hinge = (F::one() - sign * self.pa.predict(x)).max(F::zero());
+        self.pa.update(x, hinge, sign);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for PAClassifier<F>
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(&observation_features(x), binary_label(y, "PAClassifier"));
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        binary_proba(sigmoid(self.pa.predict(&observation_features(x))))
+    }
+}
+
+/// Passive-aggressive regressor.
+///
+/// The regression counterpart of [`PAClassifier`], using the epsilon-insensitive loss: the model
+/// stays passive when the absolute error is below `epsilon`, and otherwise updates its weights
+/// just enough to bring the error down to `epsilon`, within the limits set by `C`.
+///
+/// # Parameters
+///
+/// - `c`: The aggressiveness parameter, 1 by default.
+/// - `epsilon`: The error below which the model isn't updated, 0.1 by default.
+/// - `variant`: See [`PAVariant`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::learner::Regressor;
+/// use light_river::linear_model::pa::PARegressor;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: PARegressor<f64> = PARegressor::new(None, Some(0.01), Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, 2.0 * x - 1.0);
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.8)]);
+/// assert!((model.predict_one(&observation) - 0.6).abs() < 0.05);
+/// ```
+///
+/// # References
+///
+/// [^1]: K. Crammer, O. Dekel, J. Keshet, S. Shalev-Shwartz and Y. Singer (2006). "Online
+/// passive-aggressive algorithms". Journal of machine learning research 7:551-585.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PARegressor<F> {
+    pa: PA<F>,
+    epsilon: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PARegressor<F> {
+    pub fn new(c: Option<F>, epsilon: Option<F>, variant: PAVariant) -> Self {
+        Self {
+            pa: PA::new(c.unwrap_or(F::one()), variant),
+            epsilon: epsilon.unwrap_or(F::from_f64(0.1).unwrap()),
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.pa.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.pa.intercept
+    }
+    /// Learn from a sample whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: RegressionTarget<F>) {
+        self.learn(&features(x), y);
+    }
+    /// Prediction given features which are either dense or sparse.
+    pub fn predict_features(&self, x: &Features<F>) -> RegressionTarget<F> {
+        self.pa.predict(&features(x))
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: RegressionTarget<F>) {
+        let error = y - self.pa.predict(x);
+        let loss = (error.abs() - self.epsilon).max(F::zero());
+        self.pa.update(x, loss, error.signum());
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Regressor<F>
+    for PARegressor<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.learn(&observation_features(x), y);
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        self.pa.predict(&observation_features(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_passive_when_margin_is_large() {
+        let mut model = PAClassifier::new(None, PAVariant::PA);
+        let x = Observation::from([("a".to_string(), 1.0)]);
+        model.learn_one(&x, ClassifierTarget::from(true));
+        // The hinge loss of 1 is spread over the feature and the intercept, ||x||² = 2
+        assert_eq!(model.weights().get(FeatureKey::Name("a")), 0.5);
+        assert_eq!(model.intercept(), 0.5);
+        // The margin is now exactly 1
+        model.learn_one(&x, ClassifierTarget::from(true));
+        assert_eq!(model.weights().get(FeatureKey::Name("a")), 0.5);
+    }
+
+    #[test]
+    fn test_variants() {
+        let x = Observation::from([("a".to_string(), 3.0)]);
+        let step = |variant| {
+            let mut model = PAClassifier::new(Some(0.05), variant);
+            model.learn_one(&x, ClassifierTarget::from(false));
+            model.weights().get(FeatureKey::Name("a"))
+        };
+        // tau = 1 / 10 for PA, capped at 0.05 for PA-I, and 1 / (10 + 10) for PA-II
+        assert!((step(PAVariant::PA) + 0.3).abs() < 1e-10);
+        assert!((step(PAVariant::PAI) + 0.15).abs() < 1e-10);
+        assert!((step(PAVariant::PAII) + 0.15).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_noisy_labels() {
+        // PA-I with a small C is less disturbed by flipped labels than PA
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples: Vec<(Observation<f64>, bool, bool)> = (0..3000)
+            .map(|_| {
+                let (a, b): (f64, f64) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                let y = a + b > 0.0;
+                (x, y, if rng.gen_bool(0.1) { !y } else { y })
+            })
+            .collect();
+        let accuracy = |variant, c| {
+            let mut model = PAClassifier::new(Some(c), variant);
+            let mut correct = 0;
+            for (i, (x, y, noisy_y)) in samples.iter().enumerate() {
+                if i >= 2000 && model.predict_one(x) == ClassifierTarget::from(*y) {
+                    correct += 1;
+                }
+                model.learn_one(x, ClassifierTarget::from(*noisy_y));
+            }
+            correct as f64 / 1000.0
+        };
+        let (pa, pa_i) = (accuracy(PAVariant::PA, 1.0), accuracy(PAVariant::PAI, 0.01));
+        assert!(pa_i > 0.9, "{}", pa_i);
+        assert!(pa_i > pa, "{} <= {}", pa_i, pa);
+    }
+
+    #[test]
+    fn test_regressor_epsilon() {
+        let mut model = PARegressor::new(None, Some(0.5), PAVariant::PA);
+        let x = Observation::from([("a".to_string(), 1.0)]);
+        model.learn_one(&x, 0.4);
+        assert_eq!(model.predict_one(&x), 0.0);
+        model.learn_one(&x, 2.5);
+        // The error is brought down to epsilon
+        assert!((model.predict_one(&x) - 2.0).abs() < 1e-10);
+    }
+}
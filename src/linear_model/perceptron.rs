@@ -0,0 +1,156 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{binary_label, binary_proba, features, observation_features};
+use crate::optim::losses::sigmoid;
+use crate::optim::optimizers::Weights;
+use num::{Float, FromPrimitive};
+
+/// Rosenblatt's perceptron, a binary linear classifier.
+///
+/// The weights and the intercept are only updated when a sample is misclassified, by adding the
+/// features of the sample, scaled by the learning rate, in the direction of its label. The
+/// predicted probability is the logistic function of the margin, which is only meant to rank the
+/// samples.
+///
+/// The labels are booleans, `true` being the positive class.
+///
+/// # Parameters
+///
+/// - `lr`: The learning rate, 1 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::perceptron::Perceptron;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: Perceptron<f64> = Perceptron::new(None);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen_range(-0.5..0.5);
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     model.learn_one(&observation, ClassifierTarget::from(x > 0.0));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), 0.3)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: F. Rosenblatt (1958). "The perceptron: a probabilistic model for information storage
+/// and organization in the brain". Psychological review 65(6):386-408.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Perceptron<F> {
+    lr: F,
+    weights: Weights<F>,
+    intercept: F,
+    n_mistakes: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Perceptron<F> {
+    pub fn new(lr: Option<F>) -> Self {
+        Self {
+            lr: lr.unwrap_or(F::one()),
+            weights: Weights::new(),
+            intercept: F::zero(),
+            n_mistakes: 0,
+        }
+    }
+    pub fn weights(&self) -> &Weights<F> {
+        &self.weights
+    }
+    pub fn intercept(&self) -> F {
+        self.intercept
+    }
+    /// Number of samples which have been misclassified, i.e. of updates.
+    pub fn n_mistakes(&self) -> usize {
+        self.n_mistakes
+    }
+    /// Learn from a sample whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: bool) {
+        self.learn(&features(x), y);
+    }
+    /// Probability of the positive class, given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> F {
+        sigmoid(self.margin(&features(x)))
+    }
+    fn margin(&self, x: &[(FeatureKey<'_>, F)]) -> F {
+        self.intercept + self.weights.dot(x.iter())
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: bool) {
+        let sign = if y { F::one() } else { -F::one() };
+        if sign * self.margin(x) > F::zero() {
+            return;
+        }
+        self.n_mistakes += 1;
+        for (key, value) in x {
+            *self.weights.get_mut(*key) += self.lr * sign * *value;
+        }
+        self.intercept += self.lr * sign;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for Perceptron<F>
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(&observation_features(x), binary_label(y, "Perceptron"));
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        binary_proba(sigmoid(self.margin(&observation_features(x))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updates_on_mistakes_only() {
+        let mut model = Perceptron::new(Some(0.5));
+        let x = Observation::from([("a".to_string(), 2.0)]);
+        // A margin of zero is a mistake
+        model.learn_one(&x, ClassifierTarget::from(true));
+        assert_eq!(model.weights().get(FeatureKey::Name("a")), 1.0);
+        assert_eq!(model.intercept(), 0.5);
+        model.learn_one(&x, ClassifierTarget::from(true));
+        assert_eq!(model.n_mistakes(), 1);
+        model.learn_one(&x, ClassifierTarget::from(false));
+        assert_eq!(model.weights().get(FeatureKey::Name("a")), 0.0);
+        assert_eq!(model.n_mistakes(), 2);
+    }
+
+    #[test]
+    fn test_converges_on_separable_data() {
+        let mut model = Perceptron::new(None);
+        let samples: Vec<(Observation<f64>, bool)> = (0..100)
+            .map(|i| {
+                let (a, b) = ((i % 10) as f64, (i / 10) as f64);
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, a + 2.0 * b > 10.5)
+            })
+            .collect();
+        // The number of mistakes is finite on linearly separable data
+        for _ in 0..100 {
+            for (x, y) in samples.iter() {
+                model.learn_one(x, ClassifierTarget::from(*y));
+            }
+        }
+        let n_mistakes = model.n_mistakes();
+        for (x, y) in samples.iter() {
+            model.learn_one(x, ClassifierTarget::from(*y));
+        }
+        assert_eq!(model.n_mistakes(), n_mistakes);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Features, Observation,
+};
+use crate::learner::Classifier;
+use crate::linear_model::glm::{features, observation_features, GLMOptions, Glm};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+
+/// Softmax regression, also known as multinomial logistic regression.
+///
+/// Each class has its own weights and intercept, and the class probabilities are the softmax of
+/// the linear combinations of the numeric features. The model is trained by minimizing the cross
+/// entropy, and each class is updated by its own copy of the optimizer. New classes can appear at
+/// any point of the stream.
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights are updated, see [`crate::optim::optimizers`].
+/// - `options`: The regularization, see [`GLMOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::softmax_regression::SoftmaxRegression;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model = SoftmaxRegression::new(SGD::new(0.1), Default::default());
+/// let colors = ["red", "green", "blue"];
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen_range(-1.5..1.5);
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     let color = colors[if x < -0.5 { 0 } else if x < 0.5 { 1 } else { 2 }];
+///     model.learn_one(&observation, ClassifierTarget::from(color));
+/// }
+///
+/// let observation = Observation::from([("x".to_string(), -1.2)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from("red"));
+/// let observation = Observation::from([("x".to_string(), 1.2)]);
+/// assert_eq!(model.predict_one(&observation), ClassifierTarget::from("blue"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftmaxRegression<F, O> {
+    // Untrained model, from which the models of new classes are copied
+    prototype: Glm<F, O>,
+    classes: HashMap<ClassifierTarget, Glm<F, O>>,
+}
+
+impl<F, O> SoftmaxRegression<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    pub fn new(optimizer: O, options: GLMOptions<F>) -> Self {
+        Self {
+            prototype: Glm::new(optimizer, options),
+            classes: HashMap::new(),
+        }
+    }
+    /// Weights of a class, if it has been seen.
+    pub fn weights(&self, y: &ClassifierTarget) -> Option<&Weights<F>> {
+        self.classes.get(y).map(|glm| &glm.weights)
+    }
+    /// Learn from a weighted sample, whose features are either dense or sparse.
+    pub fn learn_features(&mut self, x: &Features<F>, y: ClassifierTarget, w: F) {
+        self.learn(&features(x), y, w);
+    }
+    /// Class probabilities given features which are either dense or sparse.
+    pub fn predict_proba_features(&self, x: &Features<F>) -> ClassifierTargetProbabilities<F> {
+        self.proba(&features(x))
+    }
+    fn proba(&self, x: &[(FeatureKey<'_>, F)]) -> ClassifierTargetProbabilities<F> {
+        let scores: Vec<(&ClassifierTarget, F)> = self
+            .classes
+            .iter()
+            .map(|(y, glm)| (y, glm.predict(x)))
+            .collect();
+        let max = scores
+            .iter()
+            .fold(F::neg_infinity(), |max, (_, s)| max.max(*s));
+        let total = scores
+            .iter()
+            .fold(F::zero(), |sum, (_, s)| sum + (*s - max).exp());
+        scores
+            .into_iter()
+            .map(|(y, s)| (y.clone(), (s - max).exp() / total))
+            .collect()
+    }
+    fn learn(&mut self, x: &[(FeatureKey<'_>, F)], y: ClassifierTarget, w: F) {
+        if !self.classes.contains_key(&y) {
+            self.classes.insert(y.clone(), self.prototype.clone());
+        }
+        let proba = self.proba(x);
+        for (class, glm) in self.classes.iter_mut() {
+            // Derivative of the cross entropy with respect to the score of the class
+            let target = if *class == y { F::one() } else { F::zero() };
+            glm.learn(x, proba[class] - target, w);
+        }
+    }
+}
+
+impl<F, O> Classifier<F> for SoftmaxRegression<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn(&observation_features(x), y, F::one());
+    }
+    /// Probability of each class seen so far. Empty until the model has learned from a sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.proba(&observation_features(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SparseVector;
+    use crate::optim::optimizers::{Adam, SGD};
+    use rand::prelude::*;
+
+    #[test]
+    fn test_new_classes() {
+        let mut model = SoftmaxRegression::new(SGD::new(0.1), Default::default());
+        let x = Observation::from([("a".to_string(), 1.0)]);
+        assert!(model.predict_proba(&x).is_empty());
+        model.learn_one(&x, ClassifierTarget::from(0));
+        // A single class is certain
+        assert_eq!(model.predict_proba(&x)[&ClassifierTarget::from(0)], 1.0);
+        model.learn_one(&x, ClassifierTarget::from(1));
+        let proba = model.predict_proba(&x);
+        assert_eq!(proba.len(), 2);
+        assert!((proba.values().sum::<f64>() - 1.0).abs() < 1e-10);
+        assert!(model.weights(&ClassifierTarget::from(2)).is_none());
+    }
+
+    #[test]
+    fn test_multiclass() {
+        // Three Gaussian blobs
+        let centers = [(0.0, 1.0), (1.0, -1.0), (-1.0, -1.0)];
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut model =
+            SoftmaxRegression::new(Adam::new(0.05, None, None, None), Default::default());
+        let mut correct = 0;
+        for i in 0..5000 {
+            let y = i % 3;
+            let (ca, cb) = centers[y];
+            let a = ca + rng.gen_range(-0.5..0.5);
+            let b = cb + rng.gen_range(-0.5..0.5);
+            let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+            if i >= 4000 && model.predict_one(&x) == ClassifierTarget::from(y as i32) {
+                correct += 1;
+            }
+            model.learn_one(&x, ClassifierTarget::from(y as i32));
+        }
+        assert!(correct > 950, "{}", correct);
+    }
+
+    #[test]
+    fn test_sparse_features() {
+        let mut model = SoftmaxRegression::new(SGD::new(0.5), Default::default());
+        for i in 0..1500 {
+            let y = i % 3;
+            let x = SparseVector::from([(y, 1.0), (10 + i % 4, 1.0)]);
+            model.learn_features(&x.into(), ClassifierTarget::from(y as i32), 1.0);
+        }
+        let proba = model.predict_proba_features(&SparseVector::from([(2, 1.0)]).into());
+        assert!(proba[&ClassifierTarget::from(2)] > 0.8, "{:?}", proba);
+    }
+}
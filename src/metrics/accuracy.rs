@@ -1,45 +1,115 @@
 use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 
 use crate::common::{ClassifierOutput, ClassifierTarget};
-use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
 use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::Mean;
 use num::{Float, FromPrimitive};
 
-struct Accuracy<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
-    cm: ConfusionMatrix<F>,
+/// Accuracy, i.e. the fraction of samples whose class is correctly predicted.
+///
+/// In top-k mode, a sample is correctly predicted if the true class is among the `k` most
+/// probable classes of the output. Classes whose probability is tied with the one of the true
+/// class are given the benefit of the doubt. Outputs which only hold a prediction are correct if
+/// the prediction is the true class, whatever `k`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::accuracy::Accuracy;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use maplit::hashmap;
+///
+/// let y_pred: ClassifierOutput<f64> = ClassifierOutput::Probabilities(hashmap! {
+///     ClassifierTarget::from("cat") => 0.5,
+///     ClassifierTarget::from("dog") => 0.3,
+///     ClassifierTarget::from("cow") => 0.2,
+/// });
+/// let y_true = ClassifierTarget::from("dog");
+///
+/// let mut accuracy = Accuracy::new();
+/// accuracy.update(&y_pred, &y_true, None);
+/// assert_eq!(accuracy.get(), 0.0);
+///
+/// let mut top_2 = Accuracy::top_k(2);
+/// top_2.update(&y_pred, &y_true, None);
+/// assert_eq!(top_2.get(), 1.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Accuracy<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    k: usize,
+    mean: Mean<F>,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Accuracy<F> {
     pub fn new() -> Self {
+        Self::top_k(1)
+    }
+    pub fn top_k(k: usize) -> Self {
+        assert!(k > 0, "k must be strictly positive");
         Self {
-            cm: ConfusionMatrix::new(),
+            k,
+            mean: Mean::new(),
+        }
+    }
+    pub fn k(&self) -> usize {
+        self.k
+    }
+    fn is_hit(&self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> bool {
+        match y_pred {
+            ClassifierOutput::Prediction(label) => label == y_true,
+            ClassifierOutput::Probabilities(probabilities) => match probabilities.get(y_true) {
+                Some(p_true) => probabilities.values().filter(|p| *p > p_true).count() < self.k,
+                None => false,
+            },
+        }
+    }
+    fn score(&self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> F {
+        if self.is_hit(y_pred, y_true) {
+            F::one()
+        } else {
+            F::zero()
         }
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for Accuracy<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // implement for trait ClassificationMetric
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
     ClassificationMetric<F> for Accuracy<F>
 {
     fn update(
         &mut self,
-        y_true: &ClassifierTarget,
         y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     ) {
-        self.cm.update(y_true, y_pred, sample_weight);
+        self.mean.update(
+            self.score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
     }
     fn revert(
         &mut self,
-        y_true: &ClassifierTarget,
         y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     ) {
-        self.cm.revert(y_true, y_pred, sample_weight);
+        self.mean.revert(
+            self.score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
     }
     fn get(&self) -> F {
-        self.cm
-            .total_true_positives()
-            .div(F::from(self.cm.total_weight).unwrap())
+        self.mean.get()
     }
 
     fn is_multiclass(&self) -> bool {
@@ -47,21 +117,72 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for Accuracy<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use maplit::hashmap;
+
     #[test]
     fn test_accuracy_binary_classification() {
         let mut accuracy: Accuracy<f64> = Accuracy::new();
-        let y_trues = vec![ClassifierTarget::Bool(true), ClassifierTarget::Bool(false)];
-        let y_preds = vec![ClassifierTarget::Bool(true), ClassifierTarget::Bool(true)];
+        let y_trues = [ClassifierTarget::Bool(true), ClassifierTarget::Bool(false)];
+        let y_preds = [ClassifierTarget::Bool(true), ClassifierTarget::Bool(true)];
         let real_accuracy = 0.5;
         for (y_true, y_pred) in y_trues.iter().zip(y_preds.iter()) {
-            accuracy.update(&ClassifierOutput::Prediction(y_true.clone()), y_pred, None);
+            accuracy.update(&ClassifierOutput::Prediction(y_pred.clone()), y_true, None);
         }
         assert_eq!(accuracy.get(), real_accuracy);
     }
 
     #[test]
-    fn test_accuracy_multiclassifiaction() {}
+    fn test_accuracy_multiclassifiaction() {
+        let mut accuracy: Accuracy<f64> = Accuracy::new();
+        let y_trues = ["cat", "ant", "cat", "cat", "ant", "bird"];
+        let y_preds = ["ant", "ant", "cat", "cat", "ant", "cat"];
+        for (y_true, y_pred) in y_trues.iter().zip(y_preds.iter()) {
+            accuracy.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*y_pred)),
+                &ClassifierTarget::from(*y_true),
+                None,
+            );
+        }
+        assert!((accuracy.get() - 4.0 / 6.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_top_k_accuracy() {
+        let y_pred: ClassifierOutput<f64> = ClassifierOutput::Probabilities(hashmap! {
+            ClassifierTarget::from("cat") => 0.4,
+            ClassifierTarget::from("dog") => 0.3,
+            ClassifierTarget::from("cow") => 0.3,
+        });
+        let samples = [("cat", 1.0, 1.0), ("cow", 0.0, 1.0), ("bird", 0.0, 0.0)];
+        for (y_true, top_1, top_2) in samples {
+            let y_true = ClassifierTarget::from(y_true);
+            let mut accuracy = Accuracy::new();
+            accuracy.update(&y_pred, &y_true, None);
+            assert_eq!(accuracy.get(), top_1);
+            let mut accuracy = Accuracy::top_k(2);
+            accuracy.update(&y_pred, &y_true, None);
+            assert_eq!(accuracy.get(), top_2);
+        }
+    }
+
+    #[test]
+    fn test_accuracy_revert() {
+        let mut accuracy: Accuracy<f64> = Accuracy::top_k(3);
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from(1));
+        accuracy.update(&y_pred, &ClassifierTarget::from(1), Some(3.0));
+        accuracy.update(&y_pred, &ClassifierTarget::from(2), None);
+        accuracy.revert(&y_pred, &ClassifierTarget::from(2), None);
+        assert_eq!(accuracy.get(), 1.0);
+    }
 }
@@ -0,0 +1,300 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::AnomalyMetric;
+use crate::metrics::utils::safe_div;
+use num::{Float, FromPrimitive};
+
+// Weighted counts of the samples which are flagged as anomalies at each threshold, i.e. whose
+// score is above the threshold.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct ThresholdCounts<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    thresholds: Vec<F>,
+    true_positives: Vec<F>,
+    false_positives: Vec<F>,
+    n_anomalies: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ThresholdCounts<F> {
+    fn new(mut thresholds: Vec<F>) -> Self {
+        assert!(!thresholds.is_empty(), "at least one threshold is needed");
+        thresholds.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n_threshold = thresholds.len();
+        Self {
+            thresholds,
+            true_positives: vec![F::zero(); n_threshold],
+            false_positives: vec![F::zero(); n_threshold],
+            n_anomalies: F::zero(),
+        }
+    }
+    // Same thresholds as `ROCAUC`, evenly spread over [0, 1].
+    fn uniform(n_threshold: Option<usize>) -> Self {
+        let n_threshold = n_threshold.unwrap_or(10);
+        assert!(n_threshold >= 2, "at least 2 thresholds are needed");
+        let mut thresholds: Vec<F> = (0..n_threshold)
+            .map(|i| F::from(i).unwrap() / F::from(n_threshold - 1).unwrap())
+            .collect();
+        thresholds[0] -= F::from(1e-7).unwrap();
+        thresholds[n_threshold - 1] += F::from(1e-7).unwrap();
+        Self::new(thresholds)
+    }
+    fn update(&mut self, score: F, is_anomaly: bool, weight: F) {
+        for (i, threshold) in self.thresholds.iter().enumerate() {
+            if score > *threshold {
+                if is_anomaly {
+                    self.true_positives[i] += weight;
+                } else {
+                    self.false_positives[i] += weight;
+                }
+            }
+        }
+        if is_anomaly {
+            self.n_anomalies += weight;
+        }
+    }
+    fn precision(&self, i: usize) -> F {
+        safe_div(
+            self.true_positives[i],
+            self.true_positives[i] + self.false_positives[i],
+        )
+    }
+    fn recall(&self, i: usize) -> F {
+        safe_div(self.true_positives[i], self.n_anomalies)
+    }
+    fn f1(&self, i: usize) -> F {
+        let tp = self.true_positives[i];
+        safe_div(tp + tp, tp + self.false_positives[i] + self.n_anomalies)
+    }
+    fn decay(&mut self, factor: F) {
+        self.true_positives.iter_mut().for_each(|tp| *tp *= factor);
+        self.false_positives.iter_mut().for_each(|fp| *fp *= factor);
+        self.n_anomalies *= factor;
+    }
+}
+
+/// Area under the precision-recall curve.
+///
+/// The curve is discretized with a fixed set of thresholds, a sample being flagged as an anomaly
+/// at a given threshold if its score is above it. The area is computed as the average precision,
+/// i.e. the sum of the precisions at each threshold weighted by the recall lost at the next one.
+/// Unlike ROC AUC, the precision-recall curve is sensitive to the rarity of anomalies, which makes
+/// it more informative for heavily imbalanced streams.
+///
+/// # Parameters
+///
+/// - `n_threshold`: The number of thresholds, evenly spread over `[0, 1]`. Defaults to 10 and
+///   must be at least 2. Use `with_thresholds` for scores which don't lie in `[0, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::anomaly::PRAUC;
+/// use light_river::metrics::traits::AnomalyMetric;
+///
+/// let scores = vec![0.1, 0.4, 0.35, 0.8];
+/// let is_anomaly = vec![false, false, true, true];
+///
+/// let mut metric: PRAUC<f64> = PRAUC::new(None);
+/// for (score, is_anomaly) in scores.iter().zip(is_anomaly.iter()) {
+///     metric.update(*score, *is_anomaly, None);
+/// }
+/// assert!((metric.get() - 0.8333333333333333).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PRAUC<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    counts: ThresholdCounts<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PRAUC<F> {
+    pub fn new(n_threshold: Option<usize>) -> Self {
+        Self {
+            counts: ThresholdCounts::uniform(n_threshold),
+        }
+    }
+    /// Use the given thresholds instead of evenly spread ones.
+    pub fn with_thresholds(thresholds: Vec<F>) -> Self {
+        Self {
+            counts: ThresholdCounts::new(thresholds),
+        }
+    }
+    pub fn thresholds(&self) -> &[F] {
+        &self.counts.thresholds
+    }
+    /// The (precision, recall) pairs of the curve, from the lowest to the highest threshold.
+    pub fn curve(&self) -> Vec<(F, F)> {
+        (0..self.counts.thresholds.len())
+            .map(|i| (self.counts.precision(i), self.counts.recall(i)))
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyMetric<F>
+    for PRAUC<F>
+{
+    fn update(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.counts
+            .update(score, is_anomaly, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.counts
+            .update(score, is_anomaly, -sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        // The recall decreases with the threshold, and is 0 past the last one
+        let n_threshold = self.counts.thresholds.len();
+        (0..n_threshold).fold(F::zero(), |area, i| {
+            let next_recall = if i + 1 < n_threshold {
+                self.counts.recall(i + 1)
+            } else {
+                F::zero()
+            };
+            area + (self.counts.recall(i) - next_recall) * self.counts.precision(i)
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for PRAUC<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.counts.decay(factor);
+    }
+}
+
+/// Best F1 score achievable by flagging the samples whose score is above one of the thresholds.
+///
+/// This tells how good the scores are once a threshold has been picked, and `best_threshold`
+/// tells which threshold to pick.
+///
+/// # Parameters
+///
+/// - `n_threshold`: The number of thresholds, evenly spread over `[0, 1]`. Defaults to 10 and
+///   must be at least 2. Use `with_thresholds` for scores which don't lie in `[0, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::anomaly::BestF1;
+/// use light_river::metrics::traits::AnomalyMetric;
+///
+/// let scores = vec![0.1, 0.4, 0.35, 0.8];
+/// let is_anomaly = vec![false, false, true, true];
+///
+/// let mut metric: BestF1<f64> = BestF1::with_thresholds(vec![0.2, 0.5]);
+/// for (score, is_anomaly) in scores.iter().zip(is_anomaly.iter()) {
+///     metric.update(*score, *is_anomaly, None);
+/// }
+/// assert!((metric.get() - 0.8).abs() < 1e-10);
+/// assert_eq!(metric.best_threshold(), Some(0.2));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BestF1<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    counts: ThresholdCounts<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> BestF1<F> {
+    pub fn new(n_threshold: Option<usize>) -> Self {
+        Self {
+            counts: ThresholdCounts::uniform(n_threshold),
+        }
+    }
+    /// Use the given thresholds instead of evenly spread ones.
+    pub fn with_thresholds(thresholds: Vec<F>) -> Self {
+        Self {
+            counts: ThresholdCounts::new(thresholds),
+        }
+    }
+    pub fn thresholds(&self) -> &[F] {
+        &self.counts.thresholds
+    }
+    /// The lowest threshold which achieves the best F1 score, if any anomaly has been seen.
+    pub fn best_threshold(&self) -> Option<F> {
+        self.best().map(|i| self.counts.thresholds[i])
+    }
+    fn best(&self) -> Option<usize> {
+        if self.counts.n_anomalies <= F::zero() {
+            return None;
+        }
+        (0..self.counts.thresholds.len()).fold(None, |best, i| match best {
+            Some(j) if self.counts.f1(j) >= self.counts.f1(i) => Some(j),
+            _ => Some(i),
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyMetric<F>
+    for BestF1<F>
+{
+    fn update(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.counts
+            .update(score, is_anomaly, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.counts
+            .update(score, is_anomaly, -sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.best().map_or(F::zero(), |i| self.counts.f1(i))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for BestF1<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.counts.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const SCORES: [f64; 4] = [0.1, 0.4, 0.35, 0.8];
+    const IS_ANOMALY: [bool; 4] = [false, false, true, true];
+
+    fn feed<M: AnomalyMetric<f64>>(metric: &mut M) {
+        for (score, is_anomaly) in SCORES.iter().zip(IS_ANOMALY.iter()) {
+            metric.update(*score, *is_anomaly, None);
+        }
+    }
+
+    #[test]
+    fn test_prauc_matches_average_precision() {
+        // With enough thresholds, same value as scikit-learn's average_precision_score
+        let mut metric = PRAUC::new(Some(1000));
+        for (i, (score, is_anomaly)) in SCORES.iter().zip(IS_ANOMALY.iter()).enumerate() {
+            metric.update(*score, *is_anomaly, Some(1.0 + i as f64));
+        }
+        // Ranking: 0.8 (4, anomaly), 0.4 (2), 0.35 (3, anomaly), 0.1 (1)
+        assert_close(metric.get(), 4.0 / 7.0 + 3.0 / 7.0 * 7.0 / 9.0);
+    }
+
+    #[test]
+    fn test_prauc_revert() {
+        let mut metric = PRAUC::new(None);
+        feed(&mut metric);
+        let expected = metric.get();
+        metric.update(0.9, false, Some(3.0));
+        metric.revert(0.9, false, Some(3.0));
+        assert_close(metric.get(), expected);
+    }
+
+    #[test]
+    fn test_best_f1() {
+        let mut metric = BestF1::new(None);
+        assert_eq!(metric.best_threshold(), None);
+        feed(&mut metric);
+        assert_close(metric.get(), 0.8);
+        assert_close(metric.best_threshold().unwrap(), 1.0 / 9.0);
+
+        // A single threshold is just the F1 score at this threshold
+        let mut metric = BestF1::with_thresholds(vec![0.5]);
+        feed(&mut metric);
+        assert_close(metric.get(), 2.0 / 3.0);
+    }
+}
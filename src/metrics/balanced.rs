@@ -0,0 +1,263 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::report::recall_of;
+use crate::metrics::traits::ClassificationMetric;
+use num::{Float, FromPrimitive};
+
+// Recall of each class which has been seen as a true label so far. Classes which have only been
+// predicted have an undefined recall and are left out.
+fn recalls<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    cm: &ConfusionMatrix<F>,
+) -> Vec<F> {
+    cm.get_classes()
+        .iter()
+        .filter(|label| cm.support(label) > F::zero())
+        .map(|label| recall_of(cm, label))
+        .collect()
+}
+
+/// Balanced accuracy, i.e. the average of the recall obtained on each class.
+///
+/// Contrary to the accuracy, it is not inflated by the majority class, which makes it suitable
+/// for imbalanced streams. Classes are taken into account as soon as they appear as a true label.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::balanced::BalancedAccuracy;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![0, 1, 0, 0, 1, 0];
+/// let y_pred = vec![0, 1, 0, 0, 0, 1];
+///
+/// let mut metric: BalancedAccuracy<f64> = BalancedAccuracy::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert_eq!(metric.get(), 0.625);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BalancedAccuracy<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    cm: ConfusionMatrix<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> BalancedAccuracy<F> {
+    pub fn new() -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for BalancedAccuracy<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for BalancedAccuracy<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let recalls = recalls(&self.cm);
+        if recalls.is_empty() {
+            return F::zero();
+        }
+        let n = F::from(recalls.len()).unwrap();
+        recalls.into_iter().fold(F::zero(), |sum, r| sum + r) / n
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+/// Geometric mean of the recall obtained on each class.
+///
+/// The score drops to 0 as soon as one of the classes is never correctly predicted, which makes
+/// it a harsh but informative metric for imbalanced streams. Classes are taken into account as soon
+/// as they appear as a true label.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::balanced::GeometricMean;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec!["cat", "ant", "cat", "cat", "ant", "bird", "bird"];
+/// let y_pred = vec!["ant", "ant", "cat", "cat", "ant", "cat", "bird"];
+///
+/// let mut metric: GeometricMean<f64> = GeometricMean::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// // Cube root of 1 * 2/3 * 1/2
+/// assert!((metric.get() - 0.6933612743506348).abs() < 1e-10);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeometricMean<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> GeometricMean<F> {
+    pub fn new() -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for GeometricMean<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for GeometricMean<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let recalls = recalls(&self.cm);
+        if recalls.is_empty() {
+            return F::zero();
+        }
+        let n = F::from(recalls.len()).unwrap();
+        recalls
+            .into_iter()
+            .fold(F::one(), |product, r| product * r)
+            .powf(F::one() / n)
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for BalancedAccuracy<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for GeometricMean<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn feed<M: ClassificationMetric<f64>>(metric: &mut M, y_true: &[&str], y_pred: &[&str]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_class_appears() {
+        let mut balanced = BalancedAccuracy::new();
+        let mut gmean = GeometricMean::new();
+        feed(
+            &mut balanced,
+            &["ok", "ok", "ok", "ok"],
+            &["ok", "ok", "ok", "ok"],
+        );
+        feed(
+            &mut gmean,
+            &["ok", "ok", "ok", "ok"],
+            &["ok", "ok", "ok", "ok"],
+        );
+        assert_close(balanced.get(), 1.0);
+        assert_close(gmean.get(), 1.0);
+
+        // A fraud shows up and is missed
+        feed(&mut balanced, &["fraud"], &["ok"]);
+        feed(&mut gmean, &["fraud"], &["ok"]);
+        assert_close(balanced.get(), 0.5);
+        assert_close(gmean.get(), 0.0);
+
+        // A class which is only predicted doesn't count
+        feed(&mut balanced, &["ok"], &["unknown"]);
+        assert_close(balanced.get(), (0.8 + 0.0) / 2.0);
+    }
+
+    #[test]
+    fn test_revert() {
+        let mut metric = GeometricMean::new();
+        feed(&mut metric, &["a", "b", "b", "a"], &["a", "b", "a", "a"]);
+        let expected = metric.get();
+        assert_close(expected, 0.5_f64.sqrt());
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from("c"));
+        metric.update(&y_pred, &ClassifierTarget::from("c"), Some(3.0));
+        metric.revert(&y_pred, &ClassifierTarget::from("c"), Some(3.0));
+        assert_close(metric.get(), expected);
+    }
+}
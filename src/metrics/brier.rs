@@ -0,0 +1,158 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::{true_class_probability, Mean};
+use num::{Float, FromPrimitive};
+
+/// Brier score, i.e. the mean squared difference between the predicted probabilities and the
+/// one-hot encoded true labels.
+///
+/// This is the original multi-class definition, where the squared differences are summed over all
+/// the classes. It lies between 0 (perfect) and 2. In the binary case, it is twice the score that
+/// only looks at the probability of the positive class.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::brier::BrierScore;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use std::collections::HashMap;
+///
+/// let y_true = vec!["cat", "dog"];
+/// let y_pred = vec![
+///     HashMap::from([(ClassifierTarget::from("cat"), 0.8), (ClassifierTarget::from("dog"), 0.2)]),
+///     HashMap::from([(ClassifierTarget::from("cat"), 0.5), (ClassifierTarget::from("bird"), 0.5)]),
+/// ];
+///
+/// let mut metric: BrierScore<f64> = BrierScore::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.into_iter()) {
+///     metric.update(
+///         &ClassifierOutput::Probabilities(yp),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// // ((0.2² + 0.2²) + (0.5² + 0.5² + 1²)) / 2
+/// assert!((metric.get() - 0.79).abs() < 1e-10);
+/// ```
+///
+/// # Notes
+///
+/// Classes that are missing from the output are given a probability of 0, except when the output
+/// holds the probability of a single class, in which case the complement is given to the true label.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrierScore<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> BrierScore<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+    fn score(y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> F {
+        let probabilities = y_pred.get_probabilities();
+        let p_true = true_class_probability(&probabilities, y_true);
+        if probabilities.len() == 1 {
+            // Binary case where the probability of the other class is implied
+            return F::from(2.0).unwrap() * (F::one() - p_true).powi(2);
+        }
+        probabilities
+            .iter()
+            .filter(|(label, _)| *label != y_true)
+            .fold((F::one() - p_true).powi(2), |sum, (_, p)| sum + p.powi(2))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for BrierScore<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for BrierScore<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            Self::score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            Self::score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for BrierScore<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_binary_single_class_output() {
+        // Only the probability of the positive class is given, the complement is implied
+        let mut partial = BrierScore::new();
+        let mut full = BrierScore::new();
+        for (yt, p) in [(true, 0.9), (false, 0.3), (true, 0.4)] {
+            let y_true = ClassifierTarget::from(yt);
+            let one = HashMap::from([(ClassifierTarget::from(true), p)]);
+            let both = HashMap::from([
+                (ClassifierTarget::from(true), p),
+                (ClassifierTarget::from(false), 1.0 - p),
+            ]);
+            partial.update(&ClassifierOutput::Probabilities(one), &y_true, None);
+            full.update(&ClassifierOutput::Probabilities(both), &y_true, None);
+        }
+        assert_close(partial.get(), full.get());
+        assert_close(full.get(), 2.0 * (0.01 + 0.09 + 0.36) / 3.0);
+    }
+
+    #[test]
+    fn test_weighted_revert() {
+        let mut metric = BrierScore::new();
+        let good = ClassifierOutput::Prediction(ClassifierTarget::from("cat"));
+        let bad = ClassifierOutput::Prediction(ClassifierTarget::from("dog"));
+        metric.update(&good, &ClassifierTarget::from("cat"), Some(3.0));
+        metric.update(&bad, &ClassifierTarget::from("cat"), Some(1.0));
+        assert_close(metric.get(), 0.5);
+        metric.revert(&bad, &ClassifierTarget::from("cat"), Some(1.0));
+        assert_close(metric.get(), 0.0);
+    }
+}
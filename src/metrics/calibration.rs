@@ -0,0 +1,243 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::safe_div;
+use num::{Float, FromPrimitive};
+
+/// A confidence bin of [`ExpectedCalibrationError`], i.e. a point of a reliability diagram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationBin<F> {
+    /// Lower bound of the confidences falling in the bin.
+    pub lower: F,
+    /// Upper bound of the confidences falling in the bin.
+    pub upper: F,
+    /// Total weight of the samples falling in the bin.
+    pub weight: F,
+    /// Average confidence of the samples falling in the bin.
+    pub confidence: F,
+    /// Fraction of the samples falling in the bin that are correctly predicted.
+    pub accuracy: F,
+}
+
+/// Expected calibration error.
+///
+/// The confidence of a prediction is the probability of the most probable class. Confidences are
+/// grouped in `n_bins` bins of equal width, and the error is the average gap between the accuracy
+/// and the confidence of each bin, weighted by the number of samples in the bin. The maximum
+/// calibration error, i.e. the largest gap, is available with `mce`. The bins themselves are
+/// available with `bins`, e.g. to draw a reliability diagram.
+///
+/// Outputs which only hold a prediction are considered to have a confidence of 1.
+///
+/// # Parameters
+///
+/// - `n_bins`: The number of bins. Defaults to 10.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::calibration::ExpectedCalibrationError;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use maplit::hashmap;
+///
+/// let mut metric: ExpectedCalibrationError<f64> = ExpectedCalibrationError::new(None);
+/// for (p, y_true) in vec![(0.9, true), (0.8, false), (0.35, false), (0.95, true)] {
+///     let y_pred = ClassifierOutput::Probabilities(hashmap! {
+///         ClassifierTarget::from(true) => p,
+///         ClassifierTarget::from(false) => 1.0 - p,
+///     });
+///     metric.update(&y_pred, &ClassifierTarget::from(y_true), None);
+/// }
+/// assert!((metric.get() - 0.325).abs() < 1e-10);
+/// assert!((metric.mce() - 0.8).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: M. P. Naeini, G. F. Cooper and M. Hauskrecht (2015). "Obtaining Well Calibrated
+/// Probabilities Using Bayesian Binning". AAAI Conference on Artificial Intelligence.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectedCalibrationError<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    weights: Vec<F>,
+    confidences: Vec<F>,
+    hits: Vec<F>,
+    total_weight: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ExpectedCalibrationError<F>
+{
+    pub fn new(n_bins: Option<usize>) -> Self {
+        let n_bins = n_bins.unwrap_or(10);
+        assert!(n_bins > 0, "n_bins must be strictly positive");
+        Self {
+            weights: vec![F::zero(); n_bins],
+            confidences: vec![F::zero(); n_bins],
+            hits: vec![F::zero(); n_bins],
+            total_weight: F::zero(),
+        }
+    }
+    pub fn n_bins(&self) -> usize {
+        self.weights.len()
+    }
+    /// Maximum calibration error, i.e. the largest gap between the accuracy and the confidence of
+    /// a non-empty bin.
+    pub fn mce(&self) -> F {
+        self.bins()
+            .iter()
+            .filter(|bin| bin.weight != F::zero())
+            .fold(F::zero(), |max, bin| {
+                max.max((bin.accuracy - bin.confidence).abs())
+            })
+    }
+    /// The bins, from the lowest to the highest confidences.
+    pub fn bins(&self) -> Vec<CalibrationBin<F>> {
+        let width = F::one() / F::from(self.n_bins()).unwrap();
+        (0..self.n_bins())
+            .map(|i| CalibrationBin {
+                lower: F::from(i).unwrap() * width,
+                upper: F::from(i + 1).unwrap() * width,
+                weight: self.weights[i],
+                confidence: safe_div(self.confidences[i], self.weights[i]),
+                accuracy: safe_div(self.hits[i], self.weights[i]),
+            })
+            .collect()
+    }
+    fn _update(&mut self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget, weight: F) {
+        let (prediction, confidence) = match y_pred {
+            ClassifierOutput::Prediction(label) => (label.clone(), F::one()),
+            ClassifierOutput::Probabilities(probabilities) => {
+                let prediction = y_pred.get_predicition();
+                let confidence = probabilities[&prediction];
+                (prediction, confidence)
+            }
+        };
+        let n_bins = self.n_bins();
+        let bin = (confidence * F::from(n_bins).unwrap())
+            .floor()
+            .to_usize()
+            .unwrap_or(0)
+            .min(n_bins - 1);
+
+        self.weights[bin] += weight;
+        self.confidences[bin] += weight * confidence;
+        if prediction == *y_true {
+            self.hits[bin] += weight;
+        }
+        self.total_weight += weight;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for ExpectedCalibrationError<F>
+{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for ExpectedCalibrationError<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self._update(y_pred, y_true, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self._update(y_pred, y_true, -sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        let gaps = self.bins().iter().fold(F::zero(), |sum, bin| {
+            sum + bin.weight * (bin.accuracy - bin.confidence).abs()
+        });
+        safe_div(gaps, self.total_weight)
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for ExpectedCalibrationError<F>
+{
+    fn decay(&mut self, factor: F) {
+        for i in 0..self.n_bins() {
+            self.weights[i] *= factor;
+            self.confidences[i] *= factor;
+            self.hits[i] *= factor;
+        }
+        self.total_weight *= factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+    use maplit::hashmap;
+
+    fn output(cat: f64, dog: f64, cow: f64) -> ClassifierOutput<f64> {
+        ClassifierOutput::Probabilities(hashmap! {
+            ClassifierTarget::from("cat") => cat,
+            ClassifierTarget::from("dog") => dog,
+            ClassifierTarget::from("cow") => cow,
+        })
+    }
+
+    #[test]
+    fn test_bins() {
+        let mut metric = ExpectedCalibrationError::new(Some(4));
+        metric.update(&output(0.6, 0.3, 0.1), &ClassifierTarget::from("cat"), None);
+        metric.update(&output(0.2, 0.7, 0.1), &ClassifierTarget::from("cow"), None);
+        metric.update(&output(0.1, 0.1, 0.8), &ClassifierTarget::from("cow"), None);
+        metric.update(
+            &ClassifierOutput::Prediction(ClassifierTarget::from("dog")),
+            &ClassifierTarget::from("dog"),
+            Some(2.0),
+        );
+
+        let bins = metric.bins();
+        assert_eq!(bins.len(), 4);
+        assert_eq!(bins[0].weight, 0.0);
+        assert_eq!(bins[2].weight, 2.0);
+        assert_close(bins[2].confidence, 0.65);
+        assert_close(bins[2].accuracy, 0.5);
+        assert_eq!(bins[3].weight, 3.0);
+        assert_close(bins[3].upper, 1.0);
+        assert_close(bins[3].confidence, 2.8 / 3.0);
+        assert_close(bins[3].accuracy, 1.0);
+
+        assert_close(metric.get(), (2.0 * 0.15 + 0.2) / 5.0);
+        assert_close(metric.mce(), 0.15);
+    }
+
+    #[test]
+    fn test_revert() {
+        let mut metric = ExpectedCalibrationError::new(None);
+        metric.update(&output(0.6, 0.3, 0.1), &ClassifierTarget::from("cat"), None);
+        metric.update(&output(0.2, 0.7, 0.1), &ClassifierTarget::from("cow"), None);
+        let expected = metric.get();
+        metric.update(&output(0.1, 0.1, 0.8), &ClassifierTarget::from("cow"), None);
+        metric.revert(&output(0.1, 0.1, 0.8), &ClassifierTarget::from("cow"), None);
+        assert_close(metric.get(), expected);
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::metrics::traits::ClustringMetric;
+use crate::metrics::utils::{safe_div, Mean};
+use num::{Float, FromPrimitive};
+
+/// Cluster centers, indexed by cluster id.
+pub type ClusterCenters<F> = HashMap<i32, Observation<F>>;
+
+// Euclidean distance between the numeric features of two observations, missing features being
+// considered as zeros.
+fn euclidean_distance<F: Float>(a: &Observation<F>, b: &Observation<F>) -> F {
+    let mut sum = F::zero();
+    for (feature, x) in a.numeric() {
+        let y = b.get_numeric(feature).unwrap_or(F::zero());
+        sum = sum + (x - y).powi(2);
+    }
+    for (feature, y) in b.numeric() {
+        if a.get_numeric(feature).is_none() {
+            sum = sum + y.powi(2);
+        }
+    }
+    sum.sqrt()
+}
+
+// Number of pairs that can be made out of `n` elements.
+#[inline]
+fn n_pairs<F: Float>(n: F) -> F {
+    n * (n - F::one()) / (F::one() + F::one())
+}
+
+/// Adjusted Rand index, i.e. the agreement between the true labels and the cluster assignments,
+/// corrected for the agreement expected by chance.
+///
+/// The index is 1 for identical partitions and close to 0 for random ones, whatever the ids
+/// given to the clusters. Only the contingency table of the (true label, cluster id) pairs is
+/// stored, the pair counts being maintained incrementally.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::clustering::AdjustedRand;
+/// use light_river::metrics::traits::ClustringMetric;
+///
+/// let mut metric: AdjustedRand<f64> = AdjustedRand::new();
+/// for (y_true, y_pred) in vec![(0, 0), (0, 0), (1, 1), (1, 2)] {
+///     metric.update(y_true, y_pred);
+/// }
+/// assert!((metric.get() - 0.5714285714285714).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: L. Hubert and P. Arabie (1985). "Comparing partitions". Journal of Classification
+/// 2(1):193-218.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct AdjustedRand<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    contingency: HashMap<(i32, i32), F>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    true_counts: HashMap<i32, F>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    pred_counts: HashMap<i32, F>,
+    n: F,
+    // Sums of the number of pairs in the cells, the rows and the columns of the contingency table
+    pairs: F,
+    true_pairs: F,
+    pred_pairs: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AdjustedRand<F> {
+    pub fn new() -> Self {
+        Self {
+            contingency: HashMap::new(),
+            true_counts: HashMap::new(),
+            pred_counts: HashMap::new(),
+            n: F::zero(),
+            pairs: F::zero(),
+            true_pairs: F::zero(),
+            pred_pairs: F::zero(),
+        }
+    }
+    // Adding an element to a group of n elements creates n new pairs, whereas removing one from a
+    // group of n elements removes n - 1 pairs.
+    fn _update(&mut self, y_true: i32, y_pred: i32, revert: bool) {
+        fn bump<F: Float + AddAssign + SubAssign>(count: &mut F, pairs: &mut F, revert: bool) {
+            if revert {
+                *count -= F::one();
+                *pairs -= *count;
+            } else {
+                *pairs += *count;
+                *count += F::one();
+            }
+        }
+        bump(
+            self.contingency
+                .entry((y_true, y_pred))
+                .or_insert(F::zero()),
+            &mut self.pairs,
+            revert,
+        );
+        bump(
+            self.true_counts.entry(y_true).or_insert(F::zero()),
+            &mut self.true_pairs,
+            revert,
+        );
+        bump(
+            self.pred_counts.entry(y_pred).or_insert(F::zero()),
+            &mut self.pred_pairs,
+            revert,
+        );
+        if revert {
+            self.n -= F::one();
+        } else {
+            self.n += F::one();
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for AdjustedRand<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ClustringMetric<F>
+    for AdjustedRand<F>
+{
+    fn update(&mut self, y_true: i32, y_pred: i32) {
+        self._update(y_true, y_pred, false);
+    }
+    fn revert(&mut self, y_true: i32, y_pred: i32) {
+        self._update(y_true, y_pred, true);
+    }
+    fn get(&self) -> F {
+        let expected = safe_div(self.true_pairs * self.pred_pairs, n_pairs(self.n));
+        let max = (self.true_pairs + self.pred_pairs) / (F::one() + F::one());
+        // Both partitions are trivial, e.g. a single cluster or one cluster per element
+        if max == expected {
+            return F::one();
+        }
+        (self.pairs - expected) / (max - expected)
+    }
+}
+
+/// Simplified silhouette coefficient.
+///
+/// Computing the exact silhouette requires the distances between all the pairs of samples, which
+/// is not possible on a stream. Instead, the distance of each sample to the other samples of its
+/// cluster is approximated by its distance `a` to the center of its cluster, and the distance to
+/// the samples of the nearest other cluster by its distance `b` to the nearest other center. The
+/// coefficient is the average of `(b - a) / max(a, b)` over the samples. It lies between -1 and 1,
+/// higher values meaning more compact and better separated clusters.
+///
+/// The centers are those of the clustering model at the time each sample is seen.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use light_river::common::Observation;
+/// use light_river::metrics::clustering::{ClusterCenters, Silhouette};
+///
+/// let point = |x: f64, y: f64| Observation::from([("x".to_string(), x), ("y".to_string(), y)]);
+/// let centers: ClusterCenters<f64> = HashMap::from([(0, point(0.0, 0.0)), (1, point(4.0, 0.0))]);
+///
+/// let mut metric: Silhouette<f64> = Silhouette::new();
+/// metric.update(&point(1.0, 0.0), 0, &centers, None);
+/// metric.update(&point(4.0, 0.0), 1, &centers, None);
+/// // (3 - 1) / 3 and (4 - 0) / 4
+/// assert!((metric.get() - 5.0 / 6.0).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: E. R. Hruschka, L. N. de Castro and R. J. G. B. Campello (2004). "Evolutionary
+/// algorithms for clustering gene-expression data". IEEE International Conference on Data Mining.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Silhouette<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Silhouette<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+    fn score(x: &Observation<F>, cluster_id: i32, centers: &ClusterCenters<F>) -> F {
+        let a = match centers.get(&cluster_id) {
+            Some(center) => euclidean_distance(x, center),
+            None => return F::zero(),
+        };
+        let b = centers
+            .iter()
+            .filter(|(id, _)| **id != cluster_id)
+            .map(|(_, center)| euclidean_distance(x, center))
+            .fold(F::infinity(), F::min);
+        // A single cluster doesn't tell anything about the separation of the clusters
+        if b.is_infinite() {
+            return F::zero();
+        }
+        safe_div(b - a, a.max(b))
+    }
+    /// Update the metric with a sample `x`, assigned to `cluster_id` by a model whose centers are
+    /// `centers`.
+    pub fn update(
+        &mut self,
+        x: &Observation<F>,
+        cluster_id: i32,
+        centers: &ClusterCenters<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            Self::score(x, cluster_id, centers),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    pub fn revert(
+        &mut self,
+        x: &Observation<F>,
+        cluster_id: i32,
+        centers: &ClusterCenters<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            Self::score(x, cluster_id, centers),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    pub fn get(&self) -> F {
+        self.mean.get()
+    }
+    pub fn bigger_is_better(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for Silhouette<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Davies–Bouldin index.
+///
+/// The scatter of each cluster is the average distance of its samples to its center, and the
+/// index is the average over the clusters of the largest ratio between the scatter of two clusters
+/// and the distance between their centers. Lower values mean more compact and better separated
+/// clusters.
+///
+/// The distance of each sample to its center is computed with the centers of the clustering model
+/// at the time the sample is seen, whereas the distances between the centers are computed with
+/// the most recent centers.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use light_river::common::Observation;
+/// use light_river::metrics::clustering::{ClusterCenters, DaviesBouldin};
+///
+/// let point = |x: f64, y: f64| Observation::from([("x".to_string(), x), ("y".to_string(), y)]);
+/// let centers: ClusterCenters<f64> = HashMap::from([(0, point(0.0, 0.0)), (1, point(4.0, 0.0))]);
+///
+/// let mut metric: DaviesBouldin<f64> = DaviesBouldin::new();
+/// metric.update(&point(0.0, 1.0), 0, &centers, None);
+/// metric.update(&point(4.0, 1.0), 1, &centers, None);
+/// metric.update(&point(4.0, -1.0), 1, &centers, None);
+/// // (1 + 1) / 4
+/// assert!((metric.get() - 0.5).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: D. L. Davies and D. W. Bouldin (1979). "A Cluster Separation Measure". IEEE Transactions
+/// on Pattern Analysis and Machine Intelligence 1(2):224-227.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct DaviesBouldin<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    scatters: HashMap<i32, Mean<F>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    centers: ClusterCenters<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> DaviesBouldin<F> {
+    pub fn new() -> Self {
+        Self {
+            scatters: HashMap::new(),
+            centers: HashMap::new(),
+        }
+    }
+    /// Update the metric with a sample `x`, assigned to `cluster_id` by a model whose centers are
+    /// `centers`.
+    pub fn update(
+        &mut self,
+        x: &Observation<F>,
+        cluster_id: i32,
+        centers: &ClusterCenters<F>,
+        sample_weight: Option<F>,
+    ) {
+        if let Some(center) = centers.get(&cluster_id) {
+            self.scatters
+                .entry(cluster_id)
+                .or_insert(Mean::new())
+                .update(
+                    euclidean_distance(x, center),
+                    sample_weight.unwrap_or(F::one()),
+                );
+        }
+        self.centers = centers.clone();
+    }
+    /// Revert a sample. `centers` must be the centers that were given when the sample was added.
+    pub fn revert(
+        &mut self,
+        x: &Observation<F>,
+        cluster_id: i32,
+        centers: &ClusterCenters<F>,
+        sample_weight: Option<F>,
+    ) {
+        if let (Some(center), Some(scatter)) =
+            (centers.get(&cluster_id), self.scatters.get_mut(&cluster_id))
+        {
+            scatter.revert(
+                euclidean_distance(x, center),
+                sample_weight.unwrap_or(F::one()),
+            );
+        }
+    }
+    pub fn get(&self) -> F {
+        let clusters: Vec<_> = self
+            .scatters
+            .keys()
+            .filter(|id| self.centers.contains_key(id))
+            .collect();
+        if clusters.len() < 2 {
+            return F::zero();
+        }
+        let mut total = F::zero();
+        for i in clusters.iter() {
+            let mut worst = F::zero();
+            for j in clusters.iter().filter(|j| *j != i) {
+                let ratio = safe_div(
+                    self.scatters[i].get() + self.scatters[j].get(),
+                    euclidean_distance(&self.centers[i], &self.centers[j]),
+                );
+                worst = worst.max(ratio);
+            }
+            total += worst;
+        }
+        total / F::from(clusters.len()).unwrap()
+    }
+    pub fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for DaviesBouldin<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn point(x: f64, y: f64) -> Observation<f64> {
+        Observation::from([("x".to_string(), x), ("y".to_string(), y)])
+    }
+
+    #[test]
+    fn test_adjusted_rand() {
+        // Same values as scikit-learn's adjusted_rand_score
+        let y_true = [0, 0, 0, 1, 1, 1, 2, 2];
+        let y_pred = [0, 0, 1, 1, 2, 2, 2, 3];
+        let mut metric = AdjustedRand::new();
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(*yt, *yp);
+        }
+        assert_close(metric.get(), 0.15789473684210525);
+
+        // Cluster ids don't matter
+        let mut metric = AdjustedRand::new();
+        for (yt, yp) in y_true.iter().zip(y_true.iter()) {
+            metric.update(*yt, *yp + 10);
+        }
+        assert_close(metric.get(), 1.0);
+    }
+
+    #[test]
+    fn test_adjusted_rand_revert() {
+        let mut metric = AdjustedRand::new();
+        for (yt, yp) in [(0, 0), (0, 0), (1, 1), (1, 2)] {
+            metric.update(yt, yp);
+        }
+        let expected = metric.get();
+        metric.update(2, 0);
+        metric.revert(2, 0);
+        assert_close(metric.get(), expected);
+    }
+
+    #[test]
+    fn test_silhouette() {
+        let centers = HashMap::from([(0, point(0.0, 0.0)), (1, point(0.0, 4.0))]);
+        let mut metric = Silhouette::new();
+        metric.update(&point(0.0, 3.0), 0, &centers, None);
+        assert_close(metric.get(), (1.0 - 3.0) / 3.0);
+        metric.update(
+            &point(0.0, 0.0),
+            1,
+            &HashMap::from([(1, point(1.0, 0.0))]),
+            None,
+        );
+        assert_close(metric.get(), (1.0 - 3.0) / 6.0);
+        metric.revert(&point(0.0, 3.0), 0, &centers, None);
+        assert_close(metric.get(), 0.0);
+    }
+
+    #[test]
+    fn test_davies_bouldin() {
+        let centers = HashMap::from([
+            (0, point(0.0, 0.0)),
+            (1, point(4.0, 0.0)),
+            (2, point(0.0, 8.0)),
+        ]);
+        let mut metric = DaviesBouldin::new();
+        metric.update(&point(0.0, 2.0), 0, &centers, None);
+        metric.update(&point(4.0, 1.0), 1, &centers, None);
+        metric.update(&point(1.0, 8.0), 2, &centers, None);
+        // Cluster 0: max(3 / 4, 3 / 8), cluster 1: max(3 / 4, 2 / sqrt(80)), cluster 2:
+        // max(3 / 8, 2 / sqrt(80))
+        assert_close(metric.get(), (0.75 + 0.75 + 0.375) / 3.0);
+
+        metric.update(&point(0.0, 0.0), 0, &centers, None);
+        metric.revert(&point(0.0, 0.0), 0, &centers, None);
+        assert_close(metric.get(), (0.75 + 0.75 + 0.375) / 3.0);
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget, RegressionTarget};
+use crate::metrics::utils::{normal_cdf, safe_div};
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// One of the two models of a [`PairedComparison`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Model {
+    First,
+    Second,
+}
+
+/// Contingency table of the correctness of two classifiers on the same samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct McNemarTable<F> {
+    pub both_correct: F,
+    pub first_only: F,
+    pub second_only: F,
+    pub both_wrong: F,
+}
+
+impl<F: Float + FromPrimitive> McNemarTable<F> {
+    /// McNemar's chi-squared statistic, with Edwards' continuity correction.
+    pub fn statistic(&self) -> F {
+        let disagreements = self.first_only + self.second_only;
+        let diff = ((self.first_only - self.second_only).abs() - F::one()).max(F::zero());
+        safe_div(diff * diff, disagreements)
+    }
+    /// Probability of a statistic at least as large under the hypothesis that both classifiers
+    /// have the same error rate.
+    pub fn p_value(&self) -> F {
+        // The statistic follows a chi-squared distribution with one degree of freedom, i.e. it is
+        // the square of a standard normal variable
+        let two = F::one() + F::one();
+        two * (F::one() - normal_cdf(self.statistic().sqrt()))
+    }
+}
+
+/// Paired comparison of two models evaluated on the same stream of samples.
+///
+/// For each sample, the losses of both models are compared, and the following statistics are
+/// maintained:
+///
+/// - A sign test, which counts how many times each model has a strictly lower loss than the
+///   other. Ties are ignored.
+/// - The mean and variance of the loss differences, from which a paired z-statistic is derived.
+///   A Wilcoxon signed-rank test would require the ranks of all the differences, i.e. storing the
+///   whole stream, which is why this cheaper statistic is used instead.
+/// - For classifiers, a McNemar table of the samples each model gets right.
+///
+/// The losses are the 0/1 loss for classifiers and the absolute error for regressors. Custom
+/// losses can be given with `update`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::compare::{Model, PairedComparison};
+///
+/// let mut comparison: PairedComparison<f64> = PairedComparison::new();
+/// for i in 0..100 {
+///     let y_true = ClassifierTarget::from(i % 2 == 0);
+///     // The first model is always right, the second one every other time
+///     let first = ClassifierOutput::Prediction(y_true.clone());
+///     let second = ClassifierOutput::Prediction(ClassifierTarget::from(i % 4 < 2));
+///     comparison.update_classification(&first, &second, &y_true);
+/// }
+/// assert_eq!(comparison.wins(), (50.0, 0.0, 50.0));
+/// assert_eq!(comparison.mcnemar().first_only, 50.0);
+/// assert_eq!(comparison.winner(0.05), Some(Model::First));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PairedComparison<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    first_wins: F,
+    second_wins: F,
+    ties: F,
+    // Mean and variance of the loss differences
+    differences: Var<F>,
+    mcnemar: McNemarTable<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> PairedComparison<F> {
+    pub fn new() -> Self {
+        Self {
+            first_wins: F::zero(),
+            second_wins: F::zero(),
+            ties: F::zero(),
+            differences: Var::new(None),
+            mcnemar: McNemarTable {
+                both_correct: F::zero(),
+                first_only: F::zero(),
+                second_only: F::zero(),
+                both_wrong: F::zero(),
+            },
+        }
+    }
+    /// Update the statistics with the losses of both models on a sample. Lower is better.
+    pub fn update(&mut self, first_loss: F, second_loss: F) {
+        if first_loss < second_loss {
+            self.first_wins += F::one();
+        } else if second_loss < first_loss {
+            self.second_wins += F::one();
+        } else {
+            self.ties += F::one();
+        }
+        self.differences.update(first_loss - second_loss);
+    }
+    pub fn update_classification(
+        &mut self,
+        first_pred: &ClassifierOutput<F>,
+        second_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+    ) {
+        let first_correct = first_pred.get_predicition() == *y_true;
+        let second_correct = second_pred.get_predicition() == *y_true;
+        let cell = match (first_correct, second_correct) {
+            (true, true) => &mut self.mcnemar.both_correct,
+            (true, false) => &mut self.mcnemar.first_only,
+            (false, true) => &mut self.mcnemar.second_only,
+            (false, false) => &mut self.mcnemar.both_wrong,
+        };
+        *cell += F::one();
+        let loss = |correct: bool| if correct { F::zero() } else { F::one() };
+        self.update(loss(first_correct), loss(second_correct));
+    }
+    pub fn update_regression(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        first_pred: RegressionTarget<F>,
+        second_pred: RegressionTarget<F>,
+    ) {
+        self.update((y_true - first_pred).abs(), (y_true - second_pred).abs());
+    }
+    /// Number of samples seen so far.
+    pub fn n_samples(&self) -> F {
+        self.differences.n()
+    }
+    /// Number of samples on which the first model is better, the second model is better, and
+    /// both are equally good.
+    pub fn wins(&self) -> (F, F, F) {
+        (self.first_wins, self.second_wins, self.ties)
+    }
+    pub fn mcnemar(&self) -> McNemarTable<F> {
+        self.mcnemar
+    }
+    /// Two-sided p-value of the sign test, using the normal approximation of the binomial
+    /// distribution with a continuity correction.
+    pub fn sign_test_p_value(&self) -> F {
+        let n = self.first_wins + self.second_wins;
+        if n == F::zero() {
+            return F::one();
+        }
+        let diff = ((self.first_wins - self.second_wins).abs() - F::one()).max(F::zero());
+        let two = F::one() + F::one();
+        two * (F::one() - normal_cdf(diff / n.sqrt()))
+    }
+    /// Average of the loss of the first model minus the loss of the second one.
+    pub fn mean_difference(&self) -> F {
+        self.differences.mean()
+    }
+    /// The mean difference divided by its standard error. Negative values favor the first model.
+    pub fn z_statistic(&self) -> F {
+        let n = self.differences.n();
+        if n <= F::one() {
+            return F::zero();
+        }
+        safe_div(self.mean_difference(), (self.differences.get() / n).sqrt())
+    }
+    /// The model which is significantly better according to the sign test at level `alpha`, if
+    /// any.
+    pub fn winner(&self, alpha: F) -> Option<Model> {
+        if self.sign_test_p_value() >= alpha {
+            return None;
+        }
+        if self.first_wins > self.second_wins {
+            Some(Model::First)
+        } else {
+            Some(Model::Second)
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for PairedComparison<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn test_mcnemar() {
+        // Same values as statsmodels' mcnemar(exact=False, correction=True)
+        let table = McNemarTable {
+            both_correct: 59.0,
+            first_only: 6.0,
+            second_only: 16.0,
+            both_wrong: 80.0,
+        };
+        assert_close(table.statistic(), 81.0 / 22.0);
+        // The reference p-value has 6 significant digits
+        assert!((table.p_value() - 0.05500883362926577).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_no_difference() {
+        let mut comparison = PairedComparison::new();
+        for i in 0..100 {
+            let (first, second) = if i % 2 == 0 { (1.0, 2.0) } else { (2.0, 1.0) };
+            comparison.update_regression(0.0, first, second);
+        }
+        comparison.update(0.5, 0.5);
+        assert_eq!(comparison.wins(), (50.0, 50.0, 1.0));
+        assert!((comparison.sign_test_p_value() - 1.0).abs() < 1e-6);
+        assert_close(comparison.mean_difference(), 0.0);
+        assert_eq!(comparison.winner(0.05), None);
+    }
+
+    #[test]
+    fn test_second_is_better() {
+        let mut comparison = PairedComparison::new();
+        for i in 0..30 {
+            let second = if i % 10 == 0 { 1.5 } else { 0.5 };
+            comparison.update_regression(0.0, 1.0, second);
+        }
+        assert_eq!(comparison.wins(), (3.0, 27.0, 0.0));
+        assert!(comparison.z_statistic() > 0.0);
+        assert_eq!(comparison.winner(0.01), Some(Model::Second));
+    }
+}
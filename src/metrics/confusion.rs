@@ -2,7 +2,7 @@ use std::fmt;
 use std::{
     collections::HashMap,
     collections::HashSet,
-    ops::{AddAssign, DivAssign, MulAssign, SubAssign},
+    ops::{AddAssign, DivAssign, MulAssign, Sub, SubAssign},
 };
 
 use crate::common::{ClassifierOutput, ClassifierTarget};
@@ -66,6 +66,20 @@ pub struct ConfusionMatrix<F: Float + FromPrimitive + AddAssign + SubAssign + Mu
     pub total_weight: F,
 }
 
+/// Axis along which a [`ConfusionMatrix`] is normalized.
+///
+/// - `Row`: each row sums to 1, i.e. the cells are divided by the support of the true class.
+/// - `Column`: each column sums to 1, i.e. the cells are divided by the number of predictions of
+///   the class.
+/// - `Total`: the whole matrix sums to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Normalization {
+    Row,
+    Column,
+    Total,
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ConfusionMatrix<F> {
     pub fn new() -> Self {
         Self {
@@ -105,7 +119,7 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> C
 
         self.data
             .entry(y)
-            .or_insert_with(HashMap::new)
+            .or_default()
             .entry(label_pred)
             .and_modify(|x| *x += sample_weight)
             .or_insert(sample_weight);
@@ -138,29 +152,129 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> C
         self.n_samples -= sample_weight.unwrap_or(F::one());
         self._update(y_pred, y_true, -sample_weight.unwrap_or(F::one()));
     }
+    /// Multiply every count by `factor`, so that the samples seen so far weigh less than the
+    /// upcoming ones.
+    pub fn decay(&mut self, factor: F) {
+        self.data
+            .values_mut()
+            .flat_map(|row| row.values_mut())
+            .chain(self.sum_row.values_mut())
+            .chain(self.sum_col.values_mut())
+            .for_each(|x| *x *= factor);
+        self.n_samples *= factor;
+        self.total_weight *= factor;
+    }
+    /// Return a copy of the matrix where the cells are normalized along `axis`.
+    ///
+    /// Rows or columns which sum to zero are left as is.
+    pub fn normalized(&self, axis: Normalization) -> Self {
+        let mut normalized = Self::new();
+        normalized.n_samples = self.n_samples;
+        for (y_true, row) in self.data.iter() {
+            for (y_pred, value) in row.iter() {
+                let norm = match axis {
+                    Normalization::Row => self.support(y_true),
+                    Normalization::Column => *self.sum_col.get(y_pred).unwrap_or(&F::zero()),
+                    Normalization::Total => self.total_weight,
+                };
+                let value = if norm == F::zero() {
+                    *value
+                } else {
+                    *value / norm
+                };
+                normalized._update(&ClassifierOutput::Prediction(y_pred.clone()), y_true, value);
+            }
+        }
+        normalized
+    }
+    /// Export the matrix as a dense, row-major matrix.
+    ///
+    /// The classes are sorted and are used to index both the rows (true labels) and the columns
+    /// (predicted labels).
+    ///
+    /// ```
+    /// use light_river::metrics::confusion::ConfusionMatrix;
+    /// use light_river::common::{ClassifierTarget, ClassifierOutput};
+    ///
+    /// let mut cm: ConfusionMatrix<f64> = ConfusionMatrix::new();
+    /// for (yt, yp) in [("cat", "cat"), ("cat", "dog"), ("dog", "dog")] {
+    ///     cm.update(
+    ///         &ClassifierOutput::Prediction(ClassifierTarget::from(yp)),
+    ///         &ClassifierTarget::from(yt),
+    ///         None,
+    ///     );
+    /// }
+    /// let (classes, matrix) = cm.to_dense();
+    /// assert_eq!(classes, vec![ClassifierTarget::from("cat"), ClassifierTarget::from("dog")]);
+    /// assert_eq!(matrix, vec![vec![1.0, 1.0], vec![0.0, 1.0]]);
+    /// ```
+    pub fn to_dense(&self) -> (Vec<ClassifierTarget>, Vec<Vec<F>>) {
+        let mut classes: Vec<_> = self.get_classes().into_iter().collect();
+        classes.sort();
+        let matrix = classes
+            .iter()
+            .map(|y_true| {
+                let row = self.data.get(y_true);
+                classes
+                    .iter()
+                    .map(|y_pred| {
+                        row.and_then(|row| row.get(y_pred))
+                            .copied()
+                            .unwrap_or(F::zero())
+                    })
+                    .collect()
+            })
+            .collect();
+        (classes, matrix)
+    }
+    /// Add the counts of `other` to this matrix, e.g. to combine the matrices of several shards
+    /// of a stream into a global one.
+    pub fn merge(&mut self, other: &Self) {
+        self.combine(other, F::one());
+    }
+    // Add the counts of `other`, multiplied by `sign`, to this matrix.
+    fn combine(&mut self, other: &Self, sign: F) {
+        for (y_true, row) in other.data.iter() {
+            for (y_pred, value) in row.iter() {
+                self._update(
+                    &ClassifierOutput::Prediction(y_pred.clone()),
+                    y_true,
+                    sign * *value,
+                );
+            }
+        }
+        self.n_samples += sign * other.n_samples;
+    }
     pub fn get(&self, label: &ClassifierTarget) -> HashMap<ClassifierTarget, F> {
         // return rows of the label in the confusion matrix
         self.data.get(label).unwrap_or(&HashMap::new()).clone()
     }
+    /// Total weight of the samples whose true label is `label`.
     pub fn support(&self, label: &ClassifierTarget) -> F {
-        self.sum_col.get(label).unwrap_or(&F::zero()).clone()
+        *self.sum_row.get(label).unwrap_or(&F::zero())
+    }
+    /// Total weight of the samples whose predicted label is `label`.
+    pub fn predicted(&self, label: &ClassifierTarget) -> F {
+        *self.sum_col.get(label).unwrap_or(&F::zero())
     }
-    // For the next session you will check if the implementation of the following methods is correct
     pub fn true_positives(&self, label: &ClassifierTarget) -> F {
-        self.data
+        *self
+            .data
             .get(label)
             .unwrap_or(&HashMap::new())
             .get(label)
             .unwrap_or(&F::zero())
-            .clone()
     }
     pub fn true_negatives(&self, label: &ClassifierTarget) -> F {
-        self.total_true_positives() - self.true_positives(label)
+        self.total_weight
+            - self.true_positives(label)
+            - self.false_positives(label)
+            - self.false_negatives(label)
     }
 
     pub fn total_true_positives(&self) -> F {
-        self.data
-            .keys()
+        self.get_classes()
+            .iter()
             .fold(F::zero(), |sum, label| sum + self.true_positives(label))
     }
     pub fn false_positives(&self, label: &ClassifierTarget) -> F {
@@ -168,22 +282,22 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> C
     }
 
     pub fn total_true_negatives(&self) -> F {
-        self.data
-            .keys()
+        self.get_classes()
+            .iter()
             .fold(F::zero(), |sum, label| sum + self.true_negatives(label))
     }
 
     pub fn total_false_positives(&self) -> F {
-        self.data
-            .keys()
+        self.get_classes()
+            .iter()
             .fold(F::zero(), |sum, label| sum + self.false_positives(label))
     }
     pub fn false_negatives(&self, label: &ClassifierTarget) -> F {
         *self.sum_row.get(label).unwrap_or(&F::zero()) - self.true_positives(label)
     }
     pub fn total_false_negatives(&self) -> F {
-        self.data
-            .keys()
+        self.get_classes()
+            .iter()
             .fold(F::zero(), |sum, label| sum + self.false_negatives(label))
     }
 }
@@ -221,6 +335,90 @@ impl<
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AddAssign<&Self>
+    for ConfusionMatrix<F>
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.combine(other, F::one());
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> SubAssign<&Self>
+    for ConfusionMatrix<F>
+{
+    fn sub_assign(&mut self, other: &Self) {
+        self.combine(other, -F::one());
+    }
+}
+
+/// Difference between two snapshots of a matrix, i.e. the counts of the samples that were seen
+/// in between.
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Sub
+    for &ConfusionMatrix<F>
+{
+    type Output = ConfusionMatrix<F>;
+
+    fn sub(self, other: Self) -> ConfusionMatrix<F> {
+        let mut diff = self.clone();
+        diff -= other;
+        diff
+    }
+}
+
+// The matrix is (de)serialized as a list of cells, from which the row and column sums are
+// rebuilt. This also avoids non-string map keys, which many formats don't support.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ConfusionMatrixCells<F> {
+    n_samples: F,
+    cells: Vec<(ClassifierTarget, ClassifierTarget, F)>,
+}
+
+#[cfg(feature = "serde")]
+impl<F> serde::Serialize for ConfusionMatrix<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cells = self
+            .data
+            .iter()
+            .flat_map(|(y_true, row)| {
+                row.iter()
+                    .map(move |(y_pred, value)| (y_true.clone(), y_pred.clone(), *value))
+            })
+            .collect();
+        ConfusionMatrixCells {
+            n_samples: self.n_samples,
+            cells,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F> serde::Deserialize<'de> for ConfusionMatrix<F>
+where
+    F: Float
+        + FromPrimitive
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + DivAssign
+        + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ConfusionMatrixCells { n_samples, cells } =
+            ConfusionMatrixCells::deserialize(deserializer)?;
+        let mut cm = Self::new();
+        for (y_true, y_pred, value) in cells {
+            cm._update(&ClassifierOutput::Prediction(y_pred), &y_true, value);
+        }
+        cm.n_samples = n_samples;
+        Ok(cm)
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
     for ConfusionMatrix<F>
 {
@@ -240,7 +438,7 @@ mod tests {
     use super::*;
     #[test]
     fn test_confusion_matrix() {
-        let y_pred = vec![
+        let y_pred = [
             ClassifierOutput::Prediction(ClassifierTarget::from("ant")),
             ClassifierOutput::Prediction(ClassifierTarget::from("ant")),
             ClassifierOutput::Prediction(ClassifierTarget::from("cat")),
@@ -262,7 +460,7 @@ mod tests {
         let mut cm: ConfusionMatrix<f64> = ConfusionMatrix::new();
 
         for (yt, yp) in y_true_stream.zip(y_pred_stream) {
-            cm.update(&yp, &yt, Some(1.0)); // Assuming an update method
+            cm.update(yp, &yt, Some(1.0)); // Assuming an update method
         }
         println!("{:?}", cm);
         assert_eq!(
@@ -272,4 +470,114 @@ mod tests {
             1.0
         );
     }
+
+    fn cm() -> ConfusionMatrix<f64> {
+        let y_true = ["cat", "ant", "cat", "cat", "ant", "bird"];
+        let y_pred = ["ant", "ant", "cat", "cat", "ant", "cat"];
+        let mut cm = ConfusionMatrix::new();
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            cm.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+        cm
+    }
+
+    #[test]
+    fn test_to_dense() {
+        let (classes, matrix) = cm().to_dense();
+        assert_eq!(
+            classes,
+            vec![
+                ClassifierTarget::from("ant"),
+                ClassifierTarget::from("bird"),
+                ClassifierTarget::from("cat")
+            ]
+        );
+        assert_eq!(
+            matrix,
+            vec![
+                vec![2.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0],
+                vec![1.0, 0.0, 2.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        let cm = cm();
+        let (_, rows) = cm.normalized(Normalization::Row).to_dense();
+        assert_eq!(
+            rows,
+            vec![
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0],
+                vec![1.0 / 3.0, 0.0, 2.0 / 3.0]
+            ]
+        );
+        // The bird column is empty and is left as is
+        let (_, cols) = cm.normalized(Normalization::Column).to_dense();
+        assert_eq!(
+            cols,
+            vec![
+                vec![2.0 / 3.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0 / 3.0],
+                vec![1.0 / 3.0, 0.0, 2.0 / 3.0]
+            ]
+        );
+        let total = cm.normalized(Normalization::Total);
+        assert!((total.total_weight - 1.0).abs() < 1e-10);
+        assert_eq!(
+            total.true_positives(&ClassifierTarget::from("ant")),
+            2.0 / 6.0
+        );
+    }
+
+    #[test]
+    fn test_merge_and_diff() {
+        let full = cm();
+        let y_true = ["cat", "ant", "cat", "cat", "ant", "bird"];
+        let y_pred = ["ant", "ant", "cat", "cat", "ant", "cat"];
+        let mut shards = [ConfusionMatrix::new(), ConfusionMatrix::new()];
+        for (i, (yt, yp)) in y_true.iter().zip(y_pred.iter()).enumerate() {
+            shards[i % 2].update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+
+        let mut merged = shards[0].clone();
+        merged.merge(&shards[1]);
+        assert_eq!(merged.to_dense(), full.to_dense());
+        assert_eq!(merged.total_weight, full.total_weight);
+
+        let mut added = ConfusionMatrix::new();
+        added += &shards[0];
+        added += &shards[1];
+        assert_eq!(added.to_dense(), full.to_dense());
+
+        // Differencing snapshots gives back the other shard
+        let diff = &full - &shards[0];
+        assert_eq!(diff.to_dense(), shards[1].to_dense());
+        assert_eq!(diff.total_weight, 3.0);
+        assert_eq!(diff.support(&ClassifierTarget::from("cat")), 1.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let cm = cm();
+        let json = serde_json::to_string(&cm).unwrap();
+        let restored: ConfusionMatrix<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_dense(), cm.to_dense());
+        assert_eq!(restored.total_weight, cm.total_weight);
+        assert_eq!(
+            restored.support(&ClassifierTarget::from("cat")),
+            cm.support(&ClassifierTarget::from("cat"))
+        );
+    }
 }
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::Mean;
+use num::{Float, FromPrimitive};
+
+/// Cross-entropy between the target distribution and the predicted probabilities.
+///
+/// Without label smoothing, the target distribution puts all its mass on the true class, and the
+/// cross-entropy is the same as the log loss. With a label smoothing of `alpha`, a fraction
+/// `alpha` of the mass is instead spread evenly over the classes of the sample, i.e. the true
+/// class and the classes of the output. The loss is then the one a model trained with the same
+/// smoothing is optimizing, which makes it easier to compare with a teacher trained on soft labels.
+///
+/// # Parameters
+///
+/// - `label_smoothing`: The fraction of the mass spread over all the classes. Defaults to 0 and
+///   must lie in `[0, 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::cross_entropy::CrossEntropy;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use maplit::hashmap;
+///
+/// let y_pred: ClassifierOutput<f64> = ClassifierOutput::Probabilities(hashmap! {
+///     ClassifierTarget::from("cat") => 0.7,
+///     ClassifierTarget::from("dog") => 0.2,
+///     ClassifierTarget::from("cow") => 0.1,
+/// });
+///
+/// let mut metric = CrossEntropy::new(Some(0.3));
+/// metric.update(&y_pred, &ClassifierTarget::from("cat"), None);
+/// // The target distribution is 0.8 for cat and 0.1 for the others
+/// assert!((metric.get() - 0.6765422556938006).abs() < 1e-10);
+/// ```
+///
+/// # Notes
+///
+/// The probabilities are clipped to `[1e-15, 1]` before taking their logarithm, as for `LogLoss`.
+/// Classes which are missing from the output are given a probability of 0, unless the output holds
+/// a single class, in which case the problem is assumed to be binary.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrossEntropy<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    label_smoothing: F,
+    eps: F,
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CrossEntropy<F> {
+    pub fn new(label_smoothing: Option<F>) -> Self {
+        let label_smoothing = label_smoothing.unwrap_or(F::zero());
+        assert!(
+            label_smoothing >= F::zero() && label_smoothing < F::one(),
+            "label_smoothing must lie in [0, 1)"
+        );
+        Self {
+            label_smoothing,
+            eps: F::from(1e-15).unwrap(),
+            mean: Mean::new(),
+        }
+    }
+    pub fn label_smoothing(&self) -> F {
+        self.label_smoothing
+    }
+    fn loss(&self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> F {
+        let mut probabilities = y_pred.get_probabilities();
+        if probabilities.len() == 1 && !probabilities.contains_key(y_true) {
+            let p = F::one() - *probabilities.values().next().unwrap();
+            probabilities.insert(y_true.clone(), p);
+        }
+        let classes: HashSet<&ClassifierTarget> = probabilities.keys().chain([y_true]).collect();
+
+        let smoothing = self.label_smoothing / F::from(classes.len()).unwrap();
+        classes.iter().fold(F::zero(), |loss, class| {
+            let mut q = smoothing;
+            if *class == y_true {
+                q += F::one() - self.label_smoothing;
+            }
+            let p = *probabilities.get(*class).unwrap_or(&F::zero());
+            loss - q * p.max(self.eps).min(F::one()).ln()
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for CrossEntropy<F>
+{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for CrossEntropy<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        let loss = self.loss(y_pred, y_true);
+        self.mean.update(loss, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        let loss = self.loss(y_pred, y_true);
+        self.mean.revert(loss, sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for CrossEntropy<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::logloss::LogLoss;
+    use crate::testing::assert_close;
+    use std::collections::HashMap;
+
+    fn proba(items: &[(&str, f64)]) -> ClassifierOutput<f64> {
+        ClassifierOutput::Probabilities(
+            items
+                .iter()
+                .map(|(k, v)| (ClassifierTarget::from(*k), *v))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_no_smoothing_is_log_loss() {
+        let samples = [
+            ("cat", proba(&[("cat", 0.7), ("dog", 0.2), ("bird", 0.1)])),
+            ("dog", proba(&[("cat", 0.3), ("dog", 0.6), ("bird", 0.1)])),
+            ("bird", proba(&[("cat", 0.2), ("dog", 0.3), ("bird", 0.5)])),
+            ("dog", proba(&[("cat", 0.4)])),
+        ];
+        let mut metric = CrossEntropy::new(None);
+        let mut log_loss = LogLoss::new(None);
+        for (y_true, y_pred) in samples.iter() {
+            metric.update(y_pred, &ClassifierTarget::from(*y_true), None);
+            log_loss.update(y_pred, &ClassifierTarget::from(*y_true), None);
+        }
+        assert_close(metric.get(), log_loss.get());
+    }
+
+    #[test]
+    fn test_label_smoothing() {
+        let mut metric = CrossEntropy::new(Some(0.3));
+        metric.update(
+            &proba(&[("cat", 0.7), ("dog", 0.2), ("cow", 0.1)]),
+            &ClassifierTarget::from("cat"),
+            None,
+        );
+        let y_pred =
+            ClassifierOutput::Probabilities(HashMap::from([(ClassifierTarget::from(true), 0.6)]));
+        metric.update(&y_pred, &ClassifierTarget::from(false), Some(3.0));
+        // Binary sample: 0.85 for false, whose probability is 0.4, and 0.15 for true
+        let binary = -(0.85 * 0.4_f64.ln() + 0.15 * 0.6_f64.ln());
+        assert_close(metric.get(), (0.6765422556938006 + 3.0 * binary) / 4.0);
+
+        metric.revert(&y_pred, &ClassifierTarget::from(false), Some(3.0));
+        assert_close(metric.get(), 0.6765422556938006);
+    }
+}
@@ -0,0 +1,183 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget, RegressionTarget};
+use crate::metrics::traits::{ClassificationMetric, RegressionMetric};
+use num::{Float, FromPrimitive};
+
+/// Trait for metrics whose accumulated statistics can be down-weighted.
+pub trait Decay<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    /// Multiply the weight of every sample seen so far by `factor`.
+    fn decay(&mut self, factor: F);
+}
+
+/// Time-decayed wrapper for metrics.
+///
+/// Before each update, the statistics accumulated by the wrapped metric are multiplied by the
+/// fading factor. The weight of a sample thus decreases exponentially with its age, which makes
+/// the metric emphasize recent performance without having to store a window of samples, contrary
+/// to [`Rolling`](crate::metrics::rolling::Rolling).
+///
+/// # Parameters
+///
+/// - `metric`: The metric to decay. It should be freshly initialized.
+/// - `fading_factor`: The factor by which past samples are multiplied at each update. It must lie
+///   in `(0, 1]`, 1 meaning that no decay is applied.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::decay::TimeDecayed;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let mut metric: TimeDecayed<f64, MAE<f64>> = TimeDecayed::new(MAE::new(), 0.5);
+/// metric.update(0.0, 4.0, None);
+/// metric.update(0.0, 1.0, None);
+/// // The first error has half the weight of the second one
+/// assert_eq!(metric.get(), 2.0);
+/// ```
+///
+/// # Notes
+///
+/// Reverting is only exact for the latest sample, as older samples have been decayed since they
+/// were added.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeDecayed<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Decay<F>,
+{
+    metric: M,
+    fading_factor: F,
+}
+
+impl<F, M> TimeDecayed<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Decay<F>,
+{
+    pub fn new(metric: M, fading_factor: F) -> Self {
+        assert!(
+            fading_factor > F::zero() && fading_factor <= F::one(),
+            "fading_factor must lie in (0, 1]"
+        );
+        Self {
+            metric,
+            fading_factor,
+        }
+    }
+    pub fn fading_factor(&self) -> F {
+        self.fading_factor
+    }
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+}
+
+impl<F, M> ClassificationMetric<F> for TimeDecayed<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F> + Decay<F>,
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.metric.decay(self.fading_factor);
+        self.metric.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.metric.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        self.metric.get()
+    }
+    fn is_multiclass(&self) -> bool {
+        self.metric.is_multiclass()
+    }
+    fn bigger_is_better(&self) -> bool {
+        self.metric.bigger_is_better()
+    }
+}
+
+impl<F, M> RegressionMetric<F> for TimeDecayed<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: RegressionMetric<F> + Decay<F>,
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.metric.decay(self.fading_factor);
+        self.metric.update(y_true, y_pred, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.metric.revert(y_true, y_pred, sample_weight);
+    }
+    fn get(&self) -> F {
+        self.metric.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        self.metric.bigger_is_better()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::regression::R2;
+    use crate::metrics::report::{Average, Recall};
+    use crate::testing::assert_close;
+
+    #[test]
+    fn test_decayed_recall() {
+        let mut metric = TimeDecayed::new(Recall::new(Average::Micro), 0.5);
+        let hit = ClassifierOutput::Prediction(ClassifierTarget::from("a"));
+        let miss = ClassifierOutput::Prediction(ClassifierTarget::from("b"));
+        for y_pred in [&miss, &miss, &hit] {
+            metric.update(y_pred, &ClassifierTarget::from("a"), None);
+        }
+        // Weights are 0.25, 0.5 and 1
+        assert_close(metric.get(), 1.0 / 1.75);
+    }
+
+    #[test]
+    fn test_no_decay() {
+        let mut decayed = TimeDecayed::new(R2::new(), 1.0);
+        let mut plain = R2::new();
+        for (yt, yp) in [(1.0, 1.5), (2.0, 1.0), (4.0, 3.0)] {
+            decayed.update(yt, yp, None);
+            plain.update(yt, yp, None);
+        }
+        assert_close(decayed.get(), plain.get());
+    }
+
+    #[test]
+    fn test_decay_matches_weights() {
+        // Decaying is equivalent to giving exponentially decreasing weights to past samples
+        let samples = [(1.0, 1.5), (2.0, 1.0), (4.0, 3.0), (0.5, 1.0)];
+        let mut decayed = TimeDecayed::new(R2::new(), 0.8);
+        let mut weighted = R2::new();
+        for (i, (yt, yp)) in samples.iter().enumerate() {
+            decayed.update(*yt, *yp, None);
+            weighted.update(*yt, *yp, Some(0.8_f64.powi((samples.len() - 1 - i) as i32)));
+        }
+        assert_close(decayed.get(), weighted.get());
+    }
+}
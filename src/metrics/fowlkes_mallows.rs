@@ -0,0 +1,167 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use num::{Float, FromPrimitive};
+
+/// Fowlkes–Mallows index.
+///
+/// The index considers all the pairs of samples. A pair is a true positive if both samples have
+/// the same true class and the same predicted class, a false positive if they only have the same
+/// predicted class, and a false negative if they only have the same true class. The index is the
+/// geometric mean of the resulting pairwise precision and recall. It lies between 0 and 1, and
+/// doesn't depend on the actual values of the predicted labels, which makes it suitable to compare
+/// cluster assignments with true classes.
+///
+/// The pair counts are derived from the cells and from the row and column sums of the confusion
+/// matrix.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::fowlkes_mallows::FowlkesMallows;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![0, 0, 0, 1, 1, 1];
+/// let y_pred = vec![0, 0, 1, 1, 2, 2];
+///
+/// let mut metric: FowlkesMallows<f64> = FowlkesMallows::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert!((metric.get() - 0.4714045207910317).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: E. B. Fowlkes and C. L. Mallows (1983). "A method for comparing two hierarchical
+/// clusterings". Journal of the American Statistical Association 78(383):553-569.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FowlkesMallows<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    cm: ConfusionMatrix<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> FowlkesMallows<F> {
+    pub fn new() -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for FowlkesMallows<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for FowlkesMallows<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let n = self.cm.total_weight;
+        let mut sum_cells = F::zero();
+        let mut sum_rows = F::zero();
+        let mut sum_cols = F::zero();
+        for y_true in self.cm.get_classes().iter() {
+            let n_true = self.cm.support(y_true);
+            let n_pred = self.cm.predicted(y_true);
+            sum_rows += n_true * n_true;
+            sum_cols += n_pred * n_pred;
+            for n_cell in self.cm.get(y_true).values() {
+                sum_cells += *n_cell * *n_cell;
+            }
+        }
+
+        // Twice the number of pairs which are together in both partitions, in the predicted one
+        // and in the true one
+        let tk = sum_cells - n;
+        let pk = sum_cols - n;
+        let qk = sum_rows - n;
+        if tk == F::zero() || pk * qk <= F::zero() {
+            return F::zero();
+        }
+        (tk / pk).sqrt() * (tk / qk).sqrt()
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for FowlkesMallows<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn feed(metric: &mut FowlkesMallows<f64>, y_true: &[&str], y_pred: &[&str]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    #[test]
+    fn test_fowlkes_mallows() {
+        // Same value as scikit-learn's fowlkes_mallows_score
+        let mut metric = FowlkesMallows::new();
+        feed(
+            &mut metric,
+            &["cat", "ant", "cat", "cat", "ant", "bird"],
+            &["ant", "ant", "cat", "cat", "ant", "cat"],
+        );
+        assert_close(metric.get(), 0.4082482904638631);
+    }
+
+    #[test]
+    fn test_fowlkes_mallows_label_invariance() {
+        let mut metric = FowlkesMallows::new();
+        feed(&mut metric, &["a", "a", "b", "b"], &["x", "x", "y", "y"]);
+        assert_close(metric.get(), 1.0);
+
+        // Every sample in its own cluster, no pair is ever predicted
+        let mut metric = FowlkesMallows::new();
+        feed(&mut metric, &["a", "a", "b"], &["x", "y", "z"]);
+        assert_close(metric.get(), 0.0);
+    }
+}
@@ -0,0 +1,190 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::safe_div;
+use num::{Float, FromPrimitive};
+
+/// Penalty given to a disagreement between the true and the predicted class.
+///
+/// - `Unweighted`: every disagreement has the same penalty, which yields the classic Cohen's kappa.
+/// - `Linear`: the penalty grows linearly with the distance between the two classes.
+/// - `Quadratic`: the penalty grows quadratically with the distance between the two classes.
+///
+/// The weighted variants are meant for ordinal classification tasks, where the classes are ordered
+/// according to the ordering of `ClassifierTarget`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KappaWeighting {
+    Unweighted,
+    Linear,
+    Quadratic,
+}
+
+/// Cohen's Kappa score, i.e. the agreement between the true and predicted labels, corrected for
+/// the agreement expected by chance.
+///
+/// # Parameters
+///
+/// - `weighting`: How disagreements are penalized. See [`KappaWeighting`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::kappa::{CohenKappa, KappaWeighting};
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec!["cat", "ant", "cat", "cat", "ant", "bird"];
+/// let y_pred = vec!["ant", "ant", "cat", "cat", "ant", "cat"];
+///
+/// let mut metric: CohenKappa<f64> = CohenKappa::new(KappaWeighting::Unweighted);
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert!((metric.get() - 0.4285714285714286).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Cohen (1960). "A coefficient of agreement for nominal scales". Educational and
+/// Psychological Measurement 20(1):37-46.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CohenKappa<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+    weighting: KappaWeighting,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CohenKappa<F> {
+    pub fn new(weighting: KappaWeighting) -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+            weighting,
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+    // Penalty for predicting the j-th class when the i-th class is the true one.
+    fn penalty(&self, i: usize, j: usize, n_classes: usize) -> F {
+        let distance = F::from(i.abs_diff(j)).unwrap();
+        let max_distance = F::from(n_classes.saturating_sub(1).max(1)).unwrap();
+        match self.weighting {
+            KappaWeighting::Unweighted if i == j => F::zero(),
+            KappaWeighting::Unweighted => F::one(),
+            KappaWeighting::Linear => distance / max_distance,
+            KappaWeighting::Quadratic => (distance / max_distance).powi(2),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for CohenKappa<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let mut classes: Vec<_> = self.cm.get_classes().into_iter().collect();
+        classes.sort();
+        let n_classes = classes.len();
+        let total = self.cm.total_weight;
+
+        // kappa = 1 - sum(w * observed) / sum(w * expected)
+        let mut observed = F::zero();
+        let mut expected = F::zero();
+        for (i, y_true) in classes.iter().enumerate() {
+            let row = self.cm.get(y_true);
+            let n_true = self.cm.support(y_true);
+            for (j, y_pred) in classes.iter().enumerate() {
+                let w = self.penalty(i, j, n_classes);
+                let n_pred = self.cm.predicted(y_pred);
+                observed += w * *row.get(y_pred).unwrap_or(&F::zero());
+                expected += w * safe_div(n_true * n_pred, total);
+            }
+        }
+        if expected == F::zero() {
+            return F::zero();
+        }
+        F::one() - observed / expected
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for CohenKappa<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn feed<M: ClassificationMetric<f64>>(metric: &mut M, y_true: &[i32], y_pred: &[i32]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    // Same values as scikit-learn's cohen_kappa_score
+    const Y_TRUE: [i32; 10] = [0, 1, 2, 2, 1, 0, 3, 3, 2, 1];
+    const Y_PRED: [i32; 10] = [0, 2, 2, 3, 1, 1, 3, 2, 2, 1];
+
+    #[test]
+    fn test_kappa() {
+        let mut metric = CohenKappa::new(KappaWeighting::Unweighted);
+        feed(&mut metric, &Y_TRUE, &Y_PRED);
+        assert_close(metric.get(), 0.452054794520548);
+    }
+
+    #[test]
+    fn test_linear_kappa() {
+        let mut metric = CohenKappa::new(KappaWeighting::Linear);
+        feed(&mut metric, &Y_TRUE, &Y_PRED);
+        assert_close(metric.get(), 0.6296296296296297);
+    }
+
+    #[test]
+    fn test_quadratic_kappa() {
+        let mut metric = CohenKappa::new(KappaWeighting::Quadratic);
+        feed(&mut metric, &Y_TRUE, &Y_PRED);
+        assert_close(metric.get(), 0.7894736842105263);
+    }
+
+    #[test]
+    fn test_perfect_agreement() {
+        let mut metric = CohenKappa::new(KappaWeighting::Unweighted);
+        feed(&mut metric, &Y_TRUE, &Y_TRUE);
+        assert_close(metric.get(), 1.0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::{true_class_probability, Mean};
+use num::{Float, FromPrimitive};
+
+/// Logarithmic loss, i.e. the negative log-likelihood of the true labels under the predicted
+/// probabilities.
+///
+/// # Parameters
+///
+/// - `eps`: The probabilities are clipped to `[eps, 1 - eps]` before taking their logarithm, so that
+///   a confident mistake doesn't yield an infinite loss. Defaults to `1e-15`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::logloss::LogLoss;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use std::collections::HashMap;
+///
+/// let y_true = vec![true, false, false, true];
+/// let y_pred = vec![0.9, 0.1, 0.2, 0.65];
+///
+/// let mut metric: LogLoss<f64> = LogLoss::new(None);
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     let proba = HashMap::from([
+///         (ClassifierTarget::from(true), *yp),
+///         (ClassifierTarget::from(false), 1.0 - *yp),
+///     ]);
+///     metric.update(
+///         &ClassifierOutput::Probabilities(proba),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert!((metric.get() - 0.21616187468057912).abs() < 1e-10);
+/// ```
+///
+/// # Notes
+///
+/// When the output only holds the probability of a single class and the true label is another
+/// one, the probability of the true label is taken as the complement. A hard prediction is treated
+/// as a probability of 1 given to the predicted class.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogLoss<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    eps: F,
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> LogLoss<F> {
+    pub fn new(eps: Option<F>) -> Self {
+        Self {
+            eps: eps.unwrap_or(F::from(1e-15).unwrap()),
+            mean: Mean::new(),
+        }
+    }
+    fn loss(&self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> F {
+        let p = true_class_probability(&y_pred.get_probabilities(), y_true);
+        -p.max(self.eps).min(F::one() - self.eps).ln()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for LogLoss<F>
+{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for LogLoss<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        let loss = self.loss(y_pred, y_true);
+        self.mean.update(loss, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        let loss = self.loss(y_pred, y_true);
+        self.mean.revert(loss, sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for LogLoss<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_multiclass() {
+        // Same value as scikit-learn's log_loss
+        let y_true = ["cat", "dog", "bird"];
+        let y_pred = [
+            HashMap::from([("cat", 0.7), ("dog", 0.2), ("bird", 0.1)]),
+            HashMap::from([("cat", 0.3), ("dog", 0.6), ("bird", 0.1)]),
+            HashMap::from([("cat", 0.2), ("dog", 0.3), ("bird", 0.5)]),
+        ];
+        let mut metric = LogLoss::new(None);
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            let proba = yp
+                .iter()
+                .map(|(k, v)| (ClassifierTarget::from(*k), *v))
+                .collect();
+            metric.update(
+                &ClassifierOutput::Probabilities(proba),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+        assert_close(metric.get(), 0.5202159160882228);
+    }
+
+    #[test]
+    fn test_single_class_output() {
+        let mut metric = LogLoss::new(None);
+        let y_pred =
+            ClassifierOutput::Probabilities(HashMap::from([(ClassifierTarget::from(true), 0.25)]));
+        metric.update(&y_pred, &ClassifierTarget::from(false), None);
+        assert_close(metric.get(), -(0.75_f64.ln()));
+    }
+
+    #[test]
+    fn test_clipping() {
+        let mut metric = LogLoss::new(Some(1e-3));
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from("cat"));
+        metric.update(&y_pred, &ClassifierTarget::from("dog"), Some(2.0));
+        assert_close(metric.get(), -(1e-3_f64.ln()));
+        metric.revert(&y_pred, &ClassifierTarget::from("dog"), Some(2.0));
+        assert_close(metric.get(), 0.0);
+    }
+}
@@ -0,0 +1,181 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use num::{Float, FromPrimitive};
+
+/// Matthews correlation coefficient.
+///
+/// The coefficient lies between -1 and 1, 1 meaning perfect predictions and 0 meaning predictions
+/// that are no better than random ones. The multi-class generalization of Gorodkin, also known as
+/// the RK statistic, is used, which is equivalent to the usual formula when there are only two
+/// classes. It only depends on the diagonal and on the row and column sums of the confusion
+/// matrix, which are maintained incrementally.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::mcc::MCC;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![true, true, true, false];
+/// let y_pred = vec![true, false, true, true];
+///
+/// let mut metric: MCC<f64> = MCC::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert!((metric.get() + 0.3333333333333333).abs() < 1e-10);
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Gorodkin (2004). "Comparing two K-category assignments by a K-category correlation
+/// coefficient". Computational Biology and Chemistry 28(5):367-374.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MCC<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+}
+
+/// Gorodkin's RK statistic, i.e. the multi-class Matthews correlation coefficient.
+pub type RK<F> = MCC<F>;
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MCC<F> {
+    pub fn new() -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for MCC<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for MCC<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let n = self.cm.total_weight;
+        let correct = self.cm.total_true_positives();
+
+        let mut sum_pt = F::zero();
+        let mut sum_pp = F::zero();
+        let mut sum_tt = F::zero();
+        for label in self.cm.get_classes().iter() {
+            let n_true = self.cm.support(label);
+            let n_pred = self.cm.predicted(label);
+            sum_pt += n_pred * n_true;
+            sum_pp += n_pred * n_pred;
+            sum_tt += n_true * n_true;
+        }
+
+        let den = ((n * n - sum_pp) * (n * n - sum_tt)).sqrt();
+        if den == F::zero() {
+            return F::zero();
+        }
+        (correct * n - sum_pt) / den
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F> for MCC<F> {
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    #[test]
+    fn test_mcc_multiclass() {
+        // Same value as scikit-learn's matthews_corrcoef
+        let y_true = ["cat", "ant", "cat", "cat", "ant", "bird"];
+        let y_pred = ["ant", "ant", "cat", "cat", "ant", "cat"];
+        let mut metric = MCC::new();
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+        assert_close(metric.get(), 0.45226701686664544);
+    }
+
+    #[test]
+    fn test_rk_matches_binary_mcc() {
+        let samples = [
+            (true, true),
+            (false, true),
+            (true, false),
+            (false, false),
+            (true, true),
+        ];
+        let mut metric: RK<f64> = RK::new();
+        for (yt, yp) in samples.iter() {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+        // (tp * tn - fp * fn) / sqrt((tp + fp) * (tp + fn) * (tn + fp) * (tn + fn))
+        let (tp, tn, fp, fn_) = (2.0, 1.0, 1.0, 1.0);
+        let expected =
+            (tp * tn - fp * fn_) / ((tp + fp) * (tp + fn_) * (tn + fp) * (tn + fn_)).sqrt();
+        assert_close(metric.get(), expected);
+    }
+
+    #[test]
+    fn test_mcc_revert() {
+        let mut metric = MCC::new();
+        let samples = [(true, true), (false, false), (true, false), (false, false)];
+        for (yt, yp) in samples.iter() {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                Some(2.0),
+            );
+        }
+        let expected = metric.get();
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from(true));
+        metric.update(&y_pred, &ClassifierTarget::from(false), None);
+        metric.revert(&y_pred, &ClassifierTarget::from(false), None);
+        assert_close(metric.get(), expected);
+    }
+}
@@ -1,4 +1,26 @@
-// pub mod accuracy;
+pub mod accuracy;
+pub mod anomaly;
+pub mod balanced;
+pub mod brier;
+pub mod calibration;
+pub mod clustering;
+pub mod compare;
 pub mod confusion;
+pub mod cross_entropy;
+pub mod decay;
+pub mod fowlkes_mallows;
+pub mod kappa;
+pub mod logloss;
+pub mod mcc;
+pub mod multilabel;
+pub mod perf;
+pub mod quantile;
+pub mod ranking;
+pub mod regression;
+pub mod report;
 pub mod rocauc;
+pub mod rolling;
+pub mod time_series;
 pub mod traits;
+pub mod utils;
+pub mod wrapper;
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget, MultiLabelOutput};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::MultiLabelMetric;
+use crate::metrics::utils::{safe_div, Mean};
+use num::{Float, FromPrimitive};
+
+/// Confusion matrices of a multi-label classification task, i.e. one binary confusion matrix per
+/// label, telling whether the label was predicted and whether it was expected.
+///
+/// Labels are discovered on the fly. A label which appears after some samples have been seen is
+/// considered to have been correctly left out of those samples.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, MultiLabelOutput};
+/// use light_river::metrics::multilabel::MultiLabelConfusionMatrix;
+///
+/// let labels = |l: &[&str]| -> MultiLabelOutput { l.iter().map(|x| ClassifierTarget::from(*x)).collect() };
+///
+/// let mut cm: MultiLabelConfusionMatrix<f64> = MultiLabelConfusionMatrix::new();
+/// cm.update(&labels(&["a", "b"]), &labels(&["a"]), None);
+/// cm.update(&labels(&["c"]), &labels(&["a", "c"]), None);
+///
+/// let a = ClassifierTarget::from("a");
+/// assert_eq!(cm.true_positives(&a), 1.0);
+/// assert_eq!(cm.false_negatives(&a), 1.0);
+/// assert_eq!(cm.true_negatives(&ClassifierTarget::from("b")), 1.0);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct MultiLabelConfusionMatrix<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    data: HashMap<ClassifierTarget, ConfusionMatrix<F>>,
+    pub total_weight: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    MultiLabelConfusionMatrix<F>
+{
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+            total_weight: F::zero(),
+        }
+    }
+    fn _update(&mut self, y_pred: &MultiLabelOutput, y_true: &MultiLabelOutput, weight: F) {
+        self.total_weight += weight;
+        for label in y_pred.union(y_true) {
+            let predicted =
+                ClassifierOutput::Prediction(ClassifierTarget::from(y_pred.contains(label)));
+            let expected = ClassifierTarget::from(y_true.contains(label));
+            let cm = self.data.entry(label.clone()).or_default();
+            if weight < F::zero() {
+                cm.revert(&predicted, &expected, Some(-weight));
+            } else {
+                cm.update(&predicted, &expected, Some(weight));
+            }
+        }
+    }
+    pub fn update(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self._update(y_pred, y_true, sample_weight.unwrap_or(F::one()));
+    }
+    pub fn revert(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self._update(y_pred, y_true, -sample_weight.unwrap_or(F::one()));
+    }
+    /// The labels that have been predicted or expected at least once.
+    pub fn labels(&self) -> Vec<ClassifierTarget> {
+        let mut labels: Vec<_> = self
+            .data
+            .iter()
+            .filter(|(_, cm)| cm.total_weight != F::zero())
+            .map(|(label, _)| label.clone())
+            .collect();
+        labels.sort();
+        labels
+    }
+    /// The binary confusion matrix of `label`, where `true` means that the label is present.
+    pub fn get(&self, label: &ClassifierTarget) -> ConfusionMatrix<F> {
+        self.data.get(label).cloned().unwrap_or_default()
+    }
+    pub fn true_positives(&self, label: &ClassifierTarget) -> F {
+        self.data.get(label).map_or(F::zero(), |cm| {
+            cm.true_positives(&ClassifierTarget::from(true))
+        })
+    }
+    pub fn false_positives(&self, label: &ClassifierTarget) -> F {
+        self.data.get(label).map_or(F::zero(), |cm| {
+            cm.false_positives(&ClassifierTarget::from(true))
+        })
+    }
+    pub fn false_negatives(&self, label: &ClassifierTarget) -> F {
+        self.data.get(label).map_or(F::zero(), |cm| {
+            cm.false_negatives(&ClassifierTarget::from(true))
+        })
+    }
+    pub fn true_negatives(&self, label: &ClassifierTarget) -> F {
+        self.total_weight
+            - self.true_positives(label)
+            - self.false_positives(label)
+            - self.false_negatives(label)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for MultiLabelConfusionMatrix<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for MultiLabelConfusionMatrix<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.data.values_mut().for_each(|cm| cm.decay(factor));
+        self.total_weight *= factor;
+    }
+}
+
+/// Hamming loss, i.e. the fraction of labels that are incorrectly predicted.
+///
+/// The number of labels is the number of distinct labels seen so far.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, MultiLabelOutput};
+/// use light_river::metrics::multilabel::HammingLoss;
+/// use light_river::metrics::traits::MultiLabelMetric;
+///
+/// let labels = |l: &[&str]| -> MultiLabelOutput { l.iter().map(|x| ClassifierTarget::from(*x)).collect() };
+///
+/// let mut metric: HammingLoss<f64> = HammingLoss::new();
+/// metric.update(&labels(&["a", "b"]), &labels(&["a", "c"]), None);
+/// metric.update(&labels(&["c"]), &labels(&["c"]), None);
+/// // 2 mistakes out of 2 samples x 3 labels
+/// assert_eq!(metric.get(), 1.0 / 3.0);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HammingLoss<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: MultiLabelConfusionMatrix<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> HammingLoss<F> {
+    pub fn new() -> Self {
+        Self {
+            cm: MultiLabelConfusionMatrix::new(),
+        }
+    }
+    pub fn confusion_matrix(&self) -> &MultiLabelConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for HammingLoss<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MultiLabelMetric<F>
+    for HammingLoss<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        let labels = self.cm.labels();
+        let mistakes = labels.iter().fold(F::zero(), |sum, label| {
+            sum + self.cm.false_positives(label) + self.cm.false_negatives(label)
+        });
+        safe_div(
+            mistakes,
+            self.cm.total_weight * F::from(labels.len()).unwrap(),
+        )
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for HammingLoss<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+/// Which example-based score is computed by [`ExampleScore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum ExampleKind {
+    SubsetAccuracy,
+    Precision,
+    Recall,
+    F1,
+}
+
+impl ExampleKind {
+    fn score<F: Float>(&self, y_pred: &MultiLabelOutput, y_true: &MultiLabelOutput) -> F {
+        // Predicting no label when no label is expected is a perfect prediction
+        if y_pred.is_empty() && y_true.is_empty() {
+            return F::one();
+        }
+        let n_common = F::from(y_pred.intersection(y_true).count()).unwrap();
+        let n_pred = F::from(y_pred.len()).unwrap();
+        let n_true = F::from(y_true.len()).unwrap();
+        match self {
+            ExampleKind::SubsetAccuracy if y_pred == y_true => F::one(),
+            ExampleKind::SubsetAccuracy => F::zero(),
+            ExampleKind::Precision => safe_div(n_common, n_pred),
+            ExampleKind::Recall => safe_div(n_common, n_true),
+            ExampleKind::F1 => safe_div(n_common + n_common, n_pred + n_true),
+        }
+    }
+}
+
+/// Average over the samples of a score which is computed on each sample.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExampleScore<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    kind: ExampleKind,
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ExampleScore<F> {
+    fn new(kind: ExampleKind) -> Self {
+        Self {
+            kind,
+            mean: Mean::new(),
+        }
+    }
+    /// Subset accuracy, i.e. the fraction of samples whose labels are all correctly predicted.
+    ///
+    /// ```
+    /// use light_river::common::{ClassifierTarget, MultiLabelOutput};
+    /// use light_river::metrics::multilabel::ExampleScore;
+    /// use light_river::metrics::traits::MultiLabelMetric;
+    ///
+    /// let labels = |l: &[&str]| -> MultiLabelOutput { l.iter().map(|x| ClassifierTarget::from(*x)).collect() };
+    ///
+    /// let mut metric: ExampleScore<f64> = ExampleScore::subset_accuracy();
+    /// metric.update(&labels(&["a", "b"]), &labels(&["a", "b"]), None);
+    /// metric.update(&labels(&["a"]), &labels(&["a", "b"]), None);
+    /// assert_eq!(metric.get(), 0.5);
+    /// ```
+    pub fn subset_accuracy() -> Self {
+        Self::new(ExampleKind::SubsetAccuracy)
+    }
+    /// Example-based precision, i.e. the average fraction of predicted labels that are correct.
+    pub fn precision() -> Self {
+        Self::new(ExampleKind::Precision)
+    }
+    /// Example-based recall, i.e. the average fraction of expected labels that are predicted.
+    pub fn recall() -> Self {
+        Self::new(ExampleKind::Recall)
+    }
+    /// Example-based F1 score, i.e. the average harmonic mean of the precision and recall of each
+    /// sample.
+    pub fn f1() -> Self {
+        Self::new(ExampleKind::F1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MultiLabelMetric<F>
+    for ExampleScore<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            self.kind.score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn revert(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            self.kind.score(y_pred, y_true),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for ExampleScore<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn labels(l: &[&str]) -> MultiLabelOutput {
+        l.iter().map(|x| ClassifierTarget::from(*x)).collect()
+    }
+
+    // Same values as scikit-learn
+    fn feed<M: MultiLabelMetric<f64>>(metric: &mut M) {
+        let y_true = [vec!["a", "b"], vec!["b"], vec!["a", "c"], vec![]];
+        let y_pred = [vec!["a"], vec!["b", "c"], vec!["a", "c"], vec!["c"]];
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(&labels(yp), &labels(yt), None);
+        }
+    }
+
+    #[test]
+    fn test_hamming_loss() {
+        let mut metric = HammingLoss::new();
+        feed(&mut metric);
+        assert_close(metric.get(), 0.25);
+    }
+
+    #[test]
+    fn test_example_scores() {
+        let mut accuracy = ExampleScore::subset_accuracy();
+        let mut precision = ExampleScore::precision();
+        let mut recall = ExampleScore::recall();
+        let mut f1 = ExampleScore::f1();
+        feed(&mut accuracy);
+        feed(&mut precision);
+        feed(&mut recall);
+        feed(&mut f1);
+        assert_close(accuracy.get(), 0.25);
+        assert_close(precision.get(), 0.625);
+        assert_close(recall.get(), 0.625);
+        assert_close(f1.get(), (2.0 / 3.0 + 2.0 / 3.0 + 1.0) / 4.0);
+    }
+
+    #[test]
+    fn test_confusion_matrix_revert() {
+        let mut cm = MultiLabelConfusionMatrix::new();
+        cm.update(&labels(&["a"]), &labels(&["a", "b"]), None);
+        cm.update(&labels(&["c"]), &labels(&["b"]), Some(2.0));
+        cm.revert(&labels(&["c"]), &labels(&["b"]), Some(2.0));
+        assert_eq!(
+            cm.labels(),
+            vec![ClassifierTarget::from("a"), ClassifierTarget::from("b")]
+        );
+        assert_eq!(cm.total_weight, 1.0);
+        assert_eq!(cm.false_negatives(&ClassifierTarget::from("b")), 1.0);
+        assert_eq!(cm.true_negatives(&ClassifierTarget::from("c")), 1.0);
+    }
+}
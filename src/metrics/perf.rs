@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Latency of the calls made to a model, over a sliding window of the most recent calls.
+///
+/// Calls are timed by wrapping them with `measure`, or recorded with `record` when they are timed
+/// elsewhere. Quantiles are computed with the nearest-rank method.
+///
+/// # Parameters
+///
+/// - `window_size`: The number of calls to keep track of.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use light_river::metrics::perf::Latency;
+///
+/// let mut latency = Latency::new(100);
+/// let prediction = latency.measure(|| 40 + 2);
+/// assert_eq!(prediction, 42);
+///
+/// for ms in 1..=100 {
+///     latency.record(Duration::from_millis(ms));
+/// }
+/// assert_eq!(latency.len(), 100);
+/// assert_eq!(latency.p50(), Some(Duration::from_millis(50)));
+/// assert_eq!(latency.p99(), Some(Duration::from_millis(99)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Latency {
+    window_size: usize,
+    window: VecDeque<Duration>,
+}
+
+impl Latency {
+    pub fn new(window_size: usize) -> Self {
+        assert!(window_size > 0, "window_size must be strictly positive");
+        Self {
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+        }
+    }
+    /// Run `f`, e.g. a call to `predict_one`, record how long it took and return its result.
+    pub fn measure<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(start.elapsed());
+        result
+    }
+    pub fn record(&mut self, duration: Duration) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(duration);
+    }
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+    /// Number of calls currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// The `q`-th quantile of the latencies in the window, with `q` in `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        assert!((0.0..=1.0).contains(&q), "q must lie in [0, 1]");
+        if self.window.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.window.iter().cloned().collect();
+        sorted.sort();
+        let rank = (q * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+    pub fn p50(&self) -> Option<Duration> {
+        self.quantile(0.5)
+    }
+    pub fn p95(&self) -> Option<Duration> {
+        self.quantile(0.95)
+    }
+    pub fn p99(&self) -> Option<Duration> {
+        self.quantile(0.99)
+    }
+    pub fn mean(&self) -> Option<Duration> {
+        if self.window.is_empty() {
+            return None;
+        }
+        Some(self.window.iter().sum::<Duration>() / self.window.len() as u32)
+    }
+}
+
+/// Number of samples processed per second, over a sliding window of time.
+///
+/// Until a whole window has elapsed since the collector was created, the rate is computed over
+/// the elapsed time only.
+///
+/// # Parameters
+///
+/// - `window`: The duration over which the rate is computed.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use light_river::metrics::perf::Throughput;
+///
+/// let start = Instant::now();
+/// let mut throughput = Throughput::starting_at(Duration::from_secs(10), start);
+/// for s in 1..=20 {
+///     throughput.record_at(100, start + Duration::from_secs(s));
+/// }
+/// // Only the last 10 seconds are taken into account
+/// assert_eq!(throughput.rate_at(start + Duration::from_secs(20)), 100.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Throughput {
+    window: Duration,
+    started: Instant,
+    events: VecDeque<(Instant, usize)>,
+    n_samples: usize,
+}
+
+impl Throughput {
+    pub fn new(window: Duration) -> Self {
+        Self::starting_at(window, Instant::now())
+    }
+    /// Create a collector whose measurements start at `started` instead of now.
+    pub fn starting_at(window: Duration, started: Instant) -> Self {
+        assert!(!window.is_zero(), "window must be strictly positive");
+        Self {
+            window,
+            started,
+            events: VecDeque::new(),
+            n_samples: 0,
+        }
+    }
+    /// Run `f`, e.g. a call to `learn_one`, record that it processed `n_samples` samples and
+    /// return its result.
+    pub fn measure<T>(&mut self, n_samples: usize, f: impl FnOnce() -> T) -> T {
+        let result = f();
+        self.record(n_samples);
+        result
+    }
+    /// Record that `n_samples` samples have just been processed.
+    pub fn record(&mut self, n_samples: usize) {
+        self.record_at(n_samples, Instant::now());
+    }
+    /// Record that `n_samples` samples have been processed at `instant`. Instants are expected to
+    /// be increasing.
+    pub fn record_at(&mut self, n_samples: usize, instant: Instant) {
+        self.events.push_back((instant, n_samples));
+        self.n_samples += n_samples;
+        self.expire(instant);
+    }
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+    /// Samples per second over the window ending now.
+    pub fn rate(&mut self) -> f64 {
+        self.rate_at(Instant::now())
+    }
+    /// Samples per second over the window ending at `instant`.
+    pub fn rate_at(&mut self, instant: Instant) -> f64 {
+        self.expire(instant);
+        let elapsed = instant
+            .saturating_duration_since(self.started)
+            .min(self.window);
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        self.n_samples as f64 / elapsed.as_secs_f64()
+    }
+    // Forget the events that are out of the window ending at `instant`.
+    fn expire(&mut self, instant: Instant) {
+        while let Some((oldest, n_samples)) = self.events.front() {
+            if instant.saturating_duration_since(*oldest) < self.window {
+                break;
+            }
+            self.n_samples -= n_samples;
+            self.events.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_window() {
+        let mut latency = Latency::new(4);
+        assert_eq!(latency.p50(), None);
+        for ms in [10, 50, 20, 40, 30] {
+            latency.record(Duration::from_millis(ms));
+        }
+        // The first call is out of the window
+        assert_eq!(latency.len(), 4);
+        assert_eq!(latency.quantile(0.0), Some(Duration::from_millis(20)));
+        assert_eq!(latency.p50(), Some(Duration::from_millis(30)));
+        assert_eq!(latency.p95(), Some(Duration::from_millis(50)));
+        assert_eq!(latency.mean(), Some(Duration::from_millis(35)));
+    }
+
+    #[test]
+    fn test_throughput_warm_up() {
+        let start = Instant::now();
+        let mut throughput = Throughput::starting_at(Duration::from_secs(60), start);
+        assert_eq!(throughput.rate_at(start), 0.0);
+        throughput.record_at(30, start + Duration::from_secs(1));
+        throughput.record_at(30, start + Duration::from_secs(2));
+        assert_eq!(throughput.rate_at(start + Duration::from_secs(3)), 20.0);
+        // Once everything is out of the window, the rate drops to zero
+        assert_eq!(throughput.rate_at(start + Duration::from_secs(120)), 0.0);
+    }
+}
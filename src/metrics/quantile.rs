@@ -0,0 +1,138 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::RegressionTarget;
+use crate::metrics::traits::RegressionMetric;
+use crate::sketch::p2::P2Quantile;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Quantile of the absolute error, e.g. the median or the 99th percentile.
+///
+/// Keeping track of the exact quantile would require storing all the errors. Instead, the P²
+/// algorithm is used, see [`P2Quantile`], which approximates the quantile with five markers and therefore runs in
+/// constant time and memory. The quantile is exact until five samples have been seen.
+///
+/// # Parameters
+///
+/// - `q`: The quantile to track, between 0 and 1.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::quantile::RollingQuantile;
+/// use light_river::metrics::traits::RegressionMetric;
+/// use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+///
+/// let mut p90: RollingQuantile<f64> = RollingQuantile::new(0.9);
+/// // Absolute errors are a permutation of 1, 2, ..., 1000
+/// let mut errors: Vec<f64> = (1..=1000).map(f64::from).collect();
+/// errors.shuffle(&mut StdRng::seed_from_u64(42));
+/// for error in errors {
+///     p90.update(0.0, error, None);
+/// }
+/// assert!((p90.get() - 900.0).abs() < 10.0);
+/// ```
+///
+/// # Notes
+///
+/// Sample weights are ignored and reverting samples is not supported, as the markers can't be
+/// moved back.
+///
+/// # References
+///
+/// [^1]: R. Jain and I. Chlamtac (1985). "The P² algorithm for dynamic calculation of quantiles
+/// and histograms without storing observations". Communications of the ACM 28(10):1076-1085.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RollingQuantile<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    estimator: P2Quantile<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RollingQuantile<F> {
+    pub fn new(q: F) -> Self {
+        Self {
+            estimator: P2Quantile::new(q),
+        }
+    }
+    pub fn q(&self) -> F {
+        self.estimator.q()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for RollingQuantile<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        _sample_weight: Option<F>,
+    ) {
+        self.estimator.update((y_true - y_pred).abs());
+    }
+    /// # Panics
+    ///
+    /// Always, as the P² markers can't be moved back.
+    fn revert(
+        &mut self,
+        _y_true: RegressionTarget<F>,
+        _y_pred: RegressionTarget<F>,
+        _sample_weight: Option<F>,
+    ) {
+        panic!("RollingQuantile doesn't support reverting samples");
+    }
+    fn get(&self) -> F {
+        self.estimator.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::permutation;
+
+    #[test]
+    fn test_exact_warm_up() {
+        let mut metric = RollingQuantile::new(0.5);
+        assert_eq!(metric.get(), 0.0);
+        for (y_true, y_pred) in [(3.0, 1.0), (0.0, 4.0), (1.0, 2.0), (-1.0, 2.0)] {
+            metric.update(y_true, y_pred, None);
+        }
+        // Median of 1, 2, 3 and 4
+        assert_eq!(metric.get(), 2.5);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        for q in [0.1, 0.5, 0.95, 0.99] {
+            let mut metric = RollingQuantile::new(q);
+            for error in permutation(10000) {
+                metric.update(error, 0.0, None);
+            }
+            let expected = q * 10000.0;
+            assert!(
+                (metric.get() - expected).abs() < 100.0,
+                "q = {}: {} != {}",
+                q,
+                metric.get(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_extremes() {
+        let mut min = RollingQuantile::new(0.0);
+        let mut max = RollingQuantile::new(1.0);
+        for error in permutation(1000) {
+            min.update(error, 0.0, None);
+            max.update(error, 0.0, None);
+        }
+        assert_eq!(min.get(), 1.0);
+        assert_eq!(max.get(), 1000.0);
+    }
+}
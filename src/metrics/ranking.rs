@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::ClassifierTarget;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::RankingMetric;
+use crate::metrics::utils::{safe_div, Mean};
+use num::{Float, FromPrimitive};
+
+// Relevance of an item, items that are not labelled or whose relevance is NaN being irrelevant.
+fn relevance_of<F: Float>(relevance: &HashMap<ClassifierTarget, F>, item: &ClassifierTarget) -> F {
+    match relevance.get(item) {
+        Some(rel) if !rel.is_nan() => *rel,
+        _ => F::zero(),
+    }
+}
+
+// Discounted cumulative gain of a list of relevances, truncated to the first `k` ones.
+fn dcg<F: Float>(relevances: impl Iterator<Item = F>, k: usize) -> F {
+    relevances
+        .take(k)
+        .enumerate()
+        .fold(F::zero(), |sum, (i, rel)| {
+            sum + rel / F::from(i + 2).unwrap().log2()
+        })
+}
+
+/// Normalized discounted cumulative gain, truncated to the first `k` items of each ranking.
+///
+/// The gain of each item is its relevance, discounted logarithmically with its position in the
+/// ranking. The result is normalized by the gain of the ideal ranking, which sorts the items by
+/// decreasing relevance. Rankings without any relevant item have a score of 0, and items whose
+/// relevance is NaN are irrelevant.
+///
+/// # Parameters
+///
+/// - `k`: The number of items to take into account. All of them are used if `None`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use light_river::common::ClassifierTarget;
+/// use light_river::metrics::ranking::NDCG;
+/// use light_river::metrics::traits::RankingMetric;
+///
+/// let ranking: Vec<ClassifierTarget> = ["a", "b", "c"].iter().map(|x| ClassifierTarget::from(*x)).collect();
+/// let relevance: HashMap<ClassifierTarget, f64> =
+///     HashMap::from([(ClassifierTarget::from("c"), 1.0), (ClassifierTarget::from("a"), 1.0)]);
+///
+/// let mut metric: NDCG<f64> = NDCG::new(None);
+/// metric.update(&ranking, &relevance, None);
+/// assert!((metric.get() - 0.9197207891481876).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NDCG<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    k: Option<usize>,
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> NDCG<F> {
+    pub fn new(k: Option<usize>) -> Self {
+        if let Some(k) = k {
+            assert!(k > 0, "k must be strictly positive");
+        }
+        Self {
+            k,
+            mean: Mean::new(),
+        }
+    }
+    pub fn k(&self) -> Option<usize> {
+        self.k
+    }
+    fn score(&self, ranking: &[ClassifierTarget], relevance: &HashMap<ClassifierTarget, F>) -> F {
+        let k = self.k.unwrap_or(usize::MAX);
+        let mut ideal: Vec<F> = relevance
+            .values()
+            .cloned()
+            .filter(|rel| !rel.is_nan())
+            .collect();
+        ideal.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        safe_div(
+            dcg(ranking.iter().map(|item| relevance_of(relevance, item)), k),
+            dcg(ideal.into_iter(), k),
+        )
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RankingMetric<F>
+    for NDCG<F>
+{
+    fn update(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        let score = self.score(ranking, relevance);
+        self.mean.update(score, sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        let score = self.score(ranking, relevance);
+        self.mean.revert(score, sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for NDCG<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+/// Mean average precision.
+///
+/// The average precision of a ranking is the mean of the precisions at the positions of the
+/// relevant items, the relevant items which are missing from the ranking counting as a precision
+/// of 0. An item is relevant if its relevance is strictly positive.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use light_river::common::ClassifierTarget;
+/// use light_river::metrics::ranking::MeanAveragePrecision;
+/// use light_river::metrics::traits::RankingMetric;
+///
+/// let ranking: Vec<ClassifierTarget> = ["a", "b", "c"].iter().map(|x| ClassifierTarget::from(*x)).collect();
+/// let relevance: HashMap<ClassifierTarget, f64> =
+///     HashMap::from([(ClassifierTarget::from("c"), 1.0), (ClassifierTarget::from("a"), 1.0)]);
+///
+/// let mut metric: MeanAveragePrecision<f64> = MeanAveragePrecision::new();
+/// metric.update(&ranking, &relevance, None);
+/// // (1 / 1 + 2 / 3) / 2
+/// assert!((metric.get() - 5.0 / 6.0).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeanAveragePrecision<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    MeanAveragePrecision<F>
+{
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+    fn score(ranking: &[ClassifierTarget], relevance: &HashMap<ClassifierTarget, F>) -> F {
+        let n_relevant = relevance.values().filter(|rel| **rel > F::zero()).count();
+        let mut n_hits = 0;
+        let mut sum_precisions = F::zero();
+        for (i, item) in ranking.iter().enumerate() {
+            if relevance_of(relevance, item) > F::zero() {
+                n_hits += 1;
+                sum_precisions += F::from(n_hits).unwrap() / F::from(i + 1).unwrap();
+            }
+        }
+        safe_div(sum_precisions, F::from(n_relevant).unwrap())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for MeanAveragePrecision<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RankingMetric<F>
+    for MeanAveragePrecision<F>
+{
+    fn update(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            Self::score(ranking, relevance),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn revert(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            Self::score(ranking, relevance),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for MeanAveragePrecision<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+/// Mean reciprocal rank, i.e. the average inverse of the position of the first relevant item.
+///
+/// An item is relevant if its relevance is strictly positive. Rankings without any relevant item
+/// have a reciprocal rank of 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use light_river::common::ClassifierTarget;
+/// use light_river::metrics::ranking::MRR;
+/// use light_river::metrics::traits::RankingMetric;
+///
+/// let ranking: Vec<ClassifierTarget> = ["a", "b", "c"].iter().map(|x| ClassifierTarget::from(*x)).collect();
+///
+/// let mut metric: MRR<f64> = MRR::new();
+/// metric.update(&ranking, &HashMap::from([(ClassifierTarget::from("b"), 1.0)]), None);
+/// metric.update(&ranking, &HashMap::from([(ClassifierTarget::from("a"), 1.0)]), None);
+/// assert_eq!(metric.get(), 0.75);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MRR<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MRR<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+    fn score(ranking: &[ClassifierTarget], relevance: &HashMap<ClassifierTarget, F>) -> F {
+        ranking
+            .iter()
+            .position(|item| relevance_of(relevance, item) > F::zero())
+            .map_or(F::zero(), |i| F::one() / F::from(i + 1).unwrap())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for MRR<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RankingMetric<F>
+    for MRR<F>
+{
+    fn update(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            Self::score(ranking, relevance),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn revert(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            Self::score(ranking, relevance),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F> for MRR<F> {
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn ranking(items: &[&str]) -> Vec<ClassifierTarget> {
+        items.iter().map(|x| ClassifierTarget::from(*x)).collect()
+    }
+
+    fn relevance(items: &[(&str, f64)]) -> HashMap<ClassifierTarget, f64> {
+        items
+            .iter()
+            .map(|(x, rel)| (ClassifierTarget::from(*x), *rel))
+            .collect()
+    }
+
+    fn feed<M: RankingMetric<f64>>(metric: &mut M) {
+        metric.update(
+            &ranking(&["a", "b", "c", "d"]),
+            &relevance(&[("b", 3.0), ("d", 1.0), ("e", 2.0)]),
+            None,
+        );
+        metric.update(
+            &ranking(&["a", "b", "c"]),
+            &relevance(&[("a", 1.0), ("c", 0.0)]),
+            None,
+        );
+        metric.update(&ranking(&["a", "b"]), &relevance(&[]), None);
+    }
+
+    #[test]
+    fn test_ndcg() {
+        let mut metric = NDCG::new(None);
+        feed(&mut metric);
+        assert_close(metric.get(), (0.4879324590115489 + 1.0 + 0.0) / 3.0);
+    }
+
+    #[test]
+    fn test_ndcg_nan_relevance() {
+        let mut metric = NDCG::new(None);
+        metric.update(
+            &ranking(&["a", "b"]),
+            &relevance(&[("a", f64::NAN), ("b", 1.0)]),
+            None,
+        );
+        assert_close(metric.get(), 1.0 / 3.0_f64.log2());
+    }
+
+    #[test]
+    fn test_ndcg_at_k() {
+        let mut metric = NDCG::new(Some(3));
+        feed(&mut metric);
+        assert_close(metric.get(), (0.39748952229168844 + 1.0 + 0.0) / 3.0);
+    }
+
+    #[test]
+    fn test_map() {
+        let mut metric = MeanAveragePrecision::new();
+        feed(&mut metric);
+        assert_close(metric.get(), (1.0 / 3.0 + 1.0 + 0.0) / 3.0);
+    }
+
+    #[test]
+    fn test_mrr() {
+        let mut metric = MRR::new();
+        feed(&mut metric);
+        assert_close(metric.get(), (0.5 + 1.0 + 0.0) / 3.0);
+    }
+
+    #[test]
+    fn test_revert() {
+        let mut metric = MRR::new();
+        feed(&mut metric);
+        let expected = metric.get();
+        let (r, rel) = (ranking(&["x", "y"]), relevance(&[("y", 1.0)]));
+        metric.update(&r, &rel, Some(2.0));
+        metric.revert(&r, &rel, Some(2.0));
+        assert_close(metric.get(), expected);
+    }
+}
@@ -0,0 +1,597 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::RegressionTarget;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::RegressionMetric;
+use crate::metrics::utils::Mean;
+use num::{Float, FromPrimitive};
+
+/// Mean absolute error.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![3.0, -0.5, 2.0, 7.0];
+/// let y_pred = vec![2.5, 0.0, 2.0, 8.0];
+///
+/// let mut metric: MAE<f64> = MAE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert_eq!(metric.get(), 0.5);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MAE<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MAE<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for MAE<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for MAE<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean
+            .update((y_true - y_pred).abs(), sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean
+            .revert((y_true - y_pred).abs(), sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+/// Mean squared error.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::MSE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![3.0, -0.5, 2.0, 7.0];
+/// let y_pred = vec![2.5, 0.0, 2.0, 8.0];
+///
+/// let mut metric: MSE<f64> = MSE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert_eq!(metric.get(), 0.375);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MSE<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MSE<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for MSE<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for MSE<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean
+            .update((y_true - y_pred).powi(2), sample_weight.unwrap_or(F::one()));
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean
+            .revert((y_true - y_pred).powi(2), sample_weight.unwrap_or(F::one()));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+/// Root mean squared error.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::RMSE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![0.0, 1.0];
+/// let y_pred = vec![2.0, 3.0];
+///
+/// let mut metric: RMSE<f64> = RMSE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert_eq!(metric.get(), 2.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RMSE<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mse: MSE<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RMSE<F> {
+    pub fn new() -> Self {
+        Self { mse: MSE::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for RMSE<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for RMSE<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mse.update(y_true, y_pred, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mse.revert(y_true, y_pred, sample_weight);
+    }
+    fn get(&self) -> F {
+        // Reverting may leave a tiny negative residue because of floating point errors
+        self.mse.get().max(F::zero()).sqrt()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+/// Coefficient of determination (R²).
+///
+/// The score is 1 for perfect predictions, 0 for a model that always predicts the mean of the
+/// targets, and can be arbitrarily negative. It is 0 until at least two samples have been seen.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::R2;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![3.0, -0.5, 2.0, 7.0];
+/// let y_pred = vec![2.5, 0.0, 2.0, 8.0];
+///
+/// let mut metric: R2<f64> = R2::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert!((metric.get() - 0.9486081370449679).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct R2<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    n: F,
+    y_mean: F,
+    // Weighted sum of squared deviations of the targets from their mean
+    ss_tot: F,
+    // Weighted sum of squared residuals
+    ss_res: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> R2<F> {
+    pub fn new() -> Self {
+        Self {
+            n: F::zero(),
+            y_mean: F::zero(),
+            ss_tot: F::zero(),
+            ss_res: F::zero(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for R2<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for R2<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        // Weighted version of Welford's algorithm
+        let w = sample_weight.unwrap_or(F::one());
+        self.n += w;
+        let delta = y_true - self.y_mean;
+        self.y_mean += w * delta / self.n;
+        self.ss_tot += w * delta * (y_true - self.y_mean);
+        self.ss_res += w * (y_true - y_pred).powi(2);
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        let w = sample_weight.unwrap_or(F::one());
+        self.ss_res -= w * (y_true - y_pred).powi(2);
+        self.n -= w;
+        if self.n == F::zero() {
+            *self = Self::new();
+            return;
+        }
+        let delta = y_true - self.y_mean;
+        self.y_mean -= w * delta / self.n;
+        self.ss_tot -= w * delta * (y_true - self.y_mean);
+    }
+    fn get(&self) -> F {
+        if self.ss_tot > F::zero() {
+            F::one() - self.ss_res / self.ss_tot
+        } else {
+            F::zero()
+        }
+    }
+    fn bigger_is_better(&self) -> bool {
+        true
+    }
+}
+
+/// Symmetric mean absolute percentage error.
+///
+/// The score is expressed as a percentage and lies between 0 and 200. Samples for which both the
+/// target and the prediction are 0 count as perfect predictions.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::SMAPE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![0.0, 0.07533, 0.07533, 0.07533, 0.07533, 0.07533, 0.07533, 0.0672, 0.0672];
+/// let y_pred = vec![0.0, 0.102, 0.107, 0.047, 0.1, 0.032, 0.047, 0.108, 0.089];
+///
+/// let mut metric: SMAPE<f64> = SMAPE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert!((metric.get() - 37.8694).abs() < 1e-4);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SMAPE<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> SMAPE<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+    fn error(y_true: F, y_pred: F) -> F {
+        let den = y_true.abs() + y_pred.abs();
+        if den == F::zero() {
+            F::zero()
+        } else {
+            (y_true - y_pred).abs() / den
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for SMAPE<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for SMAPE<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.update(
+            Self::error(y_true, y_pred),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        self.mean.revert(
+            Self::error(y_true, y_pred),
+            sample_weight.unwrap_or(F::one()),
+        );
+    }
+    fn get(&self) -> F {
+        F::from(200.0).unwrap() * self.mean.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+/// Mean absolute percentage error.
+///
+/// The score is expressed as a percentage. Samples whose target is 0 are left out, as their error
+/// can't be expressed relative to the target.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::MAPE;
+/// use light_river::metrics::traits::RegressionMetric;
+///
+/// let y_true = vec![3.0, -0.5, 2.0, 7.0, 0.0];
+/// let y_pred = vec![2.5, 0.0, 2.0, 8.0, 1.0];
+///
+/// let mut metric: MAPE<f64> = MAPE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(*yt, *yp, None);
+/// }
+/// assert!((metric.get() - 32.7381).abs() < 1e-4);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MAPE<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MAPE<F> {
+    pub fn new() -> Self {
+        Self { mean: Mean::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for MAPE<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> RegressionMetric<F>
+    for MAPE<F>
+{
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        if y_true != F::zero() {
+            self.mean.update(
+                ((y_true - y_pred) / y_true).abs(),
+                sample_weight.unwrap_or(F::one()),
+            );
+        }
+    }
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    ) {
+        if y_true != F::zero() {
+            self.mean.revert(
+                ((y_true - y_pred) / y_true).abs(),
+                sample_weight.unwrap_or(F::one()),
+            );
+        }
+    }
+    fn get(&self) -> F {
+        F::from(100.0).unwrap() * self.mean.get()
+    }
+    fn bigger_is_better(&self) -> bool {
+        false
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F> for MAE<F> {
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F> for MSE<F> {
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for RMSE<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mse.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for SMAPE<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for MAPE<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.mean.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F> for R2<F> {
+    fn decay(&mut self, factor: F) {
+        // The weighted mean of the targets is left untouched by the decay
+        self.n *= factor;
+        self.ss_tot *= factor;
+        self.ss_res *= factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const Y_TRUE: [f64; 5] = [3.0, -0.5, 2.0, 7.0, 4.2];
+    const Y_PRED: [f64; 5] = [2.5, 0.0, 2.1, 7.8, 5.3];
+    const WEIGHTS: [f64; 5] = [1.0, 2.0, 0.5, 1.5, 3.0];
+
+    // Check that updating with weights is equivalent to computing the metric in batch, and that
+    // reverting the last samples brings the metric back to its previous value.
+    fn check<M: RegressionMetric<f64>>(mut metric: M, batch: fn(&[f64], &[f64], &[f64]) -> f64) {
+        for i in 0..Y_TRUE.len() {
+            metric.update(Y_TRUE[i], Y_PRED[i], Some(WEIGHTS[i]));
+        }
+        assert_close(metric.get(), batch(&Y_TRUE, &Y_PRED, &WEIGHTS));
+        for i in (2..Y_TRUE.len()).rev() {
+            metric.revert(Y_TRUE[i], Y_PRED[i], Some(WEIGHTS[i]));
+        }
+        assert_close(
+            metric.get(),
+            batch(&Y_TRUE[..2], &Y_PRED[..2], &WEIGHTS[..2]),
+        );
+    }
+
+    fn weighted_mean(values: impl Iterator<Item = f64>, w: &[f64]) -> f64 {
+        values.zip(w.iter()).map(|(v, w)| v * w).sum::<f64>() / w.iter().sum::<f64>()
+    }
+
+    #[test]
+    fn test_mae() {
+        check(MAE::new(), |yt, yp, w| {
+            weighted_mean(yt.iter().zip(yp).map(|(t, p)| (t - p).abs()), w)
+        });
+    }
+
+    #[test]
+    fn test_mse() {
+        check(MSE::new(), |yt, yp, w| {
+            weighted_mean(yt.iter().zip(yp).map(|(t, p)| (t - p).powi(2)), w)
+        });
+    }
+
+    #[test]
+    fn test_rmse() {
+        check(RMSE::new(), |yt, yp, w| {
+            weighted_mean(yt.iter().zip(yp).map(|(t, p)| (t - p).powi(2)), w).sqrt()
+        });
+    }
+
+    #[test]
+    fn test_r2() {
+        check(R2::new(), |yt, yp, w| {
+            let mean = weighted_mean(yt.iter().copied(), w);
+            let ss_res: f64 = (0..yt.len()).map(|i| w[i] * (yt[i] - yp[i]).powi(2)).sum();
+            let ss_tot: f64 = (0..yt.len()).map(|i| w[i] * (yt[i] - mean).powi(2)).sum();
+            1.0 - ss_res / ss_tot
+        });
+    }
+
+    #[test]
+    fn test_smape() {
+        check(SMAPE::new(), |yt, yp, w| {
+            200.0
+                * weighted_mean(
+                    yt.iter()
+                        .zip(yp)
+                        .map(|(t, p)| (t - p).abs() / (t.abs() + p.abs())),
+                    w,
+                )
+        });
+    }
+
+    #[test]
+    fn test_mape() {
+        check(MAPE::new(), |yt, yp, w| {
+            100.0 * weighted_mean(yt.iter().zip(yp).map(|(t, p)| ((t - p) / t).abs()), w)
+        });
+    }
+
+    #[test]
+    fn test_revert_everything() {
+        let mut metric = R2::new();
+        metric.update(1.0, 2.0, None);
+        metric.update(3.0, 2.0, None);
+        metric.revert(3.0, 2.0, None);
+        metric.revert(1.0, 2.0, None);
+        assert_eq!(metric.get(), 0.0);
+        metric.update(1.0, 1.0, None);
+        metric.update(3.0, 3.0, None);
+        assert_close(metric.get(), 1.0);
+    }
+
+    #[test]
+    fn test_is_better_than() {
+        let mut good = MAE::new();
+        let mut bad = MAE::new();
+        good.update(1.0, 1.1, None);
+        bad.update(1.0, 2.0, None);
+        assert!(good.is_better_than(&bad));
+        assert!(!bad.is_better_than(&good));
+    }
+}
@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::utils::safe_div;
+use num::{Float, FromPrimitive};
+
+/// Strategy used to reduce per-class scores to a single value.
+///
+/// - `Binary`: only the score of the given positive class is reported.
+/// - `Micro`: true positives, false positives and false negatives are summed over all classes
+///   before the score is computed.
+/// - `Macro`: the score is computed for each class and then averaged, each class having the same
+///   importance.
+/// - `Weighted`: the score is computed for each class and then averaged, each class being weighted
+///   by its support (i.e. the total weight of the samples that belong to it).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Average {
+    Binary(ClassifierTarget),
+    Micro,
+    Macro,
+    Weighted,
+}
+
+pub(crate) fn precision_of<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    cm: &ConfusionMatrix<F>,
+    label: &ClassifierTarget,
+) -> F {
+    let tp = cm.true_positives(label);
+    safe_div(tp, tp + cm.false_positives(label))
+}
+
+pub(crate) fn recall_of<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    cm: &ConfusionMatrix<F>,
+    label: &ClassifierTarget,
+) -> F {
+    let tp = cm.true_positives(label);
+    safe_div(tp, tp + cm.false_negatives(label))
+}
+
+pub(crate) fn fbeta_of<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    precision: F,
+    recall: F,
+    beta: F,
+) -> F {
+    let beta2 = beta * beta;
+    safe_div(
+        (F::one() + beta2) * precision * recall,
+        beta2 * precision + recall,
+    )
+}
+
+// Reduce a per-class score according to a macro or weighted average.
+fn average_per_class<F, S>(cm: &ConfusionMatrix<F>, weighted: bool, score: S) -> F
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: Fn(&ClassifierTarget) -> F,
+{
+    let mut total = F::zero();
+    let mut norm = F::zero();
+    for label in cm.get_classes().iter() {
+        let weight = if weighted {
+            cm.support(label)
+        } else {
+            F::one()
+        };
+        total += weight * score(label);
+        norm += weight;
+    }
+    safe_div(total, norm)
+}
+
+fn precision_score<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    cm: &ConfusionMatrix<F>,
+    average: &Average,
+) -> F {
+    match average {
+        Average::Binary(pos_val) => precision_of(cm, pos_val),
+        Average::Micro => {
+            let tp = cm.total_true_positives();
+            safe_div(tp, tp + cm.total_false_positives())
+        }
+        Average::Macro => average_per_class(cm, false, |l| precision_of(cm, l)),
+        Average::Weighted => average_per_class(cm, true, |l| precision_of(cm, l)),
+    }
+}
+
+fn recall_score<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    cm: &ConfusionMatrix<F>,
+    average: &Average,
+) -> F {
+    match average {
+        Average::Binary(pos_val) => recall_of(cm, pos_val),
+        Average::Micro => {
+            let tp = cm.total_true_positives();
+            safe_div(tp, tp + cm.total_false_negatives())
+        }
+        Average::Macro => average_per_class(cm, false, |l| recall_of(cm, l)),
+        Average::Weighted => average_per_class(cm, true, |l| recall_of(cm, l)),
+    }
+}
+
+fn fbeta_score<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    cm: &ConfusionMatrix<F>,
+    beta: F,
+    average: &Average,
+) -> F {
+    let fbeta = |l: &ClassifierTarget| fbeta_of(precision_of(cm, l), recall_of(cm, l), beta);
+    match average {
+        Average::Binary(pos_val) => fbeta(pos_val),
+        Average::Micro => fbeta_of(
+            precision_score(cm, average),
+            recall_score(cm, average),
+            beta,
+        ),
+        Average::Macro => average_per_class(cm, false, fbeta),
+        Average::Weighted => average_per_class(cm, true, fbeta),
+    }
+}
+
+/// Precision score, i.e. the ratio of true positives over all the positive predictions.
+///
+/// # Parameters
+///
+/// - `average`: How the per-class precisions are combined. See [`Average`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::report::{Average, Precision};
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![true, false, true, true, true];
+/// let y_pred = vec![true, true, false, true, true];
+///
+/// let mut metric: Precision<f64> = Precision::new(Average::Binary(ClassifierTarget::from(true)));
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert_eq!(metric.get(), 0.75);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Precision<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+    average: Average,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Precision<F> {
+    pub fn new(average: Average) -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+            average,
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for Precision<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        precision_score(&self.cm, &self.average)
+    }
+    fn is_multiclass(&self) -> bool {
+        !matches!(self.average, Average::Binary(_))
+    }
+}
+
+/// Recall score, i.e. the ratio of true positives over all the positive samples.
+///
+/// # Parameters
+///
+/// - `average`: How the per-class recalls are combined. See [`Average`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::report::{Average, Recall};
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![true, false, true, true, true];
+/// let y_pred = vec![true, true, false, true, true];
+///
+/// let mut metric: Recall<f64> = Recall::new(Average::Binary(ClassifierTarget::from(true)));
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// assert_eq!(metric.get(), 0.75);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recall<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+    average: Average,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Recall<F> {
+    pub fn new(average: Average) -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+            average,
+        }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for Recall<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        recall_score(&self.cm, &self.average)
+    }
+    fn is_multiclass(&self) -> bool {
+        !matches!(self.average, Average::Binary(_))
+    }
+}
+
+/// F-Beta score, i.e. the weighted harmonic mean of precision and recall.
+///
+/// # Parameters
+///
+/// - `beta`: Weight of recall with respect to precision. A `beta` of 1 yields the F1 score.
+/// - `average`: How the per-class scores are combined. See [`Average`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::report::{Average, FBeta};
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec!["cat", "dog", "cat", "bird", "dog"];
+/// let y_pred = vec!["cat", "cat", "cat", "bird", "dog"];
+///
+/// let mut metric: FBeta<f64> = FBeta::f1(Average::Macro);
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// println!("Macro F1: {:.3}", metric.get());
+/// ```
+///
+/// # Notes
+///
+/// With `Average::Micro`, the score is computed from the micro-averaged precision and recall.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FBeta<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    cm: ConfusionMatrix<F>,
+    beta: F,
+    average: Average,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> FBeta<F> {
+    pub fn new(beta: F, average: Average) -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+            beta,
+            average,
+        }
+    }
+    /// Shorthand for an F-Beta score with `beta = 1`.
+    pub fn f1(average: Average) -> Self {
+        Self::new(F::one(), average)
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for FBeta<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        fbeta_score(&self.cm, self.beta, &self.average)
+    }
+    fn is_multiclass(&self) -> bool {
+        !matches!(self.average, Average::Binary(_))
+    }
+}
+
+/// Scores of a single row of a [`ClassificationReport`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportRow<F: Float> {
+    pub precision: F,
+    pub recall: F,
+    pub f1: F,
+    pub support: F,
+}
+
+impl<F: Float> ReportRow<F> {
+    fn to_map(self) -> HashMap<String, F> {
+        HashMap::from([
+            ("precision".to_string(), self.precision),
+            ("recall".to_string(), self.recall),
+            ("f1".to_string(), self.f1),
+            ("support".to_string(), self.support),
+        ])
+    }
+}
+
+/// A report of the precision, recall and F1 score of each class, along with their macro, micro and
+/// weighted averages.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::report::ClassificationReport;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec!["cat", "ant", "cat", "cat", "ant", "bird"];
+/// let y_pred = vec!["ant", "ant", "cat", "cat", "ant", "cat"];
+///
+/// let mut report: ClassificationReport<f64> = ClassificationReport::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     report.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// println!("{}", report);
+///
+/// let map = report.to_map();
+/// assert_eq!(map["cat"]["recall"], 2.0 / 3.0);
+/// assert_eq!(map["macro avg"]["support"], 6.0);
+/// ```
+///
+/// # Notes
+///
+/// As a `ClassificationMetric`, the report's value is its macro-averaged F1 score.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassificationReport<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    cm: ConfusionMatrix<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationReport<F>
+{
+    pub fn new() -> Self {
+        Self {
+            cm: ConfusionMatrix::new(),
+        }
+    }
+    pub fn from_confusion_matrix(cm: ConfusionMatrix<F>) -> Self {
+        Self { cm }
+    }
+    pub fn confusion_matrix(&self) -> &ConfusionMatrix<F> {
+        &self.cm
+    }
+    /// The classes of the report, sorted.
+    pub fn classes(&self) -> Vec<ClassifierTarget> {
+        let mut classes: Vec<_> = self.cm.get_classes().into_iter().collect();
+        classes.sort();
+        classes
+    }
+    pub fn class_row(&self, label: &ClassifierTarget) -> ReportRow<F> {
+        let precision = precision_of(&self.cm, label);
+        let recall = recall_of(&self.cm, label);
+        ReportRow {
+            precision,
+            recall,
+            f1: fbeta_of(precision, recall, F::one()),
+            support: self.cm.support(label),
+        }
+    }
+    pub fn average_row(&self, average: Average) -> ReportRow<F> {
+        let support = match &average {
+            Average::Binary(pos_val) => self.cm.support(pos_val),
+            _ => self.cm.total_weight,
+        };
+        ReportRow {
+            precision: precision_score(&self.cm, &average),
+            recall: recall_score(&self.cm, &average),
+            f1: fbeta_score(&self.cm, F::one(), &average),
+            support,
+        }
+    }
+    /// The report as a map, with one entry per class plus the `"macro avg"`, `"micro avg"` and
+    /// `"weighted avg"` entries. Each entry maps `"precision"`, `"recall"`, `"f1"` and `"support"`
+    /// to their value.
+    pub fn to_map(&self) -> HashMap<String, HashMap<String, F>> {
+        let mut map: HashMap<String, HashMap<String, F>> = self
+            .classes()
+            .iter()
+            .map(|label| (label.to_string(), self.class_row(label).to_map()))
+            .collect();
+        for (name, average) in Self::averages() {
+            map.insert(name.to_string(), self.average_row(average).to_map());
+        }
+        map
+    }
+    fn averages() -> [(&'static str, Average); 3] {
+        [
+            ("macro avg", Average::Macro),
+            ("micro avg", Average::Micro),
+            ("weighted avg", Average::Weighted),
+        ]
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + fmt::Display>
+    ClassificationReport<F>
+{
+    /// The report as a JSON object, laid out like [`ClassificationReport::to_map`].
+    pub fn to_json(&self) -> String {
+        fn escape(s: &str) -> String {
+            let mut escaped = String::with_capacity(s.len());
+            for c in s.chars() {
+                match c {
+                    '"' => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => escaped.push(c),
+                }
+            }
+            escaped
+        }
+        let number = |x: F| {
+            if x.is_finite() {
+                format!("{}", x)
+            } else {
+                "null".to_string()
+            }
+        };
+        let rows = self
+            .classes()
+            .iter()
+            .map(|label| (label.to_string(), self.class_row(label)))
+            .chain(
+                Self::averages()
+                    .into_iter()
+                    .map(|(name, average)| (name.to_string(), self.average_row(average))),
+            )
+            .map(|(name, row)| {
+                format!(
+                    "\"{}\":{{\"precision\":{},\"recall\":{},\"f1\":{},\"support\":{}}}",
+                    escape(&name),
+                    number(row.precision),
+                    number(row.recall),
+                    number(row.f1),
+                    number(row.support)
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("{{{}}}", rows.join(","))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for ClassificationReport<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + fmt::Display>
+    fmt::Display for ClassificationReport<F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let classes = self.classes();
+        let width = classes
+            .iter()
+            .map(|label| label.to_string().len())
+            .chain(Self::averages().iter().map(|(name, _)| name.len()))
+            .max()
+            .unwrap_or(0);
+
+        let write_row = |f: &mut fmt::Formatter<'_>, name: &str, row: ReportRow<F>| {
+            writeln!(
+                f,
+                "{:>width$}  {:>9.3}  {:>9.3}  {:>9.3}  {:>9.1}",
+                name,
+                row.precision,
+                row.recall,
+                row.f1,
+                row.support,
+                width = width
+            )
+        };
+
+        writeln!(
+            f,
+            "{:>width$}  {:>9}  {:>9}  {:>9}  {:>9}",
+            "",
+            "Precision",
+            "Recall",
+            "F1",
+            "Support",
+            width = width
+        )?;
+        writeln!(f)?;
+        for label in classes.iter() {
+            write_row(f, &label.to_string(), self.class_row(label))?;
+        }
+        writeln!(f)?;
+        for (name, average) in Self::averages() {
+            write_row(f, name, self.average_row(average))?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    ClassificationMetric<F> for ClassificationReport<F>
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.update(y_pred, y_true, sample_weight);
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.cm.revert(y_pred, y_true, sample_weight);
+    }
+    fn get(&self) -> F {
+        self.average_row(Average::Macro).f1
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for Precision<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for Recall<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for FBeta<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for ClassificationReport<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cm.decay(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    fn feed<M: ClassificationMetric<f64>>(metric: &mut M, y_true: &[&str], y_pred: &[&str]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    // Values obtained with scikit-learn
+    const Y_TRUE: [&str; 6] = ["cat", "ant", "cat", "cat", "ant", "bird"];
+    const Y_PRED: [&str; 6] = ["ant", "ant", "cat", "cat", "ant", "cat"];
+
+    #[test]
+    fn test_precision() {
+        let mut macro_precision = Precision::new(Average::Macro);
+        let mut micro_precision = Precision::new(Average::Micro);
+        let mut weighted_precision = Precision::new(Average::Weighted);
+        let mut binary_precision = Precision::new(Average::Binary(ClassifierTarget::from("cat")));
+        feed(&mut macro_precision, &Y_TRUE, &Y_PRED);
+        feed(&mut micro_precision, &Y_TRUE, &Y_PRED);
+        feed(&mut weighted_precision, &Y_TRUE, &Y_PRED);
+        feed(&mut binary_precision, &Y_TRUE, &Y_PRED);
+        assert_close(macro_precision.get(), 0.4444444444444444);
+        assert_close(micro_precision.get(), 0.6666666666666666);
+        assert_close(weighted_precision.get(), 0.5555555555555555);
+        assert_close(binary_precision.get(), 0.6666666666666666);
+    }
+
+    #[test]
+    fn test_recall() {
+        let mut macro_recall = Recall::new(Average::Macro);
+        let mut micro_recall = Recall::new(Average::Micro);
+        let mut weighted_recall = Recall::new(Average::Weighted);
+        feed(&mut macro_recall, &Y_TRUE, &Y_PRED);
+        feed(&mut micro_recall, &Y_TRUE, &Y_PRED);
+        feed(&mut weighted_recall, &Y_TRUE, &Y_PRED);
+        assert_close(macro_recall.get(), 0.5555555555555555);
+        assert_close(micro_recall.get(), 0.6666666666666666);
+        assert_close(weighted_recall.get(), 0.6666666666666666);
+    }
+
+    #[test]
+    fn test_fbeta() {
+        let mut macro_f1 = FBeta::f1(Average::Macro);
+        let mut weighted_f1 = FBeta::f1(Average::Weighted);
+        let mut micro_f2 = FBeta::new(2.0, Average::Micro);
+        feed(&mut macro_f1, &Y_TRUE, &Y_PRED);
+        feed(&mut weighted_f1, &Y_TRUE, &Y_PRED);
+        feed(&mut micro_f2, &Y_TRUE, &Y_PRED);
+        assert_close(macro_f1.get(), 0.4888888888888889);
+        assert_close(weighted_f1.get(), 0.6);
+        assert_close(micro_f2.get(), 0.6666666666666666);
+    }
+
+    #[test]
+    fn test_revert() {
+        let mut metric = FBeta::f1(Average::Macro);
+        feed(&mut metric, &Y_TRUE, &Y_PRED);
+        let expected = metric.get();
+        metric.update(
+            &ClassifierOutput::Prediction(ClassifierTarget::from("dog")),
+            &ClassifierTarget::from("bird"),
+            Some(2.0),
+        );
+        assert_ne!(metric.get(), expected);
+        metric.revert(
+            &ClassifierOutput::Prediction(ClassifierTarget::from("dog")),
+            &ClassifierTarget::from("bird"),
+            Some(2.0),
+        );
+        assert_close(metric.get(), expected);
+    }
+
+    #[test]
+    fn test_classification_report() {
+        let mut report = ClassificationReport::new();
+        feed(&mut report, &Y_TRUE, &Y_PRED);
+
+        let map = report.to_map();
+        assert_eq!(map.len(), 6);
+        assert_close(map["ant"]["precision"], 0.6666666666666666);
+        assert_close(map["ant"]["recall"], 1.0);
+        assert_close(map["ant"]["f1"], 0.8);
+        assert_close(map["ant"]["support"], 2.0);
+        assert_close(map["bird"]["f1"], 0.0);
+        assert_close(map["macro avg"]["precision"], 0.4444444444444444);
+        assert_close(map["macro avg"]["f1"], 0.4888888888888889);
+        assert_close(map["micro avg"]["recall"], 0.6666666666666666);
+        assert_close(map["weighted avg"]["f1"], 0.6);
+        assert_close(report.get(), 0.4888888888888889);
+
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 9);
+        assert!(table
+            .lines()
+            .any(|line| line.trim_start().starts_with("weighted avg")));
+    }
+
+    #[test]
+    fn test_classification_report_json() {
+        let mut report = ClassificationReport::new();
+        feed(&mut report, &["a\"b", "c"], &["a\"b", "a\"b"]);
+        assert_eq!(
+            report.to_json(),
+            concat!(
+                r#"{"a\"b":{"precision":0.5,"recall":1,"f1":0.6666666666666666,"support":1},"#,
+                r#""c":{"precision":0,"recall":0,"f1":0,"support":1},"#,
+                r#""macro avg":{"precision":0.25,"recall":0.5,"f1":0.3333333333333333,"support":2},"#,
+                r#""micro avg":{"precision":0.5,"recall":0.5,"f1":0.5,"support":2},"#,
+                r#""weighted avg":{"precision":0.25,"recall":0.5,"f1":0.3333333333333333,"support":2}}"#
+            )
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_checkpoint() {
+        let mut metric = FBeta::f1(Average::Weighted);
+        feed(&mut metric, &Y_TRUE[..3], &Y_PRED[..3]);
+        let checkpoint = serde_json::to_string(&metric).unwrap();
+        let mut restored: FBeta<f64> = serde_json::from_str(&checkpoint).unwrap();
+        feed(&mut metric, &Y_TRUE[3..], &Y_PRED[3..]);
+        feed(&mut restored, &Y_TRUE[3..], &Y_PRED[3..]);
+        assert_close(restored.get(), metric.get());
+        assert_close(restored.get(), 0.6);
+    }
+}
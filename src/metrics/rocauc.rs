@@ -2,7 +2,8 @@ use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 
 use crate::common::{ClassifierOutput, ClassifierTarget};
 use crate::metrics::confusion::ConfusionMatrix;
-use crate::metrics::traits::ClassificationMetric;
+use crate::metrics::decay::Decay;
+use crate::metrics::traits::{AnomalyMetric, ClassificationMetric};
 use num::{Float, FromPrimitive};
 
 /// Receiver Operating Characteristic Area Under the Curve (ROC AUC).
@@ -15,13 +16,15 @@ use num::{Float, FromPrimitive};
 /// # Parameters
 ///
 /// - `n_threshold`: The number of thresholds used for discretizing the ROC curve. A higher value will lead to
-///   more accurate results, but will also require more computation time and memory.
+///   more accurate results, but will also require more computation time and memory. Defaults to 10 and
+///   must be at least 2.
 /// - `pos_val`: Value to treat as "positive".
 ///
 /// # Examples
 ///
 /// ```rust
-/// use light_river::metrics::ROCAUC;
+/// use light_river::metrics::rocauc::ROCAUC;
+/// use light_river::metrics::traits::ClassificationMetric;
 /// use light_river::common::{ClassifierTarget, ClassifierOutput};
 /// use std::collections::HashMap;
 ///
@@ -48,7 +51,7 @@ use num::{Float, FromPrimitive};
 ///     metric.update(yp, &ClassifierTarget::from(*yt), Some(1.0));
 /// }
 ///
-/// println!("ROCAUC: {:.2}%", metric.get() * 100.0);
+/// assert_eq!(format!("{:.2}%", metric.get() * 100.0), "87.50%");
 /// ```
 ///
 /// # Notes
@@ -56,8 +59,10 @@ use num::{Float, FromPrimitive};
 /// The true ROC AUC might differ from the approximation. The accuracy can be improved by increasing the number
 /// of thresholds, but this comes at the cost of more computation time and memory usage.
 ///
-struct ROCAUC<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
-    n_threshold: Option<usize>,
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ROCAUC<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    n_threshold: usize,
     pos_val: ClassifierTarget,
     thresholds: Vec<F>,
     cms: Vec<ConfusionMatrix<F>>,
@@ -65,6 +70,7 @@ struct ROCAUC<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + Div
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> ROCAUC<F> {
     pub fn new(n_threshold: Option<usize>, pos_val: ClassifierTarget) -> Self {
         let n_threshold = n_threshold.unwrap_or(10);
+        assert!(n_threshold >= 2, "ROCAUC needs at least 2 thresholds");
 
         let mut thresholds = Vec::with_capacity(n_threshold);
 
@@ -82,12 +88,68 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> R
         }
 
         Self {
-            n_threshold: Some(n_threshold),
-            pos_val: pos_val,
-            thresholds: thresholds,
-            cms: cms,
+            n_threshold,
+            pos_val,
+            thresholds,
+            cms,
         }
     }
+
+    // Binarize a sample: the probability assigned to the positive class and whether the
+    // sample actually belongs to it.
+    fn binarize(&self, y_pred: &ClassifierOutput<F>, y_true: &ClassifierTarget) -> (F, bool) {
+        let p_pred_pos = y_pred
+            .get_probabilities()
+            .get(&self.pos_val)
+            .copied()
+            .unwrap_or(F::zero());
+        (p_pred_pos, y_true.eq(&self.pos_val))
+    }
+
+    fn update_cms(&mut self, p_pred_pos: F, y_true: bool, sample_weight: Option<F>, revert: bool) {
+        let y_true = ClassifierTarget::from(y_true);
+        for (threshold, cm) in self.thresholds.iter().zip(self.cms.iter_mut()) {
+            let y_pred =
+                ClassifierOutput::Prediction(ClassifierTarget::from(p_pred_pos.gt(threshold)));
+            if revert {
+                cm.revert(&y_pred, &y_true, sample_weight);
+            } else {
+                cm.update(&y_pred, &y_true, sample_weight);
+            }
+        }
+    }
+
+    fn auc(&self) -> F {
+        // The confusion matrices are binarized, so the positive class is always `true`
+        let pos = ClassifierTarget::from(true);
+        let mut tprs: Vec<F> = vec![F::zero(); self.n_threshold];
+        let mut fprs: Vec<F> = vec![F::zero(); self.n_threshold];
+
+        for (i, cm) in self.cms.iter().enumerate() {
+            let true_positives: F = cm.true_positives(&pos);
+            let true_negatives: F = cm.true_negatives(&pos);
+            let false_positives: F = cm.false_positives(&pos);
+            let false_negatives: F = cm.false_negatives(&pos);
+
+            // Handle the case of zero division
+            if true_positives + false_negatives != F::zero() {
+                tprs[i] = true_positives / (true_positives + false_negatives);
+            }
+            if false_positives + true_negatives != F::zero() {
+                fprs[i] = false_positives / (false_positives + true_negatives);
+            }
+        }
+
+        // Trapezoidal integration. The thresholds are increasing, hence the rates are
+        // decreasing and the sign has to be flipped.
+        let two = F::from(2.0).unwrap();
+        -fprs
+            .windows(2)
+            .zip(tprs.windows(2))
+            .fold(F::zero(), |auc, (fpr, tpr)| {
+                auc + (fpr[1] - fpr[0]) * (tpr[1] + tpr[0]) / two
+            })
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
@@ -99,19 +161,8 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
         y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     ) {
-        // Get the probability of the positive class
-        let p_pred = y_pred.get_probabilities();
-        let default_proba = F::zero();
-        let p_pred_pos = p_pred.get(&self.pos_val).unwrap_or(&default_proba);
-
-        // Convert the target to a binary target
-        let y_true = ClassifierTarget::from(y_true.eq(&self.pos_val));
-
-        for (threshold, cm) in self.thresholds.iter().zip(self.cms.iter_mut()) {
-            let y_pred =
-                ClassifierOutput::Prediction(ClassifierTarget::from(p_pred_pos.ge(threshold)));
-            cm.update(&y_pred, &y_true, sample_weight);
-        }
+        let (p_pred_pos, y_true) = self.binarize(y_pred, y_true);
+        self.update_cms(p_pred_pos, y_true, sample_weight, false);
     }
 
     fn revert(
@@ -120,51 +171,11 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
         y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     ) {
-        let p_pred = y_pred.get_probabilities();
-
-        let default_proba = F::zero();
-        let p_pred_pos = p_pred.get(&self.pos_val).unwrap_or(&default_proba);
-        let y_true = ClassifierTarget::from(y_true.eq(&self.pos_val));
-
-        for (threshold, cm) in self.thresholds.iter().zip(self.cms.iter_mut()) {
-            let y_pred =
-                ClassifierOutput::Prediction(ClassifierTarget::from(p_pred_pos.ge(threshold)));
-            cm.revert(&y_pred, &y_true, sample_weight);
-        }
+        let (p_pred_pos, y_true) = self.binarize(y_pred, y_true);
+        self.update_cms(p_pred_pos, y_true, sample_weight, true);
     }
     fn get(&self) -> F {
-        let mut tprs: Vec<F> = (0..self.n_threshold.unwrap()).map(|_| F::zero()).collect();
-        let mut fprs: Vec<F> = (0..self.n_threshold.unwrap()).map(|_| F::zero()).collect();
-
-        for (i, cm) in self.cms.iter().enumerate() {
-            let true_positives: F = cm.true_positives(&self.pos_val);
-            let true_negatives: F = cm.true_negatives(&self.pos_val);
-            let false_positives: F = cm.false_positives(&self.pos_val);
-            let false_negatives: F = cm.false_negatives(&self.pos_val);
-
-            // Handle the case of zero division
-            let mut tpr: Option<F> = None;
-            if true_positives + false_negatives != F::zero() {
-                tpr = Some(true_positives.div(true_positives + false_negatives));
-            }
-
-            tprs[i] = tpr.unwrap_or(F::zero());
-
-            // Handle the case of zero division
-            let mut fpr: Option<F> = None;
-            if false_positives + true_negatives != F::zero() {
-                fpr = Some(false_positives.div(false_positives + true_negatives));
-            }
-
-            fprs[i] = fpr.unwrap_or(F::zero());
-        }
-        // Trapezoidal integration
-        let mut auc = F::zero();
-        for i in 0..self.n_threshold.unwrap() - 1 {
-            auc += (fprs[i + 1] - fprs[i]) * (tprs[i + 1] + tprs[i]) / F::from(2.0).unwrap();
-        } // TODO: Turn it functional
-
-        -auc
+        self.auc()
     }
 
     fn is_multiclass(&self) -> bool {
@@ -172,13 +183,48 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
     }
 }
 
+/// The anomaly scores are used as the probability of the positive class, hence they are expected
+/// to lie in `[0, 1]`.
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AnomalyMetric<F>
+    for ROCAUC<F>
+{
+    fn update(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.update_cms(score, is_anomaly, sample_weight, false);
+    }
+    fn revert(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>) {
+        self.update_cms(score, is_anomaly, sample_weight, true);
+    }
+    fn get(&self) -> F {
+        self.auc()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Decay<F>
+    for ROCAUC<F>
+{
+    fn decay(&mut self, factor: F) {
+        self.cms.iter_mut().for_each(|cm| cm.decay(factor));
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::ROCAUC;
+    use crate::common::{ClassifierOutput, ClassifierTarget};
+    use crate::metrics::traits::ClassificationMetric;
+    use crate::testing::assert_close;
+    use std::collections::HashMap;
+
+    fn proba(p: f64) -> ClassifierOutput<f64> {
+        ClassifierOutput::Probabilities(HashMap::from([
+            (ClassifierTarget::from("cat"), p),
+            (ClassifierTarget::from("dog"), 1.0 - p),
+        ]))
+    }
+
     #[test]
     fn test_rocauc() {
-        // same example as in the doctest
-        let y_pred = vec![
+        let y_pred = [
             ClassifierOutput::Prediction(ClassifierTarget::from("cat")),
             ClassifierOutput::Prediction(ClassifierTarget::from("dog")),
             ClassifierOutput::Prediction(ClassifierTarget::from("bird")),
@@ -191,5 +237,78 @@ mod tests {
         for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
             metric.update(yp, &ClassifierTarget::from(*yt), Some(1.0));
         }
+        // 2 of the 3 cats are ranked above the only dog
+        assert_close(metric.get(), 0.8333333333333334);
+    }
+
+    #[test]
+    fn test_rocauc_string_pos_val() {
+        // Same as the doctest, but with a non boolean positive class
+        let y_pred = [0.1, 0.4, 0.35, 0.8];
+        let y_true = ["dog", "dog", "cat", "cat"];
+
+        let mut metric = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(&proba(*yp), &ClassifierTarget::from(*yt), None);
+        }
+        assert_close(metric.get(), 0.875);
+    }
+
+    #[test]
+    fn test_rocauc_sample_weight() {
+        let mut weighted = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        let mut repeated = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        for (yt, yp, w) in [("dog", 0.1, 1.0), ("dog", 0.6, 3.0), ("cat", 0.7, 2.0)] {
+            weighted.update(&proba(yp), &ClassifierTarget::from(yt), Some(w));
+            for _ in 0..(w as usize) {
+                repeated.update(&proba(yp), &ClassifierTarget::from(yt), None);
+            }
+        }
+        assert_close(weighted.get(), repeated.get());
+    }
+
+    #[test]
+    fn test_rocauc_revert() {
+        let mut metric = ROCAUC::new(Some(20), ClassifierTarget::from("cat"));
+        for (yt, yp) in [("dog", 0.2), ("cat", 0.4), ("cat", 0.9), ("dog", 0.5)] {
+            metric.update(&proba(yp), &ClassifierTarget::from(yt), None);
+        }
+        let expected = metric.get();
+        metric.update(&proba(0.95), &ClassifierTarget::from("dog"), Some(2.0));
+        assert_ne!(metric.get(), expected);
+        metric.revert(&proba(0.95), &ClassifierTarget::from("dog"), Some(2.0));
+        assert_close(metric.get(), expected);
+    }
+
+    #[test]
+    fn test_rocauc_anomaly_scores() {
+        use crate::metrics::traits::AnomalyMetric;
+
+        let scores = [0.1, 0.4, 0.35, 0.8];
+        let is_anomaly = [false, false, true, true];
+
+        let mut from_scores: ROCAUC<f64> = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        let mut from_probas = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        for (score, anomaly) in scores.iter().zip(is_anomaly.iter()) {
+            AnomalyMetric::update(&mut from_scores, *score, *anomaly, None);
+            let y_true = ClassifierTarget::from(if *anomaly { "cat" } else { "dog" });
+            ClassificationMetric::update(&mut from_probas, &proba(*score), &y_true, None);
+        }
+        assert_close(
+            AnomalyMetric::get(&from_scores),
+            ClassificationMetric::get(&from_probas),
+        );
+    }
+
+    #[test]
+    fn test_rocauc_is_better_than() {
+        let mut good = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        let mut bad = ROCAUC::new(None, ClassifierTarget::from("cat"));
+        for (yt, yp) in [("dog", 0.2), ("cat", 0.9)] {
+            good.update(&proba(yp), &ClassifierTarget::from(yt), None);
+            bad.update(&proba(1.0 - yp), &ClassifierTarget::from(yt), None);
+        }
+        assert!(good.is_better_than(&bad));
+        assert!(!bad.is_better_than(&good));
     }
 }
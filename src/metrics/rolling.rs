@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::traits::ClassificationMetric;
+use num::{Float, FromPrimitive};
+
+/// Rolling wrapper for classification metrics.
+///
+/// The wrapped metric is computed over a sliding window of the last `window_size` samples. When
+/// the window is full, the oldest sample is removed from the metric with its `revert` method,
+/// which makes it possible to monitor the performance of a model on a drifting stream.
+///
+/// # Parameters
+///
+/// - `metric`: The metric to compute over the window. It should be freshly initialized.
+/// - `window_size`: The number of samples to keep track of.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::report::{Average, Recall};
+/// use light_river::metrics::rolling::Rolling;
+/// use light_river::metrics::traits::ClassificationMetric;
+///
+/// let y_true = vec![true, false, true, true, true];
+/// let y_pred = vec![true, true, false, true, true];
+///
+/// let mut metric: Rolling<f64, Recall<f64>> =
+///     Rolling::new(Recall::new(Average::Binary(ClassifierTarget::from(true))), 3);
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+///     metric.update(
+///         &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+///         &ClassifierTarget::from(*yt),
+///         None,
+///     );
+/// }
+/// // Only the last three samples are taken into account
+/// assert_eq!(metric.get(), 2.0 / 3.0);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rolling<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F>,
+{
+    metric: M,
+    window_size: usize,
+    window: VecDeque<(ClassifierOutput<F>, ClassifierTarget, Option<F>)>,
+}
+
+impl<F, M> Rolling<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F>,
+{
+    pub fn new(metric: M, window_size: usize) -> Self {
+        assert!(window_size > 0, "window_size must be strictly positive");
+        Self {
+            metric,
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+        }
+    }
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+    /// Number of samples currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+}
+
+impl<F, M> ClassificationMetric<F> for Rolling<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F>,
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        if self.window.len() == self.window_size {
+            let (old_pred, old_true, old_weight) = self.window.pop_front().unwrap();
+            self.metric.revert(&old_pred, &old_true, old_weight);
+        }
+        self.metric.update(y_pred, y_true, sample_weight);
+        self.window
+            .push_back((y_pred.clone(), y_true.clone(), sample_weight));
+    }
+    /// Reverting is only possible for the most recent sample of the window, as the samples
+    /// that were pushed out of it can't be brought back.
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        self.metric.revert(y_pred, y_true, sample_weight);
+        self.window.pop_back();
+    }
+    fn get(&self) -> F {
+        self.metric.get()
+    }
+    fn is_multiclass(&self) -> bool {
+        self.metric.is_multiclass()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::report::{Average, FBeta};
+
+    fn feed<M: ClassificationMetric<f64>>(metric: &mut M, y_true: &[&str], y_pred: &[&str]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    const Y_TRUE: [&str; 6] = ["cat", "ant", "cat", "cat", "ant", "bird"];
+    const Y_PRED: [&str; 6] = ["ant", "ant", "cat", "cat", "ant", "cat"];
+
+    #[test]
+    fn test_rolling_matches_last_window() {
+        for window_size in 1..=Y_TRUE.len() {
+            let mut rolling = Rolling::new(FBeta::f1(Average::Macro), window_size);
+            feed(&mut rolling, &Y_TRUE, &Y_PRED);
+
+            let start = Y_TRUE.len() - window_size;
+            let mut expected = FBeta::f1(Average::Macro);
+            feed(&mut expected, &Y_TRUE[start..], &Y_PRED[start..]);
+
+            assert_eq!(rolling.len(), window_size);
+            assert!((rolling.get() - expected.get()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_rolling_revert() {
+        let mut rolling = Rolling::new(FBeta::f1(Average::Micro), 4);
+        feed(&mut rolling, &Y_TRUE, &Y_PRED);
+        let expected = rolling.get();
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from("dog"));
+        let y_true = ClassifierTarget::from("cat");
+        rolling.update(&y_pred, &y_true, None);
+        rolling.revert(&y_pred, &y_true, None);
+        assert_eq!(rolling.len(), 3);
+        assert!((rolling.get() - expected).abs() > 1e-10);
+    }
+}
@@ -0,0 +1,105 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::metrics::traits::RegressionMetric;
+use num::{Float, FromPrimitive};
+
+/// Regression metric of multi-step forecasts, computed separately for each step of the horizon.
+///
+/// Forecasts usually get worse the further ahead they go, so the metric is kept per step: the
+/// first value is that of the forecasts one step ahead, the second that of the forecasts two
+/// steps ahead, and so on. This makes it possible to compare forecasters step by step, or on
+/// average over the horizon.
+///
+/// # Parameters
+///
+/// - `metric`: The regression metric, which is cloned for each step.
+/// - `horizon`: The number of steps of the forecasts.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::time_series::HorizonMetric;
+///
+/// let mut metric = HorizonMetric::new(MAE::new(), 3);
+/// metric.update(&[1.0, 2.0, 3.0], &[1.0, 2.5, 4.0]);
+/// metric.update(&[2.0, 3.0, 4.0], &[2.0, 3.5, 2.0]);
+/// assert_eq!(metric.get(), [0.0, 0.5, 1.5]);
+/// assert_eq!(metric.mean(), 2.0 / 3.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HorizonMetric<F, M> {
+    metrics: Vec<M>,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> HorizonMetric<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: RegressionMetric<F> + Clone,
+{
+    pub fn new(metric: M, horizon: usize) -> Self {
+        assert!(horizon > 0, "horizon must be strictly positive");
+        Self {
+            metrics: vec![metric; horizon],
+            _float: PhantomData,
+        }
+    }
+    pub fn horizon(&self) -> usize {
+        self.metrics.len()
+    }
+    /// Update the metric of each step with the true value and the forecast of that step.
+    ///
+    /// # Panics
+    ///
+    /// If there aren't as many true values and forecasts as steps.
+    pub fn update(&mut self, y_true: &[F], y_pred: &[F]) {
+        assert!(
+            y_true.len() == self.horizon() && y_pred.len() == self.horizon(),
+            "there must be a true value and a forecast for each step"
+        );
+        for ((metric, yt), yp) in self.metrics.iter_mut().zip(y_true).zip(y_pred) {
+            metric.update(*yt, *yp, None);
+        }
+    }
+    /// The metric of a step, starting from 0 for the forecasts one step ahead.
+    pub fn step(&self, step: usize) -> &M {
+        &self.metrics[step]
+    }
+    /// The value of the metric for each step.
+    pub fn get(&self) -> Vec<F> {
+        self.metrics.iter().map(|metric| metric.get()).collect()
+    }
+    /// The mean of the values of the metric over the steps.
+    pub fn mean(&self) -> F {
+        let sum = self
+            .metrics
+            .iter()
+            .fold(F::zero(), |sum, metric| sum + metric.get());
+        sum / F::from_usize(self.horizon()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::regression::MAPE;
+
+    #[test]
+    fn test_steps() {
+        let mut metric = HorizonMetric::new(MAPE::new(), 2);
+        metric.update(&[10.0, 20.0], &[11.0, 15.0]);
+        metric.update(&[10.0, 0.0], &[10.0, 5.0]);
+        assert_eq!(metric.get(), [5.0, 25.0]);
+        assert_eq!(metric.step(1).get(), 25.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "for each step")]
+    fn test_wrong_horizon() {
+        let mut metric = HorizonMetric::new(MAPE::new(), 2);
+        metric.update(&[1.0], &[1.0]);
+    }
+}
@@ -1,33 +1,136 @@
+use std::collections::HashMap;
 use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 
-use crate::common::{ClassifierOutput, ClassifierTarget, RegressionTarget};
+use crate::common::{ClassifierOutput, ClassifierTarget, MultiLabelOutput, RegressionTarget};
 use num::{Float, FromPrimitive};
 
+/// Trait for metrics that evaluate the output of a classifier.
+///
+/// Metrics can be compared with each other using `is_better_than`, which makes it possible to
+/// write evaluation and model selection code that is agnostic of the metric in use.
 pub trait ClassificationMetric<
     F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
 >
 {
     fn update(
         &mut self,
-        y_true: &ClassifierTarget,
         y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     );
     fn revert(
         &mut self,
-        y_true: &ClassifierTarget,
         y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
         sample_weight: Option<F>,
     );
     fn get(&self) -> F;
     fn is_multiclass(&self) -> bool;
+    /// Whether a higher value of the metric means a better model.
+    fn bigger_is_better(&self) -> bool {
+        true
+    }
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    fn is_better_than(&self, other: &dyn ClassificationMetric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
 }
 
+/// Trait for metrics that evaluate the output of a regressor.
 pub trait RegressionMetric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
 {
-    fn update(&mut self, y_true: RegressionTarget<F>, y_pred: RegressionTarget<F>);
-    fn revert(&mut self, y_true: RegressionTarget<F>, y_pred: RegressionTarget<F>);
+    fn update(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    );
+    fn revert(
+        &mut self,
+        y_true: RegressionTarget<F>,
+        y_pred: RegressionTarget<F>,
+        sample_weight: Option<F>,
+    );
+    fn get(&self) -> F;
+    /// Whether a higher value of the metric means a better model.
+    fn bigger_is_better(&self) -> bool;
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    fn is_better_than(&self, other: &dyn RegressionMetric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
+}
+
+/// Trait for metrics that evaluate the scores of an anomaly detector.
+///
+/// The scores are expected to be higher for anomalies, whereas `is_anomaly` tells whether the
+/// sample is actually an anomaly.
+pub trait AnomalyMetric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn update(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>);
+    fn revert(&mut self, score: F, is_anomaly: bool, sample_weight: Option<F>);
+    fn get(&self) -> F;
+    /// Whether a higher value of the metric means a better model.
+    fn bigger_is_better(&self) -> bool {
+        true
+    }
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    fn is_better_than(&self, other: &dyn AnomalyMetric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
+}
+
+/// Trait for metrics that evaluate the output of a multi-label classifier.
+pub trait MultiLabelMetric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    fn update(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    );
+    fn revert(
+        &mut self,
+        y_pred: &MultiLabelOutput,
+        y_true: &MultiLabelOutput,
+        sample_weight: Option<F>,
+    );
     fn get(&self) -> F;
+    /// Whether a higher value of the metric means a better model.
+    fn bigger_is_better(&self) -> bool {
+        true
+    }
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    fn is_better_than(&self, other: &dyn MultiLabelMetric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
+}
+
+/// Trait for metrics that evaluate a ranked list of items, such as the output of a recommender.
+///
+/// `ranking` holds the items from the most to the least relevant according to the model, whereas
+/// `relevance` holds the true relevance of the items. Items that are missing from `relevance`
+/// are considered irrelevant.
+pub trait RankingMetric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    fn update(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    );
+    fn revert(
+        &mut self,
+        ranking: &[ClassifierTarget],
+        relevance: &HashMap<ClassifierTarget, F>,
+        sample_weight: Option<F>,
+    );
+    fn get(&self) -> F;
+    /// Whether a higher value of the metric means a better model.
+    fn bigger_is_better(&self) -> bool {
+        true
+    }
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    fn is_better_than(&self, other: &dyn RankingMetric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
 }
 
 pub trait ClustringMetric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
@@ -37,8 +140,49 @@ pub trait ClustringMetric<F: Float + FromPrimitive + AddAssign + SubAssign + Mul
     fn get(&self) -> F;
 }
 
+#[inline]
+fn is_better<F: Float>(bigger_is_better: bool, value: F, other: F) -> bool {
+    if bigger_is_better {
+        value > other
+    } else {
+        value < other
+    }
+}
+
 pub enum Metric<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
     Classification(Box<dyn ClassificationMetric<F>>),
     Regression(Box<dyn RegressionMetric<F>>),
+    Anomaly(Box<dyn AnomalyMetric<F>>),
+    MultiLabel(Box<dyn MultiLabelMetric<F>>),
+    Ranking(Box<dyn RankingMetric<F>>),
     Clustring(Box<dyn ClustringMetric<F>>),
 }
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Metric<F> {
+    pub fn get(&self) -> F {
+        match self {
+            Metric::Classification(metric) => metric.get(),
+            Metric::Regression(metric) => metric.get(),
+            Metric::Anomaly(metric) => metric.get(),
+            Metric::MultiLabel(metric) => metric.get(),
+            Metric::Ranking(metric) => metric.get(),
+            Metric::Clustring(metric) => metric.get(),
+        }
+    }
+    /// Whether a higher value of the metric means a better model. Clustering metrics are assumed
+    /// to be similarity measures.
+    pub fn bigger_is_better(&self) -> bool {
+        match self {
+            Metric::Classification(metric) => metric.bigger_is_better(),
+            Metric::Regression(metric) => metric.bigger_is_better(),
+            Metric::Anomaly(metric) => metric.bigger_is_better(),
+            Metric::MultiLabel(metric) => metric.bigger_is_better(),
+            Metric::Ranking(metric) => metric.bigger_is_better(),
+            Metric::Clustring(_) => true,
+        }
+    }
+    /// Whether the current value of this metric is strictly better than the one of `other`.
+    pub fn is_better_than(&self, other: &Metric<F>) -> bool {
+        is_better(self.bigger_is_better(), self.get(), other.get())
+    }
+}
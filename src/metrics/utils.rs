@@ -0,0 +1,80 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+use num::{Float, FromPrimitive};
+
+// Divide two numbers, returning zero when the denominator is zero.
+#[inline]
+pub(crate) fn safe_div<F: Float>(num: F, den: F) -> F {
+    if den == F::zero() {
+        F::zero()
+    } else {
+        num / den
+    }
+}
+
+// Weighted running mean which supports reverting samples.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Mean<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    n: F,
+    mean: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Mean<F> {
+    pub(crate) fn new() -> Self {
+        Self {
+            n: F::zero(),
+            mean: F::zero(),
+        }
+    }
+    pub(crate) fn update(&mut self, x: F, w: F) {
+        self.n += w;
+        if self.n == F::zero() {
+            self.mean = F::zero();
+        } else {
+            self.mean += w * (x - self.mean) / self.n;
+        }
+    }
+    pub(crate) fn revert(&mut self, x: F, w: F) {
+        self.update(x, -w);
+    }
+    pub(crate) fn get(&self) -> F {
+        self.mean
+    }
+    // Scaling the weights of the past samples leaves the mean untouched
+    pub(crate) fn decay(&mut self, factor: F) {
+        self.n *= factor;
+    }
+}
+
+// Probability given to the true class. If the output holds a single class which is not the true
+// one, the problem is assumed to be binary and the complement is returned.
+pub(crate) fn true_class_probability<F: Float>(
+    probabilities: &ClassifierTargetProbabilities<F>,
+    y_true: &ClassifierTarget,
+) -> F {
+    match probabilities.get(y_true) {
+        Some(p) => *p,
+        None if probabilities.len() == 1 => F::one() - *probabilities.values().next().unwrap(),
+        None => F::zero(),
+    }
+}
+
+// Cumulative distribution function of the standard normal distribution, using the approximation
+// 7.1.26 of Abramowitz and Stegun for the error function, whose error is below 1.5e-7.
+pub(crate) fn normal_cdf<F: Float + FromPrimitive>(x: F) -> F {
+    let f = |v: f64| F::from_f64(v).unwrap();
+    let z = x.abs() / f(std::f64::consts::SQRT_2);
+    let t = F::one() / (F::one() + f(0.3275911) * z);
+    let poly = t
+        * (f(0.254829592)
+            + t * (f(-0.284496736)
+                + t * (f(1.421413741) + t * (f(-1.453152027) + t * f(1.061405429)))));
+    let erf = F::one() - poly * (-z * z).exp();
+    if x >= F::zero() {
+        (F::one() + erf) / f(2.0)
+    } else {
+        (F::one() - erf) / f(2.0)
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierOutput, ClassifierTarget};
+use crate::metrics::traits::ClassificationMetric;
+use num::{Float, FromPrimitive};
+
+/// One-vs-rest wrapper, which computes a binary metric for each class of a multi-class task.
+///
+/// For each class, the samples are binarized such that the class is the positive one, i.e.
+/// `ClassifierTarget::Bool(true)`, and all the other classes are the negative one. Probabilities
+/// are binarized the same way. The wrapped metric must therefore consider `true` as its positive
+/// value, e.g. `ROCAUC::new(None, ClassifierTarget::from(true))`.
+///
+/// A new copy of the metric is created each time a class is seen for the first time, be it as the
+/// true class, as the prediction or in the predicted probabilities. The samples that came before
+/// were all true negatives for that class, which the new metric is first updated with, as a single
+/// sample weighing as much as all of them. The value of the wrapper is the macro-average of the
+/// per-class values.
+///
+/// # Parameters
+///
+/// - `metric`: The metric to compute for each class. It should be freshly initialized.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget};
+/// use light_river::metrics::rocauc::ROCAUC;
+/// use light_river::metrics::traits::ClassificationMetric;
+/// use light_river::metrics::wrapper::PerClass;
+/// use maplit::hashmap;
+///
+/// let mut metric: PerClass<f64, ROCAUC<f64>> =
+///     PerClass::new(ROCAUC::new(None, ClassifierTarget::from(true)));
+///
+/// let samples = vec![
+///     ("cat", 0.8, 0.1, 0.1),
+///     ("dog", 0.3, 0.6, 0.1),
+///     ("cow", 0.1, 0.3, 0.6),
+///     ("cat", 0.5, 0.4, 0.1),
+/// ];
+/// for (y_true, cat, dog, cow) in samples {
+///     let y_pred = ClassifierOutput::Probabilities(hashmap! {
+///         ClassifierTarget::from("cat") => cat,
+///         ClassifierTarget::from("dog") => dog,
+///         ClassifierTarget::from("cow") => cow,
+///     });
+///     metric.update(&y_pred, &ClassifierTarget::from(y_true), None);
+/// }
+/// assert_eq!(metric.get_class(&ClassifierTarget::from("cat")), Some(1.0));
+/// assert_eq!(metric.get_class(&ClassifierTarget::from("bird")), None);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize, M: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>, M: serde::Deserialize<'de>"
+    ))
+)]
+pub struct PerClass<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F> + Clone,
+{
+    template: M,
+    #[cfg_attr(feature = "serde", serde(with = "crate::common::serde_pairs"))]
+    metrics: HashMap<ClassifierTarget, M>,
+    // Total weight of the samples seen so far
+    weight: F,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F, M> PerClass<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F> + Clone,
+{
+    pub fn new(metric: M) -> Self {
+        Self {
+            template: metric,
+            metrics: HashMap::new(),
+            weight: F::zero(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+    /// The classes seen so far, in sorted order.
+    pub fn classes(&self) -> Vec<ClassifierTarget> {
+        let mut classes: Vec<_> = self.metrics.keys().cloned().collect();
+        classes.sort();
+        classes
+    }
+    /// The metric of a class, if the class has been seen.
+    pub fn metric(&self, class: &ClassifierTarget) -> Option<&M> {
+        self.metrics.get(class)
+    }
+    /// The value of the metric of a class, if the class has been seen.
+    pub fn get_class(&self, class: &ClassifierTarget) -> Option<F> {
+        self.metrics.get(class).map(|metric| metric.get())
+    }
+    /// The value of the metric of each class.
+    pub fn get_all(&self) -> HashMap<ClassifierTarget, F> {
+        self.metrics
+            .iter()
+            .map(|(class, metric)| (class.clone(), metric.get()))
+            .collect()
+    }
+    fn binarize(
+        class: &ClassifierTarget,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+    ) -> (ClassifierOutput<F>, ClassifierTarget) {
+        let y_pred = match y_pred {
+            ClassifierOutput::Prediction(label) => {
+                ClassifierOutput::Prediction(ClassifierTarget::from(label == class))
+            }
+            ClassifierOutput::Probabilities(probabilities) => {
+                let p = *probabilities.get(class).unwrap_or(&F::zero());
+                ClassifierOutput::Probabilities(HashMap::from([
+                    (ClassifierTarget::from(true), p),
+                    (ClassifierTarget::from(false), F::one() - p),
+                ]))
+            }
+        };
+        (y_pred, ClassifierTarget::from(y_true == class))
+    }
+}
+
+impl<F, M> ClassificationMetric<F> for PerClass<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: ClassificationMetric<F> + Clone,
+{
+    fn update(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        let mut seen = vec![y_true.clone()];
+        match y_pred {
+            ClassifierOutput::Prediction(label) => seen.push(label.clone()),
+            ClassifierOutput::Probabilities(probabilities) => {
+                seen.extend(probabilities.keys().cloned())
+            }
+        }
+        for class in seen {
+            if !self.metrics.contains_key(&class) {
+                let mut metric = self.template.clone();
+                if self.weight > F::zero() {
+                    // The previous samples, which were all negatives predicted as such
+                    let negative = match y_pred {
+                        ClassifierOutput::Prediction(_) => {
+                            ClassifierOutput::Prediction(ClassifierTarget::from(false))
+                        }
+                        ClassifierOutput::Probabilities(_) => {
+                            ClassifierOutput::Probabilities(HashMap::from([
+                                (ClassifierTarget::from(true), F::zero()),
+                                (ClassifierTarget::from(false), F::one()),
+                            ]))
+                        }
+                    };
+                    metric.update(&negative, &ClassifierTarget::from(false), Some(self.weight));
+                }
+                self.metrics.insert(class, metric);
+            }
+        }
+        for (class, metric) in self.metrics.iter_mut() {
+            let (y_pred, y_true) = Self::binarize(class, y_pred, y_true);
+            metric.update(&y_pred, &y_true, sample_weight);
+        }
+        self.weight += sample_weight.unwrap_or(F::one());
+    }
+    fn revert(
+        &mut self,
+        y_pred: &ClassifierOutput<F>,
+        y_true: &ClassifierTarget,
+        sample_weight: Option<F>,
+    ) {
+        for (class, metric) in self.metrics.iter_mut() {
+            let (y_pred, y_true) = Self::binarize(class, y_pred, y_true);
+            metric.revert(&y_pred, &y_true, sample_weight);
+        }
+        self.weight -= sample_weight.unwrap_or(F::one());
+    }
+    fn get(&self) -> F {
+        if self.metrics.is_empty() {
+            return F::zero();
+        }
+        let total = self
+            .metrics
+            .values()
+            .fold(F::zero(), |sum, metric| sum + metric.get());
+        total / F::from(self.metrics.len()).unwrap()
+    }
+    fn is_multiclass(&self) -> bool {
+        true
+    }
+    fn bigger_is_better(&self) -> bool {
+        self.template.bigger_is_better()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::accuracy::Accuracy;
+    use crate::metrics::report::{Average, Precision, Recall};
+
+    fn feed<M: ClassificationMetric<f64>>(metric: &mut M, y_true: &[&str], y_pred: &[&str]) {
+        for (yt, yp) in y_true.iter().zip(y_pred.iter()) {
+            metric.update(
+                &ClassifierOutput::Prediction(ClassifierTarget::from(*yp)),
+                &ClassifierTarget::from(*yt),
+                None,
+            );
+        }
+    }
+
+    const Y_TRUE: [&str; 6] = ["cat", "ant", "cat", "cat", "ant", "bird"];
+    const Y_PRED: [&str; 6] = ["ant", "ant", "cat", "cat", "ant", "cat"];
+
+    #[test]
+    fn test_per_class_matches_macro_average() {
+        let positive = || Average::Binary(ClassifierTarget::from(true));
+        let mut per_class = PerClass::new(Recall::new(positive()));
+        feed(&mut per_class, &Y_TRUE, &Y_PRED);
+        let mut expected = Recall::new(Average::Macro);
+        feed(&mut expected, &Y_TRUE, &Y_PRED);
+        assert!((per_class.get() - expected.get()).abs() < 1e-10);
+
+        let mut per_class = PerClass::new(Precision::new(positive()));
+        feed(&mut per_class, &Y_TRUE, &Y_PRED);
+        assert_eq!(
+            per_class.classes(),
+            ClassifierTarget::from_iter(["ant", "bird", "cat"].into_iter()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            per_class.get_class(&ClassifierTarget::from("ant")),
+            Some(2.0 / 3.0)
+        );
+        assert_eq!(
+            per_class.get_class(&ClassifierTarget::from("bird")),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_late_class() {
+        // The first two samples are true negatives for the bird class, which only comes last
+        let mut per_class = PerClass::new(Accuracy::new());
+        feed(
+            &mut per_class,
+            &["cat", "cat", "bird"],
+            &["cat", "cat", "cat"],
+        );
+        for class in ["bird", "cat"] {
+            let value = per_class.get_class(&ClassifierTarget::from(class)).unwrap();
+            assert!((value - 2.0 / 3.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_per_class_revert() {
+        let mut per_class =
+            PerClass::new(Recall::new(Average::Binary(ClassifierTarget::from(true))));
+        feed(&mut per_class, &Y_TRUE, &Y_PRED);
+        let expected = per_class.get_all();
+        let y_pred = ClassifierOutput::Prediction(ClassifierTarget::from("ant"));
+        per_class.update(&y_pred, &ClassifierTarget::from("cat"), Some(2.0));
+        per_class.revert(&y_pred, &ClassifierTarget::from("cat"), Some(2.0));
+        for (class, value) in per_class.get_all() {
+            assert!((value - expected[&class]).abs() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ModelTarget, ModelType, Observation};
+use crate::metrics::traits::Metric;
+use crate::model_selection::utils::{best_first, Candidate};
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// How a [`Bandit`] picks the candidate which learns from the next sample.
+#[derive(Clone, Debug)]
+pub enum BanditPolicy<F> {
+    /// Pick a random candidate with probability `epsilon`, and the best one otherwise.
+    EpsilonGreedy { epsilon: F },
+    /// Pick the candidate with the highest upper confidence bound, i.e. the value of its metric
+    /// plus `delta * sqrt(2 ln(n) / n_i)`, where `n_i` is the number of times it has been picked
+    /// out of `n`. The larger `delta`, the more exploration; it should be of the order of the
+    /// spread of the metric between candidates.
+    UCB { delta: F },
+}
+
+/// Model selection as a multi-armed bandit problem.
+///
+/// Each sample is given to a single candidate, which is picked according to the policy. The
+/// candidate is evaluated on the sample and then trained on it, and the value of its metric is
+/// used as its reward. Candidates which perform poorly are therefore picked less and less often,
+/// which is much cheaper than training all of them, at the cost of a slower identification of the
+/// best one. Each candidate is picked once before the policy kicks in.
+///
+/// Predictions are made by the candidate with the best metric.
+///
+/// # Parameters
+///
+/// - `models`: The candidates, which are expected to be of the same kind.
+/// - `metric`: Creates the metric each candidate is evaluated with.
+/// - `policy`: How to pick the candidate which learns from each sample.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::learner::Regressor;
+/// use light_river::linear_model::glm::GLMOptions;
+/// use light_river::linear_model::linear_regression::LinearRegression;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+/// use light_river::model_selection::bandit::{Bandit, BanditPolicy};
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// // The same model with different learning rates
+/// let models: Vec<ModelType<f64>> = [0.0001, 0.001, 0.01, 0.1]
+///     .into_iter()
+///     .map(|lr| {
+///         let options = GLMOptions {
+///             intercept_lr: lr,
+///             ..Default::default()
+///         };
+///         let model = LinearRegression::new(SGD::new(lr), Squared, options);
+///         ModelType::Regressor(Box::new(model) as Box<dyn Regressor<f64>>)
+///     })
+///     .collect();
+/// let mut bandit = Bandit::new(
+///     models,
+///     || Metric::Regression(Box::new(MAE::new())),
+///     BanditPolicy::EpsilonGreedy { epsilon: 0.1 },
+///     Some(42),
+/// );
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x: f64 = rng.gen();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     bandit.learn_one(&observation, ModelTarget::Regression(3.0 * x + 1.0));
+/// }
+/// assert_eq!(bandit.best_index(), 3);
+/// assert!(bandit.n_pulls()[3] > 1500);
+/// ```
+///
+/// # References
+///
+/// [^1]: P. Auer, N. Cesa-Bianchi and P. Fischer (2002). "Finite-time analysis of the multiarmed
+/// bandit problem". Machine Learning 47:235-256.
+pub struct Bandit<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    candidates: Vec<Candidate<F>>,
+    policy: BanditPolicy<F>,
+    n_pulls: Vec<usize>,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Bandit<F> {
+    pub fn new(
+        models: Vec<ModelType<F>>,
+        metric: impl Fn() -> Metric<F>,
+        policy: BanditPolicy<F>,
+        seed: Option<u64>,
+    ) -> Self {
+        assert!(!models.is_empty(), "At least one model is required");
+        Self {
+            n_pulls: vec![0; models.len()],
+            candidates: models
+                .into_iter()
+                .map(|model| Candidate::new(model, metric()))
+                .collect(),
+            policy,
+            rng: rng(seed),
+        }
+    }
+    pub fn learn_one(&mut self, x: &Observation<F>, y: ModelTarget<F>) {
+        let arm = self.pull();
+        self.candidates[arm].learn_one(x, y);
+        self.n_pulls[arm] += 1;
+    }
+    pub fn predict_one(&self, x: &Observation<F>) -> ModelTarget<F> {
+        self.best_model().predict_one(x)
+    }
+    fn pull(&mut self) -> usize {
+        if let Some(arm) = self.n_pulls.iter().position(|n| *n == 0) {
+            return arm;
+        }
+        match self.policy {
+            BanditPolicy::EpsilonGreedy { epsilon } => {
+                if F::from_f64(self.rng.gen()).unwrap() < epsilon {
+                    self.rng.gen_range(0..self.candidates.len())
+                } else {
+                    self.best_index()
+                }
+            }
+            BanditPolicy::UCB { delta } => {
+                let n = F::from_usize(self.n_pulls.iter().sum()).unwrap();
+                let two = F::one() + F::one();
+                let bound = |i: usize| {
+                    let metric = &self.candidates[i].metric;
+                    let reward = if metric.bigger_is_better() {
+                        metric.get()
+                    } else {
+                        -metric.get()
+                    };
+                    let n_i = F::from_usize(self.n_pulls[i]).unwrap();
+                    reward + delta * (two * n.ln() / n_i).sqrt()
+                };
+                (0..self.candidates.len())
+                    .max_by(|&a, &b| bound(a).partial_cmp(&bound(b)).unwrap())
+                    .unwrap()
+            }
+        }
+    }
+    /// Index, among the models given at creation, of the candidate with the best metric. Only
+    /// the candidates which have been picked at least once are considered.
+    pub fn best_index(&self) -> usize {
+        (0..self.candidates.len())
+            .filter(|i| self.n_pulls[*i] > 0)
+            .min_by(|&a, &b| best_first(&self.candidates[a], &self.candidates[b]))
+            .unwrap_or(0)
+    }
+    pub fn best_model(&self) -> &ModelType<F> {
+        &self.candidates[self.best_index()].model
+    }
+    /// Number of times each candidate has been picked.
+    pub fn n_pulls(&self) -> &[usize] {
+        &self.n_pulls
+    }
+    pub fn metric(&self, i: usize) -> &Metric<F> {
+        &self.candidates[i].metric
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learner::Regressor;
+    use crate::metrics::regression::MAE;
+    use crate::testing::Constant;
+
+    fn bandit(policy: BanditPolicy<f64>) -> Bandit<f64> {
+        let models = [0.0, 1.0, 2.0, 3.0]
+            .iter()
+            .map(|c| ModelType::Regressor(Box::new(Constant(*c)) as Box<dyn Regressor<f64>>))
+            .collect();
+        Bandit::new(
+            models,
+            || Metric::Regression(Box::new(MAE::new())),
+            policy,
+            Some(7),
+        )
+    }
+
+    #[test]
+    fn test_each_arm_is_pulled_first() {
+        let mut bandit = bandit(BanditPolicy::EpsilonGreedy { epsilon: 0.0 });
+        let x = Observation::new();
+        for _ in 0..4 {
+            bandit.learn_one(&x, ModelTarget::Regression(1.1));
+        }
+        assert_eq!(bandit.n_pulls(), &[1, 1, 1, 1]);
+        // Without exploration, only the best arm is pulled from then on
+        for _ in 0..10 {
+            bandit.learn_one(&x, ModelTarget::Regression(1.1));
+        }
+        assert_eq!(bandit.n_pulls(), &[1, 11, 1, 1]);
+        assert_eq!(bandit.predict_one(&x), ModelTarget::Regression(1.0));
+    }
+
+    #[test]
+    fn test_ucb() {
+        let mut bandit = bandit(BanditPolicy::UCB { delta: 0.5 });
+        let x = Observation::new();
+        for i in 0..1000 {
+            let y = 2.0 + (i % 2) as f64 * 0.6;
+            bandit.learn_one(&x, ModelTarget::Regression(y));
+        }
+        assert_eq!(bandit.best_index(), 2);
+        let n_pulls = bandit.n_pulls();
+        assert!(n_pulls[2] > n_pulls[1] && n_pulls[2] > n_pulls[3]);
+        assert!(n_pulls.iter().all(|n| *n > 1));
+    }
+}
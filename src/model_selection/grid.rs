@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::common::{ModelTarget, ModelType, Observation};
+use crate::evaluate::Checkpoint;
+use crate::metrics::traits::Metric;
+use crate::model_selection::utils::{best_first, Candidate};
+use crate::stream::data_stream::Data;
+use num::{Float, FromPrimitive};
+
+/// A combination of hyperparameters, indexed by name.
+pub type Params<F> = BTreeMap<String, Data<F>>;
+
+/// The values to try for each hyperparameter.
+pub type ParamGrid<F> = BTreeMap<String, Vec<Data<F>>>;
+
+/// All the combinations of the values of a grid of hyperparameters.
+///
+/// The combinations are ordered as nested loops over the hyperparameters sorted by name, i.e. the
+/// values of the last hyperparameter vary the fastest. A hyperparameter with no values yields no
+/// combinations at all, whereas an empty grid yields a single, empty, combination.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::model_selection::grid::{expand_param_grid, ParamGrid};
+/// use light_river::stream::data_stream::Data;
+///
+/// let grid: ParamGrid<f64> = ParamGrid::from([
+///     ("lr".to_string(), vec![Data::Scalar(0.1), Data::Scalar(0.01)]),
+///     ("depth".to_string(), vec![Data::Int(2), Data::Int(4), Data::Int(6)]),
+/// ]);
+/// let combinations = expand_param_grid(&grid);
+/// assert_eq!(combinations.len(), 6);
+/// assert_eq!(combinations[1]["depth"], Data::Int(2));
+/// assert_eq!(combinations[1]["lr"], Data::Scalar(0.01));
+/// ```
+pub fn expand_param_grid<F: Float + FromStr>(grid: &ParamGrid<F>) -> Vec<Params<F>> {
+    grid.iter()
+        .fold(vec![Params::new()], |combinations, (name, values)| {
+            combinations
+                .iter()
+                .flat_map(|params| {
+                    values.iter().map(move |value| {
+                        let mut params = params.clone();
+                        params.insert(name.clone(), value.clone());
+                        params
+                    })
+                })
+                .collect()
+        })
+}
+
+/// Grid search over a stream.
+///
+/// One model is built for each combination of hyperparameters of the grid. All the models learn
+/// from every sample, after having been evaluated on it, and the value of their metric is recorded
+/// every `step` samples. Predictions are made by the model with the best metric.
+///
+/// # Parameters
+///
+/// - `grid`: The values to try for each hyperparameter, see `expand_param_grid`.
+/// - `builder`: Builds a model from a combination of hyperparameters.
+/// - `metric`: Creates the metric each model is evaluated with.
+/// - `step`: The number of samples between two records of the metrics.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::learner::Regressor;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+/// use light_river::model_selection::grid::{GridSearchStream, ParamGrid};
+/// use light_river::stream::data_stream::Data;
+///
+/// // Exponential moving average of the targets
+/// struct Ema {
+///     alpha: f64,
+///     value: f64,
+/// }
+///
+/// impl Regressor<f64> for Ema {
+///     fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+///         self.value += self.alpha * (y - self.value);
+///     }
+///     fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+///         self.value
+///     }
+/// }
+///
+/// let grid = ParamGrid::from([(
+///     "alpha".to_string(),
+///     vec![Data::Scalar(0.01), Data::Scalar(0.1), Data::Scalar(0.5)],
+/// )]);
+/// let mut search = GridSearchStream::new(
+///     &grid,
+///     |params| {
+///         let alpha = params["alpha"].to_float().unwrap();
+///         ModelType::Regressor(Box::new(Ema { alpha, value: 0.0 }))
+///     },
+///     || Metric::Regression(Box::new(MAE::new())),
+///     100,
+/// );
+///
+/// for i in 0..1000 {
+///     // A slowly drifting target
+///     search.learn_one(&Observation::new(), ModelTarget::Regression(i as f64 / 100.0));
+/// }
+/// assert_eq!(search.best_params()["alpha"], Data::Scalar(0.5));
+/// assert_eq!(search.history(0).len(), 10);
+/// ```
+pub struct GridSearchStream<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + FromStr,
+> {
+    params: Vec<Params<F>>,
+    candidates: Vec<Candidate<F>>,
+    history: Vec<Vec<Checkpoint<F>>>,
+    step: usize,
+    n_samples: usize,
+    start: Instant,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign + FromStr>
+    GridSearchStream<F>
+{
+    pub fn new(
+        grid: &ParamGrid<F>,
+        builder: impl Fn(&Params<F>) -> ModelType<F>,
+        metric: impl Fn() -> Metric<F>,
+        step: usize,
+    ) -> Self {
+        assert!(step > 0, "step must be strictly positive");
+        let params = expand_param_grid(grid);
+        assert!(
+            !params.is_empty(),
+            "The grid must hold at least one combination"
+        );
+        Self {
+            candidates: params
+                .iter()
+                .map(|p| Candidate::new(builder(p), metric()))
+                .collect(),
+            history: params.iter().map(|_| Vec::new()).collect(),
+            params,
+            step,
+            n_samples: 0,
+            start: Instant::now(),
+        }
+    }
+    pub fn learn_one(&mut self, x: &Observation<F>, y: ModelTarget<F>) {
+        for candidate in self.candidates.iter_mut() {
+            candidate.learn_one(x, y.clone());
+        }
+        self.n_samples += 1;
+        if self.n_samples.is_multiple_of(self.step) {
+            for (candidate, history) in self.candidates.iter().zip(self.history.iter_mut()) {
+                history.push(Checkpoint {
+                    n_samples: self.n_samples,
+                    value: candidate.metric.get(),
+                    elapsed: self.start.elapsed(),
+                });
+            }
+        }
+    }
+    pub fn predict_one(&self, x: &Observation<F>) -> ModelTarget<F> {
+        self.best_model().predict_one(x)
+    }
+    /// Number of combinations of hyperparameters, i.e. of models.
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+    /// Index of the model with the best metric.
+    pub fn best_index(&self) -> usize {
+        (0..self.candidates.len())
+            .min_by(|&a, &b| best_first(&self.candidates[a], &self.candidates[b]))
+            .unwrap()
+    }
+    pub fn best_model(&self) -> &ModelType<F> {
+        &self.candidates[self.best_index()].model
+    }
+    pub fn best_params(&self) -> &Params<F> {
+        &self.params[self.best_index()]
+    }
+    /// Hyperparameters of the `i`-th model.
+    pub fn params(&self, i: usize) -> &Params<F> {
+        &self.params[i]
+    }
+    pub fn metric(&self, i: usize) -> &Metric<F> {
+        &self.candidates[i].metric
+    }
+    /// Values of the metric of the `i`-th model, recorded every `step` samples.
+    pub fn history(&self, i: usize) -> &[Checkpoint<F>] {
+        &self.history[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::regression::MAE;
+    use crate::testing::Constant;
+
+    #[test]
+    fn test_expand_param_grid() {
+        let grid: ParamGrid<f64> = ParamGrid::from([
+            ("b".to_string(), vec![Data::Bool(true), Data::Bool(false)]),
+            (
+                "a".to_string(),
+                vec![Data::String("x".to_string()), Data::String("y".to_string())],
+            ),
+            ("c".to_string(), vec![Data::Int(1)]),
+        ]);
+        let combinations: Vec<Vec<String>> = expand_param_grid(&grid)
+            .iter()
+            .map(|p| p.values().map(|v| v.to_string()).collect())
+            .collect();
+        assert_eq!(
+            combinations,
+            vec![
+                vec!["x", "true", "1"],
+                vec!["x", "false", "1"],
+                vec!["y", "true", "1"],
+                vec!["y", "false", "1"],
+            ]
+        );
+
+        assert_eq!(expand_param_grid::<f64>(&ParamGrid::new()).len(), 1);
+        let grid = ParamGrid::from([("a".to_string(), Vec::<Data<f64>>::new())]);
+        assert!(expand_param_grid(&grid).is_empty());
+    }
+
+    #[test]
+    fn test_history() {
+        let grid = ParamGrid::from([(
+            "c".to_string(),
+            vec![Data::Scalar(0.0), Data::Scalar(2.0), Data::Scalar(5.0)],
+        )]);
+        let mut search = GridSearchStream::new(
+            &grid,
+            |params| ModelType::Regressor(Box::new(Constant(params["c"].to_float().unwrap()))),
+            || Metric::Regression(Box::new(MAE::new())),
+            2,
+        );
+        let x = Observation::new();
+        for y in [1.0, 3.0, 4.0, 4.0, 1.0] {
+            search.learn_one(&x, ModelTarget::Regression(y));
+        }
+        assert_eq!(search.len(), 3);
+        assert_eq!(search.best_index(), 1);
+        assert_eq!(search.predict_one(&x), ModelTarget::Regression(2.0));
+        let history: Vec<(usize, f64)> = search
+            .history(2)
+            .iter()
+            .map(|c| (c.n_samples, c.value))
+            .collect();
+        assert_eq!(history, vec![(2, 3.0), (4, 2.0)]);
+    }
+}
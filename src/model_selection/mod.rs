@@ -0,0 +1,4 @@
+pub mod bandit;
+pub mod grid;
+pub mod successive_halving;
+pub mod utils;
@@ -0,0 +1,209 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ModelTarget, ModelType, Observation};
+use crate::metrics::traits::Metric;
+use crate::model_selection::utils::{best_first, Candidate};
+use num::{Float, FromPrimitive};
+
+/// Model selection by successive halving.
+///
+/// All the candidates are trained and evaluated on the same samples, in rungs. At the end of each
+/// rung, only the best `1 / eta` of the candidates are kept, until a single one remains. The
+/// budget, i.e. the total number of calls to `learn_one` of the candidates, is split evenly
+/// between the rungs, so that the fewer candidates remain, the more samples each of them is
+/// evaluated on before the next pruning. Once a single candidate remains, it keeps on learning as
+/// usual.
+///
+/// Predictions are made by the best candidate still racing.
+///
+/// # Parameters
+///
+/// - `models`: The candidates, which are expected to be of the same kind.
+/// - `metric`: Creates the metric each candidate is evaluated with.
+/// - `budget`: The number of calls to `learn_one` split between the rungs.
+/// - `eta`: The fraction of the candidates pruned at each rung is `1 - 1 / eta`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::learner::Regressor;
+/// use light_river::linear_model::glm::GLMOptions;
+/// use light_river::linear_model::linear_regression::LinearRegression;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+/// use light_river::model_selection::successive_halving::SuccessiveHalving;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// // The same model with different learning rates
+/// let models: Vec<ModelType<f64>> = [0.0001, 0.001, 0.01, 0.1]
+///     .into_iter()
+///     .map(|lr| {
+///         let options = GLMOptions {
+///             intercept_lr: lr,
+///             ..Default::default()
+///         };
+///         let model = LinearRegression::new(SGD::new(lr), Squared, options);
+///         ModelType::Regressor(Box::new(model) as Box<dyn Regressor<f64>>)
+///     })
+///     .collect();
+/// let mut selection = SuccessiveHalving::new(
+///     models,
+///     || Metric::Regression(Box::new(MAE::new())),
+///     1000,
+///     2,
+/// );
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..500 {
+///     let x: f64 = rng.gen();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     selection.learn_one(&observation, ModelTarget::Regression(3.0 * x + 1.0));
+/// }
+/// assert_eq!(selection.n_alive(), 1);
+/// assert_eq!(selection.best_index(), 3);
+/// ```
+///
+/// # References
+///
+/// [^1]: K. Jamieson and A. Talwalkar (2016). "Non-stochastic best arm identification and
+/// hyperparameter optimization". Artificial Intelligence and Statistics, 240-248.
+pub struct SuccessiveHalving<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    candidates: Vec<Candidate<F>>,
+    // Indices of the candidates still racing, from the best to the worst as of the last pruning
+    alive: Vec<usize>,
+    budget: usize,
+    eta: usize,
+    n_rungs: usize,
+    rung: usize,
+    n_seen: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    SuccessiveHalving<F>
+{
+    pub fn new(
+        models: Vec<ModelType<F>>,
+        metric: impl Fn() -> Metric<F>,
+        budget: usize,
+        eta: usize,
+    ) -> Self {
+        assert!(!models.is_empty(), "At least one model is required");
+        assert!(eta >= 2, "eta must be at least 2");
+        // Number of prunings needed to go down to a single candidate
+        let mut n_rungs = 0;
+        let mut n_alive = models.len();
+        while n_alive > 1 {
+            n_alive = n_alive.div_ceil(eta);
+            n_rungs += 1;
+        }
+        Self {
+            alive: (0..models.len()).collect(),
+            candidates: models
+                .into_iter()
+                .map(|model| Candidate::new(model, metric()))
+                .collect(),
+            budget,
+            eta,
+            n_rungs,
+            rung: 0,
+            n_seen: 0,
+        }
+    }
+    pub fn learn_one(&mut self, x: &Observation<F>, y: ModelTarget<F>) {
+        for &i in self.alive.iter() {
+            self.candidates[i].learn_one(x, y.clone());
+        }
+        self.n_seen += 1;
+        if self.alive.len() > 1 && self.n_seen >= self.rung_budget() {
+            let candidates = &self.candidates;
+            self.alive
+                .sort_by(|&a, &b| best_first(&candidates[a], &candidates[b]));
+            self.alive.truncate(self.alive.len().div_ceil(self.eta));
+            self.rung += 1;
+            self.n_seen = 0;
+        }
+    }
+    pub fn predict_one(&self, x: &Observation<F>) -> ModelTarget<F> {
+        self.best_model().predict_one(x)
+    }
+    // Number of samples each candidate learns from during the current rung
+    fn rung_budget(&self) -> usize {
+        (self.budget / (self.alive.len() * self.n_rungs)).max(1)
+    }
+    /// Index, among the models given at creation, of the best candidate still racing.
+    pub fn best_index(&self) -> usize {
+        *self
+            .alive
+            .iter()
+            .min_by(|&&a, &&b| best_first(&self.candidates[a], &self.candidates[b]))
+            .unwrap()
+    }
+    pub fn best_model(&self) -> &ModelType<F> {
+        &self.candidates[self.best_index()].model
+    }
+    /// Indices of the candidates still racing.
+    pub fn alive(&self) -> &[usize] {
+        &self.alive
+    }
+    pub fn n_alive(&self) -> usize {
+        self.alive.len()
+    }
+    /// Number of prunings made so far.
+    pub fn rung(&self) -> usize {
+        self.rung
+    }
+    /// Metric of the `i`-th candidate. Pruned candidates keep the value they had when pruned.
+    pub fn metric(&self, i: usize) -> &Metric<F> {
+        &self.candidates[i].metric
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learner::Regressor;
+    use crate::metrics::regression::MAE;
+    use crate::testing::Constant;
+
+    fn constants(values: &[f64]) -> Vec<ModelType<f64>> {
+        values
+            .iter()
+            .map(|c| ModelType::Regressor(Box::new(Constant(*c)) as Box<dyn Regressor<f64>>))
+            .collect()
+    }
+
+    #[test]
+    fn test_rungs() {
+        let mut selection = SuccessiveHalving::new(
+            constants(&[0.0, 1.2, 2.0, 3.0]),
+            || Metric::Regression(Box::new(MAE::new())),
+            80,
+            2,
+        );
+        let x = Observation::new();
+        // Two rungs, of 10 samples for four candidates and then of 20 samples for two
+        for _ in 0..10 {
+            selection.learn_one(&x, ModelTarget::Regression(1.0));
+        }
+        assert_eq!(selection.rung(), 1);
+        assert_eq!(selection.alive(), &[1, 0]);
+        for _ in 0..19 {
+            selection.learn_one(&x, ModelTarget::Regression(1.0));
+        }
+        assert_eq!(selection.n_alive(), 2);
+        selection.learn_one(&x, ModelTarget::Regression(1.0));
+        assert_eq!(selection.alive(), &[1]);
+        assert_eq!(selection.predict_one(&x), ModelTarget::Regression(1.2));
+
+        // The remaining candidate keeps on learning
+        selection.learn_one(&x, ModelTarget::Regression(1.0));
+        assert_eq!(selection.rung(), 2);
+        assert!((selection.metric(1).get() - 0.2).abs() < 1e-10);
+        assert!((selection.metric(0).get() - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,43 @@
+use std::cmp::Ordering;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ModelTarget, ModelType, Observation};
+use crate::evaluate::{predict, score};
+use crate::metrics::traits::Metric;
+use num::{Float, FromPrimitive};
+
+// A model along with the metric of its prequential performance.
+pub(crate) struct Candidate<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    pub(crate) model: ModelType<F>,
+    pub(crate) metric: Metric<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Candidate<F> {
+    pub(crate) fn new(model: ModelType<F>, metric: Metric<F>) -> Self {
+        Self { model, metric }
+    }
+    // Test the model on the sample before training it, as in `progressive_val_score`.
+    pub(crate) fn learn_one(&mut self, x: &Observation<F>, y: ModelTarget<F>) {
+        let y_pred = predict(&self.model, x);
+        score(&mut self.metric, &y_pred, &y);
+        self.model.learn_one(x, y);
+    }
+}
+
+// Order candidates from the best to the worst metric.
+pub(crate) fn best_first<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    a: &Candidate<F>,
+    b: &Candidate<F>,
+) -> Ordering {
+    if a.metric.is_better_than(&b.metric) {
+        Ordering::Less
+    } else if b.metric.is_better_than(&a.metric) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
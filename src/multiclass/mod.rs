@@ -0,0 +1,24 @@
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+use num::Float;
+
+pub mod output_code;
+pub mod ovo;
+pub mod ovr;
+
+// Probabilities proportional to the scores of the classes, which must be positive.
+fn normalize<F: Float>(
+    scores: impl Iterator<Item = (ClassifierTarget, F)>,
+) -> ClassifierTargetProbabilities<F> {
+    let scores: Vec<(ClassifierTarget, F)> = scores.collect();
+    let total = scores
+        .iter()
+        .fold(F::zero(), |sum, (_, score)| sum + *score);
+    if total <= F::zero() {
+        let n = F::from(scores.len()).unwrap();
+        return scores.into_iter().map(|(y, _)| (y, F::one() / n)).collect();
+    }
+    scores
+        .into_iter()
+        .map(|(y, score)| (y, score / total))
+        .collect()
+}
@@ -0,0 +1,186 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::calibration::positive_proba;
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::multiclass::normalize;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Output code reduction of a multi-class problem to binary ones.
+///
+/// Each class is assigned a random binary code of `code_size` bits when it first appears, which
+/// differs from the codes of the other classes as long as there are enough codes. A binary
+/// classifier is trained per bit, to predict the bit of the code of the class of a sample. The
+/// score of a class is the number of bits minus the L1 distance between its code and the
+/// probabilities of the bits, and its probability is its share of the scores. The number of
+/// classifiers doesn't depend on the number of classes, and the redundancy of the codes corrects
+/// some of the errors of the classifiers.
+///
+/// # Parameters
+///
+/// - `classifier`: The binary classifier, whose labels are booleans, which is cloned for each bit.
+/// - `code_size`: The number of bits of the codes.
+/// - `seed`: Seed of the random number generator, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::multiclass::output_code::OutputCodeClassifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model = OutputCodeClassifier::new(GaussianNB::new(), 8, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen_range(0.0..9.0);
+///     let y = ["low", "mid", "high"][(x / 3.0) as usize];
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), ClassifierTarget::from(y));
+/// }
+/// let x = Observation::from([("x".to_string(), 0.5)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from("low"));
+/// ```
+///
+/// # References
+///
+/// [^1]: T. G. Dietterich and G. Bakiri (1995). "Solving multiclass learning problems via
+/// error-correcting output codes". Journal of artificial intelligence research 2:263-286.
+#[derive(Clone, Debug)]
+pub struct OutputCodeClassifier<F, M> {
+    members: Vec<M>,
+    codes: Vec<(ClassifierTarget, Vec<bool>)>,
+    rng: StdRng,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> OutputCodeClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(classifier: M, code_size: usize, seed: Option<u64>) -> Self {
+        assert!(code_size > 0, "code_size must be strictly positive");
+        let rng = rng(seed);
+        Self {
+            members: vec![classifier; code_size],
+            codes: Vec::new(),
+            rng,
+            _float: PhantomData,
+        }
+    }
+    /// The code of a class, if it has been seen.
+    pub fn code(&self, y: &ClassifierTarget) -> Option<&[bool]> {
+        self.codes
+            .iter()
+            .find(|(class, _)| class == y)
+            .map(|(_, code)| code.as_slice())
+    }
+    fn new_code(&mut self) -> Vec<bool> {
+        let code_size = self.members.len();
+        // Codes are drawn again while they are taken, unless they are all taken
+        let n_codes = 2f64.powi(code_size.min(64) as i32);
+        loop {
+            let code: Vec<bool> = (0..code_size).map(|_| self.rng.gen()).collect();
+            if (self.codes.len() as f64) >= n_codes || self.codes.iter().all(|(_, c)| *c != code) {
+                return code;
+            }
+        }
+    }
+}
+
+impl<F, M> Classifier<F> for OutputCodeClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let code = match self.code(&y) {
+            Some(code) => code.to_vec(),
+            None => {
+                let code = self.new_code();
+                self.codes.push((y, code.clone()));
+                code
+            }
+        };
+        for (member, bit) in self.members.iter_mut().zip(code) {
+            member.learn_one(x, ClassifierTarget::Bool(bit));
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let probabilities: Vec<Option<F>> = self
+            .members
+            .iter()
+            .map(|member| positive_proba(&member.predict_proba(x)))
+            .collect();
+        if probabilities.iter().all(|p| p.is_none()) {
+            return ClassifierTargetProbabilities::new();
+        }
+        // The bits which aren't predicted are uncertain
+        let half = F::from_f64(0.5).unwrap();
+        let probabilities: Vec<F> = probabilities
+            .into_iter()
+            .map(|p| p.unwrap_or(half))
+            .collect();
+        let code_size = F::from_usize(self.members.len()).unwrap();
+        normalize(self.codes.iter().map(|(y, code)| {
+            let distance = code
+                .iter()
+                .zip(probabilities.iter())
+                .fold(F::zero(), |sum, (bit, p)| {
+                    sum + if *bit { F::one() - *p } else { *p }
+                });
+            (y.clone(), code_size - distance)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Predicts the last bit it learned with certainty
+    #[derive(Clone, Default)]
+    struct Last(Option<bool>);
+
+    impl Classifier<f64> for Last {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+            self.0 = Some(y == ClassifierTarget::Bool(true));
+        }
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            match self.0 {
+                Some(bit) => ClassifierTargetProbabilities::from([(
+                    ClassifierTarget::Bool(true),
+                    bit as u8 as f64,
+                )]),
+                None => ClassifierTargetProbabilities::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_codes() {
+        let mut model = OutputCodeClassifier::new(Last::default(), 2, Some(7));
+        assert!(model.predict_proba(&Observation::new()).is_empty());
+        for y in 0..4 {
+            model.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+        // The four codes of two bits are all taken
+        let mut codes: Vec<&[bool]> = (0..4)
+            .map(|y| model.code(&ClassifierTarget::from(y)).unwrap())
+            .collect();
+        codes.sort();
+        assert_eq!(
+            codes,
+            [[false, false], [false, true], [true, false], [true, true]]
+        );
+        // The bits of the last class are predicted
+        assert_eq!(
+            model.predict_one(&Observation::new()),
+            ClassifierTarget::from(3)
+        );
+    }
+}
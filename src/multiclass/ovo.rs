@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::calibration::positive_proba;
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::multiclass::normalize;
+use num::{Float, FromPrimitive};
+
+/// One-vs-one reduction of a multi-class problem to binary ones.
+///
+/// A binary classifier is trained per pair of classes, to tell them apart, and only learns from
+/// the samples of its two classes. The classifiers of a class are cloned from the given one when
+/// the class first appears. Each classifier votes for its two classes in proportion to their
+/// probabilities, and the probability of a class is its share of the votes. There are
+/// `k * (k - 1) / 2` classifiers for `k` classes, but each learns from fewer samples than with a
+/// one-vs-rest reduction, and the binary problems are often simpler.
+///
+/// # Parameters
+///
+/// - `classifier`: The binary classifier, whose labels are booleans, which is cloned for each
+///   pair of classes.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::multiclass::ovo::OneVsOneClassifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut ovo = OneVsOneClassifier::new(GaussianNB::new());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen_range(0.0..9.0);
+///     let y = ["low", "mid", "high"][(x / 3.0) as usize];
+///     ovo.learn_one(&Observation::from([("x".to_string(), x)]), ClassifierTarget::from(y));
+/// }
+/// assert_eq!(ovo.n_members(), 3);
+/// let x = Observation::from([("x".to_string(), 4.5)]);
+/// assert_eq!(ovo.predict_one(&x), ClassifierTarget::from("mid"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct OneVsOneClassifier<F, M> {
+    classifier: M,
+    classes: Vec<ClassifierTarget>,
+    // Classifier of each pair of classes, given by their indices, which tells whether a sample
+    // belongs to the first class
+    members: Vec<((usize, usize), M)>,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> OneVsOneClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(classifier: M) -> Self {
+        Self {
+            classifier,
+            classes: Vec::new(),
+            members: Vec::new(),
+            _float: PhantomData,
+        }
+    }
+    /// The classes seen so far, in order of appearance.
+    pub fn classes(&self) -> &[ClassifierTarget] {
+        &self.classes
+    }
+    /// The number of binary classifiers.
+    pub fn n_members(&self) -> usize {
+        self.members.len()
+    }
+}
+
+impl<F, M> Classifier<F> for OneVsOneClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let k = match self.classes.iter().position(|class| *class == y) {
+            Some(k) => k,
+            None => {
+                let k = self.classes.len();
+                for i in 0..k {
+                    self.members.push(((i, k), self.classifier.clone()));
+                }
+                self.classes.push(y);
+                k
+            }
+        };
+        for ((i, j), member) in self.members.iter_mut() {
+            if *i == k || *j == k {
+                member.learn_one(x, ClassifierTarget::Bool(*i == k));
+            }
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let mut votes = vec![F::zero(); self.classes.len()];
+        let mut voted = false;
+        for ((i, j), member) in self.members.iter() {
+            if let Some(p) = positive_proba(&member.predict_proba(x)) {
+                votes[*i] += p;
+                votes[*j] += F::one() - p;
+                voted = true;
+            }
+        }
+        if !voted {
+            return ClassifierTargetProbabilities::new();
+        }
+        normalize(self.classes.iter().cloned().zip(votes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Remembers the labels it learns from, and is sure of the first class
+    #[derive(Clone, Default)]
+    struct Recorder(Vec<bool>);
+
+    impl Classifier<f64> for Recorder {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+            self.0.push(y == ClassifierTarget::Bool(true));
+        }
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            ClassifierTargetProbabilities::from([(ClassifierTarget::Bool(true), 1.0)])
+        }
+    }
+
+    #[test]
+    fn test_pairs() {
+        let mut ovo = OneVsOneClassifier::new(Recorder::default());
+        for y in [1, 2, 3, 1, 3] {
+            ovo.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+        assert_eq!(ovo.n_members(), 3);
+        let labels: Vec<&[bool]> = ovo.members.iter().map(|(_, m)| m.0.as_slice()).collect();
+        // The pairs (1, 2), (1, 3) and (2, 3)
+        assert_eq!(
+            labels,
+            [&[false, true][..], &[false, true, false], &[false, false]]
+        );
+        // The first class of each pair gets all of its votes
+        let probabilities = ovo.predict_proba(&Observation::new());
+        assert_eq!(probabilities[&ClassifierTarget::from(1)], 2.0 / 3.0);
+        assert_eq!(probabilities[&ClassifierTarget::from(3)], 0.0);
+    }
+}
@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::calibration::positive_proba;
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::multiclass::normalize;
+use num::{Float, FromPrimitive};
+
+/// One-vs-rest reduction of a multi-class problem to binary ones.
+///
+/// A binary classifier is trained per class, to tell whether a sample belongs to the class. The
+/// classifier of a class is cloned from the given one when the class first appears, and learns
+/// from all the samples from then on. The probability of a class is the probability given by its
+/// classifier, normalized over the classes.
+///
+/// # Parameters
+///
+/// - `classifier`: The binary classifier, whose labels are booleans, which is cloned for each
+///   class.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::multiclass::ovr::OneVsRestClassifier;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+/// let mut ovr = OneVsRestClassifier::new(model);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..3000 {
+///     let x = rng.gen_range(0.0..9.0);
+///     let y = ["low", "mid", "high"][(x / 3.0) as usize];
+///     ovr.learn_one(&Observation::from([("x".to_string(), x)]), ClassifierTarget::from(y));
+/// }
+/// assert_eq!(ovr.classes().len(), 3);
+/// let x = Observation::from([("x".to_string(), 8.5)]);
+/// assert_eq!(ovr.predict_one(&x), ClassifierTarget::from("high"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct OneVsRestClassifier<F, M> {
+    classifier: M,
+    // Classifier of each class, in order of appearance
+    members: Vec<(ClassifierTarget, M)>,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> OneVsRestClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(classifier: M) -> Self {
+        Self {
+            classifier,
+            members: Vec::new(),
+            _float: PhantomData,
+        }
+    }
+    /// The classes seen so far, in order of appearance.
+    pub fn classes(&self) -> Vec<&ClassifierTarget> {
+        self.members.iter().map(|(y, _)| y).collect()
+    }
+    /// The binary classifier of a class.
+    pub fn member(&self, y: &ClassifierTarget) -> Option<&M> {
+        self.members
+            .iter()
+            .find(|(class, _)| class == y)
+            .map(|(_, member)| member)
+    }
+}
+
+impl<F, M> Classifier<F> for OneVsRestClassifier<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        if self.member(&y).is_none() {
+            self.members.push((y.clone(), self.classifier.clone()));
+        }
+        for (class, member) in self.members.iter_mut() {
+            member.learn_one(x, ClassifierTarget::Bool(*class == y));
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let scores: Vec<(ClassifierTarget, F)> = self
+            .members
+            .iter()
+            .filter_map(|(class, member)| {
+                positive_proba(&member.predict_proba(x)).map(|p| (class.clone(), p))
+            })
+            .collect();
+        if scores.is_empty() {
+            return ClassifierTargetProbabilities::new();
+        }
+        normalize(scores.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Predicts its learning count for the positive class, to check who learns from what
+    #[derive(Clone, Default)]
+    struct Count(f64);
+
+    impl Classifier<f64> for Count {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: ClassifierTarget) {
+            if y == ClassifierTarget::Bool(true) {
+                self.0 += 1.0;
+            }
+        }
+        fn predict_proba(&self, _x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            ClassifierTargetProbabilities::from([(ClassifierTarget::Bool(true), self.0)])
+        }
+    }
+
+    #[test]
+    fn test_members() {
+        let mut ovr = OneVsRestClassifier::new(Count::default());
+        for y in ["a", "b", "a", "c", "a"] {
+            ovr.learn_one(&Observation::new(), ClassifierTarget::from(y));
+        }
+        assert_eq!(
+            ovr.classes(),
+            [
+                &ClassifierTarget::from("a"),
+                &ClassifierTarget::from("b"),
+                &ClassifierTarget::from("c")
+            ]
+        );
+        let probabilities = ovr.predict_proba(&Observation::new());
+        assert_eq!(probabilities[&ClassifierTarget::from("a")], 0.6);
+        assert_eq!(probabilities[&ClassifierTarget::from("c")], 0.2);
+    }
+}
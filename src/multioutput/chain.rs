@@ -0,0 +1,214 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierOutput, ClassifierTarget, FeatureValue, MultiClassifierTarget,
+    MultiClassifierTargetProbabilities, Observation,
+};
+use crate::learner::{Classifier, MultiOutputClassifier};
+use num::{Float, FromPrimitive};
+
+/// Classifier chain, which handles multi-output classification with a classifier per output.
+///
+/// The classifiers are arranged in a chain, and each one sees the features of the instance along
+/// with the classes of the outputs which come before its own in the chain. These are the true
+/// classes when learning, and the predicted ones when predicting, so that the dependencies between
+/// the outputs are captured. Boolean classes are given as numeric features, equal to 0 or 1, and
+/// the other classes as categorical ones, which are named after their output and override the
+/// features of the instance with the same name.
+///
+/// The order of the chain is either given, in which case the outputs outside of it are ignored, or
+/// that in which the outputs first appear, those appearing together being sorted by name. The
+/// classifier of an output is cloned from the given one when it is added to the chain.
+///
+/// # Parameters
+///
+/// - `classifier`: The classifier, which is cloned for each output.
+/// - `order`: The names of the outputs, in the order of the chain. Defaults to the order in which
+///   the outputs appear.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, MultiClassifierTarget, Observation};
+/// use light_river::learner::MultiOutputClassifier;
+/// use light_river::multioutput::chain::MultiClassifierChain;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model = MultiClassifierChain::new(GaussianNB::new(), None);
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..1000 {
+///     let x = rng.gen_range(0.0..10.0);
+///     let y = MultiClassifierTarget::from([
+///         ("big".to_string(), ClassifierTarget::Bool(x > 5.0)),
+///         ("size".to_string(), ClassifierTarget::from(["s", "m", "l"][(x / 3.34) as usize])),
+///     ]);
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), &y);
+/// }
+/// assert_eq!(model.order(), ["big", "size"]);
+/// let y = model.predict_one(&Observation::from([("x".to_string(), 9.0)]));
+/// assert_eq!(y["big"], ClassifierTarget::Bool(true));
+/// assert_eq!(y["size"], ClassifierTarget::from("l"));
+/// ```
+///
+/// # References
+///
+/// [^1]: J. Read, B. Pfahringer, G. Holmes and E. Frank (2011). "Classifier chains for multi-label
+/// classification". Machine learning 85(3):333-359.
+#[derive(Clone, Debug)]
+pub struct MultiClassifierChain<F, M> {
+    classifier: M,
+    fixed_order: bool,
+    // Classifier of each output, in the order of the chain
+    members: Vec<(String, M)>,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> MultiClassifierChain<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    pub fn new(classifier: M, order: Option<Vec<String>>) -> Self {
+        let fixed_order = order.is_some();
+        let members: Vec<(String, M)> = order
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| (name, classifier.clone()))
+            .collect();
+        for (i, (name, _)) in members.iter().enumerate() {
+            assert!(
+                members[..i].iter().all(|(other, _)| other != name),
+                "order must not contain duplicates"
+            );
+        }
+        Self {
+            classifier,
+            fixed_order,
+            members,
+            _float: PhantomData,
+        }
+    }
+    /// The names of the outputs, in the order of the chain.
+    pub fn order(&self) -> Vec<&String> {
+        self.members.iter().map(|(name, _)| name).collect()
+    }
+    /// The classifier of an output.
+    pub fn member(&self, name: &str) -> Option<&M> {
+        self.members
+            .iter()
+            .find(|(output, _)| output == name)
+            .map(|(_, member)| member)
+    }
+}
+
+// Feature given to the next classifiers of the chain for the class of an output.
+fn chain_feature<F: Float>(y: &ClassifierTarget) -> FeatureValue<F> {
+    match y {
+        ClassifierTarget::Bool(b) => FeatureValue::Numeric(if *b { F::one() } else { F::zero() }),
+        y => FeatureValue::Categorical(y.to_string()),
+    }
+}
+
+impl<F, M> MultiOutputClassifier<F> for MultiClassifierChain<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Classifier<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: &MultiClassifierTarget) {
+        if !self.fixed_order {
+            let mut new: Vec<&String> = y
+                .keys()
+                .filter(|name| self.member(name).is_none())
+                .collect();
+            new.sort();
+            for name in new {
+                self.members.push((name.clone(), self.classifier.clone()));
+            }
+        }
+        let mut x = x.clone();
+        for (name, member) in self.members.iter_mut() {
+            if let Some(target) = y.get(name) {
+                member.learn_one(&x, target.clone());
+                x.insert(name.clone(), chain_feature(target));
+            }
+        }
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> MultiClassifierTargetProbabilities<F> {
+        let mut x = x.clone();
+        let mut probabilities = MultiClassifierTargetProbabilities::new();
+        for (name, member) in self.members.iter() {
+            let p = member.predict_proba(&x);
+            if p.is_empty() {
+                continue;
+            }
+            let y = ClassifierOutput::Probabilities(p.clone()).get_predicition();
+            x.insert(name.clone(), chain_feature(&y));
+            probabilities.insert(name.clone(), p);
+        }
+        probabilities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ClassifierTargetProbabilities;
+    use std::cell::RefCell;
+
+    // Remembers the features it sees, and predicts the last class it learned
+    #[derive(Clone, Default)]
+    struct Recorder {
+        seen: RefCell<Vec<Vec<String>>>,
+        last: Option<ClassifierTarget>,
+    }
+
+    impl Classifier<f64> for Recorder {
+        fn learn_one(&mut self, x: &Observation<f64>, y: ClassifierTarget) {
+            self.seen.borrow_mut().push(x.keys().cloned().collect());
+            self.last = Some(y);
+        }
+        fn predict_proba(&self, x: &Observation<f64>) -> ClassifierTargetProbabilities<f64> {
+            self.seen.borrow_mut().push(x.keys().cloned().collect());
+            self.last.iter().map(|y| (y.clone(), 1.0)).collect()
+        }
+    }
+
+    #[test]
+    fn test_chain() {
+        let order = vec!["b".to_string(), "a".to_string()];
+        let mut model = MultiClassifierChain::new(Recorder::default(), Some(order));
+        let y = MultiClassifierTarget::from([
+            ("a".to_string(), ClassifierTarget::from(1)),
+            ("b".to_string(), ClassifierTarget::Bool(true)),
+            ("c".to_string(), ClassifierTarget::from("ignored")),
+        ]);
+        model.learn_one(&Observation::from([("x".to_string(), 1.0)]), &y);
+        assert_eq!(model.order(), ["b", "a"]);
+        // The outputs before a classifier in the chain are added to its features
+        let prediction = model.predict_one(&Observation::from([("x".to_string(), 1.0)]));
+        assert_eq!(prediction.len(), 2);
+        assert_eq!(prediction["a"], ClassifierTarget::from(1));
+        let seen = model.member("a").unwrap().seen.borrow();
+        assert_eq!(*seen, [vec!["b", "x"], vec!["b", "x"]]);
+        let seen = model.member("b").unwrap().seen.borrow();
+        assert_eq!(*seen, [vec!["x"], vec!["x"]]);
+    }
+
+    #[test]
+    fn test_order_of_appearance() {
+        let mut model = MultiClassifierChain::new(Recorder::default(), None);
+        for names in [&["z", "y"][..], &["x", "y"]] {
+            let y: MultiClassifierTarget = names
+                .iter()
+                .map(|name| (name.to_string(), ClassifierTarget::Bool(true)))
+                .collect();
+            model.learn_one(&Observation::new(), &y);
+        }
+        assert_eq!(model.order(), ["y", "z", "x"]);
+        // The missing outputs are neither learned nor given to the next classifiers
+        let seen = model.member("x").unwrap().seen.borrow();
+        assert_eq!(*seen, [vec!["y"]]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{MultiRegressionTarget, Observation};
+use crate::learner::{MultiOutputRegressor, Regressor};
+use num::{Float, FromPrimitive};
+
+/// Multi-target regression with an independent regressor per target.
+///
+/// The regressor of a target is cloned from the given one when the target first appears, and only
+/// learns from the instances which have a value for it.
+///
+/// # Parameters
+///
+/// - `regressor`: The regressor, which is cloned for each target.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{MultiRegressionTarget, Observation};
+/// use light_river::learner::MultiOutputRegressor;
+/// use light_river::linear_model::linear_regression::LinearRegression;
+/// use light_river::multioutput::regressor::MultiTargetRegressor;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+///
+/// let model = LinearRegression::new(SGD::new(0.05), Squared, Default::default());
+/// let mut model = MultiTargetRegressor::new(model);
+/// for i in 0..2000 {
+///     let x = (i % 10) as f64 / 10.0;
+///     let y = MultiRegressionTarget::from([
+///         ("up".to_string(), 2.0 * x),
+///         ("down".to_string(), 1.0 - x),
+///     ]);
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), &y);
+/// }
+/// let y = model.predict_one(&Observation::from([("x".to_string(), 0.5)]));
+/// assert!((y["up"] - 1.0).abs() < 0.05);
+/// assert!((y["down"] - 0.5).abs() < 0.05);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiTargetRegressor<F, M> {
+    regressor: M,
+    // Regressor of each target, in order of appearance
+    members: Vec<(String, M)>,
+    _float: PhantomData<F>,
+}
+
+impl<F, M> MultiTargetRegressor<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Regressor<F> + Clone,
+{
+    pub fn new(regressor: M) -> Self {
+        Self {
+            regressor,
+            members: Vec::new(),
+            _float: PhantomData,
+        }
+    }
+    /// The names of the targets seen so far, in order of appearance.
+    pub fn targets(&self) -> Vec<&String> {
+        self.members.iter().map(|(name, _)| name).collect()
+    }
+    /// The regressor of a target.
+    pub fn member(&self, name: &str) -> Option<&M> {
+        self.members
+            .iter()
+            .find(|(target, _)| target == name)
+            .map(|(_, member)| member)
+    }
+}
+
+impl<F, M> MultiOutputRegressor<F> for MultiTargetRegressor<F, M>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    M: Regressor<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: &MultiRegressionTarget<F>) {
+        let mut new: Vec<&String> = y
+            .keys()
+            .filter(|name| self.member(name).is_none())
+            .collect();
+        new.sort();
+        for name in new {
+            self.members.push((name.clone(), self.regressor.clone()));
+        }
+        for (name, member) in self.members.iter_mut() {
+            if let Some(target) = y.get(name) {
+                member.learn_one(x, *target);
+            }
+        }
+    }
+    fn predict_one(&self, x: &Observation<F>) -> MultiRegressionTarget<F> {
+        self.members
+            .iter()
+            .map(|(name, member)| (name.clone(), member.predict_one(x)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Predicts the sum of the targets it learned from
+    #[derive(Clone, Default)]
+    struct Sum(f64);
+
+    impl Regressor<f64> for Sum {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+            self.0 += y;
+        }
+        fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_members() {
+        let mut model = MultiTargetRegressor::new(Sum::default());
+        let targets = [
+            MultiRegressionTarget::from([("b".to_string(), 1.0), ("a".to_string(), 2.0)]),
+            MultiRegressionTarget::from([("c".to_string(), 3.0), ("a".to_string(), 4.0)]),
+        ];
+        for y in targets.iter() {
+            model.learn_one(&Observation::new(), y);
+        }
+        assert_eq!(model.targets(), ["a", "b", "c"]);
+        let y = model.predict_one(&Observation::new());
+        assert_eq!(
+            y,
+            MultiRegressionTarget::from([
+                ("a".to_string(), 6.0),
+                ("b".to_string(), 1.0),
+                ("c".to_string(), 3.0),
+            ])
+        );
+    }
+}
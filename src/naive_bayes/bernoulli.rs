@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::naive_bayes::utils::softmax;
+use num::{Float, FromPrimitive};
+
+/// Bernoulli naive Bayes.
+///
+/// Suited to binary features, such as whether each word appears in a document. A feature is
+/// deemed present when its value is strictly greater than `true_threshold`; features which are
+/// absent from a sample are deemed absent. Contrary to [`MultinomialNB`](super::multinomial::MultinomialNB),
+/// the absence of a feature is evidence as well, which is why every feature seen while learning is
+/// taken into account when predicting. The probability of a feature given a class is estimated
+/// with Laplace smoothing, i.e. as the number of samples of the class in which it is present plus
+/// `alpha`, over the number of samples of the class plus twice `alpha`.
+///
+/// # Parameters
+///
+/// - `alpha`: The additive smoothing, 1 by default.
+/// - `true_threshold`: The value above which a feature is present, 0 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::bernoulli::BernoulliNB;
+///
+/// let words = |text: &str| -> Observation<f64> {
+///     text.split_whitespace().map(|word| (word.to_string(), 1.0)).collect()
+/// };
+///
+/// let mut model: BernoulliNB<f64> = BernoulliNB::new(None, None);
+/// let documents = [
+///     ("Chinese Beijing Chinese", "yes"),
+///     ("Chinese Chinese Shanghai", "yes"),
+///     ("Chinese Macao", "yes"),
+///     ("Tokyo Japan Chinese", "no"),
+/// ];
+/// for (text, y) in documents {
+///     model.learn_one(&words(text), ClassifierTarget::from(y));
+/// }
+///
+/// let proba = model.predict_proba(&words("Chinese Chinese Chinese Tokyo Japan"));
+/// assert!((proba[&ClassifierTarget::from("yes")] - 0.1911).abs() < 1e-4);
+/// ```
+///
+/// # References
+///
+/// [^1]: C. D. Manning, P. Raghavan and H. Schütze (2008). "Introduction to information
+/// retrieval", chapter 13. Cambridge University Press.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BernoulliNB<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    alpha: F,
+    true_threshold: F,
+    class_counts: HashMap<ClassifierTarget, F>,
+    // Weight of the samples of each class in which each feature is present
+    feature_counts: HashMap<ClassifierTarget, HashMap<String, F>>,
+    vocabulary: HashSet<String>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> BernoulliNB<F> {
+    pub fn new(alpha: Option<F>, true_threshold: Option<F>) -> Self {
+        let alpha = alpha.unwrap_or(F::one());
+        assert!(alpha >= F::zero(), "alpha must be positive");
+        Self {
+            alpha,
+            true_threshold: true_threshold.unwrap_or(F::zero()),
+            class_counts: HashMap::new(),
+            feature_counts: HashMap::new(),
+            vocabulary: HashSet::new(),
+        }
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: &ClassifierTarget, w: F) {
+        *self.class_counts.entry(y.clone()).or_insert(F::zero()) += w;
+        let counts = self.feature_counts.entry(y.clone()).or_default();
+        for (name, value) in x.numeric() {
+            if value > self.true_threshold {
+                *counts.entry(name.clone()).or_insert(F::zero()) += w;
+                self.vocabulary.insert(name.clone());
+            }
+        }
+    }
+    /// Logarithm of the joint probability of each class and the sample, up to a constant.
+    pub fn joint_log_likelihood(&self, x: &Observation<F>) -> Vec<(ClassifierTarget, F)> {
+        let n = self
+            .class_counts
+            .values()
+            .fold(F::zero(), |sum, w| sum + *w);
+        let two = F::one() + F::one();
+        self.class_counts
+            .iter()
+            .map(|(y, w)| {
+                let counts = &self.feature_counts[y];
+                let log_likelihood = self.vocabulary.iter().fold(F::zero(), |sum, name| {
+                    let p = (*counts.get(name).unwrap_or(&F::zero()) + self.alpha)
+                        / (*w + two * self.alpha);
+                    let present = x
+                        .get_numeric(name)
+                        .is_some_and(|value| value > self.true_threshold);
+                    sum + if present { p.ln() } else { (F::one() - p).ln() }
+                });
+                (y.clone(), (*w / n).ln() + log_likelihood)
+            })
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for BernoulliNB<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn_weighted(x, &y, F::one());
+    }
+    /// Posterior probability of each class seen so far. Empty until the model has learned from a
+    /// sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        softmax(self.joint_log_likelihood(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absence_is_evidence() {
+        let mut model = BernoulliNB::new(None, Some(0.5));
+        assert!(model.predict_proba(&Observation::new()).is_empty());
+        for _ in 0..10 {
+            let x = Observation::from([("a".to_string(), 1.0), ("b".to_string(), 0.2)]);
+            model.learn_one(&x, ClassifierTarget::from(0));
+            let x = Observation::from([("b".to_string(), 1.0)]);
+            model.learn_one(&x, ClassifierTarget::from(1));
+        }
+        // Values below the threshold are absent
+        assert_eq!(model.feature_counts[&ClassifierTarget::from(0)].len(), 1);
+        // An empty sample lacks both features, which is more likely for class 0, which never has
+        // b, than for class 1, which always has it
+        let proba = model.predict_proba(&Observation::new());
+        // P(¬a, ¬b | 0) = 1/12 * 11/12, P(¬a, ¬b | 1) = 11/12 * 1/12
+        assert!((proba[&ClassifierTarget::from(0)] - 0.5).abs() < 1e-10);
+        let proba = model.predict_proba(&Observation::from([("a".to_string(), 1.0)]));
+        // P(a, ¬b | 0) = 11/12 * 11/12, P(a, ¬b | 1) = 1/12 * 1/12
+        assert!((proba[&ClassifierTarget::from(0)] - 121.0 / 122.0).abs() < 1e-10);
+    }
+}
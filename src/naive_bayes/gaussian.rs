@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, FeatureValue, Observation};
+use crate::learner::Classifier;
+use crate::naive_bayes::utils::softmax;
+use crate::tree::utils::Gaussian;
+use num::{Float, FromPrimitive};
+
+/// Gaussian naive Bayes.
+///
+/// The numeric features are assumed to follow a normal distribution within each class, whose
+/// mean and variance are updated incrementally. Features which are missing, categorical, or which
+/// haven't been seen with a class yet are ignored, both when learning and when predicting, so that
+/// the samples don't need to all have the same features.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierOutput, ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::gaussian::GaussianNB;
+///
+/// let mut model: GaussianNB<f64> = GaussianNB::new();
+/// let samples = [
+///     ([-1.0, -1.0], 1),
+///     ([-2.0, -1.0], 1),
+///     ([-3.0, -2.0], 1),
+///     ([1.0, 1.0], 2),
+///     ([2.0, 1.0], 2),
+///     ([3.0, 2.0], 2),
+/// ];
+/// for ([a, b], y) in samples {
+///     let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+///     model.learn_one(&x, ClassifierTarget::from(y));
+/// }
+///
+/// let x = Observation::from([("a".to_string(), -0.8), ("b".to_string(), -1.0)]);
+/// let output = ClassifierOutput::Probabilities(model.predict_proba(&x));
+/// assert_eq!(output.get_predicition(), ClassifierTarget::from(1));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaussianNB<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    class_counts: HashMap<ClassifierTarget, F>,
+    gaussians: HashMap<ClassifierTarget, HashMap<String, Gaussian<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> GaussianNB<F> {
+    pub fn new() -> Self {
+        Self {
+            class_counts: HashMap::new(),
+            gaussians: HashMap::new(),
+        }
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: &ClassifierTarget, w: F) {
+        *self.class_counts.entry(y.clone()).or_insert(F::zero()) += w;
+        let gaussians = self.gaussians.entry(y.clone()).or_default();
+        for (name, value) in x.iter() {
+            if let FeatureValue::Numeric(v) = value {
+                gaussians.entry(name.clone()).or_default().update(*v, w);
+            }
+        }
+    }
+    /// Logarithm of the joint probability of each class and the sample, up to a constant.
+    pub fn joint_log_likelihood(&self, x: &Observation<F>) -> Vec<(ClassifierTarget, F)> {
+        let total = self
+            .class_counts
+            .values()
+            .fold(F::zero(), |sum, w| sum + *w);
+        self.class_counts
+            .iter()
+            .map(|(y, w)| {
+                let gaussians = &self.gaussians[y];
+                let log_likelihood = x
+                    .iter()
+                    .filter_map(|(name, value)| match value {
+                        FeatureValue::Numeric(v) => Some(gaussians.get(name)?.log_pdf(*v)),
+                        _ => None,
+                    })
+                    .fold(F::zero(), |sum, l| sum + l);
+                (y.clone(), (*w / total).ln() + log_likelihood)
+            })
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for GaussianNB<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for GaussianNB<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn_weighted(x, &y, F::one());
+    }
+    /// Posterior probability of each class seen so far. Empty until the model has learned from a
+    /// sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        softmax(self.joint_log_likelihood(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(a: FeatureValue<f64>, b: FeatureValue<f64>) -> Observation<f64> {
+        [("a".to_string(), a), ("b".to_string(), b)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_posterior() {
+        let mut model = GaussianNB::new();
+        assert!(model.predict_proba(&Observation::new()).is_empty());
+        for (a, y) in [
+            (-1.0, 0),
+            (1.0, 0),
+            (1.0, 1),
+            (3.0, 1),
+            (1.0, 1),
+            (3.0, 1),
+            (1.0, 1),
+            (3.0, 1),
+        ] {
+            model.learn_one(
+                &Observation::from([("a".to_string(), a)]),
+                ClassifierTarget::from(y),
+            );
+        }
+        let x = Observation::from([("a".to_string(), 1.0)]);
+        let proba = model.predict_proba(&x);
+        // Class 0 has a mean of 0 and a variance of 2, class 1 a mean of 2 and a variance of 1.2
+        let (v0, v1) = (2.0_f64, 1.2);
+        let l0 = (-1.0 / (2.0 * v0)).exp() / v0.sqrt();
+        let l1 = (-1.0 / (2.0 * v1)).exp() / v1.sqrt();
+        let expected = 0.25 * l0 / (0.25 * l0 + 0.75 * l1);
+        assert!((proba[&ClassifierTarget::from(0)] - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_missing_features() {
+        let mut model = GaussianNB::new();
+        for i in 0..100 {
+            let a = i as f64 / 100.0;
+            let y = i % 2;
+            // The second feature is only informative, and only present, for half of the samples
+            let b = if i % 4 < 2 {
+                FeatureValue::Numeric(y as f64 * 10.0)
+            } else {
+                FeatureValue::Missing
+            };
+            model.learn_one(
+                &sample(FeatureValue::Numeric(a), b),
+                ClassifierTarget::from(y),
+            );
+        }
+        let x = sample(FeatureValue::Numeric(0.5), FeatureValue::Numeric(10.0));
+        assert_eq!(model.predict_one(&x), ClassifierTarget::from(1));
+        // Missing and categorical features are ignored
+        let x = sample(
+            FeatureValue::Missing,
+            FeatureValue::Categorical("red".to_string()),
+        );
+        let proba = model.predict_proba(&x);
+        assert!((proba[&ClassifierTarget::from(0)] - 0.5).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod bernoulli;
+pub mod gaussian;
+pub mod multinomial;
+pub mod utils;
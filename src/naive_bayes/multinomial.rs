@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::naive_bayes::utils::softmax;
+use num::{Float, FromPrimitive};
+
+/// Multinomial naive Bayes.
+///
+/// Suited to count features, such as the number of occurrences of each word in a document. The
+/// features are sparse: those which are absent from a sample are counted as zero. The probability
+/// of a feature given a class is estimated with Laplace smoothing, i.e. as its count in the class
+/// plus `alpha`, over the total count of the class plus `alpha` times the number of features.
+/// Features which haven't been seen while learning are ignored when predicting.
+///
+/// # Parameters
+///
+/// - `alpha`: The additive smoothing, 1 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::naive_bayes::multinomial::MultinomialNB;
+///
+/// // Word counts of each document
+/// let bag_of_words = |text: &str| -> Observation<f64> {
+///     let mut counts = std::collections::HashMap::new();
+///     for word in text.split_whitespace() {
+///         *counts.entry(word.to_string()).or_insert(0.0) += 1.0;
+///     }
+///     counts.into()
+/// };
+///
+/// let mut model: MultinomialNB<f64> = MultinomialNB::new(None);
+/// let documents = [
+///     ("Chinese Beijing Chinese", "yes"),
+///     ("Chinese Chinese Shanghai", "yes"),
+///     ("Chinese Macao", "yes"),
+///     ("Tokyo Japan Chinese", "no"),
+/// ];
+/// for (text, y) in documents {
+///     model.learn_one(&bag_of_words(text), ClassifierTarget::from(y));
+/// }
+///
+/// let proba = model.predict_proba(&bag_of_words("Chinese Chinese Chinese Tokyo Japan"));
+/// assert!((proba[&ClassifierTarget::from("yes")] - 0.6897).abs() < 1e-4);
+/// ```
+///
+/// # References
+///
+/// [^1]: C. D. Manning, P. Raghavan and H. Schütze (2008). "Introduction to information
+/// retrieval", chapter 13. Cambridge University Press.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultinomialNB<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    alpha: F,
+    class_counts: HashMap<ClassifierTarget, F>,
+    // Sum of the counts of each feature, and of all the features, per class
+    feature_counts: HashMap<ClassifierTarget, HashMap<String, F>>,
+    class_totals: HashMap<ClassifierTarget, F>,
+    vocabulary: HashSet<String>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> MultinomialNB<F> {
+    pub fn new(alpha: Option<F>) -> Self {
+        let alpha = alpha.unwrap_or(F::one());
+        assert!(alpha >= F::zero(), "alpha must be positive");
+        Self {
+            alpha,
+            class_counts: HashMap::new(),
+            feature_counts: HashMap::new(),
+            class_totals: HashMap::new(),
+            vocabulary: HashSet::new(),
+        }
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: &ClassifierTarget, w: F) {
+        *self.class_counts.entry(y.clone()).or_insert(F::zero()) += w;
+        let counts = self.feature_counts.entry(y.clone()).or_default();
+        let total = self.class_totals.entry(y.clone()).or_insert(F::zero());
+        for (name, count) in x.numeric() {
+            if count <= F::zero() {
+                continue;
+            }
+            *counts.entry(name.clone()).or_insert(F::zero()) += w * count;
+            *total += w * count;
+            self.vocabulary.insert(name.clone());
+        }
+    }
+    /// Logarithm of the joint probability of each class and the sample, up to a constant.
+    pub fn joint_log_likelihood(&self, x: &Observation<F>) -> Vec<(ClassifierTarget, F)> {
+        let n = self
+            .class_counts
+            .values()
+            .fold(F::zero(), |sum, w| sum + *w);
+        let vocabulary_size = F::from(self.vocabulary.len()).unwrap();
+        self.class_counts
+            .iter()
+            .map(|(y, w)| {
+                let counts = &self.feature_counts[y];
+                let denominator = self.class_totals[y] + self.alpha * vocabulary_size;
+                let log_likelihood = x
+                    .numeric()
+                    .filter(|(name, count)| *count > F::zero() && self.vocabulary.contains(*name))
+                    .fold(F::zero(), |sum, (name, count)| {
+                        let numerator = *counts.get(name).unwrap_or(&F::zero()) + self.alpha;
+                        sum + count * (numerator / denominator).ln()
+                    });
+                (y.clone(), (*w / n).ln() + log_likelihood)
+            })
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for MultinomialNB<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn_weighted(x, &y, F::one());
+    }
+    /// Posterior probability of each class seen so far. Empty until the model has learned from a
+    /// sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        softmax(self.joint_log_likelihood(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing() {
+        let mut model = MultinomialNB::new(Some(0.5));
+        assert!(model.predict_proba(&Observation::new()).is_empty());
+        let x = Observation::from([("a".to_string(), 2.0), ("b".to_string(), 0.0)]);
+        model.learn_one(&x, ClassifierTarget::from(0));
+        let x = Observation::from([("b".to_string(), 1.0)]);
+        model.learn_one(&x, ClassifierTarget::from(1));
+        // Zero counts aren't part of the vocabulary
+        assert_eq!(model.vocabulary.len(), 2);
+
+        // P(a | 0) = 2.5 / 3, P(a | 1) = 0.5 / 2
+        let x = Observation::from([("a".to_string(), 1.0), ("c".to_string(), 4.0)]);
+        let jll = model.joint_log_likelihood(&x);
+        let expected = |y| match y {
+            0 => (0.5_f64).ln() + (2.5_f64 / 3.0).ln(),
+            _ => (0.5_f64).ln() + (0.25_f64).ln(),
+        };
+        for (y, l) in jll {
+            let y = if y == ClassifierTarget::from(0) { 0 } else { 1 };
+            assert!((l - expected(y)).abs() < 1e-10);
+        }
+    }
+}
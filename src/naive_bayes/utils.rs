@@ -0,0 +1,18 @@
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities};
+use num::Float;
+
+// Normalize log joint probabilities into probabilities, with the log-sum-exp trick.
+pub(crate) fn softmax<F: Float>(
+    log_joint: Vec<(ClassifierTarget, F)>,
+) -> ClassifierTargetProbabilities<F> {
+    let max = log_joint
+        .iter()
+        .fold(F::neg_infinity(), |max, (_, l)| max.max(*l));
+    let total = log_joint
+        .iter()
+        .fold(F::zero(), |sum, (_, l)| sum + (*l - max).exp());
+    log_joint
+        .into_iter()
+        .map(|(y, l)| (y, (l - max).exp() / total))
+        .collect()
+}
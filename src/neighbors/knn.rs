@@ -0,0 +1,264 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{
+    ClassifierTarget, ClassifierTargetProbabilities, Observation, RegressionTarget,
+};
+use crate::learner::{Classifier, Regressor};
+use crate::neighbors::utils::{vote, vote_weights, Distance, Window};
+use num::{Float, FromPrimitive};
+
+/// Options of the k-nearest neighbors models.
+///
+/// - `n_neighbors`: The number of neighbors which vote, 5 by default.
+/// - `window_size`: The number of most recent samples which are kept, 1000 by default.
+/// - `distance`: How the neighbors are found, see [`Distance`].
+/// - `weighted`: Whether the votes are weighted by the inverse of the distances of the neighbors,
+///   true by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KNNOptions {
+    pub n_neighbors: usize,
+    pub window_size: usize,
+    pub distance: Distance,
+    pub weighted: bool,
+}
+
+impl Default for KNNOptions {
+    fn default() -> Self {
+        Self {
+            n_neighbors: 5,
+            window_size: 1000,
+            distance: Distance::Euclidean,
+            weighted: true,
+        }
+    }
+}
+
+/// k-nearest neighbors classifier.
+///
+/// The most recent samples are kept in a sliding window, so that the model forgets the past and
+/// adapts to concept drift. The predicted probabilities are the votes of the nearest samples of
+/// the window. Predicting has a cost linear in the size of the window.
+///
+/// # Parameters
+///
+/// - `options`: See [`KNNOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::neighbors::knn::KNNClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: KNNClassifier<f64> = KNNClassifier::new(Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..100 {
+///     let x = rng.gen_range(0..100) as f64;
+///     let y = if x < 50.0 { "low" } else { "high" };
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), ClassifierTarget::from(y));
+/// }
+///
+/// let x = Observation::from([("x".to_string(), 42.5)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from("low"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KNNClassifier<F> {
+    options: KNNOptions,
+    window: Window<F, ClassifierTarget>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> KNNClassifier<F> {
+    pub fn new(options: KNNOptions) -> Self {
+        assert!(
+            options.n_neighbors > 0,
+            "n_neighbors must be strictly positive"
+        );
+        Self {
+            options,
+            window: Window::new(options.window_size),
+        }
+    }
+    pub fn options(&self) -> &KNNOptions {
+        &self.options
+    }
+    /// Number of samples in the window.
+    pub fn n_samples(&self) -> usize {
+        self.window.len()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for KNNClassifier<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.window.push(x.clone(), y);
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let neighbors = self
+            .window
+            .nearest(x, self.options.n_neighbors, self.options.distance);
+        vote(&neighbors, self.options.weighted)
+    }
+}
+
+/// k-nearest neighbors regressor.
+///
+/// The regression counterpart of [`KNNClassifier`]: the prediction is the mean of the targets of
+/// the nearest samples of the window, weighted by the inverse of their distances if
+/// `options.weighted`. The prediction is zero until a sample has been seen.
+///
+/// # Parameters
+///
+/// - `options`: See [`KNNOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::learner::Regressor;
+/// use light_river::neighbors::knn::{KNNOptions, KNNRegressor};
+///
+/// let options = KNNOptions {
+///     n_neighbors: 2,
+///     weighted: false,
+///     ..Default::default()
+/// };
+/// let mut model: KNNRegressor<f64> = KNNRegressor::new(options);
+/// for x in [1.0, 2.0, 3.0, 10.0] {
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), 2.0 * x);
+/// }
+///
+/// let x = Observation::from([("x".to_string(), 2.4)]);
+/// assert_eq!(model.predict_one(&x), 5.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KNNRegressor<F> {
+    options: KNNOptions,
+    window: Window<F, F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> KNNRegressor<F> {
+    pub fn new(options: KNNOptions) -> Self {
+        assert!(
+            options.n_neighbors > 0,
+            "n_neighbors must be strictly positive"
+        );
+        Self {
+            options,
+            window: Window::new(options.window_size),
+        }
+    }
+    pub fn options(&self) -> &KNNOptions {
+        &self.options
+    }
+    /// Number of samples in the window.
+    pub fn n_samples(&self) -> usize {
+        self.window.len()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Regressor<F>
+    for KNNRegressor<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.window.push(x.clone(), y);
+    }
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        let neighbors = self
+            .window
+            .nearest(x, self.options.n_neighbors, self.options.distance);
+        if neighbors.is_empty() {
+            return F::zero();
+        }
+        let distances: Vec<F> = neighbors.iter().map(|(d, _, _)| *d).collect();
+        let weights = vote_weights(&distances, self.options.weighted);
+        let (sum, total) = neighbors
+            .iter()
+            .zip(weights)
+            .fold((F::zero(), F::zero()), |(sum, total), ((_, _, y), w)| {
+                (sum + w * **y, total + w)
+            });
+        sum / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(a: f64, b: f64) -> Observation<f64> {
+        Observation::from([("a".to_string(), a), ("b".to_string(), b)])
+    }
+
+    #[test]
+    fn test_weighted_votes() {
+        let options = KNNOptions {
+            n_neighbors: 3,
+            ..Default::default()
+        };
+        let mut weighted = KNNClassifier::new(options);
+        let mut uniform = KNNClassifier::new(KNNOptions {
+            weighted: false,
+            ..options
+        });
+        for (x, y) in [
+            (point(1.0, 0.0), "a"),
+            (point(3.0, 0.0), "b"),
+            (point(4.0, 0.0), "b"),
+        ] {
+            weighted.learn_one(&x, ClassifierTarget::from(y));
+            uniform.learn_one(&x, ClassifierTarget::from(y));
+        }
+        let x = point(1.5, 0.0);
+        // Votes of 2, 2/3 and 2/5 against 1, 1 and 1
+        assert_eq!(weighted.predict_one(&x), ClassifierTarget::from("a"));
+        assert_eq!(uniform.predict_one(&x), ClassifierTarget::from("b"));
+        let proba = uniform.predict_proba(&x);
+        assert!((proba[&ClassifierTarget::from("b")] - 2.0 / 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let mut model = KNNClassifier::new(KNNOptions {
+            window_size: 10,
+            ..Default::default()
+        });
+        // The concept flips after 100 samples, and the window only holds the new one
+        for i in 0..110 {
+            let x = point((i % 7) as f64, (i % 3) as f64);
+            model.learn_one(&x, ClassifierTarget::from(i < 100));
+        }
+        assert_eq!(model.n_samples(), 10);
+        assert_eq!(
+            model.predict_one(&point(3.0, 1.0)),
+            ClassifierTarget::from(false)
+        );
+    }
+
+    #[test]
+    fn test_regressor_distances() {
+        for distance in [Distance::Euclidean, Distance::Manhattan, Distance::Cosine] {
+            let mut model = KNNRegressor::new(KNNOptions {
+                n_neighbors: 1,
+                distance,
+                ..Default::default()
+            });
+            model.learn_one(&point(1.0, 0.0), 1.0);
+            model.learn_one(&point(0.0, 1.0), 2.0);
+            assert_eq!(model.predict_one(&point(3.0, 0.5)), 1.0);
+            assert_eq!(model.predict_one(&point(0.1, 0.9)), 2.0);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let model: KNNRegressor<f64> = KNNRegressor::new(Default::default());
+        assert_eq!(model.predict_one(&point(0.0, 0.0)), 0.0);
+        let model: KNNClassifier<f64> = KNNClassifier::new(Default::default());
+        assert!(model.predict_proba(&point(0.0, 0.0)).is_empty());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod knn;
+pub mod sam_knn;
+pub mod utils;
@@ -0,0 +1,468 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::learner::Classifier;
+use crate::neighbors::utils::{nearest, vote, Distance};
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+type Sample<F> = (Observation<F>, ClassifierTarget);
+
+/// Options of [`SAMKNN`].
+///
+/// - `n_neighbors`: The number of neighbors which vote, 5 by default.
+/// - `distance`: How the neighbors are found, see [`Distance`].
+/// - `weighted`: Whether the votes are weighted by the inverse of the distances of the neighbors,
+///   true by default.
+/// - `max_window_size`: The maximal number of samples of both memories together, 1000 by
+///   default.
+/// - `min_stm_size`: The size below which the short-term memory isn't shrunk, 50 by default. Its
+///   size is also re-evaluated every `min_stm_size` samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SAMKNNOptions {
+    pub n_neighbors: usize,
+    pub distance: Distance,
+    pub weighted: bool,
+    pub max_window_size: usize,
+    pub min_stm_size: usize,
+}
+
+impl Default for SAMKNNOptions {
+    fn default() -> Self {
+        Self {
+            n_neighbors: 5,
+            distance: Distance::Euclidean,
+            weighted: true,
+            max_window_size: 1000,
+            min_stm_size: 50,
+        }
+    }
+}
+
+// Which of the memories predicts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Memory {
+    Short,
+    Long,
+    Combined,
+}
+
+/// Self-adjusting memory k-nearest neighbors classifier.
+///
+/// The samples are kept in two memories:
+///
+/// - The short-term memory (STM) holds the most recent samples, and thus the current concept. Its
+///   size is regularly adapted to the one which minimizes the interleaved test-then-train error
+///   among its halves, its quarters, and so on, so that it shrinks right after a drift.
+/// - The long-term memory (LTM) holds the samples which are evicted from the STM, as long as they
+///   don't contradict it: the samples of the LTM which are close to a sample of the STM but have a
+///   different label are removed. When the memories are full, the LTM is compressed by replacing
+///   the samples of each class by half as many k-means centroids.
+///
+/// Predictions are made with a k-nearest neighbors vote over the STM, the LTM, or both, whichever
+/// has been the most accurate on the samples of the STM. This makes the model robust to all kinds
+/// of drift, including recurring concepts, which are remembered by the LTM.
+///
+/// # Parameters
+///
+/// - `options`: See [`SAMKNNOptions`].
+/// - `seed`: Random seed of the k-means compression.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::neighbors::sam_knn::SAMKNN;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut model: SAMKNN<f64> = SAMKNN::new(Default::default(), Some(42));
+/// // The labels are flipped halfway through the stream
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..400 {
+///     let x = rng.gen_range(0..100) as f64;
+///     let y = (x < 50.0) == (i < 200);
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), ClassifierTarget::from(y));
+/// }
+///
+/// let x = Observation::from([("x".to_string(), 20.0)]);
+/// assert_eq!(model.predict_one(&x), ClassifierTarget::from(false));
+/// ```
+///
+/// # References
+///
+/// [^1]: V. Losing, B. Hammer and H. Wersing (2016). "KNN classifier with self adjusting memory
+/// for heterogeneous concept drift". IEEE 16th international conference on data mining, 291-300.
+#[derive(Clone, Debug)]
+pub struct SAMKNN<F> {
+    options: SAMKNNOptions,
+    stm: VecDeque<Sample<F>>,
+    ltm: Vec<Sample<F>>,
+    // Whether each memory was right on the samples of the STM, oldest first
+    hits: HashMap<Memory, VecDeque<bool>>,
+    n_samples: usize,
+    rng: StdRng,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> SAMKNN<F> {
+    pub fn new(options: SAMKNNOptions, seed: Option<u64>) -> Self {
+        assert!(
+            options.n_neighbors > 0,
+            "n_neighbors must be strictly positive"
+        );
+        assert!(
+            options.min_stm_size > 0 && options.min_stm_size < options.max_window_size,
+            "min_stm_size must lie in (0, max_window_size)"
+        );
+        let rng = rng(seed);
+        Self {
+            options,
+            stm: VecDeque::new(),
+            ltm: Vec::new(),
+            hits: [Memory::Short, Memory::Long, Memory::Combined]
+                .into_iter()
+                .map(|memory| (memory, VecDeque::new()))
+                .collect(),
+            n_samples: 0,
+            rng,
+        }
+    }
+    pub fn options(&self) -> &SAMKNNOptions {
+        &self.options
+    }
+    /// Number of samples in the short-term memory.
+    pub fn stm_size(&self) -> usize {
+        self.stm.len()
+    }
+    /// Number of samples in the long-term memory.
+    pub fn ltm_size(&self) -> usize {
+        self.ltm.len()
+    }
+    fn predict_with(&self, memory: Memory, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let stm = self.stm.iter().map(|(x, y)| (x, y));
+        let ltm = self.ltm.iter().map(|(x, y)| (x, y));
+        let (k, distance) = (self.options.n_neighbors, self.options.distance);
+        let neighbors = match memory {
+            Memory::Short => nearest(stm, x, k, distance),
+            Memory::Long => nearest(ltm, x, k, distance),
+            Memory::Combined => nearest(stm.chain(ltm), x, k, distance),
+        };
+        vote(&neighbors, self.options.weighted)
+    }
+    fn accuracy(&self, memory: Memory) -> usize {
+        self.hits[&memory].iter().filter(|hit| **hit).count()
+    }
+    // Remove from `samples` those which contradict (x, y), i.e. which are among the nearest
+    // neighbors of x, have another label, and are closer than the furthest of the nearest
+    // neighbors of x in `reference` with label y.
+    fn clean<'a>(
+        &self,
+        x: &Observation<F>,
+        y: &ClassifierTarget,
+        reference: impl Iterator<Item = &'a Sample<F>>,
+        samples: &mut Vec<Sample<F>>,
+    ) where
+        F: 'a,
+    {
+        let (k, distance) = (self.options.n_neighbors, self.options.distance);
+        let same_label = reference
+            .filter(|(_, label)| label == y)
+            .map(|(x, y)| (x, y));
+        let Some(threshold) = nearest(same_label, x, k, distance)
+            .last()
+            .map(|(d, _, _)| *d)
+        else {
+            return;
+        };
+        let mut neighbors: Vec<(F, usize)> = samples
+            .iter()
+            .enumerate()
+            .map(|(i, (sample, _))| (distance.get(x, sample), i))
+            .collect();
+        neighbors.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut removed: Vec<usize> = neighbors
+            .into_iter()
+            .take(k)
+            .filter(|(d, i)| *d <= threshold && samples[*i].1 != *y)
+            .map(|(_, i)| i)
+            .collect();
+        removed.sort_unstable();
+        for i in removed.into_iter().rev() {
+            samples.swap_remove(i);
+        }
+    }
+    // Interleaved test-then-train error of the k-nearest neighbors over the most recent `size`
+    // samples of the STM.
+    fn error(&self, size: usize) -> F {
+        let window: Vec<&Sample<F>> = self.stm.iter().skip(self.stm.len() - size).collect();
+        let (k, distance) = (self.options.n_neighbors, self.options.distance);
+        let mut errors = 0;
+        for (i, (x, y)) in window.iter().enumerate().skip(1) {
+            let neighbors = nearest(window[..i].iter().map(|(x, y)| (x, y)), x, k, distance);
+            if most_likely(vote(&neighbors, self.options.weighted)).as_ref() != Some(y) {
+                errors += 1;
+            }
+        }
+        F::from_usize(errors).unwrap() / F::from_usize(size - 1).unwrap()
+    }
+    // Shrink the STM to the size among its halves which has the lowest error, and move the
+    // evicted samples which are consistent with the rest of the STM to the LTM.
+    fn adapt_stm_size(&mut self) {
+        let mut best = (self.error(self.stm.len()), self.stm.len());
+        let mut size = self.stm.len() / 2;
+        while size >= self.options.min_stm_size {
+            let error = self.error(size);
+            if error < best.0 {
+                best = (error, size);
+            }
+            size /= 2;
+        }
+        let n_evicted = self.stm.len() - best.1;
+        if n_evicted == 0 {
+            return;
+        }
+        let mut evicted: Vec<Sample<F>> = self.stm.drain(..n_evicted).collect();
+        for (i, (x, y)) in self.stm.iter().enumerate() {
+            let others = self
+                .stm
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, sample)| sample);
+            self.clean(x, y, others, &mut evicted);
+        }
+        self.ltm.extend(evicted);
+        for hits in self.hits.values_mut() {
+            let excess = hits.len().saturating_sub(self.stm.len());
+            hits.drain(..excess);
+        }
+    }
+    // Replace the samples of each class of the LTM by half as many k-means centroids.
+    fn compress_ltm(&mut self) {
+        let mut classes: HashMap<ClassifierTarget, Vec<Observation<F>>> = HashMap::new();
+        for (x, y) in self.ltm.drain(..) {
+            classes.entry(y).or_default().push(x);
+        }
+        let mut classes: Vec<(ClassifierTarget, Vec<Observation<F>>)> =
+            classes.into_iter().collect();
+        // Sort the classes so that the seed makes the compression reproducible
+        classes.sort_by(|a, b| a.0.cmp(&b.0));
+        for (y, points) in classes {
+            let n_clusters = (points.len() / 2).max(1);
+            for centroid in kmeans(&points, n_clusters, &mut self.rng) {
+                self.ltm.push((centroid, y.clone()));
+            }
+        }
+    }
+    fn enforce_max_size(&mut self) {
+        while self.stm.len() + self.ltm.len() > self.options.max_window_size {
+            if self.stm.len() > self.options.min_stm_size {
+                // Move the oldest sample of the STM to the LTM, unless it is contradicted
+                let (x, y) = self.stm.pop_front().unwrap();
+                let mut evicted = vec![(x, y)];
+                for (x, y) in self.stm.iter() {
+                    self.clean(x, y, self.stm.iter(), &mut evicted);
+                }
+                self.ltm.extend(evicted);
+            }
+            let n_ltm = self.ltm.len();
+            if self.stm.len() + n_ltm > self.options.max_window_size {
+                self.compress_ltm();
+                if self.ltm.len() == n_ltm {
+                    // Only one sample per class is left
+                    self.ltm.clear();
+                }
+            }
+        }
+    }
+}
+
+fn most_likely<F: Float>(proba: ClassifierTargetProbabilities<F>) -> Option<ClassifierTarget> {
+    proba
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(y, _)| y)
+}
+
+// Lloyd's k-means, initialized with randomly chosen points.
+fn kmeans<F: Float + FromPrimitive + AddAssign>(
+    points: &[Observation<F>],
+    n_clusters: usize,
+    rng: &mut StdRng,
+) -> Vec<Observation<F>> {
+    let mut centroids: Vec<Observation<F>> =
+        points.choose_multiple(rng, n_clusters).cloned().collect();
+    for _ in 0..10 {
+        let mut sums: Vec<HashMap<String, F>> = vec![HashMap::new(); centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for point in points {
+            let closest = (0..centroids.len())
+                .map(|c| (Distance::Euclidean.get(point, &centroids[c]), c))
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+                .unwrap()
+                .1;
+            counts[closest] += 1;
+            for (name, value) in point.numeric() {
+                *sums[closest].entry(name.clone()).or_insert(F::zero()) += value;
+            }
+        }
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            if count > 0 {
+                let count = F::from_usize(count).unwrap();
+                *centroid = sum
+                    .into_iter()
+                    .map(|(name, value)| (name, value / count))
+                    .collect();
+            }
+        }
+    }
+    centroids
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for SAMKNN<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        // Keep track of how each memory would have predicted the sample
+        for memory in [Memory::Short, Memory::Long, Memory::Combined] {
+            let hit = most_likely(self.predict_with(memory, x)).as_ref() == Some(&y);
+            let hits = self.hits.get_mut(&memory).unwrap();
+            hits.push_back(hit);
+            if hits.len() > self.stm.len() + 1 {
+                hits.pop_front();
+            }
+        }
+
+        let mut ltm = std::mem::take(&mut self.ltm);
+        self.clean(x, &y, self.stm.iter(), &mut ltm);
+        self.ltm = ltm;
+        self.stm.push_back((x.clone(), y));
+
+        self.n_samples += 1;
+        if self.n_samples.is_multiple_of(self.options.min_stm_size)
+            && self.stm.len() >= 2 * self.options.min_stm_size
+        {
+            self.adapt_stm_size();
+        }
+        self.enforce_max_size();
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let memory = [Memory::Short, Memory::Long, Memory::Combined]
+            .into_iter()
+            .max_by_key(|memory| self.accuracy(*memory))
+            .unwrap();
+        self.predict_with(memory, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> SAMKNNOptions {
+        SAMKNNOptions {
+            max_window_size: 300,
+            min_stm_size: 20,
+            ..Default::default()
+        }
+    }
+
+    // Two features in [0, 1), the label being whether a + b > 1, flipped if `flipped`
+    fn stream(n: usize, flipped: bool, rng: &mut StdRng) -> Vec<(Observation<f64>, bool)> {
+        (0..n)
+            .map(|_| {
+                let (a, b): (f64, f64) = (rng.gen(), rng.gen());
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, (a + b > 1.0) != flipped)
+            })
+            .collect()
+    }
+
+    // Prequential accuracy, and the sizes of the STM along the stream
+    fn evaluate(
+        model: &mut SAMKNN<f64>,
+        samples: Vec<(Observation<f64>, bool)>,
+    ) -> (f64, Vec<usize>) {
+        let n = samples.len() as f64;
+        let (mut correct, mut stm_sizes) = (0.0, vec![]);
+        for (x, y) in samples {
+            let y = ClassifierTarget::from(y);
+            if most_likely(model.predict_proba(&x)) == Some(y.clone()) {
+                correct += 1.0;
+            }
+            model.learn_one(&x, y);
+            stm_sizes.push(model.stm_size());
+        }
+        (correct / n, stm_sizes)
+    }
+
+    #[test]
+    fn test_memory_sizes() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut model = SAMKNN::new(options(), Some(42));
+        for (x, y) in stream(1000, false, &mut rng) {
+            model.learn_one(&x, ClassifierTarget::from(y));
+            assert!(model.stm_size() + model.ltm_size() <= 300);
+        }
+        assert!(model.stm_size() >= 20);
+    }
+
+    #[test]
+    fn test_abrupt_drift() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut model = SAMKNN::new(options(), Some(42));
+        let (before, stm_sizes) = evaluate(&mut model, stream(500, false, &mut rng));
+        assert!(before > 0.9, "{}", before);
+        let (_, drift_stm_sizes) = evaluate(&mut model, stream(100, true, &mut rng));
+        let (after, _) = evaluate(&mut model, stream(400, true, &mut rng));
+        assert!(after > 0.9, "{}", after);
+        // The STM is shrunk to forget the former concept
+        let (size, drift_size) = (stm_sizes[499], *drift_stm_sizes.iter().min().unwrap());
+        assert!(drift_size < size / 2, "{} {}", drift_size, size);
+    }
+
+    #[test]
+    fn test_clean() {
+        let model: SAMKNN<f64> = SAMKNN::new(
+            SAMKNNOptions {
+                n_neighbors: 2,
+                ..options()
+            },
+            Some(42),
+        );
+        let point = |a: f64| Observation::from([("a".to_string(), a)]);
+        let yes = ClassifierTarget::from(true);
+        let no = ClassifierTarget::from(false);
+        let reference = [(point(1.0), yes.clone()), (point(-1.0), yes.clone())];
+        let mut samples = vec![
+            (point(0.5), no.clone()),
+            (point(0.6), yes.clone()),
+            (point(2.0), no.clone()),
+        ];
+        model.clean(&point(0.0), &yes, reference.iter(), &mut samples);
+        // The first sample contradicts the reference within a distance of 1, the last one is
+        // too far
+        assert_eq!(samples.len(), 2);
+        assert!(samples.iter().all(|(x, _)| x.get_numeric("a") != Some(0.5)));
+    }
+
+    #[test]
+    fn test_kmeans() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let points: Vec<Observation<f64>> = (0..40)
+            .map(|i| {
+                let center = if i % 2 == 0 { -5.0 } else { 5.0 };
+                Observation::from([("a".to_string(), center + (i % 5) as f64 * 0.1)])
+            })
+            .collect();
+        let mut centroids: Vec<f64> = kmeans(&points, 2, &mut rng)
+            .iter()
+            .map(|c| c.get_numeric("a").unwrap())
+            .collect();
+        centroids.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((centroids[0] + 4.8).abs() < 1e-10, "{:?}", centroids);
+        assert!((centroids[1] - 5.2).abs() < 1e-10, "{:?}", centroids);
+    }
+}
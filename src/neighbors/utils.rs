@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use num::{Float, FromPrimitive};
+
+/// Distance between two observations, computed on their numeric features. A feature which is
+/// missing from one of the observations is taken to be zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Distance {
+    #[default]
+    Euclidean,
+    Manhattan,
+    /// One minus the cosine similarity, which only depends on the directions of the
+    /// observations. It is 1 if one of them is zero.
+    Cosine,
+}
+
+impl Distance {
+    /// # Examples
+    ///
+    /// ```
+    /// use light_river::common::Observation;
+    /// use light_river::neighbors::utils::Distance;
+    ///
+    /// let a: Observation<f64> = Observation::from([("x".to_string(), 3.0)]);
+    /// let b = Observation::from([("y".to_string(), 4.0)]);
+    /// assert_eq!(Distance::Euclidean.get(&a, &b), 5.0);
+    /// assert_eq!(Distance::Manhattan.get(&a, &b), 7.0);
+    /// assert_eq!(Distance::Cosine.get(&a, &b), 1.0);
+    /// ```
+    pub fn get<F: Float>(&self, a: &Observation<F>, b: &Observation<F>) -> F {
+        // Pairs of values of the features of a, then of the features of b which a lacks
+        let pairs = a
+            .numeric()
+            .map(|(name, value)| (value, b.get_numeric(name).unwrap_or(F::zero())))
+            .chain(
+                b.numeric()
+                    .filter(|(name, _)| a.get_numeric(name).is_none())
+                    .map(|(_, value)| (F::zero(), value)),
+            );
+        match self {
+            Distance::Euclidean => pairs
+                .fold(F::zero(), |sum, (u, v)| sum + (u - v) * (u - v))
+                .sqrt(),
+            Distance::Manhattan => pairs.fold(F::zero(), |sum, (u, v)| sum + (u - v).abs()),
+            Distance::Cosine => {
+                let (dot, norm_a, norm_b) = pairs.fold(
+                    (F::zero(), F::zero(), F::zero()),
+                    |(dot, na, nb), (u, v)| (dot + u * v, na + u * u, nb + v * v),
+                );
+                if norm_a == F::zero() || norm_b == F::zero() {
+                    F::one()
+                } else {
+                    F::one() - dot / (norm_a.sqrt() * norm_b.sqrt())
+                }
+            }
+        }
+    }
+}
+
+// Sliding window of the most recent samples, along with their targets.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Window<F, T> {
+    size: usize,
+    samples: VecDeque<(Observation<F>, T)>,
+}
+
+impl<F: Float, T> Window<F, T> {
+    pub(crate) fn new(size: usize) -> Self {
+        assert!(size > 0, "The window size must be strictly positive");
+        Self {
+            size,
+            samples: VecDeque::with_capacity(size),
+        }
+    }
+    // Add a sample, and return the oldest one if the window was full.
+    pub(crate) fn push(&mut self, x: Observation<F>, y: T) -> Option<(Observation<F>, T)> {
+        let evicted = if self.samples.len() == self.size {
+            self.samples.pop_front()
+        } else {
+            None
+        };
+        self.samples.push_back((x, y));
+        evicted
+    }
+    pub(crate) fn len(&self) -> usize {
+        self.samples.len()
+    }
+    // The k nearest samples to x, closest first, along with their distances.
+    pub(crate) fn nearest(
+        &self,
+        x: &Observation<F>,
+        k: usize,
+        distance: Distance,
+    ) -> Vec<(F, &Observation<F>, &T)> {
+        nearest(self.samples.iter().map(|(x, y)| (x, y)), x, k, distance)
+    }
+}
+
+// The k nearest samples to x among the given ones, closest first, along with their distances.
+pub(crate) fn nearest<'a, F: Float + 'a, T>(
+    samples: impl Iterator<Item = (&'a Observation<F>, &'a T)>,
+    x: &Observation<F>,
+    k: usize,
+    distance: Distance,
+) -> Vec<(F, &'a Observation<F>, &'a T)> {
+    let mut neighbors: Vec<(F, &Observation<F>, &T)> = samples
+        .map(|(sample, y)| (distance.get(x, sample), sample, y))
+        .collect();
+    let by_distance = |a: &(F, _, _), b: &(F, _, _)| a.0.partial_cmp(&b.0).unwrap();
+    if neighbors.len() > k && k > 0 {
+        neighbors.select_nth_unstable_by(k - 1, by_distance);
+        neighbors.truncate(k);
+    }
+    neighbors.sort_by(by_distance);
+    neighbors
+}
+
+// Voting weights of neighbors given their distances: the inverse of the distance if `weighted`,
+// and 1 otherwise. Neighbors at a distance of zero take all the weight.
+pub(crate) fn vote_weights<F: Float + FromPrimitive>(distances: &[F], weighted: bool) -> Vec<F> {
+    if !weighted {
+        return vec![F::one(); distances.len()];
+    }
+    if distances.iter().any(|d| *d == F::zero()) {
+        return distances
+            .iter()
+            .map(|d| if *d == F::zero() { F::one() } else { F::zero() })
+            .collect();
+    }
+    distances.iter().map(|d| d.recip()).collect()
+}
+
+// Class probabilities given by the votes of the neighbors.
+pub(crate) fn vote<F: Float + FromPrimitive>(
+    neighbors: &[(F, &Observation<F>, &ClassifierTarget)],
+    weighted: bool,
+) -> ClassifierTargetProbabilities<F> {
+    let distances: Vec<F> = neighbors.iter().map(|(d, _, _)| *d).collect();
+    let weights = vote_weights(&distances, weighted);
+    let total = weights.iter().fold(F::zero(), |sum, w| sum + *w);
+    let mut proba = ClassifierTargetProbabilities::new();
+    for ((_, _, y), w) in neighbors.iter().zip(weights) {
+        let p = proba.entry((*y).clone()).or_insert(F::zero());
+        *p = *p + w / total;
+    }
+    proba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine() {
+        let a = Observation::from([("x".to_string(), 1.0), ("y".to_string(), 1.0)]);
+        let b = Observation::from([("x".to_string(), 2.0), ("y".to_string(), 2.0)]);
+        let c = Observation::from([("x".to_string(), -1.0), ("y".to_string(), -1.0)]);
+        assert!(Distance::Cosine.get(&a, &b).abs() < 1e-10);
+        assert!((Distance::Cosine.get(&a, &c) - 2.0).abs() < 1e-10);
+        assert_eq!(Distance::Cosine.get(&a, &Observation::new()), 1.0);
+    }
+
+    #[test]
+    fn test_window() {
+        let mut window = Window::new(3);
+        for i in 0..5i32 {
+            let evicted = window.push(Observation::from([("x".to_string(), i as f64)]), i);
+            assert_eq!(evicted.map(|(_, y)| y), (i >= 3).then_some(i - 3));
+        }
+        assert_eq!(window.len(), 3);
+        let x = Observation::from([("x".to_string(), 10.0)]);
+        let nearest: Vec<(f64, i32)> = window
+            .nearest(&x, 2, Distance::Manhattan)
+            .into_iter()
+            .map(|(d, _, y)| (d, *y))
+            .collect();
+        assert_eq!(nearest, [(6.0, 4), (7.0, 3)]);
+    }
+
+    #[test]
+    fn test_vote_weights() {
+        assert_eq!(vote_weights(&[1.0, 4.0], false), [1.0, 1.0]);
+        assert_eq!(vote_weights(&[1.0, 4.0], true), [1.0, 0.25]);
+        assert_eq!(vote_weights(&[0.0, 4.0, 0.0], true), [1.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,50 @@
+use num::{Float, FromPrimitive};
+
+use crate::optim::losses::sigmoid;
+
+/// Activation function of the hidden units of a neural network.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::neural::activations::Activation;
+///
+/// assert_eq!(Activation::ReLU.apply(-2.0f64), 0.0);
+/// assert_eq!(Activation::Sigmoid.apply(0.0f64), 0.5);
+/// // The derivative is given in terms of the output of the activation
+/// assert_eq!(Activation::Tanh.derivative(0.0f64), 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Activation {
+    /// Rectified linear unit, `max(0, x)`.
+    ReLU,
+    /// Logistic function, `1 / (1 + exp(-x))`.
+    Sigmoid,
+    /// Hyperbolic tangent.
+    Tanh,
+    /// The identity, which makes the unit linear.
+    Identity,
+}
+
+impl Activation {
+    pub fn apply<F: Float + FromPrimitive>(&self, x: F) -> F {
+        match self {
+            Activation::ReLU => x.max(F::zero()),
+            Activation::Sigmoid => sigmoid(x),
+            Activation::Tanh => x.tanh(),
+            Activation::Identity => x,
+        }
+    }
+    /// Derivative of the activation, given its output rather than its input, which is what is at
+    /// hand during backpropagation.
+    pub fn derivative<F: Float + FromPrimitive>(&self, output: F) -> F {
+        match self {
+            Activation::ReLU if output > F::zero() => F::one(),
+            Activation::ReLU => F::zero(),
+            Activation::Sigmoid => output * (F::one() - output),
+            Activation::Tanh => F::one() - output * output,
+            Activation::Identity => F::one(),
+        }
+    }
+}
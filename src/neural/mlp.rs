@@ -0,0 +1,399 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, FeatureKey, Observation};
+use crate::learner::{Classifier, Regressor};
+use crate::linear_model::glm::{binary_label, binary_proba};
+use crate::neural::activations::Activation;
+use crate::optim::losses::{BinaryLoss, Log, RegressionLoss, Squared};
+use crate::optim::optimizers::{Optimizer, Weights};
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+// Fully connected network with a single linear output unit. The weights of all the layers are
+// stored together, by index, so that a single optimizer updates them all.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Network<F> {
+    hidden_sizes: Vec<usize>,
+    activation: Activation,
+    seed: u64,
+    // Input features, those of the first observation, which are empty until then
+    features: Option<Vec<String>>,
+    weights: Weights<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Network<F> {
+    fn new(hidden_sizes: &[usize], activation: Activation, seed: Option<u64>) -> Self {
+        assert!(
+            hidden_sizes.iter().all(|size| *size > 0),
+            "hidden_sizes must be strictly positive"
+        );
+        Self {
+            hidden_sizes: hidden_sizes.to_vec(),
+            activation,
+            seed: seed.unwrap_or_else(|| StdRng::from_entropy().gen()),
+            features: None,
+            weights: Weights::new(),
+        }
+    }
+    // Number of units of each layer, from the inputs to the output.
+    fn sizes(&self) -> Vec<usize> {
+        let n_inputs = self.features.as_ref().map_or(0, |features| features.len());
+        let mut sizes = vec![n_inputs];
+        sizes.extend(self.hidden_sizes.iter().copied());
+        sizes.push(1);
+        sizes
+    }
+    // Index of the first weight of each layer. The weights of each unit are followed by its bias.
+    fn offsets(sizes: &[usize]) -> Vec<usize> {
+        sizes
+            .windows(2)
+            .scan(0, |offset, pair| {
+                let start = *offset;
+                *offset += pair[1] * (pair[0] + 1);
+                Some(start)
+            })
+            .collect()
+    }
+    // Random weights, scaled by the size of their layer, and null biases.
+    fn init(&mut self, x: &Observation<F>) {
+        self.features = Some(x.numeric().map(|(name, _)| name.clone()).collect());
+        let sizes = self.sizes();
+        let offsets = Self::offsets(&sizes);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        for (layer, offset) in offsets.iter().enumerate() {
+            let (n_in, n_out) = (sizes[layer], sizes[layer + 1]);
+            let scale = (6.0 / (n_in + n_out) as f64).sqrt();
+            for unit in 0..n_out {
+                for input in 0..n_in {
+                    let w = F::from_f64(rng.gen_range(-scale..=scale)).unwrap();
+                    *self
+                        .weights
+                        .get_mut(FeatureKey::Index(offset + unit * (n_in + 1) + input)) = w;
+                }
+            }
+        }
+    }
+    // Outputs of each layer, from the inputs to the raw output. Missing features count as zeros.
+    fn forward(&self, x: &Observation<F>) -> Vec<Vec<F>> {
+        let Some(features) = &self.features else {
+            return vec![vec![], vec![F::zero()]];
+        };
+        let input: Vec<F> = features
+            .iter()
+            .map(|name| x.get_numeric(name).unwrap_or(F::zero()))
+            .collect();
+        let sizes = self.sizes();
+        let offsets = Self::offsets(&sizes);
+        let mut outputs = vec![input];
+        for (layer, offset) in offsets.iter().enumerate() {
+            let (n_in, n_out) = (sizes[layer], sizes[layer + 1]);
+            let input = outputs.last().unwrap();
+            let output = (0..n_out)
+                .map(|unit| {
+                    let start = offset + unit * (n_in + 1);
+                    let z = input.iter().enumerate().fold(
+                        self.weights.get(FeatureKey::Index(start + n_in)),
+                        |sum, (i, a)| sum + self.weights.get(FeatureKey::Index(start + i)) * *a,
+                    );
+                    if layer + 1 < offsets.len() {
+                        self.activation.apply(z)
+                    } else {
+                        z
+                    }
+                })
+                .collect();
+            outputs.push(output);
+        }
+        outputs
+    }
+    fn predict(&self, x: &Observation<F>) -> F {
+        self.forward(x).last().unwrap()[0]
+    }
+    // Backpropagation of the derivative of the loss with respect to the raw output, given by
+    // `loss_gradient`, after which the optimizer updates the weights.
+    fn learn(
+        &mut self,
+        x: &Observation<F>,
+        optimizer: &mut impl Optimizer<F>,
+        loss_gradient: impl FnOnce(F) -> F,
+    ) {
+        if self.features.is_none() {
+            self.init(x);
+        }
+        let outputs = self.forward(x);
+        let sizes = self.sizes();
+        let offsets = Self::offsets(&sizes);
+        let mut gradient = Vec::new();
+        // Derivative of the loss with respect to the input of each unit of the current layer
+        let mut deltas = vec![loss_gradient(outputs.last().unwrap()[0])];
+        for layer in (0..offsets.len()).rev() {
+            let (n_in, n_out, offset) = (sizes[layer], sizes[layer + 1], offsets[layer]);
+            let input = &outputs[layer];
+            let mut previous = vec![F::zero(); n_in];
+            for (unit, delta) in deltas.iter().enumerate() {
+                let start = offset + unit * (n_in + 1);
+                for (i, a) in input.iter().enumerate() {
+                    gradient.push((FeatureKey::Index(start + i), *delta * *a));
+                    previous[i] += self.weights.get(FeatureKey::Index(start + i)) * *delta;
+                }
+                gradient.push((FeatureKey::Index(start + n_in), *delta));
+            }
+            debug_assert_eq!(deltas.len(), n_out);
+            deltas = previous
+                .into_iter()
+                .zip(input.iter())
+                .map(|(delta, a)| delta * self.activation.derivative(*a))
+                .collect();
+        }
+        optimizer.step(&mut self.weights, &gradient);
+    }
+}
+
+/// Multilayer perceptron for regression, trained with backpropagation one sample at a time.
+///
+/// The network is made of fully connected hidden layers, whose units share the same activation
+/// function, followed by a single linear output unit, which is mapped by the mean function of the
+/// loss. After each sample, the gradient of the loss with respect to all the weights is computed
+/// by backpropagation, and the weights are updated by the optimizer. The hidden layers make it
+/// possible to learn non-linear relations between the features and the target.
+///
+/// The input features are the numeric features of the first observation, and missing features
+/// count as zeros. The weights are initialized when the first observation is learned from, at
+/// random with a scale which depends on the size of their layer, and the predictions are 0 until
+/// then. As with any neural network, the features should be scaled.
+///
+/// # Parameters
+///
+/// - `hidden_sizes`: The number of units of each hidden layer.
+/// - `activation`: The activation function of the hidden units, see [`Activation`].
+/// - `optimizer`: How the weights are updated, see [`crate::optim::optimizers`].
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `seed`: Seed of the random initialization of the weights, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::learner::Regressor;
+/// use light_river::neural::activations::Activation;
+/// use light_river::neural::mlp::MLPRegressor;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::Adam;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let optimizer = Adam::new(0.01, None, None, None);
+/// let mut model = MLPRegressor::new(&[16], Activation::Tanh, optimizer, Squared, Some(42));
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..20000 {
+///     let x: f64 = rng.gen_range(-1.0..1.0);
+///     model.learn_one(&Observation::from([("x".to_string(), x)]), x * x);
+/// }
+/// // A parabola, which a linear model can't fit
+/// for x in [-0.8, 0.0, 0.5] {
+///     let y = model.predict_one(&Observation::from([("x".to_string(), x)]));
+///     assert!((y - x * x).abs() < 0.05);
+/// }
+/// ```
+///
+/// # References
+///
+/// [^1]: D. E. Rumelhart, G. E. Hinton and R. J. Williams (1986). "Learning representations by
+/// back-propagating errors". Nature 323:533-536.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MLPRegressor<F, O, L = Squared> {
+    network: Network<F>,
+    optimizer: O,
+    loss: L,
+}
+
+impl<F, O, L> MLPRegressor<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: RegressionLoss<F>,
+{
+    pub fn new(
+        hidden_sizes: &[usize],
+        activation: Activation,
+        optimizer: O,
+        loss: L,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            network: Network::new(hidden_sizes, activation, seed),
+            optimizer,
+            loss,
+        }
+    }
+    /// The input features, which are known once the first observation has been learned from.
+    pub fn features(&self) -> Option<&[String]> {
+        self.network.features.as_deref()
+    }
+}
+
+impl<F, O, L> Regressor<F> for MLPRegressor<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: RegressionLoss<F>,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: F) {
+        let loss = &self.loss;
+        self.network
+            .learn(x, &mut self.optimizer, |y_pred| loss.gradient(y, y_pred));
+    }
+    fn predict_one(&self, x: &Observation<F>) -> F {
+        self.loss.mean(self.network.predict(x))
+    }
+}
+
+/// Multilayer perceptron for binary classification, trained with backpropagation one sample at a
+/// time.
+///
+/// This is the same network as [`MLPRegressor`], whose output is the raw score of the positive
+/// class, turned into its probability by the mean function of the loss. The labels must be
+/// booleans; multi-class problems can be handled with the reductions of
+/// [`crate::multiclass`].
+///
+/// # Parameters
+///
+/// - `hidden_sizes`: The number of units of each hidden layer.
+/// - `activation`: The activation function of the hidden units, see [`Activation`].
+/// - `optimizer`: How the weights are updated, see [`crate::optim::optimizers`].
+/// - `loss`: The loss to minimize, see [`crate::optim::losses`].
+/// - `seed`: Seed of the random initialization of the weights, for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::neural::activations::Activation;
+/// use light_river::neural::mlp::MLPClassifier;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::Adam;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let optimizer = Adam::new(0.01, None, None, None);
+/// let mut model = MLPClassifier::new(&[8], Activation::ReLU, optimizer, Log, Some(42));
+/// let obs = |a: f64, b: f64| Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+/// // The XOR problem, which isn't linearly separable
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..20000 {
+///     let (a, b) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+///     model.learn_one(&obs(a, b), ClassifierTarget::Bool(a * b > 0.0));
+/// }
+/// assert_eq!(model.predict_one(&obs(0.5, 0.5)), ClassifierTarget::Bool(true));
+/// assert_eq!(model.predict_one(&obs(-0.5, -0.5)), ClassifierTarget::Bool(true));
+/// assert_eq!(model.predict_one(&obs(0.5, -0.5)), ClassifierTarget::Bool(false));
+/// assert_eq!(model.predict_one(&obs(-0.5, 0.5)), ClassifierTarget::Bool(false));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MLPClassifier<F, O, L = Log> {
+    network: Network<F>,
+    optimizer: O,
+    loss: L,
+}
+
+impl<F, O, L> MLPClassifier<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: BinaryLoss<F>,
+{
+    pub fn new(
+        hidden_sizes: &[usize],
+        activation: Activation,
+        optimizer: O,
+        loss: L,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            network: Network::new(hidden_sizes, activation, seed),
+            optimizer,
+            loss,
+        }
+    }
+    /// The input features, which are known once the first observation has been learned from.
+    pub fn features(&self) -> Option<&[String]> {
+        self.network.features.as_deref()
+    }
+}
+
+impl<F, O, L> Classifier<F> for MLPClassifier<F, O, L>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+    L: BinaryLoss<F>,
+{
+    /// # Panics
+    ///
+    /// If the label isn't a boolean.
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        let y = binary_label(y, "MLPClassifier");
+        let loss = &self.loss;
+        self.network
+            .learn(x, &mut self.optimizer, |y_pred| loss.gradient(y, y_pred));
+    }
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        binary_proba(self.loss.mean(self.network.predict(x)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::optimizers::SGD;
+
+    // Records the gradient it is given, without updating the weights
+    struct Record(Vec<(usize, f64)>);
+
+    impl Optimizer<f64> for Record {
+        fn step(&mut self, _weights: &mut Weights<f64>, gradient: &[(FeatureKey<'_>, f64)]) {
+            self.0 = gradient
+                .iter()
+                .map(|(key, g)| match key {
+                    FeatureKey::Index(index) => (*index, *g),
+                    FeatureKey::Name(_) => unreachable!(),
+                })
+                .collect();
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        // ReLU is left out, as it isn't differentiable at 0, where the inputs of dead units may be
+        for activation in [Activation::Tanh, Activation::Sigmoid, Activation::Identity] {
+            let mut network = Network::new(&[3, 2], activation, Some(7));
+            let x = Observation::from([("a".to_string(), 0.7), ("b".to_string(), -1.3)]);
+            let mut record = Record(Vec::new());
+            network.learn(&x, &mut record, |y_pred| Squared.gradient(1.5, y_pred));
+            // (2 + 1) * 3 + (3 + 1) * 2 + (2 + 1) * 1 weights and biases
+            assert_eq!(record.0.len(), 20);
+            // Backpropagation matches finite differences of the loss
+            for (index, g) in record.0 {
+                let loss = |network: &Network<f64>| Squared.loss(1.5, network.predict(&x));
+                let mut shifted = network.clone();
+                *shifted.weights.get_mut(FeatureKey::Index(index)) += 1e-6;
+                let numeric = (loss(&shifted) - loss(&network)) / 1e-6;
+                assert!((numeric - g).abs() < 1e-4, "{} != {}", numeric, g);
+            }
+        }
+    }
+
+    #[test]
+    fn test_features() {
+        let mut model = MLPRegressor::new(&[4], Activation::ReLU, SGD::new(0.01), Squared, None);
+        let x = Observation::from([("a".to_string(), 1.0)]);
+        assert_eq!(model.predict_one(&x), 0.0);
+        assert!(model.features().is_none());
+        model.learn_one(&x, 1.0);
+        // New features are ignored
+        let y = Observation::from([("a".to_string(), 1.0), ("b".to_string(), 5.0)]);
+        assert_eq!(model.features().unwrap(), ["a"]);
+        assert_eq!(model.predict_one(&x), model.predict_one(&y));
+    }
+}
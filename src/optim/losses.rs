@@ -0,0 +1,374 @@
+use num::{Float, FromPrimitive};
+
+// Logistic function, clamped so that it doesn't overflow.
+pub(crate) fn sigmoid<F: Float + FromPrimitive>(x: F) -> F {
+    let bound = F::from_f64(30.0).unwrap();
+    F::one() / (F::one() + (-x.max(-bound).min(bound)).exp())
+}
+
+/// Loss of a binary classifier, `true` being the positive class.
+///
+/// The prediction is the raw score of the model, e.g. a linear combination of the features,
+/// which is turned into the probability of the positive class by [`mean`](Self::mean).
+pub trait BinaryLoss<F: Float + FromPrimitive> {
+    fn loss(&self, y_true: bool, y_pred: F) -> F;
+    /// Derivative of the loss with respect to the raw score.
+    fn gradient(&self, y_true: bool, y_pred: F) -> F;
+    /// Probability of the positive class given the raw score, the logistic function by default.
+    fn mean(&self, y_pred: F) -> F {
+        sigmoid(y_pred)
+    }
+}
+
+/// Loss of a regressor.
+///
+/// The prediction is the raw score of the model, which is turned into the predicted target by
+/// [`mean`](Self::mean), the inverse of the link function.
+pub trait RegressionLoss<F: Float + FromPrimitive> {
+    fn loss(&self, y_true: F, y_pred: F) -> F;
+    /// Derivative of the loss with respect to the raw score.
+    fn gradient(&self, y_true: F, y_pred: F) -> F;
+    /// Predicted target given the raw score, the identity by default.
+    fn mean(&self, y_pred: F) -> F {
+        y_pred
+    }
+}
+
+/// Log loss, also known as the binary cross-entropy.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{BinaryLoss, Log};
+///
+/// let loss = Log;
+/// assert!((loss.loss(true, 0.0) - 2.0f64.ln()).abs() < 1e-10);
+/// assert_eq!(loss.gradient(false, 0.0), 0.5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Log;
+
+impl<F: Float + FromPrimitive> BinaryLoss<F> for Log {
+    fn loss(&self, y_true: bool, y_pred: F) -> F {
+        // ln(1 + exp(-z)), computed so that it doesn't overflow
+        let z = if y_true { y_pred } else { -y_pred };
+        (-z).max(F::zero()) + (-z.abs()).exp().ln_1p()
+    }
+    fn gradient(&self, y_true: bool, y_pred: F) -> F {
+        let y = if y_true { F::one() } else { F::zero() };
+        sigmoid(y_pred) - y
+    }
+}
+
+/// Hinge loss, which is zero when the sample is classified with a margin of at least
+/// `threshold`, and grows linearly otherwise. With a threshold of 1, a linear model trained with
+/// it is a linear support vector machine, and with a threshold of 0 it is a perceptron.
+///
+/// # Parameters
+///
+/// - `threshold`: The margin below which the loss is positive, 1 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{BinaryLoss, Hinge};
+///
+/// let loss = Hinge::new(None);
+/// assert_eq!(loss.loss(true, 0.25), 0.75);
+/// assert_eq!(loss.loss(false, -2.0), 0.0);
+/// assert_eq!(loss.gradient(false, 0.5), 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hinge<F> {
+    threshold: F,
+}
+
+impl<F: Float> Hinge<F> {
+    pub fn new(threshold: Option<F>) -> Self {
+        Self {
+            threshold: threshold.unwrap_or(F::one()),
+        }
+    }
+}
+
+impl<F: Float> Default for Hinge<F> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive> BinaryLoss<F> for Hinge<F> {
+    fn loss(&self, y_true: bool, y_pred: F) -> F {
+        let y = if y_true { F::one() } else { -F::one() };
+        (self.threshold - y * y_pred).max(F::zero())
+    }
+    fn gradient(&self, y_true: bool, y_pred: F) -> F {
+        let y = if y_true { F::one() } else { -F::one() };
+        if y * y_pred < self.threshold {
+            -y
+        } else {
+            F::zero()
+        }
+    }
+}
+
+/// Half the squared error, whose minimizer is the conditional mean of the target.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{RegressionLoss, Squared};
+///
+/// let loss = Squared;
+/// assert_eq!(loss.loss(1.0, 3.0), 2.0);
+/// assert_eq!(loss.gradient(1.0, 3.0), 2.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Squared;
+
+impl<F: Float + FromPrimitive> RegressionLoss<F> for Squared {
+    fn loss(&self, y_true: F, y_pred: F) -> F {
+        let error = y_pred - y_true;
+        error * error / F::from_f64(2.0).unwrap()
+    }
+    fn gradient(&self, y_true: F, y_pred: F) -> F {
+        y_pred - y_true
+    }
+}
+
+/// Absolute error, whose minimizer is the conditional median of the target.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{Absolute, RegressionLoss};
+///
+/// let loss = Absolute;
+/// assert_eq!(loss.loss(1.0, -3.0), 4.0);
+/// assert_eq!(loss.gradient(1.0, -3.0), -1.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Absolute;
+
+impl<F: Float + FromPrimitive> RegressionLoss<F> for Absolute {
+    fn loss(&self, y_true: F, y_pred: F) -> F {
+        (y_pred - y_true).abs()
+    }
+    fn gradient(&self, y_true: F, y_pred: F) -> F {
+        let error = y_pred - y_true;
+        if error == F::zero() {
+            F::zero()
+        } else {
+            error.signum()
+        }
+    }
+}
+
+/// Huber loss, half the squared error for errors below `epsilon`, and the absolute error beyond,
+/// which makes the model robust to outliers.
+///
+/// # Parameters
+///
+/// - `epsilon`: The error beyond which the loss is linear, 0.1 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{Huber, RegressionLoss};
+///
+/// let loss = Huber::new(Some(1.0));
+/// assert_eq!(loss.loss(0.0, 0.5), 0.125);
+/// assert_eq!(loss.loss(0.0, 3.0), 2.5);
+/// assert_eq!(loss.gradient(0.0, 3.0), 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Huber<F> {
+    epsilon: F,
+}
+
+impl<F: Float + FromPrimitive> Huber<F> {
+    pub fn new(epsilon: Option<F>) -> Self {
+        Self {
+            epsilon: epsilon.unwrap_or(F::from_f64(0.1).unwrap()),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive> Default for Huber<F> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive> RegressionLoss<F> for Huber<F> {
+    fn loss(&self, y_true: F, y_pred: F) -> F {
+        let two = F::from_f64(2.0).unwrap();
+        let error = (y_pred - y_true).abs();
+        if error <= self.epsilon {
+            error * error / two
+        } else {
+            self.epsilon * (error - self.epsilon / two)
+        }
+    }
+    fn gradient(&self, y_true: F, y_pred: F) -> F {
+        (y_pred - y_true).max(-self.epsilon).min(self.epsilon)
+    }
+}
+
+/// Quantile loss, also known as the pinball loss, whose minimizer is the `alpha` quantile of the
+/// target. Underestimates are weighted by `alpha` and overestimates by `1 - alpha`.
+///
+/// # Parameters
+///
+/// - `alpha`: The quantile to estimate, between 0 and 1, 0.5 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{Quantile, RegressionLoss};
+///
+/// let loss: Quantile<f64> = Quantile::new(Some(0.9));
+/// assert!((loss.loss(10.0, 8.0) - 1.8).abs() < 1e-10);
+/// assert!((loss.loss(10.0, 12.0) - 0.2).abs() < 1e-10);
+/// assert_eq!(loss.gradient(10.0, 8.0), -0.9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantile<F> {
+    alpha: F,
+}
+
+impl<F: Float + FromPrimitive> Quantile<F> {
+    pub fn new(alpha: Option<F>) -> Self {
+        let alpha = alpha.unwrap_or(F::from_f64(0.5).unwrap());
+        assert!(
+            alpha >= F::zero() && alpha <= F::one(),
+            "alpha must lie in [0, 1]"
+        );
+        Self { alpha }
+    }
+}
+
+impl<F: Float + FromPrimitive> Default for Quantile<F> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<F: Float + FromPrimitive> RegressionLoss<F> for Quantile<F> {
+    fn loss(&self, y_true: F, y_pred: F) -> F {
+        let error = y_true - y_pred;
+        if error >= F::zero() {
+            self.alpha * error
+        } else {
+            (self.alpha - F::one()) * error
+        }
+    }
+    fn gradient(&self, y_true: F, y_pred: F) -> F {
+        match y_true.partial_cmp(&y_pred) {
+            Some(std::cmp::Ordering::Greater) => -self.alpha,
+            Some(std::cmp::Ordering::Less) => F::one() - self.alpha,
+            _ => F::zero(),
+        }
+    }
+}
+
+/// Poisson loss, the negative log-likelihood of a Poisson distribution up to a constant, for
+/// targets which are counts. The raw score is the logarithm of the rate, so the predicted target
+/// is its exponential.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::{Poisson, RegressionLoss};
+///
+/// let loss = Poisson;
+/// assert_eq!(loss.loss(2.0, 0.0), 1.0);
+/// assert_eq!(loss.gradient(2.0, 0.0), -1.0);
+/// assert_eq!(RegressionLoss::<f64>::mean(&loss, 0.0), 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Poisson;
+
+impl<F: Float + FromPrimitive> RegressionLoss<F> for Poisson {
+    fn loss(&self, y_true: F, y_pred: F) -> F {
+        y_pred.exp() - y_true * y_pred
+    }
+    fn gradient(&self, y_true: F, y_pred: F) -> F {
+        y_pred.exp() - y_true
+    }
+    fn mean(&self, y_pred: F) -> F {
+        y_pred.exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREDICTIONS: [f64; 7] = [-3.1, -1.2, -0.45, 0.3, 0.95, 1.7, 4.2];
+
+    // Compare the gradients with central finite differences, away from the kinks
+    fn check_gradient(loss: impl Fn(f64) -> f64, gradient: impl Fn(f64) -> f64) {
+        let h = 1e-6;
+        for y_pred in PREDICTIONS {
+            let numeric = (loss(y_pred + h) - loss(y_pred - h)) / (2.0 * h);
+            assert!(
+                (numeric - gradient(y_pred)).abs() < 1e-5,
+                "{}: {} != {}",
+                y_pred,
+                numeric,
+                gradient(y_pred)
+            );
+        }
+    }
+
+    fn check_binary(loss: impl BinaryLoss<f64>) {
+        for y_true in [false, true] {
+            check_gradient(|p| loss.loss(y_true, p), |p| loss.gradient(y_true, p));
+        }
+    }
+
+    fn check_regression(loss: impl RegressionLoss<f64>) {
+        for y_true in [0.0, 0.5, 2.0] {
+            check_gradient(|p| loss.loss(y_true, p), |p| loss.gradient(y_true, p));
+        }
+    }
+
+    #[test]
+    fn test_gradients() {
+        check_binary(Log);
+        check_binary(Hinge::new(None));
+        check_binary(Hinge::new(Some(0.0)));
+        check_regression(Squared);
+        check_regression(Absolute);
+        check_regression(Huber::new(Some(1.0)));
+        check_regression(Huber::new(None));
+        check_regression(Quantile::new(Some(0.2)));
+        check_regression(Poisson);
+    }
+
+    #[test]
+    fn test_log_loss_is_stable() {
+        assert!((Log.loss(true, -1000.0) - 1000.0f64).abs() < 1e-10);
+        assert!(Log.loss(true, 1000.0f64) >= 0.0);
+        assert!((Log.gradient(false, 1000.0f64) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_quantile_minimizer() {
+        // The mean gradient over a sample vanishes at its quantile
+        let loss = Quantile::new(Some(0.75));
+        let sample: Vec<f64> = (1..=100).map(|i| i as f64).collect();
+        let mean_gradient =
+            |p: f64| sample.iter().map(|y| loss.gradient(*y, p)).sum::<f64>() / 100.0;
+        assert!(mean_gradient(70.5) < 0.0);
+        assert!(mean_gradient(80.5) > 0.0);
+    }
+}
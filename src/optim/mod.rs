@@ -0,0 +1,3 @@
+pub mod losses;
+pub mod optimizers;
+pub mod schedulers;
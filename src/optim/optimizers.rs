@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::FeatureKey;
+use crate::optim::schedulers::{Constant, Scheduler};
+use num::{Float, FromPrimitive};
+
+/// Weights of a model, indexed by the names of the features of dense observations and by the
+/// indices of the features of sparse vectors. Missing weights are zero.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::FeatureKey;
+/// use light_river::optim::optimizers::Weights;
+///
+/// let mut weights: Weights<f64> = Weights::new();
+/// *weights.get_mut(FeatureKey::Name("x")) += 2.0;
+/// *weights.get_mut(FeatureKey::Index(3)) -= 1.0;
+/// assert_eq!(weights.get(FeatureKey::Name("x")), 2.0);
+/// assert_eq!(weights.get(FeatureKey::Name("y")), 0.0);
+/// assert_eq!(weights.dot([(FeatureKey::Name("x"), 3.0), (FeatureKey::Index(3), 1.0)].iter()), 5.0);
+/// assert_eq!(weights.len(), 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Weights<F> {
+    named: HashMap<String, F>,
+    indexed: HashMap<usize, F>,
+}
+
+impl<F: Float> Weights<F> {
+    pub fn new() -> Self {
+        Self {
+            named: HashMap::new(),
+            indexed: HashMap::new(),
+        }
+    }
+    pub fn get(&self, key: FeatureKey) -> F {
+        match key {
+            FeatureKey::Name(name) => self.named.get(name),
+            FeatureKey::Index(index) => self.indexed.get(&index),
+        }
+        .copied()
+        .unwrap_or(F::zero())
+    }
+    /// Mutable reference to a weight, which is inserted with a value of zero if missing.
+    pub fn get_mut(&mut self, key: FeatureKey) -> &mut F {
+        match key {
+            FeatureKey::Name(name) => {
+                if !self.named.contains_key(name) {
+                    self.named.insert(name.to_string(), F::zero());
+                }
+                self.named.get_mut(name).unwrap()
+            }
+            FeatureKey::Index(index) => self.indexed.entry(index).or_insert(F::zero()),
+        }
+    }
+    /// Number of weights which have been set, including those which are zero.
+    pub fn len(&self) -> usize {
+        self.named.len() + self.indexed.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (FeatureKey<'_>, F)> {
+        self.named
+            .iter()
+            .map(|(name, w)| (FeatureKey::Name(name), *w))
+            .chain(
+                self.indexed
+                    .iter()
+                    .map(|(index, w)| (FeatureKey::Index(*index), *w)),
+            )
+    }
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut F> {
+        self.named.values_mut().chain(self.indexed.values_mut())
+    }
+    pub fn dot<'a>(&self, x: impl Iterator<Item = &'a (FeatureKey<'a>, F)>) -> F
+    where
+        F: 'a,
+    {
+        x.fold(F::zero(), |sum, (key, value)| sum + self.get(*key) * *value)
+    }
+}
+
+/// Trait for implementing an optimizer, i.e. a rule which updates the weights of a model given
+/// the gradient of its loss.
+///
+/// The gradient is sparse: only the weights it contains are updated, so that the cost of a step
+/// doesn't depend on the number of weights. The state of the optimizer, e.g. the moments of Adam,
+/// is stored per weight as well.
+pub trait Optimizer<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    /// Update the weights in the opposite direction of the gradient, which is given as the partial
+    /// derivative of the loss with respect to each weight.
+    fn step(&mut self, weights: &mut Weights<F>, gradient: &[(FeatureKey<'_>, F)]);
+}
+
+/// Plain stochastic gradient descent.
+///
+/// # Parameters
+///
+/// - `lr`: The learning rate, which can decay over time with
+///   [`with_scheduler`](Self::with_scheduler), see [`crate::optim::schedulers`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::FeatureKey;
+/// use light_river::optim::optimizers::{Optimizer, Weights, SGD};
+///
+/// let mut optimizer = SGD::new(0.1);
+/// let mut weights = Weights::new();
+/// optimizer.step(&mut weights, &[(FeatureKey::Name("x"), 2.0)]);
+/// assert_eq!(weights.get(FeatureKey::Name("x")), -0.2);
+/// ```
+///
+/// With a learning rate which decays over time:
+///
+/// ```
+/// use light_river::common::FeatureKey;
+/// use light_river::optim::optimizers::{Optimizer, Weights, SGD};
+/// use light_river::optim::schedulers::InverseScaling;
+///
+/// let mut optimizer = SGD::with_scheduler(InverseScaling::new(0.1, Some(1.0)));
+/// let mut weights: Weights<f64> = Weights::new();
+/// optimizer.step(&mut weights, &[(FeatureKey::Name("x"), 2.0)]);
+/// optimizer.step(&mut weights, &[(FeatureKey::Name("x"), 2.0)]);
+/// assert!((weights.get(FeatureKey::Name("x")) + 0.3).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SGD<F, S = Constant<F>> {
+    lr: S,
+    t: usize,
+    marker: PhantomData<F>,
+}
+
+impl<F: Float> SGD<F> {
+    pub fn new(lr: F) -> Self {
+        Self::with_scheduler(Constant::new(lr))
+    }
+}
+
+impl<F: Float, S: Scheduler<F>> SGD<F, S> {
+    pub fn with_scheduler(lr: S) -> Self {
+        Self {
+            lr,
+            t: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F, S> Optimizer<F> for SGD<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: Scheduler<F>,
+{
+    fn step(&mut self, weights: &mut Weights<F>, gradient: &[(FeatureKey<'_>, F)]) {
+        let lr = self.lr.get(self.t);
+        self.t += 1;
+        for (key, g) in gradient {
+            *weights.get_mut(*key) -= lr * *g;
+        }
+    }
+}
+
+/// Adaptive moment estimation.
+///
+/// The weights are updated with running averages of the gradient and of its square, whose bias
+/// towards zero at the start of the stream is corrected.
+///
+/// # Parameters
+///
+/// - `lr`: The learning rate, which can decay over time with
+///   [`with_scheduler`](Self::with_scheduler).
+/// - `beta_1`: The decay of the average of the gradient, 0.9 by default.
+/// - `beta_2`: The decay of the average of the squared gradient, 0.999 by default.
+/// - `eps`: Added to the denominator for numerical stability, 1e-8 by default.
+///
+/// # References
+///
+/// [^1]: D. P. Kingma and J. Ba (2014). "Adam: A method for stochastic optimization".
+/// arXiv:1412.6980.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Adam<F, S = Constant<F>> {
+    lr: S,
+    beta_1: F,
+    beta_2: F,
+    eps: F,
+    m: Weights<F>,
+    v: Weights<F>,
+    t: usize,
+}
+
+impl<F: Float + FromPrimitive> Adam<F> {
+    pub fn new(lr: F, beta_1: Option<F>, beta_2: Option<F>, eps: Option<F>) -> Self {
+        Self::with_scheduler(Constant::new(lr), beta_1, beta_2, eps)
+    }
+}
+
+impl<F: Float + FromPrimitive, S: Scheduler<F>> Adam<F, S> {
+    pub fn with_scheduler(lr: S, beta_1: Option<F>, beta_2: Option<F>, eps: Option<F>) -> Self {
+        Self {
+            lr,
+            beta_1: beta_1.unwrap_or(F::from_f64(0.9).unwrap()),
+            beta_2: beta_2.unwrap_or(F::from_f64(0.999).unwrap()),
+            eps: eps.unwrap_or(F::from_f64(1e-8).unwrap()),
+            m: Weights::new(),
+            v: Weights::new(),
+            t: 0,
+        }
+    }
+}
+
+impl<F, S> Optimizer<F> for Adam<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: Scheduler<F>,
+{
+    fn step(&mut self, weights: &mut Weights<F>, gradient: &[(FeatureKey<'_>, F)]) {
+        let lr = self.lr.get(self.t);
+        self.t += 1;
+        let t = self.t as i32;
+        let lr = lr * (F::one() - self.beta_2.powi(t)).sqrt() / (F::one() - self.beta_1.powi(t));
+        for (key, g) in gradient {
+            let m = self.m.get_mut(*key);
+            *m = self.beta_1 * *m + (F::one() - self.beta_1) * *g;
+            let v = self.v.get_mut(*key);
+            *v = self.beta_2 * *v + (F::one() - self.beta_2) * *g * *g;
+            let step = lr * self.m.get(*key) / (self.v.get(*key).sqrt() + self.eps);
+            *weights.get_mut(*key) -= step;
+        }
+    }
+}
+
+/// Adaptive gradient, which divides the learning rate of each weight by the square root of the sum
+/// of its squared gradients, so that rare features are given larger steps.
+///
+/// # Parameters
+///
+/// - `lr`: The learning rate, which can decay over time with
+///   [`with_scheduler`](Self::with_scheduler).
+/// - `eps`: Added to the denominator for numerical stability, 1e-8 by default.
+///
+/// # References
+///
+/// [^1]: J. Duchi, E. Hazan and Y. Singer (2011). "Adaptive subgradient methods for online
+/// learning and stochastic optimization". Journal of machine learning research 12:2121-2159.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaGrad<F, S = Constant<F>> {
+    lr: S,
+    eps: F,
+    g2: Weights<F>,
+    t: usize,
+}
+
+impl<F: Float + FromPrimitive> AdaGrad<F> {
+    pub fn new(lr: F, eps: Option<F>) -> Self {
+        Self::with_scheduler(Constant::new(lr), eps)
+    }
+}
+
+impl<F: Float + FromPrimitive, S: Scheduler<F>> AdaGrad<F, S> {
+    pub fn with_scheduler(lr: S, eps: Option<F>) -> Self {
+        Self {
+            lr,
+            eps: eps.unwrap_or(F::from_f64(1e-8).unwrap()),
+            g2: Weights::new(),
+            t: 0,
+        }
+    }
+}
+
+impl<F, S> Optimizer<F> for AdaGrad<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: Scheduler<F>,
+{
+    fn step(&mut self, weights: &mut Weights<F>, gradient: &[(FeatureKey<'_>, F)]) {
+        let lr = self.lr.get(self.t);
+        self.t += 1;
+        for (key, g) in gradient {
+            let g2 = self.g2.get_mut(*key);
+            *g2 += *g * *g;
+            let step = lr * *g / (g2.sqrt() + self.eps);
+            *weights.get_mut(*key) -= step;
+        }
+    }
+}
+
+/// Root mean square propagation, which divides the learning rate of each weight by the square
+/// root of a running average of its squared gradients.
+///
+/// # Parameters
+///
+/// - `lr`: The learning rate, which can decay over time with
+///   [`with_scheduler`](Self::with_scheduler).
+/// - `rho`: The decay of the running average, 0.9 by default.
+/// - `eps`: Added to the denominator for numerical stability, 1e-8 by default.
+///
+/// # References
+///
+/// [^1]: T. Tieleman and G. Hinton (2012). "Lecture 6.5 - rmsprop: Divide the gradient by a
+/// running average of its recent magnitude". COURSERA: Neural networks for machine learning.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RMSProp<F, S = Constant<F>> {
+    lr: S,
+    rho: F,
+    eps: F,
+    g2: Weights<F>,
+    t: usize,
+}
+
+impl<F: Float + FromPrimitive> RMSProp<F> {
+    pub fn new(lr: F, rho: Option<F>, eps: Option<F>) -> Self {
+        Self::with_scheduler(Constant::new(lr), rho, eps)
+    }
+}
+
+impl<F: Float + FromPrimitive, S: Scheduler<F>> RMSProp<F, S> {
+    pub fn with_scheduler(lr: S, rho: Option<F>, eps: Option<F>) -> Self {
+        Self {
+            lr,
+            rho: rho.unwrap_or(F::from_f64(0.9).unwrap()),
+            eps: eps.unwrap_or(F::from_f64(1e-8).unwrap()),
+            g2: Weights::new(),
+            t: 0,
+        }
+    }
+}
+
+impl<F, S> Optimizer<F> for RMSProp<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: Scheduler<F>,
+{
+    fn step(&mut self, weights: &mut Weights<F>, gradient: &[(FeatureKey<'_>, F)]) {
+        let lr = self.lr.get(self.t);
+        self.t += 1;
+        for (key, g) in gradient {
+            let g2 = self.g2.get_mut(*key);
+            *g2 = self.rho * *g2 + (F::one() - self.rho) * *g * *g;
+            let step = lr * *g / (g2.sqrt() + self.eps);
+            *weights.get_mut(*key) -= step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::schedulers::{InverseScaling, Warmup};
+
+    // Minimize (w_a - 3)^2 + (w_b + 1)^2 and return the final weights
+    fn minimize(optimizer: &mut impl Optimizer<f64>, n_steps: usize) -> (f64, f64) {
+        let (a, b) = (FeatureKey::Name("a"), FeatureKey::Index(0));
+        let mut weights = Weights::new();
+        for _ in 0..n_steps {
+            let gradient = [
+                (a, 2.0 * (weights.get(a) - 3.0)),
+                (b, 2.0 * (weights.get(b) + 1.0)),
+            ];
+            optimizer.step(&mut weights, &gradient);
+        }
+        (weights.get(a), weights.get(b))
+    }
+
+    fn assert_converged((a, b): (f64, f64)) {
+        assert!((a - 3.0).abs() < 1e-2, "{}", a);
+        assert!((b + 1.0).abs() < 1e-2, "{}", b);
+    }
+
+    #[test]
+    fn test_sgd() {
+        assert_converged(minimize(&mut SGD::new(0.1), 100));
+    }
+
+    #[test]
+    fn test_adam() {
+        assert_converged(minimize(&mut Adam::new(0.1, None, None, None), 1000));
+    }
+
+    #[test]
+    fn test_adagrad() {
+        assert_converged(minimize(&mut AdaGrad::new(1.0, None), 1000));
+    }
+
+    #[test]
+    fn test_rmsprop() {
+        assert_converged(minimize(&mut RMSProp::new(0.01, None, None), 1000));
+    }
+
+    #[test]
+    fn test_scheduler() {
+        // With a constant learning rate of 1 the iterates jump back and forth around the
+        // minimum, a decaying one makes them converge
+        assert_eq!(minimize(&mut SGD::new(1.0), 100), (0.0, 0.0));
+        let mut decaying = SGD::with_scheduler(InverseScaling::new(1.0, Some(1.0)));
+        assert_converged(minimize(&mut decaying, 100));
+        let mut warm = Adam::with_scheduler(Warmup::new(Constant::new(0.1), 10), None, None, None);
+        assert_converged(minimize(&mut warm, 1000));
+    }
+
+    #[test]
+    fn test_adam_first_step() {
+        // The bias correction makes the first step of size lr in the direction of the gradient
+        let mut weights = Weights::new();
+        let mut optimizer = Adam::new(0.1, None, None, None);
+        optimizer.step(&mut weights, &[(FeatureKey::Index(2), 50.0)]);
+        assert!((weights.get(FeatureKey::Index(2)) + 0.1).abs() < 1e-6);
+    }
+}
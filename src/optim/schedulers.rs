@@ -0,0 +1,264 @@
+use std::f64::consts::PI;
+
+use crate::optim::losses::BinaryLoss;
+use num::{Float, FromPrimitive};
+
+/// Trait for implementing a learning rate scheduler, i.e. the learning rate an optimizer uses as a
+/// function of the number of steps it has already made.
+///
+/// The schedulers are plugged into the optimizers of [`crate::optim::optimizers`] with their
+/// `with_scheduler` constructor, so that the learning rate of a model decays over the lifetime of
+/// the stream.
+pub trait Scheduler<F> {
+    /// Learning rate of step `t`, starting at 0.
+    fn get(&self, t: usize) -> F;
+}
+
+/// The same learning rate at every step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constant<F> {
+    lr: F,
+}
+
+impl<F: Float> Constant<F> {
+    pub fn new(lr: F) -> Self {
+        Self { lr }
+    }
+}
+
+impl<F: Float> Scheduler<F> for Constant<F> {
+    fn get(&self, _t: usize) -> F {
+        self.lr
+    }
+}
+
+/// Learning rate decaying as `lr / (t + 1) ^ power`.
+///
+/// # Parameters
+///
+/// - `lr`: The initial learning rate.
+/// - `power`: How fast the learning rate decays, 0.5 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::schedulers::{InverseScaling, Scheduler};
+///
+/// let scheduler = InverseScaling::new(0.1, None);
+/// assert_eq!(scheduler.get(0), 0.1);
+/// assert_eq!(scheduler.get(3), 0.05);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InverseScaling<F> {
+    lr: F,
+    power: F,
+}
+
+impl<F: Float + FromPrimitive> InverseScaling<F> {
+    pub fn new(lr: F, power: Option<F>) -> Self {
+        Self {
+            lr,
+            power: power.unwrap_or(F::from_f64(0.5).unwrap()),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive> Scheduler<F> for InverseScaling<F> {
+    fn get(&self, t: usize) -> F {
+        self.lr / F::from_usize(t + 1).unwrap().powf(self.power)
+    }
+}
+
+/// The "optimal" learning rate of scikit-learn, `1 / (alpha * (t0 + t))`, where `t0` is set with
+/// Léon Bottou's heuristic, given the loss of the model and the L2 regularization `alpha`.
+///
+/// # Parameters
+///
+/// - `loss`: The loss minimized by the model.
+/// - `alpha`: The strength of the L2 regularization, 1e-4 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::losses::Hinge;
+/// use light_river::optim::schedulers::{Optimal, Scheduler};
+///
+/// let scheduler = Optimal::new(&Hinge::new(None), Some(0.01));
+/// // The initial learning rate is 1 / sqrt(sqrt(alpha))
+/// assert!((scheduler.get(0) - 10.0f64.sqrt()).abs() < 1e-10);
+/// assert!(scheduler.get(100) < scheduler.get(0));
+/// ```
+///
+/// # References
+///
+/// [^1]: L. Bottou (2012). "Stochastic gradient descent tricks". Neural networks: Tricks of the
+/// trade, 421-436.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Optimal<F> {
+    alpha: F,
+    t0: F,
+}
+
+impl<F: Float + FromPrimitive> Optimal<F> {
+    pub fn new(loss: &impl BinaryLoss<F>, alpha: Option<F>) -> Self {
+        let alpha = alpha.unwrap_or(F::from_f64(1e-4).unwrap());
+        assert!(alpha > F::zero(), "alpha must be strictly positive");
+        // Typical size of the weights, and the learning rate for which the first step is of
+        // that size
+        let typw = alpha.sqrt().recip().sqrt();
+        let eta0 = typw / loss.gradient(true, -typw).abs().max(F::one());
+        Self {
+            alpha,
+            t0: (eta0 * alpha).recip(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive> Scheduler<F> for Optimal<F> {
+    fn get(&self, t: usize) -> F {
+        (self.alpha * (self.t0 + F::from_usize(t).unwrap())).recip()
+    }
+}
+
+/// Cosine annealing with warm restarts: the learning rate follows half a cosine wave from `lr`
+/// down to `min_lr` over `period` steps, after which it restarts from `lr`.
+///
+/// # Parameters
+///
+/// - `lr`: The maximal learning rate.
+/// - `period`: The number of steps between two restarts.
+/// - `min_lr`: The minimal learning rate, 0 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::schedulers::{Cosine, Scheduler};
+///
+/// let scheduler: Cosine<f64> = Cosine::new(0.1, 100, None);
+/// assert_eq!(scheduler.get(0), 0.1);
+/// assert!((scheduler.get(50) - 0.05).abs() < 1e-10);
+/// assert_eq!(scheduler.get(100), 0.1);
+/// ```
+///
+/// # References
+///
+/// [^1]: I. Loshchilov and F. Hutter (2016). "SGDR: Stochastic gradient descent with warm
+/// restarts". arXiv:1608.03983.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cosine<F> {
+    lr: F,
+    period: usize,
+    min_lr: F,
+}
+
+impl<F: Float + FromPrimitive> Cosine<F> {
+    pub fn new(lr: F, period: usize, min_lr: Option<F>) -> Self {
+        assert!(period > 0, "The period must be strictly positive");
+        Self {
+            lr,
+            period,
+            min_lr: min_lr.unwrap_or(F::zero()),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive> Scheduler<F> for Cosine<F> {
+    fn get(&self, t: usize) -> F {
+        let progress =
+            F::from_usize(t % self.period).unwrap() / F::from_usize(self.period).unwrap();
+        let cosine = (F::from_f64(PI).unwrap() * progress).cos();
+        self.min_lr + (self.lr - self.min_lr) * (F::one() + cosine) / F::from_f64(2.0).unwrap()
+    }
+}
+
+/// Linear warmup of another scheduler: the learning rate grows linearly up to the initial one of
+/// the wrapped scheduler over the first `n_steps` steps, which are then followed by the wrapped
+/// schedule. This avoids large steps while the state of adaptive optimizers is still unreliable.
+///
+/// # Parameters
+///
+/// - `scheduler`: The schedule which follows the warmup.
+/// - `n_steps`: The number of warmup steps.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::optim::schedulers::{InverseScaling, Scheduler, Warmup};
+///
+/// let scheduler = Warmup::new(InverseScaling::new(0.1, None), 4);
+/// assert_eq!(scheduler.get(0), 0.025);
+/// assert_eq!(scheduler.get(3), 0.1);
+/// assert_eq!(scheduler.get(7), 0.05);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warmup<S> {
+    scheduler: S,
+    n_steps: usize,
+}
+
+impl<S> Warmup<S> {
+    pub fn new(scheduler: S, n_steps: usize) -> Self {
+        Self { scheduler, n_steps }
+    }
+}
+
+impl<F: Float + FromPrimitive, S: Scheduler<F>> Scheduler<F> for Warmup<S> {
+    fn get(&self, t: usize) -> F {
+        if t < self.n_steps {
+            self.scheduler.get(0) * F::from_usize(t + 1).unwrap()
+                / F::from_usize(self.n_steps).unwrap()
+        } else {
+            self.scheduler.get(t - self.n_steps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::losses::Log;
+
+    #[test]
+    fn test_constant() {
+        let scheduler = Constant::new(0.3);
+        assert_eq!(scheduler.get(0), 0.3);
+        assert_eq!(scheduler.get(1000), 0.3);
+    }
+
+    #[test]
+    fn test_optimal() {
+        // Same t0 as scikit-learn's SGDClassifier with the log loss and alpha = 1e-4
+        let scheduler: Optimal<f64> = Optimal::new(&Log, None);
+        assert!((scheduler.t0 - 1000.0f64).abs() < 1e-6, "{}", scheduler.t0);
+        assert!((scheduler.get(9000) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_decreasing() {
+        let schedulers: [Box<dyn Scheduler<f64>>; 3] = [
+            Box::new(InverseScaling::new(1.0, Some(0.25))),
+            Box::new(Optimal::new(&Log, Some(0.1))),
+            Box::new(Cosine::new(1.0, 50, Some(0.1))),
+        ];
+        for scheduler in schedulers.iter() {
+            for t in 0..49 {
+                assert!(scheduler.get(t + 1) < scheduler.get(t));
+            }
+        }
+    }
+
+    #[test]
+    fn test_warmup() {
+        let scheduler = Warmup::new(Constant::new(1.0), 10);
+        for t in 0..9 {
+            assert!(scheduler.get(t + 1) > scheduler.get(t));
+        }
+        assert_eq!(scheduler.get(9), 1.0);
+        assert_eq!(scheduler.get(100), 1.0);
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use crate::sketch::p2::P2Quantile;
+use crate::stats::mean::Mean;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// How the missing values of a feature are imputed by a [`StatImputer`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Imputation<F> {
+    /// Running mean of the numeric values.
+    Mean,
+    /// Running median of the numeric values, estimated with the P² algorithm.
+    Median,
+    /// Most frequent value, numeric or categorical. Every distinct value is counted, which suits
+    /// features with few of them.
+    Mode,
+    /// Always the same value.
+    Constant(FeatureValue<F>),
+}
+
+// Running statistic of a feature, from which its missing values are imputed.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Stat<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    Mean(Mean<F>),
+    Median { quantile: P2Quantile<F>, n: usize },
+    Mode(Vec<(FeatureValue<F>, usize)>),
+    Constant(FeatureValue<F>),
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Stat<F> {
+    fn new(imputation: &Imputation<F>) -> Self {
+        match imputation {
+            Imputation::Mean => Stat::Mean(Mean::new()),
+            Imputation::Median => Stat::Median {
+                quantile: P2Quantile::new(F::from_f64(0.5).unwrap()),
+                n: 0,
+            },
+            Imputation::Mode => Stat::Mode(Vec::new()),
+            Imputation::Constant(value) => Stat::Constant(value.clone()),
+        }
+    }
+    fn update(&mut self, value: &FeatureValue<F>) {
+        match (self, value) {
+            (Stat::Mean(mean), FeatureValue::Numeric(x)) => mean.update(*x),
+            (Stat::Median { quantile, n }, FeatureValue::Numeric(x)) => {
+                quantile.update(*x);
+                *n += 1;
+            }
+            (Stat::Mode(counts), _) => match counts.iter_mut().find(|(v, _)| v == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value.clone(), 1)),
+            },
+            _ => {}
+        }
+    }
+    // `None` until a value has been seen.
+    fn get(&self) -> Option<FeatureValue<F>> {
+        match self {
+            Stat::Mean(mean) if mean.n() > F::zero() => Some(FeatureValue::Numeric(mean.get())),
+            Stat::Median { quantile, n } if *n > 0 => Some(FeatureValue::Numeric(quantile.get())),
+            // The earliest value wins ties
+            Stat::Mode(counts) => counts
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map(|(value, _)| value.clone()),
+            Stat::Constant(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Imputer of the missing values of the features with running statistics.
+///
+/// Each feature is imputed with its own strategy, which is given by name, or else with the
+/// default strategy, if any. Features with neither are left missing. The statistics are learned
+/// from the values which aren't missing, and missing values are left as they are until a value
+/// of their feature has been learned, except for constants.
+///
+/// # Parameters
+///
+/// - `strategies`: The strategy of some of the features.
+/// - `default`: The strategy of the other features.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::learner::Transformer;
+/// use light_river::preprocessing::imputer::{Imputation, StatImputer};
+///
+/// let mut imputer = StatImputer::new(
+///     vec![("city".to_string(), Imputation::Mode)],
+///     Some(Imputation::Mean),
+/// );
+/// for (age, city) in [(20.0, "Paris"), (40.0, "Lyon"), (60.0, "Paris")] {
+///     let mut x: Observation<f64> = Observation::new();
+///     x.insert("age", FeatureValue::Numeric(age));
+///     x.insert("city", FeatureValue::Categorical(city.to_string()));
+///     imputer.learn_one(&x);
+/// }
+///
+/// let mut x = Observation::new();
+/// x.insert("age", FeatureValue::Missing);
+/// x.insert("city", FeatureValue::Missing);
+/// let imputed = imputer.transform_one(&x);
+/// assert_eq!(imputed.get_numeric("age"), Some(40.0));
+/// assert_eq!(
+///     imputed.get("city"),
+///     Some(&FeatureValue::Categorical("Paris".to_string()))
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatImputer<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    strategies: HashMap<String, Imputation<F>>,
+    default: Option<Imputation<F>>,
+    stats: HashMap<String, Stat<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> StatImputer<F> {
+    pub fn new(strategies: Vec<(String, Imputation<F>)>, default: Option<Imputation<F>>) -> Self {
+        let strategies: HashMap<String, Imputation<F>> = strategies.into_iter().collect();
+        // Constants need no learning
+        let stats = strategies
+            .iter()
+            .filter(|(_, imputation)| matches!(imputation, Imputation::Constant(_)))
+            .map(|(name, imputation)| (name.clone(), Stat::new(imputation)))
+            .collect();
+        Self {
+            strategies,
+            default,
+            stats,
+        }
+    }
+    /// The value with which the missing values of a feature are currently imputed.
+    pub fn imputed_value(&self, feature: &str) -> Option<FeatureValue<F>> {
+        match self.stats.get(feature) {
+            Some(stat) => stat.get(),
+            None if self.strategies.contains_key(feature) => None,
+            None => match self.default {
+                Some(Imputation::Constant(ref value)) => Some(value.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for StatImputer<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        for (name, value) in x.iter().filter(|(_, value)| !value.is_missing()) {
+            if !self.stats.contains_key(name) {
+                let Some(imputation) = self.strategies.get(name).or(self.default.as_ref()) else {
+                    continue;
+                };
+                self.stats.insert(name.clone(), Stat::new(imputation));
+            }
+            self.stats.get_mut(name).unwrap().update(value);
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    FeatureValue::Missing => {
+                        self.imputed_value(name).unwrap_or(FeatureValue::Missing)
+                    }
+                    _ => value.clone(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(features: &[(&str, FeatureValue<f64>)]) -> Observation<f64> {
+        features
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let mut imputer = StatImputer::new(
+            vec![
+                ("median".to_string(), Imputation::Median),
+                (
+                    "constant".to_string(),
+                    Imputation::Constant(FeatureValue::Numeric(-1.0)),
+                ),
+                ("mode".to_string(), Imputation::Mode),
+            ],
+            None,
+        );
+        let missing = observation(&[
+            ("median", FeatureValue::Missing),
+            ("constant", FeatureValue::Missing),
+            ("mode", FeatureValue::Missing),
+            ("other", FeatureValue::Missing),
+        ]);
+        // Only the constant is known before learning
+        let imputed = imputer.transform_one(&missing);
+        assert_eq!(imputed.get_numeric("constant"), Some(-1.0));
+        assert!(imputed.get("median").unwrap().is_missing());
+
+        for i in 0..101 {
+            imputer.learn_one(&observation(&[
+                ("median", FeatureValue::Numeric((i * i) as f64)),
+                ("mode", FeatureValue::Numeric((i % 3).min(1) as f64)),
+                ("other", FeatureValue::Numeric(1.0)),
+            ]));
+        }
+        let imputed = imputer.transform_one(&missing);
+        assert!((imputed.get_numeric("median").unwrap() - 2500.0).abs() < 100.0);
+        assert_eq!(imputed.get_numeric("constant"), Some(-1.0));
+        assert_eq!(imputed.get_numeric("mode"), Some(1.0));
+        // There is no default strategy
+        assert!(imputed.get("other").unwrap().is_missing());
+    }
+
+    #[test]
+    fn test_default_constant() {
+        let imputer = StatImputer::new(
+            vec![],
+            Some(Imputation::Constant(FeatureValue::Categorical(
+                "unknown".to_string(),
+            ))),
+        );
+        let imputed = imputer.transform_one(&observation(&[
+            ("city", FeatureValue::Missing),
+            ("age", FeatureValue::Numeric(3.0)),
+        ]));
+        assert_eq!(
+            imputed.get("city").unwrap().as_categorical(),
+            Some("unknown")
+        );
+        assert_eq!(imputed.get_numeric("age"), Some(3.0));
+    }
+}
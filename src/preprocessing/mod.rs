@@ -0,0 +1,3 @@
+pub mod imputer;
+pub mod one_hot;
+pub mod target_encoder;
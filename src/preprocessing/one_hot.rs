@@ -0,0 +1,207 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::Transformer;
+use num::{Float, FromPrimitive};
+
+/// Options of a [`OneHotEncoder`].
+///
+/// # Parameters
+///
+/// - `max_categories`: The maximum number of categories of each feature, unbounded by default.
+///   Once a feature has that many, its new categories share the indicator `{feature}_other`.
+/// - `drop_zeros`: Whether only the indicators of the current categories are output, `false` by
+///   default. Otherwise the indicators of all the known categories are, with a value of zero for
+///   the absent ones.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneHotEncoderOptions {
+    pub max_categories: Option<usize>,
+    pub drop_zeros: bool,
+}
+
+/// One-hot encoder of the categorical features, whose categories are discovered on the stream.
+///
+/// Each categorical feature is replaced by an indicator feature `{feature}_{category}` per known
+/// category, which is one for the category of the observation and zero for the others. The
+/// categories are learned with `learn_one`, so that a category which hasn't been learned yet has
+/// no indicator. Numeric features are left as they are, and a missing categorical feature has no
+/// category.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::learner::Transformer;
+/// use light_river::preprocessing::one_hot::{OneHotEncoder, OneHotEncoderOptions};
+///
+/// let mut encoder = OneHotEncoder::new(OneHotEncoderOptions::default());
+/// for city in ["Paris", "Lyon", "Paris"] {
+///     let mut x: Observation<f64> = Observation::new();
+///     x.insert("city", FeatureValue::Categorical(city.to_string()));
+///     encoder.learn_one(&x);
+/// }
+///
+/// let mut x: Observation<f64> = Observation::new();
+/// x.insert("city", FeatureValue::Categorical("Lyon".to_string()));
+/// x.insert("age", FeatureValue::Numeric(42.0));
+/// let encoded = encoder.transform_one(&x);
+/// let names: Vec<&String> = encoded.keys().collect();
+/// assert_eq!(names, ["age", "city_Lyon", "city_Paris"]);
+/// assert_eq!(encoded.get_numeric("city_Lyon"), Some(1.0));
+/// assert_eq!(encoded.get_numeric("city_Paris"), Some(0.0));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneHotEncoder {
+    options: OneHotEncoderOptions,
+    categories: HashMap<String, BTreeSet<String>>,
+}
+
+impl OneHotEncoder {
+    pub fn new(options: OneHotEncoderOptions) -> Self {
+        assert!(
+            options.max_categories.is_none_or(|n| n > 0),
+            "max_categories must be strictly positive"
+        );
+        Self {
+            options,
+            categories: HashMap::new(),
+        }
+    }
+    /// The known categories of a feature, sorted.
+    pub fn categories(&self, feature: &str) -> Option<&BTreeSet<String>> {
+        self.categories.get(feature)
+    }
+    fn is_full(&self, categories: &BTreeSet<String>) -> bool {
+        self.options
+            .max_categories
+            .is_some_and(|n| categories.len() >= n)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Transformer<F>
+    for OneHotEncoder
+{
+    fn learn_one(&mut self, x: &Observation<F>) {
+        for (name, value) in x.iter() {
+            if let FeatureValue::Categorical(category) = value {
+                let max_categories = self.options.max_categories;
+                let categories = self.categories.entry(name.clone()).or_default();
+                if max_categories.is_none_or(|n| categories.len() < n) {
+                    categories.insert(category.clone());
+                }
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        let mut encoded = Observation::new();
+        for (name, value) in x.iter() {
+            let category = match value {
+                FeatureValue::Categorical(category) => Some(category),
+                FeatureValue::Missing if self.categories.contains_key(name) => None,
+                _ => {
+                    encoded.insert(name.clone(), value.clone());
+                    continue;
+                }
+            };
+            let Some(categories) = self.categories.get(name) else {
+                continue;
+            };
+            if !self.options.drop_zeros {
+                for known in categories.iter() {
+                    encoded.insert(
+                        format!("{}_{}", name, known),
+                        FeatureValue::Numeric(F::zero()),
+                    );
+                }
+                if self.is_full(categories) {
+                    encoded.insert(format!("{}_other", name), FeatureValue::Numeric(F::zero()));
+                }
+            }
+            match category {
+                Some(category) if categories.contains(category) => {
+                    encoded.insert(
+                        format!("{}_{}", name, category),
+                        FeatureValue::Numeric(F::one()),
+                    );
+                }
+                Some(_) if self.is_full(categories) => {
+                    encoded.insert(format!("{}_other", name), FeatureValue::Numeric(F::one()));
+                }
+                _ => {}
+            }
+        }
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(color: Option<&str>) -> Observation<f64> {
+        let mut x = Observation::new();
+        x.insert(
+            "color",
+            color.map_or(FeatureValue::Missing, |c| {
+                FeatureValue::Categorical(c.to_string())
+            }),
+        );
+        x
+    }
+
+    fn indicators(x: &Observation<f64>) -> Vec<(String, f64)> {
+        x.numeric().map(|(name, v)| (name.clone(), v)).collect()
+    }
+
+    #[test]
+    fn test_other_bucket() {
+        let mut encoder = OneHotEncoder::new(OneHotEncoderOptions {
+            max_categories: Some(2),
+            drop_zeros: false,
+        });
+        // Unknown categories have no indicator until the feature is full
+        let encoded = encoder.transform_one(&observation(Some("red")));
+        assert!(encoded.is_empty());
+        for color in ["red", "green", "blue", "red"] {
+            encoder.learn_one(&observation(Some(color)));
+        }
+        assert_eq!(encoder.categories("color").unwrap().len(), 2);
+        assert_eq!(
+            indicators(&encoder.transform_one(&observation(Some("blue")))),
+            [
+                ("color_green".to_string(), 0.0),
+                ("color_other".to_string(), 1.0),
+                ("color_red".to_string(), 0.0),
+            ]
+        );
+        // A missing value has no category
+        assert_eq!(
+            indicators(&encoder.transform_one(&observation(None))),
+            [
+                ("color_green".to_string(), 0.0),
+                ("color_other".to_string(), 0.0),
+                ("color_red".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_zeros() {
+        let mut encoder = OneHotEncoder::new(OneHotEncoderOptions {
+            max_categories: None,
+            drop_zeros: true,
+        });
+        for color in ["red", "green", "blue"] {
+            encoder.learn_one(&observation(Some(color)));
+        }
+        assert_eq!(
+            indicators(&encoder.transform_one(&observation(Some("green")))),
+            [("color_green".to_string(), 1.0)]
+        );
+        assert!(encoder.transform_one(&observation(Some("pink"))).is_empty());
+        assert!(encoder.transform_one(&observation(None)).is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation, RegressionTarget};
+use crate::learner::SupervisedTransformer;
+use crate::stats::mean::Mean;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Target encoder, which replaces the categorical features with the running mean of the target
+/// of their category.
+///
+/// The mean of a category is smoothed towards the mean of the target over all the observations,
+/// the prior, as if `smoothing` observations with the prior as target had been seen for each
+/// category. Rare categories are thus encoded close to the prior, instead of with a noisy mean.
+/// Categories which haven't been seen yet, and missing categorical features, are encoded with the
+/// prior. Numeric features are left as they are.
+///
+/// The target of an observation is learned after it is transformed, e.g. with
+/// [`TargetEncoder::transform_and_learn_one`], so that it doesn't leak into its own encoding,
+/// which would make the features look more predictive than they are.
+///
+/// # Parameters
+///
+/// - `smoothing`: The weight of the prior in the encoding of each category, 10 by default.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{FeatureValue, Observation};
+/// use light_river::learner::SupervisedTransformer;
+/// use light_river::preprocessing::target_encoder::TargetEncoder;
+///
+/// let mut encoder: TargetEncoder<f64> = TargetEncoder::new(Some(1.0));
+/// let merchant = |name: &str| {
+///     let mut x = Observation::new();
+///     x.insert("merchant", FeatureValue::Categorical(name.to_string()));
+///     x
+/// };
+/// for (name, fraud) in [("shady", 1.0), ("shady", 1.0), ("shady", 0.0), ("shop", 0.0)] {
+///     encoder.learn_one(&merchant(name), fraud);
+/// }
+///
+/// // The prior is 0.5, and the mean of "shady" is 2 / 3
+/// let shady = encoder.transform_one(&merchant("shady"));
+/// assert!((shady.get_numeric("merchant").unwrap() - 0.625).abs() < 1e-12);
+/// let unknown = encoder.transform_one(&merchant("new"));
+/// assert_eq!(unknown.get_numeric("merchant"), Some(0.5));
+/// ```
+///
+/// # References
+///
+/// [^1]: D. Micci-Barreca (2001). "A preprocessing scheme for high-cardinality categorical
+/// attributes in classification and prediction problems". ACM SIGKDD Explorations Newsletter
+/// 3(1):27-32.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TargetEncoder<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    smoothing: F,
+    prior: Mean<F>,
+    // Mean of the target of each category of each feature
+    means: HashMap<String, HashMap<String, Mean<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> TargetEncoder<F> {
+    pub fn new(smoothing: Option<F>) -> Self {
+        let smoothing = smoothing.unwrap_or(F::from_f64(10.0).unwrap());
+        assert!(smoothing >= F::zero(), "smoothing must be positive");
+        Self {
+            smoothing,
+            prior: Mean::new(),
+            means: HashMap::new(),
+        }
+    }
+    /// The encoding of a category of a feature.
+    pub fn encode(&self, feature: &str, category: &str) -> F {
+        let prior = self.prior.get();
+        let Some(mean) = self.means.get(feature).and_then(|m| m.get(category)) else {
+            return prior;
+        };
+        let weight = mean.n() + self.smoothing;
+        (mean.n() * mean.get() + self.smoothing * prior) / weight
+    }
+    /// Transform an observation, and then learn its target.
+    pub fn transform_and_learn_one(
+        &mut self,
+        x: &Observation<F>,
+        y: RegressionTarget<F>,
+    ) -> Observation<F> {
+        let encoded = self.transform_one(x);
+        self.learn_one(x, y);
+        encoded
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    SupervisedTransformer<F> for TargetEncoder<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.prior.update(y);
+        for (name, value) in x.iter() {
+            if let FeatureValue::Categorical(category) = value {
+                self.means
+                    .entry(name.clone())
+                    .or_default()
+                    .entry(category.clone())
+                    .or_default()
+                    .update(y);
+            }
+        }
+    }
+    fn transform_one(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    FeatureValue::Categorical(category) => {
+                        FeatureValue::Numeric(self.encode(name, category))
+                    }
+                    FeatureValue::Missing if self.means.contains_key(name) => {
+                        FeatureValue::Numeric(self.prior.get())
+                    }
+                    _ => value.clone(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(city: &str, age: f64) -> Observation<f64> {
+        let mut x = Observation::new();
+        x.insert("city", FeatureValue::Categorical(city.to_string()));
+        x.insert("age", FeatureValue::Numeric(age));
+        x
+    }
+
+    #[test]
+    fn test_no_leakage() {
+        let mut encoder = TargetEncoder::new(Some(0.0));
+        // Without smoothing, the first observation of a category is encoded with the prior, and
+        // not with its own target
+        let first = encoder.transform_and_learn_one(&observation("Paris", 30.0), 1.0);
+        assert_eq!(first.get_numeric("city"), Some(0.0));
+        assert_eq!(first.get_numeric("age"), Some(30.0));
+        encoder.learn_one(&observation("Lyon", 40.0), 0.0);
+        let second = encoder.transform_and_learn_one(&observation("Paris", 50.0), 0.0);
+        assert_eq!(second.get_numeric("city"), Some(1.0));
+        assert_eq!(encoder.encode("city", "Paris"), 0.5);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut encoder = TargetEncoder::new(None);
+        for i in 0..100 {
+            encoder.learn_one(&observation("common", 0.0), (i % 2) as f64);
+        }
+        encoder.learn_one(&observation("rare", 0.0), 10.0);
+        let prior = 60.0 / 101.0;
+        // The rare category is pulled towards the prior much more than the common one
+        let rare = encoder.encode("city", "rare");
+        assert!((rare - (10.0 + 10.0 * prior) / 11.0).abs() < 1e-12);
+        let common = encoder.encode("city", "common");
+        assert!((common - (50.0 + 10.0 * prior) / 110.0).abs() < 1e-12);
+
+        let mut missing = Observation::new();
+        missing.insert("city", FeatureValue::Missing);
+        let encoded = encoder.transform_one(&missing);
+        assert!((encoded.get_numeric("city").unwrap() - prior).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+use std::iter;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureKey, RegressionTarget};
+use crate::learner::Recommender;
+use crate::optim::optimizers::{Optimizer, Weights};
+use crate::sketch::hash;
+use crate::stats::mean::Mean;
+use crate::stats::Univariate;
+use crate::utils::standard_normal;
+use num::{Float, FromPrimitive};
+use rand::prelude::*;
+
+/// Options of a [`BiasedMF`].
+///
+/// - `n_factors`: The dimension of the latent vectors, 10 by default.
+/// - `init_std`: The standard deviation of the normal distribution the latent vectors are drawn
+///   from, 0.1 by default.
+/// - `l2_latent`: The amount of L2 regularization of the latent vectors, 0 by default.
+/// - `l2_bias`: The amount of L2 regularization of the biases, 0 by default.
+/// - `seed`: Random seed of the latent vectors. A latent vector only depends on the seed and on
+///   its user or item, not on the order in which they are met.
+#[derive(Clone, Debug)]
+pub struct BiasedMFOptions<F> {
+    pub n_factors: usize,
+    pub init_std: F,
+    pub l2_latent: F,
+    pub l2_bias: F,
+    pub seed: Option<u64>,
+}
+
+impl<F: Float + FromPrimitive> Default for BiasedMFOptions<F> {
+    fn default() -> Self {
+        Self {
+            n_factors: 10,
+            init_std: F::from_f64(0.1).unwrap(),
+            l2_latent: F::zero(),
+            l2_bias: F::zero(),
+            seed: None,
+        }
+    }
+}
+
+/// Biased matrix factorization, a recommender which learns the ratings given by users to items.
+///
+/// The predicted rating of an item by a user is `mean + b_u + b_i + <p_u, q_i>`, where `mean` is
+/// the mean of the ratings learned from, `b_u` and `b_i` are the biases of the user and of the
+/// item, i.e. how much their ratings are above the mean, and `p_u` and `q_i` are their latent
+/// vectors, whose dot product accounts for the taste of the user for the item. The biases and the
+/// latent vectors are updated by the optimizer given the gradient of the squared error, and a
+/// latent vector is drawn when its user or item is first learned.
+///
+/// The users and items which haven't been learned yet have a zero bias and no latent vector, so
+/// that the prediction of a new user falls back to the mean plus the bias of the item, that of a
+/// new item to the mean plus the bias of the user, and that of both to the mean.
+///
+/// # Parameters
+///
+/// - `optimizer`: The optimizer of the biases and of the latent vectors, see
+///   [`crate::optim::optimizers`]. The biases and each coordinate of the latent vectors, of the
+///   users and of the items, have their own copy of it.
+/// - `options`: The latent vectors and the regularization, see [`BiasedMFOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::learner::Recommender;
+/// use light_river::optim::optimizers::SGD;
+/// use light_river::reco::biased_mf::{BiasedMF, BiasedMFOptions};
+///
+/// // Small latent vectors, so that the biases account for the users and the items
+/// let options = BiasedMFOptions {
+///     init_std: 0.01,
+///     seed: Some(42),
+///     ..Default::default()
+/// };
+/// let mut model = BiasedMF::<f64, _>::new(SGD::new(0.05), options);
+/// // Alice rates everything 1 above Bob, and the comedies are rated 2 above the dramas
+/// for _ in 0..500 {
+///     for (user, item, rating) in [
+///         ("alice", "comedy", 4.0),
+///         ("alice", "drama", 2.0),
+///         ("bob", "comedy", 3.0),
+///         ("bob", "drama", 1.0),
+///     ] {
+///         model.learn_one(user, item, rating);
+///     }
+/// }
+///
+/// assert!((model.predict_one("alice", "comedy") - 4.0).abs() < 0.1);
+/// // A new user is predicted from the mean and the bias of the item
+/// assert!((model.predict_one("carol", "comedy") - 3.5).abs() < 0.1);
+/// assert!((model.predict_one("carol", "western") - 2.5).abs() < 0.1);
+/// ```
+///
+/// # References
+///
+/// [^1]: Y. Koren, R. Bell and C. Volinsky (2009). "Matrix factorization techniques for
+/// recommender systems". Computer 42(8).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BiasedMF<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign, O> {
+    n_factors: usize,
+    init_std: F,
+    l2_latent: F,
+    l2_bias: F,
+    seed: u64,
+    mean: Mean<F>,
+    users: Parameters<F, O>,
+    items: Parameters<F, O>,
+}
+
+// The biases and the latent vectors of the users, or of the items. The biases and each coordinate
+// of the latent vectors are weights keyed by name, which have their own copy of the optimizer.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Parameters<F, O> {
+    // The users or items whose latent vector has been drawn
+    names: HashSet<String>,
+    // The biases, followed by the coordinates of the latent vectors
+    weights: Vec<Weights<F>>,
+    optimizers: Vec<O>,
+}
+
+impl<F, O> Parameters<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    fn new(optimizer: O, n_factors: usize) -> Self {
+        Self {
+            names: HashSet::new(),
+            weights: vec![Weights::new(); n_factors + 1],
+            optimizers: vec![optimizer; n_factors + 1],
+        }
+    }
+    fn bias(&self, name: &str) -> F {
+        self.weights[0].get(FeatureKey::Name(name))
+    }
+    // The latent vector of a user or an item, which is null until it is drawn.
+    fn latent(&self, name: &str) -> Vec<F> {
+        self.weights[1..]
+            .iter()
+            .map(|weights| weights.get(FeatureKey::Name(name)))
+            .collect()
+    }
+    fn insert(&mut self, name: &str, latent: Vec<F>) {
+        for (weights, v) in self.weights[1..].iter_mut().zip(latent) {
+            *weights.get_mut(FeatureKey::Name(name)) = v;
+        }
+        self.names.insert(name.to_string());
+    }
+    // Update the bias and then the latent vector, given the gradient with respect to each.
+    fn step(&mut self, name: &str, gradient: &[F]) {
+        let parameters = self.weights.iter_mut().zip(self.optimizers.iter_mut());
+        for ((weights, optimizer), gradient) in parameters.zip(gradient) {
+            optimizer.step(weights, &[(FeatureKey::Name(name), *gradient)]);
+        }
+    }
+}
+
+impl<F, O> BiasedMF<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    pub fn new(optimizer: O, options: BiasedMFOptions<F>) -> Self {
+        assert!(options.n_factors > 0, "n_factors must be strictly positive");
+        assert!(options.init_std >= F::zero(), "init_std must be positive");
+        Self {
+            n_factors: options.n_factors,
+            init_std: options.init_std,
+            l2_latent: options.l2_latent,
+            l2_bias: options.l2_bias,
+            seed: options.seed.unwrap_or_else(random),
+            mean: Mean::new(),
+            users: Parameters::new(optimizer.clone(), options.n_factors),
+            items: Parameters::new(optimizer, options.n_factors),
+        }
+    }
+    /// The mean of the ratings learned from.
+    pub fn global_mean(&self) -> F {
+        self.mean.get()
+    }
+    /// The bias of a user, which is zero for a new user.
+    pub fn user_bias(&self, user: &str) -> F {
+        self.users.bias(user)
+    }
+    /// The bias of an item, which is zero for a new item.
+    pub fn item_bias(&self, item: &str) -> F {
+        self.items.bias(item)
+    }
+    pub fn n_users(&self) -> usize {
+        self.users.names.len()
+    }
+    pub fn n_items(&self) -> usize {
+        self.items.names.len()
+    }
+    // Draw the latent vector of a user or an item, from a generator seeded by its name.
+    fn draw(&self, is_user: bool, name: &str) -> Vec<F> {
+        let mut rng = StdRng::seed_from_u64(hash(&(is_user, name), self.seed));
+        (0..self.n_factors)
+            .map(|_| self.init_std * F::from_f64(standard_normal(&mut rng)).unwrap())
+            .collect()
+    }
+    // Gradient of the squared error with respect to a bias and then to a latent vector, given
+    // the error and the latent vector it is multiplied with.
+    fn gradient(&self, error: F, bias: F, latent: &[F], other: &[F]) -> Vec<F> {
+        let latent = latent
+            .iter()
+            .zip(other.iter())
+            .map(|(v, other)| error * *other + self.l2_latent * *v);
+        iter::once(error + self.l2_bias * bias)
+            .chain(latent)
+            .collect()
+    }
+}
+
+impl<F, O> Recommender<F> for BiasedMF<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    fn learn_one(&mut self, user: &str, item: &str, y: RegressionTarget<F>) {
+        if !self.users.names.contains(user) {
+            let latent = self.draw(true, user);
+            self.users.insert(user, latent);
+        }
+        if !self.items.names.contains(item) {
+            let latent = self.draw(false, item);
+            self.items.insert(item, latent);
+        }
+        let error = self.predict_one(user, item) - y;
+
+        // Both vectors are updated from the values they had before the step
+        let (p, q) = (self.users.latent(user), self.items.latent(item));
+        let user_gradient = self.gradient(error, self.user_bias(user), &p, &q);
+        let item_gradient = self.gradient(error, self.item_bias(item), &q, &p);
+        self.users.step(user, &user_gradient);
+        self.items.step(item, &item_gradient);
+        self.mean.update(y);
+    }
+    fn predict_one(&self, user: &str, item: &str) -> RegressionTarget<F> {
+        // The latent vectors of the users and items which haven't been learned yet are null
+        let (p, q) = (self.users.latent(user), self.items.latent(item));
+        let dot = p
+            .iter()
+            .zip(q.iter())
+            .fold(F::zero(), |dot, (p, q)| dot + *p * *q);
+        self.mean.get() + self.user_bias(user) + self.item_bias(item) + dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::optimizers::{Adam, SGD};
+
+    #[test]
+    fn test_latent_factors() {
+        // The rating is high when the user and the item are in the same group, which the biases
+        // alone can't explain, and a pair of each group is never seen
+        let options = BiasedMFOptions {
+            n_factors: 4,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let mut model = BiasedMF::new(SGD::new(0.05), options);
+        let rating = |user: usize, item: usize| if user % 2 == item % 2 { 5.0 } else { 1.0 };
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20000 {
+            let (user, item) = (rng.gen_range(0..10), rng.gen_range(0..10));
+            if (user, item) == (0, 0) || (user, item) == (1, 3) {
+                continue;
+            }
+            let (u, i) = (format!("u{}", user), format!("i{}", item));
+            model.learn_one(&u, &i, rating(user, item));
+        }
+        assert!((model.predict_one("u0", "i0") - 5.0).abs() < 0.5);
+        assert!((model.predict_one("u1", "i3") - 5.0).abs() < 0.5);
+        assert!((model.predict_one("u0", "i3") - 1.0).abs() < 0.5);
+        assert_eq!((model.n_users(), model.n_items()), (10, 10));
+        // Nothing is known of a new user and a new item
+        assert!((model.predict_one("u10", "i10") - 3.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_biases_with_adam() {
+        // The users and the items share names, but not their biases nor the state of Adam
+        let options = BiasedMFOptions {
+            init_std: 0.0,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let mut model = BiasedMF::new(Adam::new(0.05, None, None, None), options);
+        for _ in 0..2000 {
+            for (user, item, rating) in [
+                ("a", "a", 3.0),
+                ("a", "b", 1.0),
+                ("b", "a", 2.0),
+                ("b", "b", 0.0),
+            ] {
+                model.learn_one(user, item, rating);
+            }
+        }
+        assert!((model.predict_one("a", "a") - 3.0).abs() < 0.1);
+        assert!((model.predict_one("b", "b") - 0.0).abs() < 0.1);
+        assert!((model.user_bias("a") - model.user_bias("b") - 1.0).abs() < 0.1);
+        assert!((model.item_bias("a") - model.item_bias("b") - 2.0).abs() < 0.1);
+    }
+}
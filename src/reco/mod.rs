@@ -0,0 +1 @@
+pub mod biased_mf;
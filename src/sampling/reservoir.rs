@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Uniform sample of fixed size of a stream of items, i.e. algorithm R.
+///
+/// The first `capacity` items fill the reservoir. Afterwards, the `n`-th item replaces a random
+/// item of the reservoir with probability `capacity / n`, so that each item seen so far is in the
+/// reservoir with the same probability. The reservoir can be drained, e.g. to periodically
+/// retrain a model offline on a sample of the recent past.
+///
+/// # Parameters
+///
+/// - `capacity`: The size of the sample.
+/// - `seed`: Random seed for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sampling::reservoir::ReservoirSampler;
+///
+/// let mut sampler = ReservoirSampler::new(10, Some(42));
+/// for i in 0..1000 {
+///     sampler.update(i);
+/// }
+/// assert_eq!(sampler.len(), 10);
+/// assert!(sampler.iter().all(|i| *i < 1000));
+///
+/// let sample = sampler.drain();
+/// assert_eq!(sample.len(), 10);
+/// assert!(sampler.is_empty());
+/// ```
+///
+/// # References
+///
+/// [^1]: J. S. Vitter (1985). "Random sampling with a reservoir". ACM Transactions on
+/// Mathematical Software 11(1):37-57.
+#[derive(Clone, Debug)]
+pub struct ReservoirSampler<T> {
+    capacity: usize,
+    sample: Vec<T>,
+    n_seen: usize,
+    rng: StdRng,
+}
+
+impl<T> ReservoirSampler<T> {
+    pub fn new(capacity: usize, seed: Option<u64>) -> Self {
+        assert!(capacity > 0, "capacity must be strictly positive");
+        Self {
+            capacity,
+            sample: Vec::with_capacity(capacity),
+            n_seen: 0,
+            rng: rng(seed),
+        }
+    }
+    pub fn update(&mut self, item: T) {
+        self.n_seen += 1;
+        if self.sample.len() < self.capacity {
+            self.sample.push(item);
+            return;
+        }
+        let j = self.rng.gen_range(0..self.n_seen);
+        if j < self.capacity {
+            self.sample[j] = item;
+        }
+    }
+    /// The items of the sample, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.sample.iter()
+    }
+    /// Empty the reservoir and return its items, as if no item had been seen.
+    pub fn drain(&mut self) -> Vec<T> {
+        self.n_seen = 0;
+        std::mem::replace(&mut self.sample, Vec::with_capacity(self.capacity))
+    }
+    pub fn len(&self) -> usize {
+        self.sample.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sample.is_empty()
+    }
+    /// Number of items seen since the reservoir was created or drained.
+    pub fn n_seen(&self) -> usize {
+        self.n_seen
+    }
+}
+
+// Item of a weighted reservoir. The order is reversed, so that the heap pops the smallest key.
+#[derive(Clone, Debug)]
+struct Keyed<F, T> {
+    key: F,
+    item: T,
+}
+
+impl<F: Float, T> PartialEq for Keyed<F, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<F: Float, T> Eq for Keyed<F, T> {}
+
+impl<F: Float, T> PartialOrd for Keyed<F, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float, T> Ord for Keyed<F, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.partial_cmp(&self.key).unwrap()
+    }
+}
+
+/// Weighted sample of fixed size of a stream of items, without replacement, i.e. algorithm A-ES.
+///
+/// Each item is given a random key `u^(1 / w)`, where `u` is uniform in `(0, 1)` and `w` is the
+/// weight of the item, and the reservoir keeps the items with the largest keys. This amounts to
+/// drawing the items one after the other with probabilities proportional to their weights. The
+/// logarithms of the keys are used, as the keys underflow for small weights.
+///
+/// # Parameters
+///
+/// - `capacity`: The size of the sample.
+/// - `seed`: Random seed for reproducibility.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sampling::reservoir::WeightedReservoirSampler;
+///
+/// let mut sampler: WeightedReservoirSampler<f64, &str> = WeightedReservoirSampler::new(2, Some(42));
+/// for _ in 0..100 {
+///     sampler.update("noise", 0.001);
+/// }
+/// sampler.update("signal", 1000.0);
+/// assert!(sampler.iter().any(|item| *item == "signal"));
+/// ```
+///
+/// # References
+///
+/// [^1]: P. S. Efraimidis and P. G. Spirakis (2006). "Weighted random sampling with a reservoir".
+/// Information Processing Letters 97(5):181-185.
+#[derive(Clone, Debug)]
+pub struct WeightedReservoirSampler<F, T> {
+    capacity: usize,
+    sample: BinaryHeap<Keyed<F, T>>,
+    n_seen: usize,
+    rng: StdRng,
+}
+
+impl<F, T> WeightedReservoirSampler<F, T>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+{
+    pub fn new(capacity: usize, seed: Option<u64>) -> Self {
+        assert!(capacity > 0, "capacity must be strictly positive");
+        Self {
+            capacity,
+            sample: BinaryHeap::with_capacity(capacity + 1),
+            n_seen: 0,
+            rng: rng(seed),
+        }
+    }
+    /// Add an item with a strictly positive weight.
+    pub fn update(&mut self, item: T, w: F) {
+        assert!(w > F::zero(), "weights must be strictly positive");
+        self.n_seen += 1;
+        // u lies in (0, 1]
+        let u = F::from_f64(1.0 - self.rng.gen::<f64>()).unwrap();
+        let key = u.ln() / w;
+        if self.sample.len() < self.capacity {
+            self.sample.push(Keyed { key, item });
+        } else if self
+            .sample
+            .peek()
+            .is_some_and(|smallest| key > smallest.key)
+        {
+            self.sample.pop();
+            self.sample.push(Keyed { key, item });
+        }
+    }
+    /// The items of the sample, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.sample.iter().map(|keyed| &keyed.item)
+    }
+    /// Empty the reservoir and return its items, as if no item had been seen.
+    pub fn drain(&mut self) -> Vec<T> {
+        self.n_seen = 0;
+        self.sample.drain().map(|keyed| keyed.item).collect()
+    }
+    pub fn len(&self) -> usize {
+        self.sample.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sample.is_empty()
+    }
+    /// Number of items seen since the reservoir was created or drained.
+    pub fn n_seen(&self) -> usize {
+        self.n_seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_inclusion() {
+        // Each of the 20 items should be in a sample of 5 a quarter of the time
+        let mut counts = [0; 20];
+        for seed in 0..4000 {
+            let mut sampler = ReservoirSampler::new(5, Some(seed));
+            for i in 0..20 {
+                sampler.update(i);
+            }
+            assert_eq!(sampler.n_seen(), 20);
+            for i in sampler.iter() {
+                counts[*i] += 1;
+            }
+        }
+        for count in counts {
+            assert!((count as f64 / 4000.0 - 0.25).abs() < 0.03, "{}", count);
+        }
+    }
+
+    #[test]
+    fn test_weighted_inclusion() {
+        // Item 0 weighs as much as the 9 others together, so that it is drawn first half of the
+        // time, and otherwise second with probability 9 / 17
+        let mut count = 0;
+        for seed in 0..4000 {
+            let mut sampler = WeightedReservoirSampler::new(2, Some(seed));
+            sampler.update(0, 9.0);
+            for i in 1..10 {
+                sampler.update(i, 1.0);
+            }
+            if sampler.iter().any(|i| *i == 0) {
+                count += 1;
+            }
+        }
+        let expected = 0.5 + 0.5 * 9.0 / 17.0;
+        assert!((count as f64 / 4000.0 - expected).abs() < 0.03, "{}", count);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut sampler: WeightedReservoirSampler<f64, usize> =
+            WeightedReservoirSampler::new(3, Some(1));
+        for i in 0..10 {
+            sampler.update(i, 1.0 + i as f64);
+        }
+        let mut sample = sampler.drain();
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 3);
+        assert!(sampler.is_empty());
+        assert_eq!(sampler.n_seen(), 0);
+    }
+}
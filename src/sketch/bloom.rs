@@ -0,0 +1,143 @@
+use std::f64::consts::LN_2;
+use std::hash::Hash;
+
+use crate::sketch::hash;
+
+/// Bloom filter, which tests whether a key has already been seen in a stream.
+///
+/// Each key sets a few bits of a bit array, whose positions are given by hash functions. A key
+/// is reported as seen if all its bits are set, so that there are no false negatives, but false
+/// positives happen when the bits of a new key were set by others. The size of the array and the
+/// number of hash functions are chosen so that the false positive rate is `error_rate` once
+/// `capacity` keys are inserted, and it keeps growing afterwards.
+///
+/// Filters with the same capacity and error rate can be merged, which gives the filter of the
+/// union of their keys.
+///
+/// # Parameters
+///
+/// - `capacity`: The number of keys which are expected to be inserted.
+/// - `error_rate`: The false positive rate once `capacity` keys are inserted, in `(0, 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::bloom::BloomFilter;
+///
+/// let mut seen = BloomFilter::new(1000, 0.01);
+/// let events = ["click", "view", "click", "buy", "view"];
+/// let unique: Vec<_> = events.iter().filter(|e| seen.insert(*e)).collect();
+/// assert_eq!(unique, vec![&"click", &"view", &"buy"]);
+/// assert!(seen.contains("buy"));
+/// ```
+///
+/// # References
+///
+/// [^1]: B. H. Bloom (1970). "Space/time trade-offs in hash coding with allowable errors".
+/// Communications of the ACM 13(7):422-426.
+///
+/// [^2]: A. Kirsch and M. Mitzenmacher (2006). "Less hashing, same performance: building a better
+/// Bloom filter". European Symposium on Algorithms, 456-467.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    n_bits: usize,
+    n_hashes: usize,
+}
+
+impl BloomFilter {
+    pub fn new(capacity: usize, error_rate: f64) -> Self {
+        assert!(capacity > 0, "capacity must be strictly positive");
+        assert!(
+            error_rate > 0.0 && error_rate < 1.0,
+            "error_rate must lie in (0, 1)"
+        );
+        let n_bits = (-(capacity as f64) * error_rate.ln() / (LN_2 * LN_2)).ceil() as usize;
+        let n_hashes = ((n_bits as f64 / capacity as f64) * LN_2).round().max(1.0) as usize;
+        Self {
+            bits: vec![0; n_bits.div_ceil(64)],
+            n_bits,
+            n_hashes,
+        }
+    }
+    /// Insert a key, and return whether it wasn't seen before. Keys which were seen before
+    /// always return `false`, and new keys may too with a probability of about the false
+    /// positive rate.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        let mut new = false;
+        for position in self.positions(key) {
+            let (word, bit) = (position / 64, 1 << (position % 64));
+            new |= self.bits[word] & bit == 0;
+            self.bits[word] |= bit;
+        }
+        new
+    }
+    /// Whether a key may have been inserted. `false` is always right.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        self.positions(key)
+            .all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+    /// Merge the filter of another stream into this one.
+    ///
+    /// # Panics
+    ///
+    /// If the filters don't have the same size and number of hash functions.
+    pub fn merge(&mut self, other: &BloomFilter) {
+        assert!(
+            self.n_bits == other.n_bits && self.n_hashes == other.n_hashes,
+            "Only filters with the same capacity and error rate can be merged"
+        );
+        for (word, other_word) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *word |= *other_word;
+        }
+    }
+    /// The current false positive rate, estimated from the proportion of bits which are set.
+    pub fn error_rate(&self) -> f64 {
+        let ones: u32 = self.bits.iter().map(|word| word.count_ones()).sum();
+        (ones as f64 / self.n_bits as f64).powi(self.n_hashes as i32)
+    }
+    pub fn n_bits(&self) -> usize {
+        self.n_bits
+    }
+    pub fn n_hashes(&self) -> usize {
+        self.n_hashes
+    }
+    // The hash functions are combinations of two hashes, which is as good as independent ones.
+    fn positions<K: Hash + ?Sized>(&self, key: &K) -> impl Iterator<Item = usize> {
+        let (h1, h2) = (hash(key, 0), hash(key, 1));
+        let n_bits = self.n_bits as u64;
+        (0..self.n_hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % n_bits) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rate() {
+        let mut filter = BloomFilter::new(10000, 0.01);
+        // A few new keys are taken for ones which were seen before
+        let new = (0..10000usize).filter(|i| filter.insert(i)).count();
+        assert!(new > 9900, "{}", new);
+        // No false negatives
+        assert!((0..10000usize).all(|i| filter.contains(&i) && !filter.insert(&i)));
+        let false_positives = (10000..110000usize).filter(|i| filter.contains(i)).count();
+        assert!(false_positives < 1500, "{}", false_positives);
+        assert!((filter.error_rate() - 0.01).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = BloomFilter::new(1000, 0.01);
+        let mut second = BloomFilter::new(1000, 0.01);
+        for i in 0..500usize {
+            first.insert(&i);
+            second.insert(&(i + 500));
+        }
+        first.merge(&second);
+        assert!((0..1000usize).all(|i| first.contains(&i)));
+    }
+}
@@ -0,0 +1,234 @@
+use std::hash::Hash;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::sketch::hash;
+use crate::utils::rng;
+use num::{Float, FromPrimitive};
+use rand::Rng;
+
+/// Options of a [`CountMin`] sketch.
+///
+/// # Parameters
+///
+/// - `width`: The number of counters of each row, 2048 by default. The counts are overestimated
+///   by at most `e / width` times the total count, with high probability.
+/// - `depth`: The number of rows, each with its own hash function, 5 by default. The probability
+///   that the bound on the error doesn't hold is `exp(-depth)`.
+/// - `conservative_update`: Whether only the smallest counters of a key are incremented, which
+///   reduces the overestimation, but prevents decrementing counts and merging sketches.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountMinOptions {
+    pub width: usize,
+    pub depth: usize,
+    pub conservative_update: bool,
+}
+
+impl Default for CountMinOptions {
+    fn default() -> Self {
+        Self {
+            width: 2048,
+            depth: 5,
+            conservative_update: false,
+        }
+    }
+}
+
+/// Count-Min sketch, which estimates the frequencies of the keys of a stream.
+///
+/// Each key is hashed to a counter in each row of a table of fixed size, which is incremented by
+/// the count of the key. The estimated count of a key is the minimum of its counters, which is
+/// never below the true count for non-negative counts, and only exceeds it because of collisions.
+/// The memory doesn't depend on the number of distinct keys, which makes the sketch suitable for
+/// categorical features with unbounded cardinality.
+///
+/// Sketches which share the same options and seed can be merged, e.g. to aggregate the sketches
+/// computed on several machines.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::count_min::{CountMin, CountMinOptions};
+///
+/// let mut sketch: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(42));
+/// for i in 0..10000 {
+///     sketch.update(&format!("user_{}", i % 100), 1.0);
+/// }
+/// sketch.update("rare", 3.0);
+/// assert!(sketch.get("user_7") >= 100.0 && sketch.get("user_7") < 110.0);
+/// assert!(sketch.get("rare") >= 3.0 && sketch.get("rare") < 13.0);
+/// assert_eq!(sketch.total(), 10003.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: G. Cormode and S. Muthukrishnan (2005). "An improved data stream summary: the count-min
+/// sketch and its applications". Journal of Algorithms 55(1):58-75.
+///
+/// [^2]: C. Estan and G. Varghese (2002). "New directions in traffic measurement and accounting".
+/// ACM SIGCOMM Computer Communication Review 32(4):323-336.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountMin<F> {
+    options: CountMinOptions,
+    // Seeds of the hash functions of the rows
+    seeds: Vec<u64>,
+    counts: Vec<Vec<F>>,
+    total: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CountMin<F> {
+    pub fn new(options: CountMinOptions, seed: Option<u64>) -> Self {
+        assert!(options.width > 0, "width must be strictly positive");
+        assert!(options.depth > 0, "depth must be strictly positive");
+        let mut rng = rng(seed);
+        Self {
+            options,
+            seeds: (0..options.depth).map(|_| rng.gen()).collect(),
+            counts: vec![vec![F::zero(); options.width]; options.depth],
+            total: F::zero(),
+        }
+    }
+    /// Add a count to a key.
+    ///
+    /// # Panics
+    ///
+    /// If the count is negative and conservative updates are enabled.
+    pub fn update<K: Hash + ?Sized>(&mut self, key: &K, count: F) {
+        let columns: Vec<usize> = (0..self.options.depth)
+            .map(|row| self.column(key, row))
+            .collect();
+        if self.options.conservative_update {
+            assert!(
+                count >= F::zero(),
+                "Counts can't be decremented with conservative updates"
+            );
+            // Raise the counters up to the new estimate, and leave the larger ones untouched
+            let estimate = self.estimate(&columns) + count;
+            for (row, column) in columns.into_iter().enumerate() {
+                let counter = &mut self.counts[row][column];
+                *counter = counter.max(estimate);
+            }
+        } else {
+            for (row, column) in columns.into_iter().enumerate() {
+                self.counts[row][column] += count;
+            }
+        }
+        self.total += count;
+    }
+    /// The estimated count of a key, which is never below its true count if no count is
+    /// negative.
+    pub fn get<K: Hash + ?Sized>(&self, key: &K) -> F {
+        let columns: Vec<usize> = (0..self.options.depth)
+            .map(|row| self.column(key, row))
+            .collect();
+        self.estimate(&columns)
+    }
+    /// The sum of the counts of all the keys.
+    pub fn total(&self) -> F {
+        self.total
+    }
+    /// Merge the sketch of another stream into this one.
+    ///
+    /// # Panics
+    ///
+    /// If the sketches don't share the same options and hash functions, or use conservative
+    /// updates, whose counters can't be summed.
+    pub fn merge(&mut self, other: &CountMin<F>) {
+        assert!(
+            self.seeds == other.seeds && self.options.width == other.options.width,
+            "Only sketches with the same options and seed can be merged"
+        );
+        assert!(
+            !self.options.conservative_update && !other.options.conservative_update,
+            "Sketches with conservative updates can't be merged"
+        );
+        for (row, other_row) in self.counts.iter_mut().zip(other.counts.iter()) {
+            for (count, other_count) in row.iter_mut().zip(other_row.iter()) {
+                *count += *other_count;
+            }
+        }
+        self.total += other.total;
+    }
+    fn column<K: Hash + ?Sized>(&self, key: &K, row: usize) -> usize {
+        (hash(key, self.seeds[row]) % self.options.width as u64) as usize
+    }
+    fn estimate(&self, columns: &[usize]) -> F {
+        columns
+            .iter()
+            .enumerate()
+            .map(|(row, column)| self.counts[row][*column])
+            .fold(F::infinity(), F::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Zipf-like stream: key i appears about 1000 / (i + 1) times
+    fn stream() -> Vec<usize> {
+        (0..1000)
+            .flat_map(|i| std::iter::repeat_n(i, 1000 / (i + 1)))
+            .collect()
+    }
+
+    #[test]
+    fn test_conservative_update() {
+        let options = CountMinOptions {
+            width: 64,
+            depth: 3,
+            conservative_update: false,
+        };
+        let mut plain: CountMin<f64> = CountMin::new(options, Some(42));
+        let mut conservative: CountMin<f64> = CountMin::new(
+            CountMinOptions {
+                conservative_update: true,
+                ..options
+            },
+            Some(42),
+        );
+        for key in stream() {
+            plain.update(&key, 1.0);
+            conservative.update(&key, 1.0);
+        }
+        let (mut plain_error, mut conservative_error) = (0.0, 0.0);
+        for key in 0..1000usize {
+            let count = (1000 / (key + 1)) as f64;
+            // Counts are never underestimated
+            assert!(plain.get(&key) >= count && conservative.get(&key) >= count);
+            assert!(conservative.get(&key) <= plain.get(&key));
+            plain_error += plain.get(&key) - count;
+            conservative_error += conservative.get(&key) - count;
+        }
+        assert!(conservative_error < plain_error);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(7));
+        let mut second: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(7));
+        let mut all: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(7));
+        for (i, key) in stream().into_iter().enumerate() {
+            if i % 2 == 0 {
+                first.update(&key, 1.0);
+            } else {
+                second.update(&key, 1.0);
+            }
+            all.update(&key, 1.0);
+        }
+        first.merge(&second);
+        assert_eq!(first.total(), all.total());
+        for key in 0..1000usize {
+            assert_eq!(first.get(&key), all.get(&key));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "same options and seed")]
+    fn test_merge_different_seeds() {
+        let mut first: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(1));
+        let second: CountMin<f64> = CountMin::new(CountMinOptions::default(), Some(2));
+        first.merge(&second);
+    }
+}
@@ -0,0 +1,246 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use num::{Float, FromPrimitive};
+
+// A value of the summary. `g` is the difference between the minimum rank of the value and the
+// one of the previous value, and `delta` the difference between its maximum and minimum ranks.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Tuple<F> {
+    value: F,
+    g: usize,
+    delta: usize,
+}
+
+/// Greenwald-Khanna quantile summary.
+///
+/// A sorted subset of the values is kept along with bounds on their ranks, so that the rank of
+/// the answer to any quantile query is within `epsilon * n` of the true rank, where `n` is the
+/// number of values. The summary is compressed periodically, and only keeps
+/// `O(log(epsilon * n) / epsilon)` values.
+///
+/// Summaries of different streams can be merged, e.g. to aggregate the summaries computed on
+/// several machines. The merged summary is as accurate as the least accurate of the two.
+///
+/// # Parameters
+///
+/// - `epsilon`: The maximum error on the rank of the answers, relative to the number of values.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::gk::GKQuantile;
+/// use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+///
+/// let mut gk: GKQuantile<f64> = GKQuantile::new(0.01);
+/// let mut values: Vec<f64> = (1..=10000).map(f64::from).collect();
+/// values.shuffle(&mut StdRng::seed_from_u64(42));
+/// for value in values {
+///     gk.update(value);
+/// }
+/// let median = gk.quantile(0.5).unwrap();
+/// assert!((median - 5000.0).abs() <= 100.0);
+/// assert!(gk.size() < 1000);
+/// ```
+///
+/// # References
+///
+/// [^1]: M. Greenwald and S. Khanna (2001). "Space-efficient online computation of quantile
+/// summaries". ACM SIGMOD Record 30(2):58-66.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GKQuantile<F> {
+    epsilon: F,
+    n: usize,
+    tuples: Vec<Tuple<F>>,
+    compress_every: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> GKQuantile<F> {
+    pub fn new(epsilon: F) -> Self {
+        assert!(
+            epsilon > F::zero() && epsilon < F::one(),
+            "epsilon must lie in (0, 1)"
+        );
+        let two = F::one() + F::one();
+        Self {
+            epsilon,
+            n: 0,
+            tuples: Vec::new(),
+            compress_every: (F::one() / (two * epsilon))
+                .floor()
+                .to_usize()
+                .unwrap()
+                .max(1),
+        }
+    }
+    pub fn update(&mut self, x: F) {
+        let i = self.tuples.partition_point(|t| t.value <= x);
+        // The extreme values have exact ranks
+        let delta = if i == 0 || i == self.tuples.len() {
+            0
+        } else {
+            self.threshold()
+        };
+        self.tuples.insert(
+            i,
+            Tuple {
+                value: x,
+                g: 1,
+                delta,
+            },
+        );
+        self.n += 1;
+        if self.n.is_multiple_of(self.compress_every) {
+            self.compress();
+        }
+    }
+    /// An estimate of the `q`-th quantile, or `None` if no value has been seen.
+    pub fn quantile(&self, q: F) -> Option<F> {
+        assert!(q >= F::zero() && q <= F::one(), "q must lie in [0, 1]");
+        let n = F::from_usize(self.n).unwrap();
+        let rank = (q * n).ceil().to_usize()?.max(1);
+        let error = (self.epsilon * n).ceil().to_usize().unwrap();
+        let mut min_rank = 0;
+        for tuple in self.tuples.iter() {
+            min_rank += tuple.g;
+            let max_rank = min_rank + tuple.delta;
+            if max_rank <= rank + error && rank <= min_rank + error {
+                return Some(tuple.value);
+            }
+        }
+        self.tuples.last().map(|t| t.value)
+    }
+    /// Merge the summary of another stream into this one.
+    pub fn merge(&mut self, other: &GKQuantile<F>) {
+        // The rank of a value among those of the other stream is only known to lie between the
+        // minimum rank of its predecessor there and the maximum rank of its successor, minus 1,
+        // which widens its rank bounds by `g + delta - 1` of the successor
+        let widening = |next: Option<&Tuple<F>>| next.map_or(0, |next| next.g + next.delta - 1);
+        let mut tuples = Vec::with_capacity(self.tuples.len() + other.tuples.len());
+        let (mut i, mut j) = (0, 0);
+        while i < self.tuples.len() || j < other.tuples.len() {
+            let mut tuple;
+            if j == other.tuples.len()
+                || (i < self.tuples.len() && self.tuples[i].value <= other.tuples[j].value)
+            {
+                tuple = self.tuples[i];
+                tuple.delta += widening(other.tuples.get(j));
+                i += 1;
+            } else {
+                tuple = other.tuples[j];
+                tuple.delta += widening(self.tuples.get(i));
+                j += 1;
+            }
+            tuples.push(tuple);
+        }
+        self.tuples = tuples;
+        self.n += other.n;
+        self.epsilon = self.epsilon.max(other.epsilon);
+        self.compress();
+    }
+    /// Number of values seen.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+    /// Number of values kept in the summary.
+    pub fn size(&self) -> usize {
+        self.tuples.len()
+    }
+    fn threshold(&self) -> usize {
+        let two = F::one() + F::one();
+        (two * self.epsilon * F::from_usize(self.n).unwrap())
+            .floor()
+            .to_usize()
+            .unwrap()
+    }
+    // Merge each value into its successor as long as the rank bounds of the latter stay within
+    // the threshold. The minimum and the maximum are always kept.
+    fn compress(&mut self) {
+        if self.tuples.len() < 3 {
+            return;
+        }
+        let threshold = self.threshold();
+        let last = self.tuples.pop().unwrap();
+        let mut kept = vec![last];
+        for tuple in self.tuples.drain(1..).rev() {
+            let next = kept.last_mut().unwrap();
+            if tuple.g + next.g + next.delta <= threshold {
+                next.g += tuple.g;
+            } else {
+                kept.push(tuple);
+            }
+        }
+        kept.reverse();
+        self.tuples.extend(kept);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::permutation;
+
+    #[test]
+    fn test_rank_error() {
+        let epsilon = 0.01;
+        let mut gk = GKQuantile::new(epsilon);
+        assert_eq!(gk.quantile(0.5), None);
+        for x in permutation(20000) {
+            gk.update(x);
+        }
+        for q in [0.0, 0.01, 0.25, 0.5, 0.9, 0.999, 1.0] {
+            // The values are their own ranks
+            let rank = (q * 20000.0_f64).ceil().max(1.0);
+            let answer = gk.quantile(q).unwrap();
+            assert!(
+                (answer - rank).abs() <= epsilon * 20000.0,
+                "q = {}: {}",
+                q,
+                answer
+            );
+        }
+        assert_eq!(gk.quantile(0.0), Some(1.0));
+        assert_eq!(gk.quantile(1.0), Some(20000.0));
+    }
+
+    // Merge the summaries of the values below `low`, in between, and above `high`, out of a
+    // permutation of 1, 2, ..., n, the first and last parts going to the same summary
+    fn merged(n: usize, low: f64, high: f64) -> GKQuantile<f64> {
+        let (mut outer, mut inner) = (GKQuantile::new(0.01), GKQuantile::new(0.01));
+        for x in permutation(n) {
+            if low <= x && x <= high {
+                inner.update(x);
+            } else {
+                outer.update(x);
+            }
+        }
+        // Both summaries are compressed
+        assert!(outer.size() < outer.n() / 10 && inner.size() < inner.n() / 10);
+        outer.merge(&inner);
+        outer
+    }
+
+    #[test]
+    fn test_merge() {
+        let n = 30000;
+        for (low, high) in [(1.0, 5000.0), (10001.0, 30000.0), (12001.0, 14000.0)] {
+            let gk = merged(n, low, high);
+            assert_eq!(gk.n(), n);
+            for i in 0..=100 {
+                let q = i as f64 / 100.0;
+                // The values are their own ranks
+                let rank = (q * n as f64).ceil().max(1.0);
+                let answer = gk.quantile(q).unwrap();
+                assert!(
+                    (answer - rank).abs() <= 0.01 * n as f64,
+                    "[{}, {}], q = {}: {}",
+                    low,
+                    high,
+                    q,
+                    answer
+                );
+            }
+        }
+    }
+}
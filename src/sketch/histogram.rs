@@ -0,0 +1,359 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use num::{Float, FromPrimitive};
+
+/// Streaming histogram with a bounded number of bins.
+///
+/// Each bin is summarized by its centroid and its weight. A new value gets its own bin, and the
+/// two bins with the closest centroids are merged into their weighted centroid whenever there are
+/// more than `n_bins` bins, so that the bins concentrate where the values are dense. The weight
+/// which lies below a value is estimated by assuming that the density varies linearly between
+/// consecutive centroids, and between the extreme centroids and the exact minimum and maximum.
+///
+/// Histograms of different streams can be merged, e.g. to aggregate the histograms computed on
+/// several machines.
+///
+/// Each bin can also hold statistics of the values which fell in it, e.g. their class
+/// distribution, which are combined when the bins are merged, see [`BinStats`] and
+/// [`Histogram::update_with`].
+///
+/// # Parameters
+///
+/// - `n_bins`: The maximum number of bins, at least 2.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::histogram::Histogram;
+/// use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+///
+/// let mut histogram: Histogram<f64> = Histogram::new(32);
+/// let mut values: Vec<f64> = (1..=10000).map(f64::from).collect();
+/// values.shuffle(&mut StdRng::seed_from_u64(42));
+/// for value in values {
+///     histogram.update(value);
+/// }
+/// assert_eq!(histogram.bins().len(), 32);
+/// assert!((histogram.cdf(2500.0) - 0.25).abs() < 0.01);
+/// assert!((histogram.quantile(0.9).unwrap() - 9000.0).abs() < 100.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: Y. Ben-Haim and E. Tom-Tov (2010). "A streaming parallel decision tree algorithm".
+/// Journal of Machine Learning Research 11:849-872.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram<F, S = ()> {
+    n_bins: usize,
+    // Centroids sorted by value, along with the weight of their bin
+    bins: Vec<(F, F)>,
+    // Statistics of each bin
+    stats: Vec<S>,
+    weight: F,
+    min: F,
+    max: F,
+}
+
+/// Statistics held by each bin of a [`Histogram`], besides its weight.
+pub trait BinStats: Clone + Default {
+    /// Combine the statistics of another bin into these ones.
+    fn merge(&mut self, other: &Self);
+}
+
+impl BinStats for () {
+    fn merge(&mut self, _other: &Self) {}
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Histogram<F> {
+    pub fn new(n_bins: usize) -> Self {
+        Self::with_stats(n_bins)
+    }
+}
+
+impl<F, S> Histogram<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: BinStats,
+{
+    /// A histogram whose bins hold statistics of type `S`.
+    pub fn with_stats(n_bins: usize) -> Self {
+        assert!(n_bins >= 2, "n_bins must be at least 2");
+        Self {
+            n_bins,
+            bins: Vec::with_capacity(n_bins + 1),
+            stats: Vec::with_capacity(n_bins + 1),
+            weight: F::zero(),
+            min: F::infinity(),
+            max: F::neg_infinity(),
+        }
+    }
+    pub fn update(&mut self, x: F) {
+        self.update_weighted(x, F::one());
+    }
+    /// Add a value with a strictly positive weight.
+    pub fn update_weighted(&mut self, x: F, w: F) {
+        self.update_with(x, w, |_| {});
+    }
+    /// Add a value with a strictly positive weight, and update the statistics of its bin with
+    /// `update` before it is merged with another one.
+    pub fn update_with(&mut self, x: F, w: F, update: impl FnOnce(&mut S)) {
+        assert!(w > F::zero(), "weights must be strictly positive");
+        let i = self.insert(x, w, None);
+        update(&mut self.stats[i]);
+        self.weight += w;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.shrink();
+    }
+    /// Merge the histogram of another stream into this one.
+    pub fn merge(&mut self, other: &Histogram<F, S>) {
+        for ((c, w), stats) in other.bins.iter().zip(other.stats.iter()) {
+            self.insert(*c, *w, Some(stats));
+        }
+        self.weight += other.weight;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.shrink();
+    }
+    /// The estimated share of the weight which lies below or at `x`, or zero if no value has
+    /// been seen.
+    pub fn cdf(&self, x: F) -> F {
+        if self.weight == F::zero() || x < self.min {
+            return F::zero();
+        }
+        if x >= self.max {
+            return F::one();
+        }
+        let points = self.points();
+        let two = F::one() + F::one();
+        // Weight up to the centroid of the current point, which holds half of its bin
+        let mut cumulative = F::zero();
+        for pair in points.windows(2) {
+            let ((c1, w1), (c2, w2)) = (pair[0], pair[1]);
+            if x < c2 {
+                let t = (x - c1) / (c2 - c1);
+                let w = w1 + (w2 - w1) * t;
+                return (cumulative + w1 / two + (w1 + w) / two * t) / self.weight;
+            }
+            cumulative += w1;
+        }
+        F::one()
+    }
+    /// An estimate of the `q`-th quantile, or `None` if no value has been seen. It is the inverse
+    /// of [`Histogram::cdf`].
+    pub fn quantile(&self, q: F) -> Option<F> {
+        assert!(q >= F::zero() && q <= F::one(), "q must lie in [0, 1]");
+        if self.weight == F::zero() {
+            return None;
+        }
+        let points = self.points();
+        let two = F::one() + F::one();
+        let target = q * self.weight;
+        let mut cumulative = F::zero();
+        for pair in points.windows(2) {
+            let ((c1, w1), (c2, w2)) = (pair[0], pair[1]);
+            let at_c1 = cumulative + w1 / two;
+            let at_c2 = cumulative + w1 + w2 / two;
+            if target <= at_c2 {
+                // Solve the weight below c1 + t (c2 - c1), which is quadratic in t
+                let d = (target - at_c1).max(F::zero());
+                let a = (w2 - w1) / two;
+                let t = if a.abs() <= F::epsilon() * self.weight {
+                    d / w1
+                } else {
+                    (-w1 + (w1 * w1 + two * two * a * d).max(F::zero()).sqrt()) / (two * a)
+                };
+                return Some(c1 + t.max(F::zero()).min(F::one()) * (c2 - c1));
+            }
+            cumulative += w1;
+        }
+        Some(self.max)
+    }
+    /// The estimated density of the values at `x`, i.e. the derivative of [`Histogram::cdf`]
+    /// where it is continuous. It is zero outside of the range of the values seen.
+    pub fn pdf(&self, x: F) -> F {
+        if self.weight == F::zero() || x < self.min || x > self.max {
+            return F::zero();
+        }
+        for pair in self.points().windows(2) {
+            let ((c1, w1), (c2, w2)) = (pair[0], pair[1]);
+            if x <= c2 {
+                let t = (x - c1) / (c2 - c1);
+                return (w1 + (w2 - w1) * t) / ((c2 - c1) * self.weight);
+            }
+        }
+        F::zero()
+    }
+    /// The centroids of the bins, sorted by value, along with their weights.
+    pub fn bins(&self) -> &[(F, F)] {
+        &self.bins
+    }
+    /// The statistics of the bins, in the same order as [`Histogram::bins`].
+    pub fn stats(&self) -> &[S] {
+        &self.stats
+    }
+    /// Sum of the weights of the values seen.
+    pub fn weight(&self) -> F {
+        self.weight
+    }
+    /// The smallest and the largest values seen, or `None` if no value has been seen.
+    pub fn range(&self) -> Option<(F, F)> {
+        (self.weight > F::zero()).then_some((self.min, self.max))
+    }
+    // Add weight to the bin of `x`, which is created if there is none, and return its index.
+    fn insert(&mut self, x: F, w: F, stats: Option<&S>) -> usize {
+        let i = self.bins.partition_point(|(c, _)| *c < x);
+        if i < self.bins.len() && self.bins[i].0 == x {
+            self.bins[i].1 += w;
+            if let Some(stats) = stats {
+                self.stats[i].merge(stats);
+            }
+        } else {
+            self.bins.insert(i, (x, w));
+            self.stats.insert(i, stats.cloned().unwrap_or_default());
+        }
+        i
+    }
+    // Merge the two closest bins into their weighted centroid until there are at most `n_bins`.
+    fn shrink(&mut self) {
+        while self.bins.len() > self.n_bins {
+            let bins = &self.bins;
+            let j = (0..bins.len() - 1)
+                .min_by(|a, b| {
+                    let da = bins[a + 1].0 - bins[*a].0;
+                    let db = bins[b + 1].0 - bins[*b].0;
+                    da.partial_cmp(&db).unwrap()
+                })
+                .unwrap();
+            let (c2, w2) = self.bins.remove(j + 1);
+            let (c1, w1) = &mut self.bins[j];
+            *c1 = (*c1 * *w1 + c2 * w2) / (*w1 + w2);
+            *w1 += w2;
+            let stats = self.stats.remove(j + 1);
+            self.stats[j].merge(&stats);
+        }
+    }
+    // The bins, surrounded by the minimum and maximum with no weight when they aren't centroids.
+    fn points(&self) -> Vec<(F, F)> {
+        let mut points = Vec::with_capacity(self.bins.len() + 2);
+        if self.min < self.bins[0].0 {
+            points.push((self.min, F::zero()));
+        }
+        points.extend(self.bins.iter().copied());
+        if self.max > self.bins[self.bins.len() - 1].0 {
+            points.push((self.max, F::zero()));
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::permutation;
+
+    #[test]
+    fn test_cdf_and_quantile() {
+        let mut histogram = Histogram::new(64);
+        assert_eq!(histogram.quantile(0.5), None);
+        assert_eq!(histogram.cdf(1.0), 0.0);
+        for x in permutation(20000) {
+            histogram.update(x);
+        }
+        assert_eq!(histogram.weight(), 20000.0);
+        assert_eq!(histogram.cdf(0.0), 0.0);
+        assert_eq!(histogram.cdf(20000.0), 1.0);
+        assert_eq!(histogram.quantile(0.0), Some(1.0));
+        assert_eq!(histogram.quantile(1.0), Some(20000.0));
+        for q in [0.01, 0.1, 0.5, 0.9, 0.99] {
+            let x = histogram.quantile(q).unwrap();
+            assert!((x - q * 20000.0).abs() < 200.0, "q = {}: {}", q, x);
+            // The quantile is the inverse of the cdf
+            assert!((histogram.cdf(x) - q).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_few_values() {
+        let mut histogram = Histogram::new(4);
+        histogram.update_weighted(2.0, 3.0);
+        histogram.update(2.0);
+        assert_eq!(histogram.bins(), &[(2.0, 4.0)]);
+        assert_eq!(histogram.cdf(2.0), 1.0);
+        assert_eq!(histogram.quantile(0.3), Some(2.0));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut low = Histogram::new(32);
+        let mut high = Histogram::new(32);
+        for x in permutation(10000) {
+            if x <= 5000.0 {
+                low.update(x);
+            } else {
+                high.update(x);
+            }
+        }
+        low.merge(&high);
+        assert_eq!(low.bins().len(), 32);
+        assert_eq!(low.weight(), 10000.0);
+        for q in [0.1, 0.5, 0.9] {
+            let x = low.quantile(q).unwrap();
+            assert!((x - q * 10000.0).abs() < 200.0, "q = {}: {}", q, x);
+        }
+    }
+
+    #[test]
+    fn test_pdf() {
+        let mut histogram = Histogram::new(64);
+        assert_eq!(histogram.pdf(1.0), 0.0);
+        for x in permutation(20000) {
+            histogram.update(x);
+        }
+        for x in [2500.0, 5000.0, 12345.0, 15000.0] {
+            let density = histogram.pdf(x);
+            assert!(
+                (density * 20000.0 - 1.0).abs() < 0.1,
+                "x = {}: {}",
+                x,
+                density
+            );
+        }
+        assert_eq!(histogram.pdf(0.0), 0.0);
+        assert_eq!(histogram.pdf(20001.0), 0.0);
+        assert_eq!(histogram.range(), Some((1.0, 20000.0)));
+    }
+
+    // Sum of the values which fell in a bin
+    #[derive(Clone, Debug, Default)]
+    struct Sum(f64);
+
+    impl BinStats for Sum {
+        fn merge(&mut self, other: &Self) {
+            self.0 += other.0;
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut histogram: Histogram<f64, Sum> = Histogram::with_stats(16);
+        let mut other: Histogram<f64, Sum> = Histogram::with_stats(16);
+        for x in permutation(1000) {
+            let target = if x <= 500.0 {
+                &mut histogram
+            } else {
+                &mut other
+            };
+            target.update_with(x, 1.0, |sum| sum.0 += x);
+        }
+        histogram.merge(&other);
+        assert_eq!(histogram.stats().len(), 16);
+        let total = histogram.stats().iter().map(|sum| sum.0).sum::<f64>();
+        assert_eq!(total, 500500.0);
+        // The centroid of a bin is the mean of its values
+        for ((c, w), sum) in histogram.bins().iter().zip(histogram.stats()) {
+            assert!((c - sum.0 / w).abs() < 1e-9);
+        }
+    }
+}
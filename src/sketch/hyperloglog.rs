@@ -0,0 +1,136 @@
+use std::hash::Hash;
+
+use crate::sketch::hash;
+
+/// HyperLogLog cardinality estimator, which counts the distinct keys of a stream.
+///
+/// The hash of each key selects one of `2^precision` registers, which keeps the maximum number of
+/// leading zeros of the remaining bits plus one. The number of distinct keys is estimated from the
+/// harmonic mean of the registers, with a relative standard error of `1.04 / sqrt(2^precision)`,
+/// and with linear counting when many registers are empty. The memory is a byte per register
+/// however many keys are seen.
+///
+/// Estimators with the same precision can be merged, e.g. to count the distinct keys seen by
+/// several machines.
+///
+/// # Parameters
+///
+/// - `precision`: The number of bits of the hash which select the register, in `[4, 18]`.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::hyperloglog::HyperLogLog;
+///
+/// let mut hll = HyperLogLog::new(12);
+/// for i in 0..100000 {
+///     hll.update(&format!("user_{}", i % 5000));
+/// }
+/// assert!((hll.count() as f64 - 5000.0).abs() < 5000.0 * 0.05);
+/// ```
+///
+/// # References
+///
+/// [^1]: P. Flajolet, É. Fusy, O. Gandouet and F. Meunier (2007). "HyperLogLog: the analysis of a
+/// near-optimal cardinality estimation algorithm". Analysis of Algorithms, 137-156.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HyperLogLog {
+    precision: u32,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new(precision: u32) -> Self {
+        assert!(
+            (4..=18).contains(&precision),
+            "precision must lie in [4, 18]"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+    pub fn update<K: Hash + ?Sized>(&mut self, key: &K) {
+        let h = hash(key, 0);
+        let index = (h >> (64 - self.precision)) as usize;
+        // The leading zeros of the remaining bits, which are at most 64 - precision
+        let rank = ((h << self.precision).leading_zeros() + 1).min(64 - self.precision + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+    /// The estimated number of distinct keys.
+    pub fn count(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|r| (-(*r as f64)).exp2()).sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        // Linear counting is more accurate for small cardinalities
+        let estimate = if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as usize
+    }
+    /// Merge the estimator of another stream into this one.
+    ///
+    /// # Panics
+    ///
+    /// If the estimators don't have the same precision.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(
+            self.precision, other.precision,
+            "Only estimators with the same precision can be merged"
+        );
+        for (register, other_register) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other_register);
+        }
+    }
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy() {
+        let mut hll = HyperLogLog::new(14);
+        assert_eq!(hll.count(), 0);
+        for n in [10usize, 1000, 100000] {
+            for i in 0..n {
+                hll.update(&i);
+            }
+            let error = (hll.count() as f64 - n as f64).abs() / n as f64;
+            // Three standard errors
+            assert!(error < 3.0 * 1.04 / 128.0, "n = {}: {}", n, hll.count());
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = HyperLogLog::new(12);
+        let mut second = HyperLogLog::new(12);
+        let mut all = HyperLogLog::new(12);
+        for i in 0..20000usize {
+            // Half of the keys are seen by both
+            if i < 15000 {
+                first.update(&i);
+            }
+            if i >= 5000 {
+                second.update(&i);
+            }
+            all.update(&i);
+        }
+        first.merge(&second);
+        assert_eq!(first.count(), all.count());
+    }
+}
@@ -0,0 +1,75 @@
+pub mod bloom;
+pub mod count_min;
+pub mod gk;
+pub mod histogram;
+pub mod hyperloglog;
+pub mod p2;
+pub mod space_saving;
+pub mod tdigest;
+
+use std::hash::{Hash, Hasher};
+
+// 64-bit hash of a key. Different seeds give independent hash functions, and equal seeds the same
+// one across sketches, which can thus be merged.
+//
+// The hash is specified, unlike that of `DefaultHasher` which may change between Rust releases,
+// so that sketches built by different builds or on different platforms, e.g. serialized ones, can
+// still be merged: the seed and then the key are fed to 64-bit FNV-1a, with integers written as
+// little-endian bytes, and the result goes through the SplitMix64 finalizer.
+pub(crate) fn hash<K: Hash + ?Sized>(key: &K, seed: u64) -> u64 {
+    let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+    hasher.write_u64(seed);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+    // The width of usize depends on the platform
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+    // FNV-1a mixes the last bytes poorly into the high bits, which HyperLogLog relies on
+    fn finish(&self) -> u64 {
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_stable() {
+        // Values of the specification, computed independently
+        assert_eq!(hash("light-river", 0), 0xc3f6_3015_55f9_1ea2);
+        assert_eq!(hash(&42u64, 7), 0xa242_7cdc_4c5c_a59e);
+        assert_eq!(hash(&42usize, 7), 0xa242_7cdc_4c5c_a59e);
+        assert_ne!(hash("light-river", 0), hash("light-river", 1));
+    }
+}
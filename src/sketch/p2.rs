@@ -0,0 +1,158 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// P² estimator of a single quantile.
+///
+/// Five markers are maintained, whose heights approximate the minimum, the q/2-th, q-th and
+/// (1+q)/2-th quantiles and the maximum. The markers are moved with a piecewise-parabolic
+/// interpolation each time their position drifts from the desired one, so that the estimator runs
+/// in constant time and memory. The quantile is exact until five values have been seen.
+///
+/// Contrary to [`GKQuantile`](super::gk::GKQuantile) and [`TDigest`](super::tdigest::TDigest),
+/// the quantile to track is fixed beforehand, and estimators can't be merged.
+///
+/// # Parameters
+///
+/// - `q`: The quantile to track, between 0 and 1.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::p2::P2Quantile;
+/// use light_river::stats::Univariate;
+/// use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+///
+/// let mut median: P2Quantile<f64> = P2Quantile::new(0.5);
+/// let mut values: Vec<f64> = (1..=1000).map(f64::from).collect();
+/// values.shuffle(&mut StdRng::seed_from_u64(42));
+/// for value in values {
+///     median.update(value);
+/// }
+/// assert!((median.get() - 500.0).abs() < 10.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: R. Jain and I. Chlamtac (1985). "The P² algorithm for dynamic calculation of quantiles
+/// and histograms without storing observations". Communications of the ACM 28(10):1076-1085.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct P2Quantile<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    q: F,
+    heights: Vec<F>,
+    positions: [F; 5],
+    desired: [F; 5],
+    increments: [F; 5],
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> P2Quantile<F> {
+    pub fn new(q: F) -> Self {
+        assert!(q >= F::zero() && q <= F::one(), "q must lie in [0, 1]");
+        let f = |x: f64| F::from(x).unwrap();
+        let two = f(2.0);
+        Self {
+            q,
+            heights: Vec::with_capacity(5),
+            positions: [f(1.0), f(2.0), f(3.0), f(4.0), f(5.0)],
+            desired: [
+                f(1.0),
+                f(1.0) + two * q,
+                f(1.0) + f(4.0) * q,
+                f(3.0) + two * q,
+                f(5.0),
+            ],
+            increments: [F::zero(), q / two, q, (F::one() + q) / two, F::one()],
+        }
+    }
+    pub fn q(&self) -> F {
+        self.q
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for P2Quantile<F>
+{
+    fn update(&mut self, x: F) {
+        // The first five observations are the initial markers
+        if self.heights.len() < 5 {
+            self.heights.push(x);
+            self.heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            return;
+        }
+
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            (1..5).find(|i| x < self.heights[*i]).unwrap() - 1
+        };
+        for i in k + 1..5 {
+            self.positions[i] += F::one();
+        }
+        for i in 0..5 {
+            self.desired[i] += self.increments[i];
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let (n, h) = (&self.positions, &self.heights);
+            if (d >= F::one() && n[i + 1] - n[i] > F::one())
+                || (d <= -F::one() && n[i - 1] - n[i] < -F::one())
+            {
+                let d = d.signum();
+                let parabolic = h[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (h[i + 1] - h[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (h[i] - h[i - 1]) / (n[i] - n[i - 1]));
+                self.heights[i] = if h[i - 1] < parabolic && parabolic < h[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > F::zero() { i + 1 } else { i - 1 };
+                    h[i] + d * (h[j] - h[i]) / (n[j] - n[i])
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+    /// The estimated quantile, or zero if no value has been seen.
+    fn get(&self) -> F {
+        let n = self.heights.len();
+        if n == 0 {
+            return F::zero();
+        }
+        if n == 5 && self.positions[4] > F::from(5.0).unwrap() {
+            // The extreme markers are the exact minimum and maximum
+            return match self.q {
+                q if q == F::zero() => self.heights[0],
+                q if q == F::one() => self.heights[4],
+                _ => self.heights[2],
+            };
+        }
+        // Too few observations, the exact quantile is computed with a linear interpolation
+        let rank = self.q * F::from(n - 1).unwrap();
+        let lower = rank.floor().to_usize().unwrap();
+        let upper = rank.ceil().to_usize().unwrap();
+        let frac = rank - rank.floor();
+        self.heights[lower] + frac * (self.heights[upper] - self.heights[lower])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_warm_up() {
+        let mut p2 = P2Quantile::new(0.25);
+        assert_eq!(p2.get(), 0.0);
+        for x in [5.0, 1.0, 3.0] {
+            p2.update(x);
+        }
+        assert_eq!(p2.get(), 2.0);
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use num::{Float, FromPrimitive};
+
+/// Space-Saving summary of the most frequent keys of a stream, i.e. the heavy hitters.
+///
+/// At most `capacity` keys are monitored, each with a counter. When a key which isn't monitored
+/// arrives while the summary is full, the key with the smallest count is replaced by the new one,
+/// which inherits its count as an error bound. Every key whose count exceeds `total / capacity`
+/// is thus guaranteed to be monitored, and its count is overestimated by at most its error.
+///
+/// # Parameters
+///
+/// - `capacity`: The number of keys which are monitored.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::space_saving::SpaceSaving;
+///
+/// let mut summary: SpaceSaving<String, f64> = SpaceSaving::new(10);
+/// for i in 0..10000 {
+///     // "a" and "b" are frequent, the other keys are all distinct
+///     let key = match i % 4 {
+///         0 | 1 => "a".to_string(),
+///         2 => "b".to_string(),
+///         _ => format!("key_{}", i),
+///     };
+///     summary.update(key, 1.0);
+/// }
+/// let heavy_hitters: Vec<_> = summary.heavy_hitters(0.2).into_iter().map(|(k, _)| k).collect();
+/// assert_eq!(heavy_hitters, vec!["a", "b"]);
+/// assert!(summary.get("a") >= 5000.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: A. Metwally, D. Agrawal and A. El Abbadi (2005). "Efficient computation of frequent and
+/// top-k elements in data streams". International conference on database theory, 398-412.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpaceSaving<K: Eq + Hash, F> {
+    capacity: usize,
+    // Count and error of each monitored key
+    counters: HashMap<K, (F, F)>,
+    total: F,
+}
+
+impl<K, F> SpaceSaving<K, F>
+where
+    K: Eq + Hash + Clone,
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+{
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be strictly positive");
+        Self {
+            capacity,
+            counters: HashMap::with_capacity(capacity),
+            total: F::zero(),
+        }
+    }
+    /// Add a strictly positive count to a key.
+    pub fn update(&mut self, key: K, count: F) {
+        assert!(count > F::zero(), "counts must be strictly positive");
+        self.total += count;
+        if let Some((c, _)) = self.counters.get_mut(&key) {
+            *c += count;
+            return;
+        }
+        if self.counters.len() < self.capacity {
+            self.counters.insert(key, (count, F::zero()));
+            return;
+        }
+        let (smallest, min_count) = self
+            .counters
+            .iter()
+            .min_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
+            .map(|(k, (c, _))| (k.clone(), *c))
+            .unwrap();
+        self.counters.remove(&smallest);
+        self.counters.insert(key, (min_count + count, min_count));
+    }
+    /// The estimated count of a key, which is an upper bound of its true count if it is
+    /// monitored, and zero otherwise.
+    pub fn get<Q>(&self, key: &Q) -> F
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.counters.get(key).map_or(F::zero(), |(c, _)| *c)
+    }
+    /// The maximum overestimation of the count of a key, or `None` if it isn't monitored.
+    pub fn error<Q>(&self, key: &Q) -> Option<F>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.counters.get(key).map(|(_, e)| *e)
+    }
+    /// The `n` keys with the largest estimated counts, from the most to the least frequent.
+    pub fn top(&self, n: usize) -> Vec<(K, F)> {
+        let mut counts: Vec<(K, F)> = self
+            .counters
+            .iter()
+            .map(|(k, (c, _))| (k.clone(), *c))
+            .collect();
+        counts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        counts.truncate(n);
+        counts
+    }
+    /// The keys whose count is guaranteed to exceed a fraction `phi` of the total count, from the
+    /// most to the least frequent.
+    pub fn heavy_hitters(&self, phi: F) -> Vec<(K, F)> {
+        let threshold = phi * self.total;
+        let mut counts: Vec<(K, F)> = self
+            .counters
+            .iter()
+            .filter(|(_, (c, e))| *c - *e > threshold)
+            .map(|(k, (c, _))| (k.clone(), *c))
+            .collect();
+        counts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        counts
+    }
+    /// The sum of the counts of all the keys.
+    pub fn total(&self) -> F {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    #[test]
+    fn test_guarantees() {
+        let mut summary: SpaceSaving<usize, f64> = SpaceSaving::new(20);
+        let mut counts = vec![0.0; 500];
+        for i in 0..20000usize {
+            // Key 0 is a third of the stream, keys 1 to 4 a tenth, and the others are rare
+            let key = match i % 30 {
+                0..=9 => 0,
+                10..=12 => 1,
+                13..=15 => 2,
+                16..=18 => 3,
+                19..=21 => 4,
+                _ => 5 + scrambled(i, 495),
+            };
+            counts[key] += 1.0;
+            summary.update(key, 1.0);
+        }
+        for (key, count) in counts.iter().enumerate() {
+            if *count > summary.total() / 20.0 {
+                assert!(summary.get(&key) >= *count);
+                let error = summary.error(&key).unwrap();
+                assert!(summary.get(&key) - error <= *count);
+            }
+        }
+        let top: Vec<usize> = summary.top(5).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(top[0], 0);
+        let mut others = top[1..].to_vec();
+        others.sort();
+        assert_eq!(others, vec![1, 2, 3, 4]);
+        assert_eq!(summary.heavy_hitters(0.2).len(), 1);
+        assert_eq!(summary.get(&1000), 0.0);
+    }
+}
@@ -0,0 +1,232 @@
+use std::f64::consts::PI;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use num::{Float, FromPrimitive};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Centroid<F> {
+    mean: F,
+    weight: F,
+}
+
+/// Merging t-digest.
+///
+/// The values are summarized by clusters, the centroids, whose size is bounded by a scale
+/// function which keeps them small in the tails of the distribution. Quantiles are therefore
+/// estimated with a relative accuracy which is especially good for extreme quantiles, e.g. the
+/// 99.9th percentile. New values are buffered, and merged into the centroids once the buffer is
+/// full. The number of centroids is about `compression`.
+///
+/// Digests of different streams can be merged, e.g. to aggregate the digests computed on several
+/// machines.
+///
+/// # Parameters
+///
+/// - `compression`: The accuracy of the digest, 100 by default. The higher, the more centroids
+///   are kept.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::sketch::tdigest::TDigest;
+/// use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+///
+/// let mut digest: TDigest<f64> = TDigest::new(None);
+/// let mut values: Vec<f64> = (1..=10000).map(f64::from).collect();
+/// values.shuffle(&mut StdRng::seed_from_u64(42));
+/// for value in values {
+///     digest.update(value);
+/// }
+/// assert!((digest.quantile(0.5).unwrap() - 5000.0).abs() < 50.0);
+/// assert!((digest.quantile(0.999).unwrap() - 9990.0).abs() < 5.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: T. Dunning and O. Ertl (2019). "Computing extremely accurate quantiles using t-digests".
+/// arXiv:1902.04023.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TDigest<F> {
+    compression: F,
+    centroids: Vec<Centroid<F>>,
+    buffer: Vec<Centroid<F>>,
+    buffer_size: usize,
+    weight: F,
+    min: F,
+    max: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> TDigest<F> {
+    pub fn new(compression: Option<F>) -> Self {
+        let compression = compression.unwrap_or(F::from_f64(100.0).unwrap());
+        assert!(compression >= F::one(), "compression must be at least 1");
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            buffer_size: 5 * compression.ceil().to_usize().unwrap(),
+            weight: F::zero(),
+            min: F::infinity(),
+            max: F::neg_infinity(),
+        }
+    }
+    pub fn update(&mut self, x: F) {
+        self.update_weighted(x, F::one());
+    }
+    /// Add a value with a strictly positive weight.
+    pub fn update_weighted(&mut self, x: F, w: F) {
+        assert!(w > F::zero(), "weights must be strictly positive");
+        self.buffer.push(Centroid { mean: x, weight: w });
+        self.weight += w;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        if self.buffer.len() >= self.buffer_size {
+            self.flush();
+        }
+    }
+    /// Merge the digest of another stream into this one.
+    pub fn merge(&mut self, other: &TDigest<F>) {
+        self.buffer
+            .extend(other.centroids.iter().chain(other.buffer.iter()));
+        self.weight += other.weight;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.flush();
+    }
+    /// An estimate of the `q`-th quantile, or `None` if no value has been seen.
+    ///
+    /// The estimate is interpolated linearly between the means of the centroids, and the exact
+    /// minimum and maximum.
+    pub fn quantile(&self, q: F) -> Option<F> {
+        assert!(q >= F::zero() && q <= F::one(), "q must lie in [0, 1]");
+        if self.weight == F::zero() {
+            return None;
+        }
+        let mut digest = self.clone();
+        digest.flush();
+        let centroids = &digest.centroids;
+        let two = F::one() + F::one();
+        let target = q * self.weight;
+
+        let first = centroids[0];
+        if target < first.weight / two {
+            return Some(self.min + (first.mean - self.min) * target / (first.weight / two));
+        }
+        // Cumulative weight at the center of the current centroid
+        let mut center = first.weight / two;
+        for pair in centroids.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let next = center + (left.weight + right.weight) / two;
+            if target < next {
+                let t = (target - center) / (next - center);
+                return Some(left.mean + t * (right.mean - left.mean));
+            }
+            center = next;
+        }
+        let last = centroids[centroids.len() - 1];
+        let t = ((target - center) / (last.weight / two)).min(F::one());
+        Some(last.mean + t * (self.max - last.mean))
+    }
+    /// Sum of the weights of the values seen.
+    pub fn weight(&self) -> F {
+        self.weight
+    }
+    /// Number of centroids, once the buffered values are merged.
+    pub fn size(&self) -> usize {
+        let mut digest = self.clone();
+        digest.flush();
+        digest.centroids.len()
+    }
+    // Scale function k1, which maps a quantile to an index. A centroid may only span a unit of
+    // the index.
+    fn scale(&self, q: F) -> F {
+        let two = F::one() + F::one();
+        let q = q.max(F::zero()).min(F::one());
+        self.compression / F::from_f64(2.0 * PI).unwrap() * (two * q - F::one()).asin()
+    }
+    // Merge the buffered values into the centroids.
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut all: Vec<Centroid<F>> = self.centroids.drain(..).collect();
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap());
+
+        let mut merged = vec![all[0]];
+        let mut cumulative = F::zero();
+        let mut k_left = self.scale(F::zero());
+        for centroid in all.into_iter().skip(1) {
+            let current = merged.last_mut().unwrap();
+            let q_right = (cumulative + current.weight + centroid.weight) / self.weight;
+            if self.scale(q_right) - k_left <= F::one() {
+                let weight = current.weight + centroid.weight;
+                current.mean += (centroid.mean - current.mean) * centroid.weight / weight;
+                current.weight = weight;
+            } else {
+                cumulative += current.weight;
+                k_left = self.scale(cumulative / self.weight);
+                merged.push(centroid);
+            }
+        }
+        self.centroids = merged;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::permutation;
+
+    #[test]
+    fn test_accuracy() {
+        let mut digest = TDigest::new(None);
+        assert_eq!(digest.quantile(0.5), None);
+        for x in permutation(100000) {
+            digest.update(x);
+        }
+        assert!(digest.size() <= 200, "{}", digest.size());
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(100000.0));
+        for q in [0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999] {
+            // The values are their own ranks, whose error is below 0.1% of the number of values
+            let answer = digest.quantile(q).unwrap();
+            assert!(
+                (answer - q * 100000.0).abs() < 100.0,
+                "q = {}: {}",
+                q,
+                answer
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut low = TDigest::new(None);
+        let mut high = TDigest::new(None);
+        for x in permutation(10000) {
+            if x <= 5000.0 {
+                low.update(x);
+            } else {
+                high.update(x);
+            }
+        }
+        low.merge(&high);
+        assert_eq!(low.weight(), 10000.0);
+        for q in [0.1, 0.5, 0.9] {
+            let answer = low.quantile(q).unwrap();
+            assert!((answer - q * 10000.0).abs() < 50.0, "q = {}: {}", q, answer);
+        }
+    }
+
+    #[test]
+    fn test_weighted() {
+        let mut digest = TDigest::new(None);
+        digest.update_weighted(1.0, 9.0);
+        digest.update_weighted(10.0, 1.0);
+        assert_eq!(digest.quantile(0.4), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(10.0));
+    }
+}
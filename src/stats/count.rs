@@ -0,0 +1,112 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{RevertibleUnivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+/// Running count of the values.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::count::Count;
+/// use light_river::stats::{RevertibleUnivariate, Univariate};
+///
+/// let mut count: Count<f64> = Count::new();
+/// for x in [3.0, 1.0, 2.0] {
+///     count.update(x);
+/// }
+/// count.revert(1.0);
+/// assert_eq!(count.get(), 2.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Count<F> {
+    n: F,
+}
+
+impl<F: Float> Count<F> {
+    pub fn new() -> Self {
+        Self { n: F::zero() }
+    }
+}
+
+impl<F: Float> Default for Count<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Count<F>
+{
+    fn update(&mut self, _x: F) {
+        self.n += F::one();
+    }
+    fn get(&self) -> F {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Count<F>
+{
+    fn revert(&mut self, _x: F) {
+        self.n -= F::one();
+    }
+}
+
+/// Running sum of the values.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::count::Sum;
+/// use light_river::stats::Univariate;
+///
+/// let mut sum: Sum<f64> = Sum::new();
+/// for x in [3.0, 1.0, 2.0] {
+///     sum.update(x);
+/// }
+/// sum.update_weighted(2.0, 0.5);
+/// assert_eq!(sum.get(), 7.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sum<F> {
+    sum: F,
+}
+
+impl<F: Float + AddAssign> Sum<F> {
+    pub fn new() -> Self {
+        Self { sum: F::zero() }
+    }
+    /// Add a value with a weight. A negative weight removes the value.
+    pub fn update_weighted(&mut self, x: F, w: F) {
+        self.sum += w * x;
+    }
+}
+
+impl<F: Float + AddAssign> Default for Sum<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Sum<F>
+{
+    fn update(&mut self, x: F) {
+        self.sum += x;
+    }
+    fn get(&self) -> F {
+        self.sum
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Sum<F>
+{
+    fn revert(&mut self, x: F) {
+        self.sum -= x;
+    }
+}
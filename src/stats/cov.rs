@@ -0,0 +1,134 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{Bivariate, RevertibleBivariate};
+use num::{Float, FromPrimitive};
+
+/// Running covariance of two streams of values.
+///
+/// The sum of the co-deviations from the means is updated with a generalization of Welford's
+/// algorithm, and divided by `n - ddof`. The covariance is zero until more than `ddof` pairs are
+/// seen.
+///
+/// # Parameters
+///
+/// - `ddof`: The delta degrees of freedom, 1 by default for the unbiased estimator.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::cov::Cov;
+/// use light_river::stats::Bivariate;
+///
+/// let mut cov: Cov<f64> = Cov::new(None);
+/// for (x, y) in [(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)] {
+///     cov.update(x, y);
+/// }
+/// assert!((cov.get() - 2.0).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cov<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    ddof: usize,
+    n: F,
+    mean_x: F,
+    mean_y: F,
+    c: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Cov<F> {
+    pub fn new(ddof: Option<usize>) -> Self {
+        Self {
+            ddof: ddof.unwrap_or(1),
+            n: F::zero(),
+            mean_x: F::zero(),
+            mean_y: F::zero(),
+            c: F::zero(),
+        }
+    }
+    /// Add a pair of values with a weight. A negative weight removes the pair.
+    pub fn update_weighted(&mut self, x: F, y: F, w: F) {
+        self.n += w;
+        if self.n <= F::zero() {
+            self.n = F::zero();
+            self.mean_x = F::zero();
+            self.mean_y = F::zero();
+            self.c = F::zero();
+            return;
+        }
+        let dx = x - self.mean_x;
+        self.mean_x += w * dx / self.n;
+        self.mean_y += w * (y - self.mean_y) / self.n;
+        self.c += w * dx * (y - self.mean_y);
+    }
+    /// The sum of the weights of the pairs.
+    pub fn n(&self) -> F {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Bivariate<F>
+    for Cov<F>
+{
+    fn update(&mut self, x: F, y: F) {
+        self.update_weighted(x, y, F::one());
+    }
+    fn get(&self) -> F {
+        let ddof = F::from_usize(self.ddof).unwrap();
+        if self.n > ddof {
+            self.c / (self.n - ddof)
+        } else {
+            F::zero()
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleBivariate<F> for Cov<F>
+{
+    fn revert(&mut self, x: F, y: F) {
+        self.update_weighted(x, y, -F::one());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::var::Var;
+    use crate::stats::Univariate;
+    use crate::testing::{scrambled, scrambled_other};
+
+    #[test]
+    fn test_batch() {
+        let pairs: Vec<(f64, f64)> = (0..50)
+            .map(|i| (scrambled(i, 97) as f64, scrambled_other(i, 89) as f64))
+            .collect();
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+        let expected = pairs
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>()
+            / (n - 1.0);
+
+        let mut cov = Cov::new(None);
+        let mut var = Var::new(None);
+        for (x, y) in pairs.iter() {
+            cov.update(*x, *y);
+            var.update(*x);
+        }
+        assert!((cov.get() - expected).abs() < 1e-9);
+        // The covariance of a stream with itself is its variance
+        let mut self_cov = Cov::new(None);
+        for (x, _) in pairs.iter() {
+            self_cov.update(*x, *x);
+        }
+        assert!((self_cov.get() - var.get()).abs() < 1e-9);
+
+        for (x, y) in pairs.iter().rev().take(48) {
+            cov.revert(*x, *y);
+        }
+        let (a, b) = (pairs[0], pairs[1]);
+        assert!((cov.get() - (a.0 - b.0) * (a.1 - b.1) / 2.0).abs() < 1e-9);
+    }
+}
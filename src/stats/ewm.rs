@@ -0,0 +1,159 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Exponentially weighted mean.
+///
+/// Each value is given a weight `alpha`, and the weights of the past values are multiplied by
+/// `1 - alpha`, so that the mean tracks the recent values without storing them. The mean is
+/// initialized with the first value.
+///
+/// # Parameters
+///
+/// - `alpha`: The weight of the latest value, in `(0, 1]`. The higher, the faster the past is
+///   forgotten.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::ewm::EWMean;
+/// use light_river::stats::Univariate;
+///
+/// let mut mean: EWMean<f64> = EWMean::new(0.5);
+/// for x in [1.0, 3.0, 5.0] {
+///     mean.update(x);
+/// }
+/// assert_eq!(mean.get(), 3.5);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EWMean<F> {
+    alpha: F,
+    mean: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> EWMean<F> {
+    pub fn new(alpha: F) -> Self {
+        assert!(
+            alpha > F::zero() && alpha <= F::one(),
+            "alpha must lie in (0, 1]"
+        );
+        Self { alpha, mean: None }
+    }
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for EWMean<F>
+{
+    fn update(&mut self, x: F) {
+        self.mean = Some(match self.mean {
+            None => x,
+            Some(mean) => mean + self.alpha * (x - mean),
+        });
+    }
+    /// The mean, or zero if no value has been seen.
+    fn get(&self) -> F {
+        self.mean.unwrap_or(F::zero())
+    }
+}
+
+/// Exponentially weighted variance.
+///
+/// The variance is updated incrementally along with an exponentially weighted mean, with the
+/// same weights, which is numerically stable. It is zero until two values are seen.
+///
+/// # Parameters
+///
+/// - `alpha`: The weight of the latest value, in `(0, 1]`. The higher, the faster the past is
+///   forgotten.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::ewm::EWVar;
+/// use light_river::stats::Univariate;
+///
+/// let mut var: EWVar<f64> = EWVar::new(0.5);
+/// for x in [1.0, 3.0] {
+///     var.update(x);
+/// }
+/// assert_eq!(var.mean(), 2.0);
+/// assert_eq!(var.get(), 1.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: T. Finch (2009). "Incremental calculation of weighted mean and variance". University of
+/// Cambridge.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EWVar<F> {
+    mean: EWMean<F>,
+    var: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> EWVar<F> {
+    pub fn new(alpha: F) -> Self {
+        Self {
+            mean: EWMean::new(alpha),
+            var: F::zero(),
+        }
+    }
+    pub fn mean(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for EWVar<F>
+{
+    fn update(&mut self, x: F) {
+        if let Some(mean) = self.mean.mean {
+            let alpha = self.mean.alpha;
+            let diff = x - mean;
+            self.var = (F::one() - alpha) * (self.var + alpha * diff * diff);
+        }
+        self.mean.update(x);
+    }
+    fn get(&self) -> F {
+        self.var
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_level_shift() {
+        let mut mean = EWMean::new(0.1);
+        let mut var = EWVar::new(0.1);
+        for i in 0..200 {
+            let x = if i < 100 {
+                (i % 2) as f64
+            } else {
+                10.0 + (i % 2) as f64
+            };
+            mean.update(x);
+            var.update(x);
+        }
+        // The first values are forgotten
+        assert!((mean.get() - 10.5).abs() < 0.1);
+        assert!((var.mean() - mean.get()).abs() < 1e-12);
+        assert!((var.get() - 0.25).abs() < 0.05, "{}", var.get());
+    }
+
+    #[test]
+    fn test_alpha_one() {
+        // Only the latest value is remembered
+        let mut var = EWVar::new(1.0);
+        for x in [4.0, 2.0, 7.0] {
+            var.update(x);
+            assert_eq!((var.mean(), var.get()), (x, 0.0));
+        }
+    }
+}
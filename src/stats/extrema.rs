@@ -0,0 +1,96 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+/// Running minimum, which is infinite until a value is seen.
+///
+/// Unlike most statistics, the minimum can't be reverted without keeping all the values.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::extrema::Min;
+/// use light_river::stats::Univariate;
+///
+/// let mut min: Min<f64> = Min::new();
+/// assert_eq!(min.get(), f64::INFINITY);
+/// for x in [3.0, 1.0, 2.0] {
+///     min.update(x);
+/// }
+/// assert_eq!(min.get(), 1.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Min<F> {
+    min: F,
+}
+
+impl<F: Float> Min<F> {
+    pub fn new() -> Self {
+        Self { min: F::infinity() }
+    }
+}
+
+impl<F: Float> Default for Min<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Min<F>
+{
+    fn update(&mut self, x: F) {
+        self.min = self.min.min(x);
+    }
+    fn get(&self) -> F {
+        self.min
+    }
+}
+
+/// Running maximum, which is minus infinity until a value is seen.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::extrema::Max;
+/// use light_river::stats::Univariate;
+///
+/// let mut max: Max<f64> = Max::new();
+/// assert_eq!(max.get(), f64::NEG_INFINITY);
+/// for x in [1.0, 3.0, 2.0] {
+///     max.update(x);
+/// }
+/// assert_eq!(max.get(), 3.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Max<F> {
+    max: F,
+}
+
+impl<F: Float> Max<F> {
+    pub fn new() -> Self {
+        Self {
+            max: F::neg_infinity(),
+        }
+    }
+}
+
+impl<F: Float> Default for Max<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Max<F>
+{
+    fn update(&mut self, x: F) {
+        self.max = self.max.max(x);
+    }
+    fn get(&self) -> F {
+        self.max
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{RevertibleUnivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+/// Running mean.
+///
+/// The mean is updated incrementally, which is numerically stable, and supports weighted values.
+/// It is zero until a value is seen.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::mean::Mean;
+/// use light_river::stats::{RevertibleUnivariate, Univariate};
+///
+/// let mut mean: Mean<f64> = Mean::new();
+/// for x in [1.0, 2.0, 3.0, 4.0] {
+///     mean.update(x);
+/// }
+/// assert_eq!(mean.get(), 2.5);
+/// mean.revert(4.0);
+/// assert_eq!(mean.get(), 2.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mean<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    n: F,
+    mean: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Mean<F> {
+    pub fn new() -> Self {
+        Self {
+            n: F::zero(),
+            mean: F::zero(),
+        }
+    }
+    /// Add a value with a weight. A negative weight removes the value.
+    pub fn update_weighted(&mut self, x: F, w: F) {
+        self.n += w;
+        if self.n <= F::zero() {
+            self.n = F::zero();
+            self.mean = F::zero();
+        } else {
+            self.mean += w * (x - self.mean) / self.n;
+        }
+    }
+    /// The sum of the weights of the values.
+    pub fn n(&self) -> F {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default for Mean<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Mean<F>
+{
+    fn update(&mut self, x: F) {
+        self.update_weighted(x, F::one());
+    }
+    fn get(&self) -> F {
+        self.mean
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Mean<F>
+{
+    fn revert(&mut self, x: F) {
+        self.update_weighted(x, -F::one());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted() {
+        let mut mean: Mean<f64> = Mean::new();
+        assert_eq!(mean.get(), 0.0);
+        mean.update_weighted(1.0, 3.0);
+        mean.update_weighted(5.0, 1.0);
+        assert_eq!(mean.get(), 2.0);
+        assert_eq!(mean.n(), 4.0);
+        mean.update_weighted(1.0, -3.0);
+        assert_eq!(mean.get(), 5.0);
+        mean.revert(5.0);
+        assert_eq!((mean.n(), mean.get()), (0.0, 0.0));
+    }
+}
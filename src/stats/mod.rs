@@ -0,0 +1,33 @@
+pub mod count;
+pub mod cov;
+pub mod ewm;
+pub mod extrema;
+pub mod mean;
+pub mod moments;
+pub mod rolling;
+pub mod var;
+
+/// Trait for implementing a running statistic of a stream of values.
+pub trait Univariate<F> {
+    fn update(&mut self, x: F);
+    fn get(&self) -> F;
+}
+
+/// Trait for implementing a running statistic from which values can be removed, e.g. so that it
+/// can be computed over a window.
+pub trait RevertibleUnivariate<F>: Univariate<F> {
+    /// Remove a value which has been added with `update`.
+    fn revert(&mut self, x: F);
+}
+
+/// Trait for implementing a running statistic of a stream of pairs of values.
+pub trait Bivariate<F> {
+    fn update(&mut self, x: F, y: F);
+    fn get(&self) -> F;
+}
+
+/// Trait for implementing a running statistic from which pairs of values can be removed.
+pub trait RevertibleBivariate<F>: Bivariate<F> {
+    /// Remove a pair of values which has been added with `update`.
+    fn revert(&mut self, x: F, y: F);
+}
@@ -0,0 +1,267 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{RevertibleUnivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+// Running sums of the powers 2 to 4 of the deviations from the mean, using the update formulas
+// of Terriberry, which can be run backwards to remove a value.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct CentralMoments<F> {
+    n: F,
+    mean: F,
+    m2: F,
+    m3: F,
+    m4: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> CentralMoments<F> {
+    fn new() -> Self {
+        Self {
+            n: F::zero(),
+            mean: F::zero(),
+            m2: F::zero(),
+            m3: F::zero(),
+            m4: F::zero(),
+        }
+    }
+    fn update(&mut self, x: F) {
+        let f = |v: f64| F::from_f64(v).unwrap();
+        let n = self.n;
+        self.n += F::one();
+        let delta = x - self.mean;
+        let delta_n = delta / self.n;
+        let term = delta * delta_n * n;
+        self.mean += delta_n;
+        self.m4 += term * delta_n * delta_n * (self.n * self.n - f(3.0) * self.n + f(3.0))
+            + f(6.0) * delta_n * delta_n * self.m2
+            - f(4.0) * delta_n * self.m3;
+        self.m3 += term * delta_n * (self.n - f(2.0)) - f(3.0) * delta_n * self.m2;
+        self.m2 += term;
+    }
+    fn revert(&mut self, x: F) {
+        let f = |v: f64| F::from_f64(v).unwrap();
+        if self.n <= F::one() {
+            *self = Self::new();
+            return;
+        }
+        let n = self.n - F::one();
+        let mean = (self.n * self.mean - x) / n;
+        let delta = x - mean;
+        let delta_n = delta / self.n;
+        let term = delta * delta_n * n;
+        // The updates are undone in the reverse order
+        self.m2 -= term;
+        self.m3 -= term * delta_n * (self.n - f(2.0)) - f(3.0) * delta_n * self.m2;
+        self.m4 -= term * delta_n * delta_n * (self.n * self.n - f(3.0) * self.n + f(3.0))
+            + f(6.0) * delta_n * delta_n * self.m2
+            - f(4.0) * delta_n * self.m3;
+        self.mean = mean;
+        self.n = n;
+    }
+}
+
+/// Running skewness.
+///
+/// The biased estimator is `g1 = m3 / m2^(3/2)`, where `mk` is the k-th central moment of the
+/// values. The unbiased estimator, i.e. the adjusted Fisher-Pearson coefficient, is
+/// `g1 * sqrt(n * (n - 1)) / (n - 2)`. The skewness is zero until at least 2, or 3 if unbiased,
+/// values are seen, or if all the values are equal.
+///
+/// # Parameters
+///
+/// - `bias`: Whether the biased estimator is used.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::moments::Skew;
+/// use light_river::stats::Univariate;
+///
+/// let mut skew: Skew<f64> = Skew::new(true);
+/// for x in [1.0, 1.0, 1.0, 5.0] {
+///     skew.update(x);
+/// }
+/// assert!((skew.get() - 1.1547005383792515).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Skew<F> {
+    moments: CentralMoments<F>,
+    bias: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Skew<F> {
+    pub fn new(bias: bool) -> Self {
+        Self {
+            moments: CentralMoments::new(),
+            bias,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Skew<F>
+{
+    fn update(&mut self, x: F) {
+        self.moments.update(x);
+    }
+    fn get(&self) -> F {
+        let CentralMoments { n, m2, m3, .. } = self.moments;
+        let two = F::one() + F::one();
+        if n < two || m2 <= F::zero() {
+            return F::zero();
+        }
+        let g1 = n.sqrt() * m3 / m2.powf(F::from_f64(1.5).unwrap());
+        if self.bias {
+            g1
+        } else if n > two {
+            g1 * (n * (n - F::one())).sqrt() / (n - two)
+        } else {
+            F::zero()
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Skew<F>
+{
+    fn revert(&mut self, x: F) {
+        self.moments.revert(x);
+    }
+}
+
+/// Running excess kurtosis.
+///
+/// The biased estimator is `g2 = m4 / m2^2 - 3`, where `mk` is the k-th central moment of the
+/// values, so that the kurtosis of a normal distribution is zero. The unbiased estimator is
+/// `((n + 1) * g2 + 6) * (n - 1) / ((n - 2) * (n - 3))`. The kurtosis is zero until at least 2,
+/// or 4 if unbiased, values are seen, or if all the values are equal.
+///
+/// # Parameters
+///
+/// - `bias`: Whether the biased estimator is used.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::moments::Kurtosis;
+/// use light_river::stats::Univariate;
+///
+/// let mut kurtosis: Kurtosis<f64> = Kurtosis::new(true);
+/// for x in [1.0, 2.0, 3.0, 4.0] {
+///     kurtosis.update(x);
+/// }
+/// assert!((kurtosis.get() + 1.36).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kurtosis<F> {
+    moments: CentralMoments<F>,
+    bias: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Kurtosis<F> {
+    pub fn new(bias: bool) -> Self {
+        Self {
+            moments: CentralMoments::new(),
+            bias,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Kurtosis<F>
+{
+    fn update(&mut self, x: F) {
+        self.moments.update(x);
+    }
+    fn get(&self) -> F {
+        let f = |v: f64| F::from_f64(v).unwrap();
+        let CentralMoments { n, m2, m4, .. } = self.moments;
+        if n < f(2.0) || m2 <= F::zero() {
+            return F::zero();
+        }
+        let g2 = n * m4 / (m2 * m2) - f(3.0);
+        if self.bias {
+            g2
+        } else if n > f(3.0) {
+            ((n + F::one()) * g2 + f(6.0)) * (n - F::one()) / ((n - f(2.0)) * (n - f(3.0)))
+        } else {
+            F::zero()
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Kurtosis<F>
+{
+    fn revert(&mut self, x: F) {
+        self.moments.revert(x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    fn values() -> Vec<f64> {
+        (0..200)
+            .map(|i| (scrambled(i, 101) as f64 / 10.0).powi(2))
+            .collect()
+    }
+
+    // Batch estimates of the biased skewness and kurtosis
+    fn batch(values: &[f64]) -> (f64, f64) {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let m = |k: i32| values.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+        (m(3) / m(2).powf(1.5), m(4) / m(2).powi(2) - 3.0)
+    }
+
+    #[test]
+    fn test_batch() {
+        let values = values();
+        let (mut skew, mut kurtosis) = (Skew::new(true), Kurtosis::new(true));
+        for x in values.iter() {
+            skew.update(*x);
+            kurtosis.update(*x);
+        }
+        let (expected_skew, expected_kurtosis) = batch(&values);
+        assert!((skew.get() - expected_skew).abs() < 1e-9);
+        assert!((kurtosis.get() - expected_kurtosis).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_revert() {
+        let values = values();
+        let (mut skew, mut kurtosis) = (Skew::new(false), Kurtosis::new(false));
+        let (mut tail_skew, mut tail_kurtosis) = (Skew::new(false), Kurtosis::new(false));
+        for x in values.iter() {
+            skew.update(*x);
+            kurtosis.update(*x);
+        }
+        for x in values[..120].iter() {
+            skew.revert(*x);
+            kurtosis.revert(*x);
+        }
+        for x in values[120..].iter() {
+            tail_skew.update(*x);
+            tail_kurtosis.update(*x);
+        }
+        assert!((skew.get() - tail_skew.get()).abs() < 1e-6);
+        assert!((kurtosis.get() - tail_kurtosis.get()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let mut skew = Skew::new(false);
+        let mut kurtosis = Kurtosis::new(false);
+        for _ in 0..10 {
+            skew.update(2.0);
+            kurtosis.update(2.0);
+        }
+        assert_eq!((skew.get(), kurtosis.get()), (0.0, 0.0));
+    }
+}
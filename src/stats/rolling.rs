@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{RevertibleUnivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+/// Rolling wrapper for running statistics.
+///
+/// The wrapped statistic is computed over a sliding window of the last `window_size` values. When
+/// the window is full, the oldest value is removed from the statistic with its `revert` method.
+///
+/// # Parameters
+///
+/// - `stat`: The statistic to compute over the window. It should be freshly initialized.
+/// - `window_size`: The number of values to keep track of.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::mean::Mean;
+/// use light_river::stats::rolling::Rolling;
+/// use light_river::stats::Univariate;
+///
+/// let mut mean: Rolling<f64, Mean<f64>> = Rolling::new(Mean::new(), 3);
+/// for x in [1.0, 2.0, 3.0, 4.0, 5.0] {
+///     mean.update(x);
+/// }
+/// // Only the last three values are taken into account
+/// assert_eq!(mean.get(), 4.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rolling<F, S> {
+    stat: S,
+    window_size: usize,
+    window: VecDeque<F>,
+    _float: PhantomData<F>,
+}
+
+impl<F, S> Rolling<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: RevertibleUnivariate<F>,
+{
+    pub fn new(stat: S, window_size: usize) -> Self {
+        assert!(window_size > 0, "window_size must be strictly positive");
+        Self {
+            stat,
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+            _float: PhantomData,
+        }
+    }
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    pub fn stat(&self) -> &S {
+        &self.stat
+    }
+}
+
+impl<F, S> Univariate<F> for Rolling<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: RevertibleUnivariate<F>,
+{
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().unwrap();
+            self.stat.revert(oldest);
+        }
+        self.stat.update(x);
+        self.window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.stat.get()
+    }
+}
+
+impl<F, S> RevertibleUnivariate<F> for Rolling<F, S>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    S: RevertibleUnivariate<F>,
+{
+    /// Reverting is only possible for the most recent value of the window, as the values that
+    /// were pushed out of it can't be brought back.
+    fn revert(&mut self, x: F) {
+        self.stat.revert(x);
+        self.window.pop_back();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::moments::Skew;
+    use crate::stats::var::Var;
+    use crate::testing::scrambled;
+
+    #[test]
+    fn test_rolling_matches_last_window() {
+        let values: Vec<f64> = (0..100).map(|i| scrambled(i, 101) as f64).collect();
+        for window_size in [1, 7, 30] {
+            let mut rolling_var = Rolling::new(Var::new(None), window_size);
+            let mut rolling_skew = Rolling::new(Skew::new(true), window_size);
+            for (i, x) in values.iter().enumerate() {
+                rolling_var.update(*x);
+                rolling_skew.update(*x);
+                let (mut var, mut skew) = (Var::new(None), Skew::new(true));
+                for y in values[(i + 1).saturating_sub(window_size)..=i].iter() {
+                    var.update(*y);
+                    skew.update(*y);
+                }
+                assert!((rolling_var.get() - var.get()).abs() < 1e-6);
+                assert!((rolling_skew.get() - skew.get()).abs() < 1e-6);
+            }
+            assert_eq!(rolling_var.len(), window_size);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::stats::{RevertibleUnivariate, Univariate};
+use num::{Float, FromPrimitive};
+
+/// Running variance, using Welford's algorithm.
+///
+/// The sum of the squared deviations from the mean is divided by `n - ddof`, where `n` is the sum
+/// of the weights of the values. The variance is zero until more than `ddof` values are seen.
+///
+/// # Parameters
+///
+/// - `ddof`: The delta degrees of freedom, 1 by default for the unbiased estimator.
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stats::var::Var;
+/// use light_river::stats::Univariate;
+///
+/// let mut var: Var<f64> = Var::new(None);
+/// for x in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     var.update(x);
+/// }
+/// assert_eq!(var.mean(), 5.0);
+/// assert!((var.get() - 32.0 / 7.0).abs() < 1e-12);
+/// ```
+///
+/// # References
+///
+/// [^1]: B. P. Welford (1962). "Note on a method for calculating corrected sums of squares and
+/// products". Technometrics 4(3):419-420.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Var<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    ddof: usize,
+    n: F,
+    mean: F,
+    m2: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Var<F> {
+    pub fn new(ddof: Option<usize>) -> Self {
+        Self {
+            ddof: ddof.unwrap_or(1),
+            n: F::zero(),
+            mean: F::zero(),
+            m2: F::zero(),
+        }
+    }
+    /// Add a value with a weight. A negative weight removes the value.
+    pub fn update_weighted(&mut self, x: F, w: F) {
+        self.n += w;
+        if self.n <= F::zero() {
+            self.n = F::zero();
+            self.mean = F::zero();
+            self.m2 = F::zero();
+            return;
+        }
+        let delta = x - self.mean;
+        self.mean += w * delta / self.n;
+        self.m2 = (self.m2 + w * delta * (x - self.mean)).max(F::zero());
+    }
+    /// Combine the statistics of another stream of values into these ones, e.g. to aggregate
+    /// those computed on several machines.
+    pub fn merge(&mut self, other: &Var<F>) {
+        let n = self.n + other.n;
+        if n <= F::zero() {
+            return;
+        }
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.n / n;
+        self.m2 += other.m2 + delta * delta * self.n * other.n / n;
+        self.n = n;
+    }
+    /// The sum of the weights of the values.
+    pub fn n(&self) -> F {
+        self.n
+    }
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Univariate<F>
+    for Var<F>
+{
+    fn update(&mut self, x: F) {
+        self.update_weighted(x, F::one());
+    }
+    fn get(&self) -> F {
+        let ddof = F::from_usize(self.ddof).unwrap();
+        if self.n > ddof {
+            self.m2 / (self.n - ddof)
+        } else {
+            F::zero()
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    RevertibleUnivariate<F> for Var<F>
+{
+    fn revert(&mut self, x: F) {
+        self.update_weighted(x, -F::one());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scrambled;
+
+    #[test]
+    fn test_revert() {
+        let values: Vec<f64> = (0..100).map(|i| scrambled(i, 101) as f64).collect();
+        let mut all = Var::new(None);
+        let mut tail = Var::new(None);
+        for x in values.iter() {
+            all.update(*x);
+        }
+        for x in values[50..].iter() {
+            tail.update(*x);
+        }
+        for x in values[..50].iter() {
+            all.revert(*x);
+        }
+        assert!((all.get() - tail.get()).abs() < 1e-9);
+        assert!((all.mean() - tail.mean()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut all = Var::new(None);
+        let (mut left, mut right) = (Var::new(None), Var::new(None));
+        for (i, (x, w)) in [(1.0, 1.0), (2.0, 2.0), (4.0, 1.0), (-3.0, 0.5), (7.0, 3.0)]
+            .into_iter()
+            .enumerate()
+        {
+            all.update_weighted(x, w);
+            if i < 2 {
+                left.update_weighted(x, w);
+            } else {
+                right.update_weighted(x, w);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.n(), all.n());
+        assert!((left.mean() - all.mean()).abs() < 1e-10);
+        assert!((left.get() - all.get()).abs() < 1e-10);
+        // Merging an empty variance changes nothing
+        left.merge(&Var::new(None));
+        assert!((left.get() - all.get()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ddof() {
+        let mut population = Var::new(Some(0));
+        let mut sample = Var::new(None);
+        assert_eq!(sample.get(), 0.0);
+        for x in [1.0, 3.0] {
+            population.update(x);
+            sample.update(x);
+        }
+        assert_eq!(population.get(), 1.0);
+        assert_eq!(sample.get(), 2.0);
+    }
+}
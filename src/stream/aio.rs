@@ -0,0 +1,287 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+use std::pin::pin;
+use std::task::Poll;
+use std::thread;
+
+use futures::channel::mpsc;
+use futures::executor::block_on;
+#[cfg(feature = "json")]
+use futures::io::{AsyncBufRead, AsyncBufReadExt};
+use futures::{future, SinkExt, Stream, StreamExt};
+use num::{Float, FromPrimitive};
+
+use super::data_stream::Data;
+#[cfg(feature = "json")]
+use super::jsonl_stream::{json_line, JsonlStreamError, JsonlStreamOptions};
+use crate::common::{ModelTarget, ModelType, Observation};
+use crate::evaluate::{predict, Checkpoint, EvaluateOptions, Evaluation};
+use crate::metrics::traits::Metric;
+
+/// An observation along with its target, as yielded by the streams.
+pub type Instance<F> = (Observation<F>, Option<Data<F>>);
+
+/// Turns a blocking iterator, e.g. a [`CsvStream`](super::csv_stream::CsvStream) or a dataset,
+/// into an asynchronous stream.
+///
+/// The iterator is consumed on a thread of its own, so that reading a file or waiting for a
+/// message doesn't block the executor, and its items are sent through a channel holding at most
+/// `buffer` items ahead of the stream. The thread stops once the iterator is exhausted or the
+/// stream is dropped.
+///
+/// This requires the `async` feature.
+///
+/// # Parameters
+///
+/// - `iter`: The items to yield.
+/// - `buffer`: The number of items which are read ahead.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::TryStreamExt;
+/// use light_river::stream::aio::from_blocking;
+/// use light_river::stream::csv_stream::{CsvStream, CsvStreamOptions};
+///
+/// let content = "temperature,rain\n12.5,1\n18.0,0\n";
+/// let stream = CsvStream::<f64, _>::new(content.as_bytes(), CsvStreamOptions::default()).unwrap();
+///
+/// let rows: Vec<_> = block_on(from_blocking(stream, 16).try_collect()).unwrap();
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(rows[1].0.get_numeric("temperature"), Some(18.0));
+/// ```
+pub fn from_blocking<I>(iter: I, buffer: usize) -> impl Stream<Item = I::Item>
+where
+    I: IntoIterator + Send + 'static,
+    I::Item: Send + 'static,
+{
+    let (mut sender, receiver) = mpsc::channel(buffer);
+    thread::spawn(move || {
+        for item in iter {
+            // The stream has been dropped
+            if block_on(sender.send(item)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Iterates asynchronously over the lines of a JSON Lines (or NDJSON) input, as observations
+/// along with their target.
+///
+/// This is the asynchronous counterpart of a [`JsonlStream`](super::jsonl_stream::JsonlStream),
+/// which reads the lines without blocking, e.g. from a socket or an HTTP body. Tokio readers can
+/// be adapted with `tokio_util::compat`.
+///
+/// This requires the `async` and `json` features.
+///
+/// # Parameters
+///
+/// - `reader`: The input.
+/// - `options`: The target field and the flattening of nested objects, see
+///   [`JsonlStreamOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::StreamExt;
+/// use light_river::stream::aio::jsonl_stream;
+/// use light_river::stream::data_stream::Data;
+/// use light_river::stream::jsonl_stream::JsonlStreamOptions;
+///
+/// let content = "{\"age\": 31, \"clicked\": true}\n{\"age\": 25, \"clicked\": false}\n";
+/// let options = JsonlStreamOptions {
+///     target: Some("clicked".to_string()),
+///     ..Default::default()
+/// };
+/// let stream = jsonl_stream::<f64, _>(content.as_bytes(), options);
+///
+/// let rows: Vec<_> = block_on(stream.collect());
+/// let (x, y) = rows[0].as_ref().unwrap();
+/// assert_eq!(x.get_numeric("age"), Some(31.0));
+/// assert_eq!(y, &Some(Data::Bool(true)));
+/// ```
+#[cfg(feature = "json")]
+pub fn jsonl_stream<F, R>(
+    reader: R,
+    options: JsonlStreamOptions,
+) -> impl Stream<Item = Result<Instance<F>, JsonlStreamError>>
+where
+    F: Float + std::str::FromStr,
+    R: AsyncBufRead,
+{
+    reader.lines().enumerate().filter_map(move |(i, line)| {
+        let row = match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some(json_line(&line, i + 1, &options)),
+            Err(e) => Some(Err(e.into())),
+        };
+        future::ready(row)
+    })
+}
+
+/// Evaluate a model on an asynchronous stream with progressive validation.
+///
+/// This is the asynchronous counterpart of
+/// [`progressive_val_score`](crate::evaluate::progressive_val_score): each sample is first used to
+/// make a prediction, which updates the metric, and then to train the model. The evaluation gives
+/// way to the other tasks of the executor after each sample, so that it doesn't hold a thread of
+/// the executor while the stream is ready.
+///
+/// This requires the `async` feature.
+///
+/// # Parameters
+///
+/// - `stream`: The samples along with their targets.
+/// - `model`: The model to evaluate, which is trained along the way.
+/// - `metric`: The metric to update.
+/// - `options`: How often to report the metric.
+///
+/// Returns the checkpoints of the metric, the last one being its value on the whole stream.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::stream;
+/// use light_river::common::{ModelTarget, ModelType, Observation};
+/// use light_river::evaluate::EvaluateOptions;
+/// use light_river::learner::Regressor;
+/// use light_river::metrics::regression::MAE;
+/// use light_river::metrics::traits::Metric;
+/// use light_river::stream::aio::progressive_val_score;
+///
+/// // Predicts the last target seen
+/// struct Last(f64);
+///
+/// impl Regressor<f64> for Last {
+///     fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+///         self.0 = y;
+///     }
+///     fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+///         self.0
+///     }
+/// }
+///
+/// let samples = stream::iter((0..10).map(|i| (Observation::new(), ModelTarget::Regression(i as f64))));
+/// let mut model = ModelType::Regressor(Box::new(Last(0.0)));
+/// let mut metric = Metric::Regression(Box::new(MAE::new()));
+///
+/// let checkpoints = block_on(progressive_val_score(
+///     samples,
+///     &mut model,
+///     &mut metric,
+///     EvaluateOptions::default(),
+/// ));
+/// assert_eq!(checkpoints[0].n_samples, 10);
+/// assert!((checkpoints[0].value - 0.9).abs() < 1e-10);
+/// ```
+pub async fn progressive_val_score<F>(
+    stream: impl Stream<Item = (Observation<F>, ModelTarget<F>)>,
+    model: &mut ModelType<F>,
+    metric: &mut Metric<F>,
+    options: EvaluateOptions,
+) -> Vec<Checkpoint<F>>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+{
+    let mut evaluation = Evaluation::new(options);
+    let mut stream = pin!(stream);
+    while let Some((x, y)) = stream.next().await {
+        let y_pred = predict(model, &x);
+        evaluation.reveal(model, metric, &x, y, &y_pred);
+        yield_now().await;
+    }
+    evaluation.finish(metric)
+}
+
+// Let the executor run the other tasks before resuming.
+async fn yield_now() {
+    let mut yielded = false;
+    future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate;
+    use crate::learner::Regressor;
+    use crate::metrics::regression::MAE;
+
+    // Predicts the mean of the targets seen so far
+    struct RunningMean(f64, f64);
+
+    impl Regressor<f64> for RunningMean {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+            self.0 += 1.0;
+            self.1 += (y - self.1) / self.0;
+        }
+        fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_same_as_blocking() {
+        let samples = || {
+            (0..100).map(|i| {
+                let x = Observation::from([("x".to_string(), i as f64)]);
+                (x, ModelTarget::Regression((i * 7 % 10) as f64))
+            })
+        };
+        let options = || EvaluateOptions {
+            step: Some(30),
+            ..Default::default()
+        };
+        let mut model = ModelType::Regressor(Box::new(RunningMean(0.0, 0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let expected =
+            evaluate::progressive_val_score(samples(), &mut model, &mut metric, options());
+
+        let mut model = ModelType::Regressor(Box::new(RunningMean(0.0, 0.0)));
+        let mut metric = Metric::Regression(Box::new(MAE::new()));
+        let stream = from_blocking(samples(), 4);
+        let checkpoints = block_on(progressive_val_score(
+            stream,
+            &mut model,
+            &mut metric,
+            options(),
+        ));
+        let values = |c: &[Checkpoint<f64>]| -> Vec<(usize, f64)> {
+            c.iter().map(|c| (c.n_samples, c.value)).collect()
+        };
+        assert_eq!(values(&checkpoints), values(&expected));
+        assert_eq!(checkpoints.len(), 4);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_jsonl_errors() {
+        let content = "{\"a\": 1}\n\n[1]\n{";
+        let rows: Vec<_> = block_on(
+            jsonl_stream::<f64, _>(content.as_bytes(), JsonlStreamOptions::default()).collect(),
+        );
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].as_ref().unwrap().0.get_numeric("a"), Some(1.0));
+        // The blank line is skipped, but counted
+        assert_eq!(
+            rows[1].as_ref().unwrap_err().to_string(),
+            "Line 3 isn't a JSON object"
+        );
+        assert!(matches!(
+            rows[2],
+            Err(JsonlStreamError::Json { line: 4, .. })
+        ));
+    }
+}
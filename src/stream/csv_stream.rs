@@ -0,0 +1,357 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use csv::{Reader, ReaderBuilder, StringRecord};
+use num::Float;
+
+use super::data_stream::Data;
+use crate::common::{FeatureValue, Observation};
+
+/// Type of a column of a [`CsvStream`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Floating point numbers, which make numeric features.
+    Numeric,
+    /// Integers, which make numeric features, and whose targets are suited to classification.
+    Int,
+    /// `true` or `false`, in any case, which make numeric features equal to 1 or 0.
+    Bool,
+    /// Strings, which make categorical features.
+    Categorical,
+    /// The column is left out.
+    Ignore,
+}
+
+/// Options of a [`CsvStream`].
+///
+/// - `target`: The column of the target, if any, which is left out of the features.
+/// - `delimiter`: The field delimiter, `b','` by default.
+/// - `schema`: The type of some columns. The type of the other columns is inferred from the first
+///   rows, see [`infer_rows`](Self::infer_rows).
+/// - `infer_rows`: The number of rows the types are inferred from, 100 by default. A column is of
+///   the first type among `Int`, `Numeric` and `Bool` which all of its values in these rows can be
+///   parsed as, and `Categorical` otherwise.
+/// - `missing_values`: The values which mean that a field is missing, besides the fields missing
+///   at the end of a short row. By default, the empty string, `NA` and `NaN`.
+#[derive(Clone, Debug)]
+pub struct CsvStreamOptions {
+    pub target: Option<String>,
+    pub delimiter: u8,
+    pub schema: HashMap<String, ColumnType>,
+    pub infer_rows: usize,
+    pub missing_values: Vec<String>,
+}
+
+impl Default for CsvStreamOptions {
+    fn default() -> Self {
+        Self {
+            target: None,
+            delimiter: b',',
+            schema: HashMap::new(),
+            infer_rows: 100,
+            missing_values: ["", "NA", "NaN"].map(String::from).to_vec(),
+        }
+    }
+}
+
+/// Error of a [`CsvStream`].
+#[derive(Debug)]
+pub enum CsvStreamError {
+    /// The CSV couldn't be read.
+    Csv(csv::Error),
+    /// A column of the options isn't in the header.
+    MissingColumn(String),
+    /// A value doesn't match the type of its column.
+    Parse {
+        line: u64,
+        column: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for CsvStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvStreamError::Csv(e) => write!(f, "{}", e),
+            CsvStreamError::MissingColumn(column) => write!(f, "No column named '{}'", column),
+            CsvStreamError::Parse {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "Invalid value '{}' of column '{}' on line {}",
+                value, column, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CsvStreamError {}
+
+impl From<csv::Error> for CsvStreamError {
+    fn from(e: csv::Error) -> Self {
+        CsvStreamError::Csv(e)
+    }
+}
+
+/// Iterates over the rows of a CSV with a header, as observations along with their target.
+///
+/// Each row yields the observation made of its features, and the value of the target column,
+/// which is `None` when the row lacks it or when there is no target column. The target is a
+/// [`Data`] of the type of its column, e.g. a float for regression or an integer for
+/// classification. Missing values are [`FeatureValue::Missing`] features. Rows may be shorter
+/// than the header, in which case their last fields are missing.
+///
+/// The types of the columns are either given or inferred from the first rows when the stream is
+/// created, after which a value which doesn't match the type of its column is an error.
+///
+/// # Parameters
+///
+/// - `reader`: The CSV, which [`from_path`](Self::from_path) opens from a file.
+/// - `options`: The target, the delimiter and the types of the columns, see
+///   [`CsvStreamOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::stream::csv_stream::{ColumnType, CsvStream, CsvStreamOptions};
+/// use light_river::stream::data_stream::Data;
+///
+/// let content = "city;temperature;rain\nParis;12.5;1\nLyon;NA;0\nNice;18.0\n";
+/// let options = CsvStreamOptions {
+///     target: Some("rain".to_string()),
+///     delimiter: b';',
+///     ..Default::default()
+/// };
+/// let mut stream = CsvStream::<f64, _>::new(content.as_bytes(), options).unwrap();
+/// assert_eq!(stream.schema()["temperature"], ColumnType::Numeric);
+///
+/// let (x, y) = stream.next().unwrap().unwrap();
+/// assert_eq!(x.get_numeric("temperature"), Some(12.5));
+/// assert_eq!(y, Some(Data::Int(1)));
+/// let (x, _) = stream.next().unwrap().unwrap();
+/// assert_eq!(x.get_numeric("temperature"), None);
+/// // The target of the last row is missing
+/// let (_, y) = stream.next().unwrap().unwrap();
+/// assert_eq!(y, None);
+/// assert!(stream.next().is_none());
+/// ```
+pub struct CsvStream<F: Float + std::str::FromStr, R: std::io::Read> {
+    reader: Reader<R>,
+    headers: Vec<String>,
+    types: Vec<ColumnType>,
+    target: Option<usize>,
+    missing_values: Vec<String>,
+    // Rows read to infer the types, which are yielded first
+    buffer: VecDeque<StringRecord>,
+    _float: PhantomData<F>,
+}
+
+impl<F: Float + std::str::FromStr + fmt::Display> CsvStream<F, File> {
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        options: CsvStreamOptions,
+    ) -> Result<Self, CsvStreamError> {
+        Self::new(File::open(path).map_err(csv::Error::from)?, options)
+    }
+}
+
+impl<F: Float + std::str::FromStr + fmt::Display, R: std::io::Read> CsvStream<F, R> {
+    pub fn new(reader: R, options: CsvStreamOptions) -> Result<Self, CsvStreamError> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .flexible(true)
+            .from_reader(reader);
+        let headers: Vec<String> = reader.headers()?.iter().map(String::from).collect();
+        for column in options.schema.keys().chain(options.target.iter()) {
+            if !headers.contains(column) {
+                return Err(CsvStreamError::MissingColumn(column.clone()));
+            }
+        }
+        let mut buffer = VecDeque::new();
+        for record in reader.records().take(options.infer_rows) {
+            buffer.push_back(record?);
+        }
+        let missing_values = options.missing_values;
+        let types = headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| match options.schema.get(header) {
+                Some(column_type) => *column_type,
+                None => {
+                    let values: Vec<&str> = buffer
+                        .iter()
+                        .filter_map(|record| record.get(i))
+                        .filter(|value| !missing_values.iter().any(|m| m == value))
+                        .collect();
+                    infer::<F>(&values)
+                }
+            })
+            .collect();
+        let target = options
+            .target
+            .map(|target| headers.iter().position(|h| *h == target).unwrap());
+        Ok(Self {
+            reader,
+            headers,
+            types,
+            target,
+            missing_values,
+            buffer,
+            _float: PhantomData,
+        })
+    }
+    /// The type of each column, whether given or inferred.
+    pub fn schema(&self) -> HashMap<&str, ColumnType> {
+        self.headers
+            .iter()
+            .map(String::as_str)
+            .zip(self.types.iter().copied())
+            .collect()
+    }
+    // The value of a field, which is `None` when it is missing or ignored.
+    fn parse(&self, record: &StringRecord, i: usize) -> Result<Option<Data<F>>, CsvStreamError> {
+        let Some(value) = record.get(i) else {
+            return Ok(None);
+        };
+        if self.missing_values.iter().any(|m| m == value) {
+            return Ok(None);
+        }
+        let data = match self.types[i] {
+            ColumnType::Numeric => value.parse().ok().map(Data::Scalar),
+            ColumnType::Int => value.parse().ok().map(Data::Int),
+            ColumnType::Bool => parse_bool(value).map(Data::Bool),
+            ColumnType::Categorical => Some(Data::String(value.to_string())),
+            ColumnType::Ignore => return Ok(None),
+        };
+        match data {
+            Some(data) => Ok(Some(data)),
+            None => Err(CsvStreamError::Parse {
+                line: record.position().map_or(0, |p| p.line()),
+                column: self.headers[i].clone(),
+                value: value.to_string(),
+            }),
+        }
+    }
+    fn row(
+        &self,
+        record: &StringRecord,
+    ) -> Result<(Observation<F>, Option<Data<F>>), CsvStreamError> {
+        let mut x = Observation::new();
+        let mut y = None;
+        for (i, header) in self.headers.iter().enumerate() {
+            let data = self.parse(record, i)?;
+            if Some(i) == self.target {
+                y = data;
+            } else if self.types[i] != ColumnType::Ignore {
+                let value = match &data {
+                    Some(data) => FeatureValue::from(data),
+                    None => FeatureValue::Missing,
+                };
+                x.insert(header.clone(), value);
+            }
+        }
+        Ok((x, y))
+    }
+}
+
+// Whether a string is a boolean, in any case.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+// The first type which all the values can be parsed as.
+fn infer<F: std::str::FromStr>(values: &[&str]) -> ColumnType {
+    if values.is_empty() {
+        ColumnType::Categorical
+    } else if values.iter().all(|v| v.parse::<i32>().is_ok()) {
+        ColumnType::Int
+    } else if values.iter().all(|v| v.parse::<F>().is_ok()) {
+        ColumnType::Numeric
+    } else if values.iter().all(|v| parse_bool(v).is_some()) {
+        ColumnType::Bool
+    } else {
+        ColumnType::Categorical
+    }
+}
+
+impl<F: Float + std::str::FromStr + fmt::Display, R: std::io::Read> Iterator for CsvStream<F, R> {
+    type Item = Result<(Observation<F>, Option<Data<F>>), CsvStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.buffer.pop_front() {
+            Some(record) => record,
+            None => match self.reader.records().next()? {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e.into())),
+            },
+        };
+        Some(self.row(&record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str =
+        "id,size,color,sold,price\n1,2,red,true,10\n2,3.5,blue,FALSE,12\n3,,red,true\n";
+
+    #[test]
+    fn test_inference() {
+        let options = CsvStreamOptions {
+            target: Some("price".to_string()),
+            schema: HashMap::from([("id".to_string(), ColumnType::Ignore)]),
+            ..Default::default()
+        };
+        let stream = CsvStream::<f64, _>::new(CONTENT.as_bytes(), options).unwrap();
+        assert_eq!(
+            stream.schema(),
+            HashMap::from([
+                ("id", ColumnType::Ignore),
+                ("size", ColumnType::Numeric),
+                ("color", ColumnType::Categorical),
+                ("sold", ColumnType::Bool),
+                ("price", ColumnType::Int),
+            ])
+        );
+        let rows: Vec<(Observation<f64>, Option<Data<f64>>)> = stream.map(Result::unwrap).collect();
+        let mut x = Observation::from([("size".to_string(), 3.5), ("sold".to_string(), 0.0)]);
+        x.insert("color", FeatureValue::Categorical("blue".to_string()));
+        assert_eq!(rows[1], (x, Some(Data::Int(12))));
+        assert_eq!(rows[2].0.get("size"), Some(&FeatureValue::Missing));
+        assert_eq!(rows[2].1, None);
+    }
+
+    #[test]
+    fn test_errors() {
+        // The types are inferred from the first row only
+        let options = CsvStreamOptions {
+            infer_rows: 1,
+            ..Default::default()
+        };
+        let mut stream = CsvStream::<f64, _>::new(CONTENT.as_bytes(), options).unwrap();
+        assert!(stream.next().unwrap().is_ok());
+        let error = stream.next().unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value '3.5' of column 'size' on line 3"
+        );
+        assert!(stream.next().unwrap().is_ok());
+
+        let options = CsvStreamOptions {
+            target: Some("weight".to_string()),
+            ..Default::default()
+        };
+        let error = CsvStream::<f64, _>::new(CONTENT.as_bytes(), options).err();
+        assert!(matches!(error, Some(CsvStreamError::MissingColumn(_))));
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::common::{ClassifierTarget, Observation};
+use crate::common::{ClassifierTarget, FeatureValue, Observation};
 use num::Float;
 
 /// This enum allows you to choose whether to define a single target (Name) or multiple targets (MultipleNames).
@@ -57,6 +57,7 @@ impl<F: Float + std::fmt::Display + std::str::FromStr> Data<F> {
         }
     }
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
             Data::Scalar(v) => v.to_string(),
@@ -67,6 +68,26 @@ impl<F: Float + std::fmt::Display + std::str::FromStr> Data<F> {
     }
 }
 
+impl<F: Float + std::fmt::Display + std::str::FromStr> From<&Data<F>> for FeatureValue<F> {
+    fn from(data: &Data<F>) -> Self {
+        match data {
+            Data::String(s) if s.is_empty() => FeatureValue::Missing,
+            Data::String(s) => FeatureValue::Categorical(s.clone()),
+            _ => FeatureValue::Numeric(data.to_float().unwrap()),
+        }
+    }
+}
+
+impl<F: Float + std::fmt::Display + std::str::FromStr> From<&HashMap<String, Data<F>>>
+    for Observation<F>
+{
+    fn from(row: &HashMap<String, Data<F>>) -> Self {
+        row.iter()
+            .map(|(name, data)| (name.clone(), FeatureValue::from(data)))
+            .collect()
+    }
+}
+
 pub enum DataStream<F: Float + std::str::FromStr> {
     X(HashMap<String, Data<F>>),
     XY(HashMap<String, Data<F>>, HashMap<String, Data<F>>),
@@ -97,16 +118,11 @@ impl<F: Float + std::str::FromStr + std::fmt::Display> DataStream<F> {
             DataStream::XY(_, y) => Ok(y),
         }
     }
+    /// Features of the row. Strings are categorical features, except empty ones which are
+    /// missing values.
     pub fn get_observation(&self) -> Observation<F> {
         match self {
-            DataStream::X(x) | DataStream::XY(x, _) => {
-                x.iter()
-                    .filter_map(|(k, v)| match v.to_float() {
-                        Ok(f_value) => Some((k.clone(), f_value)),
-                        Err(_) => None, // Ignore non-convertible data types
-                    })
-                    .collect()
-            }
+            DataStream::X(x) | DataStream::XY(x, _) => Observation::from(x),
         }
     }
 }
@@ -102,6 +102,7 @@ impl<F: Float + std::str::FromStr, R: std::io::Read> Iterator for IterCsv<F, R>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::FeatureValue;
     use maplit::{hashmap, hashset};
     use std::{collections::HashMap, fs::File};
     use tempfile::tempdir;
@@ -187,7 +188,7 @@ mod tests {
     #[test]
     fn test_iter_multiple_target() {
         let content = "Name,Height,Weight\nAlice,1.6,60.0\nBob,1.8,80.0";
-        let result = vec![
+        let result = [
             hashmap! {
                 "x".to_string() => hashmap!{
                     "Name".to_string() => Data::<f32>::String("Alice".to_string()),
@@ -223,4 +224,26 @@ mod tests {
             assert!(line.get_y().is_ok());
         }
     }
+
+    #[test]
+    fn test_observation() {
+        let content = "Name,Height,Weight\nAlice,1.6,\nBob,1.8,80.0";
+        let mut iter_csv = IterCsv::<f32, &[u8]>::new(content.as_bytes(), None).unwrap();
+
+        let observation = iter_csv.next().unwrap().unwrap().get_observation();
+        let features: Vec<(&String, &FeatureValue<f32>)> = observation.iter().collect();
+        assert_eq!(
+            features,
+            vec![
+                (&"Height".to_string(), &FeatureValue::Numeric(1.6)),
+                (
+                    &"Name".to_string(),
+                    &FeatureValue::Categorical("Alice".to_string())
+                ),
+                (&"Weight".to_string(), &FeatureValue::Missing),
+            ]
+        );
+        let observation = iter_csv.next().unwrap().unwrap().get_observation();
+        assert_eq!(observation.get_numeric("Weight"), Some(80.0));
+    }
 }
@@ -0,0 +1,297 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::Path;
+
+use num::Float;
+use serde_json::{Map, Value};
+
+use super::data_stream::Data;
+use crate::common::{FeatureValue, Observation};
+
+/// Options of a [`JsonlStream`].
+///
+/// - `target`: The field of the target, if any, which is left out of the features. The field of
+///   a nested object is named after its path, e.g. `label.value`.
+/// - `separator`: The separator of the keys in the names of the fields of nested objects, `.` by
+///   default.
+/// - `flatten`: Whether the fields of nested objects and the items of arrays are features, named
+///   after their path, e.g. `user.age` or `tags.0`, which is the default. They are left out
+///   otherwise.
+#[derive(Clone, Debug)]
+pub struct JsonlStreamOptions {
+    pub target: Option<String>,
+    pub separator: String,
+    pub flatten: bool,
+}
+
+impl Default for JsonlStreamOptions {
+    fn default() -> Self {
+        Self {
+            target: None,
+            separator: ".".to_string(),
+            flatten: true,
+        }
+    }
+}
+
+/// Error of a [`JsonlStream`].
+#[derive(Debug)]
+pub enum JsonlStreamError {
+    /// The input couldn't be read.
+    Io(std::io::Error),
+    /// A line isn't valid JSON.
+    Json {
+        line: usize,
+        error: serde_json::Error,
+    },
+    /// A line is valid JSON, but not an object.
+    NotAnObject { line: usize },
+}
+
+impl fmt::Display for JsonlStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonlStreamError::Io(e) => write!(f, "{}", e),
+            JsonlStreamError::Json { line, error } => {
+                write!(f, "Invalid JSON on line {}: {}", line, error)
+            }
+            JsonlStreamError::NotAnObject { line } => {
+                write!(f, "Line {} isn't a JSON object", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonlStreamError {}
+
+impl From<std::io::Error> for JsonlStreamError {
+    fn from(e: std::io::Error) -> Self {
+        JsonlStreamError::Io(e)
+    }
+}
+
+/// Iterates over the lines of a JSON Lines (or NDJSON) input, as observations along with their
+/// target.
+///
+/// Each line is a JSON object, and yields the observation made of its fields, along with the
+/// value of the target field, which is `None` when the line lacks it or when there is no target
+/// field. Blank lines are skipped. Numbers are numeric features, booleans numeric features equal
+/// to 1 or 0, strings categorical features, and nulls missing features. The target is a [`Data`]
+/// of the type of its value, with integers as [`Data::Int`] so that they suit classification.
+///
+/// This requires the `json` feature.
+///
+/// # Parameters
+///
+/// - `reader`: The input, which [`from_path`](Self::from_path) opens from a file.
+/// - `options`: The target field and the flattening of nested objects, see
+///   [`JsonlStreamOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::FeatureValue;
+/// use light_river::stream::data_stream::Data;
+/// use light_river::stream::jsonl_stream::{JsonlStream, JsonlStreamOptions};
+///
+/// let content = r#"{"user": {"age": 31, "country": "fr"}, "clicked": true}
+/// {"user": {"age": null, "country": "de"}, "clicked": false}
+/// "#;
+/// let options = JsonlStreamOptions {
+///     target: Some("clicked".to_string()),
+///     ..Default::default()
+/// };
+/// let mut stream = JsonlStream::<f64, _>::new(content.as_bytes(), options);
+///
+/// let (x, y) = stream.next().unwrap().unwrap();
+/// assert_eq!(x.get_numeric("user.age"), Some(31.0));
+/// assert_eq!(x.get("user.country"), Some(&FeatureValue::Categorical("fr".to_string())));
+/// assert_eq!(y, Some(Data::Bool(true)));
+/// let (x, _) = stream.next().unwrap().unwrap();
+/// assert_eq!(x.get("user.age"), Some(&FeatureValue::Missing));
+/// assert!(stream.next().is_none());
+/// ```
+pub struct JsonlStream<F, R: std::io::Read> {
+    lines: Lines<BufReader<R>>,
+    options: JsonlStreamOptions,
+    // Number of the last line read, from 1
+    line: usize,
+    _float: std::marker::PhantomData<F>,
+}
+
+impl<F: Float + std::str::FromStr> JsonlStream<F, File> {
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        options: JsonlStreamOptions,
+    ) -> Result<Self, JsonlStreamError> {
+        Ok(Self::new(File::open(path)?, options))
+    }
+}
+
+impl<F: Float + std::str::FromStr, R: std::io::Read> JsonlStream<F, R> {
+    pub fn new(reader: R, options: JsonlStreamOptions) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines(),
+            options,
+            line: 0,
+            _float: std::marker::PhantomData,
+        }
+    }
+}
+
+/// The observation and the target of a non blank line, whose number is given for the errors.
+pub(crate) fn json_line<F: Float + std::str::FromStr>(
+    line: &str,
+    number: usize,
+    options: &JsonlStreamOptions,
+) -> Result<(Observation<F>, Option<Data<F>>), JsonlStreamError> {
+    match serde_json::from_str(line) {
+        Ok(Value::Object(object)) => Ok(json_row(&object, options)),
+        Ok(_) => Err(JsonlStreamError::NotAnObject { line: number }),
+        Err(error) => Err(JsonlStreamError::Json {
+            line: number,
+            error,
+        }),
+    }
+}
+
+/// The observation made of the fields of a JSON object, along with the value of its target field,
+/// as described in [`JsonlStream`].
+pub(crate) fn json_row<F: Float + std::str::FromStr>(
+    object: &Map<String, Value>,
+    options: &JsonlStreamOptions,
+) -> (Observation<F>, Option<Data<F>>) {
+    let (mut x, mut y) = (Observation::new(), None);
+    add_object(options, "", object, &mut x, &mut y);
+    (x, y)
+}
+
+// Add the fields of an object to the features, prefixing their names by the path of the object,
+// and take the target out of them.
+fn add_object<F: Float + std::str::FromStr>(
+    options: &JsonlStreamOptions,
+    prefix: &str,
+    object: &Map<String, Value>,
+    x: &mut Observation<F>,
+    y: &mut Option<Data<F>>,
+) {
+    for (key, value) in object.iter() {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}{}{}", prefix, options.separator, key)
+        };
+        add(options, name, value, x, y);
+    }
+}
+
+fn add<F: Float + std::str::FromStr>(
+    options: &JsonlStreamOptions,
+    name: String,
+    value: &Value,
+    x: &mut Observation<F>,
+    y: &mut Option<Data<F>>,
+) {
+    if options.target.as_ref() == Some(&name) {
+        *y = data(value);
+        return;
+    }
+    match value {
+        Value::Object(object) if options.flatten => add_object(options, &name, object, x, y),
+        Value::Array(items) if options.flatten => {
+            for (i, item) in items.iter().enumerate() {
+                let name = format!("{}{}{}", name, options.separator, i);
+                add(options, name, item, x, y);
+            }
+        }
+        Value::Object(_) | Value::Array(_) => {}
+        Value::Null => {
+            x.insert(name, FeatureValue::Missing);
+        }
+        Value::Bool(b) => {
+            x.insert(name, FeatureValue::Numeric(F::from(*b as i32).unwrap()));
+        }
+        Value::Number(n) => {
+            let value = n.as_f64().and_then(F::from);
+            x.insert(
+                name,
+                value.map_or(FeatureValue::Missing, FeatureValue::Numeric),
+            );
+        }
+        Value::String(s) => {
+            x.insert(name, FeatureValue::Categorical(s.clone()));
+        }
+    }
+}
+
+// The target given by a JSON value, which is `None` for a null or a nested value.
+fn data<F: Float + std::str::FromStr>(value: &Value) -> Option<Data<F>> {
+    match value {
+        Value::Bool(b) => Some(Data::Bool(*b)),
+        Value::String(s) => Some(Data::String(s.clone())),
+        Value::Number(n) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) => Some(Data::Int(n)),
+            None => n.as_f64().and_then(F::from).map(Data::Scalar),
+        },
+        _ => None,
+    }
+}
+
+impl<F: Float + std::str::FromStr, R: std::io::Read> Iterator for JsonlStream<F, R> {
+    type Item = Result<(Observation<F>, Option<Data<F>>), JsonlStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            self.line += 1;
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => break line,
+                Err(e) => return Some(Err(e.into())),
+            }
+        };
+        Some(json_line(&line, self.line, &self.options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten() {
+        let content =
+            "{\"a\": 1.5, \"b\": {\"c\": [1, 2], \"d\": {\"e\": \"x\"}}, \"y\": 2.5}\n\n[1]\n{";
+        let options = |flatten| JsonlStreamOptions {
+            target: Some("b/d/e".to_string()),
+            separator: "/".to_string(),
+            flatten,
+        };
+        let mut stream = JsonlStream::<f64, _>::new(content.as_bytes(), options(true));
+        let (x, y) = stream.next().unwrap().unwrap();
+        let features: Vec<(&String, f64)> = x.numeric().collect();
+        assert_eq!(
+            features,
+            [
+                (&"a".to_string(), 1.5),
+                (&"b/c/0".to_string(), 1.0),
+                (&"b/c/1".to_string(), 2.0),
+                (&"y".to_string(), 2.5),
+            ]
+        );
+        assert_eq!(y, Some(Data::String("x".to_string())));
+        // The blank line is skipped
+        let error = stream.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Line 3 isn't a JSON object");
+        assert!(matches!(
+            stream.next(),
+            Some(Err(JsonlStreamError::Json { line: 4, .. }))
+        ));
+        assert!(stream.next().is_none());
+
+        let mut stream = JsonlStream::<f64, _>::new(content.as_bytes(), options(false));
+        let (x, y) = stream.next().unwrap().unwrap();
+        assert_eq!(x.len(), 2);
+        assert_eq!(y, None);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use apache_avro::reader::datum::GenericDatumReader;
+use apache_avro::Schema;
+use kafka::consumer::{Consumer, FetchOffset, GroupOffsetStorage};
+use num::Float;
+use serde_json::Value;
+
+use super::data_stream::Data;
+use super::jsonl_stream::{json_row, JsonlStreamOptions};
+use crate::common::Observation;
+
+/// Encoding of the messages of a [`KafkaStream`].
+#[derive(Clone, Debug)]
+pub enum Payload {
+    /// A JSON object.
+    Json,
+    /// An Avro record of the given schema, without any header.
+    Avro(Schema),
+    /// An Avro record of the given schema, after the 5 bytes header of the Confluent wire format,
+    /// i.e. a zero byte followed by the id of the schema in the registry, which is ignored.
+    ConfluentAvro(Schema),
+}
+
+/// Options of a [`KafkaStream`].
+///
+/// - `payload`: The encoding of the messages, JSON by default.
+/// - `fields`: How the fields of a message make the features and the target, see
+///   [`JsonlStreamOptions`].
+/// - `group`: The consumer group, whose offsets are committed to Kafka, `light-river` by default.
+/// - `from_beginning`: Whether the group starts from the earliest message of the partitions it
+///   has no committed offset for, rather than from the latest one, which is the default.
+/// - `commit_every`: The number of messages after which the offsets are committed, if any. The
+///   offsets of the messages which have been yielded are committed when the next message is asked
+///   for, i.e. once they have been learned from. By default, they are only committed by
+///   [`KafkaStream::commit`].
+/// - `max_empty_polls`: The number of polls in a row without any message after which the stream
+///   ends, if any. By default, the stream waits for new messages forever.
+#[derive(Clone, Debug)]
+pub struct KafkaStreamOptions {
+    pub payload: Payload,
+    pub fields: JsonlStreamOptions,
+    pub group: String,
+    pub from_beginning: bool,
+    pub commit_every: Option<usize>,
+    pub max_empty_polls: Option<usize>,
+}
+
+impl Default for KafkaStreamOptions {
+    fn default() -> Self {
+        Self {
+            payload: Payload::Json,
+            fields: JsonlStreamOptions::default(),
+            group: "light-river".to_string(),
+            from_beginning: false,
+            commit_every: None,
+            max_empty_polls: None,
+        }
+    }
+}
+
+/// Error of a [`KafkaStream`].
+#[derive(Debug)]
+pub enum KafkaStreamError {
+    /// The messages couldn't be fetched, or the offsets couldn't be committed.
+    Kafka(kafka::Error),
+    /// A message isn't valid JSON.
+    Json(serde_json::Error),
+    /// A message isn't a valid Avro record of the schema.
+    Avro(apache_avro::Error),
+    /// A message lacks the header of the Confluent wire format.
+    InvalidHeader,
+    /// A message is valid, but not an object or a record.
+    NotAnObject,
+}
+
+impl fmt::Display for KafkaStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KafkaStreamError::Kafka(e) => write!(f, "{}", e),
+            KafkaStreamError::Json(e) => write!(f, "Invalid JSON message: {}", e),
+            KafkaStreamError::Avro(e) => write!(f, "Invalid Avro message: {}", e),
+            KafkaStreamError::InvalidHeader => write!(f, "Invalid Confluent wire format header"),
+            KafkaStreamError::NotAnObject => write!(f, "The message isn't an object"),
+        }
+    }
+}
+
+impl std::error::Error for KafkaStreamError {}
+
+impl From<kafka::Error> for KafkaStreamError {
+    fn from(e: kafka::Error) -> Self {
+        KafkaStreamError::Kafka(e)
+    }
+}
+
+impl From<serde_json::Error> for KafkaStreamError {
+    fn from(e: serde_json::Error) -> Self {
+        KafkaStreamError::Json(e)
+    }
+}
+
+impl From<apache_avro::Error> for KafkaStreamError {
+    fn from(e: apache_avro::Error) -> Self {
+        KafkaStreamError::Avro(e)
+    }
+}
+
+// A message which has been fetched but not yielded yet.
+struct Message {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    value: Vec<u8>,
+}
+
+/// Consumes a Kafka topic, as observations along with their target.
+///
+/// Each message is an object, either JSON or an Avro record, and yields the observation made of
+/// its fields, along with the value of the target field, as a line of a
+/// [`JsonlStream`](super::jsonl_stream::JsonlStream) does. A message which can't be decoded
+/// yields an error, after which the stream goes on.
+///
+/// The stream consumes the topic on behalf of a consumer group, so that training can be restarted
+/// where it stopped: [`commit`](Self::commit) commits the offsets of the messages yielded so far,
+/// which is meant to be called after they have been learned from, or the stream does it every
+/// few messages, see [`KafkaStreamOptions::commit_every`]. The messages yielded after the last
+/// commit are consumed again after a restart.
+///
+/// This requires the `kafka` feature.
+///
+/// # Parameters
+///
+/// - `hosts`: The brokers, e.g. `localhost:9092`.
+/// - `topic`: The topic to consume.
+/// - `options`: The encoding of the messages, the consumer group and the commits, see
+///   [`KafkaStreamOptions`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use light_river::common::ClassifierTarget;
+/// use light_river::learner::Classifier;
+/// use light_river::linear_model::logistic_regression::LogisticRegression;
+/// use light_river::optim::losses::Log;
+/// use light_river::optim::optimizers::SGD;
+/// use light_river::stream::data_stream::Data;
+/// use light_river::stream::jsonl_stream::JsonlStreamOptions;
+/// use light_river::stream::kafka_stream::{KafkaStream, KafkaStreamOptions};
+///
+/// let options = KafkaStreamOptions {
+///     fields: JsonlStreamOptions {
+///         target: Some("clicked".to_string()),
+///         ..Default::default()
+///     },
+///     from_beginning: true,
+///     max_empty_polls: Some(10),
+///     ..Default::default()
+/// };
+/// let mut stream = KafkaStream::<f64>::new(vec!["localhost:9092".to_string()], "clicks", options)
+///     .unwrap();
+/// let mut model = LogisticRegression::new(SGD::new(0.1), Log, Default::default());
+/// while let Some(row) = stream.next() {
+///     let (x, y) = row.unwrap();
+///     if let Some(Data::Bool(y)) = y {
+///         model.learn_one(&x, ClassifierTarget::from(y));
+///     }
+///     stream.commit().unwrap();
+/// }
+/// ```
+pub struct KafkaStream<F> {
+    consumer: Consumer,
+    payload: Payload,
+    fields: JsonlStreamOptions,
+    commit_every: Option<usize>,
+    max_empty_polls: Option<usize>,
+    buffer: VecDeque<Message>,
+    // Offset of the last message yielded from each partition, which isn't committed yet
+    pending: HashMap<(String, i32), i64>,
+    // Number of messages yielded since the last commit
+    n_pending: usize,
+    _float: std::marker::PhantomData<F>,
+}
+
+impl<F: Float + std::str::FromStr> KafkaStream<F> {
+    pub fn new(
+        hosts: Vec<String>,
+        topic: &str,
+        options: KafkaStreamOptions,
+    ) -> Result<Self, KafkaStreamError> {
+        assert!(
+            options.commit_every != Some(0),
+            "commit_every must be strictly positive"
+        );
+        let fallback_offset = if options.from_beginning {
+            FetchOffset::Earliest
+        } else {
+            FetchOffset::Latest
+        };
+        let consumer = Consumer::from_hosts(hosts)
+            .with_topic(topic.to_string())
+            .with_group(options.group)
+            .with_fallback_offset(fallback_offset)
+            .with_offset_storage(Some(GroupOffsetStorage::Kafka))
+            .create()?;
+        Ok(Self {
+            consumer,
+            payload: options.payload,
+            fields: options.fields,
+            commit_every: options.commit_every,
+            max_empty_polls: options.max_empty_polls,
+            buffer: VecDeque::new(),
+            pending: HashMap::new(),
+            n_pending: 0,
+            _float: std::marker::PhantomData,
+        })
+    }
+    /// Commit the offsets of the messages yielded so far, so that the consumer group resumes
+    /// after them.
+    pub fn commit(&mut self) -> Result<(), KafkaStreamError> {
+        for ((topic, partition), offset) in self.pending.drain() {
+            self.consumer.consume_message(&topic, partition, offset)?;
+        }
+        self.consumer.commit_consumed()?;
+        self.n_pending = 0;
+        Ok(())
+    }
+    // Fetch messages until there are some, or until too many polls in a row are empty.
+    fn fetch(&mut self) -> Result<(), KafkaStreamError> {
+        let mut n_empty_polls = 0;
+        while self.buffer.is_empty() {
+            if self.max_empty_polls == Some(n_empty_polls) {
+                return Ok(());
+            }
+            for messages in self.consumer.poll()?.iter() {
+                for message in messages.messages() {
+                    self.buffer.push_back(Message {
+                        topic: messages.topic().to_string(),
+                        partition: messages.partition(),
+                        offset: message.offset,
+                        value: message.value.to_vec(),
+                    });
+                }
+            }
+            n_empty_polls += 1;
+        }
+        Ok(())
+    }
+}
+
+// The observation and the target of a message.
+fn decode<F: Float + std::str::FromStr>(
+    payload: &Payload,
+    fields: &JsonlStreamOptions,
+    value: &[u8],
+) -> Result<(Observation<F>, Option<Data<F>>), KafkaStreamError> {
+    let value = match payload {
+        Payload::Json => serde_json::from_slice(value)?,
+        Payload::Avro(schema) => avro_to_json(schema, value)?,
+        Payload::ConfluentAvro(schema) => match value {
+            [0, _, _, _, _, datum @ ..] => avro_to_json(schema, datum)?,
+            _ => return Err(KafkaStreamError::InvalidHeader),
+        },
+    };
+    match value {
+        Value::Object(object) => Ok(json_row(&object, fields)),
+        _ => Err(KafkaStreamError::NotAnObject),
+    }
+}
+
+fn avro_to_json(schema: &Schema, mut datum: &[u8]) -> Result<Value, KafkaStreamError> {
+    let value = GenericDatumReader::builder(schema)
+        .build()?
+        .read_value(&mut datum)?;
+    Ok(Value::try_from(value)?)
+}
+
+impl<F: Float + std::str::FromStr> Iterator for KafkaStream<F> {
+    type Item = Result<(Observation<F>, Option<Data<F>>), KafkaStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The messages yielded before have been learned from by now
+        if self.commit_every.is_some_and(|n| self.n_pending >= n) {
+            if let Err(e) = self.commit() {
+                return Some(Err(e));
+            }
+        }
+        if let Err(e) = self.fetch() {
+            return Some(Err(e));
+        }
+        let message = self.buffer.pop_front()?;
+        self.pending
+            .insert((message.topic, message.partition), message.offset);
+        self.n_pending += 1;
+        Some(decode(&self.payload, &self.fields, &message.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::FeatureValue;
+    use apache_avro::types::Record;
+    use apache_avro::writer::datum::GenericDatumWriter;
+
+    #[test]
+    fn test_decode() {
+        let fields = JsonlStreamOptions {
+            target: Some("label".to_string()),
+            ..Default::default()
+        };
+        let (x, y) = decode::<f64>(
+            &Payload::Json,
+            &fields,
+            br#"{"user": {"age": 31}, "label": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(x.get_numeric("user.age"), Some(31.0));
+        assert_eq!(y, Some(Data::Int(1)));
+        let error = decode::<f64>(&Payload::Json, &fields, b"[1]").unwrap_err();
+        assert!(matches!(error, KafkaStreamError::NotAnObject));
+
+        let schema = Schema::parse_str(
+            r#"{"type": "record", "name": "click", "fields": [
+                {"name": "site", "type": "string"},
+                {"name": "price", "type": ["null", "double"]},
+                {"name": "label", "type": "boolean"}
+            ]}"#,
+        )
+        .unwrap();
+        let mut record = Record::new(&schema).unwrap();
+        record.put("site", "news");
+        record.put(
+            "price",
+            apache_avro::types::Value::Union(1, Box::new(2.5.into())),
+        );
+        record.put("label", true);
+        let datum = GenericDatumWriter::builder(&schema)
+            .build()
+            .unwrap()
+            .write_value_to_vec(record)
+            .unwrap();
+        let (x, y) = decode::<f64>(&Payload::Avro(schema.clone()), &fields, &datum).unwrap();
+        assert_eq!(x.get_numeric("price"), Some(2.5));
+        assert_eq!(
+            x.get("site"),
+            Some(&FeatureValue::Categorical("news".to_string()))
+        );
+        assert_eq!(y, Some(Data::Bool(true)));
+
+        let confluent = Payload::ConfluentAvro(schema);
+        let framed = [&[0, 0, 0, 0, 7], datum.as_slice()].concat();
+        assert_eq!(decode::<f64>(&confluent, &fields, &framed).unwrap().0, x);
+        let error = decode::<f64>(&confluent, &fields, &datum).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid Confluent wire format header");
+    }
+}
@@ -1,2 +1,11 @@
+#[cfg(feature = "async")]
+pub mod aio;
+pub mod csv_stream;
 pub mod data_stream;
 pub mod iter_csv;
+#[cfg(feature = "json")]
+pub mod jsonl_stream;
+#[cfg(feature = "kafka")]
+pub mod kafka_stream;
+#[cfg(feature = "parquet")]
+pub mod parquet_stream;
@@ -0,0 +1,484 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_cast::cast;
+use arrow_ipc::reader::StreamReader;
+use arrow_schema::{ArrowError, DataType, Schema, TimeUnit};
+use num::{Float, FromPrimitive};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::errors::ParquetError;
+use parquet::file::reader::ChunkReader;
+
+use super::data_stream::Data;
+use crate::common::{FeatureValue, Observation};
+
+// An observation along with its target.
+type Row<F> = (Observation<F>, Option<Data<F>>);
+
+/// Options of a [`ParquetStream`].
+///
+/// - `target`: The column of the target, if any, which is left out of the features.
+/// - `batch_size`: The number of rows of each batch, 1024 by default. The last batch may be
+///   smaller.
+#[derive(Clone, Debug)]
+pub struct ParquetStreamOptions {
+    pub target: Option<String>,
+    pub batch_size: usize,
+}
+
+impl Default for ParquetStreamOptions {
+    fn default() -> Self {
+        Self {
+            target: None,
+            batch_size: 1024,
+        }
+    }
+}
+
+/// Iterates over the rows of a Parquet file or an Arrow IPC stream, in batches of observations
+/// along with their target.
+///
+/// The input is decoded into Arrow record batches, one column at a time, so that only the
+/// current record batch is held in memory. Each batch is made of the next `batch_size` rows, as
+/// pairs of an observation and the value of the target column, which is `None` when it is null
+/// or when there is no target column. Use `flatten` to iterate over single rows.
+///
+/// The features follow the types of the columns. Numbers, dates, times and decimals are numeric
+/// features, with dates as days and timestamps as seconds since the Unix epoch, and times as
+/// seconds since midnight. Booleans are numeric features equal to 1 or 0, strings are
+/// categorical features, and nulls, as well as binary values which aren't UTF-8 strings, missing
+/// features. Dictionary encoded columns are read as their values. The fields of a nested group
+/// are named after their path, e.g. `user.age`, while lists and maps are left out. The target is
+/// a [`Data`] of the type of its column, with integers which fit in 32 bits as [`Data::Int`] so
+/// that they suit classification.
+///
+/// This requires the `parquet` feature. Snappy and Zstandard compressed files are supported.
+///
+/// # Parameters
+///
+/// - `reader`: The Parquet file, or its bytes, which [`from_path`](ParquetStream::from_path)
+///   opens from a path. An Arrow IPC stream is read with
+///   [`from_ipc_stream`](ParquetStream::from_ipc_stream) instead.
+/// - `options`: The target column and the size of the batches, see [`ParquetStreamOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use light_river::stream::data_stream::Data;
+/// use light_river::stream::parquet_stream::{ParquetStream, ParquetStreamOptions};
+/// use parquet::data_type::{DoubleType, Int32Type};
+/// use parquet::file::writer::SerializedFileWriter;
+/// use parquet::schema::parser::parse_message_type;
+///
+/// // Write a file of 3 rows, with a feature and a label
+/// let path = tempfile::tempdir().unwrap().into_path().join("data.parquet");
+/// let schema = "message schema { REQUIRED DOUBLE x; REQUIRED INT32 label; }";
+/// let schema = Arc::new(parse_message_type(schema).unwrap());
+/// let file = std::fs::File::create(&path).unwrap();
+/// let mut writer = SerializedFileWriter::new(file, schema, Default::default()).unwrap();
+/// let mut row_group = writer.next_row_group().unwrap();
+/// let mut column = row_group.next_column().unwrap().unwrap();
+/// column.typed::<DoubleType>().write_batch(&[0.5, 1.5, 2.5], None, None).unwrap();
+/// column.close().unwrap();
+/// let mut column = row_group.next_column().unwrap().unwrap();
+/// column.typed::<Int32Type>().write_batch(&[0, 1, 1], None, None).unwrap();
+/// column.close().unwrap();
+/// row_group.close().unwrap();
+/// writer.close().unwrap();
+///
+/// let options = ParquetStreamOptions {
+///     target: Some("label".to_string()),
+///     batch_size: 2,
+/// };
+/// let stream = ParquetStream::<f64>::from_path(&path, options).unwrap();
+/// assert_eq!(stream.n_rows(), Some(3));
+/// let batches: Vec<_> = stream.map(Result::unwrap).collect();
+/// assert_eq!(batches.len(), 2);
+/// let (x, y) = &batches[1][0];
+/// assert_eq!(x.get_numeric("x"), Some(2.5));
+/// assert_eq!(y, &Some(Data::Int(1)));
+/// ```
+pub struct ParquetStream<F> {
+    batches: Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>,
+    // The record batch being read, along with the number of its rows already yielded
+    pending: Option<(RecordBatch, usize)>,
+    n_rows: Option<usize>,
+    options: ParquetStreamOptions,
+    _float: std::marker::PhantomData<F>,
+}
+
+impl<F: Float + FromPrimitive + std::str::FromStr> ParquetStream<F> {
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        options: ParquetStreamOptions,
+    ) -> Result<Self, ParquetError> {
+        Self::new(File::open(path)?, options)
+    }
+    pub fn new<R: ChunkReader + 'static>(
+        reader: R,
+        options: ParquetStreamOptions,
+    ) -> Result<Self, ParquetError> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(reader)?;
+        let n_rows = builder.metadata().file_metadata().num_rows() as usize;
+        let schema = builder.schema().clone();
+        let batches = builder.with_batch_size(options.batch_size).build()?;
+        Self::from_batches(Box::new(batches), &schema, Some(n_rows), options)
+    }
+    /// Reads an Arrow IPC stream, e.g. the output of another process or a `.arrows` file.
+    pub fn from_ipc_stream<R: Read + 'static>(
+        reader: R,
+        options: ParquetStreamOptions,
+    ) -> Result<Self, ParquetError> {
+        let batches = StreamReader::try_new_buffered(reader, None)?;
+        let schema = batches.schema();
+        Self::from_batches(Box::new(batches), &schema, None, options)
+    }
+    fn from_batches(
+        batches: Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>,
+        schema: &Schema,
+        n_rows: Option<usize>,
+        options: ParquetStreamOptions,
+    ) -> Result<Self, ParquetError> {
+        assert!(
+            options.batch_size > 0,
+            "batch_size must be strictly positive"
+        );
+        if let Some(target) = &options.target {
+            if schema.field_with_name(target).is_err() {
+                return Err(ParquetError::General(format!(
+                    "No column named '{}'",
+                    target
+                )));
+            }
+        }
+        Ok(Self {
+            batches,
+            pending: None,
+            n_rows,
+            options,
+            _float: std::marker::PhantomData,
+        })
+    }
+    /// The number of rows of the file, according to its metadata, which isn't known ahead for
+    /// an Arrow IPC stream.
+    pub fn n_rows(&self) -> Option<usize> {
+        self.n_rows
+    }
+    fn rows(&self, records: &RecordBatch) -> Result<Vec<Row<F>>, ArrowError> {
+        let mut xs: Vec<_> = (0..records.num_rows())
+            .map(|_| Observation::new())
+            .collect();
+        let mut ys = vec![None; records.num_rows()];
+        for (field, column) in records.schema().fields().iter().zip(records.columns()) {
+            if self.options.target.as_ref() == Some(field.name()) {
+                ys = targets(column)?;
+            } else {
+                add(field.name(), column, &mut xs)?;
+            }
+        }
+        Ok(xs.into_iter().zip(ys).collect())
+    }
+}
+
+// The values of a dictionary encoded column, or the column itself.
+fn decoded(column: &ArrayRef) -> Result<ArrayRef, ArrowError> {
+    match column.data_type() {
+        DataType::Dictionary(_, values) => cast(column, values),
+        _ => Ok(column.clone()),
+    }
+}
+
+// The values of a numeric column, or `None` for a column of another type.
+fn numeric<F: Float + FromPrimitive>(
+    column: &ArrayRef,
+) -> Result<Option<Vec<Option<F>>>, ArrowError> {
+    let per_second = |unit: &TimeUnit| match unit {
+        TimeUnit::Second => 1.0,
+        TimeUnit::Millisecond => 1e3,
+        TimeUnit::Microsecond => 1e6,
+        TimeUnit::Nanosecond => 1e9,
+    };
+    let scale = match column.data_type() {
+        DataType::Date32 => 1.0,
+        DataType::Date64 => 86_400_000.0,
+        DataType::Time32(unit) | DataType::Time64(unit) | DataType::Timestamp(unit, _) => {
+            per_second(unit)
+        }
+        DataType::Null | DataType::Boolean => 1.0,
+        t if t.is_numeric() => 1.0,
+        _ => return Ok(None),
+    };
+    // Dates and times are only cast to floats through their integer value
+    let column = if column.data_type().is_temporal() {
+        cast(column, &DataType::Int64)?
+    } else {
+        column.clone()
+    };
+    let values = cast(&column, &DataType::Float64)?;
+    let values = values.as_primitive::<Float64Type>();
+    Ok(Some(
+        values
+            .iter()
+            .map(|v| v.and_then(|v| F::from_f64(v / scale)))
+            .collect(),
+    ))
+}
+
+// The values of a string or binary column, or `None` for a column of another type.
+fn strings(column: &ArrayRef) -> Result<Option<Vec<Option<String>>>, ArrowError> {
+    let strings = match column.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            let column = cast(column, &DataType::LargeUtf8)?;
+            let column = column.as_string::<i64>();
+            column.iter().map(|s| s.map(str::to_string)).collect()
+        }
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+            let column = cast(column, &DataType::LargeBinary)?;
+            let column = column.as_binary::<i64>();
+            column
+                .iter()
+                .map(|bytes| {
+                    let s = bytes.and_then(|bytes| std::str::from_utf8(bytes).ok());
+                    s.map(str::to_string)
+                })
+                .collect()
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(strings))
+}
+
+// Add a column to the features, or the fields of a group, prefixed by its name.
+fn add<F: Float + FromPrimitive>(
+    name: &str,
+    column: &ArrayRef,
+    xs: &mut [Observation<F>],
+) -> Result<(), ArrowError> {
+    let column = decoded(column)?;
+    if let DataType::Struct(fields) = column.data_type() {
+        for (field, child) in fields.iter().zip(column.as_struct().columns()) {
+            add(&format!("{}.{}", name, field.name()), child, xs)?;
+        }
+        return Ok(());
+    }
+    let values: Vec<_> = if let Some(strings) = strings(&column)? {
+        let value = |s: Option<String>| s.map_or(FeatureValue::Missing, FeatureValue::Categorical);
+        strings.into_iter().map(value).collect()
+    } else if let Some(values) = numeric(&column)? {
+        let value = |v: Option<F>| v.map_or(FeatureValue::Missing, FeatureValue::Numeric);
+        values.into_iter().map(value).collect()
+    } else {
+        // Lists, maps and the other nested types
+        return Ok(());
+    };
+    for (x, value) in xs.iter_mut().zip(values) {
+        x.insert(name, value);
+    }
+    Ok(())
+}
+
+// The targets given by a column, which are `None` for nulls or a nested column.
+fn targets<F: Float + FromPrimitive + std::str::FromStr>(
+    column: &ArrayRef,
+) -> Result<Vec<Option<Data<F>>>, ArrowError> {
+    let column = decoded(column)?;
+    if let Some(strings) = strings(&column)? {
+        return Ok(strings.into_iter().map(|s| s.map(Data::String)).collect());
+    }
+    if let DataType::Boolean = column.data_type() {
+        return Ok(column
+            .as_boolean()
+            .iter()
+            .map(|b| b.map(Data::Bool))
+            .collect());
+    }
+    let Some(values) = numeric::<F>(&column)? else {
+        return Ok(vec![None; column.len()]);
+    };
+    let ints = match column.data_type().is_integer() {
+        true => Some(cast(&column, &DataType::Int64)?),
+        false => None,
+    };
+    let ints = ints.as_ref().map(|ints| ints.as_primitive::<Int64Type>());
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let int = ints
+                .filter(|ints| ints.is_valid(i))
+                .and_then(|ints| i32::try_from(ints.value(i)).ok());
+            match int {
+                Some(int) => Some(Data::Int(int)),
+                None => value.map(Data::Scalar),
+            }
+        })
+        .collect())
+}
+
+impl<F: Float + FromPrimitive + std::str::FromStr> Iterator for ParquetStream<F> {
+    type Item = Result<Vec<Row<F>>, ParquetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut batch = Vec::new();
+        while batch.len() < self.options.batch_size {
+            let (records, offset) = match self.pending.take() {
+                Some(pending) => pending,
+                None => match self.batches.next() {
+                    Some(Ok(records)) => (records, 0),
+                    Some(Err(e)) => return Some(Err(e.into())),
+                    None => break,
+                },
+            };
+            let length = (self.options.batch_size - batch.len()).min(records.num_rows() - offset);
+            match self.rows(&records.slice(offset, length)) {
+                Ok(rows) => batch.extend(rows),
+                Err(e) => return Some(Err(e.into())),
+            }
+            if offset + length < records.num_rows() {
+                self.pending = Some((records, offset + length));
+            }
+        }
+        if batch.is_empty() {
+            None
+        } else {
+            Some(Ok(batch))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::types::Int8Type;
+    use arrow_array::{DictionaryArray, Float32Array, Int64Array};
+    use arrow_ipc::writer::StreamWriter;
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_types() {
+        let schema = "message schema {
+            OPTIONAL BYTE_ARRAY city (UTF8);
+            REQUIRED group sensor { REQUIRED BOOLEAN on; }
+            REQUIRED INT64 time (TIMESTAMP_MILLIS);
+        }";
+        let schema = Arc::new(parse_message_type(schema).unwrap());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.parquet");
+        let file = File::create(&path).unwrap();
+        // Write a row group per row
+        let properties = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(file, schema, properties).unwrap();
+        for (city, on, time) in [(Some("Paris"), true, 1500), (None, false, 2500)] {
+            let mut row_group = writer.next_row_group().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            let cities: Vec<ByteArray> = city.into_iter().map(ByteArray::from).collect();
+            let levels = [city.is_some() as i16];
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&cities, Some(&levels), None)
+                .unwrap();
+            column.close().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<BoolType>()
+                .write_batch(&[on], None, None)
+                .unwrap();
+            column.close().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<Int64Type>()
+                .write_batch(&[time], None, None)
+                .unwrap();
+            column.close().unwrap();
+            row_group.close().unwrap();
+        }
+        writer.close().unwrap();
+
+        let options = ParquetStreamOptions {
+            target: Some("city".to_string()),
+            ..Default::default()
+        };
+        let mut stream = ParquetStream::<f64>::from_path(&path, options).unwrap();
+        let batch = stream.next().unwrap().unwrap();
+        assert!(stream.next().is_none());
+        let features = |x: &Observation<f64>| -> Vec<(String, f64)> {
+            x.numeric().map(|(name, v)| (name.clone(), v)).collect()
+        };
+        assert_eq!(
+            features(&batch[0].0),
+            [("sensor.on".to_string(), 1.0), ("time".to_string(), 1.5)]
+        );
+        assert_eq!(batch[0].1, Some(Data::String("Paris".to_string())));
+        assert_eq!(
+            features(&batch[1].0),
+            [("sensor.on".to_string(), 0.0), ("time".to_string(), 2.5)]
+        );
+        assert_eq!(batch[1].1, None);
+
+        let options = ParquetStreamOptions {
+            target: Some("country".to_string()),
+            ..Default::default()
+        };
+        assert!(ParquetStream::<f64>::from_path(&path, options).is_err());
+    }
+
+    #[test]
+    fn test_ipc_stream() {
+        let records = [
+            (
+                vec!["red", "blue", "red"],
+                vec![Some(1.5), None, Some(3.5)],
+                vec![0, 1, 1 << 40],
+            ),
+            (vec!["blue", "blue"], vec![Some(4.5), Some(5.5)], vec![1, 0]),
+        ];
+        let batches: Vec<_> = records
+            .into_iter()
+            .map(|(colors, sizes, labels)| {
+                let colors: DictionaryArray<Int8Type> = colors.into_iter().collect();
+                RecordBatch::try_from_iter([
+                    ("color", Arc::new(colors) as ArrayRef),
+                    ("size", Arc::new(Float32Array::from(sizes))),
+                    ("label", Arc::new(Int64Array::from(labels))),
+                ])
+                .unwrap()
+            })
+            .collect();
+        let mut writer = StreamWriter::try_new(Vec::new(), &batches[0].schema()).unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        let content = writer.into_inner().unwrap();
+
+        let options = ParquetStreamOptions {
+            target: Some("label".to_string()),
+            batch_size: 2,
+        };
+        let stream =
+            ParquetStream::<f64>::from_ipc_stream(std::io::Cursor::new(content), options).unwrap();
+        assert_eq!(stream.n_rows(), None);
+        // The batches span the record batches
+        let batches: Vec<_> = stream.map(Result::unwrap).collect();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 2, 1]);
+        let (x, y) = &batches[1][0];
+        assert_eq!(x.get_numeric("size"), Some(3.5));
+        assert_eq!(y, &Some(Data::Scalar((1u64 << 40) as f64)));
+        let (x, y) = &batches[0][1];
+        assert_eq!(
+            x.get("color"),
+            Some(&FeatureValue::Categorical("blue".to_string()))
+        );
+        assert_eq!(x.get("size"), Some(&FeatureValue::Missing));
+        assert_eq!(y, &Some(Data::Int(1)));
+    }
+}
@@ -0,0 +1,70 @@
+// Stubs and data generators shared by the unit tests.
+
+use crate::common::{ClassifierTarget, Observation};
+use crate::learner::{Classifier, Regressor};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Asserts that two values are equal up to rounding errors.
+pub(crate) fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-10, "{} != {}", a, b);
+}
+
+/// A regressor which always predicts the same value.
+pub(crate) struct Constant(pub f64);
+
+impl Regressor<f64> for Constant {
+    fn learn_one(&mut self, _x: &Observation<f64>, _y: f64) {}
+    fn predict_one(&self, _x: &Observation<f64>) -> f64 {
+        self.0
+    }
+}
+
+/// A deterministic, evenly spread but unordered sequence of integers in `[0, n)`, indexed by `i`.
+pub(crate) fn scrambled(i: usize, n: usize) -> usize {
+    (i * 7919) % n
+}
+
+/// Like [`scrambled`], but uncorrelated with it, for a second feature.
+pub(crate) fn scrambled_other(i: usize, n: usize) -> usize {
+    (i * 6007) % n
+}
+
+/// A deterministic permutation of 1, 2, ..., n, for `n + 1` coprime with 7919.
+pub(crate) fn permutation(n: usize) -> impl Iterator<Item = f64> {
+    (1..=n).map(move |i| scrambled(i, n + 1) as f64)
+}
+
+/// Two numeric features. The class is whether both are above 0.5, and is reversed after
+/// `drift_at` samples.
+pub(crate) fn drifting_stream(
+    n: usize,
+    drift_at: usize,
+) -> Vec<(Observation<f64>, ClassifierTarget)> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..n)
+        .map(|i| {
+            let (a, b): (f64, f64) = (rng.gen(), rng.gen());
+            let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+            (
+                x,
+                ClassifierTarget::from((a > 0.5 && b > 0.5) == (i < drift_at)),
+            )
+        })
+        .collect()
+}
+
+/// Prequential accuracy of a classifier on the samples from `from` onwards
+pub(crate) fn accuracy(
+    model: &mut impl Classifier<f64>,
+    stream: &[(Observation<f64>, ClassifierTarget)],
+    from: usize,
+) -> f64 {
+    let mut correct = 0;
+    for (i, (x, y)) in stream.iter().enumerate() {
+        if i >= from && !model.predict_proba(x).is_empty() && model.predict_one(x) == *y {
+            correct += 1;
+        }
+        model.learn_one(x, y.clone());
+    }
+    correct as f64 / (stream.len() - from) as f64
+}
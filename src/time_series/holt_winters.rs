@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::Observation;
+use crate::learner::Forecaster;
+use num::{Float, FromPrimitive};
+
+/// How the seasonal component of [`HoltWinters`] combines with the level and the trend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seasonality {
+    /// The seasonal component is added, for seasonal variations of constant amplitude.
+    Additive,
+    /// The seasonal component is a factor, for seasonal variations proportional to the level.
+    Multiplicative,
+}
+
+/// Holt-Winters exponential smoothing.
+///
+/// The series is decomposed into a level, a trend and a seasonal component, each of which is an
+/// exponential moving average updated with every value. The level is smoothed with `alpha`, the
+/// trend with `beta`, and the seasonal component of each step of the season with `gamma`. The
+/// forecast `h` steps ahead extends the level with `h` times the trend, and combines it with the
+/// seasonal component of its step.
+///
+/// The model is initialized from the first season: the level is its mean, the trend is null, and
+/// the seasonal components are the deviations of its values from their mean. Until then, the
+/// forecasts are the mean of the values seen so far.
+///
+/// # Parameters
+///
+/// - `alpha`: The smoothing factor of the level, in `(0, 1]`.
+/// - `beta`: The smoothing factor of the trend, in `[0, 1]`. There is no trend if `None`.
+/// - `gamma`: The smoothing factor of the seasonal component, in `[0, 1]`. There is no seasonal
+///   component if `None`.
+/// - `seasonality`: The number of steps of a season, which must be strictly positive when `gamma`
+///   is given.
+/// - `kind`: How the seasonal component combines with the level, see [`Seasonality`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::learner::Forecaster;
+/// use light_river::time_series::holt_winters::{HoltWinters, Seasonality};
+///
+/// let mut model = HoltWinters::new(0.3, Some(0.1), Some(0.5), 12, Seasonality::Multiplicative);
+/// // A trend and a seasonality of period 12, whose amplitude grows with the level
+/// let series = |t: usize| (100.0 + 2.0 * t as f64) * (1.0 + 0.2 * (t as f64 * 0.5236).sin());
+/// for t in 0..240 {
+///     model.learn_one(series(t), None);
+/// }
+/// let forecasts = model.forecast(12, None);
+/// for (h, forecast) in forecasts.iter().enumerate() {
+///     assert!((forecast / series(240 + h) - 1.0).abs() < 0.02);
+/// }
+/// ```
+///
+/// # References
+///
+/// [^1]: P. R. Winters (1960). "Forecasting sales by exponentially weighted moving averages".
+/// Management science 6(3):324-342.
+///
+/// [^2]: R. J. Hyndman and G. Athanasopoulos (2021). "Forecasting: principles and practice",
+/// chapter 8.3. OTexts.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HoltWinters<F> {
+    alpha: F,
+    beta: Option<F>,
+    gamma: Option<F>,
+    seasonality: usize,
+    kind: Seasonality,
+    // Values of the first season, from which the components are initialized
+    buffer: Vec<F>,
+    level: F,
+    trend: F,
+    // Seasonal component of each step of the season, that of the next value first
+    seasonals: VecDeque<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> HoltWinters<F> {
+    pub fn new(
+        alpha: F,
+        beta: Option<F>,
+        gamma: Option<F>,
+        seasonality: usize,
+        kind: Seasonality,
+    ) -> Self {
+        assert!(
+            alpha > F::zero() && alpha <= F::one(),
+            "alpha must be in (0, 1]"
+        );
+        for factor in beta.iter().chain(gamma.iter()) {
+            assert!(
+                *factor >= F::zero() && *factor <= F::one(),
+                "beta and gamma must be in [0, 1]"
+            );
+        }
+        assert!(
+            gamma.is_none() || seasonality > 0,
+            "seasonality must be strictly positive when gamma is given"
+        );
+        Self {
+            alpha,
+            beta,
+            gamma,
+            seasonality,
+            kind,
+            buffer: Vec::new(),
+            level: F::zero(),
+            trend: F::zero(),
+            seasonals: VecDeque::new(),
+        }
+    }
+    pub fn level(&self) -> F {
+        self.level
+    }
+    pub fn trend(&self) -> F {
+        self.trend
+    }
+    /// The seasonal components of the steps of the season, starting from the step of the next
+    /// value.
+    pub fn seasonals(&self) -> &VecDeque<F> {
+        &self.seasonals
+    }
+    fn n_initial(&self) -> usize {
+        if self.gamma.is_some() {
+            self.seasonality
+        } else {
+            1
+        }
+    }
+    fn initialize(&mut self) {
+        let n = F::from_usize(self.buffer.len()).unwrap();
+        self.level = self.buffer.iter().fold(F::zero(), |sum, y| sum + *y) / n;
+        if self.gamma.is_some() {
+            self.seasonals = self
+                .buffer
+                .iter()
+                .map(|y| match self.kind {
+                    Seasonality::Additive => *y - self.level,
+                    Seasonality::Multiplicative => *y / self.level,
+                })
+                .collect();
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Forecaster<F>
+    for HoltWinters<F>
+{
+    fn learn_one(&mut self, y: F, _x: Option<&Observation<F>>) {
+        if self.buffer.len() < self.n_initial() {
+            self.buffer.push(y);
+            if self.buffer.len() == self.n_initial() {
+                self.initialize();
+            }
+            return;
+        }
+        let (level, trend) = (self.level, self.trend);
+        let seasonal = self.seasonals.pop_front();
+        // The value without its seasonal component
+        let adjusted = match (seasonal, self.kind) {
+            (None, _) => y,
+            (Some(s), Seasonality::Additive) => y - s,
+            (Some(s), Seasonality::Multiplicative) => y / s,
+        };
+        self.level = self.alpha * adjusted + (F::one() - self.alpha) * (level + trend);
+        if let Some(beta) = self.beta {
+            self.trend = beta * (self.level - level) + (F::one() - beta) * trend;
+        }
+        if let (Some(gamma), Some(s)) = (self.gamma, seasonal) {
+            let deviation = match self.kind {
+                Seasonality::Additive => y - level - trend,
+                Seasonality::Multiplicative => y / (level + trend),
+            };
+            self.seasonals
+                .push_back(gamma * deviation + (F::one() - gamma) * s);
+        }
+    }
+    fn forecast(&self, horizon: usize, _xs: Option<&[Observation<F>]>) -> Vec<F> {
+        if self.buffer.len() < self.n_initial() {
+            let mean = if self.buffer.is_empty() {
+                F::zero()
+            } else {
+                let n = F::from_usize(self.buffer.len()).unwrap();
+                self.buffer.iter().fold(F::zero(), |sum, y| sum + *y) / n
+            };
+            return vec![mean; horizon];
+        }
+        (1..=horizon)
+            .map(|h| {
+                let base = self.level + F::from_usize(h).unwrap() * self.trend;
+                match self.seasonals.get((h - 1) % self.seasonality.max(1)) {
+                    None => base,
+                    Some(s) => match self.kind {
+                        Seasonality::Additive => base + *s,
+                        Seasonality::Multiplicative => base * *s,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_additive() {
+        let mut model = HoltWinters::new(0.5, Some(0.5), Some(0.5), 3, Seasonality::Additive);
+        model.learn_one(1.0, None);
+        assert_eq!(model.forecast(2, None), [1.0, 1.0]);
+        model.learn_one(5.0, None);
+        model.learn_one(3.0, None);
+        // Initialized from the first season
+        assert_eq!(model.level(), 3.0);
+        assert_eq!(model.seasonals(), &[-2.0, 2.0, 0.0]);
+        model.learn_one(2.0, None);
+        // level = 0.5 * (2 + 2) + 0.5 * 3, trend = 0.5 * 0.5, seasonal = 0.5 * -1 + 0.5 * -2
+        assert_eq!(model.level(), 3.5);
+        assert_eq!(model.trend(), 0.25);
+        assert_eq!(model.seasonals(), &[2.0, 0.0, -1.5]);
+        assert_eq!(model.forecast(4, None), [5.75, 4.0, 2.75, 6.5]);
+    }
+
+    #[test]
+    fn test_no_seasonality() {
+        let mut model = HoltWinters::new(1.0, None, None, 0, Seasonality::Additive);
+        for y in [1.0, 4.0, 2.0] {
+            model.learn_one(y, None);
+        }
+        // Without trend nor seasonality, the last value is forecast with alpha = 1
+        assert_eq!(model.forecast(3, None), [2.0, 2.0, 2.0]);
+    }
+}
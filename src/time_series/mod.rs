@@ -0,0 +1,2 @@
+pub mod holt_winters;
+pub mod snarimax;
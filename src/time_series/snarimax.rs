@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Observation};
+use crate::learner::{Forecaster, Regressor};
+use num::{Float, FromPrimitive};
+
+/// Options of [`SNARIMAX`].
+///
+/// - `p`: The number of past values of the differenced series used as features, named `y-{i}`.
+/// - `d`: The order of the differencing.
+/// - `q`: The number of past errors used as features, named `e-{i}`.
+/// - `m`: The period of the seasonality, 1 by default.
+/// - `sp`: The number of seasonal past values of the differenced series used as features, named
+///   `sy-{i * m}`.
+/// - `sd`: The order of the seasonal differencing.
+/// - `sq`: The number of seasonal past errors used as features, named `se-{i * m}`.
+///
+/// All the orders are 0 by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SNARIMAXOptions {
+    pub p: usize,
+    pub d: usize,
+    pub q: usize,
+    pub m: usize,
+    pub sp: usize,
+    pub sd: usize,
+    pub sq: usize,
+}
+
+impl Default for SNARIMAXOptions {
+    fn default() -> Self {
+        Self {
+            p: 0,
+            d: 0,
+            q: 0,
+            m: 1,
+            sp: 0,
+            sd: 0,
+            sq: 0,
+        }
+    }
+}
+
+// Differencing of a series, as the coefficients of the lags of (1 - B)^d (1 - B^m)^sd, where B is
+// the backshift operator. The coefficient of the current value is 1.
+#[derive(Clone, Debug)]
+struct Differencer<F> {
+    coefficients: Vec<F>,
+}
+
+impl<F: Float + FromPrimitive> Differencer<F> {
+    fn new(d: usize, m: usize, sd: usize) -> Self {
+        // Coefficients of (1 - B^step)^order, by lag
+        let expand = |order: usize, step: usize| {
+            let mut coefficients = vec![0i64; order * step + 1];
+            let mut binomial = 1i64;
+            for k in 0..=order {
+                coefficients[k * step] = if k % 2 == 0 { binomial } else { -binomial };
+                binomial = binomial * (order - k) as i64 / (k + 1) as i64;
+            }
+            coefficients
+        };
+        let (a, b) = (expand(d, 1), expand(sd, m));
+        let mut coefficients = vec![0i64; a.len() + b.len() - 1];
+        for (i, ca) in a.iter().enumerate() {
+            for (j, cb) in b.iter().enumerate() {
+                coefficients[i + j] += ca * cb;
+            }
+        }
+        Self {
+            coefficients: coefficients
+                .into_iter()
+                .map(|c| F::from_i64(c).unwrap())
+                .collect(),
+        }
+    }
+    // The number of past values needed to difference a value.
+    fn n_required(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+    // Differenced value, given the past values, the most recent first.
+    fn diff(&self, y: F, past: &VecDeque<F>) -> F {
+        self.coefficients[1..]
+            .iter()
+            .zip(past.iter())
+            .fold(y, |acc, (c, y)| acc + *c * *y)
+    }
+    // Value whose difference is the given one, given the past values, the most recent first.
+    fn undiff(&self, y_diff: F, past: &VecDeque<F>) -> F {
+        self.coefficients[1..]
+            .iter()
+            .zip(past.iter())
+            .fold(y_diff, |acc, (c, y)| acc - *c * *y)
+    }
+}
+
+// Pushes a value at the front of a history, which keeps at most `size` values.
+fn push<F>(history: &mut VecDeque<F>, value: F, size: usize) {
+    history.push_front(value);
+    history.truncate(size);
+}
+
+/// SNARIMAX, i.e. seasonal non-linear autoregressive integrated moving-average with exogenous
+/// inputs, for online time series forecasting.
+///
+/// The series is differenced `d` times, and `sd` times with a lag of `m`, so as to remove its
+/// trend and seasonality. A regressor learns to predict the differenced series from its past
+/// values, from its past errors, and from the exogenous features given along with each value. The
+/// regressor is pluggable: a linear regression makes the model a seasonal ARIMAX, and a
+/// non-linear regressor makes it non-linear. The forecasts are the predictions of the regressor,
+/// fed back into the past values with null errors, and integrated back into the original series.
+///
+/// Values are only learned from once enough of them have been seen to be differenced.
+///
+/// # Parameters
+///
+/// - `regressor`: The regressor of the differenced series.
+/// - `options`: The orders of the model, see [`SNARIMAXOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::learner::Forecaster;
+/// use light_river::linear_model::linear_regression::LinearRegression;
+/// use light_river::optim::losses::Squared;
+/// use light_river::optim::optimizers::SGD;
+/// use light_river::time_series::snarimax::{SNARIMAX, SNARIMAXOptions};
+///
+/// let options = SNARIMAXOptions {
+///     p: 1,
+///     d: 1,
+///     m: 4,
+///     sd: 1,
+///     ..Default::default()
+/// };
+/// let regressor = LinearRegression::new(SGD::new(0.01), Squared, Default::default());
+/// let mut model = SNARIMAX::new(regressor, options);
+/// // A trend and a seasonality of period 4
+/// let series = |t: usize| 10.0 + 0.5 * t as f64 + [0.0, 3.0, 1.0, -2.0][t % 4];
+/// for t in 0..100 {
+///     model.learn_one(series(t), None);
+/// }
+/// let forecasts = model.forecast(6, None);
+/// for (h, forecast) in forecasts.iter().enumerate() {
+///     assert!((forecast - series(100 + h)).abs() < 1e-6);
+/// }
+/// ```
+///
+/// # References
+///
+/// [^1]: G. E. P. Box, G. M. Jenkins, G. C. Reinsel and G. M. Ljung (2015). "Time series
+/// analysis: forecasting and control". John Wiley & Sons.
+///
+/// [^2]: O. Anava, E. Hazan, S. Mannor and O. Shamir (2013). "Online learning for time series
+/// prediction". Conference on learning theory, 172-184.
+#[derive(Clone, Debug)]
+pub struct SNARIMAX<F, R> {
+    regressor: R,
+    options: SNARIMAXOptions,
+    differencer: Differencer<F>,
+    // Past values of the series, of the differenced series and of the errors, the most recent first
+    y: VecDeque<F>,
+    y_diff: VecDeque<F>,
+    errors: VecDeque<F>,
+}
+
+impl<F, R> SNARIMAX<F, R>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    R: Regressor<F> + Clone,
+{
+    pub fn new(regressor: R, options: SNARIMAXOptions) -> Self {
+        assert!(options.m > 0, "m must be strictly positive");
+        Self {
+            regressor,
+            options,
+            differencer: Differencer::new(options.d, options.m, options.sd),
+            y: VecDeque::new(),
+            y_diff: VecDeque::new(),
+            errors: VecDeque::new(),
+        }
+    }
+    pub fn options(&self) -> &SNARIMAXOptions {
+        &self.options
+    }
+    pub fn regressor(&self) -> &R {
+        &self.regressor
+    }
+    // Exogenous features, along with the past values of the differenced series and the past
+    // errors which are available.
+    fn features(
+        &self,
+        x: Option<&Observation<F>>,
+        y_diff: &VecDeque<F>,
+        errors: &VecDeque<F>,
+    ) -> Observation<F> {
+        let SNARIMAXOptions {
+            p, q, m, sp, sq, ..
+        } = self.options;
+        let mut features = x.cloned().unwrap_or_default();
+        let mut add = |prefix: &str, history: &VecDeque<F>, lags: Vec<usize>| {
+            for lag in lags {
+                if let Some(value) = history.get(lag - 1) {
+                    features.insert(format!("{prefix}-{lag}"), FeatureValue::Numeric(*value));
+                }
+            }
+        };
+        add("y", y_diff, (1..=p).collect());
+        add("e", errors, (1..=q).collect());
+        add("sy", y_diff, (1..=sp).map(|i| i * m).collect());
+        add("se", errors, (1..=sq).map(|i| i * m).collect());
+        features
+    }
+}
+
+impl<F, R> Forecaster<F> for SNARIMAX<F, R>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    R: Regressor<F> + Clone,
+{
+    fn learn_one(&mut self, y: F, x: Option<&Observation<F>>) {
+        let n_required = self.differencer.n_required();
+        if self.y.len() >= n_required {
+            let y_diff = self.differencer.diff(y, &self.y);
+            let x = self.features(x, &self.y_diff, &self.errors);
+            let error = y_diff - self.regressor.predict_one(&x);
+            self.regressor.learn_one(&x, y_diff);
+            let SNARIMAXOptions {
+                p, q, m, sp, sq, ..
+            } = self.options;
+            push(&mut self.y_diff, y_diff, p.max(sp * m));
+            push(&mut self.errors, error, q.max(sq * m));
+        }
+        push(&mut self.y, y, n_required);
+    }
+    fn forecast(&self, horizon: usize, xs: Option<&[Observation<F>]>) -> Vec<F> {
+        if let Some(xs) = xs {
+            assert_eq!(
+                xs.len(),
+                horizon,
+                "there must be features for each forecast"
+            );
+        }
+        let SNARIMAXOptions {
+            p, q, m, sp, sq, ..
+        } = self.options;
+        let n_required = self.differencer.n_required();
+        let (mut y, mut y_diff, mut errors) =
+            (self.y.clone(), self.y_diff.clone(), self.errors.clone());
+        (0..horizon)
+            .map(|h| {
+                let x = self.features(xs.map(|xs| &xs[h]), &y_diff, &errors);
+                let forecast_diff = self.regressor.predict_one(&x);
+                let forecast = self.differencer.undiff(forecast_diff, &y);
+                push(&mut y, forecast, n_required);
+                push(&mut y_diff, forecast_diff, p.max(sp * m));
+                push(&mut errors, F::zero(), q.max(sq * m));
+                forecast
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_differencer() {
+        let differencer = Differencer::<f64>::new(2, 3, 1);
+        // (1 - B)^2 (1 - B^3) = 1 - 2B + B^2 - B^3 + 2B^4 - B^5
+        assert_eq!(differencer.coefficients, [1.0, -2.0, 1.0, -1.0, 2.0, -1.0]);
+        let past = VecDeque::from([4.0, 1.0, 7.0, 2.0, 5.0]);
+        let y_diff = differencer.diff(3.0, &past);
+        assert_eq!(differencer.undiff(y_diff, &past), 3.0);
+    }
+
+    // Remembers the features it sees, and predicts the mean of what it learned
+    #[derive(Clone, Default)]
+    struct Recorder {
+        seen: RefCell<Vec<Vec<String>>>,
+        sum: f64,
+        n: f64,
+    }
+
+    impl Regressor<f64> for Recorder {
+        fn learn_one(&mut self, _x: &Observation<f64>, y: f64) {
+            self.sum += y;
+            self.n += 1.0;
+        }
+        fn predict_one(&self, x: &Observation<f64>) -> f64 {
+            self.seen.borrow_mut().push(x.keys().cloned().collect());
+            if self.n > 0.0 {
+                self.sum / self.n
+            } else {
+                0.0
+            }
+        }
+    }
+
+    #[test]
+    fn test_features() {
+        let options = SNARIMAXOptions {
+            p: 2,
+            q: 1,
+            m: 3,
+            sp: 1,
+            sq: 1,
+            ..Default::default()
+        };
+        let mut model = SNARIMAX::new(Recorder::default(), options);
+        for y in [1.0, 2.0, 3.0] {
+            model.learn_one(y, Some(&Observation::from([("x".to_string(), y)])));
+        }
+        let seen = model.regressor().seen.borrow().clone();
+        assert_eq!(
+            seen,
+            [
+                vec!["x"],
+                vec!["e-1", "x", "y-1"],
+                vec!["e-1", "x", "y-1", "y-2"]
+            ]
+        );
+        // The forecasts are fed back into the past values, with null errors
+        let forecasts = model.forecast(2, None);
+        assert_eq!(forecasts, [2.0, 2.0]);
+        let seen = model.regressor().seen.borrow();
+        assert_eq!(
+            seen[3..],
+            [
+                vec!["e-1", "se-3", "sy-3", "y-1", "y-2"],
+                vec!["e-1", "se-3", "sy-3", "y-1", "y-2"]
+            ]
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, Observation};
+use crate::drift::adwin::ADWIN;
+use crate::learner::Classifier;
+use crate::tree::hoeffding_tree_classifier::{Branch, HoeffdingTreeClassifierOptions, Leaf};
+use crate::tree::splitter::ClassCounts;
+use num::{Float, FromPrimitive};
+
+/// Options of a [`HoeffdingAdaptiveTreeClassifier`].
+///
+/// - `tree`: The options shared with the [`HoeffdingTreeClassifier`](super::hoeffding_tree_classifier::HoeffdingTreeClassifier).
+/// - `adwin_delta`: The confidence of the ADWIN detectors which monitor the error of each node.
+/// - `drift_window_threshold`: The number of errors both a subtree and its alternate must have
+///   monitored before they are compared.
+/// - `switch_significance`: The significance level at which an alternate subtree is deemed better
+///   or worse than the subtree it would replace.
+#[derive(Clone, Debug)]
+pub struct HoeffdingAdaptiveTreeClassifierOptions<F> {
+    pub tree: HoeffdingTreeClassifierOptions<F>,
+    pub adwin_delta: F,
+    pub drift_window_threshold: usize,
+    pub switch_significance: F,
+}
+
+impl<F: Float + FromPrimitive> Default for HoeffdingAdaptiveTreeClassifierOptions<F> {
+    fn default() -> Self {
+        Self {
+            tree: HoeffdingTreeClassifierOptions::default(),
+            adwin_delta: F::from_f64(0.002).unwrap(),
+            drift_window_threshold: 300,
+            switch_significance: F::from_f64(0.05).unwrap(),
+        }
+    }
+}
+
+// Number of alternate subtrees which replaced the subtree they were grown for, and which were
+// discarded.
+#[derive(Clone, Copy, Debug, Default)]
+struct Alternates {
+    switched: usize,
+    pruned: usize,
+}
+
+#[derive(Clone, Debug)]
+enum AdaNode<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    Leaf {
+        leaf: Leaf<F>,
+        error: ADWIN<F>,
+    },
+    Branch {
+        branch: Branch<F, AdaNode<F>>,
+        depth: usize,
+        error: ADWIN<F>,
+        // Subtree grown from scratch since the error of this one increased
+        alternate: Option<Box<AdaNode<F>>>,
+    },
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> AdaNode<F> {
+    fn new_leaf(
+        depth: usize,
+        stats: ClassCounts<F>,
+        options: &HoeffdingAdaptiveTreeClassifierOptions<F>,
+    ) -> Self {
+        AdaNode::Leaf {
+            leaf: Leaf::new(depth, stats),
+            error: ADWIN::new(Some(options.adwin_delta)),
+        }
+    }
+    fn error(&self) -> &ADWIN<F> {
+        match self {
+            AdaNode::Leaf { error, .. } | AdaNode::Branch { error, .. } => error,
+        }
+    }
+    fn error_mut(&mut self) -> &mut ADWIN<F> {
+        match self {
+            AdaNode::Leaf { error, .. } | AdaNode::Branch { error, .. } => error,
+        }
+    }
+    fn predict_proba(
+        &self,
+        x: &Observation<F>,
+        options: &HoeffdingAdaptiveTreeClassifierOptions<F>,
+    ) -> ClassifierTargetProbabilities<F> {
+        let mut node = self;
+        loop {
+            match node {
+                AdaNode::Branch { branch, .. } => node = &branch.children[branch.route(x)],
+                AdaNode::Leaf { leaf, .. } if !leaf.stats.is_empty() => {
+                    return leaf.predict_proba(x, &options.tree)
+                }
+                AdaNode::Leaf { .. } => return ClassifierTargetProbabilities::new(),
+            }
+        }
+    }
+    // Whether the alternate subtree should replace this one (`Some(true)`), be discarded
+    // (`Some(false)`), or keep on growing (`None`).
+    fn compare_alternate(
+        &self,
+        options: &HoeffdingAdaptiveTreeClassifierOptions<F>,
+    ) -> Option<bool> {
+        let AdaNode::Branch {
+            error,
+            alternate: Some(alternate),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let threshold = options.drift_window_threshold;
+        if error.width() < threshold || alternate.error().width() < threshold {
+            return None;
+        }
+        let (old_error, alt_error) = (error.mean(), alternate.error().mean());
+        let n = F::one() / F::from(error.width()).unwrap()
+            + F::one() / F::from(alternate.error().width()).unwrap();
+        let two = F::one() + F::one();
+        let bound = (two
+            * old_error
+            * (F::one() - old_error)
+            * (two / options.switch_significance).ln()
+            * n)
+            .sqrt();
+        if old_error - alt_error > bound {
+            Some(true)
+        } else if alt_error - old_error > bound {
+            Some(false)
+        } else {
+            None
+        }
+    }
+    fn learn(
+        &mut self,
+        x: &Observation<F>,
+        y: &ClassifierTarget,
+        w: F,
+        options: &HoeffdingAdaptiveTreeClassifierOptions<F>,
+        alternates: &mut Alternates,
+    ) {
+        match self.compare_alternate(options) {
+            Some(true) => {
+                let AdaNode::Branch { alternate, .. } = self else {
+                    unreachable!()
+                };
+                *self = *alternate.take().unwrap();
+                alternates.switched += 1;
+            }
+            Some(false) => {
+                if let AdaNode::Branch { alternate, .. } = self {
+                    *alternate = None;
+                }
+                alternates.pruned += 1;
+            }
+            None => {}
+        }
+
+        let correct = self
+            .predict_proba(x, options)
+            .into_iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .is_some_and(|(class, _)| class == *y);
+        let previous_error = self.error().mean();
+        let drift = self
+            .error_mut()
+            .update(if correct { F::zero() } else { F::one() })
+            .is_drift();
+
+        let split = match self {
+            AdaNode::Leaf { leaf, .. } => {
+                leaf.learn(x, y, w, &options.tree);
+                if !leaf.should_attempt_split(&options.tree) {
+                    return;
+                }
+                let depth = leaf.depth;
+                leaf.attempt_split(&options.tree).map(|s| (s, depth))
+            }
+            AdaNode::Branch {
+                branch,
+                depth,
+                error,
+                alternate,
+            } => {
+                if drift && error.mean() > previous_error && alternate.is_none() {
+                    *alternate = Some(Box::new(AdaNode::new_leaf(
+                        *depth,
+                        ClassCounts::new(),
+                        options,
+                    )));
+                }
+                if let Some(alternate) = alternate {
+                    alternate.learn(x, y, w, options, alternates);
+                }
+                let position = branch.route(x);
+                branch.weights[position] += w;
+                branch.children[position].learn(x, y, w, options, alternates);
+                None
+            }
+        };
+        if let Some((suggestion, depth)) = split {
+            let children = suggestion
+                .children
+                .iter()
+                .map(|stats| AdaNode::new_leaf(depth + 1, stats.clone(), options))
+                .collect();
+            *self = AdaNode::Branch {
+                branch: Branch::new(suggestion, children),
+                depth,
+                error: ADWIN::new(Some(options.adwin_delta)),
+                alternate: None,
+            };
+        }
+    }
+    // Visit the nodes of the tree, without the alternate subtrees.
+    fn visit(&self, f: &mut impl FnMut(&AdaNode<F>)) {
+        f(self);
+        if let AdaNode::Branch { branch, .. } = self {
+            for child in branch.children.iter() {
+                child.visit(f);
+            }
+        }
+    }
+}
+
+/// Hoeffding adaptive tree classifier.
+///
+/// This is a [`HoeffdingTreeClassifier`](super::hoeffding_tree_classifier::HoeffdingTreeClassifier)
+/// which adapts to concept drift. Each node monitors the error of the predictions made for the
+/// samples which go through it with an [`ADWIN`] detector. When the error of a branch increases
+/// significantly, an alternate subtree starts growing from scratch alongside it. Once both have
+/// seen enough samples, the alternate replaces the original subtree if it is significantly more
+/// accurate, and is discarded if it is significantly less accurate.
+///
+/// # Parameters
+///
+/// - `options`: See [`HoeffdingAdaptiveTreeClassifierOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_adaptive_tree_classifier::HoeffdingAdaptiveTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut tree: HoeffdingAdaptiveTreeClassifier<f64> =
+///     HoeffdingAdaptiveTreeClassifier::new(Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for i in 0..6000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     // The concept is reversed halfway through the stream
+///     tree.learn_one(&observation, ClassifierTarget::from((x > 0.5) == (i < 3000)));
+/// }
+/// assert!(tree.n_switched_alternate_trees() > 0);
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(tree.predict_one(&observation), ClassifierTarget::from(false));
+/// ```
+///
+/// # References
+///
+/// [^1]: A. Bifet and R. Gavaldà (2009). "Adaptive learning from evolving data streams".
+/// International Symposium on Intelligent Data Analysis, 249-260.
+#[derive(Clone, Debug)]
+pub struct HoeffdingAdaptiveTreeClassifier<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    options: HoeffdingAdaptiveTreeClassifierOptions<F>,
+    root: AdaNode<F>,
+    alternates: Alternates,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    HoeffdingAdaptiveTreeClassifier<F>
+{
+    pub fn new(options: HoeffdingAdaptiveTreeClassifierOptions<F>) -> Self {
+        assert!(
+            options.tree.grace_period > 0,
+            "grace_period must be strictly positive"
+        );
+        Self {
+            root: AdaNode::new_leaf(0, ClassCounts::new(), &options),
+            options,
+            alternates: Alternates::default(),
+        }
+    }
+    pub fn options(&self) -> &HoeffdingAdaptiveTreeClassifierOptions<F> {
+        &self.options
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample, except for the error monitoring which counts each sample once.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: &ClassifierTarget, w: F) {
+        self.root
+            .learn(x, y, w, &self.options, &mut self.alternates);
+    }
+    pub fn n_nodes(&self) -> usize {
+        let mut n = 0;
+        self.root.visit(&mut |_| n += 1);
+        n
+    }
+    pub fn n_leaves(&self) -> usize {
+        let mut n = 0;
+        self.root.visit(&mut |node| {
+            if let AdaNode::Leaf { .. } = node {
+                n += 1
+            }
+        });
+        n
+    }
+    /// Depth of the deepest leaf, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        self.root.visit(&mut |node| {
+            if let AdaNode::Leaf { leaf, .. } = node {
+                depth = depth.max(leaf.depth)
+            }
+        });
+        depth
+    }
+    /// Number of alternate subtrees currently growing.
+    pub fn n_alternate_trees(&self) -> usize {
+        let mut n = 0;
+        self.root.visit(&mut |node| {
+            if let AdaNode::Branch {
+                alternate: Some(_), ..
+            } = node
+            {
+                n += 1
+            }
+        });
+        n
+    }
+    /// Number of alternate subtrees which replaced the subtree they were grown for.
+    pub fn n_switched_alternate_trees(&self) -> usize {
+        self.alternates.switched
+    }
+    /// Number of alternate subtrees which were discarded.
+    pub fn n_pruned_alternate_trees(&self) -> usize {
+        self.alternates.pruned
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for HoeffdingAdaptiveTreeClassifier<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn_weighted(x, &y, F::one());
+    }
+    /// Class probabilities given by the leaf the sample falls in. Empty until the tree has
+    /// learned from a sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        self.root.predict_proba(x, &self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{accuracy, drifting_stream};
+    use crate::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+
+    #[test]
+    fn test_stationary_stream() {
+        let stream = drifting_stream(5000, usize::MAX);
+        let mut tree = HoeffdingAdaptiveTreeClassifier::new(Default::default());
+        assert!(accuracy(&mut tree, &stream, 0) > 0.95);
+        assert!(tree.n_leaves() > 1);
+        assert_eq!(tree.n_nodes(), 2 * tree.n_leaves() - 1);
+        assert_eq!(tree.n_switched_alternate_trees(), 0);
+    }
+
+    #[test]
+    fn test_concept_drift() {
+        let stream = drifting_stream(10000, 5000);
+        let mut adaptive = HoeffdingAdaptiveTreeClassifier::new(Default::default());
+        let mut static_tree = HoeffdingTreeClassifier::new(Default::default());
+        let adaptive_accuracy = accuracy(&mut adaptive, &stream, 5000);
+        let static_accuracy = accuracy(&mut static_tree, &stream, 5000);
+        assert!(adaptive.n_switched_alternate_trees() > 0);
+        assert!(adaptive_accuracy > 0.9);
+        assert!(adaptive_accuracy > static_accuracy + 0.1);
+    }
+}
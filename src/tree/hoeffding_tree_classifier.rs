@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{ClassifierTarget, ClassifierTargetProbabilities, FeatureValue, Observation};
+use crate::learner::Classifier;
+use crate::naive_bayes::utils::softmax;
+use crate::tree::splitter::{
+    info_gain_range, AttributeObserver, ClassCounts, NumericSplitter, SplitSuggestion, SplitTest,
+};
+use crate::tree::utils::hoeffding_bound;
+use num::{Float, FromPrimitive};
+
+/// How the leaves of a classification tree make their predictions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LeafPrediction {
+    /// The class distribution of the samples which reached the leaf.
+    MajorityClass,
+    /// Naive Bayes, using the statistics the leaf keeps to find splits.
+    NaiveBayes,
+    /// Whichever of the two above has been the most accurate on the samples which reached the
+    /// leaf.
+    #[default]
+    NaiveBayesAdaptive,
+}
+
+/// Options of a [`HoeffdingTreeClassifier`].
+///
+/// - `grace_period`: The number of samples a leaf sees between two split attempts.
+/// - `split_confidence`: The probability of choosing a wrong split, i.e. the `delta` of the
+///   Hoeffding bound.
+/// - `tie_threshold`: Splits are made when the Hoeffding bound gets below this threshold, even if
+///   the two best candidates can't be told apart.
+/// - `max_depth`: The depth beyond which leaves aren't split anymore. Unlimited when `None`.
+/// - `splitter`: How the numeric features are observed.
+/// - `leaf_prediction`: How the leaves make their predictions.
+/// - `nb_threshold`: The weight a leaf must have seen before naive Bayes is used.
+#[derive(Clone, Debug)]
+pub struct HoeffdingTreeClassifierOptions<F> {
+    pub grace_period: usize,
+    pub split_confidence: F,
+    pub tie_threshold: F,
+    pub max_depth: Option<usize>,
+    pub splitter: NumericSplitter,
+    pub leaf_prediction: LeafPrediction,
+    pub nb_threshold: usize,
+}
+
+impl<F: Float + FromPrimitive> Default for HoeffdingTreeClassifierOptions<F> {
+    fn default() -> Self {
+        Self {
+            grace_period: 200,
+            split_confidence: F::from_f64(1e-7).unwrap(),
+            tie_threshold: F::from_f64(0.05).unwrap(),
+            max_depth: None,
+            splitter: NumericSplitter::default(),
+            leaf_prediction: LeafPrediction::default(),
+            nb_threshold: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Leaf<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    pub(crate) depth: usize,
+    pub(crate) stats: ClassCounts<F>,
+    weight_at_last_attempt: F,
+    observers: HashMap<String, AttributeObserver<F>>,
+    // Weight of the samples correctly classified by each prediction strategy
+    mc_correct: F,
+    nb_correct: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Leaf<F> {
+    pub(crate) fn new(depth: usize, stats: ClassCounts<F>) -> Self {
+        let weight = stats.values().fold(F::zero(), |sum, w| sum + *w);
+        Self {
+            depth,
+            stats,
+            weight_at_last_attempt: weight,
+            observers: HashMap::new(),
+            mc_correct: F::zero(),
+            nb_correct: F::zero(),
+        }
+    }
+    pub(crate) fn weight(&self) -> F {
+        self.stats.values().fold(F::zero(), |sum, w| sum + *w)
+    }
+    pub(crate) fn learn(
+        &mut self,
+        x: &Observation<F>,
+        y: &ClassifierTarget,
+        w: F,
+        options: &HoeffdingTreeClassifierOptions<F>,
+    ) {
+        if options.leaf_prediction == LeafPrediction::NaiveBayesAdaptive && !self.stats.is_empty() {
+            let best = |probabilities: ClassifierTargetProbabilities<F>| {
+                probabilities
+                    .into_iter()
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .map(|(class, _)| class)
+            };
+            if best(self.majority_class()).as_ref() == Some(y) {
+                self.mc_correct += w;
+            }
+            if best(self.naive_bayes(x)).as_ref() == Some(y) {
+                self.nb_correct += w;
+            }
+        }
+        *self.stats.entry(y.clone()).or_insert(F::zero()) += w;
+        for (name, value) in x.iter() {
+            if value.is_missing() {
+                continue;
+            }
+            self.observers
+                .entry(name.clone())
+                .or_insert_with(|| AttributeObserver::new(value, &options.splitter))
+                .update(value, y, w);
+        }
+    }
+    // Whether enough samples have been seen since the last attempt to split the leaf.
+    pub(crate) fn should_attempt_split(&self, options: &HoeffdingTreeClassifierOptions<F>) -> bool {
+        let depth_ok = options
+            .max_depth
+            .is_none_or(|max_depth| self.depth < max_depth);
+        depth_ok
+            && self.weight() - self.weight_at_last_attempt >= F::from(options.grace_period).unwrap()
+    }
+    // The split to make, if the best candidate is better than the others with enough confidence.
+    pub(crate) fn attempt_split(
+        &mut self,
+        options: &HoeffdingTreeClassifierOptions<F>,
+    ) -> Option<SplitSuggestion<F>> {
+        let weight = self.weight();
+        self.weight_at_last_attempt = weight;
+        if self.stats.len() < 2 {
+            return None;
+        }
+        let mut suggestions: Vec<SplitSuggestion<F>> = self
+            .observers
+            .iter()
+            .filter_map(|(name, observer)| observer.best_split(name, &self.stats))
+            .collect();
+        suggestions.sort_by(|a, b| b.merit.partial_cmp(&a.merit).unwrap());
+        let best = suggestions.first()?;
+        // Not splitting has a merit of zero
+        let second = suggestions
+            .get(1)
+            .map_or(F::zero(), |s| s.merit.max(F::zero()));
+        let bound = hoeffding_bound(
+            info_gain_range(&self.stats),
+            options.split_confidence,
+            weight,
+        );
+        if best.merit > F::zero() && (best.merit - second > bound || bound < options.tie_threshold)
+        {
+            Some(suggestions.swap_remove(0))
+        } else {
+            None
+        }
+    }
+    pub(crate) fn predict_proba(
+        &self,
+        x: &Observation<F>,
+        options: &HoeffdingTreeClassifierOptions<F>,
+    ) -> ClassifierTargetProbabilities<F> {
+        let use_nb = match options.leaf_prediction {
+            LeafPrediction::MajorityClass => false,
+            LeafPrediction::NaiveBayes => true,
+            LeafPrediction::NaiveBayesAdaptive => self.nb_correct > self.mc_correct,
+        };
+        if use_nb && self.weight() >= F::from(options.nb_threshold).unwrap() {
+            self.naive_bayes(x)
+        } else {
+            self.majority_class()
+        }
+    }
+    fn majority_class(&self) -> ClassifierTargetProbabilities<F> {
+        let weight = self.weight();
+        self.stats
+            .iter()
+            .map(|(y, w)| (y.clone(), *w / weight))
+            .collect()
+    }
+    fn naive_bayes(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        let weight = self.weight();
+        let eps = F::from_f64(1e-12).unwrap();
+        let log_joint: Vec<(ClassifierTarget, F)> = self
+            .stats
+            .iter()
+            .map(|(y, w)| {
+                let log_likelihood = x
+                    .iter()
+                    .filter_map(|(name, value)| self.observers.get(name)?.likelihood(value, y))
+                    .fold(F::zero(), |sum, l| sum + l.max(eps).ln());
+                (y.clone(), (*w / weight).ln() + log_likelihood)
+            })
+            .collect();
+        softmax(log_joint)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Branch<F, C = usize> {
+    pub(crate) feature: String,
+    pub(crate) test: SplitTest<F>,
+    pub(crate) children: Vec<C>,
+    // Weight of the samples which went down each child, to route samples whose value is missing
+    pub(crate) weights: Vec<F>,
+}
+
+impl<F: Float + AddAssign, C> Branch<F, C> {
+    pub(crate) fn new(suggestion: SplitSuggestion<F>, children: Vec<C>) -> Self {
+        Self {
+            weights: suggestion
+                .children
+                .iter()
+                .map(|c| c.values().fold(F::zero(), |sum, w| sum + *w))
+                .collect(),
+            feature: suggestion.feature,
+            test: suggestion.test,
+            children,
+        }
+    }
+    // Position of the child a sample goes down to. Missing values, values of the wrong type and
+    // unseen categories go down the heaviest child.
+    pub(crate) fn route(&self, x: &Observation<F>) -> usize {
+        let position = match (&self.test, x.get(&self.feature)) {
+            (SplitTest::Threshold(t), Some(FeatureValue::Numeric(v))) => {
+                Some(if *v <= *t { 0 } else { 1 })
+            }
+            (SplitTest::Values(values), Some(FeatureValue::Categorical(v))) => {
+                values.iter().position(|value| value == v)
+            }
+            _ => None,
+        };
+        position.unwrap_or_else(|| {
+            (0..self.weights.len())
+                .max_by(|a, b| self.weights[*a].partial_cmp(&self.weights[*b]).unwrap())
+                .unwrap()
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Node<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> {
+    Leaf(Leaf<F>),
+    Branch(Branch<F>),
+}
+
+/// Hoeffding tree classifier, also known as the Very Fast Decision Tree (VFDT).
+///
+/// The tree starts as a single leaf, which keeps statistics about each feature. Every
+/// `grace_period` samples, a leaf looks for the best split according to the information gain. The
+/// Hoeffding bound tells how many samples are needed to be confident that the best candidate is
+/// indeed better than the second best one. When it is, the leaf is replaced by a branch whose
+/// children are new leaves. The tree therefore converges to the one a batch algorithm would have
+/// built, while looking at each sample only once.
+///
+/// Numeric features are split on a threshold and categorical features are split into one child
+/// per category. Missing features are ignored when learning, and samples with a missing or unseen
+/// value go down the child which has seen the most samples.
+///
+/// # Parameters
+///
+/// - `options`: See [`HoeffdingTreeClassifierOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::{ClassifierTarget, Observation};
+/// use light_river::learner::Classifier;
+/// use light_river::tree::hoeffding_tree_classifier::HoeffdingTreeClassifier;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut tree: HoeffdingTreeClassifier<f64> = HoeffdingTreeClassifier::new(Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     tree.learn_one(&observation, ClassifierTarget::from(x > 0.7));
+/// }
+/// assert!(tree.n_leaves() > 1);
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert_eq!(tree.predict_one(&observation), ClassifierTarget::from(true));
+/// ```
+///
+/// # References
+///
+/// [^1]: P. Domingos and G. Hulten (2000). "Mining high-speed data streams". Proceedings of the
+/// sixth ACM SIGKDD international conference on Knowledge discovery and data mining, 71-80.
+///
+/// [^2]: G. Holmes, R. Kirkby and B. Pfahringer (2005). "Stress-testing Hoeffding trees".
+/// European conference on principles of data mining and knowledge discovery, 495-502.
+#[derive(Clone, Debug)]
+pub struct HoeffdingTreeClassifier<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    options: HoeffdingTreeClassifierOptions<F>,
+    // Nodes of the tree, the root being the first one
+    nodes: Vec<Node<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    HoeffdingTreeClassifier<F>
+{
+    pub fn new(options: HoeffdingTreeClassifierOptions<F>) -> Self {
+        assert!(
+            options.grace_period > 0,
+            "grace_period must be strictly positive"
+        );
+        Self {
+            options,
+            nodes: vec![Node::Leaf(Leaf::new(0, ClassCounts::new()))],
+        }
+    }
+    pub fn options(&self) -> &HoeffdingTreeClassifierOptions<F> {
+        &self.options
+    }
+    // Index of the leaf a sample falls in.
+    fn sort(&self, x: &Observation<F>) -> usize {
+        let mut node = 0;
+        while let Node::Branch(branch) = &self.nodes[node] {
+            node = branch.children[branch.route(x)];
+        }
+        node
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: &ClassifierTarget, w: F) {
+        let mut node = 0;
+        while let Node::Branch(branch) = &mut self.nodes[node] {
+            let position = branch.route(x);
+            branch.weights[position] += w;
+            node = branch.children[position];
+        }
+        let Node::Leaf(leaf) = &mut self.nodes[node] else {
+            unreachable!()
+        };
+        leaf.learn(x, y, w, &self.options);
+        if !leaf.should_attempt_split(&self.options) {
+            return;
+        }
+        if let Some(suggestion) = leaf.attempt_split(&self.options) {
+            let depth = leaf.depth + 1;
+            let children: Vec<usize> = (self.nodes.len()..)
+                .take(suggestion.children.len())
+                .collect();
+            for stats in suggestion.children.iter() {
+                self.nodes.push(Node::Leaf(Leaf::new(depth, stats.clone())));
+            }
+            self.nodes[node] = Node::Branch(Branch::new(suggestion, children));
+        }
+    }
+    pub fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn n_leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Leaf(_)))
+            .count()
+    }
+    /// Depth of the deepest leaf, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Leaf(leaf) => Some(leaf.depth),
+                Node::Branch(_) => None,
+            })
+            .max()
+            .unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Classifier<F>
+    for HoeffdingTreeClassifier<F>
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: ClassifierTarget) {
+        self.learn_weighted(x, &y, F::one());
+    }
+    /// Class probabilities given by the leaf the sample falls in. Empty until the tree has
+    /// learned from a sample.
+    fn predict_proba(&self, x: &Observation<F>) -> ClassifierTargetProbabilities<F> {
+        match &self.nodes[self.sort(x)] {
+            Node::Leaf(leaf) if !leaf.stats.is_empty() => leaf.predict_proba(x, &self.options),
+            _ => ClassifierTargetProbabilities::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{ModelTarget, ModelType};
+    use crate::evaluate::{progressive_val_score, EvaluateOptions};
+    use crate::metrics::accuracy::Accuracy;
+    use crate::metrics::traits::Metric;
+    use rand::prelude::*;
+
+    // Prequential accuracy of a tree on a stream
+    fn accuracy(
+        tree: HoeffdingTreeClassifier<f64>,
+        stream: Vec<(Observation<f64>, ClassifierTarget)>,
+    ) -> f64 {
+        let mut model = ModelType::Classifier(Box::new(tree));
+        let mut metric = Metric::Classification(Box::new(Accuracy::new()));
+        let stream = stream
+            .into_iter()
+            .map(|(x, y)| (x, ModelTarget::Classification(y)));
+        progressive_val_score(stream, &mut model, &mut metric, EvaluateOptions::default())[0].value
+    }
+
+    // Two numeric features, the class being whether both are above 0.5
+    fn numeric_stream(n: usize) -> Vec<(Observation<f64>, ClassifierTarget)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let (a, b): (f64, f64) = (rng.gen(), rng.gen());
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, ClassifierTarget::from(a > 0.5 && b > 0.5))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_numeric_splits() {
+        for splitter in [
+            NumericSplitter::Gaussian { n_splits: 10 },
+            NumericSplitter::Histogram { n_bins: 64 },
+        ] {
+            let options = HoeffdingTreeClassifierOptions {
+                splitter: splitter.clone(),
+                ..Default::default()
+            };
+            let mut tree = HoeffdingTreeClassifier::new(options.clone());
+            for (x, y) in numeric_stream(5000) {
+                tree.learn_one(&x, y);
+            }
+            assert!(tree.depth() >= 2, "{:?}", splitter);
+            let accuracy = accuracy(HoeffdingTreeClassifier::new(options), numeric_stream(5000));
+            assert!(accuracy > 0.9, "{:?}: {}", splitter, accuracy);
+        }
+    }
+
+    #[test]
+    fn test_categorical_split() {
+        let colors = ["red", "green", "blue", "green"];
+        let mut tree: HoeffdingTreeClassifier<f64> =
+            HoeffdingTreeClassifier::new(Default::default());
+        for i in 0..600 {
+            let mut x = Observation::new();
+            x.insert(
+                "color",
+                FeatureValue::Categorical(colors[i % 4].to_string()),
+            );
+            tree.learn_one(&x, ClassifierTarget::from(i % 4 == 0));
+        }
+        // One leaf per color
+        assert_eq!(tree.n_leaves(), 3);
+        let mut x = Observation::new();
+        x.insert("color", FeatureValue::Categorical("red".to_string()));
+        assert_eq!(tree.predict_one(&x), ClassifierTarget::from(true));
+        // Unseen categories go down the heaviest branch, the green one
+        x.insert("color", FeatureValue::Categorical("pink".to_string()));
+        assert_eq!(tree.predict_one(&x), ClassifierTarget::from(false));
+    }
+
+    #[test]
+    fn test_grace_period_and_max_depth() {
+        let options = HoeffdingTreeClassifierOptions {
+            grace_period: 1000,
+            ..Default::default()
+        };
+        let mut tree = HoeffdingTreeClassifier::new(options);
+        for (x, y) in numeric_stream(999) {
+            tree.learn_one(&x, y);
+        }
+        assert_eq!(tree.n_nodes(), 1);
+
+        let options = HoeffdingTreeClassifierOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let mut tree = HoeffdingTreeClassifier::new(options);
+        for (x, y) in numeric_stream(5000) {
+            tree.learn_one(&x, y);
+        }
+        assert_eq!(tree.depth(), 1);
+    }
+
+    #[test]
+    fn test_leaf_prediction() {
+        // A single leaf, whose classes are two Gaussians with different means
+        let stream = || {
+            let mut rng = StdRng::seed_from_u64(7);
+            (0..1000)
+                .map(|i| {
+                    let y = i % 2 == 0;
+                    let noise: f64 = rng.gen::<f64>() - 0.5;
+                    let x = if y { 1.0 } else { -1.0 } + noise;
+                    (
+                        Observation::from([("x".to_string(), x)]),
+                        ClassifierTarget::from(y),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let tree = |leaf_prediction| {
+            HoeffdingTreeClassifier::new(HoeffdingTreeClassifierOptions {
+                max_depth: Some(0),
+                leaf_prediction,
+                ..Default::default()
+            })
+        };
+        assert!(accuracy(tree(LeafPrediction::MajorityClass), stream()) < 0.6);
+        assert!(accuracy(tree(LeafPrediction::NaiveBayes), stream()) > 0.95);
+        assert!(accuracy(tree(LeafPrediction::NaiveBayesAdaptive), stream()) > 0.95);
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::{FeatureValue, Features, Observation, RegressionTarget};
+use crate::learner::Regressor;
+use crate::linear_model::glm::GLMOptions;
+use crate::linear_model::linear_regression::LinearRegression;
+use crate::optim::optimizers::{Optimizer, SGD};
+use crate::tree::hoeffding_tree_classifier::Branch;
+use crate::tree::splitter::{TargetObserver, VarianceSplit};
+use crate::tree::utils::{hoeffding_bound, Gaussian};
+use num::{Float, FromPrimitive};
+
+/// How the leaves of a regression tree make their predictions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LeafModel {
+    /// The mean target of the samples which reached the leaf.
+    Mean,
+    /// A linear regression trained on the samples which reached the leaf. The numeric features
+    /// are standardized, and the categorical ones are one-hot encoded.
+    Linear,
+    /// Whichever of the two above has had the lowest absolute error on the samples which reached
+    /// the leaf.
+    #[default]
+    Adaptive,
+}
+
+/// Options of a [`HoeffdingTreeRegressor`].
+///
+/// - `grace_period`: The number of samples a leaf sees between two split attempts.
+/// - `split_confidence`: The probability of choosing a wrong split, i.e. the `delta` of the
+///   Hoeffding bound.
+/// - `tie_threshold`: Splits are made when the Hoeffding bound gets below this threshold, even if
+///   the two best candidates can't be told apart.
+/// - `max_depth`: The depth beyond which leaves aren't split anymore. Unlimited when `None`.
+/// - `n_bins`: The number of bins of the histograms in which the numeric features are quantized
+///   to find split thresholds.
+/// - `leaf_model`: How the leaves make their predictions.
+/// - `linear`: The regularization and the learning rate of the intercept of the linear models of
+///   the leaves, see [`GLMOptions`].
+#[derive(Clone, Debug)]
+pub struct HoeffdingTreeRegressorOptions<F> {
+    pub grace_period: usize,
+    pub split_confidence: F,
+    pub tie_threshold: F,
+    pub max_depth: Option<usize>,
+    pub n_bins: usize,
+    pub leaf_model: LeafModel,
+    pub linear: GLMOptions<F>,
+}
+
+impl<F: Float + FromPrimitive> Default for HoeffdingTreeRegressorOptions<F> {
+    fn default() -> Self {
+        Self {
+            grace_period: 200,
+            split_confidence: F::from_f64(1e-7).unwrap(),
+            tie_threshold: F::from_f64(0.05).unwrap(),
+            max_depth: None,
+            n_bins: 64,
+            leaf_model: LeafModel::default(),
+            linear: GLMOptions::default(),
+        }
+    }
+}
+
+// Linear regression on standardized features, the categorical ones being one-hot encoded.
+#[derive(Clone, Debug)]
+struct LinearModel<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign, O> {
+    regression: LinearRegression<F, O>,
+    scalers: HashMap<String, Gaussian<F>>,
+}
+
+impl<F, O> LinearModel<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+{
+    fn new(optimizer: O, options: GLMOptions<F>) -> Self {
+        Self {
+            regression: LinearRegression::new(optimizer, Default::default(), options),
+            scalers: HashMap::new(),
+        }
+    }
+    fn features(&self, x: &Observation<F>) -> Observation<F> {
+        x.iter()
+            .filter_map(|(name, value)| match value {
+                FeatureValue::Numeric(v) => {
+                    let scaler = self.scalers.get(name)?;
+                    let std = scaler.variance().sqrt();
+                    let centered = *v - scaler.mean();
+                    let standardized = if std > F::zero() {
+                        centered / std
+                    } else {
+                        centered
+                    };
+                    Some((name.clone(), FeatureValue::Numeric(standardized)))
+                }
+                FeatureValue::Categorical(v) => {
+                    Some((format!("{}={}", name, v), FeatureValue::Numeric(F::one())))
+                }
+                FeatureValue::Missing => None,
+            })
+            .collect()
+    }
+    fn predict(&self, x: &Observation<F>) -> F {
+        self.regression.predict_one(&self.features(x))
+    }
+    fn learn(&mut self, x: &Observation<F>, y: F, w: F) {
+        for (name, value) in x.iter() {
+            if let FeatureValue::Numeric(v) = value {
+                self.scalers.entry(name.clone()).or_default().update(*v, w);
+            }
+        }
+        let features = Features::Dense(self.features(x));
+        self.regression.learn_features(&features, y, w);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Leaf<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign, O> {
+    depth: usize,
+    target: Gaussian<F>,
+    weight_at_last_attempt: F,
+    observers: HashMap<String, TargetObserver<F>>,
+    linear: LinearModel<F, O>,
+    // Weighted absolute error of each prediction strategy
+    mean_error: F,
+    linear_error: F,
+}
+
+impl<F, O> Leaf<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F>,
+{
+    fn new(depth: usize, target: Gaussian<F>, linear: LinearModel<F, O>) -> Self {
+        Self {
+            depth,
+            weight_at_last_attempt: target.weight(),
+            target,
+            observers: HashMap::new(),
+            linear,
+            mean_error: F::zero(),
+            linear_error: F::zero(),
+        }
+    }
+    fn learn(
+        &mut self,
+        x: &Observation<F>,
+        y: RegressionTarget<F>,
+        w: F,
+        options: &HoeffdingTreeRegressorOptions<F>,
+    ) {
+        if options.leaf_model == LeafModel::Adaptive && self.target.weight() > F::zero() {
+            self.mean_error += w * (self.target.mean() - y).abs();
+            self.linear_error += w * (self.linear.predict(x) - y).abs();
+        }
+        self.target.update(y, w);
+        if options.leaf_model != LeafModel::Mean {
+            self.linear.learn(x, y, w);
+        }
+        for (name, value) in x.iter() {
+            if value.is_missing() {
+                continue;
+            }
+            self.observers
+                .entry(name.clone())
+                .or_insert_with(|| TargetObserver::new(value, options.n_bins))
+                .update(value, y, w);
+        }
+    }
+    // Whether enough samples have been seen since the last attempt to split the leaf.
+    fn should_attempt_split(&self, options: &HoeffdingTreeRegressorOptions<F>) -> bool {
+        let depth_ok = options
+            .max_depth
+            .is_none_or(|max_depth| self.depth < max_depth);
+        depth_ok
+            && self.target.weight() - self.weight_at_last_attempt
+                >= F::from(options.grace_period).unwrap()
+    }
+    // The split to make, if the best candidate is better than the others with enough confidence.
+    fn attempt_split(
+        &mut self,
+        options: &HoeffdingTreeRegressorOptions<F>,
+    ) -> Option<VarianceSplit<F>> {
+        let weight = self.target.weight();
+        self.weight_at_last_attempt = weight;
+        let mut suggestions: Vec<VarianceSplit<F>> = self
+            .observers
+            .iter()
+            .filter_map(|(name, observer)| observer.best_split(name, &self.target))
+            .collect();
+        suggestions.sort_by(|a, b| b.merit.partial_cmp(&a.merit).unwrap());
+        let best = suggestions.first()?;
+        // Not splitting has a merit of zero
+        let second = suggestions
+            .get(1)
+            .map_or(F::zero(), |s| s.merit.max(F::zero()));
+        let bound = hoeffding_bound(F::one(), options.split_confidence, weight);
+        if best.merit > F::zero() && (best.merit - second > bound || bound < options.tie_threshold)
+        {
+            Some(suggestions.swap_remove(0))
+        } else {
+            None
+        }
+    }
+    fn predict(&self, x: &Observation<F>, options: &HoeffdingTreeRegressorOptions<F>) -> F {
+        let use_linear = match options.leaf_model {
+            LeafModel::Mean => false,
+            LeafModel::Linear => true,
+            LeafModel::Adaptive => self.linear_error < self.mean_error,
+        };
+        if use_linear {
+            self.linear.predict(x)
+        } else {
+            self.target.mean()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign, O> {
+    Leaf(Leaf<F, O>),
+    Branch(Branch<F>),
+}
+
+/// Hoeffding tree regressor.
+///
+/// The regression counterpart of the
+/// [`HoeffdingTreeClassifier`](super::hoeffding_tree_classifier::HoeffdingTreeClassifier). Every
+/// `grace_period` samples, a leaf looks for the split which reduces the variance of the target the
+/// most, and the Hoeffding bound tells whether it is better than the second best one with enough
+/// confidence. The leaves predict the mean target of their samples, or the output of a linear
+/// model, so that the tree is a piecewise linear approximation of the target.
+///
+/// When a leaf is split, its children start from a copy of its linear model.
+///
+/// # Parameters
+///
+/// - `optimizer`: How the weights of the linear models of the leaves are updated, see
+///   [`crate::optim::optimizers`]. Each leaf has its own copy of it. SGD with a learning rate of
+///   0.01 when the tree is created with [`new`](Self::new).
+/// - `options`: See [`HoeffdingTreeRegressorOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use light_river::common::Observation;
+/// use light_river::learner::Regressor;
+/// use light_river::tree::hoeffding_tree_regressor::HoeffdingTreeRegressor;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut tree: HoeffdingTreeRegressor<f64> = HoeffdingTreeRegressor::new(Default::default());
+/// let mut rng = StdRng::seed_from_u64(42);
+/// for _ in 0..2000 {
+///     let x = rng.gen::<f64>();
+///     let observation = Observation::from([("x".to_string(), x)]);
+///     tree.learn_one(&observation, if x < 0.3 { 10.0 } else { 20.0 });
+/// }
+/// assert!(tree.n_leaves() > 1);
+///
+/// let observation = Observation::from([("x".to_string(), 0.9)]);
+/// assert!((tree.predict_one(&observation) - 20.0).abs() < 1.0);
+/// ```
+///
+/// # References
+///
+/// [^1]: E. Ikonomovska, J. Gama and S. Džeroski (2011). "Learning model trees from evolving data
+/// streams". Data mining and knowledge discovery 23(1):128-168.
+#[derive(Clone, Debug)]
+pub struct HoeffdingTreeRegressor<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O = SGD<F>,
+> {
+    options: HoeffdingTreeRegressorOptions<F>,
+    // Nodes of the tree, the root being the first one
+    nodes: Vec<Node<F, O>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    HoeffdingTreeRegressor<F>
+{
+    pub fn new(options: HoeffdingTreeRegressorOptions<F>) -> Self {
+        Self::with_optimizer(SGD::new(F::from_f64(0.01).unwrap()), options)
+    }
+}
+
+impl<F, O> HoeffdingTreeRegressor<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    /// A tree whose leaves update the weights of their linear models with a copy of `optimizer`.
+    pub fn with_optimizer(optimizer: O, options: HoeffdingTreeRegressorOptions<F>) -> Self {
+        assert!(
+            options.grace_period > 0,
+            "grace_period must be strictly positive"
+        );
+        let linear = LinearModel::new(optimizer, options.linear.clone());
+        Self {
+            options,
+            nodes: vec![Node::Leaf(Leaf::new(0, Gaussian::new(), linear))],
+        }
+    }
+    pub fn options(&self) -> &HoeffdingTreeRegressorOptions<F> {
+        &self.options
+    }
+    // Index of the leaf a sample falls in.
+    fn sort(&self, x: &Observation<F>) -> usize {
+        let mut node = 0;
+        while let Node::Branch(branch) = &self.nodes[node] {
+            node = branch.children[branch.route(x)];
+        }
+        node
+    }
+    /// Learn from a weighted sample. A weight of `k` is the same as learning `k` times from the
+    /// sample.
+    pub fn learn_weighted(&mut self, x: &Observation<F>, y: RegressionTarget<F>, w: F) {
+        let mut node = 0;
+        while let Node::Branch(branch) = &mut self.nodes[node] {
+            let position = branch.route(x);
+            branch.weights[position] += w;
+            node = branch.children[position];
+        }
+        let Node::Leaf(leaf) = &mut self.nodes[node] else {
+            unreachable!()
+        };
+        leaf.learn(x, y, w, &self.options);
+        if !leaf.should_attempt_split(&self.options) {
+            return;
+        }
+        if let Some(split) = leaf.attempt_split(&self.options) {
+            let depth = leaf.depth + 1;
+            let linear = leaf.linear.clone();
+            let children: Vec<usize> = (self.nodes.len()..).take(split.children.len()).collect();
+            let weights = split.children.iter().map(|g| g.weight()).collect();
+            for target in split.children {
+                self.nodes
+                    .push(Node::Leaf(Leaf::new(depth, target, linear.clone())));
+            }
+            self.nodes[node] = Node::Branch(Branch {
+                feature: split.feature,
+                test: split.test,
+                children,
+                weights,
+            });
+        }
+    }
+    pub fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn n_leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Leaf(_)))
+            .count()
+    }
+    /// Depth of the deepest leaf, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Leaf(leaf) => Some(leaf.depth),
+                Node::Branch(_) => None,
+            })
+            .max()
+            .unwrap()
+    }
+}
+
+impl<F, O> Regressor<F> for HoeffdingTreeRegressor<F, O>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+    O: Optimizer<F> + Clone,
+{
+    fn learn_one(&mut self, x: &Observation<F>, y: RegressionTarget<F>) {
+        self.learn_weighted(x, y, F::one());
+    }
+    /// Prediction of the leaf the sample falls in. Zero until the tree has learned from a sample.
+    fn predict_one(&self, x: &Observation<F>) -> RegressionTarget<F> {
+        match &self.nodes[self.sort(x)] {
+            Node::Leaf(leaf) => leaf.predict(x, &self.options),
+            Node::Branch(_) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::schedulers::InverseScaling;
+    use rand::prelude::*;
+
+    // Prequential mean absolute error of a tree on a stream
+    fn mae(tree: &mut HoeffdingTreeRegressor<f64>, stream: &[(Observation<f64>, f64)]) -> f64 {
+        let mut error = 0.0;
+        for (x, y) in stream.iter() {
+            error += (tree.predict_one(x) - y).abs();
+            tree.learn_one(x, *y);
+        }
+        error / stream.len() as f64
+    }
+
+    // Two numeric features, the target being a step function of the first one plus noise
+    fn step_stream(n: usize) -> Vec<(Observation<f64>, f64)> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|_| {
+                let (a, b, noise): (f64, f64, f64) = (rng.gen(), rng.gen(), rng.gen());
+                let x = Observation::from([("a".to_string(), a), ("b".to_string(), b)]);
+                (x, if a < 0.4 { -5.0 } else { 5.0 } + noise - 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_variance_reduction_split() {
+        let options = HoeffdingTreeRegressorOptions {
+            leaf_model: LeafModel::Mean,
+            ..Default::default()
+        };
+        let mut tree = HoeffdingTreeRegressor::new(options);
+        assert_eq!(tree.predict_one(&Observation::new()), 0.0);
+        let stream = step_stream(3000);
+        mae(&mut tree, &stream);
+        let Node::Branch(root) = &tree.nodes[0] else {
+            panic!("The root should have been split")
+        };
+        assert_eq!(root.feature, "a");
+        let x = Observation::from([("a".to_string(), 0.1), ("b".to_string(), 0.5)]);
+        assert!((tree.predict_one(&x) + 5.0).abs() < 0.2);
+        let x = Observation::from([("a".to_string(), 0.9), ("b".to_string(), 0.5)]);
+        assert!((tree.predict_one(&x) - 5.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_categorical_split() {
+        let mut tree = HoeffdingTreeRegressor::new(Default::default());
+        let colors = ["red", "green", "blue"];
+        for i in 0..1000 {
+            let color = colors[i % 3];
+            let x: Observation<f64> = [(
+                "color".to_string(),
+                FeatureValue::Categorical(color.to_string()),
+            )]
+            .into_iter()
+            .collect();
+            tree.learn_one(&x, if color == "red" { 1.0 } else { 3.0 });
+        }
+        assert_eq!(tree.n_leaves(), 3);
+        let x: Observation<f64> = [(
+            "color".to_string(),
+            FeatureValue::Categorical("red".to_string()),
+        )]
+        .into_iter()
+        .collect();
+        assert!((tree.predict_one(&x) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_leaf_models() {
+        // A linear target, which a single leaf with a linear model fits best
+        let mut rng = StdRng::seed_from_u64(42);
+        let stream: Vec<(Observation<f64>, f64)> = (0..2000)
+            .map(|_| {
+                let a: f64 = rng.gen();
+                (Observation::from([("a".to_string(), a)]), 3.0 * a + 2.0)
+            })
+            .collect();
+        let errors: Vec<f64> = [LeafModel::Mean, LeafModel::Linear, LeafModel::Adaptive]
+            .into_iter()
+            .map(|leaf_model| {
+                let options = HoeffdingTreeRegressorOptions {
+                    leaf_model,
+                    max_depth: Some(0),
+                    ..Default::default()
+                };
+                mae(&mut HoeffdingTreeRegressor::new(options), &stream)
+            })
+            .collect();
+        assert!(errors[1] < errors[0] / 2.0, "{:?}", errors);
+        assert!(errors[2] < errors[0] / 2.0, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_leaf_optimizer() {
+        // The leaves have their own copy of the optimizer, here with a decaying learning rate
+        let mut rng = StdRng::seed_from_u64(42);
+        let stream: Vec<(Observation<f64>, f64)> = (0..2000)
+            .map(|_| {
+                let a: f64 = rng.gen();
+                (Observation::from([("a".to_string(), a)]), 3.0 * a + 2.0)
+            })
+            .collect();
+        let options = HoeffdingTreeRegressorOptions {
+            leaf_model: LeafModel::Linear,
+            max_depth: Some(0),
+            linear: GLMOptions {
+                intercept_lr: 0.1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let optimizer = SGD::with_scheduler(InverseScaling::new(0.1, Some(0.25)));
+        let mut tree = HoeffdingTreeRegressor::with_optimizer(optimizer, options);
+        for (x, y) in stream.iter() {
+            tree.learn_one(x, *y);
+        }
+        let y_pred = tree.predict_one(&Observation::from([("a".to_string(), 0.5)]));
+        assert!((y_pred - 3.5).abs() < 0.01, "{}", y_pred);
+    }
+
+    #[test]
+    fn test_grace_period_and_max_depth() {
+        let options = HoeffdingTreeRegressorOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let mut tree = HoeffdingTreeRegressor::new(options);
+        let stream = step_stream(199);
+        mae(&mut tree, &stream);
+        assert_eq!(tree.n_leaves(), 1);
+        mae(&mut tree, &step_stream(5000));
+        assert_eq!(tree.depth(), 1);
+        assert_eq!(tree.n_nodes(), 1 + tree.n_leaves());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod hoeffding_adaptive_tree_classifier;
+pub mod hoeffding_tree_classifier;
+pub mod hoeffding_tree_regressor;
+pub mod splitter;
+pub mod utils;
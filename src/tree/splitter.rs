@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::common::RegressionTarget;
+use crate::common::{ClassifierTarget, FeatureValue};
+use crate::sketch::histogram::{BinStats, Histogram};
+use crate::tree::utils::{entropy, Gaussian};
+use num::{Float, FromPrimitive};
+
+/// How the leaves of a tree observe the numeric features, to find the best split thresholds.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NumericSplitter {
+    /// Fit a Gaussian per class, and evaluate `n_splits` thresholds evenly spaced between the
+    /// smallest and largest values seen. Cheap and accurate when the classes are well separated.
+    Gaussian { n_splits: usize },
+    /// Maintain a streaming histogram of at most `n_bins` bins, whose boundaries are the candidate
+    /// thresholds. Makes no assumption on the distribution of the feature.
+    Histogram { n_bins: usize },
+}
+
+impl Default for NumericSplitter {
+    fn default() -> Self {
+        NumericSplitter::Gaussian { n_splits: 10 }
+    }
+}
+
+pub(crate) type ClassCounts<F> = HashMap<ClassifierTarget, F>;
+
+// Test made by a branch on a feature: numeric values go to the first child if they are lower than
+// or equal to the threshold, categorical values to the child of the same index as the value.
+#[derive(Clone, Debug)]
+pub(crate) enum SplitTest<F> {
+    Threshold(F),
+    Values(Vec<String>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SplitSuggestion<F> {
+    pub(crate) feature: String,
+    pub(crate) test: SplitTest<F>,
+    pub(crate) merit: F,
+    // Class distribution of each child
+    pub(crate) children: Vec<ClassCounts<F>>,
+}
+
+fn total<F: Float>(counts: &ClassCounts<F>) -> F {
+    counts.values().fold(F::zero(), |sum, w| sum + *w)
+}
+
+fn add<F: Float + AddAssign>(counts: &mut ClassCounts<F>, y: &ClassifierTarget, w: F) {
+    *counts.entry(y.clone()).or_insert(F::zero()) += w;
+}
+
+impl<F: Float + AddAssign> BinStats for ClassCounts<F> {
+    fn merge(&mut self, other: &Self) {
+        for (y, w) in other.iter() {
+            add(self, y, *w);
+        }
+    }
+}
+
+// Reduction of the entropy of the class distribution brought by a split.
+pub(crate) fn info_gain<F: Float>(pre: &ClassCounts<F>, children: &[ClassCounts<F>]) -> F {
+    let weight = total(pre);
+    children.iter().fold(entropy(pre.values()), |gain, child| {
+        gain - total(child) / weight * entropy(child.values())
+    })
+}
+
+// Range of the information gain, given the class distribution before the split.
+pub(crate) fn info_gain_range<F: Float>(pre: &ClassCounts<F>) -> F {
+    F::from(pre.len().max(2)).unwrap().log2()
+}
+
+// Split of a numeric feature at `threshold`, given the distribution of the values lower than or
+// equal to it.
+fn threshold_split<F: Float>(
+    feature: &str,
+    threshold: F,
+    left: ClassCounts<F>,
+    pre: &ClassCounts<F>,
+) -> SplitSuggestion<F> {
+    let right = pre
+        .iter()
+        .map(|(y, w)| {
+            (
+                y.clone(),
+                (*w - *left.get(y).unwrap_or(&F::zero())).max(F::zero()),
+            )
+        })
+        .collect();
+    let children = vec![left, right];
+    SplitSuggestion {
+        feature: feature.to_string(),
+        test: SplitTest::Threshold(threshold),
+        merit: info_gain(pre, &children),
+        children,
+    }
+}
+
+fn best<F: Float>(
+    suggestions: impl Iterator<Item = SplitSuggestion<F>>,
+) -> Option<SplitSuggestion<F>> {
+    suggestions.max_by(|a, b| a.merit.partial_cmp(&b.merit).unwrap())
+}
+
+// Statistics a leaf keeps about a feature, from which split candidates are derived.
+#[derive(Clone, Debug)]
+pub(crate) enum AttributeObserver<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    Gaussian {
+        n_splits: usize,
+        min: F,
+        max: F,
+        classes: HashMap<ClassifierTarget, Gaussian<F>>,
+    },
+    // Each bin holds the class distribution of its values
+    Histogram(Histogram<F, ClassCounts<F>>),
+    Categorical {
+        values: HashMap<String, ClassCounts<F>>,
+    },
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+    AttributeObserver<F>
+{
+    // Observer suited to the type of `value`, which mustn't be missing.
+    pub(crate) fn new(value: &FeatureValue<F>, splitter: &NumericSplitter) -> Self {
+        match (value, splitter) {
+            (FeatureValue::Categorical(_), _) => AttributeObserver::Categorical {
+                values: HashMap::new(),
+            },
+            (_, NumericSplitter::Gaussian { n_splits }) => AttributeObserver::Gaussian {
+                n_splits: *n_splits,
+                min: F::infinity(),
+                max: F::neg_infinity(),
+                classes: HashMap::new(),
+            },
+            (_, NumericSplitter::Histogram { n_bins }) => {
+                AttributeObserver::Histogram(Histogram::with_stats((*n_bins).max(2)))
+            }
+        }
+    }
+    // Values whose type doesn't match the observer are ignored.
+    pub(crate) fn update(&mut self, value: &FeatureValue<F>, y: &ClassifierTarget, w: F) {
+        match (self, value) {
+            (
+                AttributeObserver::Gaussian {
+                    min, max, classes, ..
+                },
+                FeatureValue::Numeric(x),
+            ) => {
+                *min = min.min(*x);
+                *max = max.max(*x);
+                classes.entry(y.clone()).or_default().update(*x, w);
+            }
+            // Samples without weight tell nothing about the distribution
+            (AttributeObserver::Histogram(histogram), FeatureValue::Numeric(x))
+                if w > F::zero() =>
+            {
+                histogram.update_with(*x, w, |counts| add(counts, y, w));
+            }
+            (AttributeObserver::Categorical { values }, FeatureValue::Categorical(x)) => {
+                add(values.entry(x.clone()).or_default(), y, w);
+            }
+            _ => {}
+        }
+    }
+    // Best split on this feature, given the class distribution of the leaf.
+    pub(crate) fn best_split(
+        &self,
+        feature: &str,
+        pre: &ClassCounts<F>,
+    ) -> Option<SplitSuggestion<F>> {
+        match self {
+            AttributeObserver::Gaussian {
+                n_splits,
+                min,
+                max,
+                classes,
+            } => {
+                if min >= max {
+                    return None;
+                }
+                let step = (*max - *min) / F::from(n_splits + 1).unwrap();
+                best((1..=*n_splits).map(|i| {
+                    let threshold = *min + step * F::from(i).unwrap();
+                    let left = classes
+                        .iter()
+                        .map(|(y, g)| (y.clone(), g.weight() * g.cdf(threshold)))
+                        .collect();
+                    threshold_split(feature, threshold, left, pre)
+                }))
+            }
+            AttributeObserver::Histogram(histogram) => {
+                let mut left = ClassCounts::new();
+                let bins = histogram.bins().windows(2).zip(histogram.stats());
+                best(bins.map(|(pair, counts)| {
+                    left.merge(counts);
+                    let threshold = (pair[0].0 + pair[1].0) / (F::one() + F::one());
+                    threshold_split(feature, threshold, left.clone(), pre)
+                }))
+            }
+            AttributeObserver::Categorical { values } => {
+                if values.len() < 2 {
+                    return None;
+                }
+                let mut names: Vec<&String> = values.keys().collect();
+                names.sort();
+                let children: Vec<ClassCounts<F>> =
+                    names.iter().map(|name| values[*name].clone()).collect();
+                Some(SplitSuggestion {
+                    feature: feature.to_string(),
+                    test: SplitTest::Values(names.into_iter().cloned().collect()),
+                    merit: info_gain(pre, &children),
+                    children,
+                })
+            }
+        }
+    }
+    // Likelihood of `value` given the class, as used by naive Bayes. `None` when the observer
+    // can't tell, e.g. for a value of another type.
+    pub(crate) fn likelihood(&self, value: &FeatureValue<F>, y: &ClassifierTarget) -> Option<F> {
+        match (self, value) {
+            (AttributeObserver::Gaussian { classes, .. }, FeatureValue::Numeric(x)) => {
+                Some(classes.get(y).map_or(F::zero(), |g| g.pdf(*x)))
+            }
+            (AttributeObserver::Histogram(histogram), FeatureValue::Numeric(x)) => {
+                // Share of the class which falls in the closest bin
+                let (_, nearest) =
+                    histogram
+                        .bins()
+                        .iter()
+                        .zip(histogram.stats())
+                        .min_by(|a, b| {
+                            let (da, db) = ((a.0 .0 - *x).abs(), (b.0 .0 - *x).abs());
+                            da.partial_cmp(&db).unwrap()
+                        })?;
+                let class_weight = histogram.stats().iter().fold(F::zero(), |sum, counts| {
+                    sum + *counts.get(y).unwrap_or(&F::zero())
+                });
+                Some(*nearest.get(y).unwrap_or(&F::zero()) / class_weight.max(F::one()))
+            }
+            (AttributeObserver::Categorical { values }, FeatureValue::Categorical(x)) => {
+                // Laplace smoothing
+                let count = values
+                    .get(x)
+                    .and_then(|c| c.get(y))
+                    .copied()
+                    .unwrap_or(F::zero());
+                let class_weight = values.values().fold(F::zero(), |sum, counts| {
+                    sum + *counts.get(y).unwrap_or(&F::zero())
+                });
+                Some((count + F::one()) / (class_weight + F::from(values.len()).unwrap()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct VarianceSplit<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    pub(crate) feature: String,
+    pub(crate) test: SplitTest<F>,
+    pub(crate) merit: F,
+    // Target statistics of each child
+    pub(crate) children: Vec<Gaussian<F>>,
+}
+
+// Share of the variance of the target which is explained by a split. Its range is 1.
+pub(crate) fn variance_reduction<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+>(
+    pre: &Gaussian<F>,
+    children: &[Gaussian<F>],
+) -> F {
+    let variance = pre.variance();
+    if variance <= F::zero() {
+        return F::zero();
+    }
+    children.iter().fold(F::one(), |merit, child| {
+        merit - child.weight() / pre.weight() * child.variance() / variance
+    })
+}
+
+fn best_variance_split<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>(
+    suggestions: impl Iterator<Item = VarianceSplit<F>>,
+) -> Option<VarianceSplit<F>> {
+    suggestions.max_by(|a, b| a.merit.partial_cmp(&b.merit).unwrap())
+}
+
+// Statistics a leaf of a regression tree keeps about a feature. Numeric features are quantized
+// into a streaming histogram of at most `n_bins` bins, each of which holds the statistics of the
+// target of the samples which fell in it.
+#[derive(Clone, Debug)]
+pub(crate) enum TargetObserver<
+    F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign,
+> {
+    // Each bin holds the target statistics of its samples
+    Numeric(Histogram<F, Gaussian<F>>),
+    Categorical {
+        values: HashMap<String, Gaussian<F>>,
+    },
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> TargetObserver<F> {
+    // Observer suited to the type of `value`, which mustn't be missing.
+    pub(crate) fn new(value: &FeatureValue<F>, n_bins: usize) -> Self {
+        match value {
+            FeatureValue::Categorical(_) => TargetObserver::Categorical {
+                values: HashMap::new(),
+            },
+            _ => TargetObserver::Numeric(Histogram::with_stats(n_bins.max(2))),
+        }
+    }
+    // Values whose type doesn't match the observer are ignored.
+    pub(crate) fn update(&mut self, value: &FeatureValue<F>, y: RegressionTarget<F>, w: F) {
+        match (self, value) {
+            // Samples without weight tell nothing about the distribution
+            (TargetObserver::Numeric(histogram), FeatureValue::Numeric(x)) if w > F::zero() => {
+                histogram.update_with(*x, w, |target| target.update(y, w));
+            }
+            (TargetObserver::Categorical { values }, FeatureValue::Categorical(x)) => {
+                values.entry(x.clone()).or_default().update(y, w);
+            }
+            _ => {}
+        }
+    }
+    // Best split on this feature, given the target statistics of the leaf.
+    pub(crate) fn best_split(&self, feature: &str, pre: &Gaussian<F>) -> Option<VarianceSplit<F>> {
+        match self {
+            TargetObserver::Numeric(histogram) => {
+                let (bins, stats) = (histogram.bins(), histogram.stats());
+                // Statistics of the bins to the right of each boundary
+                let mut right = vec![Gaussian::new(); bins.len()];
+                for i in (0..bins.len().saturating_sub(1)).rev() {
+                    right[i] = right[i + 1].clone();
+                    right[i].merge(&stats[i + 1]);
+                }
+                let mut left = Gaussian::new();
+                let boundaries = bins.windows(2).zip(stats).zip(right);
+                best_variance_split(boundaries.map(|((pair, target), right)| {
+                    left.merge(target);
+                    let children = vec![left.clone(), right];
+                    VarianceSplit {
+                        feature: feature.to_string(),
+                        test: SplitTest::Threshold((pair[0].0 + pair[1].0) / (F::one() + F::one())),
+                        merit: variance_reduction(pre, &children),
+                        children,
+                    }
+                }))
+            }
+            TargetObserver::Categorical { values } => {
+                if values.len() < 2 {
+                    return None;
+                }
+                let mut names: Vec<&String> = values.keys().collect();
+                names.sort();
+                let children: Vec<Gaussian<F>> =
+                    names.iter().map(|name| values[*name].clone()).collect();
+                Some(VarianceSplit {
+                    feature: feature.to_string(),
+                    test: SplitTest::Values(names.into_iter().cloned().collect()),
+                    merit: variance_reduction(pre, &children),
+                    children,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(items: &[(bool, f64)]) -> ClassCounts<f64> {
+        items
+            .iter()
+            .map(|(y, w)| (ClassifierTarget::from(*y), *w))
+            .collect()
+    }
+
+    #[test]
+    fn test_info_gain() {
+        let pre = counts(&[(true, 4.0), (false, 4.0)]);
+        let perfect = [counts(&[(true, 4.0)]), counts(&[(false, 4.0)])];
+        assert_eq!(info_gain(&pre, &perfect), 1.0);
+        let useless = [
+            counts(&[(true, 2.0), (false, 2.0)]),
+            counts(&[(true, 2.0), (false, 2.0)]),
+        ];
+        assert_eq!(info_gain(&pre, &useless), 0.0);
+    }
+
+    fn observe(splitter: &NumericSplitter) -> (AttributeObserver<f64>, ClassCounts<f64>) {
+        let mut observer = AttributeObserver::new(&FeatureValue::Numeric(0.0), splitter);
+        let mut pre = ClassCounts::new();
+        for i in 0..100 {
+            let x = i as f64 / 100.0;
+            let y = ClassifierTarget::from(x >= 0.3);
+            observer.update(&FeatureValue::Numeric(x), &y, 1.0);
+            add(&mut pre, &y, 1.0);
+        }
+        (observer, pre)
+    }
+
+    #[test]
+    fn test_gaussian_split() {
+        let (observer, pre) = observe(&NumericSplitter::Gaussian { n_splits: 10 });
+        let split = observer.best_split("x", &pre).unwrap();
+        match split.test {
+            SplitTest::Threshold(t) => assert!((t - 0.3).abs() < 0.1, "{}", t),
+            _ => panic!("Expected a numeric split"),
+        }
+        assert!(split.merit > 0.5);
+    }
+
+    #[test]
+    fn test_histogram_split() {
+        let (observer, pre) = observe(&NumericSplitter::Histogram { n_bins: 16 });
+        if let AttributeObserver::Histogram(histogram) = &observer {
+            assert_eq!(histogram.bins().len(), 16);
+        }
+        let split = observer.best_split("x", &pre).unwrap();
+        match split.test {
+            SplitTest::Threshold(t) => assert!((t - 0.3).abs() < 0.05, "{}", t),
+            _ => panic!("Expected a numeric split"),
+        }
+        let total_weight: f64 = split.children.iter().map(total).sum();
+        assert_eq!(total_weight, 100.0);
+    }
+
+    #[test]
+    fn test_categorical() {
+        let mut observer = AttributeObserver::new(
+            &FeatureValue::Categorical(String::new()),
+            &NumericSplitter::default(),
+        );
+        let mut pre = ClassCounts::new();
+        for (color, y) in [
+            ("red", true),
+            ("blue", false),
+            ("red", true),
+            ("green", false),
+        ] {
+            let y = ClassifierTarget::from(y);
+            observer.update(&FeatureValue::Categorical(color.to_string()), &y, 1.0);
+            add(&mut pre, &y, 1.0);
+        }
+        let split = observer.best_split("color", &pre).unwrap();
+        assert_eq!(split.merit, 1.0);
+        match split.test {
+            SplitTest::Values(values) => assert_eq!(values, ["blue", "green", "red"]),
+            _ => panic!("Expected a categorical split"),
+        }
+        let red = FeatureValue::Categorical("red".to_string());
+        // (2 + 1) / (2 + 3)
+        assert_eq!(
+            observer.likelihood(&red, &ClassifierTarget::from(true)),
+            Some(0.6)
+        );
+    }
+
+    #[test]
+    fn test_variance_split() {
+        let mut observer = TargetObserver::new(&FeatureValue::Numeric(0.0), 16);
+        let mut pre = Gaussian::new();
+        for i in 0..100 {
+            let x = i as f64 / 100.0;
+            let y = if x < 0.6 { 1.0 } else { 5.0 };
+            observer.update(&FeatureValue::Numeric(x), y, 1.0);
+            pre.update(y, 1.0);
+        }
+        let split = observer.best_split("x", &pre).unwrap();
+        match split.test {
+            SplitTest::Threshold(t) => assert!((t - 0.6).abs() < 0.05, "{}", t),
+            _ => panic!("Expected a numeric split"),
+        }
+        assert!(split.merit > 0.9);
+        let total_weight: f64 = split.children.iter().map(|g| g.weight()).sum();
+        assert_eq!(total_weight, 100.0);
+    }
+}
@@ -0,0 +1,165 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use crate::metrics::utils::normal_cdf;
+use crate::sketch::histogram::BinStats;
+use crate::stats::var::Var;
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+
+// Weighted running mean and variance, along with the density of the normal distribution they
+// describe.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Gaussian<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign>
+{
+    var: Var<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Gaussian<F> {
+    pub(crate) fn new() -> Self {
+        Self {
+            var: Var::new(None),
+        }
+    }
+    pub(crate) fn update(&mut self, x: F, w: F) {
+        // A null weight would divide zero by zero on the first update
+        if w == F::zero() {
+            return;
+        }
+        self.var.update_weighted(x, w);
+    }
+    // Combine the statistics of two sets of observations.
+    pub(crate) fn merge(&mut self, other: &Gaussian<F>) {
+        self.var.merge(&other.var);
+    }
+    pub(crate) fn weight(&self) -> F {
+        self.var.n()
+    }
+    pub(crate) fn mean(&self) -> F {
+        self.var.mean()
+    }
+    // Unbiased estimate of the variance
+    pub(crate) fn variance(&self) -> F {
+        self.var.get()
+    }
+    // Probability density at `x`. The variance is floored so that the density stays finite.
+    pub(crate) fn pdf(&self, x: F) -> F {
+        let variance = self.variance().max(F::from_f64(1e-9).unwrap());
+        let two = F::one() + F::one();
+        (-(x - self.mean()).powi(2) / (two * variance)).exp()
+            / (two * F::from_f64(std::f64::consts::PI).unwrap() * variance).sqrt()
+    }
+    // Logarithm of the probability density at `x`, which doesn't underflow far from the mean.
+    pub(crate) fn log_pdf(&self, x: F) -> F {
+        let variance = self.variance().max(F::from_f64(1e-9).unwrap());
+        let two = F::one() + F::one();
+        -(x - self.mean()).powi(2) / (two * variance)
+            - (two * F::from_f64(std::f64::consts::PI).unwrap() * variance).ln() / two
+    }
+    // Probability of a value lower than or equal to `x`.
+    pub(crate) fn cdf(&self, x: F) -> F {
+        let std = self.variance().sqrt();
+        if std == F::zero() {
+            return if x >= self.mean() {
+                F::one()
+            } else {
+                F::zero()
+            };
+        }
+        normal_cdf((x - self.mean()) / std)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> Default
+    for Gaussian<F>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Histogram bins hold the statistics of the target of their samples.
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + MulAssign + DivAssign> BinStats
+    for Gaussian<F>
+{
+    fn merge(&mut self, other: &Self) {
+        Gaussian::merge(self, other);
+    }
+}
+
+// With probability 1 - `confidence`, the true mean of a variable whose values span `range` is
+// within this bound of the mean of `n` observations.
+pub(crate) fn hoeffding_bound<F: Float>(range: F, confidence: F, n: F) -> F {
+    let two = F::one() + F::one();
+    (range * range * (F::one() / confidence).ln() / (two * n)).sqrt()
+}
+
+// Entropy, in bits, of a distribution given by its (unnormalized) weights.
+pub(crate) fn entropy<'a, F: Float + 'a>(weights: impl Iterator<Item = &'a F> + Clone) -> F {
+    let total = weights.clone().fold(F::zero(), |sum, w| sum + *w);
+    if total <= F::zero() {
+        return F::zero();
+    }
+    weights
+        .filter(|w| **w > F::zero())
+        .fold(F::zero(), |sum, w| {
+            let p = *w / total;
+            sum - p * p.log2()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaussian() {
+        let mut gaussian = Gaussian::new();
+        for (x, w) in [(1.0, 1.0), (2.0, 2.0), (4.0, 1.0)] {
+            gaussian.update(x, w);
+        }
+        // Weighted sum of the squared deviations from the mean, over the weight minus 1
+        assert!((gaussian.variance() - 4.75 / 3.0).abs() < 1e-10);
+        // The mean is 2.25
+        assert!((gaussian.cdf(2.25) - 0.5).abs() < 1e-6);
+        assert!((gaussian.log_pdf(3.0) - gaussian.pdf(3.0).ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_null_weight() {
+        let mut gaussian = Gaussian::new();
+        gaussian.update(3.0, 0.0);
+        assert_eq!((gaussian.weight(), gaussian.mean()), (0.0, 0.0));
+        gaussian.update(1.0, 1.0);
+        gaussian.update(3.0, 1.0);
+        gaussian.update(100.0, 0.0);
+        assert_eq!(gaussian.mean(), 2.0);
+        assert_eq!(gaussian.variance(), 2.0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let values = [(1.0, 1.0), (2.0, 2.0), (4.0, 1.0), (-3.0, 0.5), (7.0, 3.0)];
+        let mut all = Gaussian::new();
+        let (mut left, mut right) = (Gaussian::new(), Gaussian::new());
+        for (i, (x, w)) in values.iter().enumerate() {
+            all.update(*x, *w);
+            if i < 2 {
+                left.update(*x, *w);
+            } else {
+                right.update(*x, *w);
+            }
+        }
+        left.merge(&right);
+        assert!((left.weight() - all.weight()).abs() < 1e-10);
+        assert!((left.mean() - all.mean()).abs() < 1e-10);
+        assert!((left.variance() - all.variance()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(entropy([2.0, 2.0].iter()), 1.0);
+        assert_eq!(entropy([0.0, 5.0].iter()), 0.0);
+        assert!((entropy([1.0, 1.0, 1.0, 1.0].iter()) - 2.0_f64).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,35 @@
+use std::f64::consts::PI;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Random number generator seeded with `seed`, or from the operating system if it is `None`.
+pub(crate) fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+// Draw a standard normal variable with the Box-Muller transform.
+pub(crate) fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    // 1 - u lies in (0, 1], so that its logarithm is finite
+    let (u, v): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_normal() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 100000;
+        let values: Vec<f64> = (0..n).map(|_| standard_normal(&mut rng)).collect();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!(mean.abs() < 0.01);
+        assert!((variance - 1.0).abs() < 0.02);
+        assert!(values.iter().all(|v| v.is_finite()));
+    }
+}